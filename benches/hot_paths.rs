@@ -0,0 +1,125 @@
+//! Criterion benchmarks of the Rust side of an assertion's hot paths, on the
+//! workload of `benchmarks/bench_hot_paths.py`: 2000 nested orders, and a
+//! CSV export of 20000 rows.
+//!
+//! Run with `cargo bench --bench hot_paths --features bench`. Criterion keeps each run under
+//! `target/criterion` and reports how it compares with the previous one, so
+//! running it on the base branch and then on a change shows a regression.
+
+use std::collections::HashMap;
+use std::ffi::CString;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use pyo3::prelude::*;
+use pysnaptest::hot_paths;
+use pysnaptest::{PySnapshot, RedactionType};
+use serde_json::{json, Value};
+
+const ORDERS: usize = 2000;
+
+/// The Python source of `nested_payload(ORDERS)` in the Python benchmark.
+fn nested_payload_source() -> CString {
+    let source = format!(
+        "{{'customer': {{'id': 42, 'name': 'Ada', 'tags': ['vip', 'beta']}}, \
+         'orders': [{{'id': i, 'created_at': f'2024-01-{{i % 28 + 1:02d}}T10:00:00Z', \
+         'total': i * 1.25, 'paid': i % 2 == 0, 'note': None, \
+         'lines': [{{'sku': f'SKU-{{i}}-{{j}}', 'quantity': j, 'price': j * 0.5}} \
+         for j in range(5)]}} for i in range({ORDERS})]}}"
+    );
+    CString::new(source).expect("the payload source has no NUL byte")
+}
+
+fn nested_payload() -> Value {
+    let orders: Vec<Value> = (0..ORDERS)
+        .map(|i| {
+            let lines: Vec<Value> = (0..5)
+                .map(|j| {
+                    json!({
+                        "sku": format!("SKU-{i}-{j}"),
+                        "quantity": j,
+                        "price": j as f64 * 0.5,
+                    })
+                })
+                .collect();
+            json!({
+                "id": i,
+                "created_at": format!("2024-01-{:02}T10:00:00Z", i % 28 + 1),
+                "total": i as f64 * 1.25,
+                "paid": i % 2 == 0,
+                "note": null,
+                "lines": lines,
+            })
+        })
+        .collect();
+    json!({
+        "customer": {"id": 42, "name": "Ada", "tags": ["vip", "beta"]},
+        "orders": orders,
+    })
+}
+
+fn csv_export(rows: usize) -> String {
+    let mut export = String::from("id,name,total,created_at\n");
+    for i in 0..rows {
+        export.push_str(&format!(
+            "{i},customer {i},{},2024-01-01\n",
+            i as f64 * 1.25
+        ));
+    }
+    export
+}
+
+fn depythonize(c: &mut Criterion) {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| {
+        let payload = py
+            .eval(&nested_payload_source(), None, None)
+            .expect("the payload evaluates");
+        c.bench_function("depythonize nested orders", |b| {
+            b.iter(|| hot_paths::depythonize(black_box(&payload)).expect("the payload converts"))
+        });
+    });
+}
+
+fn csv_parsing(c: &mut Criterion) {
+    let export = csv_export(ORDERS * 10);
+    c.bench_function("parse and render csv", |b| {
+        b.iter(|| hot_paths::render_csv(black_box(&export)).expect("the export parses"))
+    });
+}
+
+fn redactions(c: &mut Criterion) {
+    let payload = nested_payload();
+    let redactions = HashMap::from([
+        (
+            ".orders[].created_at".to_string(),
+            RedactionType::Standard("[date]".to_string()),
+        ),
+        (
+            ".customer.id".to_string(),
+            RedactionType::Standard("[id]".to_string()),
+        ),
+        (".orders[].lines[].sku".to_string(), RedactionType::Hash),
+    ]);
+    c.bench_function("apply redactions", |b| {
+        b.iter(|| hot_paths::redact(black_box(&payload), &redactions).expect("the selectors parse"))
+    });
+}
+
+fn file_io(c: &mut Criterion) {
+    let folder = std::env::temp_dir().join(format!("pysnaptest-bench-{}", std::process::id()));
+    std::fs::create_dir_all(&folder).expect("the bench folder is created");
+    let snapshot = folder.join("bench__orders@pysnap.snap");
+    let body = serde_json::to_string_pretty(&nested_payload()).expect("the payload renders");
+    let contents = format!("---\nsource: benches/hot_paths.rs\n---\n{body}\n");
+
+    c.bench_function("write snapshot", |b| {
+        b.iter(|| hot_paths::write_file(&snapshot, black_box(contents.as_bytes())))
+    });
+    c.bench_function("read snapshot", |b| {
+        b.iter(|| PySnapshot::from_file(black_box(snapshot.clone())).expect("the snapshot loads"))
+    });
+    let _ = std::fs::remove_dir_all(&folder);
+}
+
+criterion_group!(benches, depythonize, csv_parsing, redactions, file_io);
+criterion_main!(benches);
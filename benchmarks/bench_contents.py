@@ -0,0 +1,68 @@
+"""Benchmark ``PySnapshot.contents()`` on 100 MB snapshots.
+
+Run with ``python benchmarks/bench_contents.py [size_mb]`` after building the
+extension (``maturin develop --release``). Each snapshot is written to a
+temporary directory in the on-disk format insta uses, then loaded and read
+several times; the best wall time per variant is reported.
+"""
+
+from __future__ import annotations
+
+import sys
+import tempfile
+import time
+from pathlib import Path
+
+from pysnaptest import PySnapshot
+
+REPEAT = 5
+
+
+def write_snapshot(folder: Path, name: str, header: str, body: str = "") -> Path:
+    snapshot = folder / f"pysnaptest__{name}@pysnap.snap"
+    snapshot.write_text(f"---\nsource: src/lib.rs\n{header}---\n{body}")
+    return snapshot
+
+
+def best_of(label: str, fn) -> None:
+    timings = []
+    for _ in range(REPEAT):
+        start = time.perf_counter()
+        fn()
+        timings.append(time.perf_counter() - start)
+    print(f"{label:<40} {min(timings) * 1000:9.1f} ms")
+
+
+def main() -> None:
+    size = int(sys.argv[1]) if len(sys.argv) > 1 else 100
+    payload = bytes(range(256)) * (size * 4096)
+    text = "x" * (size * 1024 * 1024 - 1) + "\n"
+
+    with tempfile.TemporaryDirectory() as tmp:
+        folder = Path(tmp)
+
+        binary = write_snapshot(
+            folder, "binary", "extension: bin\nsnapshot_kind: binary\n"
+        )
+        Path(f"{binary}.bin").write_bytes(payload)
+        textual = write_snapshot(folder, "text", "expression: result\n", text)
+
+        print(f"{size} MB snapshots, best of {REPEAT}")
+        best_of("baseline: Path.read_bytes()", Path(f"{binary}.bin").read_bytes)
+
+        snapshot = PySnapshot.from_file(binary)
+        best_of("binary contents() (memory-mapped)", snapshot.contents)
+        best_of("binary bytes(contents())", lambda: bytes(snapshot.contents()))
+        best_of(
+            "binary from_file() + contents()",
+            lambda: PySnapshot.from_file(binary).contents(),
+        )
+
+        snapshot = PySnapshot.from_file(textual)
+        best_of("text contents()", snapshot.contents)
+        best_of("text contents().decode()", lambda: snapshot.contents().decode())
+        best_of("text contents_text()", snapshot.contents_text)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,177 @@
+"""Benchmark the hot paths of an assertion, and catch regressions.
+
+Run with ``python benchmarks/bench_hot_paths.py`` after building the
+extension (``maturin develop --release``). Each case asserts against
+committed snapshots that match, so the timings cover the whole passing path:
+converting the Python value, applying redactions, rendering, and reading the
+snapshot file. The best wall time of each case is reported.
+
+``--json results.json`` saves the timings, and ``--baseline results.json``
+compares against saved ones: the script exits with status 1 when a case got
+slower than ``--tolerance`` times its baseline (1.25 by default), so a CI job
+running it on both branches fails on a performance regression.
+"""
+
+from __future__ import annotations
+
+import argparse
+import json
+import shutil
+import sys
+import tempfile
+import time
+from pathlib import Path
+from typing import Callable, Dict
+
+from pysnaptest import (
+    PySnapshot,
+    assert_csv_snapshot,
+    assert_json_snapshot,
+    validate_redactions,
+)
+from pysnaptest.review import accept_pending_snapshot
+
+REPEAT = 5
+
+
+def nested_payload(orders: int) -> dict:
+    return {
+        "customer": {"id": 42, "name": "Ada", "tags": ["vip", "beta"]},
+        "orders": [
+            {
+                "id": i,
+                "created_at": f"2024-01-{i % 28 + 1:02d}T10:00:00Z",
+                "total": i * 1.25,
+                "paid": i % 2 == 0,
+                "note": None,
+                "lines": [
+                    {"sku": f"SKU-{i}-{j}", "quantity": j, "price": j * 0.5}
+                    for j in range(5)
+                ],
+            }
+            for i in range(orders)
+        ],
+    }
+
+
+def csv_export(rows: int) -> str:
+    lines = ["id,name,total,created_at"]
+    lines.extend(f"{i},customer {i},{i * 1.25},2024-01-01" for i in range(rows))
+    return "\n".join(lines) + "\n"
+
+
+def committed(folder: Path, assertion: Callable[[str], None]) -> Path:
+    """Runs `assertion` once into `folder` and accepts what it wrote."""
+
+    folder.mkdir()
+    try:
+        assertion(str(folder))
+    except AssertionError:
+        pass
+    for pending in folder.glob("*.snap.new"):
+        accept_pending_snapshot(pending)
+    return folder
+
+
+def best_of(fn: Callable[[], None], setup: Callable[[], None] = lambda: None) -> float:
+    timings = []
+    for _ in range(REPEAT):
+        setup()
+        start = time.perf_counter()
+        fn()
+        timings.append(time.perf_counter() - start)
+    return min(timings) * 1000
+
+
+def asserting(tmp: Path, label: str, assertion: Callable[[str], None]) -> float:
+    """Times `assertion` against matching committed snapshots, in a fresh
+    copy of them each round so every round reads the same snapshot names."""
+
+    seed = committed(tmp / f"{label}-seed", assertion)
+    rounds = iter(range(REPEAT))
+    folder = Path()
+
+    def setup() -> None:
+        nonlocal folder
+        folder = tmp / f"{label}-{next(rounds)}"
+        shutil.copytree(seed, folder)
+
+    return best_of(lambda: assertion(str(folder)), setup)
+
+
+def run(orders: int) -> Dict[str, float]:
+    payload = nested_payload(orders)
+    export = csv_export(orders * 10)
+    redactions = {".orders[].created_at": "[date]", ".customer.id": "[id]"}
+    results = {}
+    with tempfile.TemporaryDirectory() as tmp:
+        folder = Path(tmp)
+        results["json assertion"] = asserting(
+            folder,
+            "json",
+            lambda path: assert_json_snapshot(
+                payload, snapshot_path=path, snapshot_name="payload"
+            ),
+        )
+        results["json assertion with redactions"] = asserting(
+            folder,
+            "redacted",
+            lambda path: assert_json_snapshot(
+                payload,
+                snapshot_path=path,
+                snapshot_name="payload",
+                redactions=redactions,
+            ),
+        )
+        results["redaction selectors"] = best_of(
+            lambda: validate_redactions(payload, redactions)
+        )
+        results["csv assertion"] = asserting(
+            folder,
+            "csv",
+            lambda path: assert_csv_snapshot(
+                export, snapshot_path=path, snapshot_name="export"
+            ),
+        )
+        snapshot = next((folder / "json-seed").glob("*.snap"))
+        results["snapshot file read"] = best_of(
+            lambda: PySnapshot.from_file(snapshot).contents_text()
+        )
+    return results
+
+
+def regressions(
+    results: Dict[str, float], baseline: Dict[str, float], tolerance: float
+) -> Dict[str, float]:
+    return {
+        label: ms / baseline[label]
+        for label, ms in results.items()
+        if label in baseline and ms > baseline[label] * tolerance
+    }
+
+
+def main() -> int:
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    parser.add_argument("--orders", type=int, default=2000)
+    parser.add_argument("--json", type=Path, help="save the timings here")
+    parser.add_argument("--baseline", type=Path, help="timings to compare with")
+    parser.add_argument("--tolerance", type=float, default=1.25)
+    args = parser.parse_args()
+
+    results = run(args.orders)
+    baseline = json.loads(args.baseline.read_text()) if args.baseline else {}
+    print(f"{args.orders} orders, best of {REPEAT}")
+    for label, ms in results.items():
+        compared = f"  ({ms / baseline[label]:.2f}x)" if label in baseline else ""
+        print(f"{label:<40} {ms:9.1f} ms{compared}")
+    if args.json:
+        args.json.write_text(json.dumps(results, indent=2) + "\n")
+
+    slower = regressions(results, baseline, args.tolerance)
+    for label, ratio in slower.items():
+        print(f"regression: {label} is {ratio:.2f}x its baseline", file=sys.stderr)
+    return 1 if slower else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
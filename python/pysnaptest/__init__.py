@@ -12,31 +12,172 @@ plugin via ``pysnaptest.pytest_plugin``.
 from .assertion import (
     snapshot,
     assert_json_snapshot,
+    assert_many_json_snapshots,
+    assert_graphql_snapshot,
+    assert_openapi_snapshot,
+    assert_call_snapshot,
+    golden,
+    characterize,
+    Characterization,
     assert_csv_snapshot,
     assert_snapshot,
     assert_dataframe_snapshot,
     assert_binary_snapshot,
+    assert_pdf_snapshot,
+    assert_audio_snapshot,
+    assert_term_snapshot,
+    assert_logs_snapshot,
+    assert_sql_snapshot,
+    assert_hash_snapshot,
+    assert_stats_snapshot,
+    assert_schema_snapshot,
+    validate_against_schema_snapshot,
+    SchemaViolationError,
+    assert_vector_snapshot,
     sorted_redaction,
     rounded_redaction,
+    hash_redaction,
+    type_redaction,
+    truncate_redaction,
+    validate_redactions,
     extract_from_pytest_env,
 )
-from .mocks import mock_json_snapshot, patch_json_snapshot
+from .mocks import (
+    RECORDED,
+    DryRunChange,
+    find_expired_recordings,
+    mock_json_snapshot,
+    patch_json_snapshot,
+    register_result_factory,
+    rerecord,
+)
+from .http_mocks import mock_http_json_snapshot
+from .testcase import SnapshotTestCaseMixin
+from .seeds import assert_snapshot_for_seed, list_seed_snapshots, prune_seed_snapshots
+from .llm import assert_llm_snapshot, mock_llm_snapshot
+from .presets import register_preset
 from .encoders import to_jsonable, is_jsonable_object
-from ._pysnaptest import PySnapshot
+from .importers import import_snapshots
+from .exports import export_snapshots, import_exported_snapshots
+from .upgrade import upgrade_snapshots
+from .junit import format_junit_system_out
+from .manifest import ManifestEntry, ManifestIssue, read_manifest, verify_manifest
+from .soft import SoftSnapshotError, raise_soft_failures
+from ._pysnaptest import (
+    PySnapshot,
+    ScratchStorage,
+    SerializationError,
+    SnapshotCorruptedError,
+    SnapshotInfo,
+    SnapshotMismatchError,
+    SnapshotReadOnlyError,
+    SnapshotSettings,
+    SnapshotTeardownWarning,
+    clear_replay_cache,
+    configure_storage,
+    explain_snapshot_name,
+    get_effective_config,
+    pending_summary,
+    register_replay_decoder,
+    replay_cache_info,
+    serialization_limits_info,
+    set_default_redactions,
+    set_diff_options,
+    set_fail_fast,
+    set_json_format,
+    set_read_only,
+    set_replay_cache_size,
+    set_round_floats,
+    set_serialization_limits,
+    set_structural_json_comparison,
+    strip_image_metadata,
+)
 
 __all__ = [
     "snapshot",
     "assert_json_snapshot",
+    "assert_many_json_snapshots",
+    "assert_graphql_snapshot",
+    "assert_openapi_snapshot",
+    "assert_call_snapshot",
+    "golden",
+    "characterize",
+    "Characterization",
     "assert_csv_snapshot",
     "assert_snapshot",
     "assert_dataframe_snapshot",
     "assert_binary_snapshot",
+    "assert_pdf_snapshot",
+    "assert_audio_snapshot",
+    "assert_term_snapshot",
+    "assert_logs_snapshot",
+    "assert_sql_snapshot",
+    "assert_hash_snapshot",
+    "assert_stats_snapshot",
+    "assert_schema_snapshot",
+    "validate_against_schema_snapshot",
+    "SchemaViolationError",
+    "assert_vector_snapshot",
     "sorted_redaction",
     "rounded_redaction",
+    "hash_redaction",
+    "type_redaction",
+    "truncate_redaction",
+    "validate_redactions",
     "extract_from_pytest_env",
+    "SnapshotInfo",
+    "SerializationError",
+    "SnapshotCorruptedError",
+    "SnapshotMismatchError",
+    "SnapshotReadOnlyError",
+    "SnapshotSettings",
+    "ScratchStorage",
+    "SnapshotTeardownWarning",
+    "SnapshotTestCaseMixin",
     "mock_json_snapshot",
     "patch_json_snapshot",
+    "RECORDED",
+    "DryRunChange",
+    "find_expired_recordings",
+    "rerecord",
+    "mock_http_json_snapshot",
+    "assert_snapshot_for_seed",
+    "list_seed_snapshots",
+    "prune_seed_snapshots",
+    "assert_llm_snapshot",
+    "mock_llm_snapshot",
+    "register_preset",
     "to_jsonable",
     "is_jsonable_object",
+    "import_snapshots",
+    "export_snapshots",
+    "import_exported_snapshots",
+    "upgrade_snapshots",
+    "format_junit_system_out",
+    "ManifestEntry",
+    "ManifestIssue",
+    "read_manifest",
+    "verify_manifest",
+    "SoftSnapshotError",
+    "raise_soft_failures",
     "PySnapshot",
+    "clear_replay_cache",
+    "configure_storage",
+    "explain_snapshot_name",
+    "get_effective_config",
+    "pending_summary",
+    "register_replay_decoder",
+    "register_result_factory",
+    "replay_cache_info",
+    "serialization_limits_info",
+    "set_default_redactions",
+    "set_diff_options",
+    "set_fail_fast",
+    "set_json_format",
+    "set_read_only",
+    "set_replay_cache_size",
+    "set_round_floats",
+    "set_serialization_limits",
+    "set_structural_json_comparison",
+    "strip_image_metadata",
 ]
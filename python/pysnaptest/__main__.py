@@ -2,14 +2,26 @@
 
 Run ``pysnaptest --help`` for usage. Mirrors the common
 ``cargo insta`` subcommands (``review``, ``accept``, ``reject``,
-``pending-snapshots``, ``unused``) but works without any Rust tooling.
+``pending-snapshots``, ``unused``) but works without any Rust tooling, and
+adds ``gc`` for snapshots whose test is gone, ``expired`` for mock recordings
+past their age, ``rerecord`` to drop one test's recordings, ``explain``
+to show how a test's snapshot file name is built, ``import`` to convert a
+syrupy or snapshottest suite's snapshots, ``export`` and
+``import-exported`` to move text snapshots' bodies to plain files and back,
+``upgrade`` to bring snapshots of older versions to the current layout, and
+``verify-manifest`` to find snapshots changed behind their manifest's back.
 """
 
 from __future__ import annotations
 
 import argparse
+import json
 from typing import Optional, Sequence
 
+from ._pysnaptest import explain_snapshot_name
+from .exports import FORMATS, export_snapshots, import_exported_snapshots
+from .importers import SOURCES, import_snapshots
+from .manifest import verify_manifest
 from .review import (
     accept_all,
     find_pending_snapshots,
@@ -17,7 +29,10 @@ from .review import (
     reject_all,
     review,
 )
+from .mocks import find_expired_recordings, rerecord
+from .orphans import find_orphaned_snapshots
 from .unused import delete_snapshot, unused_snapshots
+from .upgrade import upgrade_snapshots
 
 
 def build_parser() -> argparse.ArgumentParser:
@@ -53,6 +68,89 @@ def build_parser() -> argparse.ArgumentParser:
         help="Arguments forwarded to pytest (e.g. a test path). "
         "Prefix with -- to separate them from pysnaptest options.",
     )
+    gc = sub.add_parser(
+        "gc",
+        help="Report snapshots whose test file or test no longer exists.",
+    )
+    gc.add_argument(
+        "--delete",
+        action="store_true",
+        help="Delete the orphaned snapshots (and any binary sidecars).",
+    )
+    expired = sub.add_parser(
+        "expired",
+        help="List mock recordings older than --max-age-days.",
+    )
+    expired.add_argument(
+        "--max-age-days",
+        type=float,
+        default=30,
+        help="Maximum allowed age of a recording in days (default: 30).",
+    )
+    rerecord_parser = sub.add_parser(
+        "rerecord",
+        help="Delete one test's mock recordings so its next run records them.",
+    )
+    rerecord_parser.add_argument(
+        "node_id",
+        help="The test's pytest node id, e.g. tests/test_api.py::test_checkout.",
+    )
+    explain = sub.add_parser(
+        "explain",
+        help="Show every component of a test's next snapshot file name.",
+    )
+    explain.add_argument(
+        "node_id",
+        help="The test's pytest node id, e.g. tests/test_api.py::test_checkout.",
+    )
+    explain.add_argument("--snapshot-path", help="Override the snapshot folder.")
+    explain.add_argument("--snapshot-name", help="Override the snapshot name.")
+    explain.add_argument(
+        "--prefix", help='Prefix strategy: "module", "none" or a custom prefix.'
+    )
+    explain.add_argument(
+        "--allow-duplicates",
+        action="store_true",
+        help="Explain an assertion made with allow_duplicates=True.",
+    )
+    import_parser = sub.add_parser(
+        "import",
+        help="Convert syrupy or snapshottest snapshots into pysnaptest ones.",
+    )
+    import_parser.add_argument(
+        "source",
+        choices=SOURCES,
+        help="The library the snapshots were recorded with.",
+    )
+    export = sub.add_parser(
+        "export",
+        help="Write every text snapshot's body to a plain file, with a manifest.",
+    )
+    export.add_argument(
+        "--format",
+        choices=FORMATS,
+        default="raw",
+        help="How the bodies are written (default: raw).",
+    )
+    export.add_argument(
+        "--destination",
+        help="Directory to export to (default: <root>/exported_snapshots).",
+    )
+    import_exported = sub.add_parser(
+        "import-exported",
+        help="Write the files of an export back into their snapshots.",
+    )
+    import_exported.add_argument(
+        "destination", help="The directory the snapshots were exported to."
+    )
+    sub.add_parser(
+        "upgrade",
+        help="Rewrite snapshots of older pysnaptest versions into the current layout.",
+    )
+    sub.add_parser(
+        "verify-manifest",
+        help="Report snapshots edited, added or deleted behind their manifest's back.",
+    )
 
     return parser
 
@@ -83,6 +181,41 @@ def main(argv: Optional[Sequence[str]] = None) -> int:
         print(f"\n{len(pending)} pending snapshot(s).")
     elif args.command == "unused":
         return _unused_command(args)
+    elif args.command == "gc":
+        return _gc_command(args)
+    elif args.command == "expired":
+        return _expired_command(args)
+    elif args.command == "rerecord":
+        deleted = rerecord(args.node_id, args.root)
+        for path in deleted:
+            print(f"  {path}")
+        print(f"Deleted {len(deleted)} recording file(s) of {args.node_id}.")
+    elif args.command == "explain":
+        overrides = {
+            "snapshot_path": args.snapshot_path,
+            "snapshot_name": args.snapshot_name,
+            "prefix": args.prefix,
+            "allow_duplicates": args.allow_duplicates,
+        }
+        print(json.dumps(explain_snapshot_name(args.node_id, overrides), indent=2))
+    elif args.command == "import":
+        written = import_snapshots(args.source, args.root)
+        for path in written:
+            print(f"  {path}")
+        print(f"Imported {len(written)} snapshot(s) from {args.source}.")
+    elif args.command == "export":
+        exported = export_snapshots(args.root, args.format, args.destination)
+        print(f"Exported {len(exported)} snapshot(s).")
+    elif args.command == "import-exported":
+        written = import_exported_snapshots(args.destination, args.root)
+        print(f"Wrote {len(written)} snapshot(s) back.")
+    elif args.command == "upgrade":
+        upgraded = upgrade_snapshots(args.root)
+        for path in upgraded:
+            print(f"  {path}")
+        print(f"Upgraded {len(upgraded)} snapshot(s).")
+    elif args.command == "verify-manifest":
+        return _verify_manifest_command(args)
     else:  # "review" or no subcommand
         review(args.root)
     return 0
@@ -120,5 +253,81 @@ def _unused_command(args: argparse.Namespace) -> int:
     return 1
 
 
+def _gc_command(args: argparse.Namespace) -> int:
+    """Handle ``pysnaptest gc``: report or delete orphaned snapshots.
+
+    Args:
+        args: Parsed CLI arguments.
+
+    Returns:
+        int: ``0`` when nothing is orphaned (or it was deleted), ``1``
+        otherwise so the command can gate CI.
+    """
+
+    orphans = find_orphaned_snapshots(args.root)
+    if not orphans:
+        print("No orphaned snapshots found.")
+        return 0
+
+    verb = "Deleting" if args.delete else "Found"
+    print(f"{verb} {len(orphans)} orphaned snapshot(s):")
+    for orphan in orphans:
+        if args.delete:
+            delete_snapshot(orphan.path)
+        print(f"  {orphan.path}: {orphan.reason}")
+    if args.delete:
+        return 0
+    print("Re-run with `pysnaptest gc --delete` to remove them.")
+    return 1
+
+
+def _expired_command(args: argparse.Namespace) -> int:
+    """Handle ``pysnaptest expired``: report recordings past their age.
+
+    Args:
+        args: Parsed CLI arguments.
+
+    Returns:
+        int: ``0`` when every recording is fresh, ``1`` otherwise.
+    """
+
+    expired = find_expired_recordings(args.root, args.max_age_days)
+    if not expired:
+        print("No expired recordings found.")
+        return 0
+
+    print(
+        f"Found {len(expired)} recording(s) older than "
+        f"{args.max_age_days:g} days:"
+    )
+    for path in expired:
+        print(f"  {path}")
+    print("Re-record them with record=True against the live service.")
+    return 1
+
+
+
+def _verify_manifest_command(args: argparse.Namespace) -> int:
+    """Handle ``pysnaptest verify-manifest``: report snapshots that disagree
+    with their folder's manifest.
+
+    Args:
+        args: Parsed CLI arguments.
+
+    Returns:
+        int: ``0`` when every snapshot agrees, ``1`` otherwise.
+    """
+
+    issues = verify_manifest(args.root)
+    if not issues:
+        print("Every snapshot matches its manifest.")
+        return 0
+
+    print(f"Found {len(issues)} snapshot(s) disagreeing with their manifest:")
+    for issue in issues:
+        print(f"  {issue.path}: {issue.problem}")
+    return 1
+
+
 if __name__ == "__main__":
     raise SystemExit(main())
@@ -2,31 +2,157 @@
 
 These declarations mirror the pyo3 bindings defined in ``src/`` so that editors
 and type checkers can offer completion and validation for the Rust-backed API.
+The signatures and exports are regenerated from the built extension by
+``python -m pysnaptest.stubgen``; the annotations and docstrings are kept.
 """
 
 import os
 from pathlib import Path
-from typing import Any, Optional, Union
+from typing import Any, Callable, Iterable, Mapping, Optional, Sequence, TypeVar, Union
 
 _StrPath = Union[str, os.PathLike[str]]
-_Redactions = dict[str, Union[str, int, None]]
+_Redactions = dict[Union[str, int], Union[str, int, None, "_Redactions"]]
+_F = TypeVar("_F", bound=Callable[..., Any])
+
+MANIFEST_FILE: str
+SNAPSHOT_FORMAT_VERSION: int
+SNAPSHOT_SUFFIX: str
 
 class SnapshotInfo:
-    """Snapshot configuration resolved from the active pytest test."""
+    """Snapshot configuration: where a test's snapshots go and their name.
+
+    Infos are equal, and hash alike, when they name and record their
+    snapshots the same way, whatever settings they were built under.
+    """
 
     @staticmethod
     def from_pytest(
         snapshot_path_override: Optional[_StrPath] = ...,
         snapshot_name_override: Optional[str] = ...,
         allow_duplicates: bool = ...,
+        prefix: Optional[str] = ...,
+        settings: Optional["SnapshotSettings"] = ...,
+        scope: Optional[str] = ...,
+        node_id: Optional[str] = ...,
+    ) -> "SnapshotInfo":
+        """Build snapshot info from the ``PYTEST_CURRENT_TEST`` environment,
+        or for the test ``node_id`` when given, under the entered
+        ``SnapshotSettings`` and ``settings``, shared by the tests of the
+        module or session with ``scope``."""
+        ...
+
+    @staticmethod
+    def from_node_id(
+        node_id: str,
+        snapshot_path_override: Optional[_StrPath] = ...,
+        snapshot_name_override: Optional[str] = ...,
+        allow_duplicates: bool = ...,
+        prefix: Optional[str] = ...,
+        settings: Optional["SnapshotSettings"] = ...,
+        scope: Optional[str] = ...,
+    ) -> "SnapshotInfo":
+        """Build snapshot info for the pytest test ``node_id`` rather than
+        the running one, e.g. a fixture's ``request.node.nodeid``."""
+        ...
+
+    @staticmethod
+    def manual(
+        folder: _StrPath,
+        name: str,
+        allow_duplicates: bool = ...,
+        prefix: Optional[str] = ...,
+    ) -> "SnapshotInfo":
+        """Build snapshot info with an explicit folder and snapshot name."""
+        ...
+
+    @staticmethod
+    def from_frame(
+        frame: Any, allow_duplicates: bool = ..., prefix: Optional[str] = ...
     ) -> "SnapshotInfo":
-        """Build snapshot info from the ``PYTEST_CURRENT_TEST`` environment."""
+        """Build snapshot info for the function running in an
+        ``inspect.FrameInfo`` or frame object."""
+        ...
+
+    @staticmethod
+    def from_unittest(
+        module_file: _StrPath,
+        class_name: str,
+        method_name: str,
+        allow_duplicates: bool = ...,
+        prefix: Optional[str] = ...,
+    ) -> "SnapshotInfo":
+        """Build snapshot info for a ``unittest`` test method."""
+        ...
+
+    def with_redactions(self, redactions: _Redactions) -> "SnapshotInfo":
+        """Return a copy applying ``redactions`` to every JSON or CSV
+        assertion and mocked call made with it."""
+        ...
+
+    def with_header(
+        self, description: Optional[str] = ..., expression: Optional[str] = ...
+    ) -> "SnapshotInfo":
+        """Return a copy whose snapshots record ``description`` and
+        ``expression`` in their header."""
+        ...
+
+    def with_name(self, suffix: str) -> "SnapshotInfo":
+        """Return a copy whose snapshots are named ``<name>_<suffix>``, e.g.
+        one per stage of a pipeline."""
+        ...
+
+    def with_folder(self, folder: _StrPath) -> "SnapshotInfo":
+        """Return a copy storing its snapshots in ``folder``."""
+        ...
+
+    def with_allow_duplicates(self, allow_duplicates: bool) -> "SnapshotInfo":
+        """Return a copy whose snapshots may be asserted by other tests too."""
+        ...
+
+    def with_namespace(self, namespace: Optional[str]) -> "SnapshotInfo":
+        """Return a copy storing its snapshots in the ``namespace``
+        subdirectory of its folder, e.g. ``"http"``; ``None`` stores them in
+        the folder itself."""
+        ...
+
+    def with_storage(self, storage: Optional["ScratchStorage"]) -> "SnapshotInfo":
+        """Return a copy keeping its snapshots in ``storage`` instead of its
+        folder; ``None`` stores them in the folder again."""
+        ...
+
+    def reset_counter(self) -> None:
+        """Restart the numbering of snapshots assigned under this name."""
+        ...
+
+    @staticmethod
+    def from_session() -> "SnapshotInfo":
+        """Build snapshot info for the active interactive session."""
+        ...
+
+    @staticmethod
+    def reset_all_counters() -> None:
+        """Restart the numbering of every snapshot name."""
         ...
 
     def snapshot_folder(self) -> Path:
         """Return the directory snapshots are stored in."""
         ...
 
+    def scope(self) -> Optional[str]:
+        """Return ``"module"`` or ``"session"`` for snapshots shared by the
+        tests of a module or the session, ``None`` for one test's."""
+        ...
+
+    def references(self) -> list[str]:
+        """Return the node ids of the tests that read this scoped snapshot
+        name in this session."""
+        ...
+
+    def stage(self) -> Optional[str]:
+        """Return the pytest stage (``"setup"``, ``"call"`` or
+        ``"teardown"``) the test was in when this info was built."""
+        ...
+
     def last_snapshot_name(self) -> str:
         """Return the name of the most recently used snapshot."""
         ...
@@ -35,38 +161,342 @@ class SnapshotInfo:
         """Return the name the next snapshot assertion will use."""
         ...
 
-    def last_snapshot_path(self, module_path: Optional[str]) -> Path:
-        """Return the path of the most recently used snapshot."""
+    def last_snapshot_path(
+        self, module_path: None = ..., /, *, prefix: Optional[str] = ...
+    ) -> Path:
+        """Return the path of the most recently used snapshot, named with the
+        ``prefix`` strategy (``"module"``, ``"none"`` or a custom prefix)
+        instead of this info's own when given. A module path passed
+        positionally, as before prefixes, raises ``TypeError``."""
+        ...
+
+    def next_snapshot_path(
+        self, module_path: None = ..., /, *, prefix: Optional[str] = ...
+    ) -> Path:
+        """Return the path the next snapshot assertion will write; see
+        :meth:`last_snapshot_path`."""
+        ...
+
+    @property
+    def name(self) -> str:
+        """The base name of the snapshots, before numbering and suffixes."""
+        ...
+
+    @property
+    def folder(self) -> Path:
+        """The folder the snapshots are stored in."""
+        ...
+
+    @property
+    def allow_duplicates(self) -> bool:
+        """Whether the snapshots may be asserted by more than one test."""
+        ...
+
+    @property
+    def relative_test_file_path(self) -> Optional[str]:
+        """The test file recorded in the snapshots' header, or ``None``."""
+        ...
+
+    @property
+    def module(self) -> Optional[str]:
+        """The Python module of the test, if known."""
+        ...
+
+    @property
+    def prefix(self) -> Optional[str]:
+        """Prepended to the snapshot file names, if anything."""
+        ...
+
+    @property
+    def description(self) -> Optional[str]:
+        """Recorded as the snapshots' ``description``, if set."""
+        ...
+
+    @property
+    def expression(self) -> Optional[str]:
+        """Recorded as the snapshots' ``expression``, if set."""
         ...
 
-    def next_snapshot_path(self, module_path: Optional[str]) -> Path:
-        """Return the path the next snapshot assertion will write."""
+    @property
+    def node_id(self) -> Optional[str]:
+        """The pytest node id given to :meth:`from_node_id`, if any."""
         ...
 
+    @property
+    def namespace(self) -> Optional[str]:
+        """The subdirectory of the folder the snapshots are stored in, if any."""
+        ...
+
+class SnapshotSettings:
+    """Settings applied to the snapshots of the assertions given them, or of
+    every assertion on this thread while entered as a context manager."""
+
+    def __init__(
+        self,
+        *,
+        snapshot_path: Optional[_StrPath] = ...,
+        snapshot_suffix: Optional[str] = ...,
+        description: Optional[str] = ...,
+        sort_maps: bool = ...,
+        filters: Optional[Union[Mapping[str, str], Sequence[tuple[str, str]]]] = ...,
+        redactions: Optional[_Redactions] = ...,
+        json_format: Optional[str] = ...,
+        max_line_width: Optional[int] = ...,
+        namespace: Optional[str] = ...,
+        storage: Optional["ScratchStorage"] = ...,
+    ) -> None: ...
+    @property
+    def snapshot_path(self) -> Optional[Path]:
+        """The folder snapshots are stored in, if set."""
+        ...
+
+    @property
+    def snapshot_suffix(self) -> Optional[str]:
+        """Appended to snapshot names, if set."""
+        ...
+
+    @property
+    def description(self) -> Optional[str]:
+        """Recorded in the snapshot header, if set."""
+        ...
+
+    @property
+    def sort_maps(self) -> bool:
+        """Whether the keys of serialized maps are sorted."""
+        ...
+
+    @property
+    def filters(self) -> list[tuple[str, str]]:
+        """The ``(regex, replacement)`` filters, in the order they apply."""
+        ...
+
+    @property
+    def json_format(self) -> Optional[str]:
+        """How JSON snapshots are laid out (``"pretty"``, ``"compact"`` or
+        ``"yaml"``), if set."""
+        ...
+
+    @property
+    def max_line_width(self) -> Optional[int]:
+        """The line width JSON snapshots are laid out within, if set."""
+        ...
+
+    @property
+    def namespace(self) -> Optional[str]:
+        """The subdirectory of their folder snapshots are stored in, if set."""
+        ...
+
+    @property
+    def storage(self) -> Optional["ScratchStorage"]:
+        """The ``ScratchStorage`` snapshots are kept in, if set."""
+        ...
+
+    @staticmethod
+    def current(settings: Optional["SnapshotSettings"] = ...) -> "SnapshotSettings":
+        """Return the settings entered on this thread, with ``settings``
+        applied over them, as one ``SnapshotSettings``."""
+        ...
+
+    def bind(self, func: Callable[..., Any], *args: Any, **kwargs: Any) -> Any:
+        """Call ``func(*args, **kwargs)`` with these settings entered and
+        return its result."""
+        ...
+
+    def __enter__(self) -> "SnapshotSettings": ...
+    def __exit__(self, *_exc_info: Any) -> bool: ...
+
+class ScratchStorage:
+    """Keeps the snapshots of the ``SnapshotInfo``s given it out of their
+    folders, in a private scratch folder of the system temp directory while
+    insta works on them."""
+
+    def __init__(self) -> None: ...
+    def files(self) -> dict[Path, bytes]:
+        """Return the stored files, by the path their ``SnapshotInfo`` gives
+        them, with their contents."""
+        ...
+
+    def read(self, path: _StrPath) -> Optional[bytes]:
+        """Return the contents of the stored file at ``path``, or ``None``."""
+        ...
+
+    def clear(self) -> None:
+        """Forget every stored file."""
+        ...
+
+    def __len__(self) -> int: ...
+
+class SnapshotBuffer:
+    """Read-only, memory-mapped contents of a large binary snapshot.
+
+    Supports the buffer protocol (``memoryview``), ``len()``, ``bytes()`` and
+    equality with bytes-like values.
+    """
+
+    def __len__(self) -> int: ...
+    def __bytes__(self) -> bytes: ...
+    def __buffer__(self, flags: int) -> memoryview: ...
+
 class PySnapshot:
-    """A snapshot loaded from disk via insta."""
+    """A snapshot loaded from disk via insta; equal to another loaded from the
+    same file with the same contents."""
 
     @staticmethod
     def from_file(p: _StrPath) -> "PySnapshot":
-        """Load a snapshot from ``p``."""
+        """Load a snapshot from ``p``, checking its body against the
+        checksum in its header."""
+        ...
+
+    def contents(
+        self, encryption_key: Optional[str] = ...
+    ) -> Union[bytes, SnapshotBuffer]:
+        """Return the snapshot contents, decrypting or decompressing if needed.
+
+        Large plain binary snapshots are memory-mapped and returned as a
+        :class:`SnapshotBuffer` instead of ``bytes``.
+        """
+        ...
+
+    def contents_text(self, encryption_key: Optional[str] = ...) -> str:
+        """Return a text snapshot's contents as ``str``, decrypting if needed."""
+        ...
+
+    def metadata(self) -> dict[str, Any]:
+        """Return the snapshot's ``info`` metadata in header order: its
+        provenance (``pysnaptest_version``, ``python_version``, ``test``,
+        ``created_at``, ``git_commit``) and any mock or duplicate fields."""
+        ...
+
+    @property
+    def path(self) -> Path:
+        """The ``.snap`` file the snapshot was loaded from."""
+        ...
+
+    @property
+    def kind(self) -> str:
+        """``"text"`` or ``"binary"``."""
         ...
 
-    def contents(self) -> bytes:
-        """Return the snapshot contents as raw bytes."""
+    @property
+    def compressed(self) -> bool:
+        """Whether the binary data is stored zstd-compressed."""
         ...
 
+    @property
+    def external(self) -> bool:
+        """Whether the binary data is stored outside the repository."""
+        ...
+
+class patch_json_snapshot:
+    """Patch a function so calls are snapshot tested.
+
+    Usable as a context manager, returning the snapshotting mock, or as a
+    decorator. The options are those of :func:`pysnaptest.mock_json_snapshot`.
+    """
+
+    def __init__(
+        self,
+        target: str,
+        *,
+        record: bool = ...,
+        snapshot_path: Optional[str] = ...,
+        snapshot_name: Optional[str] = ...,
+        redactions: Optional[_Redactions] = ...,
+        allow_duplicates: bool = ...,
+        encryption_key: Optional[str] = ...,
+        simulate_latency: Optional[float] = ...,
+        side_effects: Optional[Iterable[Any]] = ...,
+        mode: str = ...,
+        max_age_days: Optional[float] = ...,
+        ignore: Optional[list[str]] = ...,
+        node_id: Optional[str] = ...,
+        scope: Optional[str] = ...,
+        namespace: Optional[str] = ...,
+        decode: Optional[Callable[[Any], Any]] = ...,
+        encode: Optional[Callable[[Any], Any]] = ...,
+    ) -> None: ...
+    @property
+    def target(self) -> str:
+        """The dotted path being patched."""
+        ...
+
+    def __enter__(self) -> Callable[..., Any]: ...
+    def __exit__(self, *_exc_info: Any) -> bool: ...
+    def __call__(self, func: _F) -> _F: ...
+
+class golden:
+    """Wrap a pure function so every call is asserted against a snapshot."""
+
+    def __init__(
+        self, func: Callable[..., Any], test_info: Optional[SnapshotInfo] = ...
+    ) -> None: ...
+    @property
+    def __wrapped__(self) -> Callable[..., Any]:
+        """The wrapped function."""
+        ...
+
+    def __call__(self, *args: Any, **kwargs: Any) -> Any: ...
+
+def characterize(
+    func: Callable[..., Any],
+    inputs: Sequence[Any],
+    test_info: Optional[SnapshotInfo] = ...,
+) -> list[tuple[int, Path, str]]:
+    """Assert one call snapshot per input; returns (index, path, status)."""
+    ...
+
 def assert_json_snapshot(
     test_info: SnapshotInfo,
     result: Any,
     redactions: Optional[_Redactions] = ...,
+    encryption_key: Optional[str] = ...,
+    sort_arrays_by: Optional[dict[str, str]] = ...,
+    ignore: Optional[list[str]] = ...,
+    round_floats: Optional[int] = ...,
+    json_format: Optional[str] = ...,
+    max_line_width: Optional[int] = ...,
 ) -> None:
     """Assert that ``result`` matches its stored JSON snapshot."""
     ...
 
+def assert_many_json_snapshots(
+    test_info: SnapshotInfo,
+    values: dict[str, Any],
+    redactions: Optional[_Redactions] = ...,
+) -> None:
+    """Assert one JSON snapshot per labeled value, named ``<name>_<label>``,
+    binding the snapshot settings once for all of them."""
+    ...
+
+def assert_graphql_snapshot(
+    test_info: SnapshotInfo,
+    response: Any,
+    query: Optional[str] = ...,
+    operation_name: Optional[str] = ...,
+    separate_errors: bool = ...,
+    redactions: Optional[_Redactions] = ...,
+) -> None:
+    """Assert that a GraphQL response, in the query's selection order and with
+    tracing data and request ids redacted, matches its stored JSON snapshot."""
+    ...
+
+def assert_openapi_snapshot(
+    test_info: SnapshotInfo, document: Any, keep_extensions: bool = ...
+) -> None:
+    """Assert that an OpenAPI document matches its stored JSON snapshot,
+    reporting a mismatch as added, removed and changed operations and schemas."""
+    ...
+
 def assert_csv_snapshot(
     test_info: SnapshotInfo,
     result: Any,
     redactions: Optional[_Redactions] = ...,
+    max_rows: Optional[int] = ...,
+    sample: Optional[int] = ...,
+    head: Optional[int] = ...,
+    tail: Optional[int] = ...,
+    columns: Optional[list[str]] = ...,
+    summary: bool = ...,
 ) -> None:
     """Assert that CSV text matches its stored snapshot."""
     ...
@@ -75,11 +505,271 @@ def assert_binary_snapshot(
     test_info: SnapshotInfo,
     extension: str,
     result: bytes,
+    readable_diff_renderer: Optional[Callable[[bytes], str]] = ...,
+    compress: bool = ...,
+    scrub_metadata: bool = ...,
 ) -> None:
     """Assert that binary data matches its stored snapshot."""
     ...
 
-def assert_snapshot(test_info: SnapshotInfo, result: Any) -> None:
+def strip_image_metadata(image: bytes) -> bytes:
+    """A PNG or JPEG image without its timestamps, text and EXIF/XMP data."""
+    ...
+
+def assert_pdf_snapshot(
+    test_info: SnapshotInfo, result: bytes, mode: str = ...
+) -> None:
+    """Assert that a PDF's text layer (``mode="text"``) or bytes
+    (``mode="binary"``) match its stored snapshot."""
+    ...
+
+def assert_term_snapshot(
+    test_info: SnapshotInfo,
+    text: str,
+    strip_ansi: bool = ...,
+    width: Optional[int] = ...,
+) -> None:
+    """Assert that terminal output, with escape sequences stripped or shown as
+    markers, matches its stored snapshot."""
+    ...
+
+def assert_logs_snapshot(
+    test_info: SnapshotInfo, records: Iterable[Any], normalize: bool = ...
+) -> None:
+    """Assert that log records, one ``LEVEL logger: message`` line each,
+    match their stored snapshot."""
+    ...
+
+def assert_sql_snapshot(
+    test_info: SnapshotInfo,
+    sql: Union[str, Sequence[str]],
+    dialect: Optional[str] = ...,
+) -> None:
+    """Assert that SQL, formatted with placeholders normalized to ``?``,
+    matches its stored snapshot."""
+    ...
+
+def assert_audio_snapshot(
+    test_info: SnapshotInfo,
+    result: bytes,
+    extension: str = ...,
+    mode: str = ...,
+    tolerance: Optional[float] = ...,
+) -> None:
+    """Assert that encoded audio matches its stored binary snapshot, comparing
+    decoded samples (``mode="pcm"``) or spectral fingerprints
+    (``mode="fingerprint"``) within ``tolerance``."""
+    ...
+
+def assert_hash_snapshot(
+    test_info: SnapshotInfo, data: Union[bytes, _StrPath], algorithm: str = ...
+) -> None:
+    """Assert that the digest and size of data (or a file) match its snapshot."""
+    ...
+
+def assert_stats_snapshot(
+    test_info: SnapshotInfo,
+    values: Union[Sequence[Optional[float]], Mapping[str, Sequence[Optional[float]]]],
+    stats: Optional[list[str]] = ...,
+    decimals: int = ...,
+) -> None:
+    """Assert that rounded summary statistics of numeric values match."""
+    ...
+
+def assert_call_snapshot(
+    test_info: SnapshotInfo, func: Callable[..., Any], *args: Any, **kwargs: Any
+) -> Any:
+    """Call ``func`` and assert its arguments and result match one snapshot."""
+    ...
+
+def sanitize_snapshot_name(name: str) -> str:
+    """The file-name-safe form of a snapshot name derived from a test id."""
+    ...
+
+def explain_snapshot_name(
+    test_node_id: str, overrides: Optional[Mapping[str, Any]] = ...
+) -> dict[str, Any]:
+    """Every component of the next snapshot file name of the pytest test
+    ``test_node_id``: ``test_file``, ``folder``, ``prefix``, ``test_name``,
+    ``counter``, ``suffix``, ``file_name``, ``path`` and whether it
+    ``exists``. ``overrides`` takes the ``snapshot_path``, ``snapshot_name``,
+    ``allow_duplicates`` and ``prefix`` of :meth:`SnapshotInfo.from_pytest`."""
+    ...
+
+def write_imported_snapshot(
+    test_path: _StrPath,
+    recorded_path: _StrPath,
+    test_name: str,
+    index: int,
+    contents: Any,
+    imported_from: str,
+) -> Optional[Path]:
+    """Write ``contents`` as the ``index``th snapshot (counting from 1) of the
+    test ``test_name`` in ``test_path``: a text snapshot for a string, a JSON
+    snapshot otherwise. Returns the path written, or ``None`` when that
+    snapshot already exists."""
+    ...
+
+def upgrade_snapshot(path: _StrPath) -> Optional[Path]:
+    """Bring the snapshot at ``path`` to the current layout (sanitized name,
+    current ``format_version``). Returns its path once upgraded, or ``None``
+    when it already was."""
+    ...
+
+def seed_label(seed: Any) -> str:
+    """The form of ``seed`` used in seed snapshot names."""
+    ...
+
+def assert_snapshot_for_seed(
+    test_info: SnapshotInfo,
+    seed: Any,
+    value: Any,
+    redactions: Optional[_Redactions] = ...,
+) -> None:
+    """Assert that ``value`` matches the snapshot stored for ``seed``."""
+    ...
+
+def assert_schema_snapshot(test_info: SnapshotInfo, value: Any) -> None:
+    """Assert that the inferred structure of ``value`` matches its snapshot."""
+    ...
+
+class SchemaViolationError(AssertionError):
+    """A payload does not match its schema snapshot."""
+
+    violations: list[dict[str, Optional[str]]]
+
+class SerializationError(ValueError):
+    """A value cannot be serialized to JSON, e.g. as it refers to itself."""
+
+class SnapshotCorruptedError(ValueError):
+    """A snapshot's body does not match the checksum in its header."""
+
+class SnapshotMismatchError(AssertionError):
+    """A snapshot did not match its committed value."""
+
+    snapshot_failure: Optional[dict[str, Optional[str]]]
+
+class SnapshotReadOnlyError(AssertionError):
+    """A snapshot file would have been written with read-only snapshots on."""
+
+class SnapshotTeardownWarning(UserWarning):
+    """A snapshot was asserted while pytest tore its test down."""
+
+def validate_against_schema_snapshot(test_info: SnapshotInfo, value: Any) -> None:
+    """Validate ``value`` against its committed schema snapshot."""
+    ...
+
+def assert_vector_snapshot(
+    test_info: SnapshotInfo,
+    vector: Sequence[float],
+    atol: float = ...,
+    rtol: float = ...,
+    metric: str = ...,
+) -> None:
+    """Assert that a vector is within tolerance of its stored snapshot."""
+    ...
+
+def configure_storage(
+    directory: Optional[_StrPath] = ...,
+    put: Optional[Callable[[str, bytes], None]] = ...,
+    get: Optional[Callable[[str], bytes]] = ...,
+    threshold: int = ...,
+) -> None:
+    """Store binary snapshots above ``threshold`` bytes in external storage."""
+    ...
+
+def set_default_redactions(redactions: Optional[_Redactions]) -> None:
+    """Set the redactions every JSON and CSV assertion and mocked call
+    applies under its own; ``None`` clears them."""
+    ...
+
+def set_round_floats(decimals: Optional[int]) -> None:
+    """Round every float of the JSON snapshots to ``decimals`` places;
+    ``None`` stops rounding."""
+    ...
+
+def set_serialization_limits(
+    max_depth: Optional[int] = ..., max_size: Optional[int] = ...
+) -> None:
+    """Limit how deeply nested (256 containers by default) and how large
+    (100 MiB of compact JSON by default) a converted value may be; ``None``
+    lifts a limit."""
+    ...
+
+def serialization_limits_info() -> dict[str, Optional[int]]:
+    """Return the ``max_depth`` and ``max_size`` values are converted within."""
+    ...
+
+def get_effective_config() -> dict[str, Any]:
+    """Return the insta configuration assertions run under: ``update``,
+    ``behavior``, ``force_pass``, ``output``, ``workspace_root``, ``ci``, and
+    the variables ``ignored`` as they changed after insta read them."""
+    ...
+
+def set_json_format(
+    format: Optional[str] = ..., max_line_width: Optional[int] = ...
+) -> None:
+    """Lay out every JSON snapshot as ``format`` (``"pretty"``,
+    ``"compact"`` or ``"yaml"``) within ``max_line_width``; ``None``
+    restores insta's pretty JSON."""
+    ...
+
+def set_structural_json_comparison(enabled: bool = ...) -> None:
+    """Let JSON snapshots that differ only in key order or layout pass."""
+    ...
+
+def pending_summary() -> dict[str, Any]:
+    """Summarize the pending snapshots this process's assertions wrote that
+    still await review: ``{"total": N, "tests": {node_id: {"count": n,
+    "paths": [...]}}, "force_passed": {path: diff}}``, the last holding those
+    passed under ``INSTA_FORCE_PASS=1``."""
+    ...
+
+def set_diff_options(
+    context_lines: int = ...,
+    word_diff: bool = ...,
+    max_lines: Optional[int] = ...,
+    color: Optional[bool] = ...,
+    html: bool = ...,
+) -> None:
+    """Set how the diff carried by a failing text assertion's
+    ``AssertionError`` is rendered, and whether a side-by-side HTML diff is
+    written next to the ``.snap.new``."""
+    ...
+
+def set_fail_fast(enabled: bool = ...) -> None:
+    """Raise on the first snapshot mismatch (the default), or collect
+    mismatches for :func:`take_soft_failures` instead."""
+    ...
+
+def set_read_only(enabled: bool = ...) -> None:
+    """Fail every assertion or mocked call that would create or rewrite a
+    snapshot file, writing nothing; off by default."""
+    ...
+
+def take_soft_failures() -> list[SnapshotMismatchError]:
+    """Take the snapshot mismatches collected with fail-fast off."""
+    ...
+
+def set_config_redactions(redactions: Optional[_Redactions]) -> None:
+    """Install the ``[tool.pysnaptest.redactions]`` table of
+    ``pyproject.toml``; used by the pytest plugin."""
+    ...
+
+def observe_test_stage(node_id: str, stage: str) -> bool:
+    """Note the stage pytest runs the test ``node_id`` in, restarting its
+    snapshot numbering when it is run again; used by the pytest plugin."""
+    ...
+
+def settle_repetitions(node_id: Optional[str], passed: bool = ...) -> None:
+    """Write the ``repetitions`` counted for the ``allow_duplicates``
+    snapshots of the test ``node_id`` where they changed, or only forget them
+    unless it ``passed``; used by the pytest plugin."""
+    ...
+
+def assert_snapshot(
+    test_info: SnapshotInfo, result: Any, encryption_key: Optional[str] = ...
+) -> None:
     """Assert that a value matches its stored text snapshot."""
     ...
 
@@ -88,8 +778,15 @@ def assert_json_snapshot_named(
     result: Any,
     name: str,
     redactions: Optional[_Redactions] = ...,
+    encryption_key: Optional[str] = ...,
+    duration_ms: Optional[float] = ...,
+    recorded_at: Optional[str] = ...,
+    ignore: Optional[list[str]] = ...,
+    result_type: Optional[type] = ...,
 ) -> None:
-    """Assert a JSON snapshot under an explicit ``name`` (no counter tick)."""
+    """Assert a JSON snapshot under an explicit ``name`` (no counter tick),
+    keeping ``duration_ms``, ``recorded_at`` and ``result_type`` in its
+    metadata."""
     ...
 
 def prepare_mock_call(
@@ -98,13 +795,92 @@ def prepare_mock_call(
     request: Any,
     record: bool,
     redactions: Optional[_Redactions] = ...,
+    encryption_key: Optional[str] = ...,
+    matches: Optional[Callable[[Any, Any], bool]] = ...,
+    ignore: Optional[list[str]] = ...,
+    dry_run: bool = ...,
 ) -> tuple[str, Path, bool]:
-    """Scope ``test_info`` to a mock, write its request snapshot, and return
-    ``(name, response_path, do_record)`` for the response."""
+    """Scope ``test_info`` to a mock, write its request snapshot (unless
+    ``dry_run``), and return ``(name, response_path, do_record)`` for the
+    response. On replay, ``matches(recorded, request)`` may accept a
+    differing request."""
+    ...
+
+def preview_json_snapshot_named(
+    test_info: SnapshotInfo,
+    result: Any,
+    name: str,
+    redactions: Optional[_Redactions] = ...,
+    encryption_key: Optional[str] = ...,
+    ignore: Optional[list[str]] = ...,
+) -> tuple[Path, Optional[str]]:
+    """Return the path of the JSON snapshot ``name`` and how asserting
+    ``result`` would change it (``"new"``, ``"changed"`` or ``None``),
+    without writing anything."""
+    ...
+
+def validate_redactions(value: Any, redactions: _Redactions) -> list[str]:
+    """Return the selectors of ``redactions`` that match nothing in ``value``."""
+    ...
+
+def scan_for_secrets(text: str) -> list[str]:
+    """Return the names of the credential rules that match ``text``."""
     ...
 
-def read_json_snapshot(snapshot_path: _StrPath) -> Any:
-    """Load a recorded JSON snapshot file and return its parsed value."""
+def read_json_snapshot(
+    snapshot_path: _StrPath, encryption_key: Optional[str] = ...
+) -> Any:
+    """Load a recorded JSON snapshot file and return its parsed value.
+
+    A binary recording is returned as ``bytes``, or passed through the
+    decoder registered for its extension with :func:`register_replay_decoder`.
+    """
+    ...
+
+def read_json_snapshot_text(
+    snapshot_path: _StrPath, encryption_key: Optional[str] = ...
+) -> Union[str, bytes]:
+    """Return a recorded JSON snapshot's body as text, decrypted if needed, or
+    a binary recording's bytes."""
+    ...
+
+def register_replay_decoder(
+    extension: str, decoder: Optional[Callable[[bytes], Any]]
+) -> None:
+    """Decode binary recorded responses with ``extension`` using ``decoder``
+    on replay; ``None`` unregisters it."""
+    ...
+
+def recorded_duration_ms(snapshot_path: _StrPath) -> Optional[float]:
+    """Return the call duration recorded in a response snapshot, if any."""
+    ...
+
+def recorded_at(snapshot_path: _StrPath) -> Optional[str]:
+    """Return when a response snapshot's call was recorded (UTC), if known."""
+    ...
+
+def recorded_type(snapshot_path: _StrPath) -> Optional[str]:
+    """Return the ``module:qualname`` of the result a response snapshot was
+    recorded from, if not a builtin type."""
+    ...
+
+def recorded_by(snapshot_path: _StrPath) -> Optional[str]:
+    """Return the node id of the test that recorded a mock snapshot, if known."""
+    ...
+
+def clear_replay_cache() -> None:
+    """Drop every recording cached for mock replay and reset the counts of
+    :func:`replay_cache_info`."""
+    ...
+
+def set_replay_cache_size(size: int) -> None:
+    """Keep at most ``size`` parsed recordings for mock replay (128 by
+    default); ``0`` turns caching off."""
+    ...
+
+def replay_cache_info() -> dict[str, int]:
+    """Return the replay cache's ``hits``, ``misses``, cached ``entries`` and
+    ``capacity``."""
     ...
 
 def accept_pending_snapshot(pending_path: _StrPath) -> Path:
@@ -116,8 +892,23 @@ def reject_pending_snapshot(pending_path: _StrPath) -> None:
     ...
 
 def print_pending_diff(
-    pending_path: _StrPath,
-    workspace_root: Optional[_StrPath] = ...,
+    pending_path: _StrPath, workspace_root: Optional[_StrPath] = ...
 ) -> None:
     """Print insta's own diff for a pending snapshot against its target."""
     ...
+
+def delete_snapshot(snapshot_path: _StrPath) -> list[Path]:
+    """Delete a committed snapshot and its binary sidecar file, if any;
+    returns the removed paths."""
+    ...
+
+def snapshot_content_hash(snapshot_path: _StrPath) -> str:
+    """Return the SHA-256 of a snapshot's contents, as its folder's manifest
+    lists it."""
+    ...
+
+def render_text_diff(
+    old: str, new: str, old_label: Optional[str] = ..., new_label: Optional[str] = ...
+) -> str:
+    """A unified diff of two texts, as insta renders it."""
+    ...
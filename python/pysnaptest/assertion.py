@@ -7,19 +7,56 @@ structures.
 
 from __future__ import annotations
 
-from typing import TYPE_CHECKING, Any, Callable, Dict, Optional, Union, overload
+from typing import (
+    TYPE_CHECKING,
+    Any,
+    Callable,
+    Dict,
+    Iterable,
+    List,
+    Mapping,
+    NamedTuple,
+    Optional,
+    Sequence,
+    Union,
+    overload,
+)
+from contextlib import nullcontext
 from functools import partial, wraps
+from pathlib import Path
 import asyncio
 import io
+import json
+import os
 
 from ._pysnaptest import (
     assert_json_snapshot as _assert_json_snapshot,
+    assert_many_json_snapshots as _assert_many_json_snapshots,
+    assert_graphql_snapshot as _assert_graphql_snapshot,
+    assert_openapi_snapshot as _assert_openapi_snapshot,
+    assert_call_snapshot as _assert_call_snapshot,
+    golden,
+    characterize as _characterize,
     assert_csv_snapshot as _assert_csv_snapshot,
     assert_snapshot as _assert_snapshot,
     assert_binary_snapshot as _assert_binary_snapshot,
+    assert_pdf_snapshot as _assert_pdf_snapshot,
+    assert_audio_snapshot as _assert_audio_snapshot,
+    assert_term_snapshot as _assert_term_snapshot,
+    assert_logs_snapshot as _assert_logs_snapshot,
+    assert_sql_snapshot as _assert_sql_snapshot,
+    assert_hash_snapshot as _assert_hash_snapshot,
+    assert_stats_snapshot as _assert_stats_snapshot,
+    assert_schema_snapshot as _assert_schema_snapshot,
+    validate_against_schema_snapshot as _validate_against_schema_snapshot,
+    SchemaViolationError,
+    assert_vector_snapshot as _assert_vector_snapshot,
+    validate_redactions as _validate_redactions,
     SnapshotInfo,
+    SnapshotSettings,
 )
 from .encoders import is_jsonable_object, to_jsonable
+from .presets import Redactions
 
 if TYPE_CHECKING:
     import pandas as pd
@@ -49,10 +86,88 @@ def rounded_redaction(decimals: int) -> int:
     return decimals
 
 
+def hash_redaction() -> str:
+    """Replace values with a short, stable hash, e.g. ``"<hash:1a2b3c4d>"``,
+    so a snapshot still shows whether they changed.
+
+    Returns:
+        str: The ``"hash"`` mode recognised by the snapshot machinery.
+    """
+
+    return "hash"
+
+
+def type_redaction() -> str:
+    """Replace values with their type and length, e.g. ``"<str:len=12>"`` or
+    ``"<int:len=5>"``.
+
+    Returns:
+        str: The ``"type"`` mode recognised by the snapshot machinery.
+    """
+
+    return "type"
+
+
+def truncate_redaction(length: int) -> str:
+    """Cut strings down to their first ``length`` characters, followed by
+    ``…``.
+
+    Args:
+        length: Number of characters to keep.
+
+    Returns:
+        str: The ``"truncate:N"`` mode recognised by the snapshot machinery.
+    """
+
+    return f"truncate:{length}"
+
+
+def validate_redactions(
+    value: Any,
+    redactions: Redactions,
+    custom_encoder: Optional[Dict[type, Callable[[Any], Any]]] = None,
+) -> List[str]:
+    """Report redaction selectors that match nothing in ``value``.
+
+    insta silently ignores a selector that matches nothing, so a typo such as
+    ``.data[].id`` (sequences only) instead of ``.data.*.id`` (map values)
+    leaks the value it was meant to scrub. This runs insta's own selector
+    engine against ``value`` without writing a snapshot.
+
+    Args:
+        value: Payload the redactions would be applied to. It is normalized with
+            :func:`pysnaptest.to_jsonable` exactly like
+            :func:`assert_json_snapshot` does.
+        redactions: Mapping of selectors (or nested field names) to
+            replacement values. Only the selectors are inspected; nested
+            mappings are reported as the selectors they compile to.
+        custom_encoder: Optional mapping of types to encoder callables used when
+            normalizing ``value``.
+
+    Returns:
+        List[str]: Sorted selectors that matched nothing. Empty when every
+        redaction applies.
+
+    Raises:
+        ValueError: If a selector is not valid insta selector syntax.
+    """
+
+    return _validate_redactions(
+        to_jsonable(value, custom_encoder=custom_encoder), redactions
+    )
+
+
 def extract_from_pytest_env(
     snapshot_path: Optional[str] = None,
     snapshot_name: Optional[str] = None,
     allow_duplicates: bool = False,
+    prefix: Optional[str] = None,
+    description: Optional[str] = None,
+    expression: Optional[str] = None,
+    settings: Optional[SnapshotSettings] = None,
+    node_id: Optional[str] = None,
+    scope: Optional[str] = None,
+    namespace: Optional[str] = None,
 ) -> SnapshotInfo:
     """Load snapshot info from the active pytest test.
 
@@ -60,25 +175,63 @@ def extract_from_pytest_env(
         snapshot_path: Optional path override for storing snapshots.
         snapshot_name: Optional name override for the snapshot file.
         allow_duplicates: Whether to allow duplicate snapshot names.
+        prefix: How snapshot file names (``<prefix>__<name>@pysnap.snap``)
+            are prefixed: ``"module"`` with the test's module, e.g.
+            ``test_api``; ``"none"`` not at all; any other string is the
+            prefix itself. Defaults to the ``PYSNAPTEST_PREFIX`` environment
+            variable, or else ``"module"``.
+        description: What the asserted value represents, recorded in the
+            ``description`` of the snapshot header ahead of the test file path.
+        expression: The expression asserted, e.g. ``"client.get('/users')"``,
+            recorded as the ``expression`` of the snapshot header. Left out of
+            the header when not given.
+        settings: :class:`SnapshotSettings` applied over those entered as a
+            context manager. The other arguments win over both.
+        node_id: The pytest node id of the test the snapshots belong to,
+            instead of the active one; see :meth:`SnapshotInfo.from_node_id`.
+        scope: ``"module"`` or ``"session"`` to share the snapshots with
+            every test of the module or session, e.g. those of a shared
+            fixture; see :meth:`SnapshotInfo.from_pytest`.
+        namespace: Store the snapshots in this subdirectory of the snapshot
+            folder, e.g. ``"http"``; see :meth:`SnapshotInfo.with_namespace`.
 
     Returns:
         SnapshotInfo: Snapshot configuration for the active test.
     """
 
-    return SnapshotInfo.from_pytest(
+    info = SnapshotInfo.from_pytest(
         snapshot_path_override=snapshot_path,
         snapshot_name_override=snapshot_name,
         allow_duplicates=allow_duplicates,
+        prefix=prefix,
+        settings=settings,
+        scope=scope,
+        node_id=node_id,
     )
+    if description is not None or expression is not None:
+        info = info.with_header(description=description, expression=expression)
+    if namespace is not None:
+        info = info.with_namespace(namespace)
+    return info
 
 
 def assert_json_snapshot(
     result: Any,
     snapshot_path: Optional[str] = None,
     snapshot_name: Optional[str] = None,
-    redactions: Optional[Dict[str, Union[str, int, None]]] = None,
+    redactions: Optional[Redactions] = None,
     allow_duplicates: bool = False,
     custom_encoder: Optional[Dict[type, Callable[[Any], Any]]] = None,
+    encryption_key: Optional[str] = None,
+    sort_arrays_by: Optional[Dict[str, str]] = None,
+    ignore: Optional[List[str]] = None,
+    prefix: Optional[str] = None,
+    round_floats: Optional[int] = None,
+    description: Optional[str] = None,
+    expression: Optional[str] = None,
+    settings: Optional[SnapshotSettings] = None,
+    json_format: Optional[str] = None,
+    max_line_width: Optional[int] = None,
 ) -> None:
     """Assert that a value matches a stored JSON snapshot.
 
@@ -91,14 +244,42 @@ def assert_json_snapshot(
         result: Object that will be serialized to JSON.
         snapshot_path: Optional path override for storing the snapshot.
         snapshot_name: Optional name override for the snapshot file.
-        redactions: Mapping of selectors to replacement values.
+        redactions: Mapping of selectors to replacement values. Instead of
+            selectors, keys may be field names with nested dicts mirroring the
+            payload, e.g. ``{"user": {"sessions": {"*": {"token": "[t]"}}}}``.
         allow_duplicates: Whether to allow duplicate snapshot names.
         custom_encoder: Optional mapping of types to encoder callables used when
             normalizing ``result``.
+        encryption_key: Encrypt the stored snapshot with this key. Defaults to
+            the ``PYSNAPTEST_KEY`` environment variable; unset means plaintext.
+        sort_arrays_by: Mapping of selectors to the field (a dotted path
+            within each element) to sort the matched arrays by before
+            snapshotting, e.g. ``{".users": "id"}``. Applied before redactions.
+        ignore: Selectors of values to leave out of the snapshot entirely,
+            keys included, e.g. ``[".metadata.request_id"]``.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
+        round_floats: Round every float in ``result`` to this many decimal
+            places. Defaults to the places set with
+            :func:`pysnaptest.set_round_floats`, if any.
+        description: What the value represents, recorded in the snapshot
+            header; see :func:`extract_from_pytest_env`.
+        expression: The expression asserted, recorded in the snapshot header;
+            see :func:`extract_from_pytest_env`.
+        settings: :class:`SnapshotSettings` for this assertion; see
+            :func:`extract_from_pytest_env`.
+        json_format: How the snapshot is laid out: ``"pretty"`` (2-space
+            JSON), ``"compact"`` (one line while it fits in
+            ``max_line_width``) or ``"yaml"``. Defaults to the format of
+            ``settings`` or :func:`pysnaptest.set_json_format`, else pretty.
+        max_line_width: The line width the snapshot is laid out within:
+            pretty snapshots keep the arrays and objects that fit on one
+            line, compact ones are pretty-printed beyond it (120 by default).
 
     Raises:
         TypeError: If ``result`` is a pandas or polars ``DataFrame``. Use
             :func:`assert_dataframe_snapshot` instead.
+        ValueError: If ``json_format`` is not a known format.
     """
 
     if try_is_pandas_df(result) or try_is_polars_df(result):
@@ -108,16 +289,284 @@ def assert_json_snapshot(
         )
 
     result = to_jsonable(result, custom_encoder=custom_encoder)
-    test_info = extract_from_pytest_env(snapshot_path, snapshot_name, allow_duplicates)
-    _assert_json_snapshot(test_info, result, redactions)
+    test_info = extract_from_pytest_env(
+        snapshot_path,
+        snapshot_name,
+        allow_duplicates,
+        prefix,
+        description,
+        expression,
+        settings,
+    )
+    _assert_json_snapshot(
+        test_info,
+        result,
+        redactions,
+        encryption_key,
+        sort_arrays_by,
+        ignore,
+        round_floats,
+        json_format,
+        max_line_width,
+    )
+
+
+def assert_many_json_snapshots(
+    values: Mapping[str, Any],
+    snapshot_path: Optional[str] = None,
+    snapshot_name: Optional[str] = None,
+    redactions: Optional[Redactions] = None,
+    allow_duplicates: bool = False,
+    custom_encoder: Optional[Dict[type, Callable[[Any], Any]]] = None,
+    prefix: Optional[str] = None,
+    description: Optional[str] = None,
+    settings: Optional[SnapshotSettings] = None,
+) -> None:
+    """Assert that each labeled value matches its stored JSON snapshot.
+
+    Every value is stored as ``<name>_<label>``, like a separate
+    :func:`assert_json_snapshot` call, but all of them are asserted in one
+    call that sets up the snapshot settings once, which adds up for tests
+    with dozens of small snapshots. Every value is asserted even when an
+    earlier one fails, so a first run records all of them.
+
+    Args:
+        values: Mapping of labels to the objects to serialize to JSON, each
+            normalized with :func:`pysnaptest.to_jsonable`.
+        snapshot_path: Optional path override for storing the snapshots.
+        snapshot_name: Optional override for the name the labels are
+            appended to.
+        redactions: Mapping of selectors to replacement values, applied to
+            every value.
+        allow_duplicates: Whether to allow duplicate snapshot names.
+        custom_encoder: Optional mapping of types to encoder callables used when
+            normalizing the values.
+        prefix: Prefix strategy of the snapshot file names; see
+            :func:`extract_from_pytest_env`.
+        description: What the values represent, recorded in the snapshot
+            headers; see :func:`extract_from_pytest_env`.
+        settings: :class:`SnapshotSettings` for these assertions; see
+            :func:`extract_from_pytest_env`.
+
+    Raises:
+        AssertionError: Listing the labels whose snapshots changed or are new.
+    """
+
+    values = {
+        label: to_jsonable(value, custom_encoder=custom_encoder)
+        for label, value in values.items()
+    }
+    test_info = extract_from_pytest_env(
+        snapshot_path,
+        snapshot_name,
+        allow_duplicates,
+        prefix,
+        description,
+        None,
+        settings,
+    )
+    _assert_many_json_snapshots(test_info, values, redactions)
+
+
+def assert_graphql_snapshot(
+    response: Any,
+    query: Optional[str] = None,
+    snapshot_path: Optional[str] = None,
+    snapshot_name: Optional[str] = None,
+    operation_name: Optional[str] = None,
+    separate_errors: bool = False,
+    redactions: Optional[Redactions] = None,
+    allow_duplicates: bool = False,
+    prefix: Optional[str] = None,
+) -> None:
+    """Assert that a GraphQL response matches a stored JSON snapshot.
+
+    Given the ``query``, the fields of ``data`` are written in the order the
+    query selects them (fragments included), rather than alphabetically.
+    ``extensions.tracing`` and request ids in ``extensions`` are redacted
+    automatically.
+
+    Args:
+        response: The response as a dict, a JSON string or bytes, or an
+            HTTP response object with a ``json()`` method.
+        query: The GraphQL document that was executed.
+        snapshot_path: Optional path override for storing the snapshot.
+        snapshot_name: Optional name override for the snapshot file.
+        operation_name: The operation of ``query`` that was executed, when it
+            defines several; defaults to the first.
+        separate_errors: Store ``errors`` in a ``<name>-errors`` snapshot of
+            its own (an empty list when there are none), so data and error
+            changes show up separately.
+        redactions: Mapping of selectors to replacement values, applied to
+            both snapshots.
+        allow_duplicates: Whether to allow duplicate snapshot names.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
+
+    Raises:
+        ValueError: If ``query`` can't be parsed, names no such
+            ``operation_name``, or the response is not a JSON object.
+    """
+
+    if isinstance(response, (str, bytes)):
+        response = json.loads(response)
+    elif callable(getattr(response, "json", None)):
+        response = response.json()
+    test_info = extract_from_pytest_env(
+        snapshot_path, snapshot_name, allow_duplicates, prefix
+    )
+    _assert_graphql_snapshot(
+        test_info, response, query, operation_name, separate_errors, redactions
+    )
+
+
+def _load_openapi(document: Any) -> Any:
+    if callable(getattr(document, "openapi", None)):
+        # A FastAPI (or compatible) application.
+        return document.openapi()
+    if isinstance(document, os.PathLike):
+        document = Path(document).read_text(encoding="utf-8")
+    if isinstance(document, bytes):
+        document = document.decode("utf-8")
+    if not isinstance(document, str):
+        return document
+    if document.lstrip().startswith("{"):
+        return json.loads(document)
+    try:
+        import yaml
+    except ImportError as e:
+        raise ImportError(
+            "Reading a YAML OpenAPI document requires PyYAML; install it or pass "
+            "the document as a dict or JSON"
+        ) from e
+    return yaml.safe_load(document)
+
+
+def assert_openapi_snapshot(
+    document: Any,
+    snapshot_path: Optional[str] = None,
+    snapshot_name: Optional[str] = None,
+    keep_extensions: bool = False,
+    allow_duplicates: bool = False,
+    prefix: Optional[str] = None,
+) -> None:
+    """Assert that an OpenAPI document matches the stored contract.
+
+    The document is stored as JSON without vendor extensions (``x-*`` keys),
+    and with ``required``, ``parameters`` and ``$ref``-only lists such as
+    ``oneOf`` sorted. On a mismatch, the error lists the operations and
+    schemas that were added (``+``), removed (``-``) or changed (``~``)
+    instead of a line diff.
+
+    Args:
+        document: The document as a dict, JSON or YAML text (YAML needs
+            PyYAML), a path to a file, or an application with an ``openapi()``
+            method, such as FastAPI.
+        snapshot_path: Optional path override for storing the snapshot.
+        snapshot_name: Optional name override for the snapshot file.
+        keep_extensions: Keep ``x-*`` vendor extensions in the snapshot.
+        allow_duplicates: Whether to allow duplicate snapshot names.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
+
+    Raises:
+        ValueError: If ``document`` has no ``openapi`` or ``swagger`` field.
+    """
+
+    document = _load_openapi(document)
+    test_info = extract_from_pytest_env(
+        snapshot_path, snapshot_name, allow_duplicates, prefix
+    )
+    _assert_openapi_snapshot(test_info, document, keep_extensions)
+
+
+def assert_call_snapshot(func: Callable, /, *args: Any, **kwargs: Any) -> Any:
+    """Call ``func`` and assert that its inputs and result match the stored snapshot.
+
+    One JSON snapshot records the function's ``__qualname__``, the ``args``
+    and ``kwargs`` it was called with and the ``result`` it returned, each
+    normalized with :func:`pysnaptest.to_jsonable`, so a regression shows what
+    the function was given next to what it produced. Exceptions raised by
+    ``func`` propagate without being recorded.
+
+    Args:
+        func: The function under test.
+        *args: Positional arguments passed to ``func``.
+        **kwargs: Keyword arguments passed to ``func``.
+
+    Returns:
+        Any: Whatever ``func`` returned.
+    """
+
+    return _assert_call_snapshot(extract_from_pytest_env(), func, *args, **kwargs)
+
+
+class Characterization(NamedTuple):
+    """The snapshot :func:`characterize` asserted for one input."""
+
+    input: Any
+    snapshot: Path
+    status: str
+    """``"created"`` if the snapshot was new, otherwise ``"unchanged"``."""
+
+
+def characterize(
+    func: Callable,
+    inputs: Sequence[Any],
+    snapshot_path: Optional[str] = None,
+    snapshot_name: Optional[str] = None,
+    prefix: Optional[str] = None,
+) -> List[Characterization]:
+    """Pin the current behaviour of ``func`` across many inputs at once.
+
+    Each input gets its own :func:`assert_call_snapshot`-style snapshot, named
+    ``<test>_<func>-<digest>`` after a digest of its arguments, so inputs can
+    be added or reordered without renaming the others. A tuple input is
+    unpacked into positional arguments; anything else is the only argument.
+    Every input is asserted even after a failure, so a first run (with
+    ``INSTA_UPDATE=always``, or followed by ``pysnaptest review``) records the
+    whole batch in one go.
+
+    Args:
+        func: The function to characterize.
+        inputs: Inputs to call it with.
+        snapshot_path: Optional path override for storing the snapshots.
+        snapshot_name: Optional name override for the snapshot files.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
+
+    Returns:
+        List[Characterization]: One entry per input, in order.
+
+    Raises:
+        AssertionError: Listing every input whose snapshot changed or is new
+            and pending review.
+    """
+
+    inputs = list(inputs)
+    test_info = extract_from_pytest_env(snapshot_path, snapshot_name, prefix=prefix)
+    return [
+        Characterization(inputs[index], Path(path), status)
+        for index, path, status in _characterize(func, inputs, test_info)
+    ]
 
 
 def assert_csv_snapshot(
     result: Any,
     snapshot_path: Optional[str] = None,
     snapshot_name: Optional[str] = None,
-    redactions: Optional[Dict[str, Union[str, int, None]]] = None,
+    redactions: Optional[Redactions] = None,
     allow_duplicates: bool = False,
+    max_rows: Optional[int] = None,
+    sample: Optional[int] = None,
+    head: Optional[int] = None,
+    tail: Optional[int] = None,
+    columns: Optional[List[str]] = None,
+    summary: bool = False,
+    prefix: Optional[str] = None,
+    description: Optional[str] = None,
+    expression: Optional[str] = None,
+    settings: Optional[SnapshotSettings] = None,
 ) -> None:
     """Assert that CSV text matches the stored snapshot.
 
@@ -127,10 +576,61 @@ def assert_csv_snapshot(
         snapshot_name: Optional name override for the snapshot file.
         redactions: Mapping of selectors to replacement values.
         allow_duplicates: Whether to allow duplicate snapshot names.
+        max_rows: Only snapshot the header and the first ``max_rows`` rows.
+        sample: Only snapshot the header and ``sample`` evenly spaced rows,
+            chosen deterministically. Mutually exclusive with ``max_rows``,
+            ``head`` and ``tail``.
+        head: Only snapshot the first ``head`` rows (same as ``max_rows``).
+        tail: Only snapshot the last ``tail`` rows; combines with ``head``.
+        columns: Only snapshot these columns, in this order.
+        summary: Also snapshot the whole table's row count and per-column
+            min/max as a ``<name>-summary`` JSON snapshot, so changes in the
+            rows left out still fail the test.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
+        description: What the value represents, recorded in the snapshot
+            header; see :func:`extract_from_pytest_env`.
+        expression: The expression asserted, recorded in the snapshot header;
+            see :func:`extract_from_pytest_env`.
+        settings: :class:`SnapshotSettings` for this assertion; see
+            :func:`extract_from_pytest_env`.
     """
 
-    test_info = extract_from_pytest_env(snapshot_path, snapshot_name, allow_duplicates)
-    _assert_csv_snapshot(test_info, result, redactions)
+    test_info = extract_from_pytest_env(
+        snapshot_path,
+        snapshot_name,
+        allow_duplicates,
+        prefix,
+        description,
+        expression,
+        settings,
+    )
+    _assert_csv_snapshot(
+        test_info,
+        result,
+        redactions,
+        max_rows,
+        sample,
+        head,
+        tail,
+        columns,
+        summary,
+    )
+
+
+def _check_table_options(
+    dataframe_snapshot_format: str,
+    head: Optional[int],
+    tail: Optional[int],
+    columns: Optional[List[str]],
+    summary: bool,
+) -> None:
+    if dataframe_snapshot_format != "csv" and (
+        head is not None or tail is not None or columns is not None or summary
+    ):
+        raise ValueError(
+            "head, tail, columns and summary are only supported for the 'csv' format."
+        )
 
 
 def try_is_pandas_df(maybe_df: Any) -> bool:
@@ -210,11 +710,16 @@ def assert_pandas_dataframe_snapshot(
     df: pd.DataFrame,
     snapshot_path: Optional[str] = None,
     snapshot_name: Optional[str] = None,
-    redactions: Optional[Dict[str, Union[str, int, None]]] = None,
+    redactions: Optional[Redactions] = None,
     dataframe_snapshot_format: str = "csv",
     allow_duplicates: bool = False,
     readable_diff: Optional[str] = None,
     *args,
+    head: Optional[int] = None,
+    tail: Optional[int] = None,
+    columns: Optional[List[str]] = None,
+    summary: bool = False,
+    prefix: Optional[str] = None,
     **kwargs,
 ) -> None:
     """Snapshot assertion for pandas DataFrames.
@@ -230,18 +735,39 @@ def assert_pandas_dataframe_snapshot(
             ``"csv"`` or ``"json"`` diff on mismatch instead of just a byte
             difference. ``None`` (default) keeps the byte-only behavior.
         *args: Positional arguments forwarded to the DataFrame export method.
+        head: ``"csv"`` format only; see :func:`assert_csv_snapshot`.
+        tail: ``"csv"`` format only; see :func:`assert_csv_snapshot`.
+        columns: ``"csv"`` format only; see :func:`assert_csv_snapshot`.
+        summary: ``"csv"`` format only; see :func:`assert_csv_snapshot`.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
         **kwargs: Keyword arguments forwarded to the DataFrame export method.
     """
 
+    _check_table_options(dataframe_snapshot_format, head, tail, columns, summary)
     if dataframe_snapshot_format == "csv":
         result = df.to_csv(*args, **kwargs)
         assert_csv_snapshot(
-            result, snapshot_path, snapshot_name, redactions, allow_duplicates
+            result,
+            snapshot_path,
+            snapshot_name,
+            redactions,
+            allow_duplicates,
+            head=head,
+            tail=tail,
+            columns=columns,
+            summary=summary,
+            prefix=prefix,
         )
     elif dataframe_snapshot_format == "json":
         result = df.to_dict(orient="list", *args, **kwargs)
         assert_json_snapshot(
-            result, snapshot_path, snapshot_name, redactions, allow_duplicates
+            result,
+            snapshot_path,
+            snapshot_name,
+            redactions,
+            allow_duplicates,
+            prefix=prefix,
         )
     elif dataframe_snapshot_format == "parquet":
         result = df.to_parquet(engine="pyarrow")
@@ -261,6 +787,7 @@ def assert_pandas_dataframe_snapshot(
             snapshot_name,
             extension=dataframe_snapshot_format,
             allow_duplicates=allow_duplicates,
+            prefix=prefix,
             _readable_diff_renderer=renderer,
         )
     else:
@@ -273,11 +800,16 @@ def assert_polars_dataframe_snapshot(
     df: pl.DataFrame,
     snapshot_path: Optional[str] = None,
     snapshot_name: Optional[str] = None,
-    redactions: Optional[Dict[str, Union[str, int, None]]] = None,
+    redactions: Optional[Redactions] = None,
     dataframe_snapshot_format: str = "csv",
     allow_duplicates: bool = False,
     readable_diff: Optional[str] = None,
     *args,
+    head: Optional[int] = None,
+    tail: Optional[int] = None,
+    columns: Optional[List[str]] = None,
+    summary: bool = False,
+    prefix: Optional[str] = None,
     **kwargs,
 ) -> None:
     """Snapshot assertion for polars DataFrames.
@@ -293,18 +825,39 @@ def assert_polars_dataframe_snapshot(
             ``"csv"`` or ``"json"`` diff on mismatch instead of just a byte
             difference. ``None`` (default) keeps the byte-only behavior.
         *args: Positional arguments forwarded to the DataFrame export method.
+        head: ``"csv"`` format only; see :func:`assert_csv_snapshot`.
+        tail: ``"csv"`` format only; see :func:`assert_csv_snapshot`.
+        columns: ``"csv"`` format only; see :func:`assert_csv_snapshot`.
+        summary: ``"csv"`` format only; see :func:`assert_csv_snapshot`.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
         **kwargs: Keyword arguments forwarded to the DataFrame export method.
     """
 
+    _check_table_options(dataframe_snapshot_format, head, tail, columns, summary)
     if dataframe_snapshot_format == "csv":
         result = df.write_csv(*args, **kwargs)
         assert_csv_snapshot(
-            result, snapshot_path, snapshot_name, redactions, allow_duplicates
+            result,
+            snapshot_path,
+            snapshot_name,
+            redactions,
+            allow_duplicates,
+            head=head,
+            tail=tail,
+            columns=columns,
+            summary=summary,
+            prefix=prefix,
         )
     elif dataframe_snapshot_format == "json":
         result = df.to_dict(as_series=False)
         assert_json_snapshot(
-            result, snapshot_path, snapshot_name, redactions, allow_duplicates
+            result,
+            snapshot_path,
+            snapshot_name,
+            redactions,
+            allow_duplicates,
+            prefix=prefix,
         )
     elif dataframe_snapshot_format == "bin":
         result = df.serialize(format="binary", *args, **kwargs)
@@ -324,6 +877,7 @@ def assert_polars_dataframe_snapshot(
             snapshot_name,
             extension=dataframe_snapshot_format,
             allow_duplicates=allow_duplicates,
+            prefix=prefix,
             _readable_diff_renderer=renderer,
         )
     else:
@@ -336,11 +890,16 @@ def assert_dataframe_snapshot(
     df: Union[pd.DataFrame, pl.DataFrame],
     snapshot_path: Optional[str] = None,
     snapshot_name: Optional[str] = None,
-    redactions: Optional[Dict[str, Union[str, int, None]]] = None,
+    redactions: Optional[Redactions] = None,
     dataframe_snapshot_format: str = "csv",
     allow_duplicates: bool = False,
     readable_diff: Optional[str] = None,
     *args,
+    head: Optional[int] = None,
+    tail: Optional[int] = None,
+    columns: Optional[List[str]] = None,
+    summary: bool = False,
+    prefix: Optional[str] = None,
     **kwargs,
 ) -> None:
     """Snapshot assertion for either pandas or polars ``DataFrame`` objects.
@@ -357,6 +916,12 @@ def assert_dataframe_snapshot(
             a readable ``"csv"`` or ``"json"`` diff on mismatch instead of just a
             byte difference. ``None`` (default) keeps the byte-only behavior.
         *args: Positional arguments forwarded to the DataFrame export method.
+        head: ``"csv"`` format only; see :func:`assert_csv_snapshot`.
+        tail: ``"csv"`` format only; see :func:`assert_csv_snapshot`.
+        columns: ``"csv"`` format only; see :func:`assert_csv_snapshot`.
+        summary: ``"csv"`` format only; see :func:`assert_csv_snapshot`.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
         **kwargs: Keyword arguments forwarded to the DataFrame export method.
     """
 
@@ -370,6 +935,11 @@ def assert_dataframe_snapshot(
             allow_duplicates,
             readable_diff,
             *args,
+            head=head,
+            tail=tail,
+            columns=columns,
+            summary=summary,
+            prefix=prefix,
             **kwargs,
         )
     elif try_is_polars_df(df):
@@ -382,6 +952,11 @@ def assert_dataframe_snapshot(
             allow_duplicates,
             readable_diff,
             *args,
+            head=head,
+            tail=tail,
+            columns=columns,
+            summary=summary,
+            prefix=prefix,
             **kwargs,
         )
     else:
@@ -396,7 +971,13 @@ def assert_binary_snapshot(
     snapshot_name: Optional[str] = None,
     extension: str = "bin",
     allow_duplicates: bool = False,
+    compress: bool = False,
+    prefix: Optional[str] = None,
+    scrub_metadata: bool = False,
     _readable_diff_renderer: Optional[Callable[[bytes], str]] = None,
+    description: Optional[str] = None,
+    expression: Optional[str] = None,
+    settings: Optional[SnapshotSettings] = None,
 ) -> None:
     """Assert that binary data matches the stored snapshot.
 
@@ -406,13 +987,451 @@ def assert_binary_snapshot(
         snapshot_name: Optional name override for the snapshot file.
         extension: File extension to use when saving the snapshot.
         allow_duplicates: Whether to allow duplicate snapshot names.
+        compress: Store the snapshot zstd-compressed (as ``<extension>.zst``).
+            :meth:`PySnapshot.contents` decompresses it transparently.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
+        scrub_metadata: Drop volatile metadata from a PNG or JPEG image
+            before comparing it: PNG ``tIME``/``tEXt``/``zTXt``/``iTXt``/
+            ``eXIf`` chunks and JPEG EXIF, XMP and comments. Raises
+            ``ValueError`` for other data.
         _readable_diff_renderer: Optional callback that decodes binary snapshot
             bytes into readable text (CSV/JSON) so a mismatch shows a readable
             diff. Supplied internally by the DataFrame snapshot helpers.
+        description: What the value represents, recorded in the snapshot
+            header; see :func:`extract_from_pytest_env`.
+        expression: The expression asserted, recorded in the snapshot header;
+            see :func:`extract_from_pytest_env`.
+        settings: :class:`SnapshotSettings` for this assertion; see
+            :func:`extract_from_pytest_env`.
+    """
+
+    test_info = extract_from_pytest_env(
+        snapshot_path,
+        snapshot_name,
+        allow_duplicates,
+        prefix,
+        description,
+        expression,
+        settings,
+    )
+    _assert_binary_snapshot(
+        test_info, extension, result, _readable_diff_renderer, compress, scrub_metadata
+    )
+
+
+def assert_pdf_snapshot(
+    result: bytes,
+    snapshot_path: Optional[str] = None,
+    snapshot_name: Optional[str] = None,
+    mode: str = "text",
+    allow_duplicates: bool = False,
+    prefix: Optional[str] = None,
+) -> None:
+    """Assert that a PDF document matches the stored snapshot.
+
+    PDF writers embed timestamps and document ids and may order objects
+    differently on every run, so in ``"text"`` mode only the page count, page
+    sizes and rotations, and the text of each page are snapshotted, as a
+    readable text snapshot.
+
+    Args:
+        result: The PDF's bytes.
+        snapshot_path: Optional path override for storing the snapshot.
+        snapshot_name: Optional name override for the snapshot file.
+        mode: ``"text"`` for the text layer, or ``"binary"`` to store the
+            exact bytes as a ``.pdf`` binary snapshot.
+        allow_duplicates: Whether to allow duplicate snapshot names.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
+
+    Raises:
+        ValueError: If ``result`` can't be parsed as a PDF, or for an unknown
+            ``mode``.
+    """
+
+    test_info = extract_from_pytest_env(
+        snapshot_path, snapshot_name, allow_duplicates, prefix
+    )
+    _assert_pdf_snapshot(test_info, result, mode)
+
+
+def assert_term_snapshot(
+    text: Union[str, bytes],
+    snapshot_path: Optional[str] = None,
+    snapshot_name: Optional[str] = None,
+    strip_ansi: bool = True,
+    width: Optional[int] = None,
+    allow_duplicates: bool = False,
+    prefix: Optional[str] = None,
+) -> None:
+    """Assert that terminal output matches the stored snapshot.
+
+    The output is normalized the way a terminal shows it: ``\\r\\n`` becomes
+    ``\\n``, text overwritten through a bare ``\\r`` (progress bars) is
+    replaced, and escape sequences are removed.
+
+    Args:
+        text: The captured output; bytes are decoded as UTF-8.
+        snapshot_path: Optional path override for storing the snapshot.
+        snapshot_name: Optional name override for the snapshot file.
+        strip_ansi: Whether to remove colors and text styles. When ``False``
+            they are kept as readable markers such as ``[bold red]`` and
+            ``[/]``; cursor movement and other sequences are dropped either
+            way.
+        width: Hard-wrap lines at this many characters, like a terminal of
+            that width.
+        allow_duplicates: Whether to allow duplicate snapshot names.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
+
+    Raises:
+        ValueError: If ``width`` is less than 1.
+    """
+
+    if isinstance(text, bytes):
+        text = text.decode("utf-8", errors="replace")
+    test_info = extract_from_pytest_env(
+        snapshot_path, snapshot_name, allow_duplicates, prefix
+    )
+    _assert_term_snapshot(test_info, text, strip_ansi, width)
+
+
+def assert_logs_snapshot(
+    records: Iterable[Any],
+    snapshot_path: Optional[str] = None,
+    snapshot_name: Optional[str] = None,
+    normalize: bool = True,
+    allow_duplicates: bool = False,
+    prefix: Optional[str] = None,
+) -> None:
+    """Assert that log records match the stored snapshot.
+
+    Each record is stored as one ``LEVEL logger: message`` line; record
+    timestamps, thread ids and source locations are left out. An exception
+    attached to a record adds its type and message, but not its traceback.
+
+    Args:
+        records: ``logging.LogRecord`` objects (e.g. pytest's
+            ``caplog.records``), or dicts with ``levelname`` (or ``level``),
+            ``name`` (or ``logger``) and ``message`` (or ``msg``) keys.
+        snapshot_path: Optional path override for storing the snapshot.
+        snapshot_name: Optional name override for the snapshot file.
+        normalize: Whether to replace timestamps, times, UUIDs, memory
+            addresses, thread and process ids and the directories of
+            absolute paths inside messages with placeholders.
+        allow_duplicates: Whether to allow duplicate snapshot names.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
+
+    Raises:
+        ValueError: If a record has no level or message.
+    """
+
+    test_info = extract_from_pytest_env(
+        snapshot_path, snapshot_name, allow_duplicates, prefix
+    )
+    _assert_logs_snapshot(test_info, list(records), normalize)
+
+
+def assert_sql_snapshot(
+    sql: Union[str, Iterable[Union[str, Mapping[str, Any]]]],
+    snapshot_path: Optional[str] = None,
+    snapshot_name: Optional[str] = None,
+    dialect: Optional[str] = None,
+    allow_duplicates: bool = False,
+    prefix: Optional[str] = None,
+) -> None:
+    """Assert that SQL queries match the stored snapshot.
+
+    Each query is formatted deterministically, with upper-case keywords, and
+    its bind-parameter placeholders (``%s``, ``%(name)s``, ``?``, ``$1``,
+    ``:name``, ...) are normalized to ``?``, so the snapshot doesn't depend on
+    the driver's parameter style. String literals are left untouched.
+
+    Args:
+        sql: A query, or a list of queries snapshotted together (separated by
+            a blank line). Dicts with a ``"sql"`` key, such as Django's
+            ``CaptureQueriesContext.captured_queries``, are accepted too.
+        snapshot_path: Optional path override for storing the snapshot.
+        snapshot_name: Optional name override for the snapshot file.
+        dialect: ``"postgresql"``, ``"mysql"``, ``"sqlite"``, ``"mssql"`` or
+            ``"oracle"``, for dialect-specific quoting (MySQL backslash
+            escapes, SQL Server ``[identifiers]``) and placeholders (SQL
+            Server ``@name``). By default, common ANSI rules apply.
+        allow_duplicates: Whether to allow duplicate snapshot names.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
+
+    Raises:
+        ValueError: For an unknown ``dialect``.
+    """
+
+    if not isinstance(sql, str):
+        sql = [
+            query["sql"] if isinstance(query, Mapping) else query for query in sql
+        ]
+    test_info = extract_from_pytest_env(
+        snapshot_path, snapshot_name, allow_duplicates, prefix
+    )
+    _assert_sql_snapshot(test_info, sql, dialect)
+
+
+def assert_audio_snapshot(
+    result: bytes,
+    snapshot_path: Optional[str] = None,
+    snapshot_name: Optional[str] = None,
+    extension: str = "wav",
+    mode: str = "pcm",
+    tolerance: Optional[float] = None,
+    allow_duplicates: bool = False,
+    prefix: Optional[str] = None,
+) -> None:
+    """Assert that encoded audio matches the stored snapshot within tolerance.
+
+    The audio is stored as a binary snapshot, but compared by its decoded
+    signal: when the new recording is within ``tolerance`` of the committed
+    one, the committed file is kept and the assertion passes, so encoders
+    that aren't bit-stable don't churn the snapshot. WAV, FLAC, MP3 and
+    Ogg Vorbis are supported.
+
+    Args:
+        result: The encoded audio's bytes.
+        snapshot_path: Optional path override for storing the snapshot.
+        snapshot_name: Optional name override for the snapshot file.
+        extension: File extension of the stored snapshot, also used as a hint
+            for the container format.
+        mode: ``"pcm"`` compares the root-mean-square difference of the
+            samples (full scale is 1.0); ``"fingerprint"`` compares the mean
+            difference, in dB, of per-frame spectral band levels, which
+            ignores phase and small sample shifts.
+        tolerance: Largest accepted difference; defaults to ``1e-3`` for
+            ``"pcm"`` and ``1.0`` (dB) for ``"fingerprint"``.
+        allow_duplicates: Whether to allow duplicate snapshot names.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
+
+    Raises:
+        ValueError: If ``result`` can't be decoded, for an unknown ``mode`` or
+            a negative ``tolerance``.
+        AssertionError: If the sample rate or channel count changed, or the
+            difference exceeds ``tolerance``.
+    """
+
+    test_info = extract_from_pytest_env(
+        snapshot_path, snapshot_name, allow_duplicates, prefix
+    )
+    _assert_audio_snapshot(test_info, result, extension, mode, tolerance)
+
+
+def assert_hash_snapshot(
+    result: Union[bytes, str, os.PathLike],
+    snapshot_path: Optional[str] = None,
+    snapshot_name: Optional[str] = None,
+    algorithm: str = "sha256",
+    allow_duplicates: bool = False,
+    prefix: Optional[str] = None,
+) -> None:
+    """Assert that the digest of an artifact matches the stored snapshot.
+
+    Only the algorithm, hex digest and size are stored, so this suits artifacts
+    too large to commit. A mismatch shows that the content changed, not how.
+
+    Args:
+        result: Raw bytes, or a path to a file that is hashed in a streaming
+            fashion.
+        snapshot_path: Optional path override for storing the snapshot.
+        snapshot_name: Optional name override for the snapshot file.
+        algorithm: Digest algorithm, ``"sha256"`` or ``"sha512"``.
+        allow_duplicates: Whether to allow duplicate snapshot names.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
+    """
+
+    test_info = extract_from_pytest_env(
+        snapshot_path, snapshot_name, allow_duplicates, prefix
+    )
+    _assert_hash_snapshot(test_info, result, algorithm)
+
+
+def _numeric_columns(values: Any) -> Any:
+    """Convert DataFrames, Series and arrays into lists the Rust side reads."""
+
+    module = type(values).__module__.split(".")[0]
+    if module == "pandas" and hasattr(values, "columns"):
+        from pandas.api.types import is_bool_dtype, is_numeric_dtype
+
+        return {
+            str(name): values[name].tolist()
+            for name in values.columns
+            if is_numeric_dtype(values[name]) and not is_bool_dtype(values[name])
+        }
+    if module == "polars" and hasattr(values, "columns"):
+        return {
+            name: values[name].to_list()
+            for name in values.columns
+            if values[name].dtype.is_numeric()
+        }
+    if isinstance(values, dict):
+        return {str(name): _numeric_columns(column) for name, column in values.items()}
+    if hasattr(values, "to_list"):
+        return values.to_list()
+    if hasattr(values, "tolist"):
+        return values.tolist()
+    return values
+
+
+def assert_stats_snapshot(
+    result: Any,
+    snapshot_path: Optional[str] = None,
+    snapshot_name: Optional[str] = None,
+    stats: Optional[List[str]] = None,
+    decimals: int = 4,
+    allow_duplicates: bool = False,
+    prefix: Optional[str] = None,
+) -> None:
+    """Assert that summary statistics of numeric data match the stored snapshot.
+
+    Only the statistics are stored, each rounded to ``decimals``, so this suits
+    values (model features, embeddings) too volatile to pin exactly.
+
+    Args:
+        result: A sequence of numbers (list, NumPy array, pandas or polars
+            Series), a dict of column name to numbers, or a pandas or polars
+            DataFrame, whose numeric columns are summarised one by one.
+            ``None`` and ``NaN`` are treated as missing.
+        snapshot_path: Optional path override for storing the snapshot.
+        snapshot_name: Optional name override for the snapshot file.
+        stats: Statistics to store, in order. Defaults to
+            ``["count", "mean", "std", "min", "max"]``; ``"nulls"``, ``"sum"``,
+            ``"var"`` and ``"median"`` are also available. ``std`` and ``var``
+            are sample statistics (``ddof=1``).
+        decimals: Number of decimal places to round each statistic to.
+        allow_duplicates: Whether to allow duplicate snapshot names.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
+    """
+
+    test_info = extract_from_pytest_env(
+        snapshot_path, snapshot_name, allow_duplicates, prefix
+    )
+    _assert_stats_snapshot(test_info, _numeric_columns(result), stats, decimals)
+
+
+def assert_schema_snapshot(
+    result: Any,
+    snapshot_path: Optional[str] = None,
+    snapshot_name: Optional[str] = None,
+    allow_duplicates: bool = False,
+    custom_encoder: Optional[Dict[type, Callable[[Any], Any]]] = None,
+    prefix: Optional[str] = None,
+) -> None:
+    """Assert that the structure of a value matches the stored snapshot.
+
+    Only a JSON-Schema-like description of ``result`` is stored: the type of
+    each value, the properties of objects and which of them are ``required``
+    (present every time), and the merged schema of array ``items``. Values can
+    change freely as long as the shape stays the same.
+
+    Args:
+        result: Object whose structure is snapshotted, normalized with
+            :func:`pysnaptest.to_jsonable` first.
+        snapshot_path: Optional path override for storing the snapshot.
+        snapshot_name: Optional name override for the snapshot file.
+        allow_duplicates: Whether to allow duplicate snapshot names.
+        custom_encoder: Optional mapping of types to encoder callables used when
+            normalizing ``result``.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
     """
 
-    test_info = extract_from_pytest_env(snapshot_path, snapshot_name, allow_duplicates)
-    _assert_binary_snapshot(test_info, extension, result, _readable_diff_renderer)
+    result = to_jsonable(result, custom_encoder=custom_encoder)
+    test_info = extract_from_pytest_env(
+        snapshot_path, snapshot_name, allow_duplicates, prefix
+    )
+    _assert_schema_snapshot(test_info, result)
+
+
+def validate_against_schema_snapshot(
+    result: Any,
+    snapshot_path: Optional[str] = None,
+    snapshot_name: Optional[str] = None,
+    allow_duplicates: bool = False,
+    custom_encoder: Optional[Dict[type, Callable[[Any], Any]]] = None,
+    prefix: Optional[str] = None,
+) -> None:
+    """Check a value against a schema recorded by :func:`assert_schema_snapshot`.
+
+    The committed schema is looked up under the name
+    :func:`assert_schema_snapshot` would use (pass ``snapshot_name`` to check
+    against another test's schema) and is never rewritten. Every value must
+    have one of its schema's types (an integer also passes as a number), and
+    every required property must be present. Properties the schema does not
+    list are accepted.
+
+    Args:
+        result: Object to validate, normalized with
+            :func:`pysnaptest.to_jsonable` first.
+        snapshot_path: Optional path override for the schema snapshot.
+        snapshot_name: Optional name override for the schema snapshot file.
+        allow_duplicates: Whether to allow duplicate snapshot names.
+        custom_encoder: Optional mapping of types to encoder callables used when
+            normalizing ``result``.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
+
+    Raises:
+        SchemaViolationError: An ``AssertionError`` whose ``violations`` lists
+            each problem as a dict with ``path`` (e.g. ``".lines[2].sku"``),
+            ``kind`` (``"type_mismatch"`` or ``"missing_key"``), ``expected``
+            and ``actual`` (``None`` for a missing key).
+        ValueError: If no schema snapshot has been recorded.
+    """
+
+    result = to_jsonable(result, custom_encoder=custom_encoder)
+    test_info = extract_from_pytest_env(
+        snapshot_path, snapshot_name, allow_duplicates, prefix
+    )
+    _validate_against_schema_snapshot(test_info, result)
+
+
+def assert_vector_snapshot(
+    result: Any,
+    snapshot_path: Optional[str] = None,
+    snapshot_name: Optional[str] = None,
+    atol: float = 1e-6,
+    rtol: float = 0.0,
+    metric: str = "cosine",
+    allow_duplicates: bool = False,
+    prefix: Optional[str] = None,
+) -> None:
+    """Assert that a vector is within tolerance of the stored snapshot.
+
+    The vector is stored as a JSON array. On later runs a new vector that is
+    within tolerance of the committed one passes and leaves the snapshot
+    unchanged, so embeddings can be pinned without bit-exact brittleness.
+
+    Args:
+        result: A sequence of finite numbers (list, NumPy array, pandas or
+            polars Series).
+        snapshot_path: Optional path override for storing the snapshot.
+        snapshot_name: Optional name override for the snapshot file.
+        atol: Absolute tolerance.
+        rtol: Relative tolerance, scaled by the committed vector. Not used by
+            the ``"cosine"`` metric.
+        metric: ``"cosine"`` (cosine distance at most ``atol``),
+            ``"euclidean"`` (distance at most ``atol + rtol * norm``) or
+            ``"allclose"`` (every element within tolerance, like
+            ``numpy.allclose``).
+        allow_duplicates: Whether to allow duplicate snapshot names.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
+    """
+
+    test_info = extract_from_pytest_env(
+        snapshot_path, snapshot_name, allow_duplicates, prefix
+    )
+    _assert_vector_snapshot(test_info, _numeric_columns(result), atol, rtol, metric)
 
 
 def assert_snapshot(
@@ -420,6 +1439,11 @@ def assert_snapshot(
     snapshot_path: Optional[str] = None,
     snapshot_name: Optional[str] = None,
     allow_duplicates: bool = False,
+    encryption_key: Optional[str] = None,
+    prefix: Optional[str] = None,
+    description: Optional[str] = None,
+    expression: Optional[str] = None,
+    settings: Optional[SnapshotSettings] = None,
 ) -> None:
     """Assert that a string matches the stored snapshot.
 
@@ -428,21 +1452,44 @@ def assert_snapshot(
         snapshot_path: Optional path override for storing the snapshot.
         snapshot_name: Optional name override for the snapshot file.
         allow_duplicates: Whether to allow duplicate snapshot names.
+        encryption_key: Encrypt the stored snapshot with this key. Defaults to
+            the ``PYSNAPTEST_KEY`` environment variable; unset means plaintext.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
+        description: What the value represents, recorded in the snapshot
+            header; see :func:`extract_from_pytest_env`.
+        expression: The expression asserted, recorded in the snapshot header;
+            see :func:`extract_from_pytest_env`.
+        settings: :class:`SnapshotSettings` for this assertion; see
+            :func:`extract_from_pytest_env`.
     """
 
-    test_info = extract_from_pytest_env(snapshot_path, snapshot_name, allow_duplicates)
-    _assert_snapshot(test_info, result)
+    test_info = extract_from_pytest_env(
+        snapshot_path,
+        snapshot_name,
+        allow_duplicates,
+        prefix,
+        description,
+        expression,
+        settings,
+    )
+    _assert_snapshot(test_info, result, encryption_key)
 
 
 def insta_snapshot(
     result: Any,
     snapshot_path: Optional[str] = None,
     snapshot_name: Optional[str] = None,
-    redactions: Optional[Dict[str, Union[str, int, None]]] = None,
+    redactions: Optional[Redactions] = None,
     dataframe_snapshot_format: str = "csv",
     allow_duplicates: bool = False,
     custom_encoder: Optional[Dict[type, Callable[[Any], Any]]] = None,
     readable_diff: Optional[str] = None,
+    encryption_key: Optional[str] = None,
+    sort_arrays_by: Optional[Dict[str, str]] = None,
+    ignore: Optional[List[str]] = None,
+    prefix: Optional[str] = None,
+    settings: Optional[SnapshotSettings] = None,
 ) -> None:
     """Dispatch a value to the appropriate snapshot assertion.
 
@@ -460,8 +1507,48 @@ def insta_snapshot(
             normalizing JSON snapshots.
         readable_diff: For binary DataFrame formats, show a ``"csv"``/``"json"``
             diff on mismatch. ``None`` (default) keeps byte-only reporting.
+        encryption_key: Encrypt JSON and text snapshots with this key. Defaults
+            to the ``PYSNAPTEST_KEY`` environment variable.
+        sort_arrays_by: Mapping of selectors to the field to sort the matched
+            arrays of JSON snapshots by; see :func:`assert_json_snapshot`.
+        ignore: Selectors of values to leave out of JSON snapshots entirely.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
+        settings: :class:`SnapshotSettings` for this assertion, whatever it
+            dispatches to.
     """
 
+    with settings if settings is not None else nullcontext():
+        _dispatch_snapshot(
+            result,
+            snapshot_path,
+            snapshot_name,
+            redactions,
+            dataframe_snapshot_format,
+            allow_duplicates,
+            custom_encoder,
+            readable_diff,
+            encryption_key,
+            sort_arrays_by,
+            ignore,
+            prefix,
+        )
+
+
+def _dispatch_snapshot(
+    result: Any,
+    snapshot_path: Optional[str],
+    snapshot_name: Optional[str],
+    redactions: Optional[Redactions],
+    dataframe_snapshot_format: str,
+    allow_duplicates: bool,
+    custom_encoder: Optional[Dict[type, Callable[[Any], Any]]],
+    readable_diff: Optional[str],
+    encryption_key: Optional[str],
+    sort_arrays_by: Optional[Dict[str, str]],
+    ignore: Optional[List[str]],
+    prefix: Optional[str],
+) -> None:
     if isinstance(result, (dict, list)):
         assert_json_snapshot(
             result,
@@ -470,6 +1557,10 @@ def insta_snapshot(
             redactions,
             allow_duplicates,
             custom_encoder=custom_encoder,
+            encryption_key=encryption_key,
+            sort_arrays_by=sort_arrays_by,
+            ignore=ignore,
+            prefix=prefix,
         )
     elif isinstance(result, bytes):
         assert_binary_snapshot(
@@ -477,6 +1568,7 @@ def insta_snapshot(
             snapshot_path,
             snapshot_name,
             allow_duplicates=allow_duplicates,
+            prefix=prefix,
         )
     elif try_is_pandas_df(result) or try_is_polars_df(result):
         assert_dataframe_snapshot(
@@ -487,6 +1579,7 @@ def insta_snapshot(
             dataframe_snapshot_format,
             allow_duplicates,
             readable_diff,
+            prefix=prefix,
         )
     elif is_jsonable_object(result):
         assert_json_snapshot(
@@ -496,15 +1589,25 @@ def insta_snapshot(
             redactions,
             allow_duplicates,
             custom_encoder=custom_encoder,
+            encryption_key=encryption_key,
+            sort_arrays_by=sort_arrays_by,
+            ignore=ignore,
+            prefix=prefix,
         )
     else:
         if redactions is not None:
             raise ValueError("Redactions may only be used with json or csv snapshots.")
+        if sort_arrays_by is not None or ignore is not None:
+            raise ValueError(
+                "sort_arrays_by and ignore may only be used with json snapshots."
+            )
         assert_snapshot(
             result,
             snapshot_path,
             snapshot_name,
             allow_duplicates=allow_duplicates,
+            encryption_key=encryption_key,
+            prefix=prefix,
         )
 
 
@@ -517,11 +1620,15 @@ def snapshot(
     *,
     snapshot_path: Optional[str] = None,
     snapshot_name: Optional[str] = None,
-    redactions: Optional[Dict[str, Union[str, int, None]]] = None,
+    redactions: Optional[Redactions] = None,
     dataframe_snapshot_format: str = "csv",
     allow_duplicates: bool = False,
     custom_encoder: Optional[Dict[type, Callable[[Any], Any]]] = None,
     readable_diff: Optional[str] = None,
+    encryption_key: Optional[str] = None,
+    sort_arrays_by: Optional[Dict[str, str]] = None,
+    ignore: Optional[List[str]] = None,
+    prefix: Optional[str] = None,
 ) -> Callable:  # noqa: F811
     ...
 
@@ -531,11 +1638,15 @@ def snapshot(  # noqa: F811
     *,
     snapshot_path: Optional[str] = None,
     snapshot_name: Optional[str] = None,
-    redactions: Optional[Dict[str, Union[str, int, None]]] = None,
+    redactions: Optional[Redactions] = None,
     dataframe_snapshot_format: str = "csv",
     allow_duplicates: bool = False,
     custom_encoder: Optional[Dict[type, Callable[[Any], Any]]] = None,
     readable_diff: Optional[str] = None,
+    encryption_key: Optional[str] = None,
+    sort_arrays_by: Optional[Dict[str, str]] = None,
+    ignore: Optional[List[str]] = None,
+    prefix: Optional[str] = None,
 ) -> Callable:
     """Decorator that snapshots the return value of ``func``.
 
@@ -551,6 +1662,13 @@ def snapshot(  # noqa: F811
         readable_diff: For binary DataFrame formats (``"parquet"``/``"bin"``),
             show a readable ``"csv"``/``"json"`` diff on mismatch instead of just
             a byte difference. ``None`` (default) keeps byte-only reporting.
+        encryption_key: Encrypt JSON and text snapshots with this key. Defaults
+            to the ``PYSNAPTEST_KEY`` environment variable.
+        sort_arrays_by: Mapping of selectors to the field to sort the matched
+            arrays of JSON snapshots by; see :func:`assert_json_snapshot`.
+        ignore: Selectors of values to leave out of JSON snapshots entirely.
+        prefix: Prefix strategy of the snapshot file name; see
+            :func:`extract_from_pytest_env`.
 
     Returns:
         Callable: The wrapped function.
@@ -574,6 +1692,10 @@ def snapshot(  # noqa: F811
                     allow_duplicates=allow_duplicates,
                     custom_encoder=custom_encoder,
                     readable_diff=readable_diff,
+                    encryption_key=encryption_key,
+                    sort_arrays_by=sort_arrays_by,
+                    ignore=ignore,
+                    prefix=prefix,
                 )
 
             return asserted_func
@@ -590,6 +1712,10 @@ def snapshot(  # noqa: F811
                 allow_duplicates=allow_duplicates,
                 custom_encoder=custom_encoder,
                 readable_diff=readable_diff,
+                encryption_key=encryption_key,
+                sort_arrays_by=sort_arrays_by,
+                ignore=ignore,
+                prefix=prefix,
             )
 
         return asserted_func
@@ -16,14 +16,18 @@ relevant helpers and guarded against ``ImportError``.
 from __future__ import annotations
 
 import dataclasses
+import json
+import sys
 from collections.abc import Mapping
 from datetime import date, datetime, time, timedelta
 from decimal import Decimal
 from enum import Enum
 from pathlib import PurePath
-from typing import Any, Callable, Dict, Optional, Set
+from typing import Any, Callable, Dict, Iterable, List, Optional, Set
 from uuid import UUID
 
+from ._pysnaptest import SerializationError, serialization_limits_info
+
 
 def is_pydantic(obj: Any) -> bool:
     """Check whether ``obj`` is a Pydantic ``BaseModel`` instance.
@@ -38,12 +42,10 @@ def is_pydantic(obj: Any) -> bool:
         bool: ``True`` if ``obj`` is a Pydantic model instance.
     """
 
-    try:
-        import pydantic
-    except ImportError:
-        return False
-
-    return isinstance(obj, pydantic.BaseModel)
+    # A model's class was defined through pydantic, so it is imported already;
+    # looking it up spares a failing import per value when it is not installed.
+    pydantic = sys.modules.get("pydantic")
+    return pydantic is not None and isinstance(obj, pydantic.BaseModel)
 
 
 def _pydantic_to_dict(obj: Any) -> Any:
@@ -118,6 +120,11 @@ def to_jsonable(
     ``UUID``/``PurePath``/``Decimal``, ``bytes``, mappings, and
     set/tuple/list-style containers. Anything else falls back to ``str(obj)``.
 
+    Sets and frozensets become lists sorted by their encoded elements, so
+    they snapshot identically on every run whatever the hash seed: numbers
+    and strings sort naturally, and a set mixing types orders ``None``,
+    booleans, numbers, strings, lists and dicts in that order.
+
     Args:
         obj: Object to convert.
         custom_encoder: Optional mapping of types to encoder callables, matching
@@ -128,34 +135,65 @@ def to_jsonable(
 
     Returns:
         Any: A JSON-native representation of ``obj``.
+
+    Raises:
+        SerializationError: If ``obj`` refers to itself, e.g. a dict holding
+            itself or a dataclass whose child points back at it, or nests
+            deeper than the ``max_depth`` of
+            :func:`pysnaptest.set_serialization_limits`; the message names the
+            offending path, like ``.a.b``.
     """
 
+    seen = _Ancestors(serialization_limits_info()["max_depth"], _seen or ())
+    try:
+        return _encode(obj, custom_encoder, seen)
+    except _Unserializable as error:
+        path = "".join(reversed(error.path))
+        raise SerializationError(f"{error.reason} at path {path}") from None
+
+
+#: Types that are returned as they are, checked by identity before anything
+#: else since they make up most of a payload.
+_NATIVE_SCALARS = frozenset({str, int, float, bool, type(None)})
+
+
+def _encode(
+    obj: Any,
+    custom_encoder: Optional[Dict[type, Callable[[Any], Any]]],
+    seen: _Ancestors,
+) -> Any:
+    """The recursion of :func:`to_jsonable`."""
+
     if custom_encoder:
         for encoder_type, encoder in custom_encoder.items():
             if isinstance(obj, encoder_type):
-                return to_jsonable(
-                    encoder(obj), custom_encoder=custom_encoder, _seen=_seen
-                )
+                return _encode(encoder(obj), custom_encoder, seen)
 
-    # Native scalars pass straight through. ``bool`` is a subclass of ``int``
-    # and is handled here as well.
-    if obj is None or isinstance(obj, (str, int, float, bool)):
+    kind = type(obj)
+    if kind in _NATIVE_SCALARS:
         return obj
 
-    if _seen is None:
-        _seen = set()
+    # Plain dicts and lists are the other common case: none of the checks
+    # below can match them before the container branches do.
+    if kind is dict:
+        return _encode_mapping(obj, custom_encoder, seen)
+    if kind is list:
+        return _encode_items(obj, custom_encoder, seen)
 
-    def recurse(value: Any) -> Any:
-        return to_jsonable(value, custom_encoder=custom_encoder, _seen=_seen)
+    # Native scalar subclasses pass straight through. ``bool`` is a subclass
+    # of ``int`` and is handled here as well.
+    if isinstance(obj, (str, int, float, bool)):
+        return obj
 
     if is_pydantic(obj):
-        return recurse(_pydantic_to_dict(obj))
+        return _encode(_pydantic_to_dict(obj), custom_encoder, seen)
 
     if _is_dataclass_instance(obj):
-        return recurse(dataclasses.asdict(obj))
+        fields = {f.name: getattr(obj, f.name) for f in dataclasses.fields(obj)}
+        return _encode_mapping(fields, custom_encoder, seen, obj)
 
     if isinstance(obj, Enum):
-        return recurse(obj.value)
+        return _encode(obj.value, custom_encoder, seen)
 
     if isinstance(obj, (datetime, date, time)):
         return obj.isoformat()
@@ -175,39 +213,105 @@ def to_jsonable(
         except UnicodeDecodeError:
             return bytes(obj).hex()
 
-    obj_id = id(obj)
-    if obj_id in _seen:
-        return "<circular reference>"
-
     if isinstance(obj, Mapping):
-        _seen.add(obj_id)
-        try:
-            result: Dict[str, Any] = {}
-            for key, value in obj.items():
-                encoded_key = recurse(key)
-                if not isinstance(encoded_key, str):
-                    encoded_key = str(encoded_key)
-                result[encoded_key] = recurse(value)
-            return result
-        finally:
-            _seen.discard(obj_id)
+        return _encode_mapping(obj, custom_encoder, seen)
 
     if isinstance(obj, (set, frozenset)):
-        _seen.add(obj_id)
-        try:
-            try:
-                items = sorted(obj)
-            except TypeError:
-                items = list(obj)
-            return [recurse(item) for item in items]
-        finally:
-            _seen.discard(obj_id)
+        return sorted(_encode_items(obj, custom_encoder, seen), key=_set_order_key)
 
     if isinstance(obj, (list, tuple)):
-        _seen.add(obj_id)
-        try:
-            return [recurse(item) for item in obj]
-        finally:
-            _seen.discard(obj_id)
+        return _encode_items(obj, custom_encoder, seen)
 
     return str(obj)
+
+
+class _Unserializable(Exception):
+    """Raised where a container refers back to one of its ancestors or is
+    nested too deep; each container it unwinds through adds its segment of
+    the path, innermost first."""
+
+    def __init__(self, reason: str) -> None:
+        super().__init__(reason)
+        self.reason = reason
+        self.path: List[str] = []
+
+
+class _Ancestors(set):
+    """The ``id()`` of the containers enclosing the value being encoded, at
+    most ``max_depth`` of them."""
+
+    def __init__(self, max_depth: Optional[int], ids: Iterable[int] = ()) -> None:
+        super().__init__(ids)
+        self.max_depth = max_depth
+
+    def enter(self, obj_id: int) -> None:
+        if obj_id in self:
+            raise _Unserializable("circular reference")
+        if self.max_depth is not None and len(self) >= self.max_depth:
+            raise _Unserializable(f"value nests deeper than {self.max_depth} levels")
+        self.add(obj_id)
+
+
+def _encode_mapping(
+    obj: Mapping,
+    custom_encoder: Optional[Dict[type, Callable[[Any], Any]]],
+    seen: _Ancestors,
+    owner: Any = None,
+) -> Any:
+    """Encodes a mapping; the cycle guard is keyed on ``owner`` when ``obj``
+    holds the fields of a dataclass instance."""
+
+    obj_id = id(obj if owner is None else owner)
+    seen.enter(obj_id)
+    result: Dict[str, Any] = {}
+    try:
+        for key, value in obj.items():
+            encoded_key = _encode(key, custom_encoder, seen)
+            if not isinstance(encoded_key, str):
+                encoded_key = str(encoded_key)
+            try:
+                result[encoded_key] = _encode(value, custom_encoder, seen)
+            except _Unserializable as error:
+                error.path.append(f".{encoded_key}")
+                raise
+        return result
+    finally:
+        seen.discard(obj_id)
+
+
+def _encode_items(
+    items: Any,
+    custom_encoder: Optional[Dict[type, Callable[[Any], Any]]],
+    seen: _Ancestors,
+) -> Any:
+    """Encodes the items of a list, tuple or set, in iteration order."""
+
+    obj_id = id(items)
+    seen.enter(obj_id)
+    result: List[Any] = []
+    try:
+        for item in items:
+            result.append(_encode(item, custom_encoder, seen))
+        return result
+    except _Unserializable as error:
+        error.path.append(f"[{len(result)}]")
+        raise
+    finally:
+        seen.discard(obj_id)
+
+
+def _set_order_key(item: Any) -> Any:
+    """Orders the encoded elements of a set the same way on every run:
+    ``None``, booleans, numbers (NaN last), strings, then lists and dicts by
+    their JSON text, so sets mixing types need no common ordering."""
+
+    if item is None:
+        return (0, 0)
+    if isinstance(item, bool):
+        return (1, item)
+    if isinstance(item, (int, float)):
+        return (2, (1, 0) if item != item else (0, item))
+    if isinstance(item, str):
+        return (3, item)
+    rank = 4 if isinstance(item, list) else 5
+    return (rank, json.dumps(item, sort_keys=True, default=str))
@@ -0,0 +1,203 @@
+"""Export text snapshots as plain golden files, and import them back.
+
+:func:`export_snapshots` writes the body of every text snapshot under a root
+to a plain file tree that tooling outside Python can read and write: each
+``<dir>/<name>@pysnap.snap`` becomes ``<destination>/<dir>/<name>.txt``,
+holding exactly what follows the snapshot's header. Binary snapshots, whose
+bodies already live in sidecar files, are not exported.
+
+Next to the files, ``manifest.json`` records the snapshot each one came from
+and its header, byte for byte::
+
+    {
+      "format": "raw",
+      "snapshots": {
+        "tests/snapshots/test_api__test_api_test_get.txt": {
+          "header": "---\\nsource: src/lib.rs\\n...\\n---\\n",
+          "snapshot": "tests/snapshots/test_api__test_api_test_get@pysnap.snap"
+        }
+      }
+    }
+
+:func:`import_exported_snapshots` joins each header with its (possibly
+edited) file again, so an export followed by an import leaves the snapshots
+exactly as they were.
+"""
+
+from __future__ import annotations
+
+import json
+import os
+import tempfile
+from pathlib import Path
+from typing import Dict, List, Literal, Optional, Tuple
+
+from .review import SNAPSHOT_GLOB, SNAPSHOT_SUFFIX, _root
+
+#: The formats :func:`export_snapshots` writes.
+FORMATS = ("raw",)
+
+#: Name of the manifest written next to the exported files.
+MANIFEST = "manifest.json"
+
+
+def _write_atomically(path: Path, data: bytes) -> None:
+    """Replace ``path`` with ``data`` through a flushed temporary file, so an
+    interrupted import never leaves a truncated snapshot."""
+
+    fd, staged = tempfile.mkstemp(
+        prefix=f".{path.name}.", suffix=".tmp", dir=path.parent
+    )
+    try:
+        with os.fdopen(fd, "wb") as file:
+            file.write(data)
+            file.flush()
+            os.fsync(file.fileno())
+        os.replace(staged, path)
+    except BaseException:
+        Path(staged).unlink(missing_ok=True)
+        raise
+
+#: Extension of the exported files.
+EXPORT_SUFFIX = ".txt"
+
+#: Where :func:`export_snapshots` writes under the root by default.
+DEFAULT_DESTINATION = "exported_snapshots"
+
+
+def split_snapshot(text: str) -> Tuple[str, str]:
+    """Split the text of a snapshot file into its header and its body.
+
+    Args:
+        text: The contents of a ``*@pysnap.snap`` file.
+
+    Returns:
+        Tuple[str, str]: The header, from the opening ``---`` line through
+        the closing one and its newline, and everything after it. The header
+        is empty for a file without one.
+    """
+
+    if not text.startswith("---\n"):
+        return "", text
+    end = text.find("\n---\n", len("---"))
+    if end == -1:
+        return "", text
+    end += len("\n---\n")
+    return text[:end], text[end:]
+
+
+def _is_binary(header: str) -> bool:
+    return any(
+        line.strip() == "snapshot_kind: binary" for line in header.splitlines()
+    )
+
+
+def _read_manifest(destination: Path) -> Dict[str, Dict[str, str]]:
+    manifest = destination / MANIFEST
+    try:
+        data = json.loads(manifest.read_text(encoding="utf-8"))
+    except FileNotFoundError:
+        raise ValueError(f"No {MANIFEST} in {destination}") from None
+    if data.get("format") not in FORMATS:
+        raise ValueError(
+            f"Unsupported export format {data.get('format')!r} in {manifest}"
+        )
+    return data["snapshots"]
+
+
+def export_snapshots(
+    root: Optional[str] = None,
+    format: Literal["raw"] = "raw",
+    destination: Optional[str] = None,
+) -> List[Path]:
+    """Write the body of every text snapshot under ``root`` to a plain file.
+
+    The files mirror the snapshots' directories under ``destination``, and
+    ``manifest.json`` records what :func:`import_exported_snapshots` needs to
+    turn them back into the same snapshots. Files of an earlier export to the
+    same destination are overwritten.
+
+    Args:
+        root: Directory to search. Defaults to ``INSTA_WORKSPACE_ROOT`` if set,
+            otherwise the current working directory.
+        format: How bodies are written; ``"raw"`` writes them as they are.
+        destination: Directory to export to. Defaults to
+            ``exported_snapshots`` under ``root``.
+
+    Returns:
+        List[Path]: The exported files, sorted.
+
+    Raises:
+        ValueError: If ``format`` is not a supported format.
+    """
+
+    if format not in FORMATS:
+        raise ValueError(
+            f"Unsupported export format {format!r}; expected one of "
+            + ", ".join(repr(f) for f in FORMATS)
+        )
+    base = _root(root)
+    target = Path(destination) if destination else base / DEFAULT_DESTINATION
+    manifest: Dict[str, Dict[str, str]] = {}
+    exported = []
+    for snapshot in sorted(base.glob(SNAPSHOT_GLOB)):
+        header, body = split_snapshot(snapshot.read_bytes().decode("utf-8"))
+        if _is_binary(header):
+            continue
+        relative = snapshot.relative_to(base)
+        name = relative.name[: -len(SNAPSHOT_SUFFIX)] + EXPORT_SUFFIX
+        exported_file = relative.parent / name
+        path = target / exported_file
+        path.parent.mkdir(parents=True, exist_ok=True)
+        path.write_bytes(body.encode("utf-8"))
+        manifest[exported_file.as_posix()] = {
+            "header": header,
+            "snapshot": relative.as_posix(),
+        }
+        exported.append(path)
+    target.mkdir(parents=True, exist_ok=True)
+    (target / MANIFEST).write_text(
+        json.dumps({"format": format, "snapshots": manifest}, indent=2, sort_keys=True)
+        + "\n",
+        encoding="utf-8",
+    )
+    return exported
+
+
+def import_exported_snapshots(
+    destination: str, root: Optional[str] = None
+) -> List[Path]:
+    """Turn the files of an :func:`export_snapshots` export back into
+    snapshots.
+
+    Every file listed in the export's manifest is written, after its original
+    header, to the snapshot it was exported from, so the files may have been
+    edited in between. Files not in the manifest are ignored.
+
+    Args:
+        destination: The directory the snapshots were exported to.
+        root: Directory the snapshots are written under. Defaults to
+            ``INSTA_WORKSPACE_ROOT`` if set, otherwise the current working
+            directory.
+
+    Returns:
+        List[Path]: The snapshot files written, sorted.
+
+    Raises:
+        ValueError: If ``destination`` holds no manifest of a supported
+            format, or a file listed in it is missing.
+    """
+
+    base = _root(root)
+    source = Path(destination)
+    written = []
+    for exported_file, entry in sorted(_read_manifest(source).items()):
+        path = source / exported_file
+        if not path.is_file():
+            raise ValueError(f"Exported file {path} listed in {MANIFEST} is missing")
+        body = path.read_bytes().decode("utf-8")
+        snapshot = base / entry["snapshot"]
+        snapshot.parent.mkdir(parents=True, exist_ok=True)
+        _write_atomically(snapshot, (entry["header"] + body).encode("utf-8"))
+        written.append(snapshot)
+    return sorted(written)
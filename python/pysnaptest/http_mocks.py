@@ -0,0 +1,461 @@
+"""Record and replay HTTP calls made through ``requests`` or ``httpx``.
+
+:class:`mock_http_json_snapshot` patches a client's entry point
+(``requests.Session.request``, ``httpx.Client.send`` or
+``httpx.AsyncClient.send``). Each call's method, URL, headers and body become
+its request snapshot, and the response's status, headers and body its reply
+snapshot. On replay the recorded reply is rebuilt as a real ``requests`` or
+``httpx`` response object, so code under test cannot tell the difference.
+
+The snapshot bookkeeping is the same as for :func:`pysnaptest.mock_json_snapshot`
+(the Rust ``prepare_mock_call`` primitive): successive calls in a test are
+recorded as ``<test>_http``, ``<test>_http-2``, ..., and a request that differs
+from its recording fails the test with the request snapshot's diff.
+
+Bodies are stored under exactly one of ``json`` (parsed JSON), ``text``
+(other UTF-8) or ``base64`` (anything else), and omitted when empty.
+
+Credentials and per-call noise (``Authorization``, ``Cookie``, ``User-Agent``,
+trace ids, ...) are left out of request snapshots altogether, and ``match_on``
+narrows which parts of a request must match its recording for it to replay.
+"""
+
+from __future__ import annotations
+
+import asyncio
+import base64
+import functools
+import inspect
+import json
+import pkgutil
+import time
+from http import HTTPStatus
+from typing import Any, Callable, Dict, Iterable, List, Optional, Sequence, Union
+from urllib.parse import parse_qsl, urlsplit
+from unittest.mock import patch
+
+from ._pysnaptest import (
+    assert_json_snapshot_named as _assert_json_snapshot_named,
+    prepare_mock_call as _prepare_mock_call,
+    read_json_snapshot as _read_json_snapshot,
+)
+from .assertion import extract_from_pytest_env
+from .mocks import check_recording_age, replay_delay, utc_timestamp
+from .presets import Redactions
+
+# Framing headers describe the bytes on the wire, not the replayed body (which
+# is re-encoded and never compressed), so they are not recorded.
+_FRAMING_HEADERS = frozenset({"content-encoding", "content-length", "transfer-encoding"})
+
+
+# Request headers that are never recorded: credentials, and values that change
+# on every call.
+DEFAULT_IGNORED_HEADERS = (
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "user-agent",
+    "traceparent",
+    "tracestate",
+    "x-request-id",
+    "x-correlation-id",
+    "x-amzn-trace-id",
+)
+
+# The parts of a recorded request that `match_on` can name.
+_MATCHERS: Dict[str, Callable[[Dict[str, Any]], Any]] = {
+    "method": lambda request: request.get("method", "").upper(),
+    "url": lambda request: request.get("url"),
+    "scheme": lambda request: urlsplit(request.get("url", "")).scheme,
+    "host": lambda request: urlsplit(request.get("url", "")).netloc,
+    "path": lambda request: urlsplit(request.get("url", "")).path,
+    "query": lambda request: sorted(
+        parse_qsl(urlsplit(request.get("url", "")).query, keep_blank_values=True)
+    ),
+    "headers": lambda request: request.get("headers") or {},
+    "body": lambda request: {
+        key: request[key] for key in ("json", "text", "base64") if key in request
+    },
+    "body_json": lambda request: request.get("json"),
+}
+
+DEFAULT_MATCH_ON = ("method", "url", "headers", "body")
+
+
+def _encode_body(content: bytes) -> Dict[str, Any]:
+    if not content:
+        return {}
+    try:
+        text = content.decode("utf-8")
+    except UnicodeDecodeError:
+        return {"base64": base64.b64encode(content).decode("ascii")}
+    try:
+        return {"json": json.loads(text)}
+    except ValueError:
+        return {"text": text}
+
+
+def _decode_body(recorded: Dict[str, Any]) -> bytes:
+    if "json" in recorded:
+        return json.dumps(recorded["json"]).encode("utf-8")
+    if "text" in recorded:
+        return recorded["text"].encode("utf-8")
+    if "base64" in recorded:
+        return base64.b64decode(recorded["base64"])
+    return b""
+
+
+def _headers(headers: Any, skip: frozenset = frozenset()) -> Dict[str, str]:
+    return {
+        name.lower(): value
+        for name, value in headers.items()
+        if name.lower() not in skip
+    }
+
+
+class _RequestsAdapter:
+    """``requests.Session.request(method, url, ...)``."""
+
+    is_async = False
+
+    def __init__(self, original: Callable):
+        import requests
+
+        self.requests = requests
+        self.signature = inspect.signature(original)
+
+    def prepare(self, args: tuple, kwargs: dict) -> Any:
+        # Only what the caller passed is recorded: session-level headers and
+        # auth are applied later by requests and stay out of the snapshot.
+        bound = self.signature.bind(*args, **kwargs).arguments
+        return self.requests.Request(
+            method=bound["method"],
+            url=bound["url"],
+            headers=bound.get("headers"),
+            params=bound.get("params"),
+            data=bound.get("data"),
+            json=bound.get("json"),
+            files=bound.get("files"),
+        ).prepare()
+
+    def request_record(self, request: Any) -> Dict[str, Any]:
+        body = request.body
+        if isinstance(body, str):
+            body = body.encode("utf-8")
+        return {
+            "method": request.method,
+            "url": request.url,
+            "headers": _headers(request.headers),
+            **_encode_body(body or b""),
+        }
+
+    def response_record(self, response: Any) -> Dict[str, Any]:
+        return {
+            "status": response.status_code,
+            "headers": _headers(response.headers, _FRAMING_HEADERS),
+            **_encode_body(response.content),
+        }
+
+    def replay(self, recorded: Dict[str, Any], request: Any) -> Any:
+        response = self.requests.Response()
+        response.status_code = recorded["status"]
+        response.headers = self.requests.structures.CaseInsensitiveDict(
+            recorded.get("headers") or {}
+        )
+        response._content = _decode_body(recorded)
+        response.url = request.url
+        response.request = request
+        response.encoding = self.requests.utils.get_encoding_from_headers(
+            response.headers
+        )
+        try:
+            response.reason = HTTPStatus(response.status_code).phrase
+        except ValueError:
+            response.reason = None
+        return response
+
+
+class _HttpxAdapter:
+    """``httpx.Client.send(request, ...)`` and its async counterpart."""
+
+    def __init__(self, original: Callable, is_async: bool):
+        import httpx
+
+        self.httpx = httpx
+        self.is_async = is_async
+
+    def prepare(self, args: tuple, kwargs: dict) -> Any:
+        request = args[1] if len(args) > 1 else kwargs["request"]
+        request.read()
+        return request
+
+    def request_record(self, request: Any) -> Dict[str, Any]:
+        return {
+            "method": request.method,
+            "url": str(request.url),
+            "headers": _headers(request.headers),
+            **_encode_body(request.content),
+        }
+
+    def response_record(self, response: Any) -> Dict[str, Any]:
+        return {
+            "status": response.status_code,
+            "headers": _headers(response.headers, _FRAMING_HEADERS),
+            **_encode_body(response.content),
+        }
+
+    def replay(self, recorded: Dict[str, Any], request: Any) -> Any:
+        return self.httpx.Response(
+            recorded["status"],
+            headers=recorded.get("headers") or {},
+            content=_decode_body(recorded),
+            request=request,
+        )
+
+
+_ADAPTERS: Dict[str, Callable[[Callable], Any]] = {
+    "requests.Session.request": _RequestsAdapter,
+    "httpx.Client.send": lambda original: _HttpxAdapter(original, is_async=False),
+    "httpx.AsyncClient.send": lambda original: _HttpxAdapter(original, is_async=True),
+}
+
+
+class mock_http_json_snapshot:
+    """Patch an HTTP client so its calls are recorded once and then replayed.
+
+    Like :class:`pysnaptest.patch_json_snapshot`, instances work as a context
+    manager or as a decorator.
+    """
+
+    def __init__(
+        self,
+        client_attr: str,
+        *,
+        record: bool = False,
+        snapshot_path: Optional[str] = None,
+        snapshot_name: Optional[str] = None,
+        redactions: Optional[Redactions] = None,
+        allow_duplicates: bool = False,
+        encryption_key: Optional[str] = None,
+        match_on: Sequence[str] = DEFAULT_MATCH_ON,
+        ignore_headers: Iterable[str] = DEFAULT_IGNORED_HEADERS,
+        simulate_latency: Optional[float] = None,
+        max_age_days: Optional[float] = None,
+        ignore: Optional[List[str]] = None,
+        node_id: Optional[str] = None,
+        scope: Optional[str] = None,
+        namespace: Optional[str] = None,
+    ):
+        """Create the patch configuration.
+
+        Args:
+            client_attr: Entry point to patch: ``"requests.Session.request"``,
+                ``"httpx.Client.send"`` or ``"httpx.AsyncClient.send"``.
+            record: Whether to always call through and record new snapshots.
+            snapshot_path: Optional path override for storing the snapshots.
+            snapshot_name: Optional name override for the snapshot files.
+            redactions: Mapping of selectors to replacement values, applied to
+                both request and response snapshots (e.g. ``".headers.date"``).
+            allow_duplicates: Whether to allow duplicate snapshot names.
+            encryption_key: Encrypt the recorded snapshots with this key.
+                Defaults to the ``PYSNAPTEST_KEY`` environment variable.
+            match_on: Parts of a request that must match its recording for
+                the recorded response to be replayed: any of ``"method"``,
+                ``"url"``, ``"scheme"``, ``"host"``, ``"path"``, ``"query"``
+                (order-insensitive), ``"headers"``, ``"body"`` and
+                ``"body_json"`` (the parsed JSON body). Defaults to the whole
+                request. A matching request keeps its recorded snapshot.
+            ignore_headers: Request headers (case-insensitive) never recorded
+                or compared. Defaults to credentials and tracing headers.
+            simulate_latency: On replay, wait this fraction of each call's
+                recorded duration before returning the response.
+            max_age_days: Fail the replay of recordings made more than this
+                many days ago; see :func:`pysnaptest.mock_json_snapshot`.
+            ignore: Selectors of values to leave out of the request and
+                response snapshots entirely (e.g. ``".json.timestamp"``).
+            node_id: Store the recordings under the pytest test with this
+                node id; see :func:`pysnaptest.mock_json_snapshot`.
+            scope: ``"module"`` or ``"session"`` to share the recordings with
+                every test of the module or session; see
+                :func:`pysnaptest.mock_json_snapshot`.
+            namespace: Store the recordings in this subdirectory of the
+                snapshot folder, e.g. ``"http"``.
+
+        Raises:
+            ValueError: If ``client_attr`` is not a supported entry point, or
+                ``match_on`` names an unknown part.
+        """
+
+        if client_attr not in _ADAPTERS:
+            supported = ", ".join(_ADAPTERS)
+            raise ValueError(
+                f"Unsupported HTTP client entry point {client_attr!r}; "
+                f"expected one of: {supported}"
+            )
+        unknown = [part for part in match_on if part not in _MATCHERS]
+        if unknown:
+            raise ValueError(
+                f"Unknown match_on part(s) {unknown}; expected any of: "
+                + ", ".join(_MATCHERS)
+            )
+        self.client_attr = client_attr
+        self.record = record
+        self.snapshot_path = snapshot_path
+        self.snapshot_name = snapshot_name
+        self.redactions = redactions
+        self.allow_duplicates = allow_duplicates
+        self.encryption_key = encryption_key
+        self.match_on = tuple(match_on)
+        self.ignore_headers = frozenset(name.lower() for name in ignore_headers)
+        self.simulate_latency = simulate_latency
+        self.max_age_days = max_age_days
+        self.ignore = ignore
+        self.node_id = node_id
+        self.scope = scope
+        self.namespace = namespace
+        self._patcher = None
+
+    def _matches(self, recorded: Dict[str, Any], request: Dict[str, Any]) -> bool:
+        return all(
+            _MATCHERS[part](recorded) == _MATCHERS[part](request)
+            for part in self.match_on
+        )
+
+    def _prepare(self, test_info: Any, adapter: Any, args: tuple, kwargs: dict):
+        request = adapter.prepare(args, kwargs)
+        record = adapter.request_record(request)
+        record["headers"] = _headers(record["headers"], self.ignore_headers)
+        name, response_path, do_record = _prepare_mock_call(
+            test_info,
+            "http",
+            record,
+            self.record,
+            self.redactions,
+            self.encryption_key,
+            self._matches,
+            ignore=self.ignore,
+        )
+        return request, name, response_path, do_record
+
+    def _store(
+        self,
+        test_info: Any,
+        adapter: Any,
+        name: str,
+        response: Any,
+        duration_ms: float,
+        recorded_at: str,
+    ) -> None:
+        _assert_json_snapshot_named(
+            test_info,
+            adapter.response_record(response),
+            name,
+            self.redactions,
+            self.encryption_key,
+            duration_ms,
+            recorded_at,
+            ignore=self.ignore,
+        )
+
+    def _replay(self, adapter: Any, response_path: Any, request: Any) -> Any:
+        check_recording_age(response_path, self.max_age_days)
+        recorded = _read_json_snapshot(response_path, self.encryption_key)
+        return adapter.replay(recorded, request)
+
+    def _wrap(self, original: Callable) -> Callable:
+        test_info = extract_from_pytest_env(
+            self.snapshot_path,
+            self.snapshot_name,
+            self.allow_duplicates,
+            node_id=self.node_id,
+            scope=self.scope,
+            namespace=self.namespace,
+        )
+        adapter = _ADAPTERS[self.client_attr](original)
+
+        if adapter.is_async:
+
+            @functools.wraps(original)
+            async def async_send(*args, **kwargs):
+                request, name, response_path, do_record = self._prepare(
+                    test_info, adapter, args, kwargs
+                )
+                if not do_record:
+                    delay = replay_delay(response_path, self.simulate_latency)
+                    if delay:
+                        await asyncio.sleep(delay)
+                    return self._replay(adapter, response_path, request)
+                recorded_at = utc_timestamp()
+                start = time.perf_counter()
+                response = await original(*args, **kwargs)
+                await response.aread()
+                duration_ms = (time.perf_counter() - start) * 1000
+                self._store(
+                    test_info, adapter, name, response, duration_ms, recorded_at
+                )
+                return response
+
+            return async_send
+
+        @functools.wraps(original)
+        def send(*args, **kwargs):
+            request, name, response_path, do_record = self._prepare(
+                test_info, adapter, args, kwargs
+            )
+            if not do_record:
+                delay = replay_delay(response_path, self.simulate_latency)
+                if delay:
+                    time.sleep(delay)
+                return self._replay(adapter, response_path, request)
+            recorded_at = utc_timestamp()
+            start = time.perf_counter()
+            response = original(*args, **kwargs)
+            if hasattr(response, "read"):
+                response.read()
+            duration_ms = (time.perf_counter() - start) * 1000
+            self._store(test_info, adapter, name, response, duration_ms, recorded_at)
+            return response
+
+        return send
+
+    def __enter__(self):
+        """Start patching the client entry point.
+
+        Returns:
+            mock_http_json_snapshot: This object.
+        """
+        original = pkgutil.resolve_name(self.client_attr)
+        self._patcher = patch(self.client_attr, new=self._wrap(original))
+        self._patcher.__enter__()
+        return self
+
+    def __exit__(self, exc_type, exc_val, exc_tb):
+        """Stop patching and clean up."""
+
+        return self._patcher.__exit__(exc_type, exc_val, exc_tb)
+
+    def __call__(self, func: Callable):
+        """Allow use of the object as a decorator.
+
+        Args:
+            func: The function being decorated.
+
+        Returns:
+            Callable: Wrapped function that applies the patch during execution.
+        """
+
+        if inspect.iscoroutinefunction(func):
+
+            @functools.wraps(func)
+            async def async_wrapper(*args, **kwargs):
+                with self:
+                    return await func(*args, **kwargs)
+
+            return async_wrapper
+
+        @functools.wraps(func)
+        def wrapper(*args, **kwargs):
+            with self:
+                return func(*args, **kwargs)
+
+        return wrapper
@@ -0,0 +1,271 @@
+"""Import the snapshots of a suite that used syrupy or snapshottest.
+
+:func:`import_snapshots` finds the other library's snapshot files under a
+root, reads every snapshot out of them and writes it where the test's own
+pysnaptest assertion will look for it, so a suite can switch libraries
+without re-recording anything:
+
+* syrupy keeps a test file's snapshots in ``__snapshots__/<module>.ambr``
+  next to it, as ``# name: <test>`` blocks. A test's first snapshot is
+  named after it and the following ones ``<test>.1``, ``<test>.2``, ...
+* snapshottest keeps them in ``snapshots/snap_<module>.py`` next to the test
+  file, as ``snapshots['<test> <n>'] = <value>`` assignments counting from 1.
+
+Both name a test method ``<Class>.<method>``, which becomes the pytest node's
+``<Class>::<method>``. The ``n``th snapshot of a test becomes its ``n``th
+pysnaptest snapshot, ``snapshots/<module>__<module>_<test>[-n]@pysnap.snap``.
+
+Strings become text snapshots, the body of the file being the string itself,
+as ``assert_snapshot`` writes them. Dicts, lists, tuples, sets, numbers,
+booleans and ``None`` become JSON snapshots, as ``assert_json_snapshot``
+writes them (dict keys as strings, sets sorted). Anything else, such as
+syrupy's rendering of a custom object or snapshottest's ``GenericRepr``, is
+kept as text: the other library's rendering of it. A snapshot the test
+already has in pysnaptest is left untouched, as are snapshot files whose test
+file is gone.
+"""
+
+from __future__ import annotations
+
+import ast
+import math
+from pathlib import Path
+from typing import Any, Iterator, List, Literal, Optional, Tuple
+
+from ._pysnaptest import write_imported_snapshot
+from .review import _root
+
+#: The libraries :func:`import_snapshots` reads.
+SOURCES = ("syrupy", "snapshottest")
+
+
+class _NotData(ValueError):
+    """Raised for an expression that is not plain data."""
+
+
+def _data(node: ast.expr) -> Any:
+    """The plain data the expression ``node`` spells out.
+
+    Raises:
+        _NotData: When it spells out anything else.
+    """
+
+    if isinstance(node, ast.Constant) and isinstance(
+        node.value, (str, int, float, type(None))
+    ):
+        if isinstance(node.value, float) and not math.isfinite(node.value):
+            raise _NotData(node)
+        return node.value
+    if isinstance(node, ast.UnaryOp) and isinstance(node.op, ast.USub):
+        value = _data(node.operand)
+        if isinstance(value, (int, float)) and not isinstance(value, bool):
+            return -value
+    elif isinstance(node, ast.Dict):
+        if any(key is None for key in node.keys):
+            raise _NotData(node)
+        keys = [_data(key) for key in node.keys]  # type: ignore[arg-type]
+        if any(isinstance(key, (dict, list)) for key in keys):
+            raise _NotData(node)
+        return {
+            key if isinstance(key, str) else _key(key): _data(value)
+            for key, value in zip(keys, node.values)
+        }
+    elif isinstance(node, (ast.List, ast.Tuple)):
+        return [_data(item) for item in node.elts]
+    elif isinstance(node, ast.Set):
+        return sorted((_data(item) for item in node.elts), key=repr)
+    elif (
+        isinstance(node, ast.Call)
+        and isinstance(node.func, ast.Name)
+        and node.func.id in ("dict", "list", "tuple", "set", "frozenset")
+        and not node.keywords
+        and len(node.args) <= 1
+    ):
+        # syrupy writes containers as ``dict({...})``, ``list([...])``, ...
+        if not node.args:
+            return {} if node.func.id == "dict" else []
+        value = _data(node.args[0])
+        if node.func.id in ("set", "frozenset") and isinstance(value, list):
+            return sorted(value, key=repr)
+        return value
+    raise _NotData(node)
+
+
+def _key(key: Any) -> str:
+    """A non-string dict key as JSON spells it."""
+
+    if key is None:
+        return "null"
+    if isinstance(key, bool):
+        return "true" if key else "false"
+    return str(key)
+
+
+def _test_name(name: str) -> str:
+    """The pytest node name of the test the other library names ``name``.
+
+    ``<Class>.<method>[params]`` becomes ``<Class>::<method>[params]``; dots
+    inside the parameters are kept.
+    """
+
+    head, bracket, params = name.partition("[")
+    return head.replace(".", "::") + bracket + params
+
+
+def _ambr_name(name: str) -> Tuple[str, int]:
+    """The test and the (1-based) index of the syrupy snapshot ``name``."""
+
+    head, bracket, params = name.partition("[")
+    if bracket:
+        params, dot, index = params.rpartition("].")
+        if dot and index.isdigit():
+            return _test_name(f"{head}[{params}]"), int(index) + 1
+        return _test_name(name), 1
+    test, dot, index = head.rpartition(".")
+    if dot and index.isdigit():
+        return _test_name(test), int(index) + 1
+    return _test_name(name), 1
+
+
+def _ambr_value(body: str) -> Any:
+    """The value of a snapshot written by syrupy's amber serializer."""
+
+    if body.startswith("'''\n") and body.endswith("\n'''"):
+        # Multi-line strings are written between lines of triple quotes.
+        return body[len("'''\n") : -len("\n'''")]
+    try:
+        return _data(ast.parse(body, mode="eval").body)
+    except (SyntaxError, _NotData):
+        return body
+
+
+def read_ambr(path: Path) -> Iterator[Tuple[str, int, Any]]:
+    """The snapshots of syrupy's ``.ambr`` file at ``path``.
+
+    Args:
+        path: Path to a ``__snapshots__/<module>.ambr`` file.
+
+    Yields:
+        Tuple[str, int, Any]: Each snapshot's test, index (counting from 1)
+        and value.
+    """
+
+    name: Optional[str] = None
+    lines: List[str] = []
+    for line in path.read_text(encoding="utf-8").splitlines():
+        if name is None:
+            if line.startswith("# name: "):
+                name, lines = line[len("# name: ") :], []
+        elif line == "# ---":
+            test, index = _ambr_name(name)
+            yield test, index, _ambr_value("\n".join(lines))
+            name = None
+        else:
+            # The body of a snapshot is indented by two spaces.
+            lines.append(line[2:] if line.startswith("  ") else line.lstrip())
+
+
+def read_snapshottest(path: Path) -> Iterator[Tuple[str, int, Any]]:
+    """The snapshots of snapshottest's ``snap_*.py`` module at ``path``.
+
+    The module is parsed, not imported.
+
+    Args:
+        path: Path to a ``snapshots/snap_<module>.py`` file.
+
+    Yields:
+        Tuple[str, int, Any]: Each snapshot's test, index (counting from 1)
+        and value.
+    """
+
+    tree = ast.parse(path.read_text(encoding="utf-8"))
+    for node in tree.body:
+        if not (
+            isinstance(node, ast.Assign)
+            and len(node.targets) == 1
+            and isinstance(node.targets[0], ast.Subscript)
+            and isinstance(node.targets[0].value, ast.Name)
+            and node.targets[0].value.id == "snapshots"
+        ):
+            continue
+        key = node.targets[0].slice
+        if not (isinstance(key, ast.Constant) and isinstance(key.value, str)):
+            continue
+        test, _, index = key.value.rpartition(" ")
+        if not (test and index.isdigit()):
+            continue
+        yield _test_name(test), int(index), _snapshottest_value(node.value)
+
+
+def _snapshottest_value(node: ast.expr) -> Any:
+    """The value of a snapshot written by snapshottest."""
+
+    if (
+        isinstance(node, ast.Call)
+        and isinstance(node.func, ast.Name)
+        and node.func.id == "GenericRepr"
+        and len(node.args) == 1
+        and isinstance(node.args[0], ast.Constant)
+        and isinstance(node.args[0].value, str)
+    ):
+        return node.args[0].value
+    try:
+        return _data(node)
+    except _NotData:
+        return ast.unparse(node)
+
+
+def _snapshot_files(source: str, root: Path) -> Iterator[Tuple[Path, Path]]:
+    """Each snapshot file of ``source`` under ``root`` and its test file."""
+
+    if source == "syrupy":
+        for path in sorted(root.rglob("__snapshots__/*.ambr")):
+            yield path, path.parent.parent / f"{path.stem}.py"
+    else:
+        for path in sorted(root.rglob("snapshots/snap_*.py")):
+            yield path, path.parent.parent / f"{path.stem[len('snap_'):]}.py"
+
+
+def import_snapshots(
+    source: Literal["syrupy", "snapshottest"], root: Optional[str] = None
+) -> List[Path]:
+    """Convert the syrupy or snapshottest snapshots under ``root`` into
+    pysnaptest snapshots.
+
+    Every snapshot is written where the test's pysnaptest assertion of the
+    same position reads it, with a header recording the test and
+    ``imported_from: <source>``. The other library's files are left in place.
+
+    Args:
+        source: The library the snapshots were recorded with, ``"syrupy"`` or
+            ``"snapshottest"``.
+        root: The project root, which test file paths are recorded relative
+            to. Defaults to ``INSTA_WORKSPACE_ROOT`` if set, otherwise the
+            current working directory.
+
+    Returns:
+        List[Path]: The snapshot files written, in the order they were.
+
+    Raises:
+        ValueError: If ``source`` is not a supported library.
+    """
+
+    if source not in SOURCES:
+        raise ValueError(
+            f"Unsupported snapshot library {source!r}; expected one of "
+            + ", ".join(repr(s) for s in SOURCES)
+        )
+    base = _root(root).resolve()
+    read = read_ambr if source == "syrupy" else read_snapshottest
+    written = []
+    for snapshot_file, test_file in _snapshot_files(source, base):
+        if not test_file.is_file():
+            continue
+        recorded = test_file.relative_to(base)
+        for test, index, value in read(snapshot_file):
+            path = write_imported_snapshot(
+                test_file, recorded, test, index, value, source
+            )
+            if path is not None:
+                written.append(Path(path))
+    return written
@@ -0,0 +1,113 @@
+"""Snapshots from notebooks and other interactive sessions.
+
+Outside pytest there is no running test to name snapshots after. Once a
+session is started, the assertion helpers fall back to it instead: snapshots
+are named after the session (or their ``snapshot_name``) and stored in the
+session's own directory, ``snapshots/<session>`` next to the notebook by
+default.
+
+Unless ``INSTA_UPDATE`` says otherwise, a session never overwrites a stored
+snapshot by itself. A new or changed value is written as a pending
+``.snap.new`` file and the assertion fails; inspect the result and keep it
+explicitly with :func:`accept_session`, or drop it with
+:func:`reject_session`:
+
+.. code-block:: python
+
+    from pysnaptest import assert_json_snapshot
+    from pysnaptest.interactive import accept_session, start_session
+
+    start_session("pricing-exploration")
+    assert_json_snapshot(summary, snapshot_name="summary")  # fails: new
+    accept_session()
+"""
+
+from __future__ import annotations
+
+import os
+from pathlib import Path
+from typing import List, Optional, Union
+
+from ._pysnaptest import SnapshotInfo
+from .review import accept_pending_snapshot, find_pending_snapshots, reject_pending_snapshot
+
+#: Names the active session; derived from the notebook path when unset.
+SESSION_ENV = "PYSNAPTEST_SESSION"
+#: Overrides the directory the session's snapshots are stored in.
+SESSION_DIR_ENV = "PYSNAPTEST_SESSION_DIR"
+
+
+def start_session(
+    name: Optional[str] = None, snapshot_dir: Optional[Union[str, Path]] = None
+) -> SnapshotInfo:
+    """Start (or restart) an interactive snapshot session.
+
+    Restarting numbers repeated snapshots from the beginning again, as when a
+    notebook is rerun from the top. Call it before the first assertion: it
+    selects the pending-snapshot workflow (``INSTA_UPDATE=new``) unless
+    ``INSTA_UPDATE`` is already set, and insta reads that only once.
+
+    Args:
+        name: The session's name. Defaults to the stem of the Jupyter notebook
+            the kernel runs, when Jupyter reports it.
+        snapshot_dir: Directory to store the session's snapshots in. Defaults
+            to ``snapshots/<name>`` next to the notebook, or in the current
+            directory.
+
+    Returns:
+        SnapshotInfo: The session's snapshot configuration.
+
+    Raises:
+        ValueError: If no ``name`` is given and no notebook can be found.
+    """
+
+    if name is not None:
+        os.environ[SESSION_ENV] = name
+    if snapshot_dir is not None:
+        os.environ[SESSION_DIR_ENV] = str(Path(snapshot_dir).resolve())
+    info = SnapshotInfo.from_session()
+    os.environ.setdefault("INSTA_UPDATE", "new")
+    SnapshotInfo.reset_all_counters()
+    return info
+
+
+def end_session() -> None:
+    """End the interactive session started by :func:`start_session`."""
+
+    os.environ.pop(SESSION_ENV, None)
+    os.environ.pop(SESSION_DIR_ENV, None)
+
+
+def session_snapshot_dir() -> Path:
+    """The directory the active session's snapshots are stored in."""
+
+    return Path(SnapshotInfo.from_session().snapshot_folder())
+
+
+def pending_session_snapshots() -> List[Path]:
+    """The session's pending ``.snap.new`` snapshots, sorted."""
+
+    return find_pending_snapshots(str(session_snapshot_dir()))
+
+
+def accept_session() -> List[Path]:
+    """Keep every pending snapshot of the session.
+
+    Returns:
+        List[Path]: The ``.snap`` files written.
+    """
+
+    return [accept_pending_snapshot(path) for path in pending_session_snapshots()]
+
+
+def reject_session() -> int:
+    """Discard every pending snapshot of the session.
+
+    Returns:
+        int: The number of pending snapshots discarded.
+    """
+
+    pending = pending_session_snapshots()
+    for path in pending:
+        reject_pending_snapshot(path)
+    return len(pending)
@@ -0,0 +1,56 @@
+"""Render snapshot failures for JUnit XML reports.
+
+A failing snapshot assertion raises :class:`SnapshotMismatchError`, whose
+``snapshot_failure`` attribute describes a text snapshot's failure as a
+dict: the ``snapshot`` name, the committed ``expected_path``, the pending
+``actual_path`` (the ``.snap.new`` file), the uncolored ``diff`` and the
+``html_path`` of the side-by-side diff (``None`` unless one was written).
+:func:`format_junit_system_out` formats it for the ``system-out`` of a JUnit
+test case, where CI dashboards show it next to the failure; the
+``[[ATTACHMENT|...]]`` lines let Jenkins' JUnit attachments plugin link the
+files. A :class:`~pysnaptest.soft.SoftSnapshotError` is formatted as each of
+its failures in turn.
+"""
+
+from __future__ import annotations
+
+from typing import Mapping, Optional, Union
+
+from ._pysnaptest import SnapshotMismatchError
+from .soft import SoftSnapshotError
+
+_Failure = Mapping[str, Optional[str]]
+
+
+def format_junit_system_out(failure: Union[BaseException, _Failure]) -> str:
+    """Format a snapshot failure for JUnit XML ``system-out``.
+
+    Args:
+        failure: A :class:`SnapshotMismatchError` or a
+            :class:`~pysnaptest.soft.SoftSnapshotError`, or the
+            ``snapshot_failure`` dict of the former.
+
+    Returns:
+        str: The attachments, paths and diff of the failure, or an empty
+        string for an exception that does not describe one (another
+        exception, or a mismatch of a binary snapshot).
+    """
+
+    if isinstance(failure, SoftSnapshotError):
+        return "".join(format_junit_system_out(each) for each in failure.failures)
+    if isinstance(failure, BaseException):
+        if not isinstance(failure, SnapshotMismatchError):
+            return ""
+        failure = getattr(failure, "snapshot_failure", None)
+        if failure is None:
+            return ""
+    attachments = [failure["actual_path"], failure.get("html_path")]
+    lines = [f"[[ATTACHMENT|{path}]]" for path in attachments if path]
+    lines += [
+        f"Snapshot '{failure['snapshot']}' did not match.",
+        f"Expected: {failure['expected_path']}",
+        f"Actual: {failure['actual_path']}",
+        "",
+        failure["diff"] or "",
+    ]
+    return "\n".join(lines) + "\n"
@@ -0,0 +1,107 @@
+"""Snapshot helpers for LLM API responses.
+
+Chat completion responses carry fields that change on every call -- response
+and tool-call ids, creation timestamps, token counts, latencies -- around the
+part a test actually cares about: the message content and the tool calls. These
+helpers snapshot OpenAI- and Anthropic-style responses (plain dicts or the SDKs'
+Pydantic models) with those fields scrubbed by a redaction preset from
+:mod:`pysnaptest.presets`.
+"""
+
+from __future__ import annotations
+
+from typing import Any, Callable, Iterable, List, Optional, Union
+
+from .assertion import assert_json_snapshot
+from .mocks import mock_json_snapshot
+from .presets import Redactions, resolve_redactions
+
+
+def assert_llm_snapshot(
+    result: Any,
+    snapshot_path: Optional[str] = None,
+    snapshot_name: Optional[str] = None,
+    redactions: Optional[Redactions] = None,
+    preset: Union[str, Iterable[str]] = "llm",
+    allow_duplicates: bool = False,
+    encryption_key: Optional[str] = None,
+    ignore: Optional[List[str]] = None,
+) -> None:
+    """Assert that an LLM response matches the stored snapshot.
+
+    Args:
+        result: The response, as a dict or an SDK response model.
+        snapshot_path: Optional path override for storing the snapshot.
+        snapshot_name: Optional name override for the snapshot file.
+        redactions: Extra redactions, applied over the preset's.
+        preset: Redaction preset(s) to apply: ``"openai"``, ``"anthropic"``,
+            ``"llm"`` (both, the default) or any registered with
+            :func:`pysnaptest.presets.register_preset`.
+        allow_duplicates: Whether to allow duplicate snapshot names.
+        encryption_key: Encrypt the stored snapshot with this key. Defaults to
+            the ``PYSNAPTEST_KEY`` environment variable.
+        ignore: Selectors of values to leave out of the snapshot entirely.
+    """
+
+    assert_json_snapshot(
+        result,
+        snapshot_path,
+        snapshot_name,
+        resolve_redactions(preset, redactions),
+        allow_duplicates,
+        encryption_key=encryption_key,
+        ignore=ignore,
+    )
+
+
+def mock_llm_snapshot(
+    func: Callable,
+    record: bool = False,
+    snapshot_path: Optional[str] = None,
+    snapshot_name: Optional[str] = None,
+    redactions: Optional[Redactions] = None,
+    preset: Union[str, Iterable[str]] = "llm",
+    allow_duplicates: bool = False,
+    encryption_key: Optional[str] = None,
+    simulate_latency: Optional[float] = None,
+    max_age_days: Optional[float] = None,
+    ignore: Optional[List[str]] = None,
+):
+    """Return a mock of an LLM client call that records and replays responses.
+
+    Works like :func:`pysnaptest.mock_json_snapshot` with the preset's
+    redactions applied. Replayed responses are the recorded, scrubbed dicts, so
+    ids and token counts read back as their placeholders (e.g. ``"[tokens]"``).
+
+    Args:
+        func: The client call to wrap, e.g. ``client.chat.completions.create``.
+        record: Whether to record snapshots regardless of differences.
+        snapshot_path: Optional path override for storing the snapshot.
+        snapshot_name: Optional name override for the snapshot file.
+        redactions: Extra redactions, applied over the preset's.
+        preset: Redaction preset(s) to apply; see :func:`assert_llm_snapshot`.
+        allow_duplicates: Whether to allow duplicate snapshot names.
+        encryption_key: Encrypt the recorded snapshots with this key.
+        simulate_latency: On replay, wait this fraction of the recorded call
+            duration; see :func:`pysnaptest.mock_json_snapshot`.
+        max_age_days: Fail the replay of recordings older than this; see
+            :func:`pysnaptest.mock_json_snapshot`.
+        ignore: Selectors of values to leave out of the recorded snapshots
+            entirely; see :func:`pysnaptest.mock_json_snapshot`.
+
+    Returns:
+        Callable: The wrapped function.
+    """
+
+    return mock_json_snapshot(
+        func,
+        record=record,
+        snapshot_path=snapshot_path,
+        snapshot_name=snapshot_name,
+        redactions=resolve_redactions(preset, redactions),
+        allow_duplicates=allow_duplicates,
+        encryption_key=encryption_key,
+        simulate_latency=simulate_latency,
+        max_age_days=max_age_days,
+        ignore=ignore,
+    )
@@ -0,0 +1,134 @@
+"""Verify the audit trail of the snapshots pysnaptest wrote.
+
+Every snapshot folder keeps a ``.pysnaptest-manifest.jsonl``: whenever an
+assertion, or ``pysnaptest accept``, creates a snapshot or rewrites it with
+new contents, a line is appended with the test that wrote it, the snapshot's
+file name, whether it was ``"created"`` or ``"updated"``, the SHA-256 of its
+contents and a UTC timestamp. Comparing the folder against its manifest finds
+snapshots that changed without a test writing them:
+
+* ``"edited"`` — the contents differ from those last recorded, as after a
+  hand edit or a bad merge;
+* ``"unrecorded"`` — the manifest has no line for the snapshot, as for a
+  file dropped in by hand or written before the folder had a manifest;
+* ``"missing"`` — the manifest lists a snapshot that is gone, as when it
+  was deleted by hand rather than with ``pysnaptest unused --delete`` or
+  ``pysnaptest gc --delete``, which drop its lines.
+
+Only the contents below the header are hashed, so the metadata pysnaptest
+rewrites itself does not count as an edit. Folders without a manifest are
+not checked.
+"""
+
+from __future__ import annotations
+
+import json
+from pathlib import Path
+from typing import Dict, List, NamedTuple, Optional
+
+from ._pysnaptest import MANIFEST_FILE, snapshot_content_hash
+from .review import SNAPSHOT_SUFFIX, _root
+
+
+class ManifestEntry(NamedTuple):
+    """A line of a snapshot folder's manifest."""
+
+    test: Optional[str]
+    path: Path
+    change: str
+    sha256: str
+    timestamp: str
+
+
+class ManifestIssue(NamedTuple):
+    """A snapshot that does not agree with its folder's manifest, and how."""
+
+    path: Path
+    problem: str
+
+
+def read_manifest(directory: str | Path) -> List[ManifestEntry]:
+    """Read the manifest of a snapshot folder.
+
+    Args:
+        directory: The snapshot folder.
+
+    Returns:
+        List[ManifestEntry]: Its lines, oldest first, with each ``path``
+        resolved against ``directory``; empty without a manifest. Lines that
+        are not valid entries are skipped.
+    """
+
+    directory = Path(directory)
+    entries: List[ManifestEntry] = []
+    try:
+        lines = (directory / MANIFEST_FILE).read_text(encoding="utf-8").splitlines()
+    except FileNotFoundError:
+        return entries
+    for line in lines:
+        try:
+            fields = json.loads(line)
+            entries.append(
+                ManifestEntry(
+                    test=fields["test"],
+                    path=directory / fields["path"],
+                    change=fields["change"],
+                    sha256=fields["sha256"],
+                    timestamp=fields["timestamp"],
+                )
+            )
+        except (ValueError, KeyError, TypeError):
+            continue
+    return entries
+
+
+def verify_directory(directory: str | Path) -> List[ManifestIssue]:
+    """Compare a snapshot folder against its manifest.
+
+    Args:
+        directory: The snapshot folder.
+
+    Returns:
+        List[ManifestIssue]: The snapshots ``"edited"``, ``"unrecorded"`` or
+        ``"missing"``, sorted by path; empty without a manifest.
+    """
+
+    directory = Path(directory)
+    if not (directory / MANIFEST_FILE).is_file():
+        return []
+    latest: Dict[Path, ManifestEntry] = {
+        entry.path: entry for entry in read_manifest(directory)
+    }
+    issues = [
+        ManifestIssue(path, "missing") for path in latest if not path.is_file()
+    ]
+    for path in directory.glob(f"*{SNAPSHOT_SUFFIX}"):
+        entry = latest.get(path)
+        if entry is None:
+            issues.append(ManifestIssue(path, "unrecorded"))
+            continue
+        try:
+            edited = snapshot_content_hash(path) != entry.sha256
+        except ValueError:
+            edited = True
+        if edited:
+            issues.append(ManifestIssue(path, "edited"))
+    return sorted(issues)
+
+
+def verify_manifest(root: Optional[str] = None) -> List[ManifestIssue]:
+    """Compare every snapshot folder with a manifest under ``root`` against it.
+
+    Args:
+        root: Directory to search. Defaults to ``INSTA_WORKSPACE_ROOT`` if
+            set, otherwise the current working directory.
+
+    Returns:
+        List[ManifestIssue]: The disagreeing snapshots of every folder,
+        sorted by path.
+    """
+
+    issues: List[ManifestIssue] = []
+    for manifest in sorted(_root(root).rglob(MANIFEST_FILE)):
+        issues.extend(verify_directory(manifest.parent))
+    return sorted(issues)
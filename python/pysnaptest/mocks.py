@@ -12,23 +12,358 @@ ticking the shared duplicate counter -- is owned by the Rust
 methods used by the regular JSON snapshot machinery. The remaining two Rust
 primitives, `assert_json_snapshot_named` and `read_json_snapshot`, write and
 read the response snapshot once Python has decided what belongs there.
+`patch_json_snapshot`, which swaps a dotted target for such a mock and restores
+it afterwards, is implemented in Rust as well.
 """
 
 from __future__ import annotations
 
+import asyncio
 import functools
-import importlib
 import inspect
-from typing import Callable, Dict, Optional, Union
-from unittest.mock import patch
+import time
+from datetime import datetime, timezone
+from pathlib import Path
+from typing import Any, Callable, Dict, Iterable, List, NamedTuple, Optional, Union
 
 from ._pysnaptest import (
     assert_json_snapshot_named as _assert_json_snapshot_named,
+    patch_json_snapshot,
     prepare_mock_call as _prepare_mock_call,
+    preview_json_snapshot_named as _preview_json_snapshot_named,
     read_json_snapshot as _read_json_snapshot,
+    read_json_snapshot_text as _read_json_snapshot_text,
+    recorded_at as _recorded_at,
+    recorded_by as _recorded_by,
+    recorded_duration_ms as _recorded_duration_ms,
+    recorded_type as _recorded_type,
+    sanitize_snapshot_name,
 )
 from .assertion import extract_from_pytest_env
 from .encoders import to_jsonable
+from .orphans import defined_tests
+from .presets import Redactions
+from .review import _root
+from .unused import (
+    MOCK_NAMESPACE,
+    delete_snapshot,
+    discover_snapshot_dirs,
+    is_mock_snapshot,
+    snapshot_files,
+    snapshot_names,
+)
+
+
+class _Recorded:
+    def __repr__(self) -> str:
+        return "RECORDED"
+
+
+RECORDED = _Recorded()
+"""Side-effect entry that lets a call record or replay as usual."""
+
+
+def _encoded(value: Any, encode: Optional[Callable[[Any], Any]]) -> Any:
+    """``value`` as it is snapshotted: through ``encode``, then made JSON-able."""
+
+    return to_jsonable(value if encode is None else encode(value))
+
+
+def _is_exception(effect: Any) -> bool:
+    return isinstance(effect, BaseException) or (
+        isinstance(effect, type) and issubclass(effect, BaseException)
+    )
+
+
+def _apply_side_effect(
+    test_info: Any,
+    effect: Any,
+    name: str,
+    redactions: Optional[Redactions],
+    encryption_key: Optional[str],
+    ignore: Optional[List[str]],
+    encode: Optional[Callable[[Any], Any]],
+) -> Any:
+    """Raise an exception effect, or snapshot and return a value effect."""
+
+    if _is_exception(effect):
+        raise effect
+    _assert_json_snapshot_named(
+        test_info,
+        _encoded(effect, encode),
+        name,
+        redactions,
+        encryption_key,
+        ignore=ignore,
+        result_type=type(effect),
+    )
+    return effect
+
+
+def _store_result(
+    test_info: Any,
+    result: Any,
+    name: str,
+    redactions: Optional[Redactions],
+    encryption_key: Optional[str],
+    duration_ms: float,
+    spy: bool,
+    recorded_at: Optional[str],
+    ignore: Optional[List[str]],
+    encode: Optional[Callable[[Any], Any]],
+) -> None:
+    """Assert a real call's result as its response snapshot."""
+
+    try:
+        _assert_json_snapshot_named(
+            test_info,
+            _encoded(result, encode),
+            name,
+            redactions,
+            encryption_key,
+            duration_ms,
+            recorded_at,
+            ignore=ignore,
+            result_type=type(result),
+        )
+    except AssertionError as exc:
+        if not spy:
+            raise
+        raise AssertionError(
+            f"{name!r} drifted from its recording (spy mode): {exc}"
+        ) from exc
+
+
+class DryRunChange(NamedTuple):
+    """A snapshot file a ``mode="dry_run"`` mock would have written."""
+
+    path: Path
+    change: str
+    """``"new"`` for a file that does not exist yet, ``"changed"`` otherwise."""
+
+
+def _preview(
+    test_info: Any,
+    value: Any,
+    name: str,
+    redactions: Optional[Redactions],
+    encryption_key: Optional[str],
+    ignore: Optional[List[str]],
+    report: List[DryRunChange],
+    encode: Optional[Callable[[Any], Any]] = None,
+) -> None:
+    """Add the change asserting ``value`` as ``name`` would make to ``report``."""
+
+    path, change = _preview_json_snapshot_named(
+        test_info,
+        _encoded(value, encode),
+        name,
+        redactions,
+        encryption_key,
+        ignore=ignore,
+    )
+    if change is not None:
+        report.append(DryRunChange(path, change))
+
+
+MOCK_MODES = ("replay", "spy", "dry_run")
+
+RECORDED_AT_FORMAT = "%Y-%m-%dT%H:%M:%SZ"
+
+
+# Factories rebuilding replayed results, by the ``module:qualname`` of the
+# type they were recorded from.
+_RESULT_FACTORIES: Dict[str, Callable[[Any], Any]] = {}
+
+
+def register_result_factory(
+    result_type: Union[type, str], factory: Optional[Callable[[Any], Any]]
+) -> None:
+    """Rebuild responses recorded from ``result_type`` results with ``factory``
+    on replay; ``None`` unregisters it.
+
+    Args:
+        result_type: The class, or its ``module:qualname``.
+        factory: Called with the replayed value, after a mock's ``decode``
+            if it has one; what it returns is what the mock returns.
+
+    Raises:
+        ValueError: If ``result_type`` is a builtin type, which is not
+            recorded.
+    """
+
+    if isinstance(result_type, type):
+        if result_type.__module__ == "builtins":
+            raise ValueError(
+                f"{result_type!r} is a builtin type, which replay returns without a factory"
+            )
+        result_type = f"{result_type.__module__}:{result_type.__qualname__}"
+    if factory is None:
+        _RESULT_FACTORIES.pop(result_type, None)
+    else:
+        _RESULT_FACTORIES[result_type] = factory
+
+
+def _replay(
+    response_path: Any,
+    encryption_key: Optional[str],
+    decode: Optional[Callable[[Any], Any]],
+) -> Any:
+    """Return the recorded response at ``response_path``, built by ``decode``
+    from its JSON text when given, then by the factory registered for the
+    type of the recorded result, if any."""
+
+    if decode is None:
+        value = _read_json_snapshot(response_path, encryption_key)
+    else:
+        value = decode(_read_json_snapshot_text(response_path, encryption_key))
+    factory = _RESULT_FACTORIES.get(_recorded_type(response_path) or "")
+    return value if factory is None else factory(value)
+
+
+def utc_timestamp() -> str:
+    """The current UTC time as stored in a recording's ``recorded_at``."""
+
+    return datetime.now(timezone.utc).strftime(RECORDED_AT_FORMAT)
+
+
+def recording_age_days(
+    response_path: Any, now: Optional[datetime] = None
+) -> Optional[float]:
+    """Days since the recording at ``response_path`` was made.
+
+    Args:
+        response_path: Path of the recorded response snapshot.
+        now: Reference time; defaults to the current UTC time.
+
+    Returns:
+        Optional[float]: The age, or ``None`` when the recording has no
+        ``recorded_at`` timestamp (made by an older pysnaptest, or a scripted
+        side effect).
+    """
+
+    stamp = _recorded_at(response_path)
+    if stamp is None:
+        return None
+    try:
+        recorded = datetime.strptime(stamp, RECORDED_AT_FORMAT)
+    except ValueError:
+        return None
+    recorded = recorded.replace(tzinfo=timezone.utc)
+    return ((now or datetime.now(timezone.utc)) - recorded).total_seconds() / 86400
+
+
+def check_recording_age(response_path: Any, max_age_days: Optional[float]) -> None:
+    """Fail replay of a recording older than ``max_age_days``.
+
+    Raises:
+        AssertionError: If the recording has expired.
+    """
+
+    if max_age_days is None:
+        return
+    age = recording_age_days(response_path)
+    if age is not None and age > max_age_days:
+        raise AssertionError(
+            f"Recording {Path(response_path).name} is {age:.0f} days old "
+            f"(max_age_days={max_age_days}); re-record it with record=True"
+        )
+
+
+def find_expired_recordings(
+    root: Optional[str] = None,
+    max_age_days: float = 30,
+    now: Optional[datetime] = None,
+) -> List[Path]:
+    """Find mock recordings older than ``max_age_days``.
+
+    Args:
+        root: Directory whose ``snapshots`` directories are searched (defaults
+            to ``$INSTA_WORKSPACE_ROOT`` or the current directory).
+        max_age_days: Maximum allowed age of a recording.
+        now: Reference time; defaults to the current UTC time.
+
+    Returns:
+        List[Path]: Sorted response snapshots past their age. Recordings
+        without a ``recorded_at`` timestamp are never reported.
+    """
+
+    expired = []
+    for path in snapshot_files(discover_snapshot_dirs(_root(root))):
+        if not is_mock_snapshot(path):
+            continue
+        age = recording_age_days(path, now)
+        if age is not None and age > max_age_days:
+            expired.append(path)
+    return sorted(expired)
+
+
+def replay_delay(response_path: Any, simulate_latency: Optional[float]) -> float:
+    """Seconds to wait before replaying a recording made at ``response_path``.
+
+    Args:
+        response_path: Path of the recorded response snapshot.
+        simulate_latency: Fraction of the recorded call duration to wait, or
+            ``None`` to replay immediately.
+
+    Returns:
+        float: The delay; ``0.0`` when the recording has no duration.
+    """
+
+    if not simulate_latency:
+        return 0.0
+    duration_ms = _recorded_duration_ms(response_path) or 0.0
+    return duration_ms / 1000 * simulate_latency
+
+
+def _recorded_by_node(path: Path, test_node_id: str, root: Path) -> bool:
+    """Whether the mock snapshot at ``path`` was recorded by ``test_node_id``.
+
+    The ``test`` metadata decides when present. Older recordings are matched
+    by name instead: the test file's ``snapshots`` directory, and a filename
+    scoped to the test (but not to a longer-named test of the same file).
+    """
+
+    recorded = _recorded_by(path)
+    if recorded is not None:
+        return recorded == test_node_id
+    test_file, _, test_name = test_node_id.partition("::")
+    if path.parent != (root / test_file).parent.resolve() / "snapshots":
+        return False
+    stem = f"{MOCK_NAMESPACE}__{Path(test_file).stem}_"
+    name = next((n for n in snapshot_names(path) if n.startswith(stem)), None)
+    scoped = f"{stem}{sanitize_snapshot_name(test_name)}_"
+    if name is None or not name.startswith(scoped):
+        return False
+    return not any(
+        name.startswith(f"{stem}{sanitize_snapshot_name(other)}_")
+        for other in defined_tests(root / test_file)
+        if other != test_name and other.startswith(test_name)
+    )
+
+
+def rerecord(test_node_id: str, root: Optional[Union[str, Path]] = None) -> List[Path]:
+    """Delete the mock recordings made by one test, so its next run records
+    them afresh.
+
+    Args:
+        test_node_id: The test's pytest node id, e.g.
+            ``"tests/test_api.py::test_checkout"``.
+        root: The pytest root directory the node id is relative to (defaults
+            to ``$INSTA_WORKSPACE_ROOT`` or the current directory).
+
+    Returns:
+        List[Path]: The deleted files, sorted.
+    """
+
+    base = _root(None if root is None else str(root)).resolve()
+    deleted: List[Path] = []
+    for path in snapshot_files(discover_snapshot_dirs(base)):
+        if is_mock_snapshot(path) and _recorded_by_node(
+            path, test_node_id, base
+        ):
+            deleted.extend(delete_snapshot(path))
+    return sorted(deleted)
 
 
 def mock_json_snapshot(
@@ -36,13 +371,29 @@ def mock_json_snapshot(
     record: bool = False,
     snapshot_path: Optional[str] = None,
     snapshot_name: Optional[str] = None,
-    redactions: Optional[Dict[str, Union[str, int, None]]] = None,
+    redactions: Optional[Redactions] = None,
     allow_duplicates: bool = False,
+    encryption_key: Optional[str] = None,
+    simulate_latency: Optional[float] = None,
+    side_effects: Optional[Iterable[Any]] = None,
+    mode: str = "replay",
+    max_age_days: Optional[float] = None,
+    ignore: Optional[List[str]] = None,
+    node_id: Optional[str] = None,
+    scope: Optional[str] = None,
+    namespace: Optional[str] = None,
+    decode: Optional[Callable[[Any], Any]] = None,
+    encode: Optional[Callable[[Any], Any]] = None,
 ):
     """Return a function mock that snapshots its JSON result.
 
     Both synchronous and async def functions are supported: an async func
     yields an async mock that awaits the real function while recording.
+    A binary response snapshot replays as ``bytes``, or through the decoder
+    registered for its extension with :func:`register_replay_decoder`. A
+    replayed value, decoded or not, is then passed to the factory registered
+    with :func:`register_result_factory` for the type of the recorded result,
+    if any.
 
     Args:
         func: Function to wrap with snapshot behaviour.
@@ -51,13 +402,81 @@ def mock_json_snapshot(
         snapshot_name: Optional name override for the snapshot file.
         redactions: Mapping of selectors to replacement values.
         allow_duplicates: Whether to allow duplicate snapshot names.
+        encryption_key: Encrypt the recorded request/response snapshots with
+            this key (and decrypt them on replay). Defaults to the
+            ``PYSNAPTEST_KEY`` environment variable.
+        simulate_latency: On replay, wait this fraction of the call's recorded
+            wall-clock duration (``1.0`` for the full duration) before
+            returning, so timeout and retry logic can be exercised.
+        side_effects: Outcomes for successive calls, as with
+            ``unittest.mock``'s ``side_effect``: an exception (class or
+            instance) is raised, :data:`RECORDED` records or replays as usual,
+            and any other value is returned and stored as that call's response
+            snapshot. Calls past the end of the list record or replay as usual.
+            The request snapshot is written either way.
+        mode: ``"replay"`` calls the real function only to record a missing
+            (or, with ``record``, any) response and otherwise replays it.
+            ``"spy"`` always calls the real function and asserts its result
+            against the recording, so a third-party dependency's behavioural
+            drift fails the test while the real result is still used.
+            ``"dry_run"`` always calls the real function too, but writes
+            nothing: the request and response snapshots a re-recording would
+            create or change are listed, as :class:`DryRunChange` entries, in
+            the mock's ``dry_run_report``, to preview its churn first.
+        max_age_days: Fail the replay of a recording made more than this many
+            days ago, so fixtures get re-recorded against the live service
+            now and then. Each real call stores its ``recorded_at`` time.
+        ignore: Selectors of values to leave out of the request and response
+            snapshots entirely (keys included). Ignored request values do not
+            affect matching, and replayed responses lack the ignored values.
+        node_id: Store the recordings under the pytest test with this node
+            id rather than the running one, e.g. ``request.node.nodeid`` in a
+            fixture, or a fixed id for a session-scoped fixture, so they keep
+            stable names whichever test first requests the fixture.
+        scope: ``"module"`` or ``"session"`` to share the recordings with
+            every test of the module or session, as a fixture of that scope
+            does; each test replays them from the first recording on.
+        namespace: Store the recordings in this subdirectory of the snapshot
+            folder, e.g. ``"db"``, apart from the test's assertion snapshots.
+        decode: Build each replayed result from the recorded response's JSON
+            text (its ``bytes`` for a binary recording) rather than returning
+            plain dicts and lists, e.g. ``User.model_validate_json`` to get a
+            Pydantic model back. A factory registered for the recorded type
+            is applied to what it returns. Results the real function returns
+            while recording are returned as they are.
+        encode: Reduce each result to the value snapshotted as its response
+            before it is made JSON-able, e.g. ``lambda r: r.json()`` for an
+            HTTP response object or ``lambda cur: cur.fetchall()`` for a
+            cursor. Values given in ``side_effects`` go through it too. The
+            mock still returns the real result. The response snapshot keeps
+            the type of the result either way, so a factory registered with
+            :func:`register_result_factory` can rebuild it on replay.
 
     Returns:
         Callable: The wrapped function.
+
+    Raises:
+        ValueError: If ``mode`` is not ``"replay"``, ``"spy"`` or
+            ``"dry_run"``.
     """
 
-    test_info = extract_from_pytest_env(snapshot_path, snapshot_name, allow_duplicates)
+    if mode not in MOCK_MODES:
+        raise ValueError(
+            f"Unknown mock mode {mode!r}; expected 'replay', 'spy' or 'dry_run'"
+        )
+    spy = mode == "spy"
+    dry_run = mode == "dry_run"
+    report: List[DryRunChange] = []
+    test_info = extract_from_pytest_env(
+        snapshot_path,
+        snapshot_name,
+        allow_duplicates,
+        node_id=node_id,
+        scope=scope,
+        namespace=namespace,
+    )
     suffix = getattr(func, "__name__", "mocked_fn")
+    effects = iter(side_effects or ())
 
     if inspect.iscoroutinefunction(func):
 
@@ -65,131 +484,168 @@ def mock_json_snapshot(
         async def async_wrapper(*args, **kwargs):
             request = to_jsonable({"args": list(args), "kwargs": kwargs or None})
             name, response_path, do_record = _prepare_mock_call(
-                test_info, suffix, request, record, redactions
+                test_info,
+                suffix,
+                request,
+                record,
+                redactions,
+                encryption_key,
+                ignore=ignore,
+                dry_run=dry_run,
             )
-            if do_record:
+            effect = next(effects, RECORDED)
+            if dry_run:
+                _preview(
+                    test_info,
+                    request,
+                    f"{name}-request",
+                    redactions,
+                    encryption_key,
+                    ignore,
+                    report,
+                )
+                if _is_exception(effect):
+                    raise effect
+                result = await func(*args, **kwargs) if effect is RECORDED else effect
+                _preview(
+                    test_info,
+                    result,
+                    name,
+                    redactions,
+                    encryption_key,
+                    ignore,
+                    report,
+                    encode,
+                )
+                return result
+            if effect is not RECORDED:
+                return _apply_side_effect(
+                    test_info, effect, name, redactions, encryption_key, ignore, encode
+                )
+            if do_record or spy:
+                recorded_at = utc_timestamp() if do_record else None
+                start = time.perf_counter()
                 result = await func(*args, **kwargs)
-                _assert_json_snapshot_named(
-                    test_info, to_jsonable(result), name, redactions
+                duration_ms = (time.perf_counter() - start) * 1000
+                _store_result(
+                    test_info,
+                    result,
+                    name,
+                    redactions,
+                    encryption_key,
+                    duration_ms,
+                    spy,
+                    recorded_at,
+                    ignore,
+                    encode,
                 )
                 return result
-            return _read_json_snapshot(response_path)
+            check_recording_age(response_path, max_age_days)
+            delay = replay_delay(response_path, simulate_latency)
+            if delay:
+                await asyncio.sleep(delay)
+            return _replay(response_path, encryption_key, decode)
 
+        async_wrapper.dry_run_report = report
         return async_wrapper
 
     @functools.wraps(func)
     def wrapper(*args, **kwargs):
         request = to_jsonable({"args": list(args), "kwargs": kwargs or None})
         name, response_path, do_record = _prepare_mock_call(
-            test_info, suffix, request, record, redactions
+            test_info,
+            suffix,
+            request,
+            record,
+            redactions,
+            encryption_key,
+            ignore=ignore,
+            dry_run=dry_run,
         )
-        if do_record:
+        effect = next(effects, RECORDED)
+        if dry_run:
+            _preview(
+                test_info,
+                request,
+                f"{name}-request",
+                redactions,
+                encryption_key,
+                ignore,
+                report,
+            )
+            if _is_exception(effect):
+                raise effect
+            result = func(*args, **kwargs) if effect is RECORDED else effect
+            _preview(
+                test_info,
+                result,
+                name,
+                redactions,
+                encryption_key,
+                ignore,
+                report,
+                encode,
+            )
+            return result
+        if effect is not RECORDED:
+            return _apply_side_effect(
+                test_info, effect, name, redactions, encryption_key, ignore, encode
+            )
+        if do_record or spy:
+            recorded_at = utc_timestamp() if do_record else None
+            start = time.perf_counter()
             result = func(*args, **kwargs)
-            _assert_json_snapshot_named(
-                test_info, to_jsonable(result), name, redactions
+            duration_ms = (time.perf_counter() - start) * 1000
+            _store_result(
+                test_info,
+                result,
+                name,
+                redactions,
+                encryption_key,
+                duration_ms,
+                spy,
+                recorded_at,
+                ignore,
+                encode,
             )
             return result
-        return _read_json_snapshot(response_path)
+        check_recording_age(response_path, max_age_days)
+        delay = replay_delay(response_path, simulate_latency)
+        if delay:
+            time.sleep(delay)
+        return _replay(response_path, encryption_key, decode)
 
+    wrapper.dry_run_report = report
     return wrapper
 
 
-def resolve_function(dotted_path: str):
-    """Resolve a dotted path to a callable.
+def patched_function(patcher: patch_json_snapshot, func: Callable) -> Callable:
+    """Wrap ``func`` so ``patcher`` is active while it runs.
+
+    This is what applying a :class:`patch_json_snapshot` as a decorator
+    returns; async def functions get an async wrapper that keeps the patch
+    active across their awaits.
 
     Args:
-        dotted_path: module.attr style path to the target function.
+        patcher: The patch to apply.
+        func: The function being decorated.
 
     Returns:
-        Callable: The resolved function object.
+        Callable: Wrapped function that applies the patch during execution.
     """
-    module_path, attr_name = dotted_path.rsplit(".", 1)
-    module = importlib.import_module(module_path)
-    return getattr(module, attr_name)
-
-
-class patch_json_snapshot:
-    """Patch a function so calls are snapshot tested.
-
-    Instances of this class can be used as a context manager or decorator to
-    temporarily replace a target function with a snapshotting mock.
-    """
-
-    def __init__(
-        self,
-        dotted_path: str,
-        *,
-        record: bool = False,
-        snapshot_path: Optional[str] = None,
-        snapshot_name: Optional[str] = None,
-        redactions: Optional[Dict[str, Union[str, int, None]]] = None,
-        allow_duplicates: bool = False,
-    ):
-        """Create the patch configuration.
-
-        Args:
-            dotted_path: module.attr style path to patch.
-            record: Whether to always record new snapshots.
-            snapshot_path: Optional path override for storing the snapshot.
-            snapshot_name: Optional name override for the snapshot file.
-            redactions: Mapping of selectors to replacement values.
-            allow_duplicates: Whether to allow duplicate snapshot names.
-        """
-
-        self.dotted_path = dotted_path
-        self.record = record
-        self.snapshot_path = snapshot_path
-        self.snapshot_name = snapshot_name
-        self.redactions = redactions
-        self.allow_duplicates = allow_duplicates
-        self._patcher = None
-
-    def __enter__(self):
-        """Start patching and return the created mock.
-
-        Returns:
-            unittest.mock.MagicMock: The patched mock.
-        """
-        original_fn = resolve_function(self.dotted_path)
-        mocked_fn = mock_json_snapshot(
-            original_fn,
-            record=self.record,
-            snapshot_path=self.snapshot_path,
-            snapshot_name=self.snapshot_name,
-            redactions=self.redactions,
-            allow_duplicates=self.allow_duplicates,
-        )
-        self._patcher = patch(self.dotted_path, side_effect=mocked_fn)
-        self.mock = self._patcher.__enter__()
-        return self.mock
-
-    def __exit__(self, exc_type, exc_val, exc_tb):
-        """Stop patching and clean up."""
 
-        return self._patcher.__exit__(exc_type, exc_val, exc_tb)
-
-    def __call__(self, func: Callable):
-        """Allow use of the object as a decorator.
-
-        Args:
-            func: The function being decorated.
-
-        Returns:
-            Callable: Wrapped function that applies the patch during execution.
-        """
-
-        if inspect.iscoroutinefunction(func):
+    if inspect.iscoroutinefunction(func):
 
-            @functools.wraps(func)
-            async def async_wrapper(*args, **kwargs):
-                with self:
-                    return await func(*args, **kwargs)
+        @functools.wraps(func)
+        async def async_wrapper(*args, **kwargs):
+            with patcher:
+                return await func(*args, **kwargs)
 
-            return async_wrapper
+        return async_wrapper
 
-        @functools.wraps(func)
-        def wrapper(*args, **kwargs):
-            with self:
-                return func(*args, **kwargs)
+    @functools.wraps(func)
+    def wrapper(*args, **kwargs):
+        with patcher:
+            return func(*args, **kwargs)
 
-        return wrapper
+    return wrapper
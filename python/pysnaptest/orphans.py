@@ -0,0 +1,176 @@
+"""Find snapshots whose owning test no longer exists ("orphaned" snapshots).
+
+Unlike :mod:`pysnaptest.unused`, this does not run the test suite. Every
+snapshot header records the file of the test that wrote it
+(``description: "Test File Path: ..."``), and the snapshot's name starts with
+that file's stem and the test's name (``<stem>_<test_name>...``). Walking the
+snapshot directories and cross-referencing both against the test files on disk
+finds snapshots left behind when a test file was deleted or a test was renamed
+or removed. When the project is a git repository, the commit that deleted a
+test file is reported alongside its snapshots.
+
+Snapshots written under a custom ``snapshot_name`` cannot be attributed to a
+test by name; they are only reported when their test file is gone.
+"""
+
+from __future__ import annotations
+
+import ast
+import re
+import subprocess
+from pathlib import Path
+from typing import List, NamedTuple, Optional, Set
+
+from ._pysnaptest import recorded_by as _recorded_by, sanitize_snapshot_name
+from .review import _root
+from .unused import discover_snapshot_dirs, snapshot_files, snapshot_names
+
+_DESCRIPTION_RE = re.compile(
+    r'^description: "?(?:.*; )?Test File Path: (?P<path>.+?)"?$'
+)
+
+
+class OrphanedSnapshot(NamedTuple):
+    """A snapshot whose owning test is gone, and why it is considered so."""
+
+    path: Path
+    reason: str
+
+
+def defined_tests(test_file: Path) -> Set[str]:
+    """Names of the tests defined in ``test_file``.
+
+    Top-level ``test*`` functions are listed by name, and ``test*`` methods of
+    ``Test*`` classes as ``"<class>::<method>"``, matching pytest node ids.
+    """
+
+    try:
+        tree = ast.parse(test_file.read_text(encoding="utf-8"))
+    except (OSError, SyntaxError, UnicodeDecodeError):
+        return set()
+    functions = (ast.FunctionDef, ast.AsyncFunctionDef)
+    tests = set()
+    for node in tree.body:
+        if isinstance(node, functions) and node.name.startswith("test"):
+            tests.add(node.name)
+        elif isinstance(node, ast.ClassDef) and node.name.startswith("Test"):
+            tests.update(
+                f"{node.name}::{item.name}"
+                for item in node.body
+                if isinstance(item, functions) and item.name.startswith("test")
+            )
+    return tests
+
+
+def recorded_test_file(snapshot_path: Path) -> Optional[str]:
+    """The ``Test File Path`` recorded in a snapshot's header, if any."""
+
+    try:
+        with open(snapshot_path, encoding="utf-8") as handle:
+            if handle.readline().rstrip() != "---":
+                return None
+            for line in handle:
+                line = line.rstrip()
+                if line == "---":
+                    break
+                match = _DESCRIPTION_RE.match(line)
+                if match:
+                    return match.group("path")
+    except (OSError, UnicodeDecodeError):
+        return None
+    return None
+
+
+def _locate(test_file: str, snapshot_path: Path, root: Path) -> Optional[Path]:
+    """Find the recorded test file: relative to ``root``, or (for tests run
+    from their own directory) next to the ``snapshots`` directory the
+    snapshot is in, maybe within a namespace."""
+
+    folder = next(
+        (p for p in snapshot_path.parents if p.name == "snapshots"),
+        snapshot_path.parent,
+    )
+    for candidate in (root / test_file, folder.parent / Path(test_file).name):
+        if candidate.is_file():
+            return candidate
+    return None
+
+
+def _owns(name: str, test: str) -> bool:
+    """Whether a snapshot named ``<stem>_<name>`` can belong to ``test``.
+
+    Names are matched in their sanitized form (``Class.method`` for a method)
+    as well as the raw one older snapshots of methods were written under.
+    """
+
+    return any(
+        name == candidate or any(name.startswith(f"{candidate}{sep}") for sep in "_-[")
+        for candidate in {test, sanitize_snapshot_name(test)}
+    )
+
+
+def _deleted_in(test_file: str, root: Path) -> Optional[str]:
+    """The commit (``<short sha> <subject>``) that deleted ``test_file``, if
+    ``root`` is in a git repository that knows."""
+
+    try:
+        result = subprocess.run(
+            ["git", "log", "-1", "--format=%h %s", "--diff-filter=D", "--", test_file],
+            cwd=root,
+            capture_output=True,
+            text=True,
+            check=True,
+        )
+    except (OSError, subprocess.CalledProcessError):
+        return None
+    return result.stdout.strip() or None
+
+
+def find_orphaned_snapshots(root: Optional[str] = None) -> List[OrphanedSnapshot]:
+    """Find snapshots whose owning test no longer exists.
+
+    Args:
+        root: Project root the recorded test file paths are relative to.
+            Defaults to ``INSTA_WORKSPACE_ROOT`` if set, otherwise the current
+            directory.
+
+    Returns:
+        List[OrphanedSnapshot]: Orphaned snapshots, sorted by path.
+    """
+
+    base = _root(root).resolve()
+    orphans: List[OrphanedSnapshot] = []
+    for path in sorted(snapshot_files(discover_snapshot_dirs(base))):
+        test_file = recorded_test_file(path)
+        if test_file is None:
+            continue
+        located = _locate(test_file, path, base)
+        if located is None:
+            reason = f"test file {test_file} no longer exists"
+            commit = _deleted_in(test_file, base)
+            if commit:
+                reason += f" (deleted in {commit})"
+            orphans.append(OrphanedSnapshot(path, reason))
+            continue
+
+        node_id = _recorded_by(path)
+        if node_id is not None:
+            test = node_id.partition("::")[2].split("[", 1)[0]
+            if test not in defined_tests(located):
+                orphans.append(
+                    OrphanedSnapshot(path, f"test {test} no longer exists in {test_file}")
+                )
+            continue
+
+        stem = f"{located.stem}_"
+        scoped = next((n for n in snapshot_names(path) if n.startswith(stem)), None)
+        if scoped is None:
+            continue
+        name = scoped[len(stem) :]
+        if not name.lower().startswith("test"):
+            continue  # a custom snapshot_name
+        if not any(_owns(name, test) for test in defined_tests(located)):
+            orphans.append(
+                OrphanedSnapshot(path, f"no test in {test_file} owns this snapshot")
+            )
+    return orphans
@@ -0,0 +1,108 @@
+"""Named, reusable sets of redactions.
+
+A preset bundles the selectors needed to scrub volatile fields from a familiar
+payload shape (an OpenAI chat completion, an Anthropic message, ...) so tests
+don't each have to rediscover them. Helpers that accept a ``preset`` argument
+merge its redactions under the caller's own ``redactions``, which win on
+conflicts. Projects can add their own presets with :func:`register_preset`.
+"""
+
+from __future__ import annotations
+
+from typing import Dict, Iterable, Optional, Union
+
+# Selectors (or nested field names) mapped to replacement values.
+Redactions = Dict[Union[str, int], Union[str, int, None, "Redactions"]]
+
+_PRESETS: Dict[str, Redactions] = {}
+
+
+def register_preset(name: str, redactions: Redactions) -> None:
+    """Register (or replace) a named redaction preset.
+
+    Args:
+        name: Name used to refer to the preset.
+        redactions: Mapping of selectors to replacement values, as accepted by
+            :func:`pysnaptest.assert_json_snapshot`.
+    """
+
+    _PRESETS[name] = dict(redactions)
+
+
+def get_preset(name: str) -> Redactions:
+    """Return a copy of the redactions registered under ``name``.
+
+    Raises:
+        ValueError: If no preset of that name is registered.
+    """
+
+    try:
+        return dict(_PRESETS[name])
+    except KeyError:
+        available = ", ".join(sorted(_PRESETS))
+        raise ValueError(
+            f"Unknown redaction preset {name!r}; available presets: {available}"
+        ) from None
+
+
+def resolve_redactions(
+    preset: Union[str, Iterable[str], None],
+    redactions: Optional[Redactions] = None,
+) -> Optional[Redactions]:
+    """Merge one or more presets with explicit ``redactions``.
+
+    Later presets override earlier ones, and ``redactions`` overrides them all.
+    """
+
+    names = [preset] if isinstance(preset, str) else list(preset or [])
+    merged: Redactions = {}
+    for name in names:
+        merged.update(get_preset(name))
+    merged.update(redactions or {})
+    return merged or None
+
+
+# insta's `.usage.*` would redact the keys as well, so token fields are listed.
+_OPENAI_USAGE = (
+    "prompt_tokens",
+    "completion_tokens",
+    "total_tokens",
+    "prompt_tokens_details",
+    "completion_tokens_details",
+    "input_tokens",
+    "output_tokens",
+    "input_tokens_details",
+    "output_tokens_details",
+)
+_ANTHROPIC_USAGE = (
+    "input_tokens",
+    "output_tokens",
+    "cache_creation_input_tokens",
+    "cache_read_input_tokens",
+    "cache_creation",
+)
+
+OPENAI_REDACTIONS: Redactions = {
+    ".id": "[id]",
+    ".created": "[created]",
+    ".created_at": "[created]",
+    ".system_fingerprint": "[system_fingerprint]",
+    **{f".usage.{field}": "[tokens]" for field in _OPENAI_USAGE},
+    ".choices[].message.tool_calls[].id": "[tool_call_id]",
+    ".output[].id": "[id]",
+    ".output[].call_id": "[tool_call_id]",
+    ".latency": "[latency]",
+    ".latency_ms": "[latency]",
+}
+
+ANTHROPIC_REDACTIONS: Redactions = {
+    ".id": "[id]",
+    **{f".usage.{field}": "[tokens]" for field in _ANTHROPIC_USAGE},
+    ".content[].id": "[tool_use_id]",
+    ".latency": "[latency]",
+    ".latency_ms": "[latency]",
+}
+
+register_preset("openai", OPENAI_REDACTIONS)
+register_preset("anthropic", ANTHROPIC_REDACTIONS)
+register_preset("llm", {**OPENAI_REDACTIONS, **ANTHROPIC_REDACTIONS})
@@ -8,6 +8,30 @@ snapshot-management flags to ``pytest``:
 * ``--snapshot-new`` — record changed/new snapshots as pending ``*.snap.new``
   files instead (sets ``INSTA_UPDATE=new``); accept them with
   ``pysnaptest accept``.
+* ``--snapshot-soft`` — keep running a test after a snapshot mismatch and
+  fail it at its end with every mismatch at once (``set_fail_fast(False)``).
+* ``--snapshot-force-pass`` — pass mismatching assertions, recording them as
+  pending ``*.snap.new`` files and their diffs in the session summary (sets
+  ``INSTA_FORCE_PASS=1``, and ``INSTA_UPDATE=new`` unless an update mode is
+  chosen), so a nightly job reports the drift of the whole suite in one run.
+* ``--snapshot-update-filter=GLOBS`` — let only the snapshots of tests or
+  names matching the comma-separated globs be written (sets
+  ``PYSNAPTEST_UPDATE_FILTER``), e.g. ``--snapshot-update
+  --snapshot-update-filter='tests/api/*'`` to refresh one subsystem.
+* ``--snapshot-read-only`` — fail any test that would create or rewrite a
+  snapshot file, naming it, and write nothing (``set_read_only()``), so a CI
+  job never mutates the checkout.
+* ``--snapshot-secret-scan`` — refuse to write text/JSON snapshots that look
+  like they contain credentials (sets ``PYSNAPTEST_SECRET_SCAN=1``).
+
+It also exports the session's rootdir as ``PYTEST_ROOTDIR``: test ids are
+relative to it, so snapshots are found even when pytest runs from a
+subdirectory. Redactions listed in the ``[tool.pysnaptest.redactions]`` table
+of the rootdir's ``pyproject.toml`` are applied by every assertion. At the
+end of the session, it lists the snapshots left pending review, by test.
+
+A test run again in the same session, as pytest-rerunfailures does, starts
+over from its first snapshot name instead of moving on to ``<name>-2``.
 
 insta does the actual work (diffing, writing, format); this plugin only selects
 the update mode. The environment variable is set in :func:`pytest_configure`,
@@ -18,10 +42,21 @@ per workspace on first read, so setting it any later would have no effect.
 from __future__ import annotations
 
 import os
-from typing import TYPE_CHECKING
+import warnings
+from pathlib import Path
+from typing import Any, Dict, Generator, Optional
+
+import pytest
 
-if TYPE_CHECKING:
-    import pytest
+from ._pysnaptest import (
+    observe_test_stage,
+    pending_summary,
+    set_config_redactions,
+    set_fail_fast,
+    set_read_only,
+    settle_repetitions,
+)
+from .soft import raise_soft_failures
 
 
 def pytest_addoption(parser: "pytest.Parser") -> None:
@@ -40,6 +75,36 @@ def pytest_addoption(parser: "pytest.Parser") -> None:
         default=False,
         help="Record changed snapshots as pending *.snap.new files (INSTA_UPDATE=new).",
     )
+    group.addoption(
+        "--snapshot-soft",
+        action="store_true",
+        default=False,
+        help="Report every snapshot mismatch of a test at its end instead of failing fast.",
+    )
+    group.addoption(
+        "--snapshot-force-pass",
+        action="store_true",
+        default=False,
+        help="Pass mismatching snapshots, reporting their diffs at the end (INSTA_FORCE_PASS=1).",
+    )
+    group.addoption(
+        "--snapshot-update-filter",
+        default=None,
+        metavar="GLOBS",
+        help="Only write the snapshots of tests or names matching these comma-separated globs.",
+    )
+    group.addoption(
+        "--snapshot-read-only",
+        action="store_true",
+        default=False,
+        help="Fail tests that would create or rewrite a snapshot file, writing nothing.",
+    )
+    group.addoption(
+        "--snapshot-secret-scan",
+        action="store_true",
+        default=False,
+        help="Fail snapshots that appear to contain credentials (PYSNAPTEST_SECRET_SCAN=1).",
+    )
 
 
 def pytest_configure(config: "pytest.Config") -> None:
@@ -47,12 +112,128 @@ def pytest_configure(config: "pytest.Config") -> None:
 
     ``--snapshot-update`` takes precedence over ``--snapshot-new`` if both are
     given. An ``INSTA_UPDATE`` value already present in the environment is left
-    untouched so explicit user configuration wins.
+    untouched so explicit user configuration wins, as are ``INSTA_FORCE_PASS``
+    and ``PYSNAPTEST_UPDATE_FILTER``.
+    ``--snapshot-force-pass`` selects ``INSTA_UPDATE=new`` unless another mode
+    is, so pending files are written on CI too. ``--snapshot-secret-scan``,
+    ``--snapshot-soft`` and ``--snapshot-read-only`` are independent of the
+    update mode; read-only wins over any of them that would write.
     """
 
+    os.environ.setdefault("PYTEST_ROOTDIR", str(config.rootpath))
+    set_config_redactions(load_config_redactions(config.rootpath))
+    if config.getoption("--snapshot-secret-scan"):
+        os.environ["PYSNAPTEST_SECRET_SCAN"] = "1"
+    if config.getoption("--snapshot-soft"):
+        set_fail_fast(False)
+    if config.getoption("--snapshot-read-only"):
+        set_read_only(True)
+    update_filter = config.getoption("--snapshot-update-filter")
+    if update_filter and not os.environ.get("PYSNAPTEST_UPDATE_FILTER"):
+        os.environ["PYSNAPTEST_UPDATE_FILTER"] = update_filter
+    force_pass = config.getoption("--snapshot-force-pass")
+    if force_pass and not os.environ.get("INSTA_FORCE_PASS"):
+        os.environ["INSTA_FORCE_PASS"] = "1"
     if os.environ.get("INSTA_UPDATE"):
         return
     if config.getoption("--snapshot-update"):
         os.environ["INSTA_UPDATE"] = "always"
-    elif config.getoption("--snapshot-new"):
+    elif config.getoption("--snapshot-new") or force_pass:
         os.environ["INSTA_UPDATE"] = "new"
+
+
+@pytest.hookimpl(tryfirst=True)
+def pytest_runtest_setup(item: "pytest.Item") -> None:
+    """Restart the snapshot numbering of a test that is run again."""
+
+    observe_test_stage(item.nodeid, "setup")
+
+
+@pytest.hookimpl(wrapper=True)
+def pytest_runtest_call(item: "pytest.Item") -> Generator[None, Any, Any]:
+    """Fail a test with the snapshot mismatches collected while it ran, and
+    store the repetitions its ``allow_duplicates`` snapshots counted."""
+
+    try:
+        result = yield
+    except BaseException as error:
+        settle_repetitions(item.nodeid, passed=False)
+        raise_soft_failures(cause=error)
+        raise
+    settle_repetitions(item.nodeid)
+    raise_soft_failures()
+    return result
+
+
+def pytest_terminal_summary(terminalreporter: Any, config: "pytest.Config") -> None:
+    """Print the snapshots the session left pending review, by test, and the
+    diffs of those whose assertions were force-passed."""
+
+    summary = pending_summary()
+    if not summary["total"]:
+        return
+    terminalreporter.write_sep("=", "pysnaptest")
+    terminalreporter.write_line(
+        f"{summary['total']} snapshot(s) pending review, run `pysnaptest review`:",
+        yellow=True,
+    )
+    for test, pending in summary["tests"].items():
+        terminalreporter.write_line(f"  {test or '<outside a test>'} ({pending['count']})")
+        for path in pending["paths"]:
+            terminalreporter.write_line(f"    {_relative(path, config.rootpath)}")
+    force_passed = summary["force_passed"]
+    if not force_passed:
+        return
+    terminalreporter.write_sep("-", "snapshot drift")
+    terminalreporter.write_line(
+        f"{len(force_passed)} mismatching snapshot(s) passed with INSTA_FORCE_PASS=1:"
+    )
+    for path, diff in force_passed.items():
+        terminalreporter.write_line(f"  {_relative(path, config.rootpath)}")
+        for line in (diff or "(new or binary snapshot, no diff)").splitlines():
+            terminalreporter.write_line(f"    {line}")
+
+
+def _relative(path: Path, root: Path) -> Path:
+    try:
+        return path.relative_to(root)
+    except ValueError:
+        return path
+
+
+def load_config_redactions(rootdir: Path) -> Optional[Dict[str, Any]]:
+    """Read the ``[tool.pysnaptest.redactions]`` table of ``pyproject.toml``.
+
+    Its keys are selectors or nested tables, as accepted by the
+    ``redactions`` argument of the assertions, e.g.::
+
+        [tool.pysnaptest.redactions]
+        ".created_at" = "[timestamp]"
+        user = { id = "[id]" }
+
+    Args:
+        rootdir: Directory containing ``pyproject.toml``.
+
+    Returns:
+        The table, or ``None`` when there is none.
+    """
+
+    pyproject = Path(rootdir) / "pyproject.toml"
+    if not pyproject.is_file():
+        return None
+    try:
+        import tomllib
+    except ImportError:  # Python < 3.11
+        try:
+            import tomli as tomllib  # type: ignore[no-redef]
+        except ImportError:
+            if "[tool.pysnaptest" in pyproject.read_text(encoding="utf-8"):
+                warnings.warn(
+                    "pysnaptest: install tomli to apply the redactions of "
+                    f"{pyproject} on Python < 3.11",
+                    stacklevel=2,
+                )
+            return None
+    with pyproject.open("rb") as f:
+        tool = tomllib.load(f).get("tool", {}).get("pysnaptest", {})
+    return tool.get("redactions")
@@ -0,0 +1,139 @@
+"""Pin property-based-testing counterexamples as regression snapshots.
+
+When Hypothesis (or any seeded generator) finds and shrinks a failing example,
+:func:`assert_snapshot_for_seed` snapshots the behaviour for that seed or
+example id under its own name, ``<test>_seed-<label>``, next to the test's
+other snapshots. The snapshot then keeps guarding the fix long after the
+property test has moved on to other examples.
+
+:func:`list_seed_snapshots` finds these snapshots again, and
+:func:`prune_seed_snapshots` deletes those of seeds no longer worth keeping.
+"""
+
+from __future__ import annotations
+
+import re
+from pathlib import Path
+from typing import Any, Dict, Iterable, List, NamedTuple, Optional, Tuple, Union
+
+from ._pysnaptest import (
+    assert_snapshot_for_seed as _assert_snapshot_for_seed,
+    sanitize_snapshot_name,
+    seed_label,
+)
+from .assertion import extract_from_pytest_env
+from .encoders import to_jsonable
+from .review import _root
+from .unused import (
+    delete_snapshot,
+    discover_snapshot_dirs,
+    is_mock_snapshot,
+    snapshot_files,
+    snapshot_names,
+)
+
+_SEED_RE = re.compile(r"^(?P<name>.+)_seed-(?P<seed>[A-Za-z0-9_.]+)(?:-\d+)?$")
+
+
+class SeedSnapshot(NamedTuple):
+    """A snapshot pinned to one seed."""
+
+    path: Path
+    name: str
+    """The snapshot name it is scoped to, ``<test file stem>_<test>``."""
+    seed: str
+    """The seed's label, see :func:`pysnaptest.seeds.seed_label`."""
+
+
+def assert_snapshot_for_seed(
+    seed: Any,
+    result: Any,
+    snapshot_path: Optional[str] = None,
+    snapshot_name: Optional[str] = None,
+    redactions: Optional[Dict[str, Union[str, int, None]]] = None,
+) -> None:
+    """Assert that a value matches the snapshot stored for ``seed``.
+
+    Args:
+        seed: The seed or example id, e.g. a Hypothesis seed. Its ``str()`` is
+            used in the snapshot name, with characters other than ASCII
+            letters, digits, ``_`` and ``.`` replaced by ``_``.
+        result: Object that will be serialized to JSON, normalized with
+            :func:`pysnaptest.to_jsonable` first.
+        snapshot_path: Optional path override for storing the snapshot.
+        snapshot_name: Optional name override for the snapshot file; the seed
+            is appended to it.
+        redactions: Mapping of selectors to replacement values.
+    """
+
+    test_info = extract_from_pytest_env(snapshot_path, snapshot_name)
+    _assert_snapshot_for_seed(test_info, seed, to_jsonable(result), redactions)
+
+
+def _test_scope(test_node_id: str, root: Path) -> Tuple[Path, str]:
+    """The snapshot directory and snapshot name of a test's seed snapshots."""
+
+    test_file, _, test_name = test_node_id.partition("::")
+    directory = (root / test_file).parent.resolve() / "snapshots"
+    name = f"{Path(test_file).stem}_{test_name.split('[', 1)[0]}"
+    return directory, sanitize_snapshot_name(name)
+
+
+def list_seed_snapshots(
+    test_node_id: Optional[str] = None, root: Optional[Union[str, Path]] = None
+) -> List[SeedSnapshot]:
+    """Find the snapshots written by :func:`assert_snapshot_for_seed`.
+
+    Args:
+        test_node_id: Only list those of this test, given as a pytest node id
+            such as ``"tests/test_parser.py::test_roundtrip"``.
+        root: Directory whose ``snapshots`` directories are searched, and that
+            ``test_node_id`` is relative to (defaults to
+            ``$INSTA_WORKSPACE_ROOT`` or the current directory).
+
+    Returns:
+        List[SeedSnapshot]: The snapshots, sorted by path.
+    """
+
+    base = _root(None if root is None else str(root)).resolve()
+    scope = _test_scope(test_node_id, base) if test_node_id else None
+    found = []
+    for path in sorted(snapshot_files(discover_snapshot_dirs(base))):
+        if is_mock_snapshot(path):
+            continue
+        seed_match = next(
+            filter(None, (_SEED_RE.match(name) for name in snapshot_names(path))), None
+        )
+        if seed_match is None:
+            continue
+        name = seed_match.group("name")
+        # A namespace puts snapshots in a subdirectory of the test's folder.
+        if scope is not None and (name != scope[1] or scope[0] not in path.parents):
+            continue
+        found.append(SeedSnapshot(path, name, seed_match.group("seed")))
+    return found
+
+
+def prune_seed_snapshots(
+    keep: Iterable[Any],
+    test_node_id: Optional[str] = None,
+    root: Optional[Union[str, Path]] = None,
+) -> List[Path]:
+    """Delete seed snapshots except those of the seeds in ``keep``.
+
+    Args:
+        keep: Seeds (or example ids) whose snapshots are kept.
+        test_node_id: Only prune those of this test; see
+            :func:`list_seed_snapshots`.
+        root: See :func:`list_seed_snapshots`.
+
+    Returns:
+        List[Path]: The deleted files, sorted.
+    """
+
+    kept = {seed_label(seed) for seed in keep}
+    deleted: List[Path] = []
+    for snapshot in list_seed_snapshots(test_node_id, root):
+        if snapshot.seed not in kept:
+            deleted.extend(delete_snapshot(snapshot.path))
+    return sorted(deleted)
@@ -0,0 +1,47 @@
+"""Soft snapshot assertions: every mismatch of a test reported at once.
+
+With fail-fast off (``set_fail_fast(False)``, or ``pytest --snapshot-soft``),
+a mismatching snapshot assertion does not raise; its
+:class:`SnapshotMismatchError` is collected and the test goes on, so a test
+asserting many labeled snapshots reports all of their diffs in one run.
+:func:`raise_soft_failures` raises the collected errors as one
+:class:`SoftSnapshotError`; the pytest plugin calls it when each test ends.
+"""
+
+from __future__ import annotations
+
+from typing import List, Optional
+
+from ._pysnaptest import SnapshotMismatchError, take_soft_failures
+
+
+class SoftSnapshotError(AssertionError):
+    """Several snapshots of a test did not match.
+
+    Attributes:
+        failures: The :class:`SnapshotMismatchError` of each mismatch, in
+            assertion order.
+    """
+
+    def __init__(self, failures: List[SnapshotMismatchError]):
+        self.failures = failures
+        messages = "\n\n".join(
+            f"{index}. {failure}" for index, failure in enumerate(failures, 1)
+        )
+        super().__init__(f"{len(failures)} snapshot(s) did not match:\n\n{messages}")
+
+
+def raise_soft_failures(cause: Optional[BaseException] = None) -> None:
+    """Raise the snapshot mismatches collected since the last call, if any.
+
+    Args:
+        cause: An exception that ended the test early, set as the raised
+            error's ``__cause__``.
+
+    Raises:
+        SoftSnapshotError: If any mismatch was collected.
+    """
+
+    failures = take_soft_failures()
+    if failures:
+        raise SoftSnapshotError(failures) from cause
@@ -0,0 +1,501 @@
+"""Regenerate the type stub of the compiled ``pysnaptest._pysnaptest`` module.
+
+pyo3 records the signature of every function, method and constructor in its
+``__text_signature__``, which :func:`inspect.signature` reads, so the
+parameters and exports of the stub come from the built extension itself. What
+the extension cannot tell -- annotations, docstrings, class attributes and
+special methods Python does not list -- is kept from the current
+``_pysnaptest.pyi``: regenerating it after changing a binding adds, removes
+and reorders parameters and exports while keeping the types written for them.
+A new parameter takes the annotation its name has elsewhere in the stub, or
+``Any``, and a new export the first paragraph of its Rust doc comment.
+
+Run ``python -m pysnaptest.stubgen`` after building to rewrite the stub, or
+``python -m pysnaptest.stubgen --check`` to exit with status 1 when it is out
+of date.
+"""
+
+from __future__ import annotations
+
+import argparse
+import ast
+import inspect
+import re
+import sys
+import textwrap
+from collections import Counter
+from pathlib import Path
+from types import ModuleType
+from typing import Any, Dict, List, Optional, Sequence, Tuple
+
+from . import _pysnaptest
+
+#: The stub shipped next to the extension module.
+STUB_PATH = Path(__file__).with_name("_pysnaptest.pyi")
+
+#: Lines longer than this are wrapped the way ``ruff format`` wraps them.
+LINE_LENGTH = 88
+
+#: The start of a stub that does not exist yet.
+_DEFAULT_PREAMBLE = (
+    '"""Type stubs for the compiled ``pysnaptest._pysnaptest`` extension module."""'
+    "\n\nfrom typing import Any\n"
+)
+
+#: Members every class has, left out unless the stub declares them.
+_IMPLICIT_MEMBERS = frozenset(
+    {
+        "__doc__",
+        "__module__",
+        "__dict__",
+        "__weakref__",
+        "__new__",
+        "__init__",
+        "__repr__",
+        "__str__",
+        "__hash__",
+        "__eq__",
+        "__ne__",
+        "__lt__",
+        "__le__",
+        "__gt__",
+        "__ge__",
+    }
+)
+
+_Annotations = Dict[Tuple[str, bool], str]
+
+
+def render_stub(module: ModuleType = _pysnaptest, stub: str = "") -> str:
+    """Render the stub of ``module``, keeping the annotations of ``stub``.
+
+    Args:
+        module: The extension module to describe.
+        stub: The current text of its stub, or ``""`` to start afresh.
+
+    Returns:
+        str: The stub declaring every export of ``module`` with its runtime
+        signature: constants first, then the classes and functions in the
+        order of ``stub``, then new ones in alphabetical order.
+    """
+
+    tree = ast.parse(stub)
+    exports = {
+        name: getattr(module, name)
+        for name in dir(module)
+        if not name.startswith("__")
+    }
+    nodes: Dict[str, ast.stmt] = {}
+    preamble_end: Optional[int] = None
+    for node in tree.body:
+        name = _declared_name(node)
+        if name is not None and name in exports:
+            nodes.setdefault(name, node)
+            if preamble_end is None:
+                preamble_end = node.lineno - 1
+    lines = stub.splitlines()
+    preamble = "\n".join(lines[:preamble_end]).rstrip() if nodes else stub.rstrip()
+    preamble = preamble or _DEFAULT_PREAMBLE.rstrip()
+
+    order = list(nodes) + sorted(name for name in exports if name not in nodes)
+    constants = [name for name in order if _is_constant(exports[name])]
+    annotations = _known_annotations(tree, stub)
+    parts = [preamble + "\n"]
+    if constants:
+        parts.append(
+            "".join(
+                _render_constant(name, exports[name], nodes.get(name), stub)
+                for name in constants
+            )
+        )
+    for name in order:
+        obj, node = exports[name], nodes.get(name)
+        if isinstance(obj, type):
+            parts.append(_render_class(name, obj, node, stub, annotations))
+        elif not _is_constant(obj):
+            parts.append(_render_function(name, obj, node, stub, annotations, ""))
+    return "\n".join(parts)
+
+
+def _declared_name(node: ast.stmt) -> Optional[str]:
+    """The name a top-level statement of a stub declares, if any."""
+
+    if isinstance(node, (ast.FunctionDef, ast.ClassDef)):
+        return node.name
+    if isinstance(node, ast.AnnAssign) and isinstance(node.target, ast.Name):
+        return node.target.id
+    return None
+
+
+def _is_constant(obj: Any) -> bool:
+    return not callable(obj)
+
+
+def _segment(stub: str, node: ast.AST) -> str:
+    """The source text of ``node`` in ``stub``, on one line."""
+
+    text = ast.get_source_segment(stub, node) or ""
+    text = re.sub(r",\s*\n\s*", ", ", text)
+    text = re.sub(r"\s*\n\s*", "", text)
+    return re.sub(r", ([\])])", r"\1", text)
+
+
+def _known_annotations(tree: ast.Module, stub: str) -> _Annotations:
+    """The most common annotation of each parameter name in ``stub``, keyed
+    by the name and whether the parameter has a default."""
+
+    counts: Dict[Tuple[str, bool], Counter] = {}
+    for node in ast.walk(tree):
+        if not isinstance(node, ast.FunctionDef):
+            continue
+        for arg, has_default in _arguments(node.args):
+            if arg.annotation is not None:
+                key = (arg.arg, has_default)
+                counts.setdefault(key, Counter())[_segment(stub, arg.annotation)] += 1
+    return {key: count.most_common(1)[0][0] for key, count in counts.items()}
+
+
+def _arguments(args: ast.arguments) -> List[Tuple[ast.arg, bool]]:
+    """The named parameters of a stub function and whether each has a
+    default."""
+
+    positional = args.posonlyargs + args.args
+    first_default = len(positional) - len(args.defaults)
+    named = [(arg, index >= first_default) for index, arg in enumerate(positional)]
+    named += [
+        (arg, default is not None)
+        for arg, default in zip(args.kwonlyargs, args.kw_defaults)
+    ]
+    return named
+
+
+def _docstring(stub: str, node: Optional[ast.AST]) -> Optional[str]:
+    """The docstring literal of a stub class or function, as written."""
+
+    body = getattr(node, "body", None)
+    if (
+        body
+        and isinstance(body[0], ast.Expr)
+        and isinstance(body[0].value, ast.Constant)
+        and isinstance(body[0].value.value, str)
+    ):
+        return ast.get_source_segment(stub, body[0])
+    return None
+
+
+def _runtime_docstring(doc: Optional[str], indent: str) -> Optional[str]:
+    """A docstring literal holding the first paragraph of a Rust doc comment,
+    its Markdown code spans turned into reStructuredText ones."""
+
+    if not doc or not doc.strip():
+        return None
+    paragraph = " ".join(inspect.cleandoc(doc).split("\n\n")[0].split())
+    paragraph = re.sub(r"\[(`[^`]+`)\](?!\()", r"\1", paragraph)
+    paragraph = re.sub(r"(?<!`)`([^`]+)`(?!`)", r"``\1``", paragraph)
+    lines = textwrap.wrap(f'"""{paragraph}"""', width=LINE_LENGTH - len(indent))
+    return f"\n{indent}".join(lines)
+
+
+def _render_constant(name: str, value: Any, node: Optional[ast.stmt], stub: str) -> str:
+    if isinstance(node, ast.AnnAssign):
+        return f"{name}: {_segment(stub, node.annotation)}\n"
+    return f"{name}: {type(value).__name__}\n"
+
+
+def _render_class(
+    name: str,
+    cls: type,
+    node: Optional[ast.stmt],
+    stub: str,
+    annotations: _Annotations,
+) -> str:
+    """Render a class: its runtime bases, constructor and methods, and the
+    docstring, attributes and annotations of its declaration in ``stub``."""
+
+    bases = [base.__name__ for base in cls.__bases__ if base is not object]
+    header = f"class {name}({', '.join(bases)}):" if bases else f"class {name}:"
+    doc = _docstring(stub, node) or _runtime_docstring(
+        cls.__dict__.get("__doc__"), "    "
+    )
+    declared: Dict[str, ast.stmt] = {}
+    attributes: List[str] = []
+    for statement in getattr(node, "body", []):
+        if isinstance(statement, ast.FunctionDef):
+            declared.setdefault(statement.name, statement)
+        elif isinstance(statement, ast.AnnAssign):
+            attributes.append(f"    {_segment(stub, statement)}")
+
+    members: List[str] = []
+    if getattr(cls, "__text_signature__", None):
+        members.append(
+            _render_function(
+                "__init__", cls, declared.get("__init__"), stub, annotations, "    "
+            )
+        )
+    runtime = {
+        member: value
+        for member, value in cls.__dict__.items()
+        if member not in _IMPLICIT_MEMBERS
+        and (callable(value) or isinstance(value, staticmethod) or _is_property(value))
+    }
+    for member, statement in declared.items():
+        if member in runtime:
+            members.append(
+                _render_member(member, runtime[member], statement, stub, annotations)
+            )
+        elif member != "__init__" and member.startswith("__"):
+            # A protocol method Python exposes only on some versions, like
+            # ``__buffer__``, stays as written.
+            members.append(_render_written(statement, stub))
+    for member, value in runtime.items():
+        if member not in declared:
+            members.append(_render_member(member, value, None, stub, annotations))
+
+    if not (doc or attributes or members):
+        return f"{header} ...\n"
+    blocks = [header]
+    if doc:
+        blocks.append(f"    {doc}")
+    if attributes:
+        if doc:
+            blocks.append("")
+        blocks.extend(attributes)
+    if members and (doc or attributes):
+        blocks.append("")
+    for index, member in enumerate(members):
+        last = index == len(members) - 1
+        # Members with a docstring are followed by a blank line.
+        one_line = member.rstrip("\n").splitlines()[-1].strip() != "..."
+        blocks.append(member.rstrip("\n") if last or one_line else member)
+    return "\n".join(blocks) + "\n"
+
+
+def _is_property(value: Any) -> bool:
+    return inspect.isgetsetdescriptor(value) or isinstance(value, property)
+
+
+def _render_member(
+    name: str,
+    value: Any,
+    node: Optional[ast.stmt],
+    stub: str,
+    annotations: _Annotations,
+) -> str:
+    if _is_property(value):
+        return _render_function(
+            name, value, node, stub, annotations, "    ", decorator="property"
+        )
+    decorator = "staticmethod" if isinstance(value, staticmethod) else None
+    try:
+        signature = _runtime_signature(value.__func__ if decorator else value)
+    except (TypeError, ValueError):
+        signature = None
+    if node is not None and (signature is None or _forwards_anything(signature)):
+        # ``__call__`` and the like take ``*args, **kwargs`` in Rust; the
+        # stub's narrower signature is the one worth completing.
+        return _render_written(node, stub)
+    if signature is None:
+        return ""
+    return _render_function(
+        name,
+        value.__func__ if decorator else value,
+        node,
+        stub,
+        annotations,
+        "    ",
+        decorator=decorator,
+        method=decorator is None,
+    )
+
+
+def _forwards_anything(signature: inspect.Signature) -> bool:
+    """Whether a method takes nothing but ``*args`` and ``**kwargs``."""
+
+    kinds = [param.kind for param in signature.parameters.values()][-2:]
+    return len(signature.parameters) <= 3 and kinds == [
+        inspect.Parameter.VAR_POSITIONAL,
+        inspect.Parameter.VAR_KEYWORD,
+    ]
+
+
+def _runtime_signature(obj: Any) -> inspect.Signature:
+    """The signature pyo3 recorded for ``obj``; ``golden`` instances carry a
+    ``__wrapped__`` that must not be followed."""
+
+    return inspect.signature(obj, follow_wrapped=False)
+
+
+def _render_written(node: ast.stmt, stub: str) -> str:
+    """A class member exactly as written in ``stub``."""
+
+    lines = stub.splitlines()
+    start = min([node.lineno] + [d.lineno for d in getattr(node, "decorator_list", [])])
+    text = "\n".join(lines[start - 1 : node.end_lineno])
+    return text + "\n"
+
+
+def _render_function(
+    name: str,
+    obj: Any,
+    node: Optional[ast.stmt],
+    stub: str,
+    annotations: _Annotations,
+    indent: str,
+    decorator: Optional[str] = None,
+    method: bool = False,
+) -> str:
+    """Render a function or method with the parameters of its runtime
+    signature and the annotations and docstring of ``node``."""
+
+    written = {}
+    returns = None
+    if isinstance(node, ast.FunctionDef):
+        for arg, _ in _arguments(node.args):
+            if arg.annotation is not None:
+                written[arg.arg] = _segment(stub, arg.annotation)
+        for key, arg in (("*", node.args.vararg), ("**", node.args.kwarg)):
+            if arg is not None and arg.annotation is not None:
+                written[key] = _segment(stub, arg.annotation)
+        if node.returns is not None:
+            returns = _segment(stub, node.returns)
+
+    if decorator == "property":
+        params = ["self"]
+    else:
+        params = _parameters(
+            _runtime_signature(obj),
+            written,
+            annotations,
+            name == "__init__" or method,
+        )
+    if returns is None:
+        returns = "None" if name == "__init__" else "Any"
+    doc = _docstring(stub, node)
+    if doc is None and node is None and not name.startswith("__"):
+        doc = _runtime_docstring(getattr(obj, "__doc__", None), indent + "    ")
+
+    lines = [f"{indent}@{decorator}"] if decorator else []
+    lines.append(_signature(indent, name, params, returns, doc is None))
+    if doc is None:
+        return "\n".join(lines) + "\n"
+    body = indent + "    "
+    return "\n".join(lines + [f"{body}{doc}", f"{body}..."]) + "\n"
+
+
+def _parameters(
+    signature: inspect.Signature,
+    written: Dict[str, str],
+    annotations: _Annotations,
+    takes_self: bool,
+) -> List[str]:
+    """The parameters of a stub signature, with ``/`` and ``*`` markers."""
+
+    params = list(signature.parameters.values())
+    if takes_self and not (params and params[0].name == "self"):
+        params.insert(0, inspect.Parameter("self", inspect.Parameter.POSITIONAL_ONLY))
+    positional_only = [p.name for p in params if p.kind is p.POSITIONAL_ONLY]
+    if positional_only == ["self"] and takes_self:
+        positional_only = []
+    rendered: List[str] = []
+    star = False
+    for index, param in enumerate(params):
+        has_default = param.default is not param.empty
+        variadic = {param.VAR_POSITIONAL: "*", param.VAR_KEYWORD: "**"}
+        annotation = (
+            written.get(param.name)
+            or written.get(variadic.get(param.kind, ""))
+            or annotations.get((param.name, has_default))
+            or "Any"
+        )
+        if index == 0 and takes_self:
+            text = "self"
+        elif param.kind is param.VAR_POSITIONAL:
+            text, star = f"*{param.name}: {annotation}", True
+        elif param.kind is param.VAR_KEYWORD:
+            text = f"**{param.name}: {annotation}"
+        else:
+            if param.kind is param.KEYWORD_ONLY and not star:
+                rendered.append("*")
+                star = True
+            text = f"{param.name}: {annotation}" + (" = ..." if has_default else "")
+        rendered.append(text)
+        if positional_only and param.name == positional_only[-1]:
+            rendered.append("/")
+    return rendered
+
+
+def _signature(
+    indent: str, name: str, params: List[str], returns: str, one_line: bool
+) -> str:
+    """The ``def`` line of a stub function, split like ``ruff format`` does:
+    on one line, else with the parameters on a line of their own, else one
+    parameter per line."""
+
+    head = f"{indent}def {name}("
+    tail = f") -> {returns}:" + (" ..." if one_line else "")
+    line = head + ", ".join(params) + tail
+    if len(line) <= LINE_LENGTH:
+        return line
+    inner = indent + "    "
+    hugged = inner + ", ".join(params)
+    if len(hugged) <= LINE_LENGTH:
+        return f"{head}\n{hugged}\n{indent}{tail}"
+    exploded = [_split_parameter(inner, f"{param},") for param in params]
+    return "\n".join([head] + exploded + [indent + tail])
+
+
+def _split_parameter(indent: str, param: str) -> str:
+    """A parameter on its own line, its subscripted annotation split over
+    three lines when it is too long for one."""
+
+    match = re.match(r"^(\**\w+: [\w.]+)\[(.*)\](.*)$", param)
+    if len(indent + param) <= LINE_LENGTH or match is None:
+        return indent + param
+    head, inner, rest = match.groups()
+    return f"{indent}{head}[\n{indent}    {inner}\n{indent}]{rest}"
+
+
+def main(argv: Optional[Sequence[str]] = None) -> int:
+    """Rewrite the stub, or with ``--check`` report whether it is current.
+
+    Args:
+        argv: Command-line arguments; defaults to ``sys.argv[1:]``.
+
+    Returns:
+        int: ``1`` when ``--check`` finds the stub out of date, else ``0``.
+    """
+
+    parser = argparse.ArgumentParser(
+        prog="python -m pysnaptest.stubgen",
+        description="Regenerate the type stub of the pysnaptest extension module.",
+    )
+    parser.add_argument(
+        "--check",
+        action="store_true",
+        help="Exit with status 1 instead of writing when the stub is out of date.",
+    )
+    parser.add_argument(
+        "--stub",
+        type=Path,
+        default=STUB_PATH,
+        help="The stub to regenerate (defaults to the installed _pysnaptest.pyi).",
+    )
+    args = parser.parse_args(argv)
+
+    current = args.stub.read_text() if args.stub.exists() else ""
+    generated = render_stub(_pysnaptest, current)
+    if generated == current:
+        return 0
+    if args.check:
+        print(
+            f"{args.stub} is out of date; run python -m pysnaptest.stubgen",
+            file=sys.stderr,
+        )
+        return 1
+    args.stub.write_text(generated)
+    print(f"Wrote {args.stub}")
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,90 @@
+"""Snapshot assertions for ``unittest`` test cases.
+
+pysnaptest's assertion functions name snapshots after the pytest test found in
+``PYTEST_CURRENT_TEST``, which ``unittest`` does not set. Mix
+:class:`SnapshotTestCaseMixin` into a :class:`unittest.TestCase` to assert
+snapshots named after the running test method instead:
+
+.. code-block:: python
+
+    class TestApi(SnapshotTestCaseMixin, unittest.TestCase):
+        def test_get(self):
+            self.assertJsonSnapshot(api.get("/users"))
+
+Snapshots are stored in ``snapshots/`` next to the test module and named
+``<module file stem>_<class>.<method>``, the names pytest gives the same
+tests, so a suite can move between the two runners. Numbering of repeated
+assertions restarts with every run of a test method.
+"""
+
+from __future__ import annotations
+
+import inspect
+from typing import Any, Callable, Dict, List, Optional, Union
+
+from ._pysnaptest import (
+    SnapshotInfo,
+    assert_json_snapshot as _assert_json_snapshot,
+    assert_snapshot as _assert_snapshot,
+)
+from .encoders import to_jsonable
+
+
+class SnapshotTestCaseMixin:
+    """Adds snapshot assertions to a :class:`unittest.TestCase`."""
+
+    #: Prefix strategy of the test case's snapshot file names; see
+    #: :func:`pysnaptest.assertion.extract_from_pytest_env`.
+    snapshot_prefix: Optional[str] = None
+
+    _testMethodName: str
+
+    def setUp(self) -> None:
+        super().setUp()  # type: ignore[misc]
+        self.snapshot_info().reset_counter()
+
+    def snapshot_info(self, allow_duplicates: bool = False) -> SnapshotInfo:
+        """The :class:`SnapshotInfo` of the running test method, e.g. for
+        :func:`pysnaptest.golden` or :func:`pysnaptest.characterize`."""
+
+        cls = type(self)
+        return SnapshotInfo.from_unittest(
+            inspect.getfile(cls),
+            cls.__qualname__,
+            self._testMethodName,
+            allow_duplicates,
+            self.snapshot_prefix,
+        )
+
+    def assertJsonSnapshot(
+        self,
+        result: Any,
+        redactions: Optional[Dict[str, Union[str, int, None]]] = None,
+        allow_duplicates: bool = False,
+        custom_encoder: Optional[Dict[type, Callable[[Any], Any]]] = None,
+        encryption_key: Optional[str] = None,
+        sort_arrays_by: Optional[Dict[str, str]] = None,
+        ignore: Optional[List[str]] = None,
+    ) -> None:
+        """Assert that a value matches the test method's JSON snapshot; see
+        :func:`pysnaptest.assert_json_snapshot` for the arguments."""
+
+        _assert_json_snapshot(
+            self.snapshot_info(allow_duplicates),
+            to_jsonable(result, custom_encoder=custom_encoder),
+            redactions,
+            encryption_key,
+            sort_arrays_by,
+            ignore,
+        )
+
+    def assertSnapshot(
+        self,
+        result: Any,
+        allow_duplicates: bool = False,
+        encryption_key: Optional[str] = None,
+    ) -> None:
+        """Assert that ``str(result)`` matches the test method's text
+        snapshot; see :func:`pysnaptest.assert_snapshot`."""
+
+        _assert_snapshot(self.snapshot_info(allow_duplicates), result, encryption_key)
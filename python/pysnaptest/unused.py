@@ -25,18 +25,47 @@ import os
 import subprocess
 import sys
 import tempfile
-import re
 from pathlib import Path
 from typing import Dict, Iterable, List, Optional, Sequence, Set
 
 from ._pysnaptest import delete_snapshot as _delete_snapshot
 from .review import SNAPSHOT_GLOB, SNAPSHOT_SUFFIX, _root
 
-#: Splits a snapshot filename into its Rust module prefix and the remainder,
-#: which starts with the owning test file's stem: ``<stem>_<test_name>...``.
-_NAME_RE = re.compile(
-    rf"^(?P<module>.+?)__(?P<remainder>.+){re.escape(SNAPSHOT_SUFFIX)}$"
-)
+#: Separates the prefix, the namespace and the name in a snapshot filename.
+_SEPARATOR = "__"
+
+#: Namespace of the snapshots written by the mock layer.
+MOCK_NAMESPACE = "mocks"
+
+
+def snapshot_names(snapshot_path: Path) -> List[str]:
+    """The snapshot names ``snapshot_path``'s filename may hold, most specific
+    first.
+
+    A snapshot is stored as ``<prefix>__<name>@pysnap.snap``, and a mock
+    recording as ``<prefix>__mocks__<name>@pysnap.snap``; the prefix may be
+    absent, and both it and the name may contain ``__``. The candidates are
+    what follows each ``__``, from the last one on, and then the whole
+    filename. A test's snapshot name starts with its file's stem,
+    ``<stem>_<test_name>...``.
+
+    Args:
+        snapshot_path: Path to a ``*@pysnap.snap`` file.
+
+    Returns:
+        List[str]: The candidate names; empty for other files.
+    """
+
+    filename = snapshot_path.name
+    if not filename.endswith(SNAPSHOT_SUFFIX):
+        return []
+    stem = filename[: -len(SNAPSHOT_SUFFIX)]
+    parts = stem.split(_SEPARATOR)
+    return [
+        _SEPARATOR.join(parts[index:])
+        for index in range(len(parts) - 1, -1, -1)
+        if all(parts[index:])
+    ]
 
 
 def read_referenced(reference_file: str | Path) -> Set[Path]:
@@ -95,15 +124,23 @@ def snapshot_files(directories: Iterable[Path]) -> Set[Path]:
     return found
 
 
+def is_mock_snapshot(snapshot_path: Path) -> bool:
+    """Whether ``snapshot_path`` is a recording of the mock layer."""
+
+    namespace = f"{MOCK_NAMESPACE}{_SEPARATOR}"
+    return any(name.startswith(namespace) for name in snapshot_names(snapshot_path))
+
+
 def owning_stem(snapshot_path: Path, known_stems: Iterable[str]) -> Optional[str]:
     """Resolve which test-file stem owns ``snapshot_path``.
 
-    A snapshot filename looks like ``<module>__<stem>_<test_name>...@pysnap.snap``.
-    Because both ``<stem>`` and ``<test_name>`` may contain underscores, the stem
-    is ambiguous in isolation; it is resolved by matching against the set of
-    ``known_stems`` (the test modules that exist next to the snapshot directory)
-    and preferring the longest stem that fits on an underscore boundary. That way
-    ``test`` never steals ``test_snapshots``'s files.
+    A snapshot filename looks like ``<prefix>__<stem>_<test_name>...@pysnap.snap``
+    (see :func:`snapshot_names`). Because both ``<stem>`` and ``<test_name>``
+    may contain underscores, the stem is ambiguous in isolation; it is resolved
+    by matching against the set of ``known_stems`` (the test modules that exist
+    next to the snapshot directory) and preferring the longest stem that fits on
+    an underscore boundary. That way ``test`` never steals ``test_snapshots``'s
+    files.
 
     Args:
         snapshot_path: Path to a ``*@pysnap.snap`` file.
@@ -113,17 +150,16 @@ def owning_stem(snapshot_path: Path, known_stems: Iterable[str]) -> Optional[str
         Optional[str]: The owning stem, or ``None`` if no known stem matches.
     """
 
-    match = _NAME_RE.match(snapshot_path.name)
-    if match is None:
-        return None
-    remainder = match.group("remainder")
-
-    best: Optional[str] = None
-    for stem in known_stems:
-        if remainder == stem or remainder.startswith(f"{stem}_"):
-            if best is None or len(stem) > len(best):
-                best = stem
-    return best
+    known_stems = list(known_stems)
+    for name in snapshot_names(snapshot_path):
+        best: Optional[str] = None
+        for stem in known_stems:
+            if name == stem or name.startswith(f"{stem}_"):
+                if best is None or len(stem) > len(best):
+                    best = stem
+        if best is not None:
+            return best
+    return None
 
 
 def sibling_test_stems(directory: Path) -> Set[str]:
@@ -0,0 +1,48 @@
+"""Upgrade snapshots written by older pysnaptest versions.
+
+Every snapshot header records the format version of the layout it was
+written in (``info.format_version``; snapshots from before it was recorded
+have none). When a release changes how snapshots are named or laid out, the
+snapshots committed with an older one no longer line up with what the tests
+assert: a renamed file is silently re-recorded next to the old one.
+:func:`upgrade_snapshots` rewrites them all into the current layout, so an
+upgrade of the library shows up as one reviewable change to the snapshots.
+"""
+
+from __future__ import annotations
+
+from pathlib import Path
+from typing import List, Optional
+
+from ._pysnaptest import upgrade_snapshot
+from .review import SNAPSHOT_GLOB, _root
+
+
+def upgrade_snapshots(root: Optional[str] = None) -> List[Path]:
+    """Rewrite every snapshot under ``root`` written in an older format.
+
+    Each snapshot is renamed to the name its test gives it today (older
+    versions kept the ``::`` of test classes, runs of whitespace and overlong
+    parametrized ids in file names), together with its binary sidecar, and
+    its header gets the current ``format_version`` and loses the
+    ``expression`` field older versions recorded. Snapshots already in the
+    current format are left alone, so upgrading twice changes nothing.
+
+    Args:
+        root: Directory to search. Defaults to ``INSTA_WORKSPACE_ROOT`` if set,
+            otherwise the current working directory.
+
+    Returns:
+        List[Path]: The upgraded snapshots, at their current paths.
+
+    Raises:
+        ValueError: If a snapshot was written by a newer pysnaptest, or its
+            upgraded name is taken by another snapshot.
+    """
+
+    upgraded = []
+    for path in sorted(_root(root).glob(SNAPSHOT_GLOB)):
+        new_path = upgrade_snapshot(path)
+        if new_path is not None:
+            upgraded.append(Path(new_path))
+    return upgraded
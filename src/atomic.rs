@@ -0,0 +1,100 @@
+//! Writing snapshot files so that an interrupted run never leaves one
+//! truncated.
+//!
+//! Every file pysnaptest writes itself (accepted, imported and upgraded
+//! snapshots, mock header updates, externally stored objects) is written to a
+//! temporary file in the destination's folder, flushed to disk and renamed
+//! over the destination. A Ctrl-C or an OOM kill in between leaves either the
+//! old file or the new one, never a prefix of the new one that a later replay
+//! would deserialize into garbage. A leftover temporary file is dot-prefixed
+//! and ends in `.tmp`, so no snapshot glob picks it up.
+//!
+//! Files insta writes itself while asserting are out of reach, but they are
+//! pending `.snap.new` files unless `INSTA_UPDATE=always`.
+
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use insta::Snapshot;
+
+/// A dot-prefixed temporary path next to `path`, unique to this process.
+fn staging_path(path: &Path) -> PathBuf {
+    static STAGED: AtomicUsize = AtomicUsize::new(0);
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(
+        ".{name}.{}-{}.tmp",
+        std::process::id(),
+        STAGED.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// Flushes the directory entry of a rename to disk, where the platform can.
+fn sync_parent(path: &Path) {
+    #[cfg(unix)]
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        let _ = File::open(parent).and_then(|dir| dir.sync_all());
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+/// Moves the fully written file at `from` over `to`.
+fn commit(from: &Path, to: &Path) -> io::Result<()> {
+    File::open(from)?.sync_all()?;
+    fs::rename(from, to)?;
+    sync_parent(to);
+    Ok(())
+}
+
+/// Writes `contents` to `path`, replacing it atomically.
+pub(crate) fn write(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let staged = staging_path(path);
+    let written = File::create(&staged)
+        .and_then(|mut file| {
+            file.write_all(contents.as_ref())?;
+            file.sync_all()
+        })
+        .and_then(|()| {
+            fs::rename(&staged, path)?;
+            sync_parent(path);
+            Ok(())
+        });
+    if written.is_err() {
+        let _ = fs::remove_file(&staged);
+    }
+    written
+}
+
+/// Saves `snapshot` to `path` as `Snapshot::save` does, replacing the file
+/// and its binary sidecar atomically. The sidecar is moved into place first,
+/// so the `.snap` never refers to a missing or partial one.
+pub(crate) fn save_snapshot(snapshot: &Snapshot, path: &Path) -> Result<(), Box<dyn Error>> {
+    let staging = staging_path(path);
+    fs::create_dir(&staging)?;
+    let saved = (|| -> Result<(), Box<dyn Error>> {
+        let file_name = path
+            .file_name()
+            .ok_or("Snapshot path without a file name")?;
+        let staged = staging.join(file_name);
+        snapshot.save(&staged)?;
+        if let Some(sidecar) = snapshot.build_binary_path(&staged) {
+            let target = snapshot
+                .build_binary_path(path)
+                .ok_or("Binary snapshot without a sidecar path")?;
+            commit(&sidecar, &target)?;
+        }
+        commit(&staged, path)?;
+        Ok(())
+    })();
+    let _ = fs::remove_dir_all(&staging);
+    saved
+}
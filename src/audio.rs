@@ -0,0 +1,261 @@
+//! Decoding and tolerance-based comparison for `assert_audio_snapshot`.
+//!
+//! Audio encoders are rarely bit-stable: a new encoder build, dither or frame
+//! alignment changes the bytes of a file that sounds the same. An audio
+//! snapshot stores the encoded file as a binary snapshot, but before
+//! asserting, both the new and the committed file are decoded and compared
+//! under a [`Mode`]. Within the tolerance the *committed* bytes are asserted
+//! instead, so the snapshot neither fails nor churns; outside it the new
+//! file is asserted and fails as usual.
+
+use std::io::Cursor;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::PyResult;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::dsp::complex::Complex;
+use symphonia::core::dsp::fft::Fft;
+use symphonia::core::errors::Error;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::vectors::Comparison;
+
+/// Samples per fingerprint frame.
+const FRAME: usize = 2048;
+
+/// Frequency bands per fingerprint frame, spaced logarithmically from
+/// [`LOWEST_BAND_HZ`] up to the Nyquist frequency.
+const BANDS: usize = 16;
+
+const LOWEST_BAND_HZ: f32 = 40.0;
+
+/// Band levels below this are treated as silence, so encoder noise in
+/// inaudible bands does not count as a difference.
+const FLOOR_DB: f32 = -80.0;
+
+/// How decoded audio is compared with the committed recording.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Mode {
+    /// Root-mean-square difference of the samples (full scale is 1.0) at
+    /// most the tolerance.
+    Pcm,
+    /// Mean absolute difference, in dB, of per-frame band levels at most the
+    /// tolerance. Insensitive to phase and to encoders that shift samples.
+    Fingerprint,
+}
+
+impl Mode {
+    pub(crate) fn parse(mode: &str) -> PyResult<Self> {
+        match mode.to_ascii_lowercase().as_str() {
+            "pcm" => Ok(Mode::Pcm),
+            "fingerprint" => Ok(Mode::Fingerprint),
+            other => Err(PyValueError::new_err(format!(
+                "Unsupported audio snapshot mode {other:?}; expected \"pcm\" or \"fingerprint\""
+            ))),
+        }
+    }
+
+    pub(crate) fn default_tolerance(self) -> f64 {
+        match self {
+            Mode::Pcm => 1e-3,
+            Mode::Fingerprint => 1.0,
+        }
+    }
+}
+
+/// Decoded audio: interleaved samples in `[-1.0, 1.0]`.
+pub(crate) struct Audio {
+    sample_rate: u32,
+    channels: usize,
+    samples: Vec<f32>,
+}
+
+impl Audio {
+    fn frames(&self) -> usize {
+        self.samples.len() / self.channels.max(1)
+    }
+
+    fn seconds(&self) -> f64 {
+        self.frames() as f64 / f64::from(self.sample_rate.max(1))
+    }
+}
+
+fn undecodable(e: Error) -> pyo3::PyErr {
+    PyValueError::new_err(format!("Unable to decode audio: {e}"))
+}
+
+/// Decodes the first audio track of `data`; `extension` (e.g. `"wav"`) is
+/// a hint for the container format.
+pub(crate) fn decode(data: &[u8], extension: &str) -> PyResult<Audio> {
+    let source = MediaSourceStream::new(Box::new(Cursor::new(data.to_vec())), Default::default());
+    let mut hint = Hint::new();
+    hint.with_extension(extension);
+    let mut format = symphonia::default::get_probe()
+        .format(
+            &hint,
+            source,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(undecodable)?
+        .format;
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| PyValueError::new_err("Unable to decode audio: no audio track found"))?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(undecodable)?;
+    let mut audio = Audio {
+        sample_rate: track.codec_params.sample_rate.unwrap_or(0),
+        channels: track.codec_params.channels.map_or(0, |c| c.count()),
+        samples: Vec::new(),
+    };
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(Error::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(undecodable(e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt packet is skipped, as players do.
+            Err(Error::DecodeError(_)) => continue,
+            Err(e) => return Err(undecodable(e)),
+        };
+        let spec = *decoded.spec();
+        audio.sample_rate = spec.rate;
+        audio.channels = spec.channels.count();
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        audio.samples.extend_from_slice(buffer.samples());
+    }
+    Ok(audio)
+}
+
+pub(crate) fn compare(mode: Mode, committed: &Audio, new: &Audio, tolerance: f64) -> Comparison {
+    let changed = |what: &str, from: String, to: String| Comparison {
+        within: false,
+        detail: format!("{what} changed from {from} to {to}"),
+    };
+    if committed.sample_rate != new.sample_rate {
+        return changed(
+            "sample rate",
+            format!("{} Hz", committed.sample_rate),
+            format!("{} Hz", new.sample_rate),
+        );
+    }
+    if committed.channels != new.channels {
+        return changed(
+            "channel count",
+            committed.channels.to_string(),
+            new.channels.to_string(),
+        );
+    }
+    let (difference, unit) = match mode {
+        Mode::Pcm => (rms_difference(&committed.samples, &new.samples), ""),
+        Mode::Fingerprint => {
+            let (old, new_levels) = (fingerprint(committed), fingerprint(new));
+            // Encoders pad differently at either end; more than a frame of
+            // extra audio is a real change.
+            if old.len().abs_diff(new_levels.len()) > 1 {
+                return changed(
+                    "duration",
+                    format!("{:.3} s", committed.seconds()),
+                    format!("{:.3} s", new.seconds()),
+                );
+            }
+            (mean_level_difference(&old, &new_levels), " dB")
+        }
+    };
+    let name = match mode {
+        Mode::Pcm => "RMS sample difference",
+        Mode::Fingerprint => "mean spectral difference",
+    };
+    Comparison {
+        within: difference <= tolerance,
+        detail: format!("{name} {difference:.6}{unit} (tolerance {tolerance}{unit})"),
+    }
+}
+
+/// Root-mean-square difference of two signals, the shorter one padded with
+/// silence.
+fn rms_difference(a: &[f32], b: &[f32]) -> f64 {
+    let len = a.len().max(b.len());
+    if len == 0 {
+        return 0.0;
+    }
+    let sum: f64 = (0..len)
+        .map(|i| {
+            let d = f64::from(a.get(i).copied().unwrap_or(0.0))
+                - f64::from(b.get(i).copied().unwrap_or(0.0));
+            d * d
+        })
+        .sum();
+    (sum / len as f64).sqrt()
+}
+
+/// Per-frame band levels, in dB, of the mono mix of `audio`.
+fn fingerprint(audio: &Audio) -> Vec<[f32; BANDS]> {
+    let channels = audio.channels.max(1);
+    let mono: Vec<f32> = audio
+        .samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect();
+    let fft = Fft::new(FRAME);
+    let window: Vec<f32> = (0..FRAME)
+        .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / FRAME as f32).cos())
+        .collect();
+    let bin_hz = audio.sample_rate as f32 / FRAME as f32;
+    let nyquist = audio.sample_rate as f32 / 2.0;
+    let edges: Vec<usize> = (0..=BANDS)
+        .map(|band| {
+            let hz = LOWEST_BAND_HZ * (nyquist / LOWEST_BAND_HZ).powf(band as f32 / BANDS as f32);
+            ((hz / bin_hz).round() as usize).clamp(1, FRAME / 2)
+        })
+        .collect();
+    let mut spectrum = vec![Complex::default(); FRAME];
+    mono.chunks(FRAME)
+        .map(|chunk| {
+            let input: Vec<Complex> = (0..FRAME)
+                .map(|i| Complex::new(chunk.get(i).copied().unwrap_or(0.0) * window[i], 0.0))
+                .collect();
+            fft.fft(&input, &mut spectrum);
+            let mut levels = [FLOOR_DB; BANDS];
+            for (band, level) in levels.iter_mut().enumerate() {
+                let bins = &spectrum[edges[band]..edges[band + 1].max(edges[band] + 1)];
+                let power = bins
+                    .iter()
+                    .map(|c| (c.re * c.re + c.im * c.im) / (FRAME * FRAME) as f32)
+                    .sum::<f32>()
+                    / bins.len() as f32;
+                *level = (10.0 * power.max(f32::MIN_POSITIVE).log10()).max(FLOOR_DB);
+            }
+            levels
+        })
+        .collect()
+}
+
+/// Mean absolute difference of the band levels the two fingerprints share.
+fn mean_level_difference(a: &[[f32; BANDS]], b: &[[f32; BANDS]]) -> f64 {
+    let pairs = a.len().min(b.len());
+    if pairs == 0 {
+        return 0.0;
+    }
+    let sum: f64 = a
+        .iter()
+        .zip(b)
+        .flat_map(|(x, y)| x.iter().zip(y).map(|(x, y)| f64::from((x - y).abs())))
+        .sum();
+    sum / (pairs * BANDS) as f64
+}
@@ -0,0 +1,103 @@
+//! Checksums of snapshot bodies, verified when snapshots are loaded.
+//!
+//! When an assertion writes a snapshot (or `pysnaptest accept` does), the
+//! SHA-256 of what it stored is kept in the `checksum` field of the header's
+//! `info`: of the bytes below the header for a text snapshot, and of the data
+//! file for a binary one. `PySnapshot.from_file` and mock replay check it and
+//! raise [`SnapshotCorruptedError`] when a body was edited by hand without
+//! its header, or truncated by an interrupted copy, rather than handing out
+//! subtly wrong data.
+//!
+//! Snapshots without a checksum, written by older versions or by
+//! `cargo-insta`, load as before. Rewriting other header fields (a mock's
+//! `recorded_at`, `pysnaptest upgrade`) leaves the checksum valid.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use sha2::{Digest, Sha256};
+
+use crate::hashing::hex;
+use crate::mapped::{binary_sidecar, split_snapshot};
+use crate::mocks::{read_info_field, write_file_info_field};
+
+/// The `info` field holding a snapshot's checksum.
+pub(crate) const CHECKSUM_FIELD: &str = "checksum";
+
+pyo3::create_exception!(
+    _pysnaptest,
+    SnapshotCorruptedError,
+    PyValueError,
+    "A snapshot's body does not match the checksum recorded in its header."
+);
+
+/// The checksum of the snapshot at `snapshot` as it is stored now
+/// (`sha256:<hex>`), or `None` for a legacy snapshot, or a binary one whose
+/// data file is missing.
+fn body_checksum(snapshot: &Path) -> io::Result<Option<String>> {
+    let digest = match binary_sidecar(snapshot) {
+        Some(sidecar) => match fs::read(&sidecar) {
+            Ok(data) => Sha256::digest(data),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        },
+        None => match split_snapshot(&fs::read(snapshot)?) {
+            Some((_, body)) => Sha256::digest(body),
+            None => return Ok(None),
+        },
+    };
+    Ok(Some(format!("sha256:{}", hex(&digest))))
+}
+
+/// A digest of everything stored for the snapshot at `snapshot`: its file
+/// and, for a binary snapshot, its data file. `None` when it is not stored.
+///
+/// Taken before and after an assertion to tell whether insta wrote the
+/// snapshot, which modification times cannot do on filesystems that only
+/// keep them to the second or two.
+pub(crate) fn fingerprint(snapshot: &Path) -> Option<[u8; 32]> {
+    let mut digest = Sha256::new();
+    digest.update(fs::read(snapshot).ok()?);
+    if let Some(data) = binary_sidecar(snapshot).and_then(|sidecar| fs::read(sidecar).ok()) {
+        digest.update(data);
+    }
+    Some(digest.finalize().into())
+}
+
+/// Records the checksum of the snapshot just written at `snapshot` (a file
+/// on disk) in its header.
+pub(crate) fn stamp(snapshot: &Path) -> PyResult<()> {
+    let checksum = body_checksum(snapshot)
+        .map_err(|e| PyValueError::new_err(format!("Unable to read snapshot {snapshot:?}: {e}")))?;
+    match checksum {
+        Some(checksum) => {
+            write_file_info_field(snapshot, CHECKSUM_FIELD, Some(&format!("\"{checksum}\"")))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Checks the snapshot at `snapshot` against the checksum in its header, if
+/// it has one.
+pub(crate) fn verify(snapshot: &Path) -> PyResult<()> {
+    let Some(recorded) = read_info_field(snapshot, CHECKSUM_FIELD) else {
+        return Ok(());
+    };
+    let actual = body_checksum(snapshot)
+        .map_err(|e| PyValueError::new_err(format!("Unable to read snapshot {snapshot:?}: {e}")))?;
+    // The data file of a binary snapshot may be absent, as when gitignored.
+    if actual.as_deref() == Some(recorded.as_str())
+        || (actual.is_none() && binary_sidecar(snapshot).is_some())
+    {
+        return Ok(());
+    }
+    Err(SnapshotCorruptedError::new_err(format!(
+        "Snapshot {snapshot:?} does not match the checksum in its header ({recorded}, but \
+         its body hashes to {}): it was edited by hand or truncated. Restore it from \
+         version control, or re-record it.",
+        actual.as_deref().unwrap_or("nothing")
+    )))
+}
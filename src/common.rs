@@ -1,4 +1,7 @@
-use std::collections::BTreeMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::CString;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::ops::Deref;
 use std::path::PathBuf;
 use std::str::{self, FromStr};
@@ -8,13 +11,31 @@ use std::{env, path::Path};
 use once_cell::sync::Lazy;
 
 use pyo3::FromPyObject;
-use pyo3::{exceptions::PyValueError, pyclass, pymethods, Bound, PyAny, PyErr, PyResult};
+use pyo3::{
+    exceptions::{PyUserWarning, PyValueError},
+    pyclass, pyfunction, pymethods, Bound, PyAny, PyErr, PyResult, Python,
+};
 
+use crate::compression::{decompress, is_compressed_sidecar};
+use crate::diff::ReportingComparator;
+use crate::encryption::{decrypt_if_encrypted, is_encrypted};
 use crate::errors::PytestInfoError;
+use crate::hashing::hex;
+use crate::json_format::{JsonFormat, JsonStyle};
+use crate::mapped::{binary_sidecar, should_map, MappedFile, SnapshotBuffer};
+use crate::mocks::read_info;
+use crate::provenance::Provenance;
+use crate::reruns;
+use crate::scopes::{self, Scope};
+use crate::scratch_storage::ScratchStorage;
+use crate::snapshot_settings::SnapshotSettings;
+use crate::storage::{is_pointer_sidecar, resolve_pointer};
 
 use insta::internals::{Redaction, SnapshotContents};
 use insta::{rounded_redaction, sorted_redaction, Snapshot};
-use pyo3::types::PyAnyMethods;
+use pyo3::types::{PyAnyMethods, PyBytes, PyDict, PyDictMethods, PyString};
+use pyo3::{Py, PyObject};
+use sha2::{Digest, Sha256};
 
 const PYSNAPSHOT_SUFFIX: &str = "pysnap";
 
@@ -25,42 +46,259 @@ const PYSNAPSHOT_SUFFIX: &str = "pysnap";
 /// globs from the same source of truth.
 pub const SNAPSHOT_FILE_SUFFIX: &str = "@pysnap.snap";
 
-static TEST_NAME_COUNTERS: Lazy<Mutex<BTreeMap<String, usize>>> =
+/// The index of the last snapshot assigned under each snapshot name, keyed by
+/// the snapshot folder too: tests of same-named files in different
+/// directories share a name but not their snapshots.
+static TEST_NAME_COUNTERS: Lazy<Mutex<BTreeMap<CounterKey, usize>>> =
     Lazy::new(|| Mutex::new(BTreeMap::new()));
 
+/// A snapshot folder and a snapshot name.
+pub(crate) type CounterKey = (PathBuf, String);
+
 #[derive(Debug)]
-struct Description {
+pub(crate) struct Description {
     test_file_path: String,
+    /// What the snapshot's value represents, as given by the assertion.
+    text: Option<String>,
 }
 
 impl Description {
     pub fn new(test_file_path: String) -> Self {
-        Self { test_file_path }
+        Self {
+            test_file_path,
+            text: None,
+        }
+    }
+
+    /// Puts `text` before the test file path, on the same line so the path
+    /// stays where `pysnaptest gc` reads it.
+    pub(crate) fn with_text(self, text: Option<&str>) -> Self {
+        Self {
+            text: text.map(one_line),
+            ..self
+        }
     }
 }
 
 impl From<Description> for String {
     fn from(val: Description) -> Self {
-        format!("Test File Path: {}", val.test_file_path)
+        match val.text {
+            Some(text) => format!("{text}; Test File Path: {}", val.test_file_path),
+            None => format!("Test File Path: {}", val.test_file_path),
+        }
     }
 }
 
+/// `text` with its runs of whitespace, newlines included, collapsed to
+/// single spaces, so insta writes it as a one-line header field.
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Longest snapshot name derived from a test id, and longest file stem a
+/// snapshot is stored under (module prefix, namespace, duplicate counter and
+/// a mock's `_<fn>-<digest>-request` suffix included), that is kept as is.
+/// With `@pysnap.snap.new` this stays well below the 255-character file name
+/// limit of Windows and most Linux filesystems.
+pub(crate) const MAX_SNAPSHOT_NAME_LEN: usize = 180;
+
+/// The end of an overlong file stem kept when it is shortened, so a mock's
+/// `-<digest>-<N>-request` and duplicate counters stay readable.
+const KEPT_STEM_TAIL: usize = 32;
+
+/// `text` if it is at most [`MAX_SNAPSHOT_NAME_LEN`] long, or else its start
+/// and its last `tail` bytes (to a character boundary) around a hash of
+/// `hashed`, the whole name it stands for.
+fn shorten(text: &str, hashed: &str, tail: usize) -> String {
+    if text.len() <= MAX_SNAPSHOT_NAME_LEN {
+        return text.to_string();
+    }
+    let digest = hex(&Sha256::digest(hashed.as_bytes()));
+    let mut tail_start = text.len() - tail;
+    while !text.is_char_boundary(tail_start) {
+        tail_start += 1;
+    }
+    let mut cut = MAX_SNAPSHOT_NAME_LEN - 17 - (text.len() - tail_start);
+    while !text.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    format!(
+        "{}_{}{}",
+        text[..cut].trim_end(),
+        &digest[..16],
+        &text[tail_start..]
+    )
+}
+
+/// Makes a snapshot name derived from a test id safe to use as a file name.
+///
+/// The `::` between a test class and method becomes `.`. Parametrized ids
+/// can contain paths or long reprs: the other characters Windows forbids in
+/// file names (`/\:<>|"?*`) and control characters are dropped, runs of
+/// whitespace collapse to one space, and a name longer than
+/// [`MAX_SNAPSHOT_NAME_LEN`] is cut short and suffixed with a hash of the
+/// whole name, so distinct long ids keep distinct, stable names.
+#[pyfunction]
+pub fn sanitize_snapshot_name(name: &str) -> String {
+    let cleaned: String = name
+        .replace("::", ".")
+        .chars()
+        .filter(|c| !matches!(c, '/' | '\\' | ':' | '<' | '>' | '|' | '"' | '?' | '*'))
+        .map(|c| if c.is_whitespace() { ' ' } else { c })
+        .filter(|c| !c.is_control())
+        .collect();
+    let cleaned = cleaned
+        .split(' ')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    shorten(&cleaned, name, 0)
+}
+
+/// Drops the `\\?\` verbatim prefix `canonicalize` puts on Windows paths
+/// (`\\?\C:\repo` becomes `C:\repo`, `\\?\UNC\server\share` becomes
+/// `\\server\share`). Verbatim paths disable `/` as a separator, so joining a
+/// `/`-separated relative path onto one silently yields a path that does not
+/// exist, and they leak into snapshot metadata. Other paths are returned as is.
+fn strip_verbatim_prefix(path: PathBuf) -> PathBuf {
+    let Some(text) = path.to_str() else {
+        return path;
+    };
+    if let Some(unc) = text.strip_prefix(r"\\?\UNC\") {
+        PathBuf::from(format!(r"\\{unc}"))
+    } else if let Some(local) = text
+        .strip_prefix(r"\\?\")
+        .filter(|rest| rest.as_bytes().get(1) == Some(&b':'))
+    {
+        PathBuf::from(local)
+    } else {
+        path
+    }
+}
+
+/// `path` with `/` separators, as recorded in snapshot metadata so that
+/// snapshots written on Windows and elsewhere read the same.
+pub(crate) fn portable_path(path: &Path) -> String {
+    let text = path.to_string_lossy();
+    if std::path::MAIN_SEPARATOR == '\\' {
+        text.replace('\\', "/")
+    } else {
+        text.into_owned()
+    }
+}
+
+/// Set by the pytest plugin to the session's rootdir, which test ids in
+/// `PYTEST_CURRENT_TEST` are relative to.
+pub const PYTEST_ROOTDIR_ENV: &str = "PYTEST_ROOTDIR";
+
+/// Directories a test file path from `PYTEST_CURRENT_TEST` may be relative
+/// to, in order: `INSTA_WORKSPACE_ROOT`, the pytest rootdir, and the nearest
+/// directory at or above the current one holding a `pyproject.toml`.
+pub(crate) fn workspace_roots() -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = crate::insta_env::workspace_root()
+        .into_iter()
+        .chain(
+            env::var_os(PYTEST_ROOTDIR_ENV)
+                .filter(|root| !root.is_empty())
+                .map(PathBuf::from),
+        )
+        .collect();
+    if let Ok(cwd) = env::current_dir() {
+        roots.extend(
+            cwd.ancestors()
+                .find(|dir| dir.join("pyproject.toml").is_file())
+                .map(Path::to_path_buf),
+        );
+    }
+    roots
+}
+
+/// Names the interactive session (a notebook or REPL) whose snapshots are
+/// taken outside pytest; see [`SnapshotInfo::interactive_session`].
+pub const SESSION_ENV: &str = "PYSNAPTEST_SESSION";
+
+/// Overrides the directory an interactive session's snapshots are stored in.
+pub const SESSION_DIR_ENV: &str = "PYSNAPTEST_SESSION_DIR";
+
+/// Set by Jupyter to the path of the notebook a kernel runs.
+const NOTEBOOK_ENV: &str = "JPY_SESSION_NAME";
+
+/// Selects the prefix strategy of snapshots that don't pass one; see
+/// [`resolve_prefix`].
+pub const PREFIX_ENV: &str = "PYSNAPTEST_PREFIX";
+
+/// Resolves a prefix strategy to the prefix of snapshot file names
+/// (`<prefix>__<name>@pysnap.snap`). `"module"`, the default, is the Python
+/// module of the test (`None` without one), `"none"` leaves names unprefixed,
+/// and anything else is the prefix itself. Without a `strategy`,
+/// `PYSNAPTEST_PREFIX` decides. Dots in a prefix become `__`, as in insta's
+/// module prefixes.
+pub(crate) fn resolve_prefix(
+    strategy: Option<&str>,
+    module: Option<&str>,
+) -> PyResult<Option<String>> {
+    let strategy = match strategy {
+        Some(strategy) => strategy.to_string(),
+        None => env::var(PREFIX_ENV)
+            .ok()
+            .filter(|strategy| !strategy.is_empty())
+            .unwrap_or_else(|| "module".to_string()),
+    };
+    let prefix = match strategy.as_str() {
+        "module" => module,
+        "none" => None,
+        custom => Some(custom),
+    };
+    prefix
+        .map(|prefix| {
+            let prefix = sanitize_snapshot_name(prefix).replace('.', "__");
+            if prefix.is_empty() {
+                Err(PyValueError::new_err(format!(
+                    "Invalid snapshot prefix {strategy:?}: use \"module\", \"none\" or a \
+                     non-empty prefix"
+                )))
+            } else {
+                Ok(prefix)
+            }
+        })
+        .transpose()
+}
+
 #[derive(Debug)]
 pub(crate) struct PytestInfo {
     test_path: String,
     test_name: String,
+    /// The `setup`, `call` or `teardown` stage pytest reports the test in.
+    stage: Option<String>,
 }
 
 impl PytestInfo {
+    /// Reads the running test from `PYTEST_CURRENT_TEST`, noting its stage
+    /// so that a rerun of the test restarts its snapshot numbering.
     pub fn from_env() -> Result<Self, PytestInfoError> {
         let pytest_str = env::var("PYTEST_CURRENT_TEST").map_err(PytestInfoError::InvalidEnvVar)?;
-        pytest_str.parse()
+        let info: Self = pytest_str.parse()?;
+        if let Some(stage) = &info.stage {
+            reruns::observe(reruns::split_stage(&pytest_str).0, stage);
+        }
+        Ok(info)
     }
 
+    /// Locates the test file. pytest reports it relative to its rootdir,
+    /// which is not the current directory when pytest runs from a
+    /// subdirectory or an IDE runner, so it is also looked up under each of
+    /// [`workspace_roots`] before falling back to `./<filename>`.
     pub fn test_path(&self) -> Result<PathBuf, PytestInfoError> {
         let path = self.test_path_raw();
         if path.exists() {
-            Ok(path)
+            return Ok(path);
+        }
+        if let Some(found) = workspace_roots()
+            .into_iter()
+            .map(|root| root.join(&path))
+            .find(|candidate| candidate.exists())
+        {
+            Ok(found)
         } else if let Some(filename) = path.file_name() {
             let mut filepath = PathBuf::from("./");
             filepath.push(filename);
@@ -73,6 +311,10 @@ impl PytestInfo {
     pub fn test_path_raw(&self) -> PathBuf {
         Path::new(&self.test_path).to_path_buf()
     }
+
+    pub fn stage(&self) -> Option<&str> {
+        self.stage.as_deref()
+    }
 }
 
 impl FromStr for PytestInfo {
@@ -91,10 +333,37 @@ impl FromStr for PytestInfo {
         Ok(PytestInfo {
             test_name: test_name.to_string(),
             test_path: test_path.to_string(),
+            stage: reruns::split_stage(s).1.map(str::to_string),
         })
     }
 }
 
+pyo3::create_exception!(
+    _pysnaptest,
+    SnapshotTeardownWarning,
+    PyUserWarning,
+    "A snapshot was asserted while pytest tore its test down."
+);
+
+/// Warns with [`SnapshotTeardownWarning`] when the running test is in its
+/// `teardown` stage: a fixture finalized there may belong to another test,
+/// e.g. a module-scoped one torn down after the module's last test, and the
+/// snapshot would be named and numbered after that test. Turn the warning
+/// into an error with a `filterwarnings` entry to refuse such snapshots.
+fn warn_if_in_teardown() -> PyResult<()> {
+    let Ok(current) = env::var("PYTEST_CURRENT_TEST") else {
+        return Ok(());
+    };
+    let (node_id, Some("teardown")) = reruns::split_stage(&current) else {
+        return Ok(());
+    };
+    let message = CString::new(format!(
+        "Snapshot asserted during the teardown of {node_id}; it is attributed to \
+         that test, which may not be the one the fixture served"
+    ))?;
+    Python::with_gil(|py| PyErr::warn(py, &py.get_type::<SnapshotTeardownWarning>(), &message, 1))
+}
+
 #[pyclass(frozen)]
 #[derive(Debug, Clone)]
 pub struct SnapshotInfo {
@@ -102,27 +371,77 @@ pub struct SnapshotInfo {
     pub(crate) snapshot_name: String,
     pub(crate) relative_test_file_path: Option<String>,
     pub(crate) allow_duplicates: bool,
+    /// The Python module of the test, the file stem of its source file.
+    pub(crate) module: Option<String>,
+    /// Prepended to snapshot file names, see [`resolve_prefix`].
+    pub(crate) prefix: Option<String>,
+    /// What the asserted value represents, recorded in the header's
+    /// `description` before the test file path.
+    pub(crate) description: Option<String>,
+    /// The expression asserted, recorded as the header's `expression`; none
+    /// leaves the field out.
+    pub(crate) expression: Option<String>,
+    /// The [`SnapshotSettings`] the info was built under, if any.
+    pub(crate) settings: Option<SnapshotSettings>,
+    /// The pytest stage of the test when the info was built, see
+    /// [`PytestInfo`].
+    pub(crate) stage: Option<String>,
+    /// The pytest node id the info was bound to with
+    /// `SnapshotInfo.from_node_id`, recorded as its snapshots' test instead
+    /// of the running one.
+    pub(crate) node_id: Option<String>,
+    /// What the snapshots are shared by, if not just this test; see
+    /// [`crate::scopes`].
+    pub(crate) scope: Option<Scope>,
+    /// The subdirectory of `snapshot_folder` the snapshots are stored in,
+    /// e.g. `http` for a fixture's recordings; see [`parse_namespace`].
+    pub(crate) namespace: Option<String>,
+    /// Keeps the snapshots out of the folder; see
+    /// [`crate::scratch_storage`].
+    pub(crate) storage: Option<ScratchStorage>,
+    /// Redactions every assertion made with the info applies, given with
+    /// `SnapshotInfo.with_redactions`; see [`SnapshotInfo::redactions`].
+    pub(crate) redactions: HashMap<String, RedactionType>,
 }
 
 impl TryFrom<PytestInfo> for SnapshotInfo {
     type Error = PyErr;
     fn try_from(value: PytestInfo) -> Result<Self, Self::Error> {
-        let test_file_dir = value
-            .test_path()?
-            .canonicalize()?
+        let test_path = value.test_path()?;
+        let raw_path = value.test_path_raw();
+        // Found under a workspace root: record it relative to that root.
+        let recorded_path = if test_path.ends_with(&raw_path) {
+            &raw_path
+        } else {
+            &test_path
+        };
+        Ok(Self {
+            stage: value.stage,
+            ..Self::for_test(&test_path, recorded_path, &value.test_name)?
+        })
+    }
+}
+
+impl SnapshotInfo {
+    /// Snapshot info for the test `test_name` defined in the file at
+    /// `test_path`: snapshots are stored in `snapshots/` next to the file and
+    /// named `<file stem>_<test_name>`, and `recorded_path` is the test file
+    /// path written to their metadata. The file's stem is the test's module.
+    pub(crate) fn for_test(
+        test_path: &Path,
+        recorded_path: &Path,
+        test_name: &str,
+    ) -> PyResult<Self> {
+        let test_file_dir = strip_verbatim_prefix(test_path.canonicalize()?)
             .parent()
             .ok_or_else(|| {
                 PyValueError::new_err(format!(
-                    "Invalid test_path: {:?}, not yielding a parent directory",
-                    value.test_path_raw()
+                    "Invalid test_path: {test_path:?}, not yielding a parent directory"
                 ))
             })?
             .join("snapshots");
 
-        let test_name = &value.test_name;
-        let test_path = value.test_path_raw();
-        let file_name = test_path.file_stem().and_then(|s| s.to_str());
-
+        let file_name = recorded_path.file_stem().and_then(|s| s.to_str());
         let name = if let Some(f) = file_name {
             format!("{f}_{test_name}")
         } else {
@@ -130,49 +449,449 @@ impl TryFrom<PytestInfo> for SnapshotInfo {
         };
         Ok(Self {
             snapshot_folder: test_file_dir,
-            snapshot_name: name,
-            relative_test_file_path: Some(value.test_path()?.to_string_lossy().to_string()),
+            snapshot_name: sanitize_snapshot_name(&name),
+            relative_test_file_path: Some(portable_path(recorded_path)),
             allow_duplicates: false,
+            module: file_name.map(str::to_string),
+            prefix: resolve_prefix(None, file_name)?,
+            description: None,
+            expression: None,
+            settings: None,
+            stage: None,
+            node_id: None,
+            scope: None,
+            namespace: None,
+            storage: None,
+            redactions: HashMap::new(),
         })
     }
 }
 
 impl SnapshotInfo {
-    pub(crate) fn counters<'a>() -> MutexGuard<'a, BTreeMap<String, usize>> {
+    /// Snapshot info for an interactive session, used when
+    /// `PYTEST_CURRENT_TEST` is not set. The session is named by
+    /// `PYSNAPTEST_SESSION`, or else after the Jupyter notebook the kernel
+    /// runs, and its snapshots are stored in `PYSNAPTEST_SESSION_DIR`, or else
+    /// in `snapshots/<session>` next to the notebook (or the current
+    /// directory). `None` outside of a session.
+    pub(crate) fn interactive_session() -> PyResult<Option<Self>> {
+        let notebook = env::var_os(NOTEBOOK_ENV)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from);
+        let Some(name) = env::var(SESSION_ENV)
+            .ok()
+            .filter(|name| !name.is_empty())
+            .or_else(|| {
+                notebook
+                    .as_ref()
+                    .and_then(|path| path.file_stem())
+                    .map(|stem| stem.to_string_lossy().into_owned())
+            })
+        else {
+            return Ok(None);
+        };
+        let name = sanitize_snapshot_name(&name);
+        let folder = match env::var_os(SESSION_DIR_ENV).filter(|dir| !dir.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => notebook
+                .as_ref()
+                .and_then(|path| path.parent())
+                .unwrap_or(Path::new(""))
+                .join("snapshots")
+                .join(&name),
+        };
+        Ok(Some(Self {
+            // insta resolves relative snapshot paths against the Rust source
+            // tree, not the current directory.
+            snapshot_folder: std::path::absolute(folder)?,
+            snapshot_name: name,
+            relative_test_file_path: notebook.as_deref().map(portable_path),
+            allow_duplicates: false,
+            module: None,
+            prefix: resolve_prefix(None, None)?,
+            description: None,
+            expression: None,
+            settings: None,
+            stage: None,
+            node_id: None,
+            scope: None,
+            namespace: None,
+            storage: None,
+            redactions: HashMap::new(),
+        }))
+    }
+}
+
+impl SnapshotInfo {
+    /// The fields two infos are compared and hashed by: all but the
+    /// settings they were built under, whose redactions hold callables.
+    #[allow(clippy::type_complexity)]
+    fn identity(
+        &self,
+    ) -> (
+        &Path,
+        &str,
+        Option<&str>,
+        bool,
+        Option<&str>,
+        Option<&str>,
+        Option<&str>,
+        Option<&str>,
+        Option<&str>,
+        Option<&str>,
+        Option<Scope>,
+        (Option<&str>, Option<usize>),
+    ) {
+        (
+            &self.snapshot_folder,
+            &self.snapshot_name,
+            self.relative_test_file_path.as_deref(),
+            self.allow_duplicates,
+            self.module.as_deref(),
+            self.prefix.as_deref(),
+            self.description.as_deref(),
+            self.expression.as_deref(),
+            self.stage.as_deref(),
+            self.node_id.as_deref(),
+            self.scope,
+            (
+                self.namespace.as_deref(),
+                self.storage.as_ref().map(ScratchStorage::id),
+            ),
+        )
+    }
+
+    /// Whether `other` names and records its snapshots the same way.
+    pub(crate) fn same_as(&self, other: &Self) -> bool {
+        self.identity() == other.identity()
+    }
+
+    /// A hash consistent with [`SnapshotInfo::same_as`].
+    pub(crate) fn identity_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.identity().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// `SnapshotInfo(name=..., folder=..., ...)`, with the optional fields
+    /// that are set after the four every info has.
+    pub(crate) fn repr(&self, py: Python<'_>) -> PyResult<String> {
+        let mut fields = vec![
+            format!("name={}", py_repr(py, &self.snapshot_name)?),
+            format!("folder={}", py_repr(py, &self.folder().to_string_lossy())?),
+            format!(
+                "relative_test_file_path={}",
+                match &self.relative_test_file_path {
+                    Some(path) => py_repr(py, path)?,
+                    None => "None".to_string(),
+                }
+            ),
+            format!(
+                "allow_duplicates={}",
+                if self.allow_duplicates {
+                    "True"
+                } else {
+                    "False"
+                }
+            ),
+        ];
+        let optional = [
+            ("prefix", self.prefix.as_deref()),
+            ("description", self.description.as_deref()),
+            ("expression", self.expression.as_deref()),
+            ("node_id", self.node_id.as_deref()),
+            ("scope", self.scope.map(Scope::as_str)),
+            ("namespace", self.namespace.as_deref()),
+        ];
+        for (field, value) in optional {
+            if let Some(value) = value {
+                fields.push(format!("{field}={}", py_repr(py, value)?));
+            }
+        }
+        Ok(format!("SnapshotInfo({})", fields.join(", ")))
+    }
+
+    pub(crate) fn counters<'a>() -> MutexGuard<'a, BTreeMap<CounterKey, usize>> {
         TEST_NAME_COUNTERS.lock().unwrap_or_else(|x| x.into_inner())
     }
 
-    pub(crate) fn snapshot_name_with_idx(&self, test_idx: usize) -> String {
-        if test_idx == 1 || test_idx == 0 {
-            self.snapshot_name.to_string()
-        } else {
-            format!("{}-{}", self.snapshot_name, test_idx)
+    /// The provenance of a snapshot recorded now for this info: by the test
+    /// it is bound to, if any, or else by the running one.
+    pub(crate) fn provenance(&self) -> Provenance {
+        match &self.node_id {
+            Some(node_id) => Provenance::for_test(node_id.clone()),
+            None => Provenance::current(),
         }
     }
 
+    /// The key of this info's counter in [`SnapshotInfo::counters`].
+    pub(crate) fn counter_key(&self) -> CounterKey {
+        (self.folder(), self.snapshot_name.clone())
+    }
+
+    /// The folder the snapshots are stored in: `snapshot_folder`, or its
+    /// namespace's subdirectory, within the scratch folder of a
+    /// [`ScratchStorage`].
+    pub(crate) fn folder(&self) -> PathBuf {
+        let folder = match &self.namespace {
+            Some(namespace) => self.snapshot_folder.join(namespace),
+            None => self.snapshot_folder.clone(),
+        };
+        match &self.storage {
+            Some(storage) => storage.scratch_folder(&folder),
+            None => folder,
+        }
+    }
+
+    pub(crate) fn snapshot_name_with_idx(&self, test_idx: usize) -> String {
+        indexed_name(&self.snapshot_name, test_idx)
+    }
+
     /// Ticks the shared duplicate counter and returns the assigned snapshot
     /// name (the base name on first use, `<base>-N` on subsequent uses).
+    ///
+    /// With `allow_duplicates` the current name is reused instead, so every
+    /// occurrence is compared against one stored snapshot. The base name is
+    /// still claimed on first use, so a later assertion without
+    /// `allow_duplicates` moves on to `<base>-2` rather than overwriting it.
     pub(crate) fn snapshot_name(&self) -> String {
-        let mut c = Self::counters();
-        let mut test_idx = c.get(&self.snapshot_name).cloned().unwrap_or(0);
-        if !self.allow_duplicates {
-            test_idx += 1;
-            c.insert(self.snapshot_name.clone(), test_idx);
+        let key = self.counter_key();
+        if self.scope.is_some() {
+            scopes::acquire(&key, self.node_id.as_deref());
         }
+        let mut c = Self::counters();
+        let current = c.get(&key).cloned().unwrap_or(0);
+        let test_idx = next_index(current, self.allow_duplicates);
+        c.insert(key.clone(), test_idx);
+        drop(c);
+        reruns::record(key);
 
         self.snapshot_name_with_idx(test_idx)
     }
 
+    /// Returns a copy applying `redactions` on top of this info's, replacing
+    /// those with the same selector. Exposed to Python as
+    /// `SnapshotInfo.with_redactions`.
+    pub(crate) fn with_default_redactions(
+        &self,
+        redactions: HashMap<String, RedactionType>,
+    ) -> Self {
+        let mut merged = self.redactions.clone();
+        merged.extend(redactions);
+        Self {
+            redactions: merged,
+            ..self.clone()
+        }
+    }
+
+    /// The redactions an assertion applies: the process-wide defaults, then
+    /// this info's, each overridden per selector by the next and finally by
+    /// the assertion's own `redactions`.
+    pub(crate) fn redactions(
+        &self,
+        redactions: Option<HashMap<String, RedactionType>>,
+    ) -> Option<HashMap<String, RedactionType>> {
+        let mut merged = crate::settings::default_redactions();
+        merged.extend(self.redactions.clone());
+        if let Some(settings) = &self.settings {
+            merged.extend(settings.redactions().clone());
+        }
+        merged.extend(redactions.unwrap_or_default());
+        (!merged.is_empty()).then_some(merged)
+    }
+
     /// Returns a copy of this `SnapshotInfo` with `suffix` appended to the
     /// snapshot name (e.g. `<test>_<func_name>` for scoping a mock's request
-    /// and response snapshots). Not exposed to Python: only used internally to
-    /// compose the mock layer's `prepare_mock_call`.
+    /// and response snapshots). Exposed to Python, sanitized, as
+    /// `SnapshotInfo.with_name`.
     pub(crate) fn with_name_suffix(&self, suffix: &str) -> Self {
         Self {
             snapshot_name: format!("{}_{}", self.snapshot_name, suffix),
             ..self.clone()
         }
     }
+
+    /// Returns a copy whose snapshots are shared by the tests of its module
+    /// or of the session, named after the module or `session` unless
+    /// `keep_name`; see [`crate::scopes`]. A session's snapshots are not
+    /// prefixed, and are stored in the workspace root's `snapshots/` folder
+    /// unless `keep_folder`.
+    pub(crate) fn with_scope(
+        self,
+        scope: Option<Scope>,
+        keep_name: bool,
+        keep_folder: bool,
+    ) -> Self {
+        match scope {
+            None => self,
+            Some(Scope::Module) => Self {
+                snapshot_name: match (&self.module, keep_name) {
+                    (Some(module), false) => sanitize_snapshot_name(module),
+                    _ => self.snapshot_name.clone(),
+                },
+                scope,
+                ..self
+            },
+            Some(Scope::Session) => Self {
+                snapshot_folder: match keep_folder {
+                    false => scopes::session_folder().unwrap_or(self.snapshot_folder),
+                    true => self.snapshot_folder,
+                },
+                snapshot_name: if keep_name {
+                    self.snapshot_name
+                } else {
+                    "session".to_string()
+                },
+                prefix: None,
+                scope,
+                ..self
+            },
+        }
+    }
+
+    /// Returns a copy using the prefix `strategy` resolves to, or this one's
+    /// prefix without a strategy.
+    pub(crate) fn with_prefix(&self, strategy: Option<&str>) -> PyResult<Self> {
+        Ok(Self {
+            prefix: match strategy {
+                Some(_) => resolve_prefix(strategy, self.module.as_deref())?,
+                None => self.prefix.clone(),
+            },
+            ..self.clone()
+        })
+    }
+
+    /// Returns a copy built under `settings`, with their suffix appended to
+    /// the snapshot name and their description and namespace used unless
+    /// this info has its own; see [`crate::snapshot_settings`]. Their `snapshot_path` is left
+    /// to the constructor, as an explicit folder wins over it.
+    pub(crate) fn with_settings(self, settings: Option<SnapshotSettings>) -> Self {
+        let Some(settings) = settings else {
+            return self;
+        };
+        let info = match settings.snapshot_suffix() {
+            Some(suffix) => self.with_name_suffix(suffix),
+            None => self,
+        };
+        Self {
+            description: info
+                .description
+                .or_else(|| settings.description().map(str::to_string)),
+            namespace: info
+                .namespace
+                .or_else(|| settings.namespace().map(str::to_string)),
+            storage: info.storage.or_else(|| settings.storage().cloned()),
+            settings: Some(settings),
+            ..info
+        }
+    }
+
+    /// Returns a copy laying out JSON snapshots as `json_format` within
+    /// `max_line_width`, where set, over its settings'.
+    pub(crate) fn with_json_style(
+        self,
+        json_format: Option<JsonFormat>,
+        max_line_width: Option<usize>,
+    ) -> Self {
+        if json_format.is_none() && max_line_width.is_none() {
+            return self;
+        }
+        let settings = self.settings.clone().unwrap_or_default();
+        Self {
+            settings: Some(settings.with_json_style(json_format, max_line_width)),
+            ..self
+        }
+    }
+
+    /// The layout of this info's JSON snapshots; see [`crate::json_format`].
+    pub(crate) fn json_style(&self) -> JsonStyle {
+        let settings = self.settings.as_ref();
+        crate::settings::json_style(
+            settings.and_then(SnapshotSettings::json_format),
+            settings.and_then(SnapshotSettings::max_line_width),
+        )
+    }
+
+    /// Returns a copy built under the `SnapshotSettings` entered on this
+    /// thread, stored in their folder if they set one; for constructors not
+    /// given a folder.
+    pub(crate) fn under_entered_settings(self) -> Self {
+        let settings = SnapshotSettings::resolve(None);
+        let folder = settings.as_ref().and_then(|s| s.snapshot_path().cloned());
+        Self {
+            snapshot_folder: folder.unwrap_or(self.snapshot_folder),
+            ..self
+        }
+        .with_settings(settings)
+    }
+
+    /// The expression recorded in the snapshot header, for insta's assertion
+    /// macros; ignored when there is none, as the expression is then omitted.
+    pub(crate) fn expression(&self) -> &str {
+        self.expression.as_deref().unwrap_or_default()
+    }
+
+    /// The file stem insta stores the snapshot `name` under, see
+    /// [`file_stem_of`]. `module_path` is the asserting Rust module's
+    /// `module_path!()`.
+    pub(crate) fn file_stem(&self, module_path: &str, name: &str) -> String {
+        file_stem_of(
+            self.prefix.as_deref(),
+            namespace(module_path).as_deref(),
+            name,
+        )
+    }
+
+    /// The committed snapshot file of `name`; see [`Self::file_stem`].
+    pub(crate) fn snapshot_file(&self, module_path: &str, name: &str) -> PathBuf {
+        self.folder().join(format!(
+            "{}{SNAPSHOT_FILE_SUFFIX}",
+            self.file_stem(module_path, name)
+        ))
+    }
+}
+
+/// The name of the `index`th snapshot asserted under `base`: `base` itself
+/// for the first, `<base>-<index>` after that.
+pub(crate) fn indexed_name(base: &str, index: usize) -> String {
+    if index <= 1 {
+        base.to_string()
+    } else {
+        format!("{base}-{index}")
+    }
+}
+
+/// The index the next snapshot asserted under a name takes, given the
+/// `current` one (0 before the first); see [`SnapshotInfo::snapshot_name`].
+pub(crate) fn next_index(current: usize, allow_duplicates: bool) -> usize {
+    if allow_duplicates && current > 0 {
+        current
+    } else {
+        current + 1
+    }
+}
+
+/// The snapshot namespace of the Rust module `module_path` asserting it:
+/// the crate root is dropped, so plain snapshots have none and the mock
+/// layer's are `mocks`.
+pub(crate) fn namespace(module_path: &str) -> Option<String> {
+    module_path
+        .split_once("::")
+        .map(|(_, module)| module.replace("::", "__"))
+}
+
+/// The file stem insta stores the snapshot `name` under,
+/// `<prefix>__<namespace>__<name>`, leaving out the parts that are `None`. A
+/// stem longer than [`MAX_SNAPSHOT_NAME_LEN`] is cut short in the middle,
+/// where a hash of the whole stem keeps it distinct.
+pub(crate) fn file_stem_of(prefix: Option<&str>, namespace: Option<&str>, name: &str) -> String {
+    let stem = prefix
+        .into_iter()
+        .chain(namespace)
+        .chain([name])
+        .collect::<Vec<_>>()
+        .join("__");
+    shorten(&stem, &stem, KEPT_STEM_TAIL)
 }
 
 impl TryInto<insta::Settings> for &SnapshotInfo {
@@ -180,12 +899,32 @@ impl TryInto<insta::Settings> for &SnapshotInfo {
 
     fn try_into(self) -> PyResult<insta::Settings> {
         let mut settings = insta::Settings::clone_current();
-        settings.set_snapshot_path(self.snapshot_folder());
+        if let Some(storage) = &self.storage {
+            storage.materialize(&self.folder()).map_err(|e| {
+                PyValueError::new_err(format!("Unable to load snapshots from their storage: {e}"))
+            })?;
+        }
+        settings.set_snapshot_path(self.folder());
         settings.set_snapshot_suffix(PYSNAPSHOT_SUFFIX);
-        if let Some(relative_test_file_path) = &self.relative_test_file_path {
-            settings.set_description(Description::new(relative_test_file_path.clone()));
+        // Assertions pass `file_stem`, which carries the prefix instead of
+        // the asserting Rust module.
+        settings.set_prepend_module_to_snapshot(false);
+        match (&self.relative_test_file_path, &self.description) {
+            (Some(relative_test_file_path), description) => settings.set_description(
+                Description::new(relative_test_file_path.clone()).with_text(description.as_deref()),
+            ),
+            (None, Some(description)) => settings.set_description(one_line(description)),
+            (None, None) => {}
+        }
+        if self.node_id.is_none() {
+            warn_if_in_teardown()?;
+        }
+        settings.set_info(&self.provenance());
+        settings.set_omit_expression(self.expression.is_none());
+        settings.set_comparator(Box::new(ReportingComparator));
+        if let Some(snapshot_settings) = &self.settings {
+            snapshot_settings.apply(&mut settings);
         }
-        settings.set_omit_expression(true);
         Ok(settings)
     }
 }
@@ -194,6 +933,12 @@ impl TryInto<insta::Settings> for &SnapshotInfo {
 pub enum RedactionType {
     Sorted,
     Rounded(usize),
+    /// `"hash"`: a short, stable hash of the value.
+    Hash,
+    /// `"type"`: the value's type, and length where it has one.
+    Type,
+    /// `"truncate:N"`: strings cut down to their first `N` characters.
+    Truncate(usize),
     Standard(String),
 }
 
@@ -205,7 +950,19 @@ impl<'source> FromPyObject<'source> for RedactionType {
         } else if let Ok(decimals) = ob.extract::<usize>() {
             Ok(RedactionType::Rounded(decimals))
         } else if let Ok(redaction) = ob.extract::<String>() {
-            Ok(RedactionType::Standard(redaction))
+            match redaction.as_str() {
+                "hash" => Ok(RedactionType::Hash),
+                "type" => Ok(RedactionType::Type),
+                _ => match redaction.strip_prefix("truncate:") {
+                    Some(len) => len.parse().map(RedactionType::Truncate).map_err(|_| {
+                        PyValueError::new_err(format!(
+                            "Invalid redaction {redaction:?}; expected \"truncate:N\" with N \
+                             a number of characters"
+                        ))
+                    }),
+                    None => Ok(RedactionType::Standard(redaction)),
+                },
+            }
         } else {
             Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
                 "Unable to extract RedactionType",
@@ -219,39 +976,267 @@ impl From<RedactionType> for Redaction {
         match value {
             RedactionType::Sorted => sorted_redaction(),
             RedactionType::Rounded(decimals) => rounded_redaction(decimals),
+            RedactionType::Hash => crate::redactions::hash_redaction(),
+            RedactionType::Type => crate::redactions::type_redaction(),
+            RedactionType::Truncate(len) => crate::redactions::truncate_redaction(len),
             RedactionType::Standard(redaction) => redaction.into(),
         }
     }
 }
 
-#[pyclass(unsendable)]
-#[derive(Debug)]
-pub struct PySnapshot(Snapshot);
+/// Checks a snapshot namespace: directory names separated by `/`, which
+/// keep the snapshots within their folder and are valid on every platform.
+pub(crate) fn parse_namespace(namespace: &str) -> PyResult<String> {
+    let valid = namespace.split('/').all(|part| {
+        !matches!(part, "" | "." | "..")
+            && !part
+                .chars()
+                .any(|c| c.is_control() || r#"\:<>|"?*"#.contains(c))
+    });
+    if !valid {
+        return Err(PyValueError::new_err(format!(
+            "Invalid snapshot namespace {namespace:?}: use directory names separated by \"/\""
+        )));
+    }
+    Ok(namespace.to_string())
+}
+
+/// Python's `repr` of `text`.
+pub(crate) fn py_repr(py: Python<'_>, text: &str) -> PyResult<String> {
+    Ok(PyString::new(py, text).repr()?.to_string())
+}
+
+/// The parsed body of a snapshot file. insta's `Snapshot` shares binary
+/// contents through a non-`Send` pointer, so `PySnapshot` keeps owned data
+/// instead and can be used from any Python thread.
+#[derive(Debug, PartialEq)]
+enum SnapshotBody {
+    Text(String),
+    /// `None` when the `.snap` metadata exists but its binary sidecar is missing.
+    Binary(Option<Vec<u8>>),
+    /// A large plain binary sidecar, mapped lazily when contents are read.
+    Mapped(PathBuf),
+}
+
+#[pyclass(frozen)]
+#[derive(Debug, PartialEq)]
+pub struct PySnapshot {
+    /// The `.snap` file, whose header `metadata()` reads.
+    path: PathBuf,
+    body: SnapshotBody,
+    /// Whether the binary sidecar is zstd-compressed (`.zst` extension).
+    compressed: bool,
+    /// Whether the binary sidecar is a pointer into external storage (`.ptr`).
+    external: bool,
+}
 
 #[pymethods]
 impl PySnapshot {
     #[staticmethod]
     pub fn from_file(p: PathBuf) -> PyResult<Self> {
-        Ok(Self(Snapshot::from_file(&p).map_err(|e| {
-            pyo3::exceptions::PyValueError::new_err(format!(
-                "Unable to load snapshot from {p:?}, details: {e}",
-            ))
-        })?))
+        crate::checksum::verify(&p)?;
+        let Some(sidecar) = binary_sidecar(&p) else {
+            return Self::from_insta(&p);
+        };
+        let external = is_pointer_sidecar(&sidecar);
+        // A pointer wraps the stored bytes, so `.zst` sits before `.ptr`.
+        let compressed = if external {
+            is_compressed_sidecar(&sidecar.with_extension(""))
+        } else {
+            is_compressed_sidecar(&sidecar)
+        };
+        let body = if !external && !compressed && should_map(&sidecar) {
+            SnapshotBody::Mapped(sidecar)
+        } else {
+            match std::fs::read(&sidecar) {
+                Ok(items) => SnapshotBody::Binary(Some(items)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => SnapshotBody::Binary(None),
+                Err(e) => {
+                    return Err(PyValueError::new_err(format!(
+                        "Unable to load snapshot from {sidecar:?}, details: {e}",
+                    )))
+                }
+            }
+        };
+        Ok(Self {
+            path: p,
+            body,
+            compressed,
+            external,
+        })
     }
 
-    pub fn contents(&self) -> PyResult<Vec<u8>> {
-        Ok(match self.0.contents() {
-            SnapshotContents::Text(text_snapshot_contents) => {
-                text_snapshot_contents.to_string().as_bytes().to_vec()
+    /// Returns the snapshot body as `bytes`, copied once straight from the
+    /// loaded snapshot. Encrypted text snapshots are decrypted with
+    /// `encryption_key`, falling back to `PYSNAPTEST_KEY`; externally stored
+    /// binary snapshots are fetched and compressed ones decompressed. Large
+    /// plain binary snapshots come back as a memory-mapped `SnapshotBuffer`
+    /// rather than `bytes`.
+    #[pyo3(signature = (encryption_key=None))]
+    pub fn contents(&self, py: Python<'_>, encryption_key: Option<&str>) -> PyResult<PyObject> {
+        let bytes = match &self.body {
+            SnapshotBody::Text(text) => {
+                return Ok(
+                    PyBytes::new(py, self.text(text, encryption_key)?.as_bytes())
+                        .into_any()
+                        .unbind(),
+                )
             }
-            SnapshotContents::Binary(Some(items)) => items.deref().to_owned(),
-            SnapshotContents::Binary(None) => {
+            SnapshotBody::Binary(Some(items)) if !self.external && !self.compressed => {
+                return Ok(PyBytes::new(py, items).into_any().unbind())
+            }
+            SnapshotBody::Binary(Some(items)) => {
+                let resolved;
+                let mut bytes: &[u8] = items;
+                if self.external {
+                    resolved = resolve_pointer(py, bytes)?;
+                    bytes = &resolved;
+                }
+                if self.compressed {
+                    decompress(bytes)?
+                } else {
+                    bytes.to_vec()
+                }
+            }
+            SnapshotBody::Binary(None) => {
                 return Err(pyo3::exceptions::PyValueError::new_err(
                     "Binary snapshot metadata exists but its data file is missing",
                 ))
             }
+            SnapshotBody::Mapped(sidecar) => {
+                let mapped = MappedFile::open(sidecar).map_err(|e| {
+                    PyValueError::new_err(format!("Unable to map snapshot {sidecar:?}: {e}"))
+                })?;
+                return Ok(Py::new(py, SnapshotBuffer::new(mapped))?.into_any());
+            }
+        };
+        Ok(PyBytes::new(py, &bytes).into_any().unbind())
+    }
+
+    /// Returns a text snapshot's body as `str` (decrypted if needed), without
+    /// the `bytes` round trip of `contents().decode()`.
+    #[pyo3(signature = (encryption_key=None))]
+    pub fn contents_text<'py>(
+        &self,
+        py: Python<'py>,
+        encryption_key: Option<&str>,
+    ) -> PyResult<Bound<'py, PyString>> {
+        match &self.body {
+            SnapshotBody::Text(text) => Ok(PyString::new(py, &self.text(text, encryption_key)?)),
+            _ => Err(PyValueError::new_err(
+                "Snapshot is binary; use contents() to read its bytes",
+            )),
+        }
+    }
+
+    /// The `.snap` file the snapshot was loaded from.
+    #[getter(path)]
+    fn py_path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// `"text"` or `"binary"`.
+    #[getter]
+    fn kind(&self) -> &'static str {
+        match self.body {
+            SnapshotBody::Text(_) => "text",
+            _ => "binary",
+        }
+    }
+
+    /// Whether the binary data is stored zstd-compressed.
+    #[getter(compressed)]
+    fn py_compressed(&self) -> bool {
+        self.compressed
+    }
+
+    /// Whether the binary data is stored outside the repository, see
+    /// `configure_storage`.
+    #[getter(external)]
+    fn py_external(&self) -> bool {
+        self.external
+    }
+
+    /// Snapshots are equal when loaded from the same file with the same
+    /// contents.
+    fn __eq__(&self, other: &Bound<'_, PyAny>) -> bool {
+        other
+            .downcast::<PySnapshot>()
+            .is_ok_and(|other| self == other.get())
+    }
+
+    fn __hash__(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.path.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        let mut repr = format!(
+            "PySnapshot(path={}, kind={}",
+            py_repr(py, &self.path.to_string_lossy())?,
+            py_repr(py, self.kind())?
+        );
+        if self.compressed {
+            repr.push_str(", compressed=True");
+        }
+        if self.external {
+            repr.push_str(", external=True");
+        }
+        repr.push(')');
+        Ok(repr)
+    }
+
+    /// Returns the snapshot's `info` metadata as a dict: its provenance
+    /// (`pysnaptest_version`, `python_version`, `test`, `created_at` and
+    /// `git_commit`) and any fields added by the mock layer or
+    /// `allow_duplicates`. Snapshots recorded by older releases have fewer
+    /// fields, or none.
+    pub fn metadata<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let info = PyDict::new(py);
+        for (field, raw) in read_info(&self.path).unwrap_or_default() {
+            // insta writes quoted strings with JSON escapes; others are plain.
+            let value: serde_json::Value =
+                serde_json::from_str(&raw).unwrap_or(serde_json::Value::String(raw));
+            info.set_item(field, pythonize::pythonize(py, &value)?)?;
+        }
+        Ok(info)
+    }
+}
+
+impl PySnapshot {
+    /// Loads a text (or legacy-format) snapshot through insta's own parser.
+    fn from_insta(p: &Path) -> PyResult<Self> {
+        let snapshot = Snapshot::from_file(p).map_err(|e| {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "Unable to load snapshot from {p:?}, details: {e}",
+            ))
+        })?;
+        let body = match snapshot.contents() {
+            SnapshotContents::Text(text) => SnapshotBody::Text(text.to_string()),
+            SnapshotContents::Binary(items) => {
+                SnapshotBody::Binary(items.as_ref().map(|items| items.deref().to_owned()))
+            }
+        };
+        Ok(Self {
+            path: p.to_path_buf(),
+            body,
+            compressed: false,
+            external: false,
         })
     }
+
+    /// Borrows plaintext bodies and only allocates to decrypt.
+    fn text<'a>(&self, text: &'a str, encryption_key: Option<&str>) -> PyResult<Cow<'a, str>> {
+        if is_encrypted(text) {
+            Ok(Cow::Owned(decrypt_if_encrypted(
+                text.to_string(),
+                encryption_key,
+            )?))
+        } else {
+            Ok(Cow::Borrowed(text))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -286,6 +1271,10 @@ mod tests {
             Some("folder_path_override".into()),
             Some("snapshot_name_override".into()),
             false,
+            None,
+            None,
+            None,
+            None,
         )
         .unwrap();
         insta::assert_debug_snapshot!(snapshot_info);
@@ -296,4 +1285,162 @@ mod tests {
         insta::assert_snapshot!(snapshot_info.last_snapshot_name(), @"snapshot_name_override-2");
         insta::assert_snapshot!(snapshot_info.next_snapshot_name(), @"snapshot_name_override-3");
     }
+
+    #[test]
+    fn test_allow_duplicates_claims_the_base_name() {
+        let duplicates = SnapshotInfo::from_pytest(
+            Some("folder_path_override".into()),
+            Some("allow_duplicates_claim".into()),
+            true,
+            None,
+            None,
+            None,
+            None,
+        )
+        .expect("overrides never consult PYTEST_CURRENT_TEST");
+        let unique = SnapshotInfo {
+            allow_duplicates: false,
+            ..duplicates.clone()
+        };
+        insta::assert_snapshot!(duplicates.snapshot_name(), @"allow_duplicates_claim");
+        insta::assert_snapshot!(duplicates.snapshot_name(), @"allow_duplicates_claim");
+        insta::assert_snapshot!(unique.snapshot_name(), @"allow_duplicates_claim-2");
+        insta::assert_snapshot!(duplicates.snapshot_name(), @"allow_duplicates_claim-2");
+    }
+
+    #[test]
+    fn test_counters_are_scoped_by_folder() {
+        let api = SnapshotInfo::manual("tests/api/snapshots".into(), "scoped".into(), false, None)
+            .expect("no prefix is valid");
+        let web = SnapshotInfo {
+            snapshot_folder: "tests/web/snapshots".into(),
+            ..api.clone()
+        };
+        insta::assert_snapshot!(api.snapshot_name(), @"scoped");
+        insta::assert_snapshot!(web.snapshot_name(), @"scoped");
+        insta::assert_snapshot!(api.snapshot_name(), @"scoped-2");
+        insta::assert_snapshot!(web.last_snapshot_name(), @"scoped");
+    }
+
+    #[test]
+    fn test_snapshot_file_stem() {
+        let info = SnapshotInfo::manual("snapshots".into(), "name".into(), false, None)
+            .expect("no prefix is valid");
+        let module = SnapshotInfo {
+            module: Some("test_api".into()),
+            ..info.clone()
+        };
+        let stem = |info: &SnapshotInfo, strategy: Option<&str>, module_path: &str| {
+            info.with_prefix(strategy)
+                .expect("valid strategy")
+                .file_stem(module_path, "test_api_test_get")
+        };
+        insta::assert_snapshot!(stem(&info, None, "pysnaptest"), @"test_api_test_get");
+        insta::assert_snapshot!(stem(&info, Some("module"), "pysnaptest"), @"test_api_test_get");
+        insta::assert_snapshot!(stem(&module, Some("module"), "pysnaptest"), @"test_api__test_api_test_get");
+        insta::assert_snapshot!(stem(&module, Some("module"), "pysnaptest::mocks"), @"test_api__mocks__test_api_test_get");
+        insta::assert_snapshot!(stem(&module, Some("none"), "pysnaptest::mocks"), @"mocks__test_api_test_get");
+        insta::assert_snapshot!(stem(&module, Some("pkg.api"), "pysnaptest"), @"pkg__api__test_api_test_get");
+        assert!(module.with_prefix(Some("")).is_err());
+    }
+
+    #[test]
+    fn test_naming_is_pure() {
+        assert_eq!([0, 1, 2].map(|i| next_index(i, false)), [1, 2, 3]);
+        assert_eq!([0, 1, 2].map(|i| next_index(i, true)), [1, 1, 2]);
+        insta::assert_snapshot!(indexed_name("test_get", 1), @"test_get");
+        insta::assert_snapshot!(indexed_name("test_get", 3), @"test_get-3");
+        assert_eq!(namespace("pysnaptest"), None);
+        assert_eq!(namespace("pysnaptest::mocks").as_deref(), Some("mocks"));
+        insta::assert_snapshot!(file_stem_of(None, None, "test_get"), @"test_get");
+        insta::assert_snapshot!(file_stem_of(Some("test_api"), Some("mocks"), "test_get-2"), @"test_api__mocks__test_get-2");
+
+        let name = format!(
+            "test_{}_fetch-0123abcd-2-request",
+            "x".repeat(MAX_SNAPSHOT_NAME_LEN)
+        );
+        let stem = file_stem_of(Some("test_api"), Some("mocks"), &name);
+        assert!(stem.len() <= MAX_SNAPSHOT_NAME_LEN);
+        assert!(stem.starts_with("test_api__mocks__test_xxx"));
+        assert!(stem.ends_with("_fetch-0123abcd-2-request"));
+        assert_ne!(
+            stem,
+            file_stem_of(Some("test_api"), Some("mocks"), &name.replace("-2-", "-3-"))
+        );
+    }
+
+    #[test]
+    fn test_provenance_timestamp() {
+        use crate::provenance::utc_timestamp;
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let at = |secs| utc_timestamp(UNIX_EPOCH + Duration::from_secs(secs));
+        insta::assert_snapshot!(at(0), @"1970-01-01T00:00:00Z");
+        insta::assert_snapshot!(at(951_827_696), @"2000-02-29T12:34:56Z");
+        insta::assert_snapshot!(at(4_107_542_399), @"2100-02-28T23:59:59Z");
+    }
+
+    #[test]
+    fn test_sanitize_snapshot_name() {
+        insta::assert_snapshot!(
+            sanitize_snapshot_name("test_x_test_load[data/in:put.json-\"a\"  |  b?*]"),
+            @"test_x_test_load[datainput.json-a b]"
+        );
+        insta::assert_snapshot!(
+            sanitize_snapshot_name("test_x_TestApi::test_get"),
+            @"test_x_TestApi.test_get"
+        );
+        let long = format!("test_x_test_repr[{}]", "{'k': 1}, ".repeat(40));
+        let sanitized = sanitize_snapshot_name(&long);
+        assert!(sanitized.len() <= MAX_SNAPSHOT_NAME_LEN);
+        assert_eq!(sanitized, sanitize_snapshot_name(&long));
+        assert_ne!(sanitized, sanitize_snapshot_name(&long.replace('1', "2")));
+    }
+
+    #[test]
+    fn test_strip_verbatim_prefix() {
+        let stripped = |path: &str| strip_verbatim_prefix(PathBuf::from(path));
+        assert_eq!(
+            stripped(r"\\?\C:\repo\tests"),
+            PathBuf::from(r"C:\repo\tests")
+        );
+        assert_eq!(
+            stripped(r"\\?\UNC\server\share\tests"),
+            PathBuf::from(r"\\server\share\tests")
+        );
+        // Verbatim paths without a drive letter have no plain equivalent.
+        assert_eq!(
+            stripped(r"\\?\Volume{1234}\tests"),
+            PathBuf::from(r"\\?\Volume{1234}\tests")
+        );
+        assert_eq!(stripped("/repo/tests"), PathBuf::from("/repo/tests"));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_windows_snapshot_info_has_plain_paths() {
+        let info: SnapshotInfo = PytestInfo {
+            test_path: "src\\common.rs".to_string(),
+            test_name: "test_a".to_string(),
+            stage: None,
+        }
+        .try_into()
+        .expect("src\\common.rs exists relative to the crate root");
+        let folder = info.snapshot_folder.to_string_lossy().into_owned();
+        assert!(!folder.starts_with(r"\\?\"), "{folder}");
+        assert!(folder.ends_with(r"src\snapshots"), "{folder}");
+        assert_eq!(
+            info.relative_test_file_path.as_deref(),
+            Some("src/common.rs")
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_windows_portable_path() {
+        assert_eq!(
+            portable_path(Path::new(r"tests\unit\test_a.py")),
+            "tests/unit/test_a.py"
+        );
+    }
 }
@@ -0,0 +1,51 @@
+//! Optional zstd compression for binary snapshots.
+//!
+//! A compressed snapshot is an ordinary insta binary snapshot whose extension
+//! gains a `.zst` suffix (`@pysnap.snap.parquet.zst`), so insta still owns
+//! writing, comparing and reviewing it and the sidecar is self-describing.
+//! `PySnapshot.contents()` recognises the suffix and decompresses
+//! transparently.
+//!
+//! insta compares the stored bytes, so compression must be stable: the same
+//! input has to produce the same file. zstd is deterministic for a fixed level
+//! and library version, and [`compress_stable`] additionally reuses the
+//! committed bytes whenever they already decompress to the new data, so a zstd
+//! upgrade that changes the encoder's output does not churn every snapshot.
+
+use std::path::Path;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::PyResult;
+
+/// Extension suffix marking a zstd-compressed binary snapshot sidecar.
+pub(crate) const COMPRESSED_EXTENSION: &str = "zst";
+
+/// Compression level: a good ratio for recorded blobs while staying fast
+/// enough for multi-MB snapshots.
+const LEVEL: i32 = 9;
+
+pub(crate) fn compress(data: &[u8]) -> PyResult<Vec<u8>> {
+    zstd::encode_all(data, LEVEL)
+        .map_err(|e| PyValueError::new_err(format!("Unable to compress snapshot: {e}")))
+}
+
+pub(crate) fn decompress(data: &[u8]) -> PyResult<Vec<u8>> {
+    zstd::decode_all(data)
+        .map_err(|e| PyValueError::new_err(format!("Unable to decompress snapshot: {e}")))
+}
+
+/// Compresses `data`, returning `committed` verbatim when it already
+/// decompresses to exactly `data`.
+pub(crate) fn compress_stable(data: &[u8], committed: Option<&[u8]>) -> PyResult<Vec<u8>> {
+    if let Some(committed) = committed {
+        if decompress(committed).is_ok_and(|previous| previous == data) {
+            return Ok(committed.to_vec());
+        }
+    }
+    compress(data)
+}
+
+/// Reports whether a binary sidecar path holds zstd-compressed data.
+pub(crate) fn is_compressed_sidecar(sidecar: &Path) -> bool {
+    sidecar.extension().and_then(|e| e.to_str()) == Some(COMPRESSED_EXTENSION)
+}
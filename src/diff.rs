@@ -0,0 +1,351 @@
+//! The diff of a text snapshot mismatch, carried by the raised error.
+//!
+//! insta prints its own diff to stdout when an assertion fails, which lands
+//! in pytest's captured output, far from the failure in a CI log. Every
+//! assertion is therefore run with a [`ReportingComparator`]: when insta
+//! finds the committed and the new contents of a text snapshot differ, it
+//! records both, and the failure raised by [`crate::panic`] renders the diff
+//! into its message, laid out by the process-wide [`DiffOptions`]:
+//!
+//! * `context_lines` unchanged lines around each change (3 by default);
+//! * line-level changes, or `word_diff` highlighting the changed words of
+//!   each changed line (`[-old-]{+new+}` without color);
+//! * at most `max_lines` lines, with a notice of how many were left out;
+//! * ANSI colors, unless `NO_COLOR` is set or stdout is not a terminal
+//!   (`FORCE_COLOR` forces them); `color` overrides the detection.
+//!
+//! With `html`, the failure also writes a side-by-side HTML page of the diff
+//! next to the pending `.snap.new` (see [`render_html`]), for reviewers to
+//! open from CI artifacts in a browser.
+
+use std::cell::RefCell;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+use insta::internals::SnapshotContents;
+use insta::{Comparator, DefaultComparator, Settings, Snapshot};
+use similar::{ChangeTag, DiffOp, TextDiff};
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const EMPHASIS: &str = "\x1b[1;4m";
+const NO_EMPHASIS: &str = "\x1b[22;24m";
+const RESET: &str = "\x1b[0m";
+
+/// How the diff of a mismatch is rendered; see the module documentation.
+#[derive(Debug, Clone)]
+pub(crate) struct DiffOptions {
+    pub(crate) context_lines: usize,
+    pub(crate) word_diff: bool,
+    pub(crate) max_lines: Option<usize>,
+    pub(crate) color: Option<bool>,
+    pub(crate) html: bool,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            context_lines: 3,
+            word_diff: false,
+            max_lines: None,
+            color: None,
+            html: false,
+        }
+    }
+}
+
+/// A text snapshot found not to match.
+pub(crate) struct Mismatch {
+    /// The committed contents.
+    pub(crate) old: String,
+    /// The new contents.
+    pub(crate) new: String,
+    /// The committed snapshot file.
+    pub(crate) snapshot: PathBuf,
+}
+
+/// Where the side-by-side HTML diff of `snapshot` is written: next to its
+/// pending `.snap.new`.
+pub(crate) fn html_diff_path(snapshot: &Path) -> PathBuf {
+    let mut path = snapshot.as_os_str().to_owned();
+    path.push(".new.html");
+    path.into()
+}
+
+thread_local! {
+    /// The last text snapshot found not to match on this thread, taken by
+    /// the failure reporting it.
+    static MISMATCH: RefCell<Option<Mismatch>> = const { RefCell::new(None) };
+    /// The file of the last snapshot of any kind found not to match on this
+    /// thread.
+    static MISMATCHED_FILE: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+    /// Whether a mismatch on this thread stops the assertion with
+    /// [`RefusedMismatch`] before insta writes anything.
+    static REFUSING: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// The panic payload of a mismatch refused by [`refuse_mismatches`].
+pub(crate) struct RefusedMismatch;
+
+/// Makes a mismatch on this thread, from now on (`refuse`) or no longer,
+/// panic with [`RefusedMismatch`] once recorded, before insta updates the
+/// snapshot: read-only snapshots insta would rewrite in place are never
+/// written at all.
+pub(crate) fn refuse_mismatches(refuse: bool) {
+    REFUSING.with(|flag| flag.set(refuse));
+}
+
+/// insta's default comparison, recording the snapshots that do not match
+/// (with their contents, for text snapshots).
+#[derive(Clone)]
+pub(crate) struct ReportingComparator;
+
+impl ReportingComparator {
+    fn record(reference: &Snapshot, test: &Snapshot, matched: bool) -> bool {
+        if matched {
+            return true;
+        }
+        // Comparisons run inside the assertion's settings, whose snapshot
+        // path and the test snapshot's name make up insta's file name.
+        let file_name = format!("{}.snap", test.snapshot_name().unwrap_or_default());
+        let snapshot = Settings::clone_current().snapshot_path().join(file_name);
+        MISMATCHED_FILE.with(|slot| *slot.borrow_mut() = Some(snapshot.clone()));
+        if let (SnapshotContents::Text(old), SnapshotContents::Text(new)) =
+            (reference.contents(), test.contents())
+        {
+            let mismatch = Mismatch {
+                old: old.to_string(),
+                new: new.to_string(),
+                snapshot,
+            };
+            MISMATCH.with(|slot| *slot.borrow_mut() = Some(mismatch));
+        }
+        if REFUSING.with(std::cell::Cell::get) {
+            std::panic::panic_any(RefusedMismatch);
+        }
+        false
+    }
+}
+
+impl Comparator for ReportingComparator {
+    fn matches(&self, reference: &Snapshot, test: &Snapshot) -> bool {
+        Self::record(reference, test, DefaultComparator.matches(reference, test))
+    }
+
+    fn matches_fully(&self, reference: &Snapshot, test: &Snapshot) -> bool {
+        Self::record(
+            reference,
+            test,
+            DefaultComparator.matches_fully(reference, test),
+        )
+    }
+
+    fn dyn_clone(&self) -> Box<dyn Comparator> {
+        Box::new(self.clone())
+    }
+}
+
+/// Forgets the mismatch recorded on this thread, before an assertion runs.
+pub(crate) fn clear_mismatch() {
+    MISMATCH.with(|mismatch| mismatch.borrow_mut().take());
+    MISMATCHED_FILE.with(|file| file.borrow_mut().take());
+}
+
+/// The file of the snapshot the last assertion on this thread found not to
+/// match, if any, whatever its kind.
+pub(crate) fn take_mismatched_file() -> Option<PathBuf> {
+    MISMATCHED_FILE.with(|file| file.borrow_mut().take())
+}
+
+/// The text snapshot the last assertion on this thread found not to match,
+/// if any.
+pub(crate) fn take_mismatch() -> Option<Mismatch> {
+    MISMATCH.with(|mismatch| mismatch.borrow_mut().take())
+}
+
+fn env_set(name: &str) -> bool {
+    std::env::var_os(name).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+/// Whether diffs are colored: `color` when given, otherwise unless
+/// `NO_COLOR` is set, and then if `FORCE_COLOR` is set or stdout is a
+/// terminal.
+fn use_color(color: Option<bool>) -> bool {
+    color.unwrap_or_else(|| {
+        !env_set("NO_COLOR") && (env_set("FORCE_COLOR") || std::io::stdout().is_terminal())
+    })
+}
+
+fn paint(line: String, style: &str, color: bool) -> String {
+    if color && !style.is_empty() {
+        format!("{style}{line}{RESET}")
+    } else {
+        line
+    }
+}
+
+/// Renders the diff of the `old` (committed) and `new` contents of a text
+/// snapshot as laid out by `options`.
+pub(crate) fn render(old: &str, new: &str, options: &DiffOptions) -> String {
+    let color = use_color(options.color);
+    let diff = TextDiff::from_lines(old, new);
+    let mut lines = vec![
+        paint("--- committed".to_string(), RED, color),
+        paint("+++ new".to_string(), GREEN, color),
+    ];
+    for group in diff.grouped_ops(options.context_lines) {
+        let (Some(first), Some(last)) = (group.first(), group.last()) else {
+            continue;
+        };
+        let (old_start, new_start) = (first.old_range().start, first.new_range().start);
+        let hunk = format!(
+            "@@ -{},{} +{},{} @@",
+            old_start + 1,
+            last.old_range().end - old_start,
+            new_start + 1,
+            last.new_range().end - new_start
+        );
+        lines.push(paint(hunk, CYAN, color));
+        for op in &group {
+            for change in diff.iter_inline_changes(op) {
+                let (sign, style, open, close) = match change.tag() {
+                    ChangeTag::Delete => ('-', RED, "[-", "-]"),
+                    ChangeTag::Insert => ('+', GREEN, "{+", "+}"),
+                    ChangeTag::Equal => (' ', "", "", ""),
+                };
+                let mut line = String::from(sign);
+                for (emphasized, value) in change.iter_strings_lossy() {
+                    let value = value.trim_end_matches(['\n', '\r']);
+                    if value.is_empty() {
+                        continue;
+                    }
+                    match (emphasized && options.word_diff, color) {
+                        (false, _) => line.push_str(value),
+                        (true, true) => line.push_str(&format!("{EMPHASIS}{value}{NO_EMPHASIS}")),
+                        (true, false) => line.push_str(&format!("{open}{value}{close}")),
+                    }
+                }
+                lines.push(paint(line, style, color));
+            }
+        }
+    }
+    if let Some(max_lines) = options.max_lines.filter(|&max| lines.len() > max) {
+        let hidden = lines.len() - max_lines;
+        lines.truncate(max_lines);
+        lines.push(format!(
+            "... {hidden} more diff line(s) not shown; raise max_lines with \
+             set_diff_options to see them"
+        ));
+    }
+    lines.join("\n")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// One side of a row of the side-by-side diff: a line number and the
+/// line's HTML, changed words in `<mark>`.
+type Cell = (usize, String);
+
+/// The cells of the old and the new side of `op`.
+fn cells<'a>(diff: &'a TextDiff<'a, 'a, 'a, str>, op: &DiffOp) -> (Vec<Cell>, Vec<Cell>) {
+    let (mut old, mut new) = (Vec::new(), Vec::new());
+    for change in diff.iter_inline_changes(op) {
+        let mut html = String::new();
+        for (emphasized, value) in change.iter_strings_lossy() {
+            let value = escape_html(value.trim_end_matches(['\n', '\r']));
+            if emphasized && change.tag() != ChangeTag::Equal && !value.is_empty() {
+                html.push_str(&format!("<mark>{value}</mark>"));
+            } else {
+                html.push_str(&value);
+            }
+        }
+        if let Some(index) = change.old_index() {
+            old.push((index + 1, html.clone()));
+        }
+        if let Some(index) = change.new_index() {
+            new.push((index + 1, html));
+        }
+    }
+    (old, new)
+}
+
+fn side(cell: Option<&Cell>, class: &str) -> String {
+    match cell {
+        Some((number, html)) => {
+            format!("<td class=\"n\">{number}</td><td class=\"{class}\">{html}</td>")
+        }
+        None => "<td class=\"n\"></td><td class=\"empty\"></td>".to_string(),
+    }
+}
+
+/// Renders the diff of a mismatch as a standalone HTML page: the committed
+/// contents on the left, the new ones on the right, changed lines paired up
+/// and their changed words highlighted, with `options.context_lines`
+/// unchanged lines around each change.
+pub(crate) fn render_html(
+    snapshot_name: &str,
+    mismatch: &Mismatch,
+    options: &DiffOptions,
+) -> String {
+    let diff = TextDiff::from_lines(&mismatch.old, &mismatch.new);
+    let mut rows = Vec::new();
+    for group in diff.grouped_ops(options.context_lines) {
+        if !rows.is_empty() {
+            rows.push("<tr class=\"gap\"><td colspan=\"4\">&#8942;</td></tr>".to_string());
+        }
+        for op in &group {
+            let (old, new) = cells(&diff, op);
+            let (old_class, new_class) = match op {
+                DiffOp::Equal { .. } => ("equal", "equal"),
+                _ => ("delete", "insert"),
+            };
+            for row in 0..old.len().max(new.len()) {
+                rows.push(format!(
+                    "<tr>{}{}</tr>",
+                    side(old.get(row), old_class),
+                    side(new.get(row), new_class)
+                ));
+            }
+        }
+    }
+    let title = escape_html(&format!("Snapshot '{snapshot_name}' did not match"));
+    let snapshot = escape_html(&mismatch.snapshot.display().to_string());
+    format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; margin: 1em; }}
+table {{ border-collapse: collapse; width: 100%; table-layout: fixed; }}
+td {{ font-family: monospace; white-space: pre-wrap; vertical-align: top; padding: 0 .5em; }}
+th {{ text-align: left; padding: .3em .5em; }}
+.n {{ width: 3em; color: #888; text-align: right; user-select: none; }}
+.delete {{ background: #ffebe9; }}
+.insert {{ background: #e6ffec; }}
+.delete mark {{ background: #ffc1bc; }}
+.insert mark {{ background: #abf2bc; }}
+.empty {{ background: #f6f8fa; }}
+.gap td {{ color: #888; text-align: center; background: #f6f8fa; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p><code>{snapshot}</code></p>
+<table>
+<tr><th colspan=\"2\">committed</th><th colspan=\"2\">new</th></tr>
+{}
+</table>
+</body>
+</html>
+",
+        rows.join("\n")
+    )
+}
@@ -0,0 +1,118 @@
+//! Checking and collapsing of repeated `allow_duplicates` assertions.
+//!
+//! With `allow_duplicates`, every assertion of a snapshot name within a test
+//! is compared against one stored snapshot, so all of them must have the same
+//! contents. Only the first is asserted through insta; each later one is
+//! compared against it in memory. A divergent occurrence fails without
+//! touching the snapshot, even in update modes where insta would otherwise
+//! let the last occurrence overwrite the first. A matching occurrence is
+//! collapsed into the stored snapshot, whose `info` metadata counts the
+//! assertions as `repetitions: N`. Occurrences are counted in memory and the
+//! count is written once, when the test ends (see [`settle_repetitions`]),
+//! and only if it differs from the stored one: a passing rerun of an
+//! unchanged test leaves the snapshot as it was.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use pyo3::exceptions::PyAssertionError;
+use pyo3::prelude::*;
+
+use crate::mocks::{read_info_field, write_info_field};
+use crate::provenance::current_test_node_id;
+
+/// The `info` key counting the assertions of a snapshot.
+const REPETITIONS_FIELD: &str = "repetitions";
+
+/// The assertions of one snapshot within one test.
+struct Occurrences {
+    test: Option<String>,
+    /// Rendered contents of the first assertion.
+    contents: String,
+    count: usize,
+    /// The count stored in the snapshot before the test, if any.
+    stored: Option<String>,
+}
+
+impl Occurrences {
+    /// Writes the count to the `snapshot`, unless it is the stored one.
+    fn settle(self, snapshot: &Path) -> PyResult<()> {
+        let count = (self.count > 1).then(|| self.count.to_string());
+        if count == self.stored {
+            return Ok(());
+        }
+        write_info_field(snapshot, REPETITIONS_FIELD, count.as_deref())
+    }
+}
+
+static OCCURRENCES: Lazy<Mutex<HashMap<PathBuf, Occurrences>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Records an `allow_duplicates` assertion of `contents` against the
+/// committed snapshot at `snapshot`, returning whether an earlier occurrence
+/// in this test already asserted it, so it needs no assertion of its own.
+///
+/// Fails with an `AssertionError` diffing the first occurrence against this
+/// one when their contents diverge. The first occurrence of a test settles
+/// the count another test left unsettled.
+pub(crate) fn collapse(snapshot_name: &str, snapshot: &Path, contents: &str) -> PyResult<bool> {
+    let test = current_test_node_id();
+    let mut occurrences = OCCURRENCES.lock().unwrap_or_else(|x| x.into_inner());
+    match occurrences.get_mut(snapshot) {
+        Some(seen) if seen.test == test => {
+            if seen.contents != contents {
+                return Err(PyAssertionError::new_err(format!(
+                    "snapshot '{snapshot_name}' was asserted with different contents earlier \
+                     in this test; with allow_duplicates every occurrence must match one \
+                     stored snapshot.\n\n{}",
+                    crate::unified_diff(&seen.contents, contents, "first occurrence", "this one")
+                )));
+            }
+            seen.count += 1;
+            Ok(true)
+        }
+        _ => {
+            let first = Occurrences {
+                test,
+                contents: contents.to_string(),
+                count: 1,
+                stored: read_info_field(snapshot, REPETITIONS_FIELD),
+            };
+            if let Some(unsettled) = occurrences.insert(snapshot.to_path_buf(), first) {
+                unsettled.settle(snapshot)?;
+            }
+            Ok(false)
+        }
+    }
+}
+
+/// Writes the `repetitions` counted for the `allow_duplicates` snapshots of
+/// the test `node_id` (`None` outside pytest) where they changed, or only
+/// forgets them unless it `passed`; called by the pytest plugin as each test
+/// ends. With read-only snapshots, a changed count raises
+/// `SnapshotReadOnlyError`.
+#[pyfunction]
+#[pyo3(signature = (node_id, passed=true))]
+pub fn settle_repetitions(node_id: Option<String>, passed: bool) -> PyResult<()> {
+    let finished: Vec<(PathBuf, Occurrences)> = {
+        let mut occurrences = OCCURRENCES.lock().unwrap_or_else(|x| x.into_inner());
+        let paths: Vec<PathBuf> = occurrences
+            .iter()
+            .filter(|(_, seen)| seen.test == node_id)
+            .map(|(path, _)| path.clone())
+            .collect();
+        paths
+            .into_iter()
+            .filter_map(|path| occurrences.remove(&path).map(|seen| (path, seen)))
+            .collect()
+    };
+    if !passed {
+        return Ok(());
+    }
+    for (snapshot, seen) in finished {
+        seen.settle(&snapshot)?;
+    }
+    Ok(())
+}
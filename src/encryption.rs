@@ -0,0 +1,284 @@
+//! Transparent encryption of text and JSON snapshots at rest.
+//!
+//! When a key is supplied (the `encryption_key` argument, falling back to the
+//! `PYSNAPTEST_KEY` environment variable) the rendered snapshot body is
+//! encrypted with AES-256-GCM before insta sees it, and decrypted again when a
+//! snapshot is loaded (`PySnapshot.contents`, mock replay).
+//!
+//! The key is usually a passphrase, so it is stretched with Argon2id first;
+//! the encryption key and the key of the synthetic IV below are then expanded
+//! from it separately with HKDF-SHA256, each under its own label. Argon2 is
+//! slow on purpose, so the keys of a secret are derived once per process.
+//!
+//! insta still owns comparison and writing, which only works if the same
+//! plaintext always produces the same ciphertext. The nonce is therefore
+//! derived from the plaintext (HMAC-SHA256 under the IV key, truncated to 96
+//! bits), a synthetic-IV construction that reveals nothing beyond whether two
+//! snapshots are equal. On a mismatch the committed snapshot is decrypted so
+//! the `AssertionError` carries a readable diff instead of two blobs of base64.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use insta::internals::SnapshotContents;
+use insta::Snapshot;
+use once_cell::sync::Lazy;
+use pyo3::exceptions::PyValueError;
+use pyo3::PyResult;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::redactions::{apply_redactions, json_to_content};
+use crate::RedactionType;
+
+/// Environment variable holding the key used when no explicit key is passed.
+pub const ENCRYPTION_KEY_ENV: &str = "PYSNAPTEST_KEY";
+
+/// Marks an encrypted snapshot body; the rest is base64 of `nonce || ciphertext`.
+const ENCRYPTED_PREFIX: &str = "pysnaptest-encrypted:v2:";
+
+const NONCE_LEN: usize = 12;
+
+/// The Argon2 salt. Ciphertexts must be deterministic, so it is fixed; it
+/// only keeps the stretched secret specific to pysnaptest.
+const ARGON2_SALT: &[u8] = b"pysnaptest snapshot encryption";
+
+/// The HKDF label of the AES-256-GCM key.
+const CIPHER_KEY_INFO: &[u8] = b"pysnaptest v2 aes-256-gcm key";
+
+/// The HKDF label of the HMAC key deriving synthetic nonces.
+const NONCE_KEY_INFO: &[u8] = b"pysnaptest v2 synthetic iv key";
+
+/// The keys derived from each secret used so far, by the SHA-256 of the
+/// secret.
+static DERIVED: Lazy<Mutex<HashMap<[u8; 32], SnapshotKey>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The keys derived from the user's secret, so any passphrase of any length
+/// can be used as `PYSNAPTEST_KEY`.
+#[derive(Clone)]
+pub(crate) struct SnapshotKey {
+    cipher: [u8; 32],
+    nonce: [u8; 32],
+}
+
+fn key_error(e: impl std::fmt::Display) -> pyo3::PyErr {
+    PyValueError::new_err(format!("Invalid encryption key: {e}"))
+}
+
+impl SnapshotKey {
+    fn from_secret(secret: &str) -> PyResult<Self> {
+        let digest: [u8; 32] = Sha256::digest(secret.as_bytes()).into();
+        let mut derived = DERIVED.lock().unwrap_or_else(|x| x.into_inner());
+        if let Some(key) = derived.get(&digest) {
+            return Ok(key.clone());
+        }
+        let mut stretched = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(secret.as_bytes(), ARGON2_SALT, &mut stretched)
+            .map_err(key_error)?;
+        let hkdf = Hkdf::<Sha256>::new(None, &stretched);
+        let mut key = Self {
+            cipher: [0u8; 32],
+            nonce: [0u8; 32],
+        };
+        hkdf.expand(CIPHER_KEY_INFO, &mut key.cipher)
+            .map_err(key_error)?;
+        hkdf.expand(NONCE_KEY_INFO, &mut key.nonce)
+            .map_err(key_error)?;
+        derived.insert(digest, key.clone());
+        Ok(key)
+    }
+
+    /// Resolves the key from `explicit`, falling back to `PYSNAPTEST_KEY`.
+    /// Returns `None` (no encryption) when neither is set or both are empty.
+    pub(crate) fn resolve(explicit: Option<&str>) -> PyResult<Option<Self>> {
+        explicit
+            .map(str::to_string)
+            .or_else(|| std::env::var(ENCRYPTION_KEY_ENV).ok())
+            .filter(|secret| !secret.is_empty())
+            .map(|secret| Self::from_secret(&secret))
+            .transpose()
+    }
+
+    fn synthetic_nonce(&self, plaintext: &[u8]) -> PyResult<[u8; NONCE_LEN]> {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.nonce).map_err(key_error)?;
+        mac.update(plaintext);
+        let digest = mac.finalize().into_bytes();
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce.copy_from_slice(&digest[..NONCE_LEN]);
+        Ok(nonce)
+    }
+
+    /// Encrypts `plaintext` deterministically into a single-line snapshot body.
+    pub(crate) fn encrypt(&self, plaintext: &str) -> PyResult<String> {
+        let nonce = self.synthetic_nonce(plaintext.as_bytes())?;
+        let ciphertext = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.cipher))
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_bytes())
+            .map_err(|e| PyValueError::new_err(format!("Unable to encrypt snapshot: {e}")))?;
+        let mut payload = nonce.to_vec();
+        payload.extend(ciphertext);
+        Ok(format!("{ENCRYPTED_PREFIX}{}", STANDARD.encode(payload)))
+    }
+
+    /// Decrypts a body produced by [`SnapshotKey::encrypt`].
+    pub(crate) fn decrypt(&self, body: &str) -> PyResult<String> {
+        let encoded = body
+            .trim()
+            .strip_prefix(ENCRYPTED_PREFIX)
+            .ok_or_else(|| PyValueError::new_err("Snapshot body is not encrypted"))?;
+        let payload = STANDARD
+            .decode(encoded)
+            .map_err(|e| PyValueError::new_err(format!("Corrupt encrypted snapshot: {e}")))?;
+        if payload.len() < NONCE_LEN {
+            return Err(PyValueError::new_err(
+                "Corrupt encrypted snapshot: payload too short",
+            ));
+        }
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let plaintext = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.cipher))
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                PyValueError::new_err(
+                    "Unable to decrypt snapshot: wrong encryption key or tampered contents",
+                )
+            })?;
+        String::from_utf8(plaintext)
+            .map_err(|e| PyValueError::new_err(format!("Decrypted snapshot is not UTF-8: {e}")))
+    }
+}
+
+/// Whether `PYSNAPTEST_KEY` asks for snapshots to be encrypted.
+pub(crate) fn key_configured() -> bool {
+    std::env::var_os(ENCRYPTION_KEY_ENV).is_some_and(|secret| !secret.is_empty())
+}
+
+/// Refuses a `kind` snapshot, which cannot be encrypted, while
+/// `PYSNAPTEST_KEY` is set, instead of writing it in plaintext.
+pub(crate) fn refuse_unencrypted(kind: &str, snapshot_name: &str) -> PyResult<()> {
+    match key_configured() {
+        true => Err(PyValueError::new_err(format!(
+            "{kind} snapshot '{snapshot_name}' cannot be encrypted, but {ENCRYPTION_KEY_ENV} \
+             is set; unset it to store this snapshot in plaintext"
+        ))),
+        false => Ok(()),
+    }
+}
+
+/// Reports whether a snapshot body was written by [`SnapshotKey::encrypt`].
+pub(crate) fn is_encrypted(body: &str) -> bool {
+    body.trim_start().starts_with(ENCRYPTED_PREFIX)
+}
+
+/// Returns `body` unchanged when it is plaintext, or decrypts it with the key
+/// resolved from `explicit`/`PYSNAPTEST_KEY`. Used wherever a snapshot is read
+/// back so encryption stays transparent to callers.
+pub(crate) fn decrypt_if_encrypted(body: String, explicit: Option<&str>) -> PyResult<String> {
+    if !is_encrypted(&body) {
+        return Ok(body);
+    }
+    SnapshotKey::resolve(explicit)?
+        .ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "Snapshot is encrypted but no key was given; set {ENCRYPTION_KEY_ENV} \
+                 or pass encryption_key"
+            ))
+        })?
+        .decrypt(&body)
+}
+
+/// Renders a JSON value as the pretty-printed text that gets encrypted, with
+/// `redactions` applied first (insta cannot redact inside ciphertext).
+pub(crate) fn render_json<T: Serialize>(
+    value: &T,
+    redactions: Option<&HashMap<String, RedactionType>>,
+) -> PyResult<String> {
+    let json = serde_json::to_value(value)
+        .map_err(|e| PyValueError::new_err(format!("Unable to serialize snapshot: {e}")))?;
+    let mut content = json_to_content(&json);
+    if let Some(redactions) = redactions {
+        content = apply_redactions(content, redactions)?;
+    }
+    serde_json::to_string_pretty(&content)
+        .map_err(|e| PyValueError::new_err(format!("Unable to serialize snapshot: {e}")))
+}
+
+/// Loads and decrypts the committed snapshot at `path`, if there is one that
+/// `key` can read. Anything else (missing, plaintext, other key) yields `None`.
+pub(crate) fn read_committed_plaintext(path: &Path, key: &SnapshotKey) -> Option<String> {
+    let snapshot = Snapshot::from_file(path).ok()?;
+    match snapshot.contents() {
+        SnapshotContents::Text(text) => {
+            let body = text.to_string();
+            if is_encrypted(&body) {
+                key.decrypt(&body).ok()
+            } else {
+                Some(body)
+            }
+        }
+        SnapshotContents::Binary(_) => None,
+    }
+}
+
+/// Describes an encrypted snapshot mismatch, with a diff of the decrypted
+/// committed value against the new plaintext.
+pub(crate) fn mismatch_message(snapshot_name: &str, previous: Option<&str>, new: &str) -> String {
+    let base = format!(
+        "encrypted snapshot '{snapshot_name}' did not match the stored value (decrypted diff \
+         below). Update the snapshot if this change is intentional."
+    );
+    match previous {
+        Some(previous) if previous == new => format!(
+            "encrypted snapshot '{snapshot_name}' holds the same value, stored unencrypted. \
+             Update the snapshot to encrypt it."
+        ),
+        Some(previous) => format!(
+            "{base}\n\n{}",
+            crate::unified_diff(previous, new, "committed", "new")
+        ),
+        None => base,
+    }
+}
+
+/// Asserts `plaintext` as an encrypted text snapshot named `snapshot_name`.
+///
+/// Like [`crate::bind_json_snapshot`] this is a macro so the snapshot's
+/// namespace follows the invoking module: an encrypted snapshot lives at
+/// exactly the path its plaintext counterpart would.
+#[macro_export]
+macro_rules! bind_encrypted_snapshot {
+    ($settings:expr, $test_info:expr, $snapshot_name:expr, $plaintext:expr, $key:expr) => {{
+        let settings: insta::Settings = $settings;
+        let snapshot_name: String = $snapshot_name;
+        let plaintext: String = $plaintext;
+        let key: $crate::encryption::SnapshotKey = $key;
+        let ciphertext = key.encrypt(&plaintext)?;
+        let file_stem = $test_info.file_stem(module_path!(), &snapshot_name);
+        let committed = $test_info.snapshot_file(module_path!(), &snapshot_name);
+        let previous = $crate::encryption::read_committed_plaintext(&committed, &key);
+        let snapshot_label = snapshot_name.clone();
+        let matched =
+            $crate::panic::run_snapshot_assertion_matched(&snapshot_label, &committed, || {
+                settings.bind(|| {
+                    insta::assert_snapshot!(file_stem, ciphertext, $test_info.expression());
+                });
+            })?;
+        if matched {
+            Ok(())
+        } else {
+            $crate::panic::report_mismatch($crate::encryption::mismatch_message(
+                &snapshot_label,
+                previous.as_deref(),
+                &plaintext,
+            ))
+        }
+    }};
+}
@@ -0,0 +1,131 @@
+//! `explain_snapshot_name`: how a test's snapshot file name comes about.
+//!
+//! A snapshot that "is not found" usually lives under a different folder,
+//! prefix or counter than expected. This spells out every component the
+//! assertion helpers would use for the next snapshot of a test, from the same
+//! naming functions they call, without asserting or ticking any counter.
+
+use std::path::PathBuf;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde::Serialize;
+
+use crate::common::{file_stem_of, indexed_name, next_index, PytestInfo};
+use crate::{SnapshotInfo, SNAPSHOT_FILE_SUFFIX};
+
+/// The overrides `SnapshotInfo.from_pytest` accepts, by keyword.
+const OVERRIDES: [&str; 4] = [
+    "snapshot_path",
+    "snapshot_name",
+    "allow_duplicates",
+    "prefix",
+];
+
+/// Every component of the next snapshot file name of a test.
+#[derive(Debug, Serialize)]
+pub(crate) struct SnapshotNameParts {
+    /// The test file path recorded in the snapshot's metadata.
+    test_file: Option<String>,
+    folder: PathBuf,
+    prefix: Option<String>,
+    /// The base snapshot name, `<file stem>_<test>` unless overridden.
+    test_name: String,
+    /// The index the next assertion takes; `-<counter>` is appended from 2 on.
+    counter: usize,
+    suffix: &'static str,
+    file_name: String,
+    path: PathBuf,
+    exists: bool,
+}
+
+impl SnapshotNameParts {
+    /// The parts of the snapshot `info` names next, with the `current`
+    /// counter of its name.
+    pub(crate) fn new(info: &SnapshotInfo, current: usize) -> Self {
+        let counter = next_index(current, info.allow_duplicates);
+        let name = indexed_name(&info.snapshot_name, counter);
+        let file_name = format!(
+            "{}{SNAPSHOT_FILE_SUFFIX}",
+            file_stem_of(info.prefix.as_deref(), None, &name)
+        );
+        let path = info.folder().join(&file_name);
+        Self {
+            test_file: info.relative_test_file_path.clone(),
+            folder: info.folder(),
+            prefix: info.prefix.clone(),
+            test_name: info.snapshot_name.clone(),
+            counter,
+            suffix: SNAPSHOT_FILE_SUFFIX,
+            file_name,
+            exists: path.is_file(),
+            path,
+        }
+    }
+}
+
+/// Explains the file name the next snapshot of the pytest test
+/// `test_node_id` (e.g. `"tests/test_api.py::test_get"`) gets, with the
+/// `overrides` of `SnapshotInfo.from_pytest` applied.
+///
+/// Returns a dict of `test_file`, `folder`, `prefix`, `test_name`, `counter`,
+/// `suffix`, `file_name`, `path` and whether that file `exists`. Raises
+/// `ValueError` for an unknown override or a test file that can't be found.
+#[pyfunction]
+#[pyo3(signature = (test_node_id, overrides=None))]
+pub fn explain_snapshot_name<'py>(
+    py: Python<'py>,
+    test_node_id: &str,
+    overrides: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let empty = PyDict::new(py);
+    let overrides = overrides.unwrap_or(&empty);
+    for key in overrides.keys() {
+        let key: String = key.extract()?;
+        if !OVERRIDES.contains(&key.as_str()) {
+            return Err(PyValueError::new_err(format!(
+                "Unknown override {key:?}; expected one of {}",
+                OVERRIDES.join(", ")
+            )));
+        }
+    }
+    let get = |key: &str| {
+        overrides
+            .get_item(key)
+            .map(|value| value.filter(|v| !v.is_none()))
+    };
+    let snapshot_path: Option<PathBuf> = get("snapshot_path")?.map(|v| v.extract()).transpose()?;
+    let snapshot_name: Option<String> = get("snapshot_name")?.map(|v| v.extract()).transpose()?;
+    let allow_duplicates: bool = get("allow_duplicates")?
+        .map(|v| v.extract())
+        .transpose()?
+        .unwrap_or(false);
+    let prefix: Option<String> = get("prefix")?.map(|v| v.extract()).transpose()?;
+
+    let node: PytestInfo = test_node_id.parse()?;
+    if snapshot_path.is_none() || snapshot_name.is_none() {
+        let test_path = node.test_path()?;
+        if !test_path.is_file() {
+            return Err(PyValueError::new_err(format!(
+                "Test file of {test_node_id} not found: looked in the current directory, \
+                 INSTA_WORKSPACE_ROOT, PYTEST_ROOTDIR and the nearest pyproject.toml directory"
+            )));
+        }
+    }
+    let info = SnapshotInfo::for_pytest_node(
+        Ok(node),
+        snapshot_path,
+        snapshot_name,
+        allow_duplicates,
+        prefix.as_deref(),
+    )?;
+    let current = SnapshotInfo::counters()
+        .get(&info.counter_key())
+        .cloned()
+        .unwrap_or(0);
+    Ok(pythonize::pythonize(
+        py,
+        &SnapshotNameParts::new(&info, current),
+    )?)
+}
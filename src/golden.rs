@@ -0,0 +1,138 @@
+//! Golden functions: the assert-side twin of `mock_json_snapshot`.
+//!
+//! A mock records a dependency once and replays it afterwards; a golden
+//! function is never replayed. `golden(func)` wraps a pure, deterministic
+//! function (a parser, a formatter, a pricing rule) so that every call made
+//! from a test really runs and asserts its arguments and result against a
+//! snapshot, exactly as `assert_call_snapshot` would. Successive calls within
+//! a test are snapshotted as `<test>_<func>`, `<test>_<func>-2`, ....
+//!
+//! The snapshot scope is resolved from the running test on each call unless a
+//! `SnapshotInfo` is given, so a module-level `@golden` function can be shared
+//! by many tests.
+//!
+//! `characterize` applies the same assertion to a whole list of inputs at
+//! once, to pin the current behaviour of legacy code before changing it.
+
+use std::path::PathBuf;
+
+use pyo3::exceptions::{PyAssertionError, PyTypeError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+
+use crate::mocks::request_digest;
+use crate::SnapshotInfo;
+
+/// Wrap a pure function so every call is asserted against a snapshot.
+#[pyclass(name = "golden", module = "pysnaptest._pysnaptest")]
+pub struct Golden {
+    func: PyObject,
+    test_info: Option<SnapshotInfo>,
+}
+
+#[pymethods]
+impl Golden {
+    #[new]
+    #[pyo3(signature = (func, test_info=None))]
+    fn new(py: Python<'_>, func: PyObject, test_info: Option<SnapshotInfo>) -> PyResult<Self> {
+        let is_async = py
+            .import("inspect")?
+            .getattr("iscoroutinefunction")?
+            .call1((&func,))?
+            .is_truthy()?;
+        if is_async {
+            return Err(PyTypeError::new_err(format!(
+                "golden() cannot wrap async function {}; await it and use \
+                 assert_call_snapshot instead",
+                crate::callable_name(func.bind(py), "__qualname__")
+            )));
+        }
+        Ok(Self { func, test_info })
+    }
+
+    /// The wrapped function.
+    #[getter]
+    fn __wrapped__(&self, py: Python<'_>) -> PyObject {
+        self.func.clone_ref(py)
+    }
+
+    /// Call the wrapped function and assert its arguments and result.
+    #[pyo3(signature = (*args, **kwargs))]
+    fn __call__(
+        &self,
+        py: Python<'_>,
+        args: &Bound<'_, PyTuple>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<PyObject> {
+        let test_info = match &self.test_info {
+            Some(test_info) => test_info.clone(),
+            None => SnapshotInfo::from_pytest(None, None, false, None, None, None, None)?,
+        };
+        let func = self.func.bind(py);
+        let scoped = test_info.with_name_suffix(&crate::callable_name(func, "__name__"));
+        crate::assert_call(py, &scoped, func, args, kwargs)
+    }
+}
+
+/// Run `func` on every input and assert one call snapshot per input.
+///
+/// A tuple input is unpacked into positional arguments; anything else is
+/// passed as the only argument. Each snapshot is named after the function and
+/// a digest of its arguments (`<test>_<func>-<digest>`), so adding or
+/// reordering inputs never renames the others. Every input is asserted even
+/// when an earlier one fails, so a first run writes all of them in one go.
+///
+/// Returns `(input index, snapshot path, status)` for every input, where the
+/// status is `"created"` for a snapshot that did not exist before and
+/// `"unchanged"` otherwise. Raises `AssertionError` listing every snapshot
+/// that changed or is new and pending review.
+#[pyfunction]
+#[pyo3(signature = (func, inputs, test_info=None))]
+pub fn characterize(
+    py: Python<'_>,
+    func: &Bound<'_, PyAny>,
+    inputs: Vec<Bound<'_, PyAny>>,
+    test_info: Option<SnapshotInfo>,
+) -> PyResult<Vec<(usize, PathBuf, &'static str)>> {
+    let test_info = match test_info {
+        Some(test_info) => test_info,
+        None => SnapshotInfo::from_pytest(None, None, false, None, None, None, None)?,
+    };
+    let name = crate::callable_name(func, "__name__");
+    let to_jsonable = py.import("pysnaptest.encoders")?.getattr("to_jsonable")?;
+    let mut results = Vec::with_capacity(inputs.len());
+    let mut failures = Vec::new();
+    for (index, input) in inputs.iter().enumerate() {
+        let args = match input.downcast::<PyTuple>() {
+            Ok(args) => args.clone(),
+            Err(_) => PyTuple::new(py, [input])?,
+        };
+        let recorded: serde_json::Value =
+            crate::serialization::depythonize(&to_jsonable.call1((&args,))?)?;
+        let digest = request_digest(&recorded, None)?;
+        let scoped = test_info.with_name_suffix(&format!("{name}-{digest}"));
+        let path = scoped.next_snapshot_path(None, None)?;
+        let existed = path.exists();
+        match crate::assert_call(py, &scoped, func, &args, None) {
+            Ok(_) => results.push((index, path, if existed { "unchanged" } else { "created" })),
+            Err(err) if err.is_instance_of::<PyAssertionError>(py) => {
+                let reason = if existed {
+                    "changed"
+                } else {
+                    "new, pending review"
+                };
+                failures.push(format!("  input {index} ({}): {reason}", path.display()));
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    if failures.is_empty() {
+        return Ok(results);
+    }
+    Err(PyAssertionError::new_err(format!(
+        "{} of {} characterization snapshot(s) of {name} did not match:\n{}",
+        failures.len(),
+        inputs.len(),
+        failures.join("\n")
+    )))
+}
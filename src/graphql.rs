@@ -0,0 +1,481 @@
+//! The GraphQL response preset behind `assert_graphql_snapshot`.
+//!
+//! A GraphQL server returns fields in the order the query selected them,
+//! which is the order a reader of the query expects; a plain JSON snapshot
+//! sorts them alphabetically instead. Given the query, objects in `data` are
+//! written in selection order (fields the query did not select, such as an
+//! unrequested `__typename`, follow alphabetically). Fragment spreads and
+//! inline fragments are expanded in place.
+//!
+//! Values that differ on every request are redacted automatically: the
+//! `tracing` entry of `extensions` and any request id (`requestId`,
+//! `request_id`, `x-request-id`, ...) within the top-level or per-error
+//! `extensions`.
+
+use std::collections::HashMap;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::PyResult;
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+/// Deepest fragment nesting expanded, so a fragment cycle cannot recurse
+/// forever.
+const MAX_FRAGMENT_DEPTH: usize = 32;
+
+/// Key order of a response, and of each entry of its `errors`.
+const RESPONSE_KEYS: [&str; 3] = ["errors", "data", "extensions"];
+const ERROR_KEYS: [&str; 4] = ["message", "locations", "path", "extensions"];
+
+/// The response keys (aliases or field names) a selection set requests, in
+/// order, with the selection set of each.
+#[derive(Debug, Default)]
+pub(crate) struct Selection {
+    fields: Vec<(String, Selection)>,
+}
+
+impl Selection {
+    fn merge(&mut self, key: String, children: Selection) {
+        match self
+            .fields
+            .iter_mut()
+            .find(|(existing, _)| *existing == key)
+        {
+            Some((_, existing)) => {
+                for (key, grandchildren) in children.fields {
+                    existing.merge(key, grandchildren);
+                }
+            }
+            None => self.fields.push((key, children)),
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&Selection> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == key)
+            .map(|(_, selection)| selection)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    Punct(char),
+    Spread,
+    /// String, number and other literal values, whose content never
+    /// matters here.
+    Literal,
+}
+
+fn syntax_error(detail: &str) -> pyo3::PyErr {
+    PyValueError::new_err(format!("Unable to parse GraphQL query: {detail}"))
+}
+
+fn tokenize(query: &str) -> PyResult<Vec<Token>> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() || c == ',' || c == '\u{feff}' {
+            i += 1;
+        } else if c == '#' {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if chars[i..].starts_with(&['.', '.', '.']) {
+            tokens.push(Token::Spread);
+            i += 3;
+        } else if chars[i..].starts_with(&['"', '"', '"']) {
+            i += 3;
+            while i < chars.len() && !chars[i..].starts_with(&['"', '"', '"']) {
+                i += if chars[i..].starts_with(&['\\', '"', '"', '"']) {
+                    4
+                } else {
+                    1
+                };
+            }
+            if i >= chars.len() {
+                return Err(syntax_error("unterminated block string"));
+            }
+            tokens.push(Token::Literal);
+            i += 3;
+        } else if c == '"' {
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                i += if chars[i] == '\\' { 2 } else { 1 };
+            }
+            if i >= chars.len() {
+                return Err(syntax_error("unterminated string"));
+            }
+            tokens.push(Token::Literal);
+            i += 1;
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Name(chars[start..i].iter().collect()));
+        } else if c.is_ascii_digit() || c == '-' {
+            i += 1;
+            while i < chars.len()
+                && (chars[i].is_ascii_alphanumeric() || matches!(chars[i], '.' | '+' | '-'))
+            {
+                i += 1;
+            }
+            tokens.push(Token::Literal);
+        } else if "{}()[]:@$!=|&".contains(c) {
+            tokens.push(Token::Punct(c));
+            i += 1;
+        } else {
+            return Err(syntax_error(&format!("unexpected character {c:?}")));
+        }
+    }
+    Ok(tokens)
+}
+
+/// A selection before fragment spreads are expanded.
+#[derive(Debug)]
+enum Item {
+    Field(String, Vec<Item>),
+    Spread(String),
+    Inline(Vec<Item>),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    at: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.at)
+    }
+
+    fn next(&mut self) -> PyResult<Token> {
+        let token = self
+            .tokens
+            .get(self.at)
+            .cloned()
+            .ok_or_else(|| syntax_error("unexpected end of query"))?;
+        self.at += 1;
+        Ok(token)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let matched = self.peek() == Some(token);
+        if matched {
+            self.at += 1;
+        }
+        matched
+    }
+
+    fn name(&mut self) -> PyResult<String> {
+        match self.next()? {
+            Token::Name(name) => Ok(name),
+            other => Err(syntax_error(&format!("expected a name, found {other:?}"))),
+        }
+    }
+
+    /// Skips a bracketed group (arguments, variable definitions), which may
+    /// nest.
+    fn skip_group(&mut self, open: char, close: char) -> PyResult<()> {
+        if !self.eat(&Token::Punct(open)) {
+            return Ok(());
+        }
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next()? {
+                Token::Punct(c) if c == open => depth += 1,
+                Token::Punct(c) if c == close => depth -= 1,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn skip_directives(&mut self) -> PyResult<()> {
+        while self.eat(&Token::Punct('@')) {
+            self.name()?;
+            self.skip_group('(', ')')?;
+        }
+        Ok(())
+    }
+
+    fn selection_set(&mut self) -> PyResult<Vec<Item>> {
+        if !self.eat(&Token::Punct('{')) {
+            return Err(syntax_error("expected a selection set"));
+        }
+        let mut items = Vec::new();
+        while !self.eat(&Token::Punct('}')) {
+            if self.eat(&Token::Spread) {
+                if self.peek() == Some(&Token::Name("on".to_string())) {
+                    self.at += 1;
+                    self.name()?;
+                }
+                match self.peek() {
+                    Some(Token::Name(_)) => {
+                        let name = self.name()?;
+                        self.skip_directives()?;
+                        items.push(Item::Spread(name));
+                    }
+                    _ => {
+                        self.skip_directives()?;
+                        items.push(Item::Inline(self.selection_set()?));
+                    }
+                }
+                continue;
+            }
+            let key = self.name()?;
+            if self.eat(&Token::Punct(':')) {
+                // `alias: field`; the response uses the alias.
+                self.name()?;
+            }
+            self.skip_group('(', ')')?;
+            self.skip_directives()?;
+            let children = if self.peek() == Some(&Token::Punct('{')) {
+                self.selection_set()?
+            } else {
+                Vec::new()
+            };
+            items.push(Item::Field(key, children));
+        }
+        Ok(items)
+    }
+}
+
+/// Parses `query` and returns the selection of the operation named
+/// `operation_name`, or of its first operation.
+pub(crate) fn parse_selection(query: &str, operation_name: Option<&str>) -> PyResult<Selection> {
+    let mut parser = Parser {
+        tokens: tokenize(query)?,
+        at: 0,
+    };
+    let mut operations: Vec<(Option<String>, Vec<Item>)> = Vec::new();
+    let mut fragments: HashMap<String, Vec<Item>> = HashMap::new();
+    while parser.peek().is_some() {
+        if parser.peek() == Some(&Token::Punct('{')) {
+            operations.push((None, parser.selection_set()?));
+            continue;
+        }
+        match parser.name()?.as_str() {
+            "query" | "mutation" | "subscription" => {
+                let name = match parser.peek() {
+                    Some(Token::Name(_)) => Some(parser.name()?),
+                    _ => None,
+                };
+                parser.skip_group('(', ')')?;
+                parser.skip_directives()?;
+                operations.push((name, parser.selection_set()?));
+            }
+            "fragment" => {
+                let name = parser.name()?;
+                if parser.name()? != "on" {
+                    return Err(syntax_error(&format!(
+                        "expected \"on\" after fragment {name}"
+                    )));
+                }
+                parser.name()?;
+                parser.skip_directives()?;
+                fragments.insert(name, parser.selection_set()?);
+            }
+            other => {
+                return Err(syntax_error(&format!(
+                    "expected an operation or fragment, found {other:?}"
+                )))
+            }
+        }
+    }
+    let items = match operation_name {
+        Some(wanted) => {
+            operations
+                .into_iter()
+                .find(|(name, _)| name.as_deref() == Some(wanted))
+                .ok_or_else(|| {
+                    PyValueError::new_err(format!("GraphQL query has no operation {wanted:?}"))
+                })?
+                .1
+        }
+        None => {
+            operations
+                .into_iter()
+                .next()
+                .ok_or_else(|| syntax_error("the query has no operation"))?
+                .1
+        }
+    };
+    let mut selection = Selection::default();
+    expand(&items, &fragments, 0, &mut selection)?;
+    Ok(selection)
+}
+
+fn expand(
+    items: &[Item],
+    fragments: &HashMap<String, Vec<Item>>,
+    depth: usize,
+    into: &mut Selection,
+) -> PyResult<()> {
+    if depth > MAX_FRAGMENT_DEPTH {
+        return Err(syntax_error(
+            "fragments are nested too deeply (is there a cycle?)",
+        ));
+    }
+    for item in items {
+        match item {
+            Item::Field(key, children) => {
+                let mut selection = Selection::default();
+                expand(children, fragments, depth, &mut selection)?;
+                into.merge(key.clone(), selection);
+            }
+            Item::Inline(children) => expand(children, fragments, depth, into)?,
+            Item::Spread(name) => {
+                let fragment = fragments
+                    .get(name)
+                    .ok_or_else(|| syntax_error(&format!("fragment {name} is not defined")))?;
+                expand(fragment, fragments, depth + 1, into)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether `key` names a request id, in any common spelling.
+fn is_request_id(key: &str) -> bool {
+    let normalized: String = key
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    matches!(normalized.as_str(), "requestid" | "xrequestid")
+}
+
+fn redact_request_ids(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_request_id(key) {
+                    *value = Value::String("[request_id]".to_string());
+                } else {
+                    redact_request_ids(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_request_ids),
+        _ => {}
+    }
+}
+
+fn redact_extensions(extensions: Option<&mut Value>) {
+    if let Some(extensions) = extensions {
+        if let Some(tracing) = extensions.get_mut("tracing") {
+            *tracing = Value::String("[tracing]".to_string());
+        }
+        redact_request_ids(extensions);
+    }
+}
+
+/// Redacts the tracing data and request ids of a response.
+pub(crate) fn redact_volatile(response: &mut Value) {
+    redact_extensions(response.get_mut("extensions"));
+    if let Some(Value::Array(errors)) = response.get_mut("errors") {
+        for error in errors {
+            redact_extensions(error.get_mut("extensions"));
+        }
+    }
+}
+
+/// A JSON value whose objects serialize their entries in a given order.
+pub(crate) enum Ordered {
+    Value(Value),
+    Object(Vec<(String, Ordered)>),
+    Array(Vec<Ordered>),
+}
+
+impl Serialize for Ordered {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Ordered::Value(value) => value.serialize(serializer),
+            Ordered::Object(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+            Ordered::Array(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+        }
+    }
+}
+
+/// Orders the entries of the object `value` by `first`, followed by the
+/// remaining ones alphabetically, ordering each entry's value with `inner`.
+fn order_keys<'a>(
+    value: Value,
+    first: impl IntoIterator<Item = &'a str>,
+    inner: impl Fn(&str, Value) -> Ordered,
+) -> Ordered {
+    let Value::Object(mut map) = value else {
+        return Ordered::Value(value);
+    };
+    let mut entries = Vec::with_capacity(map.len());
+    for key in first {
+        if let Some(value) = map.remove(key) {
+            entries.push((key.to_string(), inner(key, value)));
+        }
+    }
+    // `serde_json::Map` iterates in key order.
+    for (key, value) in map {
+        let value = inner(&key, value);
+        entries.push((key, value));
+    }
+    Ordered::Object(entries)
+}
+
+/// Orders `data` by `selection`, through lists.
+fn order_data(value: Value, selection: Option<&Selection>) -> Ordered {
+    match (value, selection) {
+        (Value::Array(items), _) => Ordered::Array(
+            items
+                .into_iter()
+                .map(|item| order_data(item, selection))
+                .collect(),
+        ),
+        (value, Some(selection)) => order_keys(
+            value,
+            selection.fields.iter().map(|(key, _)| key.as_str()),
+            |key, value| order_data(value, selection.get(key)),
+        ),
+        (value, None) => Ordered::Value(value),
+    }
+}
+
+/// Orders the `errors` of a response.
+pub(crate) fn order_errors(errors: Value) -> Ordered {
+    match errors {
+        Value::Array(errors) => Ordered::Array(
+            errors
+                .into_iter()
+                .map(|error| order_keys(error, ERROR_KEYS, |_, value| Ordered::Value(value)))
+                .collect(),
+        ),
+        other => Ordered::Value(other),
+    }
+}
+
+/// Orders a response: `errors`, `data` (by `selection`, when given) and
+/// `extensions` first.
+pub(crate) fn order_response(response: Value, selection: Option<&Selection>) -> Ordered {
+    order_keys(response, RESPONSE_KEYS, |key, value| match key {
+        "data" => order_data(value, selection),
+        "errors" => order_errors(value),
+        _ => Ordered::Value(value),
+    })
+}
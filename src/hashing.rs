@@ -0,0 +1,83 @@
+//! Digest computation for hash-only snapshots.
+//!
+//! Some artifacts (model weights, rendered videos, database dumps) are too
+//! large to commit even compressed. A hash snapshot stores only the algorithm,
+//! hex digest and size, which is enough to catch any byte-level regression
+//! deterministically without keeping the artifact itself.
+
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use serde::Serialize;
+use sha2::{Digest, Sha256, Sha512};
+
+/// What `assert_hash_snapshot` accepts: in-memory bytes, or a path whose
+/// file is streamed through the hasher without being loaded whole.
+#[derive(FromPyObject)]
+pub enum HashInput<'py> {
+    Bytes(Bound<'py, PyBytes>),
+    Path(PathBuf),
+}
+
+/// The snapshotted record. Field order is the on-disk key order.
+#[derive(Serialize)]
+pub(crate) struct ContentHash {
+    algorithm: String,
+    digest: String,
+    size: u64,
+}
+
+fn hash_reader<D: Digest + Write>(reader: &mut dyn Read) -> io::Result<(String, u64)> {
+    let mut hasher = D::new();
+    let size = io::copy(reader, &mut hasher)?;
+    Ok((hex(&hasher.finalize()), size))
+}
+
+/// Lower-case hex encoding of a digest.
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Hashes with the GIL released, so a multi-GB file does not stall other
+/// Python threads.
+fn hash_input(
+    py: Python<'_>,
+    input: &HashInput<'_>,
+    hash: fn(&mut dyn Read) -> io::Result<(String, u64)>,
+) -> io::Result<(String, u64)> {
+    match input {
+        HashInput::Bytes(bytes) => {
+            let mut bytes = bytes.as_bytes();
+            py.allow_threads(|| hash(&mut bytes))
+        }
+        HashInput::Path(path) => py.allow_threads(|| hash(&mut File::open(path)?)),
+    }
+}
+
+/// Hashes `input` with `algorithm` (`sha256` or `sha512`).
+pub(crate) fn content_hash(
+    py: Python<'_>,
+    input: &HashInput<'_>,
+    algorithm: &str,
+) -> PyResult<ContentHash> {
+    let algorithm = algorithm.to_ascii_lowercase();
+    let (digest, size) = match algorithm.as_str() {
+        "sha256" => hash_input(py, input, hash_reader::<Sha256>),
+        "sha512" => hash_input(py, input, hash_reader::<Sha512>),
+        other => {
+            return Err(PyValueError::new_err(format!(
+                "Unsupported hash algorithm {other:?}; expected \"sha256\" or \"sha512\""
+            )))
+        }
+    }
+    .map_err(|e| PyValueError::new_err(format!("Unable to hash snapshot input: {e}")))?;
+    Ok(ContentHash {
+        algorithm,
+        digest,
+        size,
+    })
+}
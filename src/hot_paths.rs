@@ -0,0 +1,40 @@
+//! The hot paths of an assertion, for the criterion suite in `benches/`.
+//!
+//! The crate is also built as an `rlib` so the suite can link against it;
+//! these wrappers are its only way in, built with the `bench` feature only,
+//! and are not part of the Python API.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+use insta::_macro_support::{serialize_value, SerializationFormat};
+use insta::internals::Content;
+use pyo3::prelude::*;
+use serde_json::Value;
+
+use crate::redactions::{apply_redactions, json_to_content};
+use crate::tabular::{CsvRows, TableOptions};
+use crate::RedactionType;
+
+/// Converts a Python value to JSON, as every JSON assertion does first.
+pub fn depythonize(value: &Bound<'_, PyAny>) -> PyResult<Value> {
+    crate::serialization::depythonize(value)
+}
+
+/// Parses CSV text and renders it as `assert_csv_snapshot` stores it.
+pub fn render_csv(data: &str) -> PyResult<String> {
+    let rows = CsvRows::new(data, TableOptions::default())?;
+    Ok(serialize_value(&rows, SerializationFormat::Csv))
+}
+
+/// Applies `redactions` to a JSON value, as an encrypted or structurally
+/// compared JSON snapshot does.
+pub fn redact(value: &Value, redactions: &HashMap<String, RedactionType>) -> PyResult<Content> {
+    apply_redactions(json_to_content(value), redactions)
+}
+
+/// Replaces the file at `path` with `contents`, as snapshots are written.
+pub fn write_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    crate::atomic::write(path, contents)
+}
@@ -0,0 +1,127 @@
+//! Scrubbing of volatile image metadata before binary snapshots.
+//!
+//! Renderers and image libraries embed creation times, software versions and
+//! free-form comments in the files they write, so re-rendering an identical
+//! image produces different bytes. With `scrub_metadata` the binary snapshot
+//! helpers drop those parts first and compare what is left:
+//!
+//! * PNG: the `tIME`, `tEXt`, `zTXt`, `iTXt` and `eXIf` chunks. Every chunk
+//!   carries its own CRC, so the remaining ones are copied unchanged.
+//! * JPEG: `APP1` segments holding EXIF or XMP data, and `COM` comments.
+//!   Colour profiles (`APP2`) and everything from the start of scan on are
+//!   kept.
+//!
+//! Pixel data is never decoded or re-encoded.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// PNG chunks holding timestamps, text or EXIF data.
+const VOLATILE_PNG_CHUNKS: [&[u8; 4]; 5] = [b"tIME", b"tEXt", b"zTXt", b"iTXt", b"eXIf"];
+
+/// Identifiers at the start of JPEG `APP1` segments carrying EXIF or XMP.
+const VOLATILE_APP1_HEADERS: [&[u8]; 2] = [b"Exif\0", b"http://ns.adobe.com/xap/1.0/\0"];
+
+/// Returns the PNG or JPEG `image` without its volatile metadata.
+///
+/// Fails for bytes that are neither, or that end inside a chunk or segment.
+pub(crate) fn strip_volatile_metadata(image: &[u8]) -> PyResult<Vec<u8>> {
+    if image.starts_with(PNG_SIGNATURE) {
+        strip_png(image)
+    } else if image.starts_with(&[0xFF, 0xD8]) {
+        strip_jpeg(image)
+    } else {
+        Err(PyValueError::new_err(
+            "scrub_metadata supports PNG and JPEG images only",
+        ))
+    }
+}
+
+fn truncated(format: &str) -> PyErr {
+    PyValueError::new_err(format!(
+        "Unable to scrub {format} metadata: the image is truncated"
+    ))
+}
+
+fn strip_png(image: &[u8]) -> PyResult<Vec<u8>> {
+    let mut scrubbed = PNG_SIGNATURE.to_vec();
+    let mut rest = &image[PNG_SIGNATURE.len()..];
+    while !rest.is_empty() {
+        let header = rest.get(..8).ok_or_else(|| truncated("PNG"))?;
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        // Length, type, data and CRC.
+        let chunk = rest.get(..12 + length).ok_or_else(|| truncated("PNG"))?;
+        if !VOLATILE_PNG_CHUNKS
+            .iter()
+            .any(|kind| &header[4..8] == *kind)
+        {
+            scrubbed.extend_from_slice(chunk);
+        }
+        rest = &rest[chunk.len()..];
+    }
+    Ok(scrubbed)
+}
+
+fn strip_jpeg(image: &[u8]) -> PyResult<Vec<u8>> {
+    let mut scrubbed = image[..2].to_vec();
+    let mut at = 2;
+    loop {
+        // Markers may be preceded by any number of 0xFF fill bytes.
+        while image.get(at + 1) == Some(&0xFF) {
+            at += 1;
+        }
+        let marker = match image.get(at..at + 2) {
+            Some([0xFF, marker]) => *marker,
+            Some(_) => {
+                return Err(PyValueError::new_err(
+                    "Unable to scrub JPEG metadata: expected a segment marker",
+                ))
+            }
+            None if at == image.len() => return Ok(scrubbed),
+            None => return Err(truncated("JPEG")),
+        };
+        if marker == 0xD9 {
+            scrubbed.extend_from_slice(&image[at..]);
+            return Ok(scrubbed);
+        }
+        // Other standalone markers carry no length.
+        if marker == 0x01 || (0xD0..=0xD7).contains(&marker) {
+            scrubbed.extend_from_slice(&image[at..at + 2]);
+            at += 2;
+            continue;
+        }
+        let length = image
+            .get(at + 2..at + 4)
+            .map(|length| u16::from_be_bytes([length[0], length[1]]) as usize)
+            .ok_or_else(|| truncated("JPEG"))?;
+        let segment = image
+            .get(at..at + 2 + length)
+            .ok_or_else(|| truncated("JPEG"))?;
+        // The entropy-coded data after the start of scan is copied verbatim.
+        if marker == 0xDA {
+            scrubbed.extend_from_slice(&image[at..]);
+            return Ok(scrubbed);
+        }
+        let payload = &segment[4.min(segment.len())..];
+        let volatile = marker == 0xFE
+            || (marker == 0xE1
+                && VOLATILE_APP1_HEADERS
+                    .iter()
+                    .any(|header| payload.starts_with(header)));
+        if !volatile {
+            scrubbed.extend_from_slice(segment);
+        }
+        at += segment.len();
+    }
+}
+
+/// Returns a PNG or JPEG image without the metadata `scrub_metadata` drops
+/// from binary snapshots: timestamps, text and EXIF or XMP data.
+#[pyfunction]
+pub fn strip_image_metadata<'py>(py: Python<'py>, image: &[u8]) -> PyResult<Bound<'py, PyBytes>> {
+    let scrubbed = py.allow_threads(|| strip_volatile_metadata(image))?;
+    Ok(PyBytes::new(py, &scrubbed))
+}
@@ -0,0 +1,105 @@
+//! Writing snapshots imported from other snapshot libraries.
+//!
+//! `pysnaptest.import_snapshots` parses syrupy's `.ambr` files and
+//! snapshottest's `snap_*.py` modules in Python and hands every snapshot it
+//! finds to [`write_imported_snapshot`], which stores it exactly where, and
+//! in the layout in which, the test's own assertion will look for it: the
+//! `index`th snapshot of a test in `snapshots/` next to the test file, with
+//! the usual header. Its `info` names the test and the library the snapshot
+//! came from:
+//!
+//! ```yaml
+//! info:
+//!   pysnaptest_version: 0.6.0
+//!   test: "tests/test_api.py::test_get"
+//!   created_at: "2026-10-16T09:30:00Z"
+//!   imported_from: syrupy
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use insta::_macro_support::{serialize_value, SerializationFormat};
+use insta::Snapshot;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyString;
+use serde::Serialize;
+
+use crate::atomic;
+use crate::common::{file_stem_of, indexed_name, portable_path, Description};
+use crate::provenance::{Provenance, WithProvenance};
+use crate::{SnapshotInfo, SNAPSHOT_FILE_SUFFIX};
+
+#[derive(Serialize)]
+struct Imported<'a> {
+    imported_from: &'a str,
+}
+
+#[derive(Serialize)]
+struct Header<'a> {
+    source: &'static str,
+    description: String,
+    info: WithProvenance<Imported<'a>>,
+}
+
+fn write_snapshot(path: &Path, header: &Header, body: &str) -> PyResult<()> {
+    let header = serialize_value(header, SerializationFormat::Yaml);
+    if let Some(folder) = path.parent() {
+        std::fs::create_dir_all(folder)?;
+    }
+    atomic::write(path, format!("---\n{}\n---\n{body}\n", header.trim_end()))?;
+    // Read back and saved by insta, so the file is laid out exactly as one
+    // insta wrote.
+    Snapshot::from_file(path)
+        .and_then(|snapshot| atomic::save_snapshot(&snapshot, path))
+        .map_err(|e| PyValueError::new_err(format!("Could not write snapshot {path:?}: {e}")))
+}
+
+/// Writes `contents` as the `index`th snapshot (counting from 1) of the test
+/// `test_name` defined in the file at `test_path`, as a text snapshot when it
+/// is a string and as a JSON snapshot otherwise. `recorded_path` is the test
+/// file path recorded in the header, relative to the project root, and
+/// `imported_from` the library the snapshot comes from.
+///
+/// Returns the path written, or `None` when the test already has a
+/// pysnaptest snapshot there, which is left untouched.
+#[pyfunction]
+#[pyo3(signature = (test_path, recorded_path, test_name, index, contents, imported_from))]
+pub fn write_imported_snapshot(
+    test_path: PathBuf,
+    recorded_path: PathBuf,
+    test_name: &str,
+    index: usize,
+    contents: &Bound<'_, PyAny>,
+    imported_from: &str,
+) -> PyResult<Option<PathBuf>> {
+    if index == 0 {
+        return Err(PyValueError::new_err("Snapshot index counts from 1, got 0"));
+    }
+    let info = SnapshotInfo::for_test(&test_path, &recorded_path, test_name)?;
+    let name = indexed_name(&info.snapshot_name, index);
+    let path = info.folder().join(format!(
+        "{}{SNAPSHOT_FILE_SUFFIX}",
+        file_stem_of(info.prefix.as_deref(), None, &name)
+    ));
+    if path.exists() {
+        return Ok(None);
+    }
+
+    let body = match contents.downcast::<PyString>() {
+        Ok(text) => text.to_cow()?.into_owned(),
+        Err(_) => {
+            let value: serde_json::Value = crate::serialization::depythonize(contents)?;
+            serialize_value(&value, SerializationFormat::Json)
+        }
+    };
+    let recorded = portable_path(&recorded_path);
+    let header = Header {
+        source: file!(),
+        description: Description::new(recorded.clone()).into(),
+        info: Provenance::for_test(format!("{recorded}::{test_name}"))
+            .with(Imported { imported_from }),
+    };
+    write_snapshot(&path, &header, &body)?;
+    Ok(Some(path))
+}
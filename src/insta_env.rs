@@ -0,0 +1,279 @@
+//! insta's environment variables, resolved as insta resolves them.
+//!
+//! insta decides how a mismatching snapshot is handled from the environment:
+//!
+//! * `INSTA_UPDATE`: `auto` (the default) writes a pending `.snap.new`, or
+//!   nothing on CI (`CI` or `TF_BUILD` set); `always` (or `1`) and `force`
+//!   rewrite the snapshot in place; `new` always writes a `.snap.new`;
+//!   `unseen` writes new snapshots in place and changed ones as `.snap.new`;
+//!   `no` writes nothing. `INSTA_FORCE_UPDATE=1` is the older `force`.
+//! * `INSTA_FORCE_PASS=1` passes mismatching assertions, still writing the
+//!   `.snap.new`.
+//! * `INSTA_OUTPUT`: what insta prints of a mismatch, `diff` (the default),
+//!   `summary`, `minimal` or `none`.
+//! * `INSTA_WORKSPACE_ROOT`: the workspace snapshots are reviewed in and
+//!   test file paths are relative to.
+//!
+//! insta reads the first three once per workspace root, when the first
+//! assertion under it runs, and keeps them for the rest of the process. The
+//! values in effect are therefore kept here the same way, so pysnaptest's own
+//! decisions (whether structural JSON comparison stands back while snapshots
+//! are rewritten, say) follow what insta does rather than a value set later,
+//! and [`get_effective_config`] reports them, along with the variables
+//! changed since, when CI behaves unexpectedly. An invalid value raises
+//! `ValueError` naming the variable instead of insta's panic.
+
+use std::collections::HashMap;
+use std::env;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// The values of `INSTA_UPDATE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Update {
+    Auto,
+    Always,
+    New,
+    Unseen,
+    No,
+    Force,
+}
+
+impl Update {
+    fn as_str(self) -> &'static str {
+        match self {
+            Update::Auto => "auto",
+            Update::Always => "always",
+            Update::New => "new",
+            Update::Unseen => "unseen",
+            Update::No => "no",
+            Update::Force => "force",
+        }
+    }
+}
+
+/// The values of `INSTA_OUTPUT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Output {
+    Diff,
+    Summary,
+    Minimal,
+    None,
+}
+
+impl Output {
+    fn as_str(self) -> &'static str {
+        match self {
+            Output::Diff => "diff",
+            Output::Summary => "summary",
+            Output::Minimal => "minimal",
+            Output::None => "none",
+        }
+    }
+}
+
+/// What insta does with a snapshot that does not match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Behavior {
+    /// Rewrites the snapshot file.
+    InPlace,
+    /// Writes a pending `.snap.new` next to it.
+    NewFile,
+    /// Writes nothing.
+    NoUpdate,
+}
+
+impl Behavior {
+    fn as_str(self) -> &'static str {
+        match self {
+            Behavior::InPlace => "in_place",
+            Behavior::NewFile => "new_file",
+            Behavior::NoUpdate => "no_update",
+        }
+    }
+}
+
+/// insta's configuration as read from the environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct InstaConfig {
+    pub(crate) update: Update,
+    pub(crate) force_pass: bool,
+    pub(crate) output: Output,
+    pub(crate) workspace_root: Option<PathBuf>,
+    pub(crate) ci: bool,
+}
+
+impl InstaConfig {
+    /// Reads the environment, raising `ValueError` on a value insta would
+    /// refuse.
+    pub(crate) fn from_env() -> PyResult<Self> {
+        let force_update = ["INSTA_FORCE_UPDATE", "INSTA_FORCE_UPDATE_SNAPSHOTS"]
+            .into_iter()
+            .any(|name| env::var(name).is_ok_and(|value| value == "1"));
+        let update = match var("INSTA_UPDATE").as_deref() {
+            _ if force_update => Update::Force,
+            None | Some("auto") => Update::Auto,
+            Some("always" | "1") => Update::Always,
+            Some("new") => Update::New,
+            Some("unseen") => Update::Unseen,
+            Some("no") => Update::No,
+            Some("force") => Update::Force,
+            Some(value) => {
+                return Err(invalid(
+                    "INSTA_UPDATE",
+                    value,
+                    "auto, always, new, unseen, no or force",
+                ))
+            }
+        };
+        let force_pass = match var("INSTA_FORCE_PASS").as_deref() {
+            None | Some("0") => false,
+            Some("1") => true,
+            Some(value) => return Err(invalid("INSTA_FORCE_PASS", value, "0 or 1")),
+        };
+        let output = match var("INSTA_OUTPUT").as_deref() {
+            None | Some("diff") => Output::Diff,
+            Some("summary") => Output::Summary,
+            Some("minimal") => Output::Minimal,
+            Some("none") => Output::None,
+            Some(value) => {
+                return Err(invalid(
+                    "INSTA_OUTPUT",
+                    value,
+                    "diff, summary, minimal or none",
+                ))
+            }
+        };
+        let ci = match env::var("CI").ok().as_deref() {
+            Some("false" | "0" | "") => false,
+            None => env::var_os("TF_BUILD").is_some(),
+            Some(_) => true,
+        };
+        Ok(Self {
+            update,
+            force_pass,
+            output,
+            workspace_root: workspace_root(),
+            ci,
+        })
+    }
+
+    /// What insta does with a mismatching snapshot, one already `stored`
+    /// or a new one.
+    pub(crate) fn behavior(&self, stored: bool) -> Behavior {
+        match self.update {
+            Update::Always | Update::Force => Behavior::InPlace,
+            Update::Auto if self.ci => Behavior::NoUpdate,
+            Update::Auto | Update::New => Behavior::NewFile,
+            Update::Unseen if stored => Behavior::NewFile,
+            Update::Unseen => Behavior::InPlace,
+            Update::No => Behavior::NoUpdate,
+        }
+    }
+
+    /// The variables whose value in `current` differs from this one's.
+    fn changed_in(&self, current: &InstaConfig) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        if self.update != current.update {
+            changed.push("INSTA_UPDATE");
+        }
+        if self.force_pass != current.force_pass {
+            changed.push("INSTA_FORCE_PASS");
+        }
+        if self.output != current.output {
+            changed.push("INSTA_OUTPUT");
+        }
+        if self.ci != current.ci {
+            changed.push("CI");
+        }
+        changed
+    }
+}
+
+/// The variable `name`, unless it is unset or empty, which insta treats the
+/// same.
+fn var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+fn invalid(name: &str, value: &str, expected: &str) -> PyErr {
+    PyValueError::new_err(format!("Invalid {name}={value:?}: expected {expected}"))
+}
+
+/// `INSTA_WORKSPACE_ROOT`, when set.
+pub(crate) fn workspace_root() -> Option<PathBuf> {
+    env::var_os("INSTA_WORKSPACE_ROOT")
+        .filter(|root| !root.is_empty())
+        .map(PathBuf::from)
+}
+
+/// The configuration insta read for each workspace root (`None` for the
+/// one it was built with).
+static IN_EFFECT: Lazy<Mutex<HashMap<Option<PathBuf>, InstaConfig>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The configuration insta applies to the assertion about to run: the one
+/// read for its workspace root when an earlier assertion ran under it, or
+/// else the environment's, kept for the ones to come.
+pub(crate) fn in_effect() -> PyResult<InstaConfig> {
+    let mut in_effect = IN_EFFECT.lock().unwrap_or_else(|x| x.into_inner());
+    if let Some(config) = in_effect.get(&workspace_root()) {
+        return Ok(config.clone());
+    }
+    let config = InstaConfig::from_env()?;
+    in_effect.insert(config.workspace_root.clone(), config.clone());
+    Ok(config)
+}
+
+/// Whether insta may rewrite a stored snapshot in place under the
+/// configuration in effect (or, before any assertion ran, the
+/// environment's), rather than only writing `.snap.new` files. An invalid
+/// configuration counts as rewriting.
+pub(crate) fn rewrites_in_place() -> bool {
+    let cached = IN_EFFECT
+        .lock()
+        .unwrap_or_else(|x| x.into_inner())
+        .get(&workspace_root())
+        .cloned();
+    cached
+        .map_or_else(InstaConfig::from_env, Ok)
+        .map_or(true, |config| config.behavior(true) == Behavior::InPlace)
+}
+
+/// Returns the insta configuration assertions run under: `update` (the
+/// `INSTA_UPDATE` mode), `behavior` (what becomes of a changed snapshot:
+/// `"in_place"`, `"new_file"` or `"no_update"`), `force_pass`, `output`,
+/// `workspace_root`, `ci`, and `ignored`, the variables set differently
+/// since insta read them, which take effect only under another workspace
+/// root.
+#[pyfunction]
+pub fn get_effective_config(py: Python<'_>) -> PyResult<Bound<'_, PyDict>> {
+    let current = InstaConfig::from_env();
+    let read = IN_EFFECT
+        .lock()
+        .unwrap_or_else(|x| x.into_inner())
+        .get(&workspace_root())
+        .cloned();
+    let (config, ignored) = match (read, current) {
+        (Some(read), Ok(current)) => {
+            let ignored = read.changed_in(&current);
+            (read, ignored)
+        }
+        (Some(read), Err(_)) => (read, Vec::new()),
+        (None, current) => (current?, Vec::new()),
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("update", config.update.as_str())?;
+    dict.set_item("behavior", config.behavior(true).as_str())?;
+    dict.set_item("force_pass", config.force_pass)?;
+    dict.set_item("output", config.output.as_str())?;
+    dict.set_item("workspace_root", config.workspace_root)?;
+    dict.set_item("ci", config.ci)?;
+    dict.set_item("ignored", ignored)?;
+    Ok(dict)
+}
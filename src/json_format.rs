@@ -0,0 +1,298 @@
+//! How JSON snapshots are laid out.
+//!
+//! By default a JSON snapshot is insta's 2-space pretty JSON. A team can
+//! pick another layout per assertion, per `SnapshotSettings` or for the
+//! whole process (`set_json_format`), the innermost one winning:
+//!
+//! * `"pretty"` with a `max_line_width` keeps every array or object that
+//!   fits within that many columns on one line,
+//! * `"compact"` writes the whole value on one line, or pretty when that
+//!   line would be longer than `max_line_width` (120 by default, like
+//!   insta's `assert_compact_json_snapshot!`),
+//! * `"yaml"` is insta's YAML serialization (`assert_yaml_snapshot!`).
+//!
+//! The pretty and compact layouts re-lay out the JSON insta renders, so
+//! scalars are written exactly as insta writes them (and redactions and
+//! `sort_maps` apply as usual); only the whitespace between them changes.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// The line width above which a compact snapshot is pretty-printed.
+const COMPACT_MAX_LINE_WIDTH: usize = 120;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum JsonFormat {
+    Pretty,
+    Compact,
+    Yaml,
+}
+
+impl JsonFormat {
+    pub(crate) fn parse(format: &str) -> PyResult<Self> {
+        match format {
+            "pretty" => Ok(Self::Pretty),
+            "compact" => Ok(Self::Compact),
+            "yaml" => Ok(Self::Yaml),
+            other => Err(PyValueError::new_err(format!(
+                "Invalid JSON format {other:?}: use \"pretty\", \"compact\" or \"yaml\""
+            ))),
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Pretty => "pretty",
+            Self::Compact => "compact",
+            Self::Yaml => "yaml",
+        }
+    }
+}
+
+/// Parses an optional `json_format` argument.
+pub(crate) fn parse_format(format: Option<&str>) -> PyResult<Option<JsonFormat>> {
+    format.map(JsonFormat::parse).transpose()
+}
+
+/// The layout a JSON snapshot is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct JsonStyle {
+    pub(crate) format: JsonFormat,
+    pub(crate) max_line_width: Option<usize>,
+}
+
+impl JsonStyle {
+    /// Whether this is insta's own pretty JSON, asserted as it always was.
+    pub(crate) fn is_insta_default(&self) -> bool {
+        self.format == JsonFormat::Pretty && self.max_line_width.is_none()
+    }
+
+    /// Lays out `json`, insta's pretty rendering of a value, in this style.
+    /// Anything that does not parse is returned as it is.
+    pub(crate) fn layout(&self, json: String) -> String {
+        let mut parser = Parser {
+            text: &json,
+            pos: 0,
+        };
+        let Some(root) = parser.node().filter(|_| parser.at_end()) else {
+            return json;
+        };
+        let mut out = String::with_capacity(json.len());
+        match self.format {
+            JsonFormat::Compact => {
+                single_line(&root, &mut out);
+                let width = self.max_line_width.unwrap_or(COMPACT_MAX_LINE_WIDTH);
+                if out.chars().count() > width {
+                    return json;
+                }
+            }
+            JsonFormat::Pretty | JsonFormat::Yaml => {
+                pretty(&root, 0, 0, 0, self.max_line_width, &mut out);
+            }
+        }
+        out
+    }
+}
+
+/// A JSON value with its scalars and keys kept as the source text.
+enum Node<'a> {
+    Scalar(&'a str),
+    Array(Vec<Node<'a>>),
+    Object(Vec<(&'a str, Node<'a>)>),
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.skip_whitespace();
+        self.pos == self.text.len()
+    }
+
+    /// Consumes `byte` after any whitespace, if it comes next.
+    fn eat(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+        let next = self.text.as_bytes().get(self.pos) == Some(&byte);
+        if next {
+            self.pos += 1;
+        }
+        next
+    }
+
+    fn string(&mut self) -> Option<&'a str> {
+        let start = self.pos;
+        let bytes = self.text.as_bytes();
+        let mut pos = start + 1;
+        while pos < bytes.len() {
+            match bytes[pos] {
+                b'\\' => pos += 2,
+                b'"' => {
+                    self.pos = pos + 1;
+                    return Some(&self.text[start..self.pos]);
+                }
+                _ => pos += 1,
+            }
+        }
+        None
+    }
+
+    fn node(&mut self) -> Option<Node<'a>> {
+        self.skip_whitespace();
+        if self.eat(b'[') {
+            let mut items = Vec::new();
+            if !self.eat(b']') {
+                loop {
+                    items.push(self.node()?);
+                    if self.eat(b']') {
+                        break;
+                    }
+                    self.eat(b',').then_some(())?;
+                }
+            }
+            return Some(Node::Array(items));
+        }
+        if self.eat(b'{') {
+            let mut fields = Vec::new();
+            if !self.eat(b'}') {
+                loop {
+                    self.skip_whitespace();
+                    let key = self.string()?;
+                    self.eat(b':').then_some(())?;
+                    fields.push((key, self.node()?));
+                    if self.eat(b'}') {
+                        break;
+                    }
+                    self.eat(b',').then_some(())?;
+                }
+            }
+            return Some(Node::Object(fields));
+        }
+        if self.text[self.pos..].starts_with('"') {
+            return self.string().map(Node::Scalar);
+        }
+        let rest = &self.text[self.pos..];
+        let len = rest
+            .find(|c: char| matches!(c, ',' | ']' | '}') || c.is_whitespace())
+            .unwrap_or(rest.len());
+        self.pos += len;
+        (len > 0).then(|| Node::Scalar(&rest[..len]))
+    }
+}
+
+/// Writes `node` on one line, separated like insta's compact JSON.
+fn single_line(node: &Node, out: &mut String) {
+    match node {
+        Node::Scalar(text) => out.push_str(text),
+        Node::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push_str(", ");
+                }
+                single_line(item, out);
+            }
+            out.push(']');
+        }
+        Node::Object(fields) => {
+            out.push('{');
+            for (index, (key, value)) in fields.iter().enumerate() {
+                if index > 0 {
+                    out.push_str(", ");
+                }
+                out.push_str(key);
+                out.push_str(": ");
+                single_line(value, out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+/// Writes `node` pretty-printed at `indent` levels, starting at `column`
+/// and followed by `trailing` characters on its last line. With
+/// `max_line_width`, a container that fits on the line is kept on it.
+fn pretty(
+    node: &Node,
+    indent: usize,
+    column: usize,
+    trailing: usize,
+    max_line_width: Option<usize>,
+    out: &mut String,
+) {
+    let is_empty = match node {
+        Node::Scalar(_) => true,
+        Node::Array(items) => items.is_empty(),
+        Node::Object(fields) => fields.is_empty(),
+    };
+    if let (false, Some(width)) = (is_empty, max_line_width) {
+        let mut line = String::new();
+        single_line(node, &mut line);
+        if column + line.chars().count() + trailing <= width {
+            out.push_str(&line);
+            return;
+        }
+    }
+    let inner = "  ".repeat(indent + 1);
+    match node {
+        Node::Scalar(_) => single_line(node, out),
+        Node::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                let last = index + 1 == items.len();
+                out.push('\n');
+                out.push_str(&inner);
+                pretty(
+                    item,
+                    indent + 1,
+                    inner.len(),
+                    usize::from(!last),
+                    max_line_width,
+                    out,
+                );
+                if !last {
+                    out.push(',');
+                }
+            }
+            if !items.is_empty() {
+                out.push('\n');
+                out.push_str(&"  ".repeat(indent));
+            }
+            out.push(']');
+        }
+        Node::Object(fields) => {
+            out.push('{');
+            for (index, (key, value)) in fields.iter().enumerate() {
+                let last = index + 1 == fields.len();
+                out.push('\n');
+                out.push_str(&inner);
+                out.push_str(key);
+                out.push_str(": ");
+                let column = inner.len() + key.chars().count() + 2;
+                pretty(
+                    value,
+                    indent + 1,
+                    column,
+                    usize::from(!last),
+                    max_line_width,
+                    out,
+                );
+                if !last {
+                    out.push(',');
+                }
+            }
+            if !fields.is_empty() {
+                out.push('\n');
+                out.push_str(&"  ".repeat(indent));
+            }
+            out.push('}');
+        }
+    }
+}
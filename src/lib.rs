@@ -6,139 +6,885 @@ use pyo3::{
     wrap_pyfunction, Bound, PyResult,
 };
 
+mod atomic;
+mod audio;
+mod checksum;
 mod common;
+mod compression;
+mod diff;
+mod duplicates;
+mod encryption;
 mod errors;
+mod explain;
+mod golden;
+mod graphql;
+mod hashing;
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod hot_paths;
+mod images;
+mod importing;
+mod insta_env;
+mod json_format;
+mod lockfile;
+mod logs;
+mod manifest;
+mod mapped;
 mod mocks;
+mod openapi;
 mod panic;
+mod patching;
+mod pdf;
+mod pending;
+mod provenance;
+mod redactions;
+mod replay_cache;
+mod reruns;
+mod schema;
+mod scopes;
+mod scratch_storage;
+mod secrets;
+mod serialization;
+mod settings;
+mod snapshot_settings;
+mod sql;
+mod stats;
+mod storage;
+mod structural;
+mod tabular;
+mod term;
+mod update_filter;
+mod upgrade;
+mod vectors;
 
 pub use common::*;
+pub use duplicates::settle_repetitions;
+pub use encryption::ENCRYPTION_KEY_ENV;
 pub use errors::*;
+pub use explain::explain_snapshot_name;
+pub use golden::{characterize, Golden};
+pub use hashing::HashInput;
+pub use images::strip_image_metadata;
+pub use importing::write_imported_snapshot;
+pub use insta_env::get_effective_config;
+pub use manifest::{snapshot_content_hash, MANIFEST_FILE};
+pub use mapped::SnapshotBuffer;
 pub use mocks::*;
+pub use patching::PatchJsonSnapshot;
+pub use pending::pending_summary;
+pub use redactions::*;
+pub use replay_cache::{clear_replay_cache, replay_cache_info, set_replay_cache_size};
+pub use reruns::observe_test_stage;
+pub use scratch_storage::ScratchStorage;
+pub use secrets::*;
+pub use settings::{
+    serialization_limits_info, set_config_redactions, set_default_redactions, set_diff_options,
+    set_fail_fast, set_json_format, set_read_only, set_round_floats, set_serialization_limits,
+    set_structural_json_comparison,
+};
+pub use snapshot_settings::SnapshotSettings;
+pub use stats::StatsInput;
+pub use storage::{configure_storage, STORAGE_DIR_ENV, STORAGE_THRESHOLD_ENV};
+pub use update_filter::UPDATE_FILTER_ENV;
+pub use upgrade::upgrade_snapshot;
 
 use std::{
     collections::HashMap,
     path::{Path, PathBuf},
 };
 
-use csv::ReaderBuilder;
 use insta::output::SnapshotPrinter;
 use insta::Snapshot;
-use pyo3::exceptions::{PyAssertionError, PyValueError};
+use pyo3::exceptions::{PyAssertionError, PyTypeError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::PyBytes;
+use pyo3::types::{PyBytes, PyDict, PyTuple};
+use scopes::Scope;
 
-/// Binds insta settings (path, redactions) and asserts a JSON snapshot under an
-/// explicit `snapshot_name`.
+/// Binds insta settings (path, redactions), runs the opt-in secret scan, and
+/// asserts a JSON snapshot under an explicit `snapshot_name`.
+///
+/// When an encryption key resolves (explicitly or via `PYSNAPTEST_KEY`) the
+/// redacted JSON is encrypted and asserted through
+/// [`bind_encrypted_snapshot`] instead.
 ///
-/// The `insta::assert_json_snapshot!` call is emitted at the macro's *invocation*
-/// site, so the on-disk `<module_path>__<name>@pysnap.snap` prefix follows the
-/// module that uses this macro. Keeping the assertion in one place lets both the
+/// The snapshot is stored as `<prefix>__<namespace>__<name>@pysnap.snap`, where
+/// the namespace follows the module that *invokes* this macro (see
+/// [`SnapshotInfo::file_stem`]). Keeping the assertion in one place lets both the
 /// counter-based [`assert_json_snapshot`] and the mock layer's
 /// `assert_json_snapshot_named` share identical settings and panic handling.
+///
+/// Callers run it inside `py.allow_threads`: nothing here touches Python, so
+/// serialization, file IO and diffing don't block other Python threads.
+///
+/// An optional trailing `Option<impl Serialize>` is stored in the snapshot's
+/// `info` metadata after its provenance (the mock layer records call
+/// durations there).
+///
+/// With `allow_duplicates`, later occurrences of a snapshot in the same test
+/// are checked against the first instead of asserted again; see
+/// [`duplicates`]. With structural comparison on, a committed snapshot equal
+/// to the new value as data passes as it is; see [`structural`].
 #[macro_export]
 macro_rules! bind_json_snapshot {
-    ($test_info:expr, $res:expr, $snapshot_name:expr, $redactions:expr) => {{
+    ($test_info:expr, $res:expr, $snapshot_name:expr, $redactions:expr, $encryption_key:expr) => {
+        $crate::bind_json_snapshot!(
+            $test_info,
+            $res,
+            $snapshot_name,
+            $redactions,
+            $encryption_key,
+            None::<serde_json::Value>
+        )
+    };
+    ($test_info:expr, $res:expr, $snapshot_name:expr, $redactions:expr, $encryption_key:expr, $info:expr) => {{
         let mut settings: insta::Settings = $test_info.try_into()?;
-        for (selector, redaction) in $redactions.unwrap_or_default() {
-            settings.add_redaction(selector.as_str(), redaction);
+        if let Some(info) = $info {
+            settings.set_info(&$test_info.provenance().with(info));
         }
+        let redactions: Option<std::collections::HashMap<String, $crate::RedactionType>> =
+            $redactions;
+        let res = $res;
         let snapshot_name = $snapshot_name;
-        let snapshot_label = snapshot_name.clone();
-        $crate::panic::run_snapshot_assertion(&snapshot_label, || {
-            settings.bind(|| {
-                insta::assert_json_snapshot!(snapshot_name, $res);
-            });
-        })
+        $crate::secrets::guard_json(&snapshot_name, &res, redactions.as_ref())?;
+        let collapsed = if $test_info.allow_duplicates {
+            let committed = $test_info.snapshot_file(module_path!(), &snapshot_name);
+            let rendered = $crate::encryption::render_json(&res, redactions.as_ref())?;
+            $crate::duplicates::collapse(&snapshot_name, &committed, &rendered)?
+        } else {
+            false
+        };
+        if collapsed {
+            Ok(())
+        } else if let Some(key) = $crate::encryption::SnapshotKey::resolve($encryption_key)? {
+            let plaintext = $crate::encryption::render_json(&res, redactions.as_ref())?;
+            $crate::bind_encrypted_snapshot!(settings, $test_info, snapshot_name, plaintext, key)
+        } else if $crate::settings::structural_json()
+            && $crate::structural::matches_committed(
+                &$test_info.snapshot_file(module_path!(), &snapshot_name),
+                &$crate::encryption::render_json(&res, redactions.as_ref())?,
+            )
+        {
+            Ok(())
+        } else {
+            for (selector, redaction) in redactions.unwrap_or_default() {
+                settings.add_redaction(selector.as_str(), redaction);
+            }
+            let file_stem = $test_info.file_stem(module_path!(), &snapshot_name);
+            $crate::panic::run_snapshot_assertion(
+                &snapshot_name,
+                &$test_info.snapshot_file(module_path!(), &snapshot_name),
+                || {
+                    settings.bind(|| {
+                        $crate::assert_styled_json!(
+                            $test_info.json_style(),
+                            file_stem,
+                            res,
+                            $test_info.expression()
+                        );
+                    });
+                },
+            )
+        }
+    }};
+}
+
+/// Asserts `$value` as the JSON snapshot `$file_stem`, laid out in `$style`
+/// (see [`json_format`]), within the insta settings bound by the caller.
+///
+/// Insta's own pretty JSON goes through `assert_json_snapshot!` as it always
+/// did; the other layouts re-lay out the JSON insta renders under the bound
+/// settings, so redactions and `sort_maps` apply to them alike.
+#[macro_export]
+macro_rules! assert_styled_json {
+    ($style:expr, $file_stem:expr, $value:expr, $expression:expr) => {{
+        let style: $crate::json_format::JsonStyle = $style;
+        match style.format {
+            _ if style.is_insta_default() => {
+                insta::assert_json_snapshot!($file_stem, $value, $expression)
+            }
+            $crate::json_format::JsonFormat::Yaml => {
+                insta::assert_yaml_snapshot!($file_stem, $value, $expression)
+            }
+            _ => {
+                let rendered = style.layout(insta::_macro_support::serialize_value(
+                    &$value,
+                    insta::_macro_support::SerializationFormat::Json,
+                ));
+                insta::assert_snapshot!($file_stem, rendered, $expression)
+            }
+        }
     }};
 }
 
 #[pyfunction]
-#[pyo3(signature = (test_info, result, redactions=None))]
+#[pyo3(signature = (
+    test_info,
+    result,
+    redactions=None,
+    encryption_key=None,
+    sort_arrays_by=None,
+    ignore=None,
+    round_floats=None,
+    json_format=None,
+    max_line_width=None,
+))]
+#[allow(clippy::too_many_arguments)]
 pub fn assert_json_snapshot(
+    py: Python<'_>,
     test_info: &SnapshotInfo,
     result: &Bound<'_, PyAny>,
-    redactions: Option<HashMap<String, RedactionType>>,
+    #[pyo3(from_py_with = redactions::extract_redactions)] redactions: Option<
+        HashMap<String, RedactionType>,
+    >,
+    encryption_key: Option<&str>,
+    sort_arrays_by: Option<HashMap<String, String>>,
+    ignore: Option<Vec<String>>,
+    round_floats: Option<u32>,
+    json_format: Option<&str>,
+    max_line_width: Option<usize>,
+) -> PyResult<()> {
+    let test_info = &test_info
+        .clone()
+        .with_json_style(json_format::parse_format(json_format)?, max_line_width);
+    let redactions = test_info.redactions(redactions);
+    let res: serde_json::Value = serialization::depythonize(result)?;
+    let res = redactions::drop_ignored(res, &ignore.unwrap_or_default())?;
+    let res = redactions::sort_arrays(res, &sort_arrays_by.unwrap_or_default())?;
+    let res = redactions::round_floats(res, settings::round_floats(round_floats));
+    let snapshot_name = test_info.snapshot_name();
+    py.allow_threads(|| {
+        bind_json_snapshot!(test_info, res, snapshot_name, redactions, encryption_key)
+    })
+}
+
+/// Asserts one JSON snapshot per entry of `values`, named `<test>_<label>`,
+/// in a single call: the insta settings and redactions are bound once for
+/// all of them instead of once per assertion.
+///
+/// Every value is asserted even when an earlier one fails, so a first run
+/// writes all of them in one go; the `AssertionError` raised then lists the
+/// labels that did not match. Encrypted snapshots and structural comparison
+/// go through [`bind_json_snapshot`] one value at a time.
+#[pyfunction]
+#[pyo3(signature = (test_info, values, redactions=None))]
+pub fn assert_many_json_snapshots(
+    py: Python<'_>,
+    test_info: &SnapshotInfo,
+    values: &Bound<'_, PyDict>,
+    #[pyo3(from_py_with = redactions::extract_redactions)] redactions: Option<
+        HashMap<String, RedactionType>,
+    >,
+) -> PyResult<()> {
+    let redactions = test_info.redactions(redactions);
+    let decimals = settings::round_floats(None);
+    let mut snapshots = Vec::with_capacity(values.len());
+    for (label, value) in values.iter() {
+        let label: String = label.extract()?;
+        let value: serde_json::Value = serialization::depythonize(&value)?;
+        let scoped = test_info.with_name_suffix(&sanitize_snapshot_name(&label));
+        let name = scoped.snapshot_name();
+        let path = scoped.snapshot_file(module_path!(), &name);
+        let existed = path.exists();
+        let value = redactions::round_floats(value, decimals);
+        snapshots.push((label, scoped, name, path, existed, value));
+    }
+    let total = snapshots.len();
+
+    let outcomes = py.allow_threads(|| -> PyResult<Vec<_>> {
+        if encryption::key_configured() || settings::structural_json() {
+            return Ok(snapshots
+                .into_iter()
+                .map(|(label, scoped, name, path, existed, value)| {
+                    let outcome =
+                        (|| bind_json_snapshot!(&scoped, value, name, redactions.clone(), None))();
+                    (label, path, existed, outcome)
+                })
+                .collect());
+        }
+        let mut settings: insta::Settings = test_info.try_into()?;
+        for (selector, redaction) in redactions.clone().unwrap_or_default() {
+            settings.add_redaction(selector.as_str(), redaction);
+        }
+        let style = test_info.json_style();
+        Ok(settings.bind(|| {
+            snapshots
+                .into_iter()
+                .map(|(label, scoped, name, path, existed, value)| {
+                    let outcome = (|| {
+                        secrets::guard_json(&name, &value, redactions.as_ref())?;
+                        if scoped.allow_duplicates {
+                            let rendered = encryption::render_json(&value, redactions.as_ref())?;
+                            if duplicates::collapse(&name, &path, &rendered)? {
+                                return Ok(());
+                            }
+                        }
+                        let file_stem = scoped.file_stem(module_path!(), &name);
+                        panic::run_snapshot_assertion(&name, &path, || {
+                            assert_styled_json!(style, file_stem, value, scoped.expression());
+                        })
+                    })();
+                    (label, path, existed, outcome)
+                })
+                .collect()
+        }))
+    })?;
+
+    let mut failures = Vec::new();
+    for (label, path, existed, outcome) in outcomes {
+        match outcome {
+            Ok(()) => {}
+            Err(err) if err.is_instance_of::<PyAssertionError>(py) => {
+                let reason = if existed {
+                    "changed"
+                } else {
+                    "new, pending review"
+                };
+                failures.push(format!("  {label} ({}): {reason}", path.display()));
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    if failures.is_empty() {
+        return Ok(());
+    }
+    Err(PyAssertionError::new_err(format!(
+        "{} of {total} JSON snapshot(s) did not match:\n{}",
+        failures.len(),
+        failures.join("\n")
+    )))
+}
+
+/// Asserts a JSON snapshot of a GraphQL response, with `data` in the
+/// selection order of `query` and tracing data and request ids redacted.
+///
+/// With `separate_errors`, `errors` is asserted as a `<name>-errors`
+/// snapshot of its own (an empty list when there are none).
+#[pyfunction]
+#[pyo3(signature = (
+    test_info,
+    response,
+    query=None,
+    operation_name=None,
+    separate_errors=false,
+    redactions=None,
+))]
+pub fn assert_graphql_snapshot(
+    py: Python<'_>,
+    test_info: &SnapshotInfo,
+    response: &Bound<'_, PyAny>,
+    query: Option<&str>,
+    operation_name: Option<&str>,
+    separate_errors: bool,
+    #[pyo3(from_py_with = redactions::extract_redactions)] redactions: Option<
+        HashMap<String, RedactionType>,
+    >,
+) -> PyResult<()> {
+    let redactions = test_info.redactions(redactions);
+    let mut res: serde_json::Value = serialization::depythonize(response)?;
+    if !res.is_object() {
+        return Err(PyValueError::new_err(
+            "A GraphQL response must be an object with \"data\" and/or \"errors\"",
+        ));
+    }
+    let selection = query
+        .map(|query| graphql::parse_selection(query, operation_name))
+        .transpose()?;
+    graphql::redact_volatile(&mut res);
+    let mut res = redactions::round_floats(res, settings::round_floats(None));
+    let errors = if separate_errors {
+        res.as_object_mut()
+            .and_then(|response| response.remove("errors"))
+    } else {
+        None
+    };
+    let snapshot_name = test_info.snapshot_name();
+    let errors_name = format!("{snapshot_name}-errors");
+    py.allow_threads(|| {
+        let res = graphql::order_response(res, selection.as_ref());
+        bind_json_snapshot!(test_info, res, snapshot_name, redactions.clone(), None)?;
+        if !separate_errors {
+            return Ok(());
+        }
+        let errors = graphql::order_errors(errors.unwrap_or(serde_json::Value::Array(Vec::new())));
+        bind_json_snapshot!(test_info, errors, errors_name, redactions, None)
+    })
+}
+
+/// Snapshots only the digest and size of `data` (bytes or a file path), for
+/// artifacts too large to store.
+#[pyfunction]
+#[pyo3(signature = (test_info, data, algorithm="sha256"))]
+pub fn assert_hash_snapshot(
+    py: Python<'_>,
+    test_info: &SnapshotInfo,
+    data: HashInput<'_>,
+    algorithm: &str,
+) -> PyResult<()> {
+    let res = hashing::content_hash(py, &data, algorithm)?;
+    let snapshot_name = test_info.snapshot_name();
+    py.allow_threads(|| bind_json_snapshot!(test_info, res, snapshot_name, None, None))
+}
+
+/// Snapshots rounded aggregate statistics of numeric values (or of each
+/// column) instead of the values themselves.
+#[pyfunction]
+#[pyo3(signature = (test_info, values, stats=None, decimals=4))]
+pub fn assert_stats_snapshot(
+    py: Python<'_>,
+    test_info: &SnapshotInfo,
+    values: StatsInput<'_>,
+    stats: Option<Vec<String>>,
+    decimals: u32,
+) -> PyResult<()> {
+    let stats =
+        stats.unwrap_or_else(|| stats::DEFAULT_STATS.iter().map(|s| s.to_string()).collect());
+    let res = stats::stats_record(py, &values, &stats, decimals)?;
+    let snapshot_name = test_info.snapshot_name();
+    py.allow_threads(|| bind_json_snapshot!(test_info, res, snapshot_name, None, None))
+}
+
+/// The form of a seed or example id used in snapshot names: its `str()`,
+/// with every character other than ASCII letters, digits, `_` and `.`
+/// replaced by `_`.
+#[pyfunction]
+pub fn seed_label(seed: &Bound<'_, PyAny>) -> PyResult<String> {
+    Ok(seed
+        .str()?
+        .to_str()?
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect())
+}
+
+/// Asserts a JSON snapshot scoped to one property-based-testing seed or
+/// example id, as `<test>_seed-<label>`.
+#[pyfunction]
+#[pyo3(signature = (test_info, seed, value, redactions=None))]
+pub fn assert_snapshot_for_seed(
+    py: Python<'_>,
+    test_info: &SnapshotInfo,
+    seed: &Bound<'_, PyAny>,
+    value: &Bound<'_, PyAny>,
+    #[pyo3(from_py_with = redactions::extract_redactions)] redactions: Option<
+        HashMap<String, RedactionType>,
+    >,
+) -> PyResult<()> {
+    let redactions = test_info.redactions(redactions);
+    let res: serde_json::Value = serialization::depythonize(value)?;
+    let res = redactions::round_floats(res, settings::round_floats(None));
+    let scoped = test_info.with_name_suffix(&format!("seed-{}", seed_label(seed)?));
+    let snapshot_name = scoped.snapshot_name();
+    py.allow_threads(|| bind_json_snapshot!(&scoped, res, snapshot_name, redactions, None))
+}
+
+/// Snapshots the inferred structure of `value` (types, which object keys are
+/// always present, array element types) instead of its data.
+#[pyfunction]
+pub fn assert_schema_snapshot(
+    py: Python<'_>,
+    test_info: &SnapshotInfo,
+    value: &Bound<'_, PyAny>,
+) -> PyResult<()> {
+    let json: serde_json::Value = serialization::depythonize(value)?;
+    let res = schema::Schema::infer(&json);
+    let snapshot_name = test_info.snapshot_name();
+    py.allow_threads(|| bind_json_snapshot!(test_info, res, snapshot_name, None, None))
+}
+
+/// Validates `value` against the committed schema snapshot that
+/// `assert_schema_snapshot` would compare with, without writing anything.
+///
+/// Raises `SchemaViolationError` listing every type mismatch and missing
+/// required property, and `ValueError` when no schema has been recorded.
+#[pyfunction]
+pub fn validate_against_schema_snapshot(
+    py: Python<'_>,
+    test_info: &SnapshotInfo,
+    value: &Bound<'_, PyAny>,
+) -> PyResult<()> {
+    let json: serde_json::Value = serialization::depythonize(value)?;
+    let snapshot_name = test_info.snapshot_name();
+    let path = test_info.snapshot_file(module_path!(), &snapshot_name);
+    let schema = schema::read_committed(&path)?;
+    mocks::memoize_snapshot_reference(&path);
+    let mut violations = Vec::new();
+    schema.validate(&json, "", &mut violations);
+    if violations.is_empty() {
+        return Ok(());
+    }
+    Err(schema::violation_error(py, &path, &violations)?)
+}
+
+/// What `assert_call_snapshot` stores. Field order is the on-disk key order.
+#[derive(serde::Serialize)]
+struct CallRecord {
+    function: String,
+    args: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kwargs: Option<serde_json::Value>,
+    result: serde_json::Value,
+}
+
+/// Calls `func(*args, **kwargs)` and snapshots its inputs and result together
+/// as one snapshot labeled with the function's name, returning the result.
+///
+/// Arguments and result are normalized with `pysnaptest.to_jsonable`; the
+/// arguments are captured before the call, so mutating them does not change
+/// what is recorded. Exceptions raised by `func` propagate unrecorded.
+#[pyfunction]
+#[pyo3(signature = (test_info, func, *args, **kwargs))]
+pub fn assert_call_snapshot(
+    py: Python<'_>,
+    test_info: &SnapshotInfo,
+    func: &Bound<'_, PyAny>,
+    args: &Bound<'_, PyTuple>,
+    kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyObject> {
+    assert_call(py, test_info, func, args, kwargs)
+}
+
+/// The body of [`assert_call_snapshot`], shared with `golden` functions.
+pub(crate) fn assert_call(
+    py: Python<'_>,
+    test_info: &SnapshotInfo,
+    func: &Bound<'_, PyAny>,
+    args: &Bound<'_, PyTuple>,
+    kwargs: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyObject> {
+    let to_jsonable = py.import("pysnaptest.encoders")?.getattr("to_jsonable")?;
+    let depythonize = |value: &Bound<'_, PyAny>| -> PyResult<serde_json::Value> {
+        serialization::depythonize(&to_jsonable.call1((value,))?)
+    };
+    let recorded_args = depythonize(args.as_any())?;
+    let recorded_kwargs = match kwargs {
+        Some(kwargs) if !kwargs.is_empty() => Some(depythonize(kwargs.as_any())?),
+        _ => None,
+    };
+    let result = func.call(args, kwargs)?;
+    let res = CallRecord {
+        function: callable_name(func, "__qualname__"),
+        args: recorded_args,
+        kwargs: recorded_kwargs,
+        result: depythonize(&result)?,
+    };
+    let snapshot_name = test_info.snapshot_name();
+    py.allow_threads(|| bind_json_snapshot!(test_info, res, snapshot_name, None, None))?;
+    Ok(result.unbind())
+}
+
+/// `func.<attribute>` (falling back to `__name__`) for labeling snapshots.
+pub(crate) fn callable_name(func: &Bound<'_, PyAny>, attribute: &str) -> String {
+    func.getattr(attribute)
+        .or_else(|_| func.getattr("__name__"))
+        .and_then(|name| name.extract::<String>())
+        .unwrap_or_else(|_| "<callable>".to_string())
+}
+
+/// Snapshots a numeric vector (e.g. an embedding) as a JSON array, but
+/// accepts a new vector that is within tolerance of the committed one under
+/// `metric`, leaving the committed snapshot untouched.
+#[pyfunction]
+#[pyo3(signature = (test_info, vector, atol=1e-6, rtol=0.0, metric="cosine"))]
+pub fn assert_vector_snapshot(
+    py: Python<'_>,
+    test_info: &SnapshotInfo,
+    vector: Vec<f64>,
+    atol: f64,
+    rtol: f64,
+    metric: &str,
+) -> PyResult<()> {
+    let metric = vectors::Metric::parse(metric, rtol)?;
+    vectors::check_finite(&vector)?;
+    let snapshot_name = test_info.snapshot_name();
+    encryption::refuse_unencrypted("Vector", &snapshot_name)?;
+    let settings: insta::Settings = test_info.try_into()?;
+    let file_stem = test_info.file_stem(module_path!(), &snapshot_name);
+    let path = test_info.snapshot_file(module_path!(), &snapshot_name);
+    py.allow_threads(|| {
+        let comparison = vectors::read_committed(&path).map(|committed| {
+            let comparison = vectors::compare(metric, &committed, &vector, atol, rtol);
+            (committed, comparison)
+        });
+        let (res, detail) = match comparison {
+            Some((committed, comparison)) if comparison.within => (committed, None),
+            Some((_, comparison)) => (vector, Some(comparison.detail)),
+            None => (vector, None),
+        };
+        let snapshot_label = snapshot_name.clone();
+        let matched = panic::run_snapshot_assertion_matched(&snapshot_label, &path, || {
+            settings.bind(|| {
+                insta::assert_json_snapshot!(file_stem, res, test_info.expression());
+            });
+        })?;
+        if matched {
+            return Ok(());
+        }
+        panic::report_mismatch(format!(
+            "snapshot '{snapshot_label}' is outside the vector tolerance{} \
+             (see the diff above). Update the snapshot if this change is intentional.",
+            detail.map(|d| format!(": {d}")).unwrap_or_default()
+        ))
+    })
+}
+
+/// Snapshots an OpenAPI document as JSON, with vendor extensions dropped
+/// (unless `keep_extensions`) and order-insensitive lists sorted. A mismatch
+/// is reported as the operations and schemas added, removed or changed.
+#[pyfunction]
+#[pyo3(signature = (test_info, document, keep_extensions=false))]
+pub fn assert_openapi_snapshot(
+    py: Python<'_>,
+    test_info: &SnapshotInfo,
+    document: &Bound<'_, PyAny>,
+    keep_extensions: bool,
 ) -> PyResult<()> {
-    let res: serde_json::Value = pythonize::depythonize(result)?;
+    let document: serde_json::Value = serialization::depythonize(document)?;
+    if !openapi::is_openapi(&document) {
+        return Err(PyValueError::new_err(
+            "Not an OpenAPI document: expected an \"openapi\" or \"swagger\" version field",
+        ));
+    }
     let snapshot_name = test_info.snapshot_name();
-    bind_json_snapshot!(test_info, res, snapshot_name, redactions)
+    encryption::refuse_unencrypted("OpenAPI", &snapshot_name)?;
+    let settings: insta::Settings = test_info.try_into()?;
+    let file_stem = test_info.file_stem(module_path!(), &snapshot_name);
+    let path = test_info.snapshot_file(module_path!(), &snapshot_name);
+    py.allow_threads(|| {
+        let res = openapi::normalize(document, keep_extensions);
+        let committed = openapi::read_committed(&path);
+        let snapshot_label = snapshot_name.clone();
+        let matched = panic::run_snapshot_assertion_matched(&snapshot_label, &path, || {
+            settings.bind(|| {
+                insta::assert_json_snapshot!(file_stem, res, test_info.expression());
+            });
+        })?;
+        if matched {
+            return Ok(());
+        }
+        let changes = committed
+            .map(|committed| openapi::semantic_diff(&committed, &res))
+            .unwrap_or_default();
+        panic::report_mismatch(format!(
+            "snapshot '{snapshot_label}' does not match the committed API contract{}\n\
+             Update the snapshot if this change is intentional.",
+            changes
+                .iter()
+                .map(|change| format!("\n  {change}"))
+                .collect::<String>()
+        ))
+    })
 }
 
+/// Asserts a CSV snapshot, optionally of only a slice of the table.
+///
+/// With `summary` set, a `<name>-summary` JSON snapshot of the whole table's
+/// row count and per-column min/max is asserted as well.
 #[pyfunction]
-#[pyo3(signature = (test_info, result, redactions=None))]
+#[pyo3(signature = (
+    test_info,
+    result,
+    redactions=None,
+    max_rows=None,
+    sample=None,
+    head=None,
+    tail=None,
+    columns=None,
+    summary=false,
+))]
+#[allow(clippy::too_many_arguments)]
 pub fn assert_csv_snapshot(
+    py: Python<'_>,
+    test_info: &SnapshotInfo,
+    result: &str,
+    #[pyo3(from_py_with = redactions::extract_redactions)] redactions: Option<
+        HashMap<String, RedactionType>,
+    >,
+    max_rows: Option<usize>,
+    sample: Option<usize>,
+    head: Option<usize>,
+    tail: Option<usize>,
+    columns: Option<Vec<String>>,
+    summary: bool,
+) -> PyResult<()> {
+    let options = tabular::TableOptions {
+        max_rows,
+        sample,
+        head,
+        tail,
+        columns,
+        summary,
+    };
+    let redactions = test_info.redactions(redactions);
+    py.allow_threads(|| csv_snapshot(test_info, result, redactions, options))
+}
+
+fn csv_snapshot(
     test_info: &SnapshotInfo,
     result: &str,
     redactions: Option<HashMap<String, RedactionType>>,
+    options: tabular::TableOptions,
 ) -> PyResult<()> {
-    let mut rdr = ReaderBuilder::new().from_reader(result.as_bytes());
-    let columns: Vec<Vec<serde_json::Value>> = vec![rdr
-        .headers()
-        .map_err(|e| PyValueError::new_err(format!("Failed to read CSV headers: {e}")))?
-        .into_iter()
-        .map(|h| h.into())
-        .collect()];
-    let records = rdr
-        .into_deserialize()
-        .collect::<Result<Vec<Vec<serde_json::Value>>, _>>()
-        .map_err(|e| PyValueError::new_err(format!("Failed to parse CSV records: {e}")))?;
-    let res: Vec<Vec<serde_json::Value>> = columns.into_iter().chain(records).collect();
+    let res = tabular::CsvRows::new(result, options)?;
 
     let snapshot_name = test_info.snapshot_name();
     let mut settings: insta::Settings = test_info.try_into()?;
+    secrets::guard_json(&snapshot_name, &res, redactions.as_ref())?;
 
     for (selector, redaction) in redactions.unwrap_or_default() {
         settings.add_redaction(selector.as_str(), redaction);
     }
 
-    let snapshot_label = snapshot_name.clone();
-    panic::run_snapshot_assertion(&snapshot_label, || {
-        settings.bind(|| {
-            insta::assert_csv_snapshot!(snapshot_name, res);
+    let summary_name = format!("{snapshot_name}-summary");
+    if let Some(key) = encryption::SnapshotKey::resolve(None)? {
+        // The CSV insta would write, redacted, to encrypt.
+        let plaintext = settings.bind(|| {
+            insta::_macro_support::serialize_value(
+                &res,
+                insta::_macro_support::SerializationFormat::Csv,
+            )
         });
-    })
+        let name = snapshot_name.clone();
+        bind_encrypted_snapshot!(settings, test_info, name, plaintext, key)?;
+    } else {
+        let file_stem = test_info.file_stem(module_path!(), &snapshot_name);
+        panic::run_snapshot_assertion(
+            &snapshot_name,
+            &test_info.snapshot_file(module_path!(), &snapshot_name),
+            || {
+                settings.bind(|| {
+                    insta::assert_csv_snapshot!(file_stem, res, test_info.expression());
+                });
+            },
+        )?;
+    }
+    match res.summary() {
+        Some(summary) => bind_json_snapshot!(test_info, summary, summary_name, None, None),
+        None => Ok(()),
+    }
 }
 
 #[pyfunction]
-#[pyo3(signature = (test_info, extension, result, readable_diff_renderer=None))]
+#[pyo3(signature = (
+    test_info,
+    extension,
+    result,
+    readable_diff_renderer=None,
+    compress=false,
+    scrub_metadata=false,
+))]
 pub fn assert_binary_snapshot(
     py: Python<'_>,
     test_info: &SnapshotInfo,
     extension: &str,
     result: Vec<u8>,
     readable_diff_renderer: Option<PyObject>,
+    compress: bool,
+    scrub_metadata: bool,
 ) -> PyResult<()> {
+    let result = if scrub_metadata {
+        py.allow_threads(|| images::strip_volatile_metadata(&result))?
+    } else {
+        result
+    };
     let snapshot_name = test_info.snapshot_name();
+    encryption::refuse_unencrypted("Binary", &snapshot_name)?;
     let settings: insta::Settings = test_info.try_into()?;
     let snapshot_label = snapshot_name.clone();
+    let file_stem = test_info.file_stem(module_path!(), &snapshot_name);
 
-    // Fast path: without a renderer this is a plain byte-compared binary snapshot.
-    let Some(renderer) = readable_diff_renderer else {
-        return panic::run_snapshot_assertion(&snapshot_label, || {
-            settings.bind(|| {
-                insta::assert_binary_snapshot!(
-                    format!("{snapshot_name}.{extension}").as_str(),
-                    result
-                );
-            });
+    // Fast path: without a renderer, compression or external storage this is
+    // a plain byte-compared binary snapshot.
+    if readable_diff_renderer.is_none() && !compress && !storage::is_configured()? {
+        return py.allow_threads(|| {
+            panic::run_snapshot_assertion(
+                &snapshot_label,
+                &test_info.snapshot_file(module_path!(), &snapshot_name),
+                || {
+                    settings.bind(|| {
+                        insta::assert_binary_snapshot!(
+                            format!("{file_stem}.{extension}").as_str(),
+                            result,
+                            test_info.expression()
+                        );
+                    });
+                },
+            )
         });
+    }
+
+    // A compressed snapshot stores `<extension>.zst`; insta splits the name
+    // at the first dot, so the snapshot name itself is unchanged.
+    let mut stored_extension = if compress {
+        format!("{extension}.{}", compression::COMPRESSED_EXTENSION)
+    } else {
+        extension.to_string()
     };
 
     // Equality is still insta's exact byte comparison. Rust owns the whole
     // compare/mismatch/raise flow; the renderer is the only Python step (it
     // decodes the binary DataFrame, which needs pandas/polars). Read the
-    // committed sidecar up front so a mismatch can be rendered against it.
-    let module_prefix = module_path!().replace("::", "__");
-    let sidecar = test_info.snapshot_folder().join(format!(
-        "{module_prefix}__{snapshot_name}@pysnap.snap.{extension}"
-    ));
-    let previous = std::fs::read(&sidecar).ok();
-    let new_bytes = result.clone();
-
-    let matched = panic::run_snapshot_assertion_matched(&snapshot_label, || {
-        settings.bind(|| {
-            insta::assert_binary_snapshot!(format!("{snapshot_name}.{extension}").as_str(), result);
+    // committed sidecar up front so a mismatch can be rendered against it
+    // (and so compressed bytes can be reused when the content is unchanged).
+    let sidecar = test_info
+        .snapshot_folder()
+        .join(format!("{file_stem}@pysnap.snap.{stored_extension}"));
+    let committed = storage::read_committed(py, &sidecar);
+    let (mut stored, previous) = if compress {
+        py.allow_threads(|| {
+            let stored = compression::compress_stable(&result, committed.as_deref())?;
+            let previous = committed.and_then(|bytes| compression::decompress(&bytes).ok());
+            Ok::<_, PyErr>((stored, previous))
+        })?
+    } else {
+        (result.clone(), committed)
+    };
+    let new_bytes = result;
+
+    // Above the storage threshold the bytes go to the backend and a pointer
+    // is committed instead, as `<extension>.ptr`.
+    if let Some(pointer) = storage::externalize(py, &stored)? {
+        stored = pointer;
+        stored_extension = format!("{stored_extension}.{}", storage::POINTER_EXTENSION);
+    }
+
+    let Some(renderer) = readable_diff_renderer else {
+        return py.allow_threads(|| {
+            panic::run_snapshot_assertion(
+                &snapshot_label,
+                &test_info.snapshot_file(module_path!(), &snapshot_name),
+                || {
+                    settings.bind(|| {
+                        insta::assert_binary_snapshot!(
+                            format!("{file_stem}.{stored_extension}").as_str(),
+                            stored,
+                            test_info.expression()
+                        );
+                    });
+                },
+            )
         });
+    };
+
+    let matched = py.allow_threads(|| {
+        panic::run_snapshot_assertion_matched(
+            &snapshot_label,
+            &test_info.snapshot_file(module_path!(), &snapshot_name),
+            || {
+                settings.bind(|| {
+                    insta::assert_binary_snapshot!(
+                        format!("{file_stem}.{stored_extension}").as_str(),
+                        stored,
+                        test_info.expression()
+                    );
+                });
+            },
+        )
     })?;
     if matched {
         return Ok(());
@@ -150,42 +896,225 @@ pub fn assert_binary_snapshot(
     );
     let Some(previous) = previous else {
         // First run: no committed snapshot to diff against.
-        return Err(PyAssertionError::new_err(base));
+        return panic::report_mismatch(base);
     };
 
     let renderer = renderer.bind(py);
     let old_text: String = renderer.call1((PyBytes::new(py, &previous),))?.extract()?;
     let new_text: String = renderer.call1((PyBytes::new(py, &new_bytes),))?.extract()?;
-    let diff = render_text_diff(&old_text, &new_text, Some("committed"), Some("new"));
-    Err(PyAssertionError::new_err(format!("{base}\n\n{diff}")))
+    let diff = py.allow_threads(|| unified_diff(&old_text, &new_text, "committed", "new"));
+    panic::report_mismatch(format!("{base}\n\n{diff}"))
 }
 
 /// Renders a unified diff between two text renderings using the same diff engine
 /// insta uses (`similar`). Used to show a human-readable CSV/JSON diff for binary
-/// DataFrame snapshots whose raw bytes differ.
+/// DataFrame snapshots whose raw bytes differ. The diff is computed with the
+/// GIL released.
 #[pyfunction]
 #[pyo3(signature = (old, new, old_label=None, new_label=None))]
 pub fn render_text_diff(
+    py: Python<'_>,
     old: &str,
     new: &str,
     old_label: Option<&str>,
     new_label: Option<&str>,
 ) -> String {
+    py.allow_threads(|| {
+        unified_diff(
+            old,
+            new,
+            old_label.unwrap_or("committed"),
+            new_label.unwrap_or("new"),
+        )
+    })
+}
+
+/// [`render_text_diff`] for callers that have already released the GIL.
+pub(crate) fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
     let diff = similar::TextDiff::from_lines(old, new);
     let mut unified = diff.unified_diff();
-    unified.header(old_label.unwrap_or("committed"), new_label.unwrap_or("new"));
+    unified.header(old_label, new_label);
     unified.to_string()
 }
 
 #[pyfunction]
-pub fn assert_snapshot(test_info: &SnapshotInfo, result: &Bound<'_, PyAny>) -> PyResult<()> {
+#[pyo3(signature = (test_info, result, encryption_key=None))]
+pub fn assert_snapshot(
+    py: Python<'_>,
+    test_info: &SnapshotInfo,
+    result: &Bound<'_, PyAny>,
+    encryption_key: Option<&str>,
+) -> PyResult<()> {
+    let result = result.to_string();
+    py.allow_threads(|| text_snapshot(test_info, result, encryption_key))
+}
+
+fn text_snapshot(
+    test_info: &SnapshotInfo,
+    result: String,
+    encryption_key: Option<&str>,
+) -> PyResult<()> {
     let snapshot_name = test_info.snapshot_name();
     let settings: insta::Settings = test_info.try_into()?;
-    let snapshot_label = snapshot_name.clone();
-    panic::run_snapshot_assertion(&snapshot_label, || {
-        settings.bind(|| {
-            insta::assert_snapshot!(snapshot_name, result);
+    secrets::guard_text(&snapshot_name, [result.as_str()])?;
+    if test_info.allow_duplicates {
+        let committed = test_info.snapshot_file(module_path!(), &snapshot_name);
+        if duplicates::collapse(&snapshot_name, &committed, &result)? {
+            return Ok(());
+        }
+    }
+    if let Some(key) = encryption::SnapshotKey::resolve(encryption_key)? {
+        return bind_encrypted_snapshot!(settings, test_info, snapshot_name, result, key);
+    }
+    let file_stem = test_info.file_stem(module_path!(), &snapshot_name);
+    panic::run_snapshot_assertion(
+        &snapshot_name,
+        &test_info.snapshot_file(module_path!(), &snapshot_name),
+        || {
+            settings.bind(|| {
+                insta::assert_snapshot!(file_stem, result, test_info.expression());
+            });
+        },
+    )
+}
+
+/// Snapshots a PDF document. In `"text"` mode only its text layer and page
+/// structure are stored (see [`pdf::text_layer`]), which stay stable across
+/// renderings; `"binary"` stores the exact bytes as a `.pdf` snapshot.
+#[pyfunction]
+#[pyo3(signature = (test_info, result, mode="text"))]
+pub fn assert_pdf_snapshot(
+    py: Python<'_>,
+    test_info: &SnapshotInfo,
+    result: Vec<u8>,
+    mode: &str,
+) -> PyResult<()> {
+    match mode {
+        "text" => py.allow_threads(|| text_snapshot(test_info, pdf::text_layer(&result)?, None)),
+        "binary" => assert_binary_snapshot(py, test_info, "pdf", result, None, false, false),
+        other => Err(PyValueError::new_err(format!(
+            "Unsupported PDF snapshot mode {other:?}; expected \"text\" or \"binary\""
+        ))),
+    }
+}
+
+/// Asserts a text snapshot of terminal output, with escape sequences
+/// stripped (or, with `strip_ansi` off, shown as `[bold red]`-style markers)
+/// and lines hard-wrapped at `width` columns.
+#[pyfunction]
+#[pyo3(signature = (test_info, text, strip_ansi=true, width=None))]
+pub fn assert_term_snapshot(
+    py: Python<'_>,
+    test_info: &SnapshotInfo,
+    text: &str,
+    strip_ansi: bool,
+    width: Option<usize>,
+) -> PyResult<()> {
+    py.allow_threads(|| text_snapshot(test_info, term::normalize(text, strip_ansi, width)?, None))
+}
+
+/// Asserts a text snapshot of log records (`logging.LogRecord`s or dicts),
+/// one `LEVEL logger: message` line each. With `normalize`, timestamps,
+/// thread and process ids, addresses and absolute paths in the messages are
+/// replaced by placeholders.
+#[pyfunction]
+#[pyo3(signature = (test_info, records, normalize=true))]
+pub fn assert_logs_snapshot(
+    py: Python<'_>,
+    test_info: &SnapshotInfo,
+    records: &Bound<'_, PyAny>,
+    normalize: bool,
+) -> PyResult<()> {
+    let rendered = logs::render(records, normalize)?;
+    py.allow_threads(|| text_snapshot(test_info, rendered, None))
+}
+
+/// Asserts a text snapshot of one SQL query, or of several separated by a
+/// blank line, formatted deterministically with bind-parameter placeholders
+/// normalized to `?`.
+#[pyfunction]
+#[pyo3(signature = (test_info, sql, dialect=None))]
+pub fn assert_sql_snapshot(
+    py: Python<'_>,
+    test_info: &SnapshotInfo,
+    sql: &Bound<'_, PyAny>,
+    dialect: Option<&str>,
+) -> PyResult<()> {
+    let dialect = sql::Dialect::parse(dialect)?;
+    let queries: Vec<String> = match sql.extract::<String>() {
+        Ok(query) => vec![query],
+        Err(_) => sql.extract()?,
+    };
+    py.allow_threads(|| {
+        let formatted: Vec<String> = queries
+            .iter()
+            .map(|query| sql::format(query, dialect))
+            .collect();
+        text_snapshot(test_info, formatted.join("\n\n"), None)
+    })
+}
+
+/// Snapshots encoded audio as a binary snapshot, but accepts a new recording
+/// whose decoded signal is within `tolerance` of the committed one under
+/// `mode` (`"pcm"` or `"fingerprint"`), leaving the committed file untouched.
+#[pyfunction]
+#[pyo3(signature = (test_info, result, extension="wav", mode="pcm", tolerance=None))]
+pub fn assert_audio_snapshot(
+    py: Python<'_>,
+    test_info: &SnapshotInfo,
+    result: Vec<u8>,
+    extension: &str,
+    mode: &str,
+    tolerance: Option<f64>,
+) -> PyResult<()> {
+    let mode = audio::Mode::parse(mode)?;
+    let tolerance = tolerance.unwrap_or(mode.default_tolerance());
+    if tolerance.is_nan() || tolerance < 0.0 {
+        return Err(PyValueError::new_err(
+            "tolerance must be a non-negative number",
+        ));
+    }
+    let snapshot_name = test_info.snapshot_name();
+    encryption::refuse_unencrypted("Audio", &snapshot_name)?;
+    let settings: insta::Settings = test_info.try_into()?;
+    let file_stem = test_info.file_stem(module_path!(), &snapshot_name);
+    let sidecar = test_info
+        .snapshot_folder()
+        .join(format!("{file_stem}@pysnap.snap.{extension}"));
+    let committed = storage::read_committed(py, &sidecar);
+    py.allow_threads(|| {
+        let new = audio::decode(&result, extension)?;
+        // A committed file that no longer decodes is compared byte for byte.
+        let comparison = committed.and_then(|bytes| {
+            let committed = audio::decode(&bytes, extension).ok()?;
+            Some((bytes, audio::compare(mode, &committed, &new, tolerance)))
         });
+        let (res, detail) = match comparison {
+            Some((committed, comparison)) if comparison.within => (committed, None),
+            Some((_, comparison)) => (result, Some(comparison.detail)),
+            None => (result, None),
+        };
+        let matched = panic::run_snapshot_assertion_matched(
+            &snapshot_name,
+            &test_info.snapshot_file(module_path!(), &snapshot_name),
+            || {
+                settings.bind(|| {
+                    insta::assert_binary_snapshot!(
+                        format!("{file_stem}.{extension}").as_str(),
+                        res,
+                        test_info.expression()
+                    );
+                });
+            },
+        )?;
+        if matched {
+            return Ok(());
+        }
+        panic::report_mismatch(format!(
+            "snapshot '{snapshot_name}' is outside the audio tolerance{} \
+             (see the diff above). Update the snapshot if this change is intentional.",
+            detail.map(|d| format!(": {d}")).unwrap_or_default()
+        ))
     })
 }
 
@@ -207,9 +1136,15 @@ fn remove_binary_sidecar(path: &Path, snapshot: &Snapshot) -> PyResult<Option<Pa
     Ok(None)
 }
 
-/// Removes a pending `.snap.new` file and, for binary snapshots, its sidecar data file.
+/// Removes a pending `.snap.new` file and, for binary snapshots, its sidecar data file,
+/// along with the side-by-side HTML diff written for it, if any.
 fn remove_pending_files(pending_path: &Path, snapshot: &Snapshot) -> PyResult<()> {
     remove_binary_sidecar(pending_path, snapshot)?;
+    let html = diff::html_diff_path(&pending_path.with_extension(""));
+    if html.exists() {
+        std::fs::remove_file(&html)
+            .map_err(|e| PyValueError::new_err(format!("Unable to remove diff {html:?}: {e}")))?;
+    }
     std::fs::remove_file(pending_path).map_err(|e| {
         PyValueError::new_err(format!(
             "Unable to remove pending snapshot {pending_path:?}: {e}"
@@ -231,22 +1166,31 @@ fn ensure_pending(pending_path: &Path) -> PyResult<()> {
 /// Accepts a pending snapshot by persisting it to its target `.snap` file.
 ///
 /// The pending snapshot is loaded through insta so the committed snapshot is written with the
-/// correct format (pending-only metadata is trimmed and binary sidecars are handled). The pending
-/// `.snap.new` file (and any binary sidecar) is removed afterwards. Returns the target path.
+/// correct format (pending-only metadata is trimmed and binary sidecars are handled), given its
+/// [`checksum`], and listed in its folder's [`manifest`] under the test its header names. The pending `.snap.new` file
+/// (and any binary sidecar) is removed afterwards. Returns the target path.
 #[pyfunction]
 pub fn accept_pending_snapshot(pending_path: PathBuf) -> PyResult<PathBuf> {
     ensure_pending(&pending_path)?;
     let target = pending_path.with_extension("");
+    let existed = target.exists();
     let snapshot = Snapshot::from_file(&pending_path).map_err(|e| {
         PyValueError::new_err(format!(
             "Unable to load pending snapshot from {pending_path:?}, details: {e}"
         ))
     })?;
-    snapshot.save(&target).map_err(|e| {
+    atomic::save_snapshot(&snapshot, &target).map_err(|e| {
         PyValueError::new_err(format!(
             "Unable to save snapshot to {target:?}, details: {e}"
         ))
     })?;
+    checksum::stamp(&target)?;
+    let test = mocks::read_info_field(&target, "test");
+    manifest::record(&target, !existed, test).map_err(|e| {
+        PyValueError::new_err(format!(
+            "Unable to record snapshot {target:?} in its manifest: {e}"
+        ))
+    })?;
     remove_pending_files(&pending_path, &snapshot)?;
     Ok(target)
 }
@@ -273,7 +1217,8 @@ pub fn reject_pending_snapshot(pending_path: PathBuf) -> PyResult<()> {
 /// deletion (`pysnaptest unused --delete`) removes exactly what insta wrote and
 /// never an unrelated sibling such as a `.snap.new` pending file. Returns the
 /// removed paths (the sidecar first, when present, then the metadata file). A
-/// corrupt/unreadable metadata file is still removed.
+/// corrupt/unreadable metadata file is still removed. Its lines are dropped
+/// from its folder's [`manifest`].
 #[pyfunction]
 pub fn delete_snapshot(snapshot_path: PathBuf) -> PyResult<Vec<PathBuf>> {
     let mut removed = Vec::new();
@@ -285,6 +1230,11 @@ pub fn delete_snapshot(snapshot_path: PathBuf) -> PyResult<Vec<PathBuf>> {
     std::fs::remove_file(&snapshot_path).map_err(|e| {
         PyValueError::new_err(format!("Unable to remove snapshot {snapshot_path:?}: {e}"))
     })?;
+    manifest::forget(&snapshot_path).map_err(|e| {
+        PyValueError::new_err(format!(
+            "Unable to drop snapshot {snapshot_path:?} from its manifest: {e}"
+        ))
+    })?;
     removed.push(snapshot_path);
     Ok(removed)
 }
@@ -313,7 +1263,7 @@ pub fn print_pending_diff(pending_path: PathBuf, workspace_root: Option<PathBuf>
         None
     };
     let root = workspace_root
-        .or_else(|| std::env::var_os("INSTA_WORKSPACE_ROOT").map(PathBuf::from))
+        .or_else(insta_env::workspace_root)
         .or_else(|| std::env::current_dir().ok())
         .unwrap_or_else(|| PathBuf::from("."));
     let title = new_snapshot
@@ -329,47 +1279,496 @@ pub fn print_pending_diff(pending_path: PathBuf, workspace_root: Option<PathBuf>
     Ok(())
 }
 
+impl SnapshotInfo {
+    /// [`SnapshotInfo::for_test`] for a test found through Python
+    /// introspection rather than `PYTEST_CURRENT_TEST`; the file is recorded
+    /// relative to the current directory when it lies below it.
+    fn for_source_file(file: &Path, test_name: &str, allow_duplicates: bool) -> PyResult<Self> {
+        if !file.is_file() {
+            return Err(PyValueError::new_err(format!(
+                "{} is not a file; use SnapshotInfo.manual to choose where snapshots go",
+                file.display()
+            )));
+        }
+        let recorded = std::env::current_dir()
+            .ok()
+            .and_then(|cwd| file.strip_prefix(cwd).ok().map(Path::to_path_buf))
+            .unwrap_or_else(|| file.to_path_buf());
+        Ok(Self {
+            allow_duplicates,
+            ..Self::for_test(file, &recorded, test_name)?
+        })
+    }
+
+    /// Snapshot info for the pytest test `node` (parsed from a node id such
+    /// as `PYTEST_CURRENT_TEST`) with the overrides of
+    /// `SnapshotInfo.from_pytest` applied. Without a test, it falls back to
+    /// the interactive session.
+    pub(crate) fn for_pytest_node(
+        node: Result<PytestInfo, PytestInfoError>,
+        snapshot_path_override: Option<PathBuf>,
+        snapshot_name_override: Option<String>,
+        allow_duplicates: bool,
+        prefix: Option<&str>,
+    ) -> PyResult<Self> {
+        let info = if let (Some(snapshot_folder), Some(snapshot_name)) = (
+            snapshot_path_override.clone(),
+            snapshot_name_override.clone(),
+        ) {
+            // Still prefixed with the running test's module, if any.
+            let module = node
+                .as_ref()
+                .ok()
+                .and_then(|info| Some(info.test_path_raw().file_stem()?.to_str()?.to_string()));
+            Self {
+                snapshot_folder,
+                snapshot_name,
+                relative_test_file_path: None,
+                allow_duplicates,
+                prefix: resolve_prefix(None, module.as_deref())?,
+                description: None,
+                expression: None,
+                settings: None,
+                stage: node
+                    .as_ref()
+                    .ok()
+                    .and_then(|info| info.stage().map(str::to_string)),
+                node_id: None,
+                scope: None,
+                namespace: None,
+                storage: None,
+                redactions: HashMap::new(),
+                module,
+            }
+        } else {
+            let pytest_info: SnapshotInfo = match node {
+                Ok(pytest_info) => pytest_info.try_into()?,
+                Err(PytestInfoError::InvalidEnvVar(std::env::VarError::NotPresent)) => {
+                    Self::interactive_session()?.ok_or_else(|| {
+                        PyValueError::new_err(
+                            "PYTEST_CURRENT_TEST is not set; outside pytest, start an \
+                             interactive session (pysnaptest.interactive.start_session) \
+                             or pass both snapshot_path and snapshot_name",
+                        )
+                    })?
+                }
+                Err(e) => return Err(e.into()),
+            };
+            Self {
+                snapshot_folder: snapshot_path_override.unwrap_or(pytest_info.snapshot_folder),
+                snapshot_name: snapshot_name_override.map_or(pytest_info.snapshot_name, |v| {
+                    v.split('-').next().map_or(v.clone(), |s| s.to_string())
+                }),
+                allow_duplicates,
+                ..pytest_info
+            }
+        };
+        info.with_prefix(prefix)
+    }
+}
+
+impl SnapshotInfo {
+    /// [`SnapshotInfo::for_pytest_node`] shared in `scope` and built under
+    /// the `SnapshotSettings` entered on this thread with `settings` applied
+    /// over them; `snapshot_path_override` wins over their `snapshot_path`.
+    #[allow(clippy::too_many_arguments)]
+    fn for_pytest_node_under(
+        node: Result<PytestInfo, PytestInfoError>,
+        snapshot_path_override: Option<PathBuf>,
+        snapshot_name_override: Option<String>,
+        allow_duplicates: bool,
+        prefix: Option<&str>,
+        settings: Option<&SnapshotSettings>,
+        scope: Option<&str>,
+    ) -> PyResult<Self> {
+        let scope = Scope::parse(scope)?;
+        let settings = SnapshotSettings::resolve(settings);
+        let snapshot_path_override = snapshot_path_override
+            .or_else(|| settings.as_ref().and_then(|s| s.snapshot_path().cloned()));
+        let (keep_name, keep_folder) = (
+            snapshot_name_override.is_some(),
+            snapshot_path_override.is_some(),
+        );
+        Ok(Self::for_pytest_node(
+            node,
+            snapshot_path_override,
+            snapshot_name_override,
+            allow_duplicates,
+            prefix,
+        )?
+        .with_scope(scope, keep_name, keep_folder)
+        .with_prefix(prefix)?
+        .with_settings(settings))
+    }
+}
+
 #[pymethods]
 impl SnapshotInfo {
+    /// Snapshot info for the running pytest test, built under the
+    /// `SnapshotSettings` entered on this thread with `settings` applied over
+    /// them; `snapshot_path_override` wins over their `snapshot_path`.
+    ///
+    /// With `scope` `"module"` or `"session"`, the snapshots are shared by
+    /// every test of the module or session, e.g. those of an expensive
+    /// shared fixture, and each test reading them numbers them from the
+    /// base name again.
+    ///
+    /// `node_id`, e.g. `request.node.nodeid`, names the test instead of
+    /// `PYTEST_CURRENT_TEST`, as `from_node_id` does, so runners that do not
+    /// set the variable work too.
     #[staticmethod]
-    #[pyo3(signature = (snapshot_path_override = None, snapshot_name_override = None, allow_duplicates = false))]
+    #[pyo3(signature = (snapshot_path_override = None, snapshot_name_override = None, allow_duplicates = false, prefix = None, settings = None, scope = None, node_id = None))]
+    #[allow(clippy::too_many_arguments)]
     fn from_pytest(
         snapshot_path_override: Option<PathBuf>,
         snapshot_name_override: Option<String>,
         allow_duplicates: bool,
+        prefix: Option<&str>,
+        settings: Option<SnapshotSettings>,
+        scope: Option<&str>,
+        node_id: Option<&str>,
+    ) -> PyResult<Self> {
+        if let Some(node_id) = node_id {
+            return Self::from_node_id(
+                node_id,
+                snapshot_path_override,
+                snapshot_name_override,
+                allow_duplicates,
+                prefix,
+                settings,
+                scope,
+            );
+        }
+        Self::for_pytest_node_under(
+            PytestInfo::from_env(),
+            snapshot_path_override,
+            snapshot_name_override,
+            allow_duplicates,
+            prefix,
+            settings.as_ref(),
+            scope,
+        )
+    }
+
+    /// Snapshot info for the pytest test `node_id`, as `from_pytest` builds
+    /// it for the running test. A fixture passes the requesting test's
+    /// (`request.node.nodeid`), or a fixed id of its own, so the snapshots it
+    /// asserts during setup, or in a shared scope, are named after and
+    /// recorded by that test rather than whatever test pytest is running.
+    #[staticmethod]
+    #[pyo3(signature = (node_id, snapshot_path_override = None, snapshot_name_override = None, allow_duplicates = false, prefix = None, settings = None, scope = None))]
+    #[allow(clippy::too_many_arguments)]
+    fn from_node_id(
+        node_id: &str,
+        snapshot_path_override: Option<PathBuf>,
+        snapshot_name_override: Option<String>,
+        allow_duplicates: bool,
+        prefix: Option<&str>,
+        settings: Option<SnapshotSettings>,
+        scope: Option<&str>,
+    ) -> PyResult<Self> {
+        let node_id = reruns::split_stage(node_id).0;
+        Ok(Self {
+            node_id: Some(node_id.to_string()),
+            ..Self::for_pytest_node_under(
+                node_id.parse(),
+                snapshot_path_override,
+                snapshot_name_override,
+                allow_duplicates,
+                prefix,
+                settings.as_ref(),
+                scope,
+            )?
+        })
+    }
+
+    /// Snapshot info with an explicit folder and snapshot name, for use
+    /// outside pytest (notebooks, scripts, doctest harnesses). Entered
+    /// `SnapshotSettings` apply, except for their folder.
+    #[staticmethod]
+    #[pyo3(signature = (folder, name, allow_duplicates = false, prefix = None))]
+    fn manual(
+        folder: PathBuf,
+        name: String,
+        allow_duplicates: bool,
+        prefix: Option<&str>,
     ) -> PyResult<Self> {
+        Ok(Self {
+            snapshot_folder: folder,
+            snapshot_name: name,
+            relative_test_file_path: None,
+            allow_duplicates,
+            module: None,
+            prefix: resolve_prefix(prefix, None)?,
+            description: None,
+            expression: None,
+            settings: None,
+            stage: None,
+            node_id: None,
+            scope: None,
+            namespace: None,
+            storage: None,
+            redactions: HashMap::new(),
+        }
+        .with_settings(SnapshotSettings::resolve(None)))
+    }
+
+    /// Snapshot info for the function running in `frame`, given as an
+    /// `inspect.FrameInfo` (e.g. `inspect.stack()[1]`) or a frame object.
+    /// Like a pytest test's, its snapshots are stored in `snapshots/` next to
+    /// its file and named `<file stem>_<function>`.
+    #[staticmethod]
+    #[pyo3(signature = (frame, allow_duplicates = false, prefix = None))]
+    fn from_frame(
+        frame: &Bound<'_, PyAny>,
+        allow_duplicates: bool,
+        prefix: Option<&str>,
+    ) -> PyResult<Self> {
+        let (filename, function): (PathBuf, String) = if frame.hasattr("f_code")? {
+            let code = frame.getattr("f_code")?;
+            (
+                code.getattr("co_filename")?.extract()?,
+                code.getattr("co_name")?.extract()?,
+            )
+        } else {
+            (
+                frame.getattr("filename")?.extract()?,
+                frame.getattr("function")?.extract()?,
+            )
+        };
         Ok(
-            if let (Some(snapshot_folder), Some(snapshot_name)) = (
-                snapshot_path_override.clone(),
-                snapshot_name_override.clone(),
-            ) {
-                Self {
-                    snapshot_folder,
-                    snapshot_name,
-                    relative_test_file_path: None,
-                    allow_duplicates,
-                }
-            } else {
-                let pytest_info: SnapshotInfo = PytestInfo::from_env()?.try_into()?;
-                Self {
-                    snapshot_folder: snapshot_path_override.unwrap_or(pytest_info.snapshot_folder),
-                    snapshot_name: snapshot_name_override.map_or(pytest_info.snapshot_name, |v| {
-                        v.split('-').next().map_or(v.clone(), |s| s.to_string())
-                    }),
-                    relative_test_file_path: pytest_info.relative_test_file_path,
-                    allow_duplicates,
-                }
-            },
+            Self::for_source_file(&filename, &function, allow_duplicates)?
+                .with_prefix(prefix)?
+                .under_entered_settings(),
         )
     }
 
-    pub fn snapshot_folder(&self) -> &PathBuf {
-        &self.snapshot_folder
+    /// Snapshot info for the `unittest` test `class_name.method_name` defined
+    /// in `module_file`. Its snapshots are named `<file stem>_<class>.<method>`,
+    /// as when pytest runs the same test case.
+    #[staticmethod]
+    #[pyo3(signature = (module_file, class_name, method_name, allow_duplicates = false, prefix = None))]
+    fn from_unittest(
+        module_file: PathBuf,
+        class_name: &str,
+        method_name: &str,
+        allow_duplicates: bool,
+        prefix: Option<&str>,
+    ) -> PyResult<Self> {
+        Ok(Self::for_source_file(
+            &module_file,
+            &format!("{class_name}::{method_name}"),
+            allow_duplicates,
+        )?
+        .with_prefix(prefix)?
+        .under_entered_settings())
+    }
+
+    /// Returns a copy applying `redactions` (flat or nested, as assertions
+    /// accept them) to every JSON or CSV assertion and mocked call made with
+    /// it; an assertion's own `redactions` win per selector.
+    fn with_redactions(
+        &self,
+        #[pyo3(from_py_with = redactions::compile_redactions)] redactions: HashMap<
+            String,
+            RedactionType,
+        >,
+    ) -> Self {
+        self.with_default_redactions(redactions)
+    }
+
+    /// Returns a copy whose snapshots record what their value represents,
+    /// `description`, and the `expression` asserted in their header; either
+    /// left out (the default) keeps this info's.
+    #[pyo3(signature = (description = None, expression = None))]
+    fn with_header(&self, description: Option<String>, expression: Option<String>) -> Self {
+        Self {
+            description: description.or_else(|| self.description.clone()),
+            expression: expression.or_else(|| self.expression.clone()),
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy whose snapshots are named `<name>_<suffix>`, e.g. one
+    /// per stage of a pipeline, and numbered apart from this info's.
+    fn with_name(&self, suffix: &str) -> PyResult<Self> {
+        let suffix = sanitize_snapshot_name(suffix);
+        if suffix.is_empty() {
+            return Err(PyValueError::new_err("with_name needs a non-empty suffix"));
+        }
+        Ok(self.with_name_suffix(&suffix))
+    }
+
+    /// Returns a copy storing its snapshots in `folder`, relative to the
+    /// current directory unless absolute.
+    fn with_folder(&self, folder: PathBuf) -> PyResult<Self> {
+        Ok(Self {
+            // insta resolves relative snapshot paths against the Rust source
+            // tree, not the current directory.
+            snapshot_folder: std::path::absolute(folder)?,
+            ..self.clone()
+        })
+    }
+
+    /// Returns a copy storing its snapshots in the `namespace` subdirectory
+    /// of its folder, e.g. `"http"` to keep a fixture's recordings apart;
+    /// `None` stores them in the folder itself.
+    fn with_namespace(&self, namespace: Option<&str>) -> PyResult<Self> {
+        Ok(Self {
+            namespace: namespace.map(parse_namespace).transpose()?,
+            ..self.clone()
+        })
+    }
+
+    /// Returns a copy keeping its snapshots in `storage`, a `ScratchStorage`,
+    /// instead of its folder; `None` stores them in the folder again.
+    #[pyo3(signature = (storage))]
+    fn with_storage(&self, storage: Option<ScratchStorage>) -> Self {
+        Self {
+            storage,
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy whose snapshots may (or may not) be asserted by more
+    /// than one test.
+    fn with_allow_duplicates(&self, allow_duplicates: bool) -> Self {
+        Self {
+            allow_duplicates,
+            ..self.clone()
+        }
+    }
+
+    /// Forgets the snapshots already assigned under this name, so the next
+    /// assertion uses the base name again. Call it when a test starts, so
+    /// numbering is scoped to that run of the test.
+    fn reset_counter(&self) {
+        Self::counters().remove(&self.counter_key());
+    }
+
+    /// The interactive session's snapshot info; see
+    /// `pysnaptest.interactive.start_session`.
+    #[staticmethod]
+    fn from_session() -> PyResult<Self> {
+        Self::interactive_session()?
+            .map(Self::under_entered_settings)
+            .ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "No interactive session: set {SESSION_ENV} or run in a Jupyter notebook"
+                ))
+            })
+    }
+
+    /// Restarts the numbering of every snapshot name, e.g. when a notebook
+    /// is run again from the top.
+    #[staticmethod]
+    fn reset_all_counters() {
+        Self::counters().clear();
+    }
+
+    /// The folder the snapshots are stored in, within its namespace.
+    pub fn snapshot_folder(&self) -> PathBuf {
+        self.folder()
+    }
+
+    /// `"module"` or `"session"` for snapshots shared by the tests of a
+    /// module or of the session, `None` for those of one test.
+    pub fn scope(&self) -> Option<&'static str> {
+        self.scope.map(Scope::as_str)
+    }
+
+    /// The node ids of the tests that read this scoped snapshot name in this
+    /// session, sorted; empty for an unscoped one.
+    pub fn references(&self) -> Vec<String> {
+        scopes::referencing_tests(&self.counter_key())
+    }
+
+    /// The pytest stage (`"setup"`, `"call"` or `"teardown"`) the test was
+    /// in when this info was built, or `None` outside pytest.
+    pub fn stage(&self) -> Option<&str> {
+        self.stage.as_deref()
+    }
+
+    /// The base name of the snapshots, before numbering and suffixes.
+    #[getter(name)]
+    fn py_name(&self) -> &str {
+        &self.snapshot_name
+    }
+
+    /// The folder the snapshots are stored in, within its namespace.
+    #[getter(folder)]
+    fn py_folder(&self) -> PathBuf {
+        self.folder()
+    }
+
+    /// Whether the snapshots may be asserted by more than one test.
+    #[getter(allow_duplicates)]
+    fn py_allow_duplicates(&self) -> bool {
+        self.allow_duplicates
+    }
+
+    /// The test file recorded in the snapshots' header, relative to the
+    /// workspace root, or `None` when not built for a test file.
+    #[getter(relative_test_file_path)]
+    fn py_relative_test_file_path(&self) -> Option<&str> {
+        self.relative_test_file_path.as_deref()
+    }
+
+    /// The Python module of the test, if known.
+    #[getter(module)]
+    fn py_module(&self) -> Option<&str> {
+        self.module.as_deref()
+    }
+
+    /// Prepended to the snapshot file names, if anything.
+    #[getter(prefix)]
+    fn py_prefix(&self) -> Option<&str> {
+        self.prefix.as_deref()
+    }
+
+    /// Recorded as the snapshots' `description`, if set.
+    #[getter(description)]
+    fn py_description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Recorded as the snapshots' `expression`, if set.
+    #[getter(expression)]
+    fn py_expression(&self) -> Option<&str> {
+        self.expression.as_deref()
+    }
+
+    /// The subdirectory of the folder the snapshots are stored in, if any.
+    #[getter(namespace)]
+    fn py_namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    /// The pytest node id given to `from_node_id`, if any.
+    #[getter(node_id)]
+    fn py_node_id(&self) -> Option<&str> {
+        self.node_id.as_deref()
+    }
+
+    /// Infos are equal when they name and record their snapshots the same
+    /// way; the settings they were built under are not compared.
+    fn __eq__(&self, other: &Bound<'_, PyAny>) -> bool {
+        other
+            .downcast::<SnapshotInfo>()
+            .is_ok_and(|other| self.same_as(other.get()))
+    }
+
+    fn __hash__(&self) -> u64 {
+        self.identity_hash()
+    }
+
+    fn __repr__(&self, py: Python<'_>) -> PyResult<String> {
+        self.repr(py)
     }
 
     pub fn last_snapshot_name(&self) -> String {
         let test_idx = Self::counters()
-            .get(&self.snapshot_name)
+            .get(&self.counter_key())
             .cloned()
             .unwrap_or(1);
         self.snapshot_name_with_idx(test_idx)
@@ -377,52 +1776,161 @@ impl SnapshotInfo {
 
     pub fn next_snapshot_name(&self) -> String {
         let test_idx = Self::counters()
-            .get(&self.snapshot_name)
+            .get(&self.counter_key())
             .cloned()
             .unwrap_or(0)
             + 1;
         self.snapshot_name_with_idx(test_idx)
     }
 
-    pub fn last_snapshot_path(&self, module_path: Option<String>) -> PyResult<PathBuf> {
-        let module_path = module_path
-            .unwrap_or(module_path!().to_string())
-            .replace("::", "__");
-        Ok(self.snapshot_folder.join(format!(
-            "{module_path}__{}{SNAPSHOT_FILE_SUFFIX}",
-            self.last_snapshot_name()
-        )))
+    /// The file of the last snapshot asserted, named with the `prefix`
+    /// strategy (`"module"`, `"none"` or a custom prefix) instead of this
+    /// info's own when given. `prefix` is keyword-only: the positional
+    /// argument is the Rust module path this took before, and only `None`
+    /// is accepted there.
+    #[pyo3(signature = (module_path = None, /, *, prefix = None))]
+    pub fn last_snapshot_path(
+        &self,
+        module_path: Option<&str>,
+        prefix: Option<&str>,
+    ) -> PyResult<PathBuf> {
+        reject_module_path("last_snapshot_path", module_path)?;
+        Ok(self
+            .with_prefix(prefix)?
+            .snapshot_file(module_path!(), &self.last_snapshot_name()))
     }
 
-    pub fn next_snapshot_path(&self, module_path: Option<String>) -> PyResult<PathBuf> {
-        let module_path = module_path
-            .unwrap_or(module_path!().to_string())
-            .replace("::", "__");
-        Ok(self.snapshot_folder.join(format!(
-            "{module_path}__{}{SNAPSHOT_FILE_SUFFIX}",
-            self.next_snapshot_name()
-        )))
+    /// The file the next snapshot asserted is stored in; see
+    /// `last_snapshot_path`.
+    #[pyo3(signature = (module_path = None, /, *, prefix = None))]
+    pub fn next_snapshot_path(
+        &self,
+        module_path: Option<&str>,
+        prefix: Option<&str>,
+    ) -> PyResult<PathBuf> {
+        reject_module_path("next_snapshot_path", module_path)?;
+        Ok(self
+            .with_prefix(prefix)?
+            .snapshot_file(module_path!(), &self.next_snapshot_name()))
+    }
+}
+
+/// Rejects a module path passed to `SnapshotInfo.<method>` positionally,
+/// as before prefixes replaced it, instead of taking it for a prefix.
+fn reject_module_path(method: &str, module_path: Option<&str>) -> PyResult<()> {
+    match module_path {
+        Some(module_path) => Err(PyTypeError::new_err(format!(
+            "SnapshotInfo.{method}() no longer takes a module path (got {module_path:?}); \
+             pass the prefix strategy by keyword, e.g. prefix=\"module\""
+        ))),
+        None => Ok(()),
     }
 }
 
 #[pymodule]
 #[pyo3(name = "_pysnaptest")]
 fn pysnaptest(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    provenance::init(m.py());
     m.add_class::<SnapshotInfo>()?;
+    m.add_class::<SnapshotSettings>()?;
+    m.add_class::<ScratchStorage>()?;
 
     m.add("SNAPSHOT_SUFFIX", SNAPSHOT_FILE_SUFFIX)?;
+    m.add("MANIFEST_FILE", MANIFEST_FILE)?;
+    m.add(
+        "SNAPSHOT_FORMAT_VERSION",
+        provenance::SNAPSHOT_FORMAT_VERSION,
+    )?;
     m.add_function(wrap_pyfunction!(assert_snapshot, m)?)?;
     m.add_function(wrap_pyfunction!(assert_binary_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(strip_image_metadata, m)?)?;
+    m.add_function(wrap_pyfunction!(assert_pdf_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(assert_audio_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(assert_term_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(assert_logs_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(assert_sql_snapshot, m)?)?;
     m.add_function(wrap_pyfunction!(render_text_diff, m)?)?;
     m.add_function(wrap_pyfunction!(assert_json_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(assert_many_json_snapshots, m)?)?;
+    m.add_function(wrap_pyfunction!(assert_graphql_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(assert_openapi_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(assert_call_snapshot, m)?)?;
     m.add_function(wrap_pyfunction!(assert_csv_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(assert_hash_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(assert_stats_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(assert_schema_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(assert_snapshot_for_seed, m)?)?;
+    m.add_function(wrap_pyfunction!(seed_label, m)?)?;
+    m.add_function(wrap_pyfunction!(sanitize_snapshot_name, m)?)?;
+    m.add_function(wrap_pyfunction!(explain_snapshot_name, m)?)?;
+    m.add_function(wrap_pyfunction!(write_imported_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(upgrade_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(pending_summary, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_against_schema_snapshot, m)?)?;
+    m.add(
+        "SchemaViolationError",
+        m.py().get_type::<schema::SchemaViolationError>(),
+    )?;
+    m.add(
+        "SerializationError",
+        m.py().get_type::<serialization::SerializationError>(),
+    )?;
+    m.add(
+        "SnapshotCorruptedError",
+        m.py().get_type::<checksum::SnapshotCorruptedError>(),
+    )?;
+    m.add(
+        "SnapshotMismatchError",
+        m.py().get_type::<panic::SnapshotMismatchError>(),
+    )?;
+    m.add(
+        "SnapshotReadOnlyError",
+        m.py().get_type::<panic::SnapshotReadOnlyError>(),
+    )?;
+    m.add(
+        "SnapshotTeardownWarning",
+        m.py().get_type::<SnapshotTeardownWarning>(),
+    )?;
+    m.add_function(wrap_pyfunction!(assert_vector_snapshot, m)?)?;
     m.add_function(wrap_pyfunction!(prepare_mock_call, m)?)?;
+    m.add_function(wrap_pyfunction!(preview_json_snapshot_named, m)?)?;
     m.add_function(wrap_pyfunction!(assert_json_snapshot_named, m)?)?;
     m.add_function(wrap_pyfunction!(read_json_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(read_json_snapshot_text, m)?)?;
+    m.add_function(wrap_pyfunction!(register_replay_decoder, m)?)?;
+    m.add_function(wrap_pyfunction!(recorded_duration_ms, m)?)?;
+    m.add_function(wrap_pyfunction!(recorded_at, m)?)?;
+    m.add_function(wrap_pyfunction!(recorded_type, m)?)?;
+    m.add_function(wrap_pyfunction!(recorded_by, m)?)?;
     m.add_function(wrap_pyfunction!(accept_pending_snapshot, m)?)?;
     m.add_function(wrap_pyfunction!(reject_pending_snapshot, m)?)?;
     m.add_function(wrap_pyfunction!(delete_snapshot, m)?)?;
+    m.add_function(wrap_pyfunction!(snapshot_content_hash, m)?)?;
     m.add_function(wrap_pyfunction!(print_pending_diff, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_redactions, m)?)?;
+    m.add_function(wrap_pyfunction!(set_default_redactions, m)?)?;
+    m.add_function(wrap_pyfunction!(set_config_redactions, m)?)?;
+    m.add_function(wrap_pyfunction!(set_round_floats, m)?)?;
+    m.add_function(wrap_pyfunction!(set_json_format, m)?)?;
+    m.add_function(wrap_pyfunction!(set_serialization_limits, m)?)?;
+    m.add_function(wrap_pyfunction!(serialization_limits_info, m)?)?;
+    m.add_function(wrap_pyfunction!(get_effective_config, m)?)?;
+    m.add_function(wrap_pyfunction!(set_structural_json_comparison, m)?)?;
+    m.add_function(wrap_pyfunction!(set_diff_options, m)?)?;
+    m.add_function(wrap_pyfunction!(set_fail_fast, m)?)?;
+    m.add_function(wrap_pyfunction!(set_read_only, m)?)?;
+    m.add_function(wrap_pyfunction!(panic::take_soft_failures, m)?)?;
+    m.add_function(wrap_pyfunction!(scan_for_secrets, m)?)?;
+    m.add_function(wrap_pyfunction!(configure_storage, m)?)?;
+    m.add_function(wrap_pyfunction!(observe_test_stage, m)?)?;
+    m.add_function(wrap_pyfunction!(settle_repetitions, m)?)?;
+    m.add_function(wrap_pyfunction!(clear_replay_cache, m)?)?;
+    m.add_function(wrap_pyfunction!(set_replay_cache_size, m)?)?;
+    m.add_function(wrap_pyfunction!(replay_cache_info, m)?)?;
     m.add_class::<PySnapshot>()?;
+    m.add_class::<SnapshotBuffer>()?;
+    m.add_class::<PatchJsonSnapshot>()?;
+    m.add_class::<Golden>()?;
+    m.add_function(wrap_pyfunction!(characterize, m)?)?;
     Ok(())
 }
@@ -0,0 +1,92 @@
+//! Advisory locks serializing the writes to one snapshot.
+//!
+//! An assertion compares against the committed `.snap` and, on a mismatch
+//! or an update, writes the `.snap.new` or `.snap` in place; mock recordings
+//! then rewrite fields of its header. `pytest -n` workers (or threads)
+//! recording the same shared fixture would interleave those writes and leave
+//! partial content, so each assertion, and each header rewrite, holds an
+//! exclusive lock on the snapshot while it runs.
+//!
+//! The lock is taken on a file named after the snapshot's absolute path in
+//! `pysnaptest-locks/` of the system temp directory, outside the snapshots
+//! folder so nothing is left behind to commit. Locks are advisory and best
+//! effort: when the lock file cannot be created, the write goes ahead
+//! unlocked.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+
+use pyo3::prelude::*;
+use sha2::{Digest, Sha256};
+
+use crate::hashing::hex;
+
+thread_local! {
+    /// The snapshots this thread holds the lock of. A second lock on the same
+    /// file from this process would wait for the first one forever.
+    static HELD: RefCell<HashSet<PathBuf>> = RefCell::new(HashSet::new());
+}
+
+/// The lock on a snapshot, released when dropped.
+pub(crate) struct SnapshotLock {
+    /// The snapshot locked, unless this thread already held its lock.
+    snapshot: Option<PathBuf>,
+    _file: Option<File>,
+}
+
+impl Drop for SnapshotLock {
+    fn drop(&mut self) {
+        if let Some(snapshot) = &self.snapshot {
+            HELD.with(|held| held.borrow_mut().remove(snapshot));
+        }
+    }
+}
+
+/// The snapshot `path` names: pending `.snap.new` files share the lock of
+/// their `.snap`.
+fn locked_snapshot(path: &Path) -> PathBuf {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    match path.to_str().and_then(|p| p.strip_suffix(".new")) {
+        Some(snapshot) => PathBuf::from(snapshot),
+        None => path,
+    }
+}
+
+fn open_lock_file(snapshot: &Path) -> std::io::Result<File> {
+    let dir = std::env::temp_dir().join("pysnaptest-locks");
+    std::fs::create_dir_all(&dir)?;
+    let name = hex(&Sha256::digest(snapshot.as_os_str().as_encoded_bytes()));
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(dir.join(format!("{name}.lock")))
+}
+
+/// Waits for the exclusive lock on the snapshot at `path` (a `.snap` or its
+/// `.snap.new`), with the GIL released so the thread holding it can finish.
+/// Reentrant within a thread.
+pub(crate) fn lock(path: &Path) -> SnapshotLock {
+    let snapshot = locked_snapshot(path);
+    if !HELD.with(|held| held.borrow_mut().insert(snapshot.clone())) {
+        return SnapshotLock {
+            snapshot: None,
+            _file: None,
+        };
+    }
+    let file = open_lock_file(&snapshot)
+        .ok()
+        .filter(|file| match file.try_lock() {
+            Ok(()) => true,
+            Err(TryLockError::WouldBlock) => {
+                Python::with_gil(|py| py.allow_threads(|| file.lock())).is_ok()
+            }
+            Err(TryLockError::Error(_)) => false,
+        });
+    SnapshotLock {
+        snapshot: Some(snapshot),
+        _file: file,
+    }
+}
@@ -0,0 +1,149 @@
+//! Rendering of log records for `assert_logs_snapshot`.
+//!
+//! Each record becomes one `LEVEL logger: message` line (continuation lines
+//! of a multi-line message are indented by two spaces). Only the level,
+//! logger name and message are kept, since record timestamps, thread and
+//! process ids and source locations differ between runs. Values that
+//! commonly end up *inside* messages are normalized by the [`FILTERS`]
+//! below.
+
+use once_cell::sync::Lazy;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use regex::Regex;
+
+/// Built-in message filters, applied in order: a pattern and its
+/// replacement.
+static FILTERS: Lazy<Vec<(Regex, &'static str)>> = Lazy::new(|| {
+    [
+        (
+            r"\b\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z|[+-]\d{2}:?\d{2})?\b",
+            "[timestamp]",
+        ),
+        (r"\b\d{2}:\d{2}:\d{2}(?:[.,]\d+)?\b", "[time]"),
+        (
+            r"(?i)\b[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}\b",
+            "[uuid]",
+        ),
+        (r"\b0x[0-9a-fA-F]{6,}\b", "[address]"),
+        (
+            r"\b(Thread|Dummy|ThreadPoolExecutor-\d+)[-_]\d+\b",
+            "${1}-[n]",
+        ),
+        (
+            r"(?i)\b(thread(?:[ _]?id)?|tid|pid)([=: ]+)\d+\b",
+            "${1}${2}[id]",
+        ),
+        // Absolute paths keep their last component.
+        (
+            r#"(^|[\s"'(=])(?:/[\w.@+-]+)+/([\w.@+-]+)"#,
+            "${1}[path]/${2}",
+        ),
+        (
+            r#"\b[A-Za-z]:\\(?:[^\\\s"']+\\)*([^\\\s"']+)"#,
+            "[path]\\${1}",
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(pattern, replacement)| Regex::new(pattern).ok().map(|re| (re, replacement)))
+    .collect()
+});
+
+/// Applies the built-in [`FILTERS`] to `message`.
+pub(crate) fn normalize_message(message: &str) -> String {
+    FILTERS
+        .iter()
+        .fold(message.to_string(), |message, (re, replacement)| {
+            re.replace_all(&message, *replacement).into_owned()
+        })
+}
+
+/// The standard `logging` name of a numeric level.
+fn level_name(level: i64) -> String {
+    match level {
+        50 => "CRITICAL".to_string(),
+        40 => "ERROR".to_string(),
+        30 => "WARNING".to_string(),
+        20 => "INFO".to_string(),
+        10 => "DEBUG".to_string(),
+        0 => "NOTSET".to_string(),
+        other => format!("Level {other}"),
+    }
+}
+
+/// Level, logger and message of a `logging.LogRecord`-like object or a dict
+/// with `levelname`/`level`, `name`/`logger` and `message`/`msg` keys.
+fn record_fields(index: usize, record: &Bound<'_, PyAny>) -> PyResult<(String, String, String)> {
+    let field = |keys: &[&str]| -> PyResult<Option<Bound<'_, PyAny>>> {
+        for key in keys {
+            let value = match record.downcast::<PyDict>() {
+                Ok(dict) => dict.get_item(key)?,
+                Err(_) => record.getattr(*key).ok(),
+            };
+            if let Some(value) = value.filter(|v| !v.is_none()) {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    };
+    let missing = |what: &str| {
+        PyValueError::new_err(format!(
+            "Log record {index} has no {what}; expected a logging.LogRecord or a dict \
+             with \"levelname\", \"name\" and \"message\" keys"
+        ))
+    };
+
+    let level = match field(&["levelname", "level"])?.ok_or_else(|| missing("level"))? {
+        level if level.extract::<i64>().is_ok() => level_name(level.extract()?),
+        level => level.str()?.to_string(),
+    };
+    let logger = match field(&["name", "logger"])? {
+        Some(logger) => logger.str()?.to_string(),
+        None => "root".to_string(),
+    };
+    let mut message = if record.downcast::<PyDict>().is_err() && record.hasattr("getMessage")? {
+        record.call_method0("getMessage")?.str()?.to_string()
+    } else {
+        field(&["message", "msg"])?
+            .ok_or_else(|| missing("message"))?
+            .str()?
+            .to_string()
+    };
+    // The exception's type and message, but not its traceback, whose paths
+    // and line numbers change with every edit.
+    if let Some(exc_info) = field(&["exc_info"])? {
+        if let Ok((kind, value)) = exc_info
+            .get_item(0)
+            .and_then(|kind| Ok((kind, exc_info.get_item(1)?)))
+        {
+            let kind = kind.getattr("__name__")?.str()?.to_string();
+            let value = value.str()?.to_string();
+            message.push('\n');
+            message.push_str(&if value.is_empty() {
+                kind
+            } else {
+                format!("{kind}: {value}")
+            });
+        }
+    }
+    Ok((level, logger, message))
+}
+
+/// Renders `records` as one `LEVEL logger: message` line each, with the
+/// built-in filters applied to the messages when `normalize` is set.
+pub(crate) fn render(records: &Bound<'_, PyAny>, normalize: bool) -> PyResult<String> {
+    let mut rendered = String::new();
+    for (index, record) in records.try_iter()?.enumerate() {
+        let (level, logger, message) = record_fields(index, &record?)?;
+        let message = if normalize {
+            normalize_message(&message)
+        } else {
+            message
+        };
+        rendered.push_str(&format!("{level} {logger}: "));
+        rendered.push_str(&message.trim_end().replace('\n', "\n  "));
+        rendered.push('\n');
+    }
+    Ok(rendered)
+}
@@ -0,0 +1,148 @@
+//! The audit trail of the snapshots pysnaptest writes.
+//!
+//! Every snapshot folder keeps a `.pysnaptest-manifest.jsonl` next to its
+//! snapshots. Whenever an assertion (or `pysnaptest accept`) creates a
+//! snapshot, or rewrites one with new contents, a line is appended to it: the
+//! test that wrote it, the snapshot's file name, whether it was `created` or
+//! `updated`, the SHA-256 of its contents and when. A reviewer can then tell
+//! a snapshot pysnaptest wrote from one edited or dropped in by hand, which
+//! `pysnaptest.verify_manifest` reports.
+//!
+//! The hash covers the contents insta compares, not the header, so the
+//! metadata pysnaptest rewrites itself (a mock's `recorded_at`, repetition
+//! counts, `pysnaptest upgrade`) does not make a snapshot look edited.
+//! Deleting a snapshot through pysnaptest drops its lines ([`forget`]).
+//! Snapshots kept in a `ScratchStorage` are not listed.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use insta::internals::SnapshotContents;
+use insta::Snapshot;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::atomic;
+use crate::hashing::hex;
+use crate::lockfile;
+use crate::provenance;
+
+/// The file name of a snapshot folder's manifest.
+pub const MANIFEST_FILE: &str = ".pysnaptest-manifest.jsonl";
+
+/// One line of a manifest.
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    test: Option<String>,
+    path: String,
+    change: String,
+    sha256: String,
+    timestamp: String,
+}
+
+/// The manifest of the folder holding `snapshot`.
+fn manifest_of(snapshot: &Path) -> PathBuf {
+    snapshot.with_file_name(MANIFEST_FILE)
+}
+
+fn file_name(snapshot: &Path) -> String {
+    snapshot
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// The SHA-256 of the contents of the snapshot at `snapshot`, as insta reads
+/// them: the text below the header, or the bytes of a binary snapshot (none
+/// when its data file is missing).
+pub(crate) fn content_hash(snapshot: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let loaded = Snapshot::from_file(snapshot)?;
+    let digest = match loaded.contents() {
+        SnapshotContents::Text(text) => Sha256::digest(text.to_string().as_bytes()),
+        // The data of a binary snapshot may be absent, as when gitignored.
+        SnapshotContents::Binary(bytes) => {
+            Sha256::digest(bytes.as_deref().map_or(&[][..], Vec::as_slice))
+        }
+    };
+    Ok(hex(&digest))
+}
+
+/// The entries of the manifest at `manifest`, skipping unreadable lines.
+fn entries(manifest: &Path) -> io::Result<Vec<Entry>> {
+    match fs::read_to_string(manifest) {
+        Ok(text) => Ok(text
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Appends the line of `snapshot`, written by `test`, to its folder's
+/// manifest: `created` when it did not exist before, otherwise `updated`,
+/// unless its contents are those the manifest already lists.
+pub(crate) fn record(snapshot: &Path, created: bool, test: Option<String>) -> io::Result<()> {
+    let sha256 = content_hash(snapshot).map_err(|e| io::Error::other(e.to_string()))?;
+    let manifest = manifest_of(snapshot);
+    let path = file_name(snapshot);
+    let _lock = lockfile::lock(&manifest);
+    if !created {
+        let listed = entries(&manifest)?
+            .into_iter()
+            .rfind(|entry| entry.path == path)
+            .is_some_and(|entry| entry.sha256 == sha256);
+        if listed {
+            return Ok(());
+        }
+    }
+    let entry = Entry {
+        test,
+        path,
+        change: if created { "created" } else { "updated" }.to_string(),
+        sha256,
+        timestamp: provenance::utc_timestamp(SystemTime::now()),
+    };
+    let mut line = serde_json::to_string(&entry).map_err(io::Error::other)?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&manifest)?
+        .write_all(line.as_bytes())
+}
+
+/// Drops the lines of `snapshot` from its folder's manifest, if it has one.
+pub(crate) fn forget(snapshot: &Path) -> io::Result<()> {
+    let manifest = manifest_of(snapshot);
+    let path = file_name(snapshot);
+    let _lock = lockfile::lock(&manifest);
+    let Ok(text) = fs::read_to_string(&manifest) else {
+        return Ok(());
+    };
+    let kept: String = text
+        .lines()
+        .filter(|line| serde_json::from_str::<Entry>(line).map_or(true, |entry| entry.path != path))
+        .flat_map(|line| [line, "\n"])
+        .collect();
+    if kept.len() == text.len() {
+        return Ok(());
+    }
+    atomic::write(&manifest, kept)
+}
+
+/// Returns the SHA-256 a snapshot folder's manifest lists for the snapshot
+/// at `snapshot_path`: that of its contents below the header, or of its
+/// bytes for a binary snapshot.
+#[pyfunction]
+pub fn snapshot_content_hash(snapshot_path: PathBuf) -> PyResult<String> {
+    content_hash(&snapshot_path).map_err(|e| {
+        PyValueError::new_err(format!(
+            "Unable to load snapshot from {snapshot_path:?}, details: {e}"
+        ))
+    })
+}
@@ -0,0 +1,184 @@
+//! Memory-mapped access to large snapshot files.
+//!
+//! Loading a recorded multi-MB blob through insta reads it into a `Vec<u8>`,
+//! which pyo3 then copies again into Python `bytes`. Above
+//! [`MMAP_THRESHOLD`] pysnaptest maps the file instead: `PySnapshot.contents()`
+//! returns a read-only [`SnapshotBuffer`] that exposes the mapping through the
+//! buffer protocol (`memoryview(buf)`, `bytes(buf)`, `numpy.frombuffer(buf)`),
+//! and mock replay parses JSON straight out of the mapping.
+//!
+//! A mapping is only sound while nobody rewrites the file in place: a reader
+//! would see torn data, or be killed by `SIGBUS` when the file shrinks.
+//! pysnaptest only ever replaces snapshot files by rename (see
+//! [`atomic`](crate::atomic)), which leaves a mapping of the old file intact,
+//! but insta rewrites a stored snapshot in place when `INSTA_UPDATE` is
+//! `always` or `force`. Under those modes the file is read into memory
+//! instead, as it is below the threshold.
+
+use std::ffi::{c_int, c_void};
+use std::fs::File;
+use std::io;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+use memmap2::Mmap;
+use pyo3::exceptions::PyBufferError;
+use pyo3::ffi;
+use pyo3::prelude::*;
+use pyo3::pybacked::PyBackedBytes;
+use pyo3::types::PyBytes;
+
+use crate::insta_env;
+
+/// Files at least this large (1 MiB) are memory-mapped rather than read.
+pub(crate) const MMAP_THRESHOLD: u64 = 1024 * 1024;
+
+/// A read-only view of a whole file: mapped, or a copy when the file may be
+/// rewritten in place.
+pub(crate) enum MappedFile {
+    Mapped(Mmap),
+    Copied(Vec<u8>),
+}
+
+impl MappedFile {
+    pub(crate) fn open(path: &Path) -> io::Result<Self> {
+        if insta_env::rewrites_in_place() {
+            return std::fs::read(path).map(Self::Copied);
+        }
+        let file = File::open(path)?;
+        // SAFETY: insta does not rewrite stored snapshots in place under the
+        // update mode in effect, and pysnaptest replaces them by rename, so
+        // the mapped file is not modified while the mapping lives.
+        unsafe { Mmap::map(&file) }.map(Self::Mapped)
+    }
+}
+
+impl Deref for MappedFile {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Mapped(mmap) => mmap,
+            Self::Copied(data) => data,
+        }
+    }
+}
+
+/// Reports whether `path` is large enough to be worth mapping.
+pub(crate) fn should_map(path: &Path) -> bool {
+    std::fs::metadata(path).is_ok_and(|m| m.len() >= MMAP_THRESHOLD)
+}
+
+/// Read-only contents of a large binary snapshot, zero-copy unless the file
+/// may be rewritten in place.
+///
+/// Supports the buffer protocol, `len()`, `bytes()` and equality with
+/// bytes-like values.
+#[pyclass(frozen)]
+pub struct SnapshotBuffer {
+    data: MappedFile,
+}
+
+impl SnapshotBuffer {
+    pub(crate) fn new(data: MappedFile) -> Self {
+        Self { data }
+    }
+}
+
+#[pymethods]
+impl SnapshotBuffer {
+    unsafe fn __getbuffer__(
+        slf: Bound<'_, Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        if flags & ffi::PyBUF_WRITABLE != 0 {
+            return Err(PyBufferError::new_err("snapshot buffers are read-only"));
+        }
+        let data: &[u8] = &slf.get().data;
+        // SAFETY: `view` comes from the interpreter; the exporter (`slf`) is
+        // kept alive by the view, which keeps the mapping alive.
+        let rc = unsafe {
+            ffi::PyBuffer_FillInfo(
+                view,
+                slf.as_ptr(),
+                data.as_ptr() as *mut c_void,
+                data.len() as ffi::Py_ssize_t,
+                1,
+                flags,
+            )
+        };
+        if rc == -1 {
+            return Err(PyErr::fetch(slf.py()));
+        }
+        Ok(())
+    }
+
+    unsafe fn __releasebuffer__(&self, _view: *mut ffi::Py_buffer) {}
+
+    fn __len__(&self) -> usize {
+        self.data.len()
+    }
+
+    fn __bytes__<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.data)
+    }
+
+    fn __eq__(&self, other: &Bound<'_, PyAny>) -> bool {
+        if let Ok(other) = other.downcast::<SnapshotBuffer>() {
+            return *self.data == *other.get().data;
+        }
+        other
+            .extract::<PyBackedBytes>()
+            .is_ok_and(|other| *self.data == *other)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<SnapshotBuffer of {} bytes>", self.data.len())
+    }
+}
+
+/// Splits a `.snap` file into its YAML metadata header and its body, for the
+/// `---`-delimited format insta writes. Returns `None` for anything else
+/// (legacy snapshots), so callers can fall back to insta's parser.
+pub(crate) fn split_snapshot(data: &[u8]) -> Option<(&str, &[u8])> {
+    let rest = data
+        .strip_prefix(b"---\n")
+        .or_else(|| data.strip_prefix(b"---\r\n"))?;
+    let mut offset = 0;
+    for line in rest.split_inclusive(|&b| b == b'\n') {
+        if line.trim_ascii_end() == b"---" {
+            let header = std::str::from_utf8(&rest[..offset]).ok()?;
+            return Some((header, &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Returns the sidecar path of a binary snapshot by reading only its small
+/// `.snap` header, so the (possibly huge) data file is not loaded the way
+/// `insta::Snapshot::from_file` would. `None` for text or legacy snapshots.
+pub(crate) fn binary_sidecar(snapshot_path: &Path) -> Option<PathBuf> {
+    use std::io::BufRead;
+
+    let mut lines = io::BufReader::new(std::fs::File::open(snapshot_path).ok()?).lines();
+    if lines.next()?.ok()?.trim_end() != "---" {
+        return None;
+    }
+    let mut binary = false;
+    let mut extension = None;
+    for line in lines {
+        let line = line.ok()?;
+        match line.split_once(':') {
+            _ if line.trim_end() == "---" => break,
+            Some(("snapshot_kind", kind)) => binary = kind.trim() == "binary",
+            Some(("extension", ext)) => extension = Some(ext.trim().trim_matches('"').to_string()),
+            _ => {}
+        }
+    }
+    let extension = extension.filter(|_| binary)?;
+    let mut sidecar = snapshot_path.as_os_str().to_owned();
+    sidecar.push(format!(".{extension}"));
+    Some(sidecar.into())
+}
@@ -3,47 +3,73 @@
 //! The orchestration for function mocks (deciding whether to actually call the
 //! wrapped function, and Pydantic/dataclass normalization via
 //! `pysnaptest.to_jsonable`) lives in Python (`pysnaptest.mocks`). But the
-//! fiddly, easy-to-get-wrong bookkeeping around snapshot naming -- scoping a
-//! mock's name, peeking its response path *before* ticking the shared
-//! duplicate counter, and deciding record-vs-replay from that path -- is done
-//! once, here, in `prepare_mock_call`, by composing the existing `SnapshotInfo`
-//! naming methods rather than duplicating their logic.
+//! fiddly, easy-to-get-wrong bookkeeping around snapshot naming -- deriving a
+//! call's snapshot stem from its mock and a digest of its request, peeking its
+//! response path *before* ticking that stem's duplicate counter, and deciding
+//! record-vs-replay from that path -- is done once, here, in
+//! `prepare_mock_call`, by composing the existing `SnapshotInfo` naming
+//! methods rather than duplicating their logic.
 //!
-//! This module exposes three thin functions to Python:
+//! This module exposes these thin functions to Python:
 //!
 //! * `prepare_mock_call` scopes the snapshot name, writes the request
 //!   snapshot (reusing `crate::bind_json_snapshot`) and returns the response
 //!   snapshot's name/path/record-decision,
 //! * `assert_json_snapshot_named` writes a JSON snapshot under an explicit
 //!   name (also reusing `crate::bind_json_snapshot`), used for the response
-//!   snapshot once the wrapped function has actually been called, and
+//!   snapshot once the wrapped function has actually been called,
+//! * `preview_json_snapshot_named` tells how such an assertion would change
+//!   the stored file without writing it, used by `mode="dry_run"`,
 //! * `read_json_snapshot` loads a recorded snapshot back into Python (reusing
 //!   insta's own file parser), used to replay a response without calling the
-//!   wrapped function.
+//!   wrapped function; a binary one replays as its bytes, or through the
+//!   decoder `register_replay_decoder` registered for its extension, and
+//!   `read_json_snapshot_text` returns a recording's JSON text instead, for a
+//!   mock's own `decode` callable,
+//! * `recorded_duration_ms` reads the call duration kept in a response
+//!   snapshot's metadata, used to simulate latency on replay,
+//! * `recorded_at` reads when the call was recorded, used to expire old
+//!   recordings, and `recorded_by` which test recorded it, used to re-record
+//!   one test's mocks,
+//! * `recorded_type` reads the type of the result a response was recorded
+//!   from, which a factory registered with the Python mock layer's
+//!   `register_result_factory` rebuilds on replay.
 //!
-//! All three live in this module so the on-disk `pysnaptest__mocks__*` filename
-//! prefix (derived from `module_path!()` at the `insta::assert_json_snapshot!`
-//! call site) is preserved.
+//! They all live in this module so their snapshots share the `mocks`
+//! namespace (`<prefix>__mocks__*`, derived from `module_path!()` at the
+//! assertion site; see `SnapshotInfo::file_stem`).
 
 use std::collections::HashMap;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 use insta::internals::SnapshotContents;
 use insta::Snapshot;
+use once_cell::sync::Lazy;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyType};
 
-use crate::{RedactionType, SnapshotInfo};
+use crate::atomic;
+use crate::encryption::{decrypt_if_encrypted, is_encrypted, render_json};
+use crate::hashing::hex;
+use crate::lockfile;
+use crate::mapped::{binary_sidecar, should_map, split_snapshot, MappedFile};
+use crate::redactions::{drop_ignored, round_floats};
+use crate::replay_cache;
+use crate::scratch_storage;
+use crate::{PySnapshot, RedactionType, SnapshotInfo, SNAPSHOT_FILE_SUFFIX};
 
 /// Records `snapshot_path` as referenced, mirroring insta's own
 /// `memoize_snapshot_file`: when `INSTA_SNAPSHOT_REFERENCES_FILE` is set, append
 /// the path (one per line) so obsolete-snapshot detection sees it as used.
 ///
 /// Best-effort: does nothing if the variable is unset or the append fails, just
-/// like insta. This exists only because mock replay returns a recorded value
-/// without running an insta assertion, so insta itself never memoizes the file.
-fn memoize_snapshot_reference(snapshot_path: &Path) {
+/// like insta. This exists only because mock replay (and schema validation)
+/// reads a snapshot without running an insta assertion, so insta itself never
+/// memoizes the file.
+pub(crate) fn memoize_snapshot_reference(snapshot_path: &Path) {
     if let Ok(ref_file) = std::env::var("INSTA_SNAPSHOT_REFERENCES_FILE") {
         if let Ok(mut f) = std::fs::OpenOptions::new()
             .append(true)
@@ -55,6 +81,70 @@ fn memoize_snapshot_reference(snapshot_path: &Path) {
     }
 }
 
+/// Hex characters of the request digest kept in a mock's snapshot stem.
+const REQUEST_DIGEST_LEN: usize = 8;
+
+/// Digest of `request` as it would be written, i.e. with `redactions` applied,
+/// so a redacted timestamp or id in the arguments does not change the stem.
+pub(crate) fn request_digest(
+    request: &serde_json::Value,
+    redactions: Option<&HashMap<String, RedactionType>>,
+) -> PyResult<String> {
+    use sha2::{Digest, Sha256};
+
+    let rendered = render_json(request, redactions)?;
+    let mut digest = hex(&Sha256::digest(rendered.as_bytes()));
+    digest.truncate(REQUEST_DIGEST_LEN);
+    Ok(digest)
+}
+
+/// Finds a recorded request of the mock `base` (`<test>_<suffix>`) that
+/// `matches(recorded, request)` accepts, returning its digest and the
+/// recorded request. Candidates are tried in digest order.
+fn find_matching_recording(
+    py: Python<'_>,
+    test_info: &SnapshotInfo,
+    base: &str,
+    request: &serde_json::Value,
+    matches: &PyObject,
+    encryption_key: Option<&str>,
+) -> PyResult<Option<(String, serde_json::Value)>> {
+    let suffix = format!("-request{SNAPSHOT_FILE_SUFFIX}");
+    let Ok(entries) = std::fs::read_dir(test_info.snapshot_folder()) else {
+        return Ok(None);
+    };
+    // A long stem is shortened in the middle, so the digest is read from
+    // the end of the file name and the name it gives is checked in full.
+    let mut candidates: Vec<(String, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().into_string().ok()?;
+            let stem = file_name.strip_suffix(&suffix)?;
+            let digest = stem.get(stem.len().checked_sub(REQUEST_DIGEST_LEN)?..)?;
+            let request = format!("{base}-{digest}-request");
+            (digest.bytes().all(|b| b.is_ascii_hexdigit())
+                && test_info.file_stem(module_path!(), &request) + SNAPSHOT_FILE_SUFFIX
+                    == file_name)
+                .then(|| (digest.to_string(), entry.path()))
+        })
+        .collect();
+    candidates.sort();
+    let request = pythonize::pythonize(py, request)
+        .map_err(|e| PyValueError::new_err(format!("Failed to convert request to Python: {e}")))?;
+    for (digest, path) in candidates {
+        let Ok(recorded) = load_json_snapshot(&path, encryption_key) else {
+            continue;
+        };
+        let recorded_obj = pythonize::pythonize(py, &*recorded).map_err(|e| {
+            PyValueError::new_err(format!("Failed to convert snapshot to Python: {e}"))
+        })?;
+        if matches.call1(py, (recorded_obj, &request))?.is_truthy(py)? {
+            return Ok(Some((digest, (*recorded).clone())));
+        }
+    }
+    Ok(None)
+}
+
 /// Scope `test_info` to a mock of `suffix`, write its request snapshot, and
 /// report where/whether the response should be recorded.
 ///
@@ -62,34 +152,93 @@ fn memoize_snapshot_reference(snapshot_path: &Path) {
 /// snapshot's assigned name (to pass back into `assert_json_snapshot_named`
 /// once the wrapped function has run), `response_path` is its on-disk path,
 /// and `do_record` is `true` when the wrapped function should actually be
-/// called (either `record` was requested, or no response snapshot exists yet).
+/// called (either `record` was requested, within any `PYSNAPTEST_UPDATE_FILTER`,
+/// or no response snapshot exists yet).
+///
+/// A call's request and response share one stem,
+/// `<test>_<suffix>-<digest>`, where the digest is taken from the (redacted)
+/// request. Adding or removing unrelated assertions or calls therefore never
+/// renames a recording; only repeats of an identical call are numbered
+/// (`<stem>-2`, ...), in the order they happen. The response path is peeked
+/// via `next_snapshot_path` *before* `snapshot_name` ticks that stem's
+/// duplicate counter.
+///
+/// When replaying a call that has no recording of its own,
+/// `matches(recorded_request, request)` may accept another recorded request of
+/// the same mock that differs only in parts the caller does not care about
+/// (auth headers, trace ids); that recording is replayed and its *recorded*
+/// request asserted, so the request snapshot neither fails nor churns.
 ///
-/// The response path is deliberately computed via `next_snapshot_path` (a
-/// peek) *before* `snapshot_name` (which ticks the shared duplicate counter);
-/// getting that ordering right is exactly the kind of bookkeeping this
-/// function exists to own on Python's behalf.
+/// Nodes matched by the `ignore` selectors are dropped from the request
+/// before it is digested, matched or written. With `dry_run` nothing is
+/// written: the request snapshot is left for [`preview_json_snapshot_named`]
+/// to compare instead.
 #[pyfunction]
-#[pyo3(signature = (test_info, suffix, request, record, redactions=None))]
+#[pyo3(signature = (
+    test_info,
+    suffix,
+    request,
+    record,
+    redactions=None,
+    encryption_key=None,
+    matches=None,
+    ignore=None,
+    dry_run=false,
+))]
+#[allow(clippy::too_many_arguments)]
 pub fn prepare_mock_call(
+    py: Python<'_>,
     test_info: &SnapshotInfo,
     suffix: &str,
     request: &Bound<'_, PyAny>,
     record: bool,
-    redactions: Option<HashMap<String, RedactionType>>,
+    #[pyo3(from_py_with = crate::redactions::extract_redactions)] redactions: Option<
+        HashMap<String, RedactionType>,
+    >,
+    encryption_key: Option<&str>,
+    matches: Option<PyObject>,
+    ignore: Option<Vec<String>>,
+    dry_run: bool,
 ) -> PyResult<(String, PathBuf, bool)> {
-    let finfo = test_info.with_name_suffix(suffix);
-    let response_path = finfo.next_snapshot_path(Some(module_path!().to_string()))?;
-    let name = finfo.snapshot_name();
+    let redactions = test_info.redactions(redactions);
+    let request_json: serde_json::Value = crate::serialization::depythonize(request)?;
+    let request_json = drop_ignored(request_json, &ignore.unwrap_or_default())?;
+    let mut request_json = round_floats(request_json, crate::settings::round_floats(None));
+    let digest = request_digest(&request_json, redactions.as_ref())?;
+    let mut finfo = test_info.with_name_suffix(&format!("{suffix}-{digest}"));
+    let mut response_path = finfo.snapshot_file(module_path!(), &finfo.next_snapshot_name());
 
-    let request_json: serde_json::Value = pythonize::depythonize(request)?;
-    crate::bind_json_snapshot!(
-        test_info,
-        request_json,
-        format!("{name}-request"),
-        redactions
-    )?;
+    if let Some(matches) = matches.filter(|_| !record && !scratch_storage::exists(&response_path)) {
+        let base = test_info.with_name_suffix(suffix);
+        if let Some((digest, recorded)) = find_matching_recording(
+            py,
+            test_info,
+            &base.snapshot_name,
+            &request_json,
+            &matches,
+            encryption_key,
+        )? {
+            finfo = test_info.with_name_suffix(&format!("{suffix}-{digest}"));
+            response_path = finfo.snapshot_file(module_path!(), &finfo.next_snapshot_name());
+            request_json = recorded;
+        }
+    }
+    let name = finfo.snapshot_name();
+    if dry_run {
+        return Ok((name, response_path, true));
+    }
+    py.allow_threads(|| {
+        crate::bind_json_snapshot!(
+            test_info,
+            request_json,
+            format!("{name}-request"),
+            redactions,
+            encryption_key
+        )
+    })?;
 
-    let do_record = record || !response_path.exists();
+    let record = record && crate::update_filter::allows(&name, &response_path);
+    let do_record = record || !scratch_storage::exists(&response_path);
     Ok((name, response_path, do_record))
 }
 
@@ -101,16 +250,275 @@ pub fn prepare_mock_call(
 /// snapshot under the name reserved by `prepare_mock_call`. `result` is
 /// expected to already be JSON-native (the Python side normalizes rich
 /// objects with `pysnaptest.to_jsonable` first).
+///
+/// `duration_ms`, the wall-clock time the wrapped call took, is kept in the
+/// snapshot's `info` metadata so replay can simulate it (see
+/// [`recorded_duration_ms`]), and so is `recorded_at`, the UTC timestamp of a
+/// real call (see [`recorded_at`]). insta leaves a passing snapshot's file
+/// alone, so when a re-recording reproduces the committed response its
+/// `recorded_at` is refreshed here; otherwise the recording could never be
+/// renewed. Nodes matched by the `ignore` selectors are dropped from
+/// `result` before it is written.
 #[pyfunction]
-#[pyo3(signature = (test_info, result, name, redactions=None))]
+#[pyo3(signature = (
+    test_info,
+    result,
+    name,
+    redactions=None,
+    encryption_key=None,
+    duration_ms=None,
+    recorded_at=None,
+    ignore=None,
+    result_type=None,
+))]
+#[allow(clippy::too_many_arguments)]
 pub fn assert_json_snapshot_named(
+    py: Python<'_>,
     test_info: &SnapshotInfo,
     result: &Bound<'_, PyAny>,
     name: String,
-    redactions: Option<HashMap<String, RedactionType>>,
+    #[pyo3(from_py_with = crate::redactions::extract_redactions)] redactions: Option<
+        HashMap<String, RedactionType>,
+    >,
+    encryption_key: Option<&str>,
+    duration_ms: Option<f64>,
+    recorded_at: Option<String>,
+    ignore: Option<Vec<String>>,
+    result_type: Option<&Bound<'_, PyType>>,
 ) -> PyResult<()> {
-    let res: serde_json::Value = pythonize::depythonize(result)?;
-    crate::bind_json_snapshot!(test_info, res, name, redactions)
+    let redactions = test_info.redactions(redactions);
+    let res: serde_json::Value = crate::serialization::depythonize(result)?;
+    let res = drop_ignored(res, &ignore.unwrap_or_default())?;
+    let res = round_floats(res, crate::settings::round_floats(None));
+    let path = test_info.snapshot_file(module_path!(), &name);
+    let info = CallInfo {
+        duration_ms: duration_ms.map(|ms| (ms * 1000.0).round() / 1000.0),
+        recorded_at: recorded_at.clone(),
+        result_type: result_type.map(type_name).transpose()?.flatten(),
+    };
+    py.allow_threads(|| {
+        crate::bind_json_snapshot!(test_info, res, name, redactions, encryption_key, Some(info))
+    })?;
+    if let Some(recorded_at) = recorded_at {
+        write_info_field(&path, "recorded_at", Some(&format!("\"{recorded_at}\"")))?;
+    }
+    Ok(())
+}
+
+/// Returns where the JSON snapshot `name` is stored and how asserting
+/// `result` under it would change the file: `"new"` when there is none yet,
+/// `"changed"` when the stored value differs (or cannot be read), and
+/// `None` when it matches. Nothing is written.
+///
+/// `result` goes through the same `ignore`, float rounding and `redactions`
+/// as [`assert_json_snapshot_named`], and is compared to the stored value
+/// as JSON, so an encrypted recording (decrypted with `encryption_key`) or
+/// one formatted differently is not reported for the bytes alone. Used by
+/// the Python mock layer's `mode="dry_run"`.
+#[pyfunction]
+#[pyo3(signature = (test_info, result, name, redactions=None, encryption_key=None, ignore=None))]
+pub fn preview_json_snapshot_named(
+    test_info: &SnapshotInfo,
+    result: &Bound<'_, PyAny>,
+    name: &str,
+    #[pyo3(from_py_with = crate::redactions::extract_redactions)] redactions: Option<
+        HashMap<String, RedactionType>,
+    >,
+    encryption_key: Option<&str>,
+    ignore: Option<Vec<String>>,
+) -> PyResult<(PathBuf, Option<&'static str>)> {
+    let redactions = test_info.redactions(redactions);
+    let res: serde_json::Value = crate::serialization::depythonize(result)?;
+    let res = drop_ignored(res, &ignore.unwrap_or_default())?;
+    let res = round_floats(res, crate::settings::round_floats(None));
+    let rendered = render_json(&res, redactions.as_ref())?;
+    let res: serde_json::Value = serde_json::from_str(&rendered)
+        .map_err(|e| PyValueError::new_err(format!("Unable to serialize snapshot: {e}")))?;
+    let path = test_info.snapshot_file(module_path!(), name);
+    let change = if !scratch_storage::exists(&path) {
+        Some("new")
+    } else {
+        match load_json_snapshot(&path, encryption_key) {
+            Ok(stored) if *stored == res => None,
+            _ => Some("changed"),
+        }
+    };
+    Ok((path, change))
+}
+
+/// Metadata recorded alongside a mocked call's response, after the
+/// snapshot's [`Provenance`](crate::provenance::Provenance).
+#[derive(serde::Serialize)]
+struct CallInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recorded_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result_type: Option<String>,
+}
+
+/// The `module:qualname` a recorded result's type is kept under, or `None`
+/// for a builtin type, which replay returns anyway.
+fn type_name(cls: &Bound<'_, PyType>) -> PyResult<Option<String>> {
+    let module = cls.module()?;
+    if module == "builtins" {
+        return Ok(None);
+    }
+    Ok(Some(format!("{module}:{}", cls.qualname()?)))
+}
+
+/// The `info` field holding the type a response was recorded from.
+const RESULT_TYPE_FIELD: &str = "result_type";
+
+/// Returns the raw value of `key` in a snapshot's `info` metadata.
+pub(crate) fn read_info_field(snapshot_path: &Path, key: &str) -> Option<String> {
+    read_info(snapshot_path)?
+        .into_iter()
+        .find_map(|(field, value)| (field == key).then(|| value.trim_matches('"').to_string()))
+}
+
+/// Returns the fields of a snapshot's `info` metadata with their raw YAML
+/// values, in header order; `None` when the file has no header.
+///
+/// Only the `---`-delimited header is read, line by line, so replaying a large
+/// recording does not load its body twice.
+pub(crate) fn read_info(snapshot_path: &Path) -> Option<Vec<(String, String)>> {
+    scratch_storage::with_file(snapshot_path, || read_file_info(snapshot_path))
+}
+
+/// [`read_info`] of a file on disk.
+fn read_file_info(snapshot_path: &Path) -> Option<Vec<(String, String)>> {
+    use std::io::BufRead;
+
+    let file = std::fs::File::open(snapshot_path).ok()?;
+    let mut lines = std::io::BufReader::new(file).lines();
+    if lines.next()?.ok()?.trim_end() != "---" {
+        return None;
+    }
+    let mut fields = Vec::new();
+    let mut in_info = false;
+    for line in lines {
+        let line = line.ok()?;
+        if line.trim_end() == "---" {
+            break;
+        }
+        if !line.starts_with(' ') {
+            in_info = line.trim_end() == "info:";
+        } else if in_info {
+            if let Some((field, value)) = line.trim().split_once(':') {
+                fields.push((field.to_string(), value.trim().to_string()));
+            }
+        }
+    }
+    Some(fields)
+}
+
+/// Sets `key` in the `info` metadata of the snapshot header at `path` to the
+/// YAML scalar `value`, adding it (and the `info` block) if missing, or
+/// removes it when `value` is `None`. A missing file is left alone: insta
+/// only stages a `.snap.new` for a failing assertion.
+///
+/// The file is replaced atomically, so a concurrent assertion against the
+/// same snapshot never reads it half-written, and under its [`lockfile`]
+/// lock, so concurrent rewrites do not lose each other's fields. With
+/// read-only snapshots, a change raises `SnapshotReadOnlyError` instead.
+pub(crate) fn write_info_field(path: &Path, key: &str, value: Option<&str>) -> PyResult<()> {
+    scratch_storage::with_file(path, || write_file_info_field(path, key, value))
+}
+
+/// [`write_info_field`] of a file on disk.
+pub(crate) fn write_file_info_field(path: &Path, key: &str, value: Option<&str>) -> PyResult<()> {
+    let _lock = lockfile::lock(path);
+    let Ok(text) = std::fs::read_to_string(path) else {
+        return Ok(());
+    };
+    let Some(header_len) = text
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.find("\n---\n"))
+        .map(|end| end + "---\n".len())
+    else {
+        return Ok(());
+    };
+    let entry = value.map(|value| format!("  {key}: {value}"));
+    let mut header: Vec<String> = text[4..header_len].lines().map(str::to_string).collect();
+    let info_start = header.iter().position(|line| line == "info:");
+    let info_end = info_start.map(|start| {
+        header[start + 1..]
+            .iter()
+            .position(|line| !line.starts_with(' '))
+            .map_or(header.len(), |offset| start + 1 + offset)
+    });
+    let field = format!("{key}:");
+    match (info_start, info_end, entry) {
+        (Some(start), Some(end), entry) => {
+            let existing = header[start + 1..end]
+                .iter()
+                .position(|line| line.trim_start().starts_with(&field))
+                .map(|offset| start + 1 + offset);
+            match (existing, entry) {
+                (Some(line), Some(entry)) if header[line] == entry => return Ok(()),
+                (Some(line), Some(entry)) => header[line] = entry,
+                (None, Some(entry)) => header.insert(end, entry),
+                (Some(_), None) if end - start == 2 => {
+                    header.drain(start..end);
+                }
+                (Some(line), None) => {
+                    header.remove(line);
+                }
+                (None, None) => return Ok(()),
+            }
+        }
+        (_, _, Some(entry)) => header.extend(["info:".to_string(), entry]),
+        (_, _, None) => return Ok(()),
+    }
+    let mut updated = String::from("---\n");
+    for line in header {
+        updated.push_str(&line);
+        updated.push('\n');
+    }
+    updated.push_str(&text[header_len + 1..]);
+    if crate::settings::read_only() {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        return Err(crate::panic::read_only_error(
+            &name,
+            path,
+            false,
+            &[(path.to_path_buf(), "modified")],
+        ));
+    }
+    atomic::write(path, updated)
+        .map_err(|e| PyValueError::new_err(format!("Unable to update snapshot {path:?}: {e}")))
+}
+
+/// Returns the call duration recorded in a response snapshot's `info`
+/// metadata, or `None` for recordings made without one.
+#[pyfunction]
+pub fn recorded_duration_ms(snapshot_path: PathBuf) -> Option<f64> {
+    read_info_field(&snapshot_path, "duration_ms")?.parse().ok()
+}
+
+/// Returns the node id of the pytest test that recorded a mock's request or
+/// response snapshot, or `None` for recordings made without one.
+#[pyfunction]
+pub fn recorded_by(snapshot_path: PathBuf) -> Option<String> {
+    read_info_field(&snapshot_path, "test")
+}
+
+/// Returns when a response snapshot's real call was recorded (a UTC
+/// `YYYY-MM-DDTHH:MM:SSZ` timestamp), or `None` for recordings made without
+/// one and for scripted side effects.
+#[pyfunction]
+pub fn recorded_at(snapshot_path: PathBuf) -> Option<String> {
+    read_info_field(&snapshot_path, "recorded_at")
+}
+
+/// Returns the type of the result a response snapshot was recorded from
+/// (`module:qualname`), or `None` for a builtin type and for recordings made
+/// without one.
+#[pyfunction]
+pub fn recorded_type(snapshot_path: PathBuf) -> Option<String> {
+    read_info_field(&snapshot_path, RESULT_TYPE_FIELD)
 }
 
 /// Read a previously recorded JSON snapshot file and return its parsed value.
@@ -124,29 +532,191 @@ pub fn assert_json_snapshot_named(
 /// obsolete-snapshot detection accurate we record the reference ourselves,
 /// exactly as insta's own `memoize_snapshot_file` does: append the path to the
 /// file named by `INSTA_SNAPSHOT_REFERENCES_FILE` (set by `pysnaptest unused`).
+///
+/// An encrypted recording is decrypted with `encryption_key`, falling back to
+/// `PYSNAPTEST_KEY`. A binary recording is returned as `bytes`, or passed
+/// through the decoder registered for its extension.
+#[pyfunction]
+#[pyo3(signature = (snapshot_path, encryption_key=None))]
+pub fn read_json_snapshot(
+    py: Python<'_>,
+    snapshot_path: PathBuf,
+    encryption_key: Option<&str>,
+) -> PyResult<PyObject> {
+    if let Some((extension, data)) = read_binary_response(py, &snapshot_path)? {
+        memoize_snapshot_reference(&snapshot_path);
+        let decoder = REPLAY_DECODERS
+            .lock()
+            .unwrap_or_else(|x| x.into_inner())
+            .get(&extension)
+            .map(|decoder| decoder.clone_ref(py));
+        return match decoder {
+            Some(decoder) => decoder.call1(py, (data,)),
+            None => Ok(data.into_any().unbind()),
+        };
+    }
+    let value = load_json_snapshot(&snapshot_path, encryption_key)?;
+    memoize_snapshot_reference(&snapshot_path);
+    let obj = pythonize::pythonize(py, &*value)
+        .map_err(|e| PyValueError::new_err(format!("Failed to convert snapshot to Python: {e}")))?;
+    Ok(obj.into())
+}
+
+/// Read a previously recorded JSON snapshot file and return its body as
+/// text, decrypted with `encryption_key` (falling back to `PYSNAPTEST_KEY`),
+/// or the bytes of a binary recording.
+///
+/// Used on replay by a mock given a `decode` callable, which builds the
+/// returned value from the recorded JSON itself. The reference is recorded
+/// as by [`read_json_snapshot`].
+#[pyfunction]
+#[pyo3(signature = (snapshot_path, encryption_key=None))]
+pub fn read_json_snapshot_text(
+    py: Python<'_>,
+    snapshot_path: PathBuf,
+    encryption_key: Option<&str>,
+) -> PyResult<PyObject> {
+    if let Some((_, data)) = read_binary_response(py, &snapshot_path)? {
+        memoize_snapshot_reference(&snapshot_path);
+        return Ok(data.into_any().unbind());
+    }
+    let body = scratch_storage::with_file(&snapshot_path, || {
+        crate::checksum::verify(&snapshot_path)?;
+        let snapshot = Snapshot::from_file(&snapshot_path).map_err(|e| {
+            PyValueError::new_err(format!(
+                "Unable to load snapshot from {snapshot_path:?}: {e}"
+            ))
+        })?;
+        match snapshot.contents() {
+            SnapshotContents::Text(content) => {
+                decrypt_if_encrypted(content.to_string(), encryption_key)
+            }
+            SnapshotContents::Binary(_) => Err(PyValueError::new_err(format!(
+                "Snapshot at {snapshot_path:?} is binary, not a JSON recording"
+            ))),
+        }
+    })?;
+    memoize_snapshot_reference(&snapshot_path);
+    Ok(body.into_pyobject(py)?.into_any().unbind())
+}
+
+/// Decoders for binary recorded responses, by the extension of their data.
+static REPLAY_DECODERS: Lazy<Mutex<HashMap<String, PyObject>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Registers `decoder` to turn the bytes of a binary recorded response whose
+/// data has the extension `extension` (such as `"png"` or `"parquet"`) into
+/// the value a mock returns on replay. `None` unregisters it, so those
+/// responses replay as `bytes` again.
 #[pyfunction]
-pub fn read_json_snapshot(snapshot_path: PathBuf) -> PyResult<PyObject> {
-    let snapshot = Snapshot::from_file(&snapshot_path).map_err(|e| {
+pub fn register_replay_decoder(extension: &str, decoder: Option<PyObject>) -> PyResult<()> {
+    let extension = extension.trim_start_matches('.');
+    if extension.is_empty() || extension.contains('.') {
+        return Err(PyValueError::new_err(format!(
+            "Invalid extension {extension:?}: expected a single extension such as \"png\""
+        )));
+    }
+    let mut decoders = REPLAY_DECODERS.lock().unwrap_or_else(|x| x.into_inner());
+    match decoder {
+        Some(decoder) => decoders.insert(extension.to_string(), decoder),
+        None => decoders.remove(extension),
+    };
+    Ok(())
+}
+
+/// Loads the data of a binary recorded response as `bytes`, with the
+/// extension it was stored under (without a compression or pointer suffix);
+/// `None` for a text snapshot.
+fn read_binary_response<'py>(
+    py: Python<'py>,
+    snapshot_path: &Path,
+) -> PyResult<Option<(String, Bound<'py, PyBytes>)>> {
+    scratch_storage::with_file(snapshot_path, || {
+        let Some(sidecar) = binary_sidecar(snapshot_path) else {
+            return Ok(None);
+        };
+        // The sidecar is `<snapshot>.<extension>[.zst][.ptr]`.
+        let stored = sidecar.to_string_lossy();
+        let stored = &stored[snapshot_path.to_string_lossy().len() + 1..];
+        let extension = stored.split('.').next().unwrap_or(stored).to_string();
+        let contents = PySnapshot::from_file(snapshot_path.to_path_buf())?.contents(py, None)?;
+        // A large snapshot comes back memory-mapped; replay hands out bytes.
+        let data = py
+            .get_type::<PyBytes>()
+            .call1((contents,))?
+            .downcast_into::<PyBytes>()?;
+        Ok(Some((extension, data)))
+    })
+}
+
+/// Loads and decrypts the JSON body of a recorded snapshot, parsed once
+/// until the file changes; see [`replay_cache`].
+fn load_json_snapshot(
+    snapshot_path: &Path,
+    encryption_key: Option<&str>,
+) -> PyResult<Arc<serde_json::Value>> {
+    scratch_storage::with_file(snapshot_path, || {
+        replay_cache::get_or_parse(snapshot_path, || {
+            parse_json_snapshot(snapshot_path, encryption_key)
+        })
+    })
+}
+
+/// Parses the JSON body of a recorded snapshot, returning whether it can be
+/// cached: a decrypted body cannot. A body that does not match its
+/// [`checksum`](crate::checksum) raises `SnapshotCorruptedError`.
+fn parse_json_snapshot(
+    snapshot_path: &Path,
+    encryption_key: Option<&str>,
+) -> PyResult<(serde_json::Value, bool)> {
+    crate::checksum::verify(snapshot_path)?;
+    if let Some(value) = read_mapped_json(snapshot_path)? {
+        return Ok((value, true));
+    }
+    let snapshot = Snapshot::from_file(snapshot_path).map_err(|e| {
         PyValueError::new_err(format!(
             "Unable to load snapshot from {snapshot_path:?}: {e}"
         ))
     })?;
-    memoize_snapshot_reference(&snapshot_path);
     match snapshot.contents() {
-        SnapshotContents::Text(content) => Python::with_gil(|py| {
-            let value: serde_json::Value =
-                serde_json::from_str(&content.to_string()).map_err(|e| {
+        SnapshotContents::Text(content) => {
+            let body = content.to_string();
+            let cacheable = !is_encrypted(&body);
+            let body = decrypt_if_encrypted(body, encryption_key)?;
+            serde_json::from_str(&body)
+                .map(|value| (value, cacheable))
+                .map_err(|e| {
                     PyValueError::new_err(format!(
                         "Invalid JSON in snapshot {snapshot_path:?}: {e}"
                     ))
-                })?;
-            let obj = pythonize::pythonize(py, &value).map_err(|e| {
-                PyValueError::new_err(format!("Failed to convert snapshot to Python: {e}"))
-            })?;
-            Ok(obj.into())
-        }),
+                })
+        }
+        // Binary recordings are read by `read_binary_response` instead.
         SnapshotContents::Binary(_) => Err(PyValueError::new_err(format!(
-            "Snapshot at {snapshot_path:?} is binary, which is not supported for mock replay"
+            "Snapshot at {snapshot_path:?} is binary, not a JSON recording"
         ))),
     }
 }
+
+/// Parses a large recorded response straight out of a memory map instead of
+/// reading the whole file into a `String` first. Returns `None` (use insta's
+/// parser) for small, encrypted or non-standard snapshot files.
+fn read_mapped_json(snapshot_path: &Path) -> PyResult<Option<serde_json::Value>> {
+    if !should_map(snapshot_path) {
+        return Ok(None);
+    }
+    let Ok(mapped) = MappedFile::open(snapshot_path) else {
+        return Ok(None);
+    };
+    let Some((header, body)) = split_snapshot(&mapped) else {
+        return Ok(None);
+    };
+    if header.contains("snapshot_kind: binary")
+        || std::str::from_utf8(body).map_or(true, is_encrypted)
+    {
+        return Ok(None);
+    }
+    serde_json::from_slice(body).map(Some).map_err(|e| {
+        PyValueError::new_err(format!("Invalid JSON in snapshot {snapshot_path:?}: {e}"))
+    })
+}
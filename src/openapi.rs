@@ -0,0 +1,294 @@
+//! Normalization and semantic diffing for `assert_openapi_snapshot`.
+//!
+//! An OpenAPI document is snapshotted as JSON after two normalizations that
+//! keep the contract the same:
+//!
+//! * Vendor extensions (`x-*` keys) are dropped, unless asked to be kept.
+//!   Keys that are user-chosen names (properties, headers, paths, ...) are
+//!   never mistaken for extensions.
+//! * Lists whose order carries no meaning are sorted: `required`,
+//!   `parameters` (by location and name, or `$ref`), and lists made up only
+//!   of `$ref`s, such as `oneOf` alternatives.
+//!
+//! On a mismatch, the committed and the new document are compared as a
+//! contract rather than as text: which operations and schemas were added or
+//! removed, and what changed inside the rest.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use insta::internals::SnapshotContents;
+use insta::Snapshot;
+use serde_json::{Map, Value};
+
+/// Keys whose entries are named by the API author rather than by the
+/// OpenAPI specification, so an `x-` prefix there is not an extension.
+const NAMED_MAPS: [&str; 17] = [
+    "paths",
+    "properties",
+    "patternProperties",
+    "headers",
+    "schemas",
+    "definitions",
+    "$defs",
+    "responses",
+    "parameters",
+    "requestBodies",
+    "examples",
+    "securitySchemes",
+    "callbacks",
+    "links",
+    "content",
+    "encoding",
+    "variables",
+];
+
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// How deep changes inside an operation or the rest of the document are
+/// spelled out before being reported for their parent.
+const DETAIL_DEPTH: usize = 2;
+
+/// Whether `document` looks like an OpenAPI (or Swagger) document.
+pub(crate) fn is_openapi(document: &Value) -> bool {
+    document.get("openapi").is_some() || document.get("swagger").is_some()
+}
+
+/// Reads the committed document at `path`; `None` on a first run.
+pub(crate) fn read_committed(path: &Path) -> Option<Value> {
+    let snapshot = Snapshot::from_file(path).ok()?;
+    match snapshot.contents() {
+        SnapshotContents::Text(text) => serde_json::from_str(&text.to_string()).ok(),
+        SnapshotContents::Binary(_) => None,
+    }
+}
+
+pub(crate) fn normalize(document: Value, keep_extensions: bool) -> Value {
+    normalize_value(document, keep_extensions, false)
+}
+
+fn normalize_value(value: Value, keep_extensions: bool, keys_are_names: bool) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(key, _)| keep_extensions || keys_are_names || !key.starts_with("x-"))
+                .map(|(key, value)| {
+                    let names = !keys_are_names && NAMED_MAPS.contains(&key.as_str());
+                    let value = normalize_value(value, keep_extensions, names);
+                    let value = if keys_are_names {
+                        value
+                    } else {
+                        sort_list(&key, value)
+                    };
+                    (key, value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| normalize_value(item, keep_extensions, false))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+fn reference(value: &Value) -> Option<&str> {
+    match value.as_object() {
+        Some(map) if map.len() == 1 => map.get("$ref")?.as_str(),
+        _ => None,
+    }
+}
+
+/// Sorts the list `value` under `key` when its order carries no meaning.
+fn sort_list(key: &str, value: Value) -> Value {
+    let Value::Array(mut items) = value else {
+        return value;
+    };
+    let text = |value: &Value| value.as_str().unwrap_or_default().to_string();
+    if key == "required" && items.iter().all(Value::is_string) {
+        items.sort_by_key(text);
+    } else if key == "parameters" {
+        items.sort_by_key(|parameter| match reference(parameter) {
+            Some(reference) => (String::new(), reference.to_string()),
+            None => (
+                parameter.get("in").map(text).unwrap_or_default(),
+                parameter.get("name").map(text).unwrap_or_default(),
+            ),
+        });
+    } else if !items.is_empty() && items.iter().all(|item| reference(item).is_some()) {
+        items.sort_by_key(|item| reference(item).unwrap_or_default().to_string());
+    }
+    Value::Array(items)
+}
+
+/// The operations of a document, as `"METHOD /path"` and the operation.
+fn operations(document: &Value) -> Vec<(String, &Value)> {
+    let Some(paths) = document.get("paths").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    paths
+        .iter()
+        .flat_map(|(path, item)| {
+            METHODS.iter().filter_map(move |method| {
+                let operation = item.get(*method)?;
+                Some((format!("{} {path}", method.to_ascii_uppercase()), operation))
+            })
+        })
+        .collect()
+}
+
+/// The schemas of an OpenAPI 3 (`components.schemas`) or Swagger 2
+/// (`definitions`) document.
+fn schemas(document: &Value) -> Option<&Map<String, Value>> {
+    document
+        .pointer("/components/schemas")
+        .or_else(|| document.get("definitions"))
+        .and_then(Value::as_object)
+}
+
+fn short(value: &Value) -> Option<String> {
+    match value {
+        Value::Object(_) | Value::Array(_) => None,
+        scalar => Some(scalar.to_string()),
+    }
+}
+
+/// Describes where `old` and `new` differ, as dotted paths below `prefix`,
+/// spelling out nested objects up to `depth` levels.
+fn changes(old: &Value, new: &Value, prefix: &str, depth: usize, into: &mut Vec<String>) {
+    if old == new {
+        return;
+    }
+    let label = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{prefix}.{key}")
+        }
+    };
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) if depth > 0 => {
+            let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+            for key in keys {
+                match (old.get(key), new.get(key)) {
+                    (Some(_), None) => into.push(format!("{} removed", label(key))),
+                    (None, Some(_)) => into.push(format!("{} added", label(key))),
+                    (Some(old), Some(new)) => changes(old, new, &label(key), depth - 1, into),
+                    (None, None) => {}
+                }
+            }
+        }
+        _ => match (short(old), short(new)) {
+            (Some(old), Some(new)) => into.push(format!("{prefix}: {old} -> {new}")),
+            _ => into.push(format!("{prefix} changed")),
+        },
+    }
+}
+
+fn schema_changes(old: &Value, new: &Value) -> Vec<String> {
+    let properties = |schema: &Value| {
+        schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default()
+    };
+    let (old_properties, new_properties) = (properties(old), properties(new));
+    let mut described = Vec::new();
+    let names: BTreeSet<&String> = old_properties.keys().chain(new_properties.keys()).collect();
+    for name in names {
+        match (old_properties.get(name), new_properties.get(name)) {
+            (Some(_), None) => described.push(format!("property {name} removed")),
+            (None, Some(_)) => described.push(format!("property {name} added")),
+            (Some(old), Some(new)) if old != new => {
+                described.push(format!("property {name} changed"))
+            }
+            _ => {}
+        }
+    }
+    let without_properties = |schema: &Value| {
+        let mut schema = schema.clone();
+        if let Some(map) = schema.as_object_mut() {
+            map.remove("properties");
+        }
+        schema
+    };
+    changes(
+        &without_properties(old),
+        &without_properties(new),
+        "",
+        1,
+        &mut described,
+    );
+    described
+}
+
+/// Lists how the contract `new` differs from `old`: one line per added (`+`),
+/// removed (`-`) or changed (`~`) operation, schema or other part.
+pub(crate) fn semantic_diff(old: &Value, new: &Value) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    let (old_operations, new_operations) = (operations(old), operations(new));
+    let find = |operations: &[(String, &Value)], wanted: &str| {
+        operations
+            .iter()
+            .find(|(name, _)| name == wanted)
+            .map(|(_, operation)| (*operation).clone())
+    };
+    let names: BTreeSet<&String> = old_operations
+        .iter()
+        .chain(new_operations.iter())
+        .map(|(name, _)| name)
+        .collect();
+    for name in names {
+        match (find(&old_operations, name), find(&new_operations, name)) {
+            (Some(_), None) => lines.push(format!("- {name}")),
+            (None, Some(_)) => lines.push(format!("+ {name}")),
+            (Some(old), Some(new)) if old != new => {
+                let mut described = Vec::new();
+                changes(&old, &new, "", DETAIL_DEPTH, &mut described);
+                lines.push(format!("~ {name}: {}", described.join(", ")));
+            }
+            _ => {}
+        }
+    }
+
+    let empty = Map::new();
+    let (old_schemas, new_schemas) = (
+        schemas(old).unwrap_or(&empty),
+        schemas(new).unwrap_or(&empty),
+    );
+    let names: BTreeSet<&String> = old_schemas.keys().chain(new_schemas.keys()).collect();
+    for name in names {
+        match (old_schemas.get(name), new_schemas.get(name)) {
+            (Some(_), None) => lines.push(format!("- schema {name}")),
+            (None, Some(_)) => lines.push(format!("+ schema {name}")),
+            (Some(old), Some(new)) if old != new => lines.push(format!(
+                "~ schema {name}: {}",
+                schema_changes(old, new).join(", ")
+            )),
+            _ => {}
+        }
+    }
+
+    // Everything else: info, servers, security, other components, ...
+    let rest = |document: &Value| {
+        let mut document = document.clone();
+        if let Some(map) = document.as_object_mut() {
+            map.remove("paths");
+            map.remove("definitions");
+        }
+        if let Some(Value::Object(components)) = document.get_mut("components") {
+            components.remove("schemas");
+        }
+        document
+    };
+    let mut described = Vec::new();
+    changes(&rest(old), &rest(new), "", DETAIL_DEPTH, &mut described);
+    lines.extend(described.into_iter().map(|change| format!("~ {change}")));
+    lines
+}
@@ -12,14 +12,58 @@
 //! user, who just wants the diff and a normal assertion failure. We install a
 //! panic hook that stays quiet while one of our assertions is running and raise
 //! a plain `AssertionError` instead.
+//!
+//! A mismatch raises [`SnapshotMismatchError`], an `AssertionError` whose
+//! `snapshot_failure` attribute describes a text snapshot's failure for CI
+//! tooling: the snapshot's name, the committed (`expected_path`) and pending
+//! (`actual_path`) files, the uncolored diff and the side-by-side HTML diff's
+//! path, if one was written. It is `None` for other snapshots.
+//!
+//! With fail-fast off (`set_fail_fast(False)`), the error is not raised but
+//! collected, for [`take_soft_failures`] to hand to whatever reports them
+//! together once the test ends.
+//!
+//! With read-only snapshots (`set_read_only()`), an assertion that would
+//! create a snapshot or rewrite a committed one raises
+//! [`SnapshotReadOnlyError`] right away, fail-fast or not, and whatever insta
+//! wrote is undone; so is the pending `.snap.new` of a mismatch.
 
 use std::any::Any;
 use std::cell::Cell;
 use std::panic::{self, AssertUnwindSafe};
-use std::sync::Once;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, Once};
 
 use pyo3::exceptions::PyAssertionError;
-use pyo3::PyResult;
+use pyo3::prelude::*;
+use serde::Serialize;
+
+use crate::checksum;
+use crate::diff;
+use crate::insta_env::{self, Behavior, InstaConfig};
+use crate::lockfile;
+use crate::manifest;
+use crate::pending;
+use crate::scratch_storage;
+use crate::settings;
+use crate::update_filter::{self, Preserved};
+
+pyo3::create_exception!(
+    _pysnaptest,
+    SnapshotMismatchError,
+    PyAssertionError,
+    "A snapshot did not match; `snapshot_failure` describes how, or is None."
+);
+
+pyo3::create_exception!(
+    _pysnaptest,
+    SnapshotReadOnlyError,
+    PyAssertionError,
+    "A snapshot file would have been created or rewritten with read-only snapshots on."
+);
+
+/// The mismatches collected with fail-fast off, in assertion order.
+static SOFT_FAILURES: Mutex<Vec<PyErr>> = Mutex::new(Vec::new());
 
 thread_local! {
     /// Set while one of our snapshot assertions is running, so the panic hook
@@ -72,8 +116,18 @@ fn panic_message(payload: &(dyn Any + Send)) -> Option<String> {
 enum AssertionOutcome {
     /// The snapshot matched (or was updated).
     Matched,
-    /// insta's expected mismatch panic fired; insta printed the diff to stdout.
-    Mismatch,
+    /// insta's expected mismatch panic fired; insta printed the diff to
+    /// stdout. `kept` when the snapshot is outside the update filter, and
+    /// whatever insta wrote was undone.
+    Mismatch { kept: bool },
+    /// The assertion would have created (`new`) or rewritten the snapshot
+    /// with read-only snapshots on. `written` are the files it wrote, undone
+    /// since, or was stopped before rewriting, each `"created"` or
+    /// `"modified"`: none for a new snapshot.
+    ReadOnly {
+        new: bool,
+        written: Vec<(PathBuf, &'static str)>,
+    },
     /// An unexpected panic (a real bug); carries its surfaced message.
     Error(String),
 }
@@ -82,21 +136,148 @@ enum AssertionOutcome {
 ///
 /// insta signals a snapshot mismatch by panicking with a message starting
 /// `snapshot assertion for '...'`; that is the one panic we treat as an expected
-/// outcome. Any other panic is an unexpected bug and its message is preserved.
-fn run_assertion<F: FnOnce()>(snapshot_name: &str, assertion: F) -> AssertionOutcome {
+/// outcome, and the pending `.snap.new` it left, if any, is recorded in
+/// [`pending`]. Any other panic is an unexpected bug and its message is
+/// preserved. The assertion holds the [`lockfile`] lock of the `snapshot`
+/// file it writes.
+///
+/// Outside the [`update_filter`], the snapshot's files are put back as they
+/// were, and a new or changed snapshot is a mismatch unless insta
+/// force-passes it. With read-only snapshots, a snapshot not stored is
+/// [`AssertionOutcome::ReadOnly`] without running the assertion, and so is a
+/// mismatch insta would rewrite in place, stopped before insta writes (see
+/// [`diff::refuse_mismatches`]); whatever else insta writes is put back. A
+/// snapshot the assertion created or rewrote is given its [`checksum`] and
+/// listed in its folder's [`manifest`], unless kept in a scratch storage.
+fn run_assertion<F: FnOnce()>(
+    snapshot_name: &str,
+    snapshot: &Path,
+    config: &InstaConfig,
+    assertion: F,
+) -> AssertionOutcome {
+    diff::clear_mismatch();
+    let lock = lockfile::lock(snapshot);
+    let read_only = settings::read_only();
+    let outside_filter = !update_filter::allows(snapshot_name, snapshot);
+    let preserved = match read_only || outside_filter {
+        true => Preserved::capture(snapshot).map(Some),
+        false => Ok(None),
+    };
+    let preserved = match preserved {
+        Ok(preserved) => preserved,
+        Err(e) => {
+            return AssertionOutcome::Error(format!(
+                "Unable to read snapshot {}: {e}",
+                snapshot.display()
+            ))
+        }
+    };
+    if read_only && !snapshot.is_file() {
+        // Nothing to compare with: the assertion could only create it.
+        let refused = AssertionOutcome::ReadOnly {
+            new: true,
+            written: Vec::new(),
+        };
+        return absorbed(snapshot, refused);
+    }
+    // insta would rewrite a mismatching snapshot in place: stop it first.
+    let refuse = read_only && config.behavior(true) == Behavior::InPlace;
+    let written_before = checksum::fingerprint(snapshot);
     let guard = AssertionGuard::enter();
+    diff::refuse_mismatches(refuse);
     let outcome = panic::catch_unwind(AssertUnwindSafe(assertion));
+    diff::refuse_mismatches(false);
     drop(guard);
+    if outcome
+        .as_ref()
+        .is_err_and(|payload| payload.is::<diff::RefusedMismatch>())
+    {
+        diff::clear_mismatch();
+        let refused = AssertionOutcome::ReadOnly {
+            new: false,
+            written: vec![(snapshot.to_path_buf(), "modified")],
+        };
+        return absorbed(snapshot, refused);
+    }
+    let (stored, written) = match preserved {
+        Some(preserved) => {
+            let stored = preserved.was_stored();
+            match preserved.restore() {
+                Ok(written) => (stored, written),
+                Err(e) => {
+                    return AssertionOutcome::Error(format!(
+                        "Unable to restore snapshot {}: {e}",
+                        snapshot.display()
+                    ))
+                }
+            }
+        }
+        None => (true, Vec::new()),
+    };
+    let kept = outside_filter.then_some(stored);
+    // Files put back as they were are not the assertion's writes.
+    let written_after = checksum::fingerprint(snapshot).filter(|_| !(read_only || outside_filter));
+    if outcome.is_ok() && written_after.is_some() && written_after != written_before {
+        if let Err(e) = checksum::stamp(snapshot) {
+            return AssertionOutcome::Error(format!(
+                "Unable to record the checksum of snapshot {}: {e}",
+                snapshot.display()
+            ));
+        }
+        let test = crate::provenance::current_test_node_id();
+        let recorded = match scratch_storage::in_scratch(snapshot) {
+            true => Ok(()),
+            false => manifest::record(snapshot, written_before.is_none(), test),
+        };
+        if let Err(e) = recorded {
+            return AssertionOutcome::Error(format!(
+                "Unable to record snapshot {} in its manifest: {e}",
+                snapshot.display()
+            ));
+        }
+    }
+    if let AssertionOutcome::Error(e) = absorbed(snapshot, AssertionOutcome::Matched) {
+        return AssertionOutcome::Error(e);
+    }
+    drop(lock);
 
+    if read_only && outcome_is_snapshot(&outcome) {
+        let rewritten = written.iter().any(|(path, _)| !is_pending(path));
+        if !stored || rewritten {
+            return AssertionOutcome::ReadOnly {
+                new: !stored,
+                written,
+            };
+        }
+    }
     match outcome {
-        Ok(()) => AssertionOutcome::Matched,
+        Ok(()) if kept.is_some() => {
+            let changed = diff::take_mismatched_file().is_some() || kept == Some(false);
+            match changed && !config.force_pass {
+                true => AssertionOutcome::Mismatch { kept: true },
+                false => AssertionOutcome::Matched,
+            }
+        }
+        Ok(()) => {
+            // A mismatch passes under `INSTA_FORCE_PASS=1`, pending all the
+            // same; insta removes the `.snap.new` of one that matched.
+            let snapshot = diff::take_mismatched_file().unwrap_or_else(|| snapshot.into());
+            let diff = diff::take_mismatch().map(|mismatch| plain_diff(&mismatch));
+            pending::record_force_passed(&snapshot, diff);
+            AssertionOutcome::Matched
+        }
         Err(payload) => {
             let raw = panic_message(payload.as_ref());
             if raw
                 .as_deref()
                 .is_some_and(|m| m.starts_with("snapshot assertion for"))
             {
-                AssertionOutcome::Mismatch
+                if let Some(snapshot) = diff::take_mismatched_file() {
+                    pending::record(&snapshot);
+                }
+                AssertionOutcome::Mismatch {
+                    kept: kept.is_some(),
+                }
             } else {
                 AssertionOutcome::Error(
                     raw.unwrap_or_else(|| format!("snapshot '{snapshot_name}' assertion failed")),
@@ -106,20 +287,223 @@ fn run_assertion<F: FnOnce()>(snapshot_name: &str, assertion: F) -> AssertionOut
     }
 }
 
+/// `outcome`, once what the assertion of `snapshot` left in a scratch folder
+/// is back in its storage.
+fn absorbed(snapshot: &Path, outcome: AssertionOutcome) -> AssertionOutcome {
+    match scratch_storage::absorb_after_assertion(snapshot) {
+        Ok(()) => outcome,
+        Err(e) => AssertionOutcome::Error(format!(
+            "Unable to keep snapshot {} in its storage: {e}",
+            snapshot.display()
+        )),
+    }
+}
+
+/// Whether insta ran the assertion to a match, an update or a mismatch,
+/// rather than failing unexpectedly.
+fn outcome_is_snapshot(outcome: &Result<(), Box<dyn Any + Send>>) -> bool {
+    match outcome {
+        Ok(()) => true,
+        Err(payload) => {
+            panic_message(payload.as_ref()).is_some_and(|m| m.starts_with("snapshot assertion for"))
+        }
+    }
+}
+
+/// Whether `path` is a pending `.snap.new` file (or a binary file of one).
+fn is_pending(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().contains(".snap.new"))
+}
+
+/// The [`SnapshotReadOnlyError`] of the snapshot `snapshot_name`, `new` or
+/// not, naming the running test and the files the assertion wrote.
+pub(crate) fn read_only_error(
+    snapshot_name: &str,
+    snapshot: &Path,
+    new: bool,
+    written: &[(PathBuf, &'static str)],
+) -> PyErr {
+    let test = crate::provenance::current_test_node_id()
+        .unwrap_or_else(|| "code outside pytest".to_string());
+    let mut message = format!(
+        "{test} would {} snapshot '{snapshot_name}' ({}), but snapshots are read-only.",
+        match new {
+            true => "create",
+            false => "rewrite",
+        },
+        snapshot.display()
+    );
+    for (path, change) in written {
+        message.push_str(&format!("\n  {change}: {}", path.display()));
+    }
+    message.push_str(
+        "\n\nThe files were left as they were. Run the test locally, without \
+         read-only snapshots, and commit the result.",
+    );
+    SnapshotReadOnlyError::new_err(message)
+}
+
+/// The `snapshot_failure` of a [`SnapshotMismatchError`]. Field order is the
+/// dict's key order.
+#[derive(Serialize)]
+struct SnapshotFailure {
+    snapshot: String,
+    expected_path: PathBuf,
+    actual_path: PathBuf,
+    diff: String,
+    html_path: Option<PathBuf>,
+}
+
+/// The diff of a mismatch as tools read it: whole and uncolored, otherwise
+/// laid out by [`settings::diff_options`].
+fn plain_diff(mismatch: &diff::Mismatch) -> String {
+    let plain = diff::DiffOptions {
+        max_lines: None,
+        color: Some(false),
+        ..settings::diff_options()
+    };
+    diff::render(&mismatch.old, &mismatch.new, &plain)
+}
+
+/// The message and the failure of a text snapshot mismatch: the diff,
+/// rendered as [`settings::diff_options`] lay it out, and the path of the
+/// side-by-side HTML diff when they ask for one.
+fn describe_mismatch(snapshot_name: &str, mismatch: diff::Mismatch) -> (String, SnapshotFailure) {
+    let options = settings::diff_options();
+    let mut message = format!(
+        "snapshot '{snapshot_name}' did not match the stored value. \
+         Update the snapshot if this change is intentional.\n\n{}",
+        diff::render(&mismatch.old, &mismatch.new, &options)
+    );
+    let mut html_path = None;
+    if options.html {
+        let path = diff::html_diff_path(&mismatch.snapshot);
+        let html = diff::render_html(snapshot_name, &mismatch, &options);
+        match std::fs::write(&path, html) {
+            Ok(()) => {
+                message.push_str(&format!("\n\nSide-by-side diff: {}", path.display()));
+                html_path = Some(path);
+            }
+            Err(e) => message.push_str(&format!(
+                "\n\nUnable to write the side-by-side diff to {}: {e}",
+                path.display()
+            )),
+        }
+    }
+    let mut actual_path = mismatch.snapshot.clone().into_os_string();
+    actual_path.push(".new");
+    let failure = SnapshotFailure {
+        snapshot: snapshot_name.to_string(),
+        diff: plain_diff(&mismatch),
+        expected_path: mismatch.snapshot,
+        actual_path: actual_path.into(),
+        html_path,
+    };
+    (message, failure)
+}
+
+/// The [`SnapshotMismatchError`] of the snapshot `snapshot_name`, described
+/// by `mismatch` when it is a text snapshot, and otherwise pointing to the
+/// diff insta printed, unless `INSTA_OUTPUT` kept it from printing one.
+fn mismatch_error(
+    snapshot_name: &str,
+    mismatch: Option<diff::Mismatch>,
+    config: &InstaConfig,
+    kept: bool,
+) -> PyErr {
+    let (mut message, failure) = match mismatch {
+        Some(mismatch) => {
+            let (message, failure) = describe_mismatch(snapshot_name, mismatch);
+            (message, Some(failure))
+        }
+        None => {
+            let see_diff = match config.output {
+                insta_env::Output::Diff => " (see the diff above)",
+                _ => "",
+            };
+            (
+                format!(
+                    "snapshot '{snapshot_name}' did not match the stored value{see_diff}. \
+                     Update the snapshot if this change is intentional."
+                ),
+                None,
+            )
+        }
+    };
+    if kept {
+        message.push_str(&format!(
+            "\n\nLeft as it was: the snapshot is outside {}.",
+            update_filter::UPDATE_FILTER_ENV
+        ));
+    }
+    // Assertions run with the GIL released.
+    Python::with_gil(|py| {
+        let err = SnapshotMismatchError::new_err(message);
+        let attached = pythonize::pythonize(py, &failure)
+            .map_err(PyErr::from)
+            .and_then(|failure| err.value(py).setattr("snapshot_failure", failure));
+        match attached {
+            Ok(()) => err,
+            Err(e) => e,
+        }
+    })
+}
+
 /// Runs an insta assertion, turning a snapshot mismatch (which insta signals by
-/// panicking) into a Python `AssertionError`. insta prints the diff to stdout
-/// before it panics, so the raised error only needs to say what to do next.
-pub fn run_snapshot_assertion<F: FnOnce()>(snapshot_name: &str, assertion: F) -> PyResult<()> {
-    match run_assertion(snapshot_name, assertion) {
+/// panicking) into a [`SnapshotMismatchError`]. For a text snapshot the error
+/// carries the diff (see [`describe_mismatch`]); for others it points to the
+/// diff insta printed to stdout before it panicked. With fail-fast off, the
+/// error is collected instead and the assertion passes. An invalid insta
+/// environment variable raises `ValueError` before anything runs.
+pub fn run_snapshot_assertion<F: FnOnce()>(
+    snapshot_name: &str,
+    snapshot: &Path,
+    assertion: F,
+) -> PyResult<()> {
+    let config = insta_env::in_effect()?;
+    match run_assertion(snapshot_name, snapshot, &config, assertion) {
         AssertionOutcome::Matched => Ok(()),
-        AssertionOutcome::Mismatch => Err(PyAssertionError::new_err(format!(
-            "snapshot '{snapshot_name}' did not match the stored value (see the diff above). \
-             Update the snapshot if this change is intentional."
-        ))),
+        AssertionOutcome::Mismatch { kept } => raise_or_collect(mismatch_error(
+            snapshot_name,
+            diff::take_mismatch(),
+            &config,
+            kept,
+        )),
+        AssertionOutcome::ReadOnly { new, written } => {
+            Err(read_only_error(snapshot_name, snapshot, new, &written))
+        }
         AssertionOutcome::Error(message) => Err(PyAssertionError::new_err(message)),
     }
 }
 
+/// Raises `err`, or with fail-fast off collects it and passes.
+fn raise_or_collect(err: PyErr) -> PyResult<()> {
+    if !settings::soft_assertions() {
+        return Err(err);
+    }
+    SOFT_FAILURES
+        .lock()
+        .unwrap_or_else(|x| x.into_inner())
+        .push(err);
+    Ok(())
+}
+
+/// Reports the mismatch a caller of [`run_snapshot_assertion_matched`]
+/// described in `message`: a [`SnapshotMismatchError`] without a
+/// `snapshot_failure`, raised, or collected with fail-fast off.
+pub(crate) fn report_mismatch(message: String) -> PyResult<()> {
+    // Callers may have released the GIL.
+    let err = Python::with_gil(|py| {
+        let err = SnapshotMismatchError::new_err(message);
+        match err.value(py).setattr("snapshot_failure", py.None()) {
+            Ok(()) => err,
+            Err(e) => e,
+        }
+    });
+    raise_or_collect(err)
+}
+
 /// Runs an insta assertion but reports the outcome as a boolean instead of
 /// raising on a snapshot mismatch.
 ///
@@ -127,14 +511,31 @@ pub fn run_snapshot_assertion<F: FnOnce()>(snapshot_name: &str, assertion: F) ->
 /// for insta's expected mismatch panic. Any other (unexpected) panic is still
 /// surfaced as a Python `AssertionError`. This lets a caller enrich the failure
 /// (e.g. render a readable CSV/JSON diff for a binary DataFrame snapshot) while
-/// insta still writes its pending `.new` file as usual.
+/// insta still writes its pending `.new` file as usual; the caller reports it
+/// with [`report_mismatch`].
 pub fn run_snapshot_assertion_matched<F: FnOnce()>(
     snapshot_name: &str,
+    snapshot: &Path,
     assertion: F,
 ) -> PyResult<bool> {
-    match run_assertion(snapshot_name, assertion) {
+    let config = insta_env::in_effect()?;
+    match run_assertion(snapshot_name, snapshot, &config, assertion) {
         AssertionOutcome::Matched => Ok(true),
-        AssertionOutcome::Mismatch => Ok(false),
+        AssertionOutcome::Mismatch { .. } => Ok(false),
+        AssertionOutcome::ReadOnly { new, written } => {
+            Err(read_only_error(snapshot_name, snapshot, new, &written))
+        }
         AssertionOutcome::Error(message) => Err(PyAssertionError::new_err(message)),
     }
 }
+
+/// Takes the snapshot mismatches collected with fail-fast off since the last
+/// call, in assertion order.
+#[pyfunction]
+pub fn take_soft_failures(py: Python<'_>) -> Vec<PyObject> {
+    let failures = std::mem::take(&mut *SOFT_FAILURES.lock().unwrap_or_else(|x| x.into_inner()));
+    failures
+        .into_iter()
+        .map(|err| err.into_value(py).into_any())
+        .collect()
+}
@@ -0,0 +1,196 @@
+//! Attribute patching for `patch_json_snapshot`.
+//!
+//! `patch_json_snapshot("pkg.module.func")` swaps the attribute for a
+//! snapshotting mock while it is active and puts the original back afterwards.
+//! Resolving the dotted target, remembering exactly what was there (an
+//! attribute defined on the owner itself, or one it merely inherits) and
+//! restoring it is done here rather than through `unittest.mock.patch`. The
+//! mock itself still comes from `pysnaptest.mocks.mock_json_snapshot`, and
+//! decorating a (possibly async) test function is left to
+//! `pysnaptest.mocks.patched_function`, so pytest sees an ordinary function.
+
+use pyo3::exceptions::{PyImportError, PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyType};
+
+/// Imports the longest importable module prefix of `target` and walks the
+/// remaining attributes, returning the object owning the last one and its
+/// name (mirroring `pkgutil.resolve_name`).
+fn resolve_owner(py: Python<'_>, target: &str) -> PyResult<(PyObject, String)> {
+    let parts: Vec<&str> = target.split('.').collect();
+    if parts.len() < 2 || parts.iter().any(|part| part.is_empty()) {
+        return Err(PyValueError::new_err(format!(
+            "Cannot patch {target:?}; expected a dotted path such as \"package.module.function\""
+        )));
+    }
+    let mut owner = py.import(parts[0])?.into_any();
+    let mut consumed = 1;
+    while consumed < parts.len() - 1 {
+        let module_name = parts[..=consumed].join(".");
+        match py.import(module_name.as_str()) {
+            Ok(module) => owner = module.into_any(),
+            Err(err) if err.is_instance_of::<PyImportError>(py) => break,
+            Err(err) => return Err(err),
+        }
+        consumed += 1;
+    }
+    for part in &parts[consumed..parts.len() - 1] {
+        owner = owner.getattr(*part)?;
+    }
+    let attribute = parts[parts.len() - 1].to_string();
+    Ok((owner.unbind(), attribute))
+}
+
+/// What an active patch replaced.
+struct ActivePatch {
+    owner: PyObject,
+    attribute: String,
+    /// The owner's own value for the attribute, or `None` when it was
+    /// inherited (from a class or base) and is deleted again on exit.
+    original: Option<PyObject>,
+}
+
+/// Patch a function so calls are snapshot tested.
+///
+/// Usable as a context manager (returning the snapshotting mock) or as a
+/// decorator. The options are those of `pysnaptest.mock_json_snapshot`.
+#[pyclass(name = "patch_json_snapshot", module = "pysnaptest._pysnaptest")]
+pub struct PatchJsonSnapshot {
+    target: String,
+    options: Py<PyDict>,
+    active: Option<ActivePatch>,
+}
+
+#[pymethods]
+impl PatchJsonSnapshot {
+    #[new]
+    #[pyo3(signature = (
+        target,
+        *,
+        record=false,
+        snapshot_path=None,
+        snapshot_name=None,
+        redactions=None,
+        allow_duplicates=false,
+        encryption_key=None,
+        simulate_latency=None,
+        side_effects=None,
+        mode="replay",
+        max_age_days=None,
+        ignore=None,
+        node_id=None,
+        scope=None,
+        namespace=None,
+        decode=None,
+        encode=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        py: Python<'_>,
+        target: String,
+        record: bool,
+        snapshot_path: Option<String>,
+        snapshot_name: Option<String>,
+        redactions: Option<PyObject>,
+        allow_duplicates: bool,
+        encryption_key: Option<String>,
+        simulate_latency: Option<f64>,
+        side_effects: Option<PyObject>,
+        mode: &str,
+        max_age_days: Option<f64>,
+        ignore: Option<Vec<String>>,
+        node_id: Option<String>,
+        scope: Option<String>,
+        namespace: Option<String>,
+        decode: Option<PyObject>,
+        encode: Option<PyObject>,
+    ) -> PyResult<Self> {
+        let options = PyDict::new(py);
+        options.set_item("record", record)?;
+        options.set_item("snapshot_path", snapshot_path)?;
+        options.set_item("snapshot_name", snapshot_name)?;
+        options.set_item("redactions", redactions)?;
+        options.set_item("allow_duplicates", allow_duplicates)?;
+        options.set_item("encryption_key", encryption_key)?;
+        options.set_item("simulate_latency", simulate_latency)?;
+        options.set_item("side_effects", side_effects)?;
+        options.set_item("mode", mode)?;
+        options.set_item("max_age_days", max_age_days)?;
+        options.set_item("ignore", ignore)?;
+        options.set_item("node_id", node_id)?;
+        options.set_item("scope", scope)?;
+        options.set_item("namespace", namespace)?;
+        options.set_item("decode", decode)?;
+        options.set_item("encode", encode)?;
+        Ok(Self {
+            target,
+            options: options.unbind(),
+            active: None,
+        })
+    }
+
+    /// The dotted path being patched.
+    #[getter]
+    fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Replace the target with a snapshotting mock and return the mock.
+    fn __enter__(&mut self, py: Python<'_>) -> PyResult<PyObject> {
+        if self.active.is_some() {
+            return Err(PyRuntimeError::new_err(format!(
+                "patch_json_snapshot({:?}) is already active",
+                self.target
+            )));
+        }
+        let (owner, attribute) = resolve_owner(py, &self.target)?;
+        let owner = owner.into_bound(py);
+        let current = owner.getattr(attribute.as_str())?;
+        let original = match owner.getattr("__dict__") {
+            Ok(namespace) => namespace.get_item(attribute.as_str()).ok(),
+            Err(_) => Some(current.clone()),
+        };
+
+        let mock = py
+            .import("pysnaptest.mocks")?
+            .getattr("mock_json_snapshot")?
+            .call((current,), Some(self.options.bind(py)))?;
+        // A plain function stored on a class would be bound as a method;
+        // keep calls through instances receiving the caller's arguments only.
+        let replacement = if owner.is_instance_of::<PyType>() {
+            py.import("builtins")?
+                .getattr("staticmethod")?
+                .call1((mock.clone(),))?
+        } else {
+            mock.clone()
+        };
+        owner.setattr(attribute.as_str(), replacement)?;
+        self.active = Some(ActivePatch {
+            owner: owner.unbind(),
+            attribute,
+            original: original.map(Bound::unbind),
+        });
+        Ok(mock.unbind())
+    }
+
+    /// Put the original attribute back; exceptions are never suppressed.
+    #[pyo3(signature = (*_exc_info))]
+    fn __exit__(&mut self, py: Python<'_>, _exc_info: &Bound<'_, PyAny>) -> PyResult<bool> {
+        if let Some(active) = self.active.take() {
+            let owner = active.owner.bind(py);
+            match active.original {
+                Some(original) => owner.setattr(active.attribute.as_str(), original)?,
+                None => owner.delattr(active.attribute.as_str())?,
+            }
+        }
+        Ok(false)
+    }
+
+    /// Decorate `func` so the patch is active while it runs.
+    fn __call__(slf: Py<Self>, py: Python<'_>, func: PyObject) -> PyResult<PyObject> {
+        py.import("pysnaptest.mocks")?
+            .getattr("patched_function")?
+            .call1((slf, func))
+            .map(Bound::unbind)
+    }
+}
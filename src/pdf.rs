@@ -0,0 +1,75 @@
+//! Text-layer extraction for `assert_pdf_snapshot`.
+//!
+//! A PDF writer embeds creation dates and document ids, and is free to number
+//! and order its objects differently on every run, so the bytes of two
+//! renderings of the same document rarely match. The text mode of
+//! `assert_pdf_snapshot` snapshots what a reader sees instead: the page count
+//! and, per page, its size and rotation followed by the text drawn on it,
+//! one text object per line.
+
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use pyo3::exceptions::PyValueError;
+use pyo3::PyResult;
+
+/// Deepest page tree searched for inherited page attributes, so a cyclic
+/// `Parent` link cannot loop forever.
+const MAX_PAGE_TREE_DEPTH: usize = 64;
+
+/// Renders the text layer and page structure of the PDF `pdf`.
+pub(crate) fn text_layer(pdf: &[u8]) -> PyResult<String> {
+    let document = Document::load_mem(pdf)
+        .map_err(|e| PyValueError::new_err(format!("Unable to parse PDF: {e}")))?;
+    let pages = document.get_pages();
+    let mut rendered = format!("pages: {}\n", pages.len());
+    for (number, page_id) in pages {
+        let (width, height) = inherited(&document, page_id, b"MediaBox")
+            .and_then(|media_box| media_box_size(&document, media_box))
+            .unwrap_or((0.0, 0.0));
+        rendered.push_str(&format!("\n--- page {number} ({width} x {height}"));
+        match inherited(&document, page_id, b"Rotate").and_then(|r| r.as_i64().ok()) {
+            Some(rotate) if rotate % 360 != 0 => {
+                rendered.push_str(&format!(", rotated {}", rotate.rem_euclid(360)))
+            }
+            _ => {}
+        }
+        rendered.push_str(") ---\n");
+        let text = document.extract_text(&[number]).map_err(|e| {
+            PyValueError::new_err(format!(
+                "Unable to extract the text of PDF page {number}: {e}"
+            ))
+        })?;
+        for line in text.lines().map(str::trim_end).filter(|l| !l.is_empty()) {
+            rendered.push_str(line);
+            rendered.push('\n');
+        }
+    }
+    Ok(rendered)
+}
+
+/// The value of `key` on the page `page_id`, or on the nearest ancestor
+/// `Pages` node defining it.
+fn inherited<'a>(document: &'a Document, page_id: ObjectId, key: &[u8]) -> Option<&'a Object> {
+    let mut node: &Dictionary = document.get_dictionary(page_id).ok()?;
+    for _ in 0..MAX_PAGE_TREE_DEPTH {
+        if let Ok(value) = node.get_deref(key, document) {
+            return Some(value);
+        }
+        let parent = node.get(b"Parent").ok()?.as_reference().ok()?;
+        node = document.get_dictionary(parent).ok()?;
+    }
+    None
+}
+
+/// Width and height of a `[llx lly urx ury]` rectangle, in points.
+fn media_box_size(document: &Document, media_box: &Object) -> Option<(f32, f32)> {
+    let corners = media_box
+        .as_array()
+        .ok()?
+        .iter()
+        .map(|corner| document.dereference(corner).ok()?.1.as_float().ok())
+        .collect::<Option<Vec<f32>>>()?;
+    match corners[..] {
+        [llx, lly, urx, ury] => Some(((urx - llx).abs(), (ury - lly).abs())),
+        _ => None,
+    }
+}
@@ -0,0 +1,95 @@
+//! The pending snapshots written during this process.
+//!
+//! Every `.snap.new` file insta leaves behind for a mismatching assertion is
+//! recorded under the test that asserted it, so the pytest plugin can end a
+//! session with how many snapshots await review instead of leaving them to
+//! be found in the output of each failure.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use crate::provenance::current_test_node_id;
+
+/// The pending files written so far, by the node id of the test that wrote
+/// them (none outside pytest).
+static PENDING: Lazy<Mutex<BTreeMap<Option<String>, BTreeSet<PathBuf>>>> =
+    Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+/// The diffs of the pending files whose assertion passed all the same
+/// under `INSTA_FORCE_PASS=1`; `None` for a new or binary snapshot.
+static FORCE_PASSED: Lazy<Mutex<BTreeMap<PathBuf, Option<String>>>> =
+    Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+/// Records the pending `.snap.new` of the committed `snapshot` under the
+/// running test, if insta wrote one, and returns it.
+pub(crate) fn record(snapshot: &Path) -> Option<PathBuf> {
+    let mut pending = snapshot.as_os_str().to_owned();
+    pending.push(".new");
+    let pending = PathBuf::from(pending);
+    if !pending.is_file() {
+        return None;
+    }
+    PENDING
+        .lock()
+        .unwrap_or_else(|x| x.into_inner())
+        .entry(current_test_node_id())
+        .or_default()
+        .insert(pending.clone());
+    Some(pending)
+}
+
+/// Records the pending `.snap.new` of `snapshot`, if insta wrote one for an
+/// assertion it force-passed, along with the `diff` of its mismatch.
+pub(crate) fn record_force_passed(snapshot: &Path, diff: Option<String>) {
+    if let Some(pending) = record(snapshot) {
+        FORCE_PASSED
+            .lock()
+            .unwrap_or_else(|x| x.into_inner())
+            .insert(pending, diff);
+    }
+}
+
+/// Summarizes the pending snapshots written by this process's assertions
+/// that still await review (accepting or rejecting one drops it), as
+/// `{"total": N, "tests": {node_id: {"count": n, "paths": [...]}},
+/// "force_passed": {path: diff}}`; the node id is `None` for assertions run
+/// outside pytest, and `force_passed` holds the pending snapshots of
+/// assertions passed under `INSTA_FORCE_PASS=1`, with their uncolored diff
+/// (`None` for a new or binary snapshot).
+#[pyfunction]
+pub fn pending_summary(py: Python<'_>) -> PyResult<Bound<'_, PyDict>> {
+    let pending = PENDING.lock().unwrap_or_else(|x| x.into_inner());
+    let tests = PyDict::new(py);
+    let mut total = 0;
+    for (test, paths) in pending.iter() {
+        let paths: Vec<&PathBuf> = paths.iter().filter(|path| path.is_file()).collect();
+        if paths.is_empty() {
+            continue;
+        }
+        total += paths.len();
+        let entry = PyDict::new(py);
+        entry.set_item("count", paths.len())?;
+        entry.set_item("paths", PyList::new(py, paths)?)?;
+        tests.set_item(test, entry)?;
+    }
+    let summary = PyDict::new(py);
+    summary.set_item("total", total)?;
+    summary.set_item("tests", tests)?;
+    let force_passed = PyDict::new(py);
+    for (path, diff) in FORCE_PASSED
+        .lock()
+        .unwrap_or_else(|x| x.into_inner())
+        .iter()
+    {
+        if path.is_file() {
+            force_passed.set_item(path, diff)?;
+        }
+    }
+    summary.set_item("force_passed", force_passed)?;
+    Ok(summary)
+}
@@ -0,0 +1,163 @@
+//! Provenance recorded in the header of every snapshot.
+//!
+//! Each snapshot's `info` metadata names the pysnaptest and Python versions
+//! that wrote it, the pytest node id of the test asserting it, when it was
+//! recorded and, inside a git checkout, the commit it was recorded at:
+//!
+//! ```yaml
+//! info:
+//!   pysnaptest_version: 0.6.0
+//!   format_version: 1
+//!   python_version: 3.12.4
+//!   test: "tests/test_api.py::test_get"
+//!   created_at: "2026-10-16T09:30:00Z"
+//!   git_commit: 5f0c1e2...
+//! ```
+//!
+//! insta compares only the contents of a snapshot, so a passing assertion
+//! leaves an existing header (and its timestamp) alone. `PySnapshot.metadata()`
+//! reads the fields back, and the gc, expiry and re-recording tools key off
+//! them.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use once_cell::sync::{Lazy, OnceCell};
+use pyo3::prelude::*;
+use serde::Serialize;
+
+/// The layout of the snapshots this version writes, recorded in their
+/// headers: bumped whenever file names or headers change in a way older
+/// snapshots have to be upgraded for (see [`crate::upgrade`]). Version 1
+/// names snapshots after sanitized test ids and records provenance.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// Overrides the git commit recorded in snapshot headers; set it empty to
+/// record none.
+pub const GIT_COMMIT_ENV: &str = "PYSNAPTEST_GIT_COMMIT";
+
+/// `major.minor.micro` of the interpreter, set when the module is imported.
+static PYTHON_VERSION: OnceCell<String> = OnceCell::new();
+
+/// The commit checked out in the current directory, looked up once.
+static GIT_HEAD: Lazy<Option<String>> = Lazy::new(|| {
+    let output = std::process::Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    let commit = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (output.status.success() && !commit.is_empty()).then_some(commit)
+});
+
+/// Records the interpreter version; called from the module initializer,
+/// which holds the GIL that assertions release before writing snapshots.
+pub(crate) fn init(py: Python<'_>) {
+    let version = py.version_info();
+    PYTHON_VERSION.get_or_init(|| format!("{}.{}.{}", version.major, version.minor, version.patch));
+}
+
+/// The provenance fields of a snapshot header.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Provenance {
+    pysnaptest_version: &'static str,
+    format_version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    python_version: Option<String>,
+    /// Node id of the pytest test that recorded the snapshot (see
+    /// `pysnaptest.rerecord` and `pysnaptest gc`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    test: Option<String>,
+    created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    git_commit: Option<String>,
+}
+
+impl Provenance {
+    /// The provenance of a snapshot recorded now, by the running test.
+    pub(crate) fn current() -> Self {
+        Self {
+            pysnaptest_version: env!("CARGO_PKG_VERSION"),
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            python_version: PYTHON_VERSION.get().cloned(),
+            test: current_test_node_id(),
+            created_at: utc_timestamp(SystemTime::now()),
+            git_commit: git_commit(),
+        }
+    }
+
+    /// The provenance of a snapshot written now on behalf of the test with
+    /// the pytest node id `test`, rather than by the running one.
+    pub(crate) fn for_test(test: String) -> Self {
+        Self {
+            test: Some(test),
+            ..Self::current()
+        }
+    }
+
+    /// These fields followed by the fields of `info`, as one `info` block.
+    pub(crate) fn with<T: Serialize>(self, info: T) -> WithProvenance<T> {
+        WithProvenance {
+            provenance: self,
+            info,
+        }
+    }
+}
+
+/// Snapshot `info` metadata extended with its [`Provenance`].
+#[derive(Debug, Serialize)]
+pub(crate) struct WithProvenance<T> {
+    #[serde(flatten)]
+    provenance: Provenance,
+    #[serde(flatten)]
+    info: T,
+}
+
+/// The running test's node id, from `PYTEST_CURRENT_TEST`
+/// (`"tests/test_x.py::test_y (call)"` without the stage).
+pub(crate) fn current_test_node_id() -> Option<String> {
+    let current = std::env::var("PYTEST_CURRENT_TEST").ok()?;
+    let node_id = match current.rsplit_once(" (") {
+        Some((node_id, stage)) if stage.ends_with(')') => node_id,
+        _ => current.as_str(),
+    };
+    Some(node_id.to_string())
+}
+
+/// `PYSNAPTEST_GIT_COMMIT` if set (none when empty), otherwise the commit
+/// checked out in the current directory, if any.
+fn git_commit() -> Option<String> {
+    match std::env::var(GIT_COMMIT_ENV) {
+        Ok(commit) => Some(commit).filter(|commit| !commit.is_empty()),
+        Err(_) => GIT_HEAD.clone(),
+    }
+}
+
+/// `time` as a UTC `YYYY-MM-DDTHH:MM:SSZ` timestamp, the format of a mock
+/// recording's `recorded_at`.
+pub(crate) fn utc_timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let (days, secs) = (secs / 86_400, secs % 86_400);
+    // Days since the epoch to a proleptic Gregorian date, counted in 400-year
+    // eras of 146097 days from 0000-03-01 so leap days end each year.
+    let days = days + 719_468;
+    let (era, day_of_era) = (days / 146_097, days % 146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
@@ -0,0 +1,358 @@
+//! Redaction helpers that run insta's own selector engine outside an assertion.
+//!
+//! insta applies a redaction wherever its selector matches and silently does
+//! nothing when it matches nothing, so a typo such as `.data[].id` (only
+//! matches sequences) versus `.data.*.id` (matches map values) leaks the value
+//! it was meant to scrub straight into the snapshot. The helpers here parse and
+//! walk selectors with the exact same `Selector` type insta uses when binding
+//! settings, so "matched" here means "would have been redacted" there.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use insta::_macro_support::Selector;
+use insta::dynamic_redaction;
+use insta::internals::{Content, Redaction};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use sha2::{Digest, Sha256};
+
+use crate::hashing::hex;
+use crate::RedactionType;
+
+/// Converts a JSON value into insta's `Content` tree, mirroring the shape insta
+/// itself produces when it serializes a `serde_json::Value` for redaction.
+pub(crate) fn json_to_content(value: &serde_json::Value) -> Content {
+    match value {
+        serde_json::Value::Null => Content::None,
+        serde_json::Value::Bool(b) => Content::Bool(*b),
+        serde_json::Value::Number(n) => {
+            if let Some(u) = n.as_u64() {
+                Content::U64(u)
+            } else if let Some(i) = n.as_i64() {
+                Content::I64(i)
+            } else {
+                Content::F64(n.as_f64().unwrap_or(f64::NAN))
+            }
+        }
+        serde_json::Value::String(s) => Content::String(s.clone()),
+        serde_json::Value::Array(items) => {
+            Content::Seq(items.iter().map(json_to_content).collect())
+        }
+        serde_json::Value::Object(map) => Content::Map(
+            map.iter()
+                .map(|(k, v)| (Content::String(k.clone()), json_to_content(v)))
+                .collect(),
+        ),
+    }
+}
+
+/// Parses `selector` with insta's grammar, surfacing a syntax error as a
+/// `ValueError` that names the offending selector (insta itself would panic).
+fn parse_selector(selector: &str) -> PyResult<Selector<'_>> {
+    Selector::parse(selector).map_err(|e| {
+        PyValueError::new_err(format!(
+            "Invalid redaction selector {selector:?} (syntax error at column {})",
+            e.column()
+        ))
+    })
+}
+
+/// Returns `true` when `selector` matches at least one node of `content`.
+fn selector_matches(selector: &Selector<'_>, content: &Content) -> bool {
+    let matched = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&matched);
+    let probe = dynamic_redaction(move |value, _path| {
+        flag.store(true, Ordering::Relaxed);
+        value
+    });
+    selector.redact(content.clone(), &probe);
+    matched.load(Ordering::Relaxed)
+}
+
+/// Applies `redactions` to `content` the way insta does when binding settings,
+/// so callers can inspect the value exactly as it would be written.
+pub(crate) fn apply_redactions(
+    mut content: Content,
+    redactions: &HashMap<String, RedactionType>,
+) -> PyResult<Content> {
+    for (selector, redaction) in redactions {
+        let redaction: Redaction = redaction.clone().into();
+        content = parse_selector(selector)?.redact(content, &redaction);
+    }
+    Ok(content)
+}
+
+/// Replaces a value with a short, stable hash of it, `<hash:1a2b3c4d>`, so
+/// the snapshot still shows whether it changed.
+pub(crate) fn hash_redaction() -> Redaction {
+    dynamic_redaction(|value, _path| {
+        let bytes = match value.resolve_inner().as_str() {
+            Some(text) => text.as_bytes().to_vec(),
+            None => serde_json::to_vec(&value).unwrap_or_default(),
+        };
+        format!("<hash:{}>", &hex(&Sha256::digest(bytes))[..8])
+    })
+}
+
+/// Replaces a value with its type, and length where it has one: `<str:len=12>`,
+/// `<int:len=5>` (digits), `<list:len=3>`, `<bool>`, ...
+pub(crate) fn type_redaction() -> Redaction {
+    dynamic_redaction(|value, _path| describe_type(&value))
+}
+
+fn describe_type(value: &Content) -> String {
+    let value = value.resolve_inner();
+    match value {
+        Content::String(text) => format!("<str:len={}>", text.chars().count()),
+        Content::Char(_) => "<str:len=1>".to_string(),
+        Content::Bytes(bytes) => format!("<bytes:len={}>", bytes.len()),
+        Content::Bool(_) => "<bool>".to_string(),
+        Content::F32(_) | Content::F64(_) => "<float>".to_string(),
+        Content::None | Content::Unit => "<null>".to_string(),
+        Content::Seq(items) | Content::Tuple(items) => format!("<list:len={}>", items.len()),
+        Content::Map(entries) => format!("<map:len={}>", entries.len()),
+        _ => match value.as_i128() {
+            Some(int) => format!("<int:len={}>", int.unsigned_abs().to_string().len()),
+            None => match value.as_u128() {
+                Some(int) => format!("<int:len={}>", int.to_string().len()),
+                None => "<value>".to_string(),
+            },
+        },
+    }
+}
+
+/// Cuts strings longer than `len` characters down to their first `len`,
+/// followed by `…`; other values are left alone.
+pub(crate) fn truncate_redaction(len: usize) -> Redaction {
+    dynamic_redaction(move |value, _path| {
+        let truncated = value
+            .resolve_inner()
+            .as_str()
+            .filter(|text| text.chars().count() > len)
+            .map(|text| format!("{}…", text.chars().take(len).collect::<String>()));
+        match truncated {
+            Some(text) => Content::String(text),
+            None => value,
+        }
+    })
+}
+
+/// Extracts an optional `redactions` argument; see [`compile_redactions`].
+pub(crate) fn extract_redactions(
+    redactions: &Bound<'_, PyAny>,
+) -> PyResult<Option<HashMap<String, RedactionType>>> {
+    if redactions.is_none() {
+        return Ok(None);
+    }
+    compile_redactions(redactions).map(Some)
+}
+
+/// Compiles a `redactions` mapping into insta selectors.
+///
+/// Keys starting with `.` are selectors, as in the flat form. Any other key
+/// names a field, and a dict value nests further into it, so
+/// `{"user": {"sessions": {"*": {"token": "[token]"}}}}` compiles to
+/// `.user.sessions.*.token`. `"*"` and `"**"` are (deep) wildcards, keys
+/// starting with `[` are subscripts or ranges taken as written, integers are
+/// indices, and keys that are not identifiers are quoted (`["content-type"]`).
+pub(crate) fn compile_redactions(
+    redactions: &Bound<'_, PyAny>,
+) -> PyResult<HashMap<String, RedactionType>> {
+    let mut compiled = HashMap::new();
+    compile_into("", redactions.downcast::<PyDict>()?, &mut compiled)?;
+    Ok(compiled)
+}
+
+fn compile_into(
+    prefix: &str,
+    redactions: &Bound<'_, PyDict>,
+    compiled: &mut HashMap<String, RedactionType>,
+) -> PyResult<()> {
+    if redactions.is_empty() && !prefix.is_empty() {
+        return Err(PyValueError::new_err(format!(
+            "Nested redactions under {prefix:?} are empty"
+        )));
+    }
+    for (key, value) in redactions.iter() {
+        let selector = format!("{prefix}{}", selector_segment(&key, prefix.is_empty())?);
+        if let Ok(nested) = value.downcast::<PyDict>() {
+            compile_into(&selector, nested, compiled)?;
+        } else {
+            parse_selector(&selector)?;
+            compiled.insert(selector, value.extract()?);
+        }
+    }
+    Ok(())
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c == '_' || c == '$' || c.is_ascii_alphabetic())
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+}
+
+/// The selector segment a nested redaction key stands for.
+fn selector_segment(key: &Bound<'_, PyAny>, top_level: bool) -> PyResult<String> {
+    if let Ok(index) = key.extract::<i64>() {
+        return Ok(format!("[{index}]"));
+    }
+    let key: String = key.extract()?;
+    match key.as_str() {
+        "*" => Ok(".*".to_string()),
+        "**" => Ok(".**".to_string()),
+        _ if key.starts_with('[') || (top_level && key.starts_with('.')) => Ok(key),
+        _ if is_identifier(&key) => Ok(format!(".{key}")),
+        _ if key.contains('"') => Err(PyValueError::new_err(format!(
+            "Redaction key {key:?} cannot be selected: insta selectors do not support '\"' in keys"
+        ))),
+        _ => Ok(format!("[\"{key}\"]")),
+    }
+}
+
+/// Sorts every array matched by a selector of `sort_by` by the field it maps
+/// to, so collections returned in no particular order snapshot stably.
+///
+/// The field may be a dotted path into each element (`"user.id"`). Elements
+/// are compared by their field's value (elements without it come first) and
+/// keep their relative order on ties; non-array matches are left alone.
+pub(crate) fn sort_arrays(
+    value: serde_json::Value,
+    sort_by: &HashMap<String, String>,
+) -> PyResult<serde_json::Value> {
+    if sort_by.is_empty() {
+        return Ok(value);
+    }
+    let mut content = json_to_content(&value);
+    for (selector, field) in sort_by {
+        let path: Vec<String> = field.split('.').map(str::to_string).collect();
+        let sort = dynamic_redaction(move |mut value, _path| {
+            if let Content::Seq(items) = value.resolve_inner_mut() {
+                items.sort_by(|a, b| {
+                    field_value(a, &path)
+                        .partial_cmp(&field_value(b, &path))
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+            }
+            value
+        });
+        content = parse_selector(selector)?.redact(content, &sort);
+    }
+    content_to_json(&content)
+}
+
+/// Rounds every float in `value` to `decimals` places, like a rounded
+/// redaction on each of them; integers are left alone.
+pub(crate) fn round_floats(value: serde_json::Value, decimals: Option<u32>) -> serde_json::Value {
+    let Some(decimals) = decimals else {
+        return value;
+    };
+    match value {
+        serde_json::Value::Number(n) if n.is_f64() => {
+            let scale = 10f64.powi(decimals.min(i32::MAX as u32) as i32);
+            n.as_f64()
+                .and_then(|f| serde_json::Number::from_f64((f * scale).round() / scale))
+                .map_or(serde_json::Value::Number(n), serde_json::Value::Number)
+        }
+        serde_json::Value::Array(items) => serde_json::Value::Array(
+            items
+                .into_iter()
+                .map(|item| round_floats(item, Some(decimals)))
+                .collect(),
+        ),
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.into_iter()
+                .map(|(key, value)| (key, round_floats(value, Some(decimals))))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// Placeholder for a node matched by an `ignore` selector; JSON never
+/// produces a unit struct, so it cannot collide with real data.
+const IGNORED: Content = Content::UnitStruct("pysnaptest::ignored");
+
+/// Drops every node matched by a selector of `ignore` from `value`: a matched
+/// map entry loses its key as well, and a matched array element is removed.
+///
+/// insta redactions can only replace a value, so matches are first replaced
+/// with a placeholder that is then pruned from the tree.
+pub(crate) fn drop_ignored(
+    value: serde_json::Value,
+    ignore: &[String],
+) -> PyResult<serde_json::Value> {
+    if ignore.is_empty() {
+        return Ok(value);
+    }
+    let mut content = json_to_content(&value);
+    let placeholder = dynamic_redaction(|_value, _path| IGNORED);
+    for selector in ignore {
+        content = parse_selector(selector)?.redact(content, &placeholder);
+    }
+    content_to_json(&prune_ignored(content))
+}
+
+fn prune_ignored(content: Content) -> Content {
+    match content {
+        Content::Map(entries) => Content::Map(
+            entries
+                .into_iter()
+                .filter(|(_, value)| *value != IGNORED)
+                .map(|(key, value)| (key, prune_ignored(value)))
+                .collect(),
+        ),
+        Content::Seq(items) => Content::Seq(
+            items
+                .into_iter()
+                .filter(|item| *item != IGNORED)
+                .map(prune_ignored)
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+fn content_to_json(content: &Content) -> PyResult<serde_json::Value> {
+    serde_json::to_value(content).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Looks up a dotted field path in a map element of a sorted array.
+fn field_value<'a>(item: &'a Content, path: &[String]) -> Option<&'a Content> {
+    path.iter()
+        .try_fold(item, |node, key| match node.resolve_inner() {
+            Content::Map(entries) => entries
+                .iter()
+                .find(|(k, _)| k.as_str() == Some(key.as_str()))
+                .map(|(_, v)| v),
+            _ => None,
+        })
+}
+
+/// Report which redaction selectors match nothing in `value`.
+///
+/// `value` is expected to already be JSON-native (the Python side normalizes
+/// rich objects with `pysnaptest.to_jsonable` first). Only the selectors of
+/// `redactions` are inspected; their replacement values are ignored. Returns
+/// the unmatched selectors, sorted, so an empty list means every redaction
+/// would have applied. A selector insta cannot parse raises `ValueError`.
+#[pyfunction]
+pub fn validate_redactions(
+    value: &Bound<'_, PyAny>,
+    #[pyo3(from_py_with = compile_redactions)] redactions: HashMap<String, RedactionType>,
+) -> PyResult<Vec<String>> {
+    let json: serde_json::Value = crate::serialization::depythonize(value)?;
+    let content = json_to_content(&json);
+    let mut unmatched = Vec::new();
+    for selector in redactions.into_keys() {
+        if !selector_matches(&parse_selector(&selector)?, &content) {
+            unmatched.push(selector);
+        }
+    }
+    unmatched.sort();
+    Ok(unmatched)
+}
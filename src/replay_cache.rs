@@ -0,0 +1,153 @@
+//! A cache of the recordings parsed during mock replay.
+//!
+//! A mocked function called in a parametrized loop replays the same response
+//! snapshots over and over, and a `matches` predicate reads every recorded
+//! request of its mock on each call. Parsed bodies are kept in a small LRU
+//! cache keyed by path, invalidated when the file's modification time or
+//! length changes, so each one is read and parsed once until it is
+//! rewritten. Decrypted recordings are not cached: their value depends on
+//! the key they are read with.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::SystemTime;
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+
+/// The number of recordings kept unless `set_replay_cache_size` says
+/// otherwise.
+const DEFAULT_CAPACITY: usize = 128;
+
+struct Entry {
+    modified: SystemTime,
+    len: u64,
+    value: Arc<serde_json::Value>,
+    /// When the entry was last read, in reads of the cache.
+    used: u64,
+}
+
+struct Cache {
+    entries: HashMap<PathBuf, Entry>,
+    capacity: usize,
+    reads: u64,
+    hits: usize,
+    misses: usize,
+}
+
+impl Cache {
+    fn evict_to(&mut self, capacity: usize) {
+        while self.entries.len() > capacity {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.used)
+                .map(|(path, _)| path.clone())
+            else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+static CACHE: Lazy<Mutex<Cache>> = Lazy::new(|| {
+    Mutex::new(Cache {
+        entries: HashMap::new(),
+        capacity: DEFAULT_CAPACITY,
+        reads: 0,
+        hits: 0,
+        misses: 0,
+    })
+});
+
+fn cache<'a>() -> MutexGuard<'a, Cache> {
+    CACHE.lock().unwrap_or_else(|x| x.into_inner())
+}
+
+/// Returns the parsed body of the recording at `path`, from the cache when
+/// the file has not changed since it was parsed. `parse` reads it otherwise,
+/// returning whether its value may be cached.
+pub(crate) fn get_or_parse(
+    path: &Path,
+    parse: impl FnOnce() -> PyResult<(serde_json::Value, bool)>,
+) -> PyResult<Arc<serde_json::Value>> {
+    let stamp = std::fs::metadata(path)
+        .ok()
+        .and_then(|metadata| Some((metadata.modified().ok()?, metadata.len())));
+    if let Some((modified, len)) = stamp {
+        let mut cache = cache();
+        cache.reads += 1;
+        let reads = cache.reads;
+        let hit = cache
+            .entries
+            .get_mut(path)
+            .filter(|entry| entry.modified == modified && entry.len == len)
+            .map(|entry| {
+                entry.used = reads;
+                entry.value.clone()
+            });
+        match hit {
+            Some(value) => {
+                cache.hits += 1;
+                return Ok(value);
+            }
+            None => cache.misses += 1,
+        }
+    }
+
+    // Parsed without the lock, so other threads replay meanwhile.
+    let (value, cacheable) = parse()?;
+    let value = Arc::new(value);
+    if let Some((modified, len)) = stamp.filter(|_| cacheable) {
+        let mut cache = cache();
+        if cache.capacity > 0 {
+            let used = cache.reads;
+            cache.entries.insert(
+                path.to_path_buf(),
+                Entry {
+                    modified,
+                    len,
+                    value: value.clone(),
+                    used,
+                },
+            );
+            let capacity = cache.capacity;
+            cache.evict_to(capacity);
+        }
+    }
+    Ok(value)
+}
+
+/// Drops every recording cached for mock replay, and resets the counts
+/// reported by `replay_cache_info`.
+#[pyfunction]
+pub fn clear_replay_cache() {
+    let mut cache = cache();
+    cache.entries.clear();
+    cache.hits = 0;
+    cache.misses = 0;
+}
+
+/// Sets how many parsed recordings mock replay keeps (128 by default),
+/// evicting the least recently used ones beyond it; `0` turns caching off.
+#[pyfunction]
+pub fn set_replay_cache_size(size: usize) {
+    let mut cache = cache();
+    cache.capacity = size;
+    cache.evict_to(size);
+}
+
+/// Returns the replay cache's `hits`, `misses`, cached `entries` and
+/// `capacity`.
+#[pyfunction]
+pub fn replay_cache_info() -> HashMap<&'static str, usize> {
+    let cache = cache();
+    HashMap::from([
+        ("hits", cache.hits),
+        ("misses", cache.misses),
+        ("entries", cache.entries.len()),
+        ("capacity", cache.capacity),
+    ])
+}
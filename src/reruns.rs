@@ -0,0 +1,112 @@
+//! Restarting snapshot numbering when pytest runs a test again.
+//!
+//! Plugins such as pytest-rerunfailures run a failed test again in the same
+//! process, so the assertions of the rerun would move on to `<name>-2`, ...
+//! and compare against snapshots the test never wrote. Each run of a test
+//! goes through its `setup`, `call` and `teardown` stages in order, as
+//! `PYTEST_CURRENT_TEST` reports them: a test seen in an earlier stage than
+//! the last one it was seen in is running again, and the counters its
+//! assertions ticked are reset.
+//!
+//! Stages are observed whenever `PYTEST_CURRENT_TEST` is read for a
+//! `SnapshotInfo`, and by the pytest plugin as each test is set up, so a
+//! rerun is noticed even when no fixture asserts snapshots.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Mutex, MutexGuard};
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+
+use crate::common::CounterKey;
+use crate::SnapshotInfo;
+
+/// What is known of a test's current run.
+struct Run {
+    /// The rank of the last stage the test was seen in; see [`rank`].
+    stage: u8,
+    /// The counters its assertions ticked in this run.
+    counters: BTreeSet<CounterKey>,
+}
+
+static RUNS: Lazy<Mutex<HashMap<String, Run>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn runs<'a>() -> MutexGuard<'a, HashMap<String, Run>> {
+    RUNS.lock().unwrap_or_else(|x| x.into_inner())
+}
+
+/// The order of a pytest stage within a run, or `None` for an unknown one.
+fn rank(stage: &str) -> Option<u8> {
+    match stage {
+        "setup" => Some(0),
+        "call" => Some(1),
+        "teardown" => Some(2),
+        _ => None,
+    }
+}
+
+/// Splits a `PYTEST_CURRENT_TEST` value such as
+/// `"tests/test_x.py::test_y (call)"` into the node id and the stage.
+pub(crate) fn split_stage(current: &str) -> (&str, Option<&str>) {
+    match current.rsplit_once(" (") {
+        Some((node_id, stage)) if stage.ends_with(')') => {
+            (node_id, Some(&stage[..stage.len() - 1]))
+        }
+        _ => (current, None),
+    }
+}
+
+/// Notes that the test `node_id` is in `stage`, resetting the counters of
+/// its previous run if this starts a new one. Returns whether it did.
+pub(crate) fn observe(node_id: &str, stage: &str) -> bool {
+    let Some(stage) = rank(stage) else {
+        return false;
+    };
+    // Taken out before locking the counters, which `record` holds the other
+    // way round.
+    let stale = match runs().get_mut(node_id) {
+        Some(run) => {
+            let rerun = stage < run.stage;
+            run.stage = stage;
+            rerun.then(|| std::mem::take(&mut run.counters))
+        }
+        None => None,
+    };
+    let Some(stale) = stale else {
+        runs().entry(node_id.to_string()).or_insert(Run {
+            stage,
+            counters: BTreeSet::new(),
+        });
+        return false;
+    };
+    let mut counters = SnapshotInfo::counters();
+    for key in &stale {
+        counters.remove(key);
+    }
+    true
+}
+
+/// Notes that the running test ticked the counter `key`.
+pub(crate) fn record(key: CounterKey) {
+    let Ok(current) = std::env::var("PYTEST_CURRENT_TEST") else {
+        return;
+    };
+    let (node_id, stage) = split_stage(&current);
+    runs()
+        .entry(node_id.to_string())
+        .or_insert_with(|| Run {
+            stage: stage.and_then(rank).unwrap_or_default(),
+            counters: BTreeSet::new(),
+        })
+        .counters
+        .insert(key);
+}
+
+/// Notes that pytest is running the `stage` (`"setup"`, `"call"` or
+/// `"teardown"`) of the test `node_id`; called by the pytest plugin as each
+/// test is set up. Returns whether this started a rerun of the test, whose
+/// snapshot numbering was then restarted.
+#[pyfunction]
+pub fn observe_test_stage(node_id: &str, stage: &str) -> bool {
+    observe(node_id, stage)
+}
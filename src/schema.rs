@@ -0,0 +1,295 @@
+//! Structural schemas for `assert_schema_snapshot`.
+//!
+//! Some payloads churn on every run (prices, ids, generated text) while their
+//! shape is the contract that matters. A schema snapshot pins that shape
+//! instead of the data: a JSON-Schema-like description of each value's type,
+//! the properties of objects and which of them are always present, and the
+//! element schema of arrays. The elements of an array are merged into one
+//! schema, so a key missing from some elements is optional and a value that
+//! is sometimes `null` has the type `["null", "string"]`.
+//!
+//! `validate_against_schema_snapshot` checks a payload against a committed
+//! schema instead of re-inferring it: every value must have one of its
+//! schema's types and every required property must be present. Properties the
+//! schema does not know about are accepted, so additive changes pass.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use insta::internals::SnapshotContents;
+use insta::Snapshot;
+use pyo3::exceptions::{PyAssertionError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+pyo3::create_exception!(
+    _pysnaptest,
+    SchemaViolationError,
+    PyAssertionError,
+    "A payload does not match its schema snapshot; `violations` lists why."
+);
+
+/// The inferred shape of a JSON value. Field order is the on-disk key order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Schema {
+    /// JSON Schema type names; written as a string when there is only one.
+    #[serde(
+        rename = "type",
+        serialize_with = "serialize_types",
+        deserialize_with = "deserialize_types"
+    )]
+    types: BTreeSet<String>,
+    /// Merged schema of an array's elements; absent for an empty array.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    items: Option<Box<Schema>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    properties: Option<BTreeMap<String, Schema>>,
+    /// Properties present every time an object was seen.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    required: Option<BTreeSet<String>>,
+}
+
+fn serialize_types<S: Serializer>(
+    types: &BTreeSet<String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match types.iter().next() {
+        Some(only) if types.len() == 1 => serializer.serialize_str(only),
+        _ => types.serialize(serializer),
+    }
+}
+
+fn deserialize_types<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeSet<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(BTreeSet<String>),
+    }
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(name) => BTreeSet::from([name]),
+        OneOrMany::Many(names) => names,
+    })
+}
+
+/// JSON Schema's name for the type of `value`.
+pub(crate) fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+impl Schema {
+    /// Infers the schema of `value`.
+    pub(crate) fn infer(value: &Value) -> Self {
+        let mut schema = Schema {
+            types: BTreeSet::from([type_name(value).to_string()]),
+            ..Schema::default()
+        };
+        match value {
+            Value::Array(elements) => {
+                schema.items = elements
+                    .iter()
+                    .map(Schema::infer)
+                    .reduce(Schema::merge)
+                    .map(Box::new);
+            }
+            Value::Object(map) => {
+                schema.properties = Some(
+                    map.iter()
+                        .map(|(key, value)| (key.clone(), Schema::infer(value)))
+                        .collect(),
+                );
+                schema.required = Some(map.keys().cloned().collect());
+            }
+            _ => {}
+        }
+        schema
+    }
+
+    /// The schema describing values of either `self` or `other`.
+    fn merge(self, other: Schema) -> Schema {
+        let mut types: BTreeSet<String> = self.types.union(&other.types).cloned().collect();
+        // Every integer is also a number.
+        if types.contains("number") {
+            types.remove("integer");
+        }
+        let items = match (self.items, other.items) {
+            (Some(a), Some(b)) => Some(Box::new(a.merge(*b))),
+            (a, b) => a.or(b),
+        };
+        let (properties, required) = match (self.properties, other.properties) {
+            (Some(mut a), Some(b)) => {
+                for (key, schema) in b {
+                    let merged = match a.remove(&key) {
+                        Some(existing) => existing.merge(schema),
+                        None => schema,
+                    };
+                    a.insert(key, merged);
+                }
+                let required = match (self.required, other.required) {
+                    (Some(x), Some(y)) => Some(x.intersection(&y).cloned().collect()),
+                    (x, y) => x.or(y),
+                };
+                (Some(a), required)
+            }
+            (Some(a), None) => (Some(a), self.required),
+            (None, b) => (b, other.required),
+        };
+        Schema {
+            types,
+            items,
+            properties,
+            required,
+        }
+    }
+}
+
+/// One way a payload breaks its schema.
+#[derive(Debug)]
+pub(crate) struct Violation {
+    /// Selector-style location, e.g. `.lines[2].sku`.
+    path: String,
+    /// The schema's type(s) for the value.
+    expected: String,
+    /// The payload's type, or `None` when a required property is missing.
+    actual: Option<&'static str>,
+}
+
+impl Violation {
+    fn kind(&self) -> &'static str {
+        match self.actual {
+            Some(_) => "type_mismatch",
+            None => "missing_key",
+        }
+    }
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.actual {
+            Some(actual) => write!(f, "{}: expected {}, got {actual}", self.path, self.expected),
+            None => write!(f, "{}: missing (expected {})", self.path, self.expected),
+        }
+    }
+}
+
+impl Schema {
+    fn expected(&self) -> String {
+        self.types.iter().cloned().collect::<Vec<_>>().join(" | ")
+    }
+
+    /// Collects how `value`, found at `path`, breaks this schema.
+    pub(crate) fn validate(&self, value: &Value, path: &str, violations: &mut Vec<Violation>) {
+        let actual = type_name(value);
+        let accepted =
+            self.types.contains(actual) || (actual == "integer" && self.types.contains("number"));
+        if !accepted {
+            violations.push(Violation {
+                path: display_path(path),
+                expected: self.expected(),
+                actual: Some(actual),
+            });
+            return;
+        }
+        match value {
+            Value::Object(map) => {
+                for key in self.required.iter().flatten() {
+                    if !map.contains_key(key) {
+                        let expected = self
+                            .properties
+                            .as_ref()
+                            .and_then(|properties| properties.get(key))
+                            .map(Schema::expected)
+                            .unwrap_or_default();
+                        violations.push(Violation {
+                            path: format!("{path}.{key}"),
+                            expected,
+                            actual: None,
+                        });
+                    }
+                }
+                for (key, property) in self.properties.iter().flatten() {
+                    if let Some(value) = map.get(key) {
+                        property.validate(value, &format!("{path}.{key}"), violations);
+                    }
+                }
+            }
+            Value::Array(elements) => {
+                if let Some(items) = &self.items {
+                    for (index, element) in elements.iter().enumerate() {
+                        items.validate(element, &format!("{path}[{index}]"), violations);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn display_path(path: &str) -> String {
+    if path.is_empty() {
+        ".".to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+/// Reads the committed schema at `path`.
+pub(crate) fn read_committed(path: &Path) -> PyResult<Schema> {
+    let snapshot = Snapshot::from_file(path).map_err(|_| {
+        PyValueError::new_err(format!(
+            "No schema snapshot at {}; record one with assert_schema_snapshot first",
+            path.display()
+        ))
+    })?;
+    let SnapshotContents::Text(text) = snapshot.contents() else {
+        return Err(PyValueError::new_err(format!(
+            "{} is a binary snapshot, not a schema snapshot",
+            path.display()
+        )));
+    };
+    serde_json::from_str(&text.to_string()).map_err(|e| {
+        PyValueError::new_err(format!("{} is not a schema snapshot: {e}", path.display()))
+    })
+}
+
+/// A `SchemaViolationError` listing `violations`, which are also attached as
+/// its `violations` attribute (dicts of `path`, `kind`, `expected`, `actual`).
+pub(crate) fn violation_error(
+    py: Python<'_>,
+    snapshot: &Path,
+    violations: &[Violation],
+) -> PyResult<PyErr> {
+    let name = snapshot
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let lines: Vec<String> = violations.iter().map(|v| format!("  {v}")).collect();
+    let err = SchemaViolationError::new_err(format!(
+        "{} violation(s) of schema snapshot {name}:\n{}",
+        violations.len(),
+        lines.join("\n")
+    ));
+    let records = PyList::empty(py);
+    for violation in violations {
+        let record = PyDict::new(py);
+        record.set_item("path", &violation.path)?;
+        record.set_item("kind", violation.kind())?;
+        record.set_item("expected", &violation.expected)?;
+        record.set_item("actual", violation.actual)?;
+        records.append(record)?;
+    }
+    err.value(py).setattr("violations", records)?;
+    Ok(err)
+}
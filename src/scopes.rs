@@ -0,0 +1,104 @@
+//! Snapshots shared by the tests of a module or of the whole session.
+//!
+//! An expensive fixture shared by many tests, e.g. a module-scoped client
+//! whose calls are recorded as mocks, would otherwise store its snapshots
+//! under the name of whichever test requested it first, and the next test
+//! would look for them under `<name>-2`. A scoped [`SnapshotInfo`] is named
+//! after its module (`<module>`) or the session (`session`, stored in the
+//! `snapshots/` folder of the workspace root) instead, and each test reading
+//! it takes a reference to it: the first assertion of a new test restarts
+//! the numbering, so every dependent test replays the same snapshots from
+//! `<name>` on.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use once_cell::sync::Lazy;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::common::{workspace_roots, CounterKey};
+use crate::provenance::current_test_node_id;
+use crate::SnapshotInfo;
+
+/// What a scoped snapshot is shared by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Scope {
+    Module,
+    Session,
+}
+
+impl Scope {
+    /// Parses a `scope` argument; `"function"` is the default, unscoped,
+    /// behaviour.
+    pub(crate) fn parse(scope: Option<&str>) -> PyResult<Option<Self>> {
+        match scope {
+            None | Some("function") => Ok(None),
+            Some("module") => Ok(Some(Self::Module)),
+            Some("session") => Ok(Some(Self::Session)),
+            Some(other) => Err(PyValueError::new_err(format!(
+                "Invalid snapshot scope {other:?}: use \"function\", \"module\" or \"session\""
+            ))),
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Module => "module",
+            Self::Session => "session",
+        }
+    }
+}
+
+/// The folder session-scoped snapshots are stored in: `snapshots/` in the
+/// first workspace root, if there is one.
+pub(crate) fn session_folder() -> Option<PathBuf> {
+    workspace_roots()
+        .into_iter()
+        .next()
+        .map(|root| root.join("snapshots"))
+}
+
+/// The tests referencing a scoped snapshot name.
+#[derive(Default)]
+struct References {
+    tests: BTreeSet<String>,
+    /// The test that asserted it last, whose numbering is running.
+    current: Option<String>,
+}
+
+static REFERENCES: Lazy<Mutex<HashMap<CounterKey, References>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn references<'a>() -> MutexGuard<'a, HashMap<CounterKey, References>> {
+    REFERENCES.lock().unwrap_or_else(|x| x.into_inner())
+}
+
+/// Takes a reference to the scoped snapshot name `key` for `test` (the
+/// running test when `None`), restarting its numbering when that test is
+/// not the one numbering it already.
+pub(crate) fn acquire(key: &CounterKey, test: Option<&str>) {
+    let Some(test) = test.map(str::to_string).or_else(current_test_node_id) else {
+        return;
+    };
+    // Released before locking the counters, as everywhere else.
+    let restart = {
+        let mut references = references();
+        let references = references.entry(key.clone()).or_default();
+        references.tests.insert(test.clone());
+        references.current.replace(test.clone()) != Some(test)
+    };
+    if restart {
+        SnapshotInfo::counters().remove(key);
+    }
+}
+
+/// The node ids of the tests that referenced the scoped snapshot name
+/// `key` in this session, sorted.
+pub(crate) fn referencing_tests(key: &CounterKey) -> Vec<String> {
+    references()
+        .get(key)
+        .map(|references| references.tests.iter().cloned().collect())
+        .unwrap_or_default()
+}
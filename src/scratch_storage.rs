@@ -0,0 +1,240 @@
+//! Scratch snapshot storage, selectable per `SnapshotInfo`.
+//!
+//! `SnapshotInfo.with_storage(ScratchStorage())`, or a `SnapshotSettings`
+//! given `storage=ScratchStorage()`, keeps an info's snapshots out of its
+//! folder: teams testing their own helpers around pysnaptest get tests that
+//! never write to the project. A `ScratchStorage` holds each snapshot file
+//! (the `.snap`, its pending `.snap.new`, binary files) with its contents for
+//! as long as it lives.
+//!
+//! This is not a filesystem-free backend. insta reads and writes snapshot
+//! files itself, by path, so an info backed by a storage stores its
+//! snapshots in a private scratch folder under the system temp directory
+//! while insta works on them: the stored files of a folder are written there
+//! when an assertion under it builds its insta settings
+//! ([`ScratchStorage::materialize`]), and taken back, and removed, once the
+//! assertion has run ([`absorb_after_assertion`]). Mock replay reads and
+//! rewrites a single recording the same way ([`with_file`]). Every scratch
+//! file is written by atomic rename, like the snapshots in a project.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+
+use crate::atomic;
+
+/// The files of a storage, and the scratch folder insta works in.
+#[derive(Debug)]
+struct Store {
+    scratch: PathBuf,
+    files: Mutex<BTreeMap<PathBuf, Vec<u8>>>,
+}
+
+impl Drop for Store {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.scratch);
+    }
+}
+
+/// The live stores, for finding the one a snapshot path belongs to.
+static STORES: Lazy<Mutex<Vec<Weak<Store>>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Numbers the scratch folders of this process.
+static NEXT_SCRATCH: AtomicUsize = AtomicUsize::new(1);
+
+/// Keeps the snapshots of the `SnapshotInfo`s given it out of their folders;
+/// see the module documentation.
+#[pyclass(frozen, module = "pysnaptest._pysnaptest")]
+#[derive(Debug, Clone)]
+pub struct ScratchStorage {
+    store: Arc<Store>,
+}
+
+impl ScratchStorage {
+    fn files(&self) -> MutexGuard<'_, BTreeMap<PathBuf, Vec<u8>>> {
+        self.store.files.lock().unwrap_or_else(|x| x.into_inner())
+    }
+
+    /// Identifies the storage, for comparing the infos using it.
+    pub(crate) fn id(&self) -> usize {
+        Arc::as_ptr(&self.store) as usize
+    }
+
+    /// Where the snapshots of `folder` are worked on: the scratch folder
+    /// with `folder`'s path below it.
+    pub(crate) fn scratch_folder(&self, folder: &Path) -> PathBuf {
+        let relative: PathBuf = folder
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(part) => Some(part),
+                _ => None,
+            })
+            .collect();
+        self.store.scratch.join(relative)
+    }
+
+    /// Writes the stored files of the scratch `folder` into it.
+    pub(crate) fn materialize(&self, folder: &Path) -> io::Result<()> {
+        let files = self.files();
+        let mut stored = files
+            .iter()
+            .filter(|(path, _)| path.parent() == Some(folder))
+            .peekable();
+        if stored.peek().is_some() {
+            fs::create_dir_all(folder)?;
+        }
+        for (path, contents) in stored {
+            atomic::write(path, contents)?;
+        }
+        Ok(())
+    }
+
+    /// Takes the files of the scratch `folder` into the storage, removing
+    /// them, and forgets the stored ones no longer there. Nothing is stored
+    /// or removed unless every file could be read.
+    fn absorb(&self, folder: &Path) -> io::Result<()> {
+        let mut found = Vec::new();
+        match fs::read_dir(folder) {
+            Ok(entries) => {
+                for entry in entries {
+                    let entry = entry?;
+                    if entry.file_type()?.is_file() {
+                        let path = entry.path();
+                        let contents = fs::read(&path)?;
+                        found.push((path, contents));
+                    }
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        let mut files = self.files();
+        files.retain(|path, _| path.parent() != Some(folder));
+        for (path, contents) in found {
+            files.insert(path.clone(), contents);
+            fs::remove_file(&path)?;
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl ScratchStorage {
+    #[new]
+    fn new() -> Self {
+        let scratch = std::env::temp_dir().join(format!(
+            "pysnaptest-scratch-{}-{}",
+            std::process::id(),
+            NEXT_SCRATCH.fetch_add(1, Ordering::Relaxed)
+        ));
+        let store = Arc::new(Store {
+            scratch,
+            files: Mutex::new(BTreeMap::new()),
+        });
+        let mut stores = STORES.lock().unwrap_or_else(|x| x.into_inner());
+        stores.retain(|store| store.strong_count() > 0);
+        stores.push(Arc::downgrade(&store));
+        Self { store }
+    }
+
+    /// The stored files, by the path their `SnapshotInfo` gives them (under
+    /// its `snapshot_folder()`), with their contents.
+    #[pyo3(name = "files")]
+    fn py_files<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for (path, contents) in self.files().iter() {
+            dict.set_item(path, PyBytes::new(py, contents))?;
+        }
+        Ok(dict)
+    }
+
+    /// The contents of the stored file at `path`, or `None`.
+    fn read<'py>(&self, py: Python<'py>, path: PathBuf) -> Option<Bound<'py, PyBytes>> {
+        self.files()
+            .get(&path)
+            .map(|contents| PyBytes::new(py, contents))
+    }
+
+    /// Forgets every stored file.
+    fn clear(&self) {
+        self.files().clear();
+    }
+
+    fn __len__(&self) -> usize {
+        self.files().len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ScratchStorage(files={})", self.files().len())
+    }
+}
+
+/// The storage whose scratch folder holds `path`, if any.
+fn storage_of(path: &Path) -> Option<ScratchStorage> {
+    STORES
+        .lock()
+        .unwrap_or_else(|x| x.into_inner())
+        .iter()
+        .filter_map(Weak::upgrade)
+        .find(|store| path.starts_with(&store.scratch))
+        .map(|store| ScratchStorage { store })
+}
+
+/// Takes what an assertion of the `snapshot` left in its scratch folder
+/// back into its storage; does nothing for a snapshot stored on disk.
+pub(crate) fn absorb_after_assertion(snapshot: &Path) -> io::Result<()> {
+    match (storage_of(snapshot), snapshot.parent()) {
+        (Some(storage), Some(folder)) => storage.absorb(folder),
+        _ => Ok(()),
+    }
+}
+
+/// Whether the snapshot file at `path` is in the scratch folder of a storage.
+pub(crate) fn in_scratch(path: &Path) -> bool {
+    storage_of(path).is_some()
+}
+
+/// Whether the snapshot file at `path` is stored, in a storage or on disk.
+pub(crate) fn exists(path: &Path) -> bool {
+    match storage_of(path) {
+        Some(storage) => storage.files().contains_key(path),
+        None => path.exists(),
+    }
+}
+
+thread_local! {
+    /// The stored files this thread has put in their scratch folder for
+    /// [`with_file`], so a nested call leaves them there.
+    static OUT: RefCell<HashSet<PathBuf>> = RefCell::new(HashSet::new());
+}
+
+/// Runs `f`, which reads or rewrites the snapshot file at `path`, with the
+/// file in its scratch folder when it is kept in a storage. Reentrant.
+pub(crate) fn with_file<T>(path: &Path, f: impl FnOnce() -> T) -> T {
+    let Some(storage) = storage_of(path) else {
+        return f();
+    };
+    if !OUT.with(|out| out.borrow_mut().insert(path.to_path_buf())) {
+        return f();
+    }
+    let contents = storage.files().get(path).cloned();
+    if let (Some(contents), Some(folder)) = (contents, path.parent()) {
+        let _ = fs::create_dir_all(folder).and_then(|()| atomic::write(path, contents));
+    }
+    let result = f();
+    OUT.with(|out| out.borrow_mut().remove(path));
+    // A file that cannot be read back keeps its stored contents.
+    if let Ok(contents) = fs::read(path) {
+        storage.files().insert(path.to_path_buf(), contents);
+        let _ = fs::remove_file(path);
+    }
+    result
+}
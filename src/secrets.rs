@@ -0,0 +1,152 @@
+//! Opt-in guard that refuses to write snapshots containing credentials.
+//!
+//! Recorded mock responses and JSON payloads are committed to git verbatim, so
+//! an access key or bearer token that slips past the redactions ends up in the
+//! repository history. When `PYSNAPTEST_SECRET_SCAN` is set (the pytest plugin
+//! sets it for `--snapshot-secret-scan`), every text or JSON snapshot is
+//! scanned *after* redactions are applied and before insta sees it; a match
+//! fails the assertion instead of writing the file.
+//!
+//! A finding that is known to be harmless (a documented example key, say) is
+//! allowed by listing its rule name in `PYSNAPTEST_ALLOW_SECRETS`
+//! (comma-separated, e.g. `aws_access_key_id,bearer_token`). Binary snapshots
+//! are never scanned.
+
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+use pyo3::exceptions::PyAssertionError;
+use pyo3::prelude::*;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::redactions::{apply_redactions, json_to_content};
+use crate::RedactionType;
+
+/// Environment variable that turns the scan on (`1`, `true` or `yes`).
+pub const SECRET_SCAN_ENV: &str = "PYSNAPTEST_SECRET_SCAN";
+
+/// Environment variable listing rule names whose findings are allowed.
+pub const ALLOW_SECRETS_ENV: &str = "PYSNAPTEST_ALLOW_SECRETS";
+
+/// Named credential patterns. The names are what users put in
+/// `PYSNAPTEST_ALLOW_SECRETS`, so they are part of the public surface.
+static RULES: Lazy<Vec<(&'static str, Regex)>> = Lazy::new(|| {
+    [
+        ("aws_access_key_id", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b"),
+        (
+            "aws_secret_access_key",
+            r#"(?i)aws_?secret_?access_?key["']?\s*[:=]\s*["']?[A-Za-z0-9/+=]{40}\b"#,
+        ),
+        ("bearer_token", r"(?i)\bbearer\s+[A-Za-z0-9\-._~+/]{20,}=*"),
+        ("private_key", r"-----BEGIN (?:[A-Z]+ )?PRIVATE KEY-----"),
+        ("github_token", r"\bgh[pousr]_[A-Za-z0-9]{36,}\b"),
+        ("slack_token", r"\bxox[abprs]-[A-Za-z0-9-]{10,}\b"),
+    ]
+    .into_iter()
+    .map(|(name, pattern)| {
+        let re = Regex::new(pattern).expect("the credential rules are valid regexes");
+        (name, re)
+    })
+    .collect()
+});
+
+/// Returns the names of the credential rules that match `text`, in rule order.
+#[pyfunction]
+pub fn scan_for_secrets(text: &str) -> Vec<&'static str> {
+    RULES
+        .iter()
+        .filter(|(_, re)| re.is_match(text))
+        .map(|(name, _)| *name)
+        .collect()
+}
+
+fn scan_enabled() -> bool {
+    std::env::var(SECRET_SCAN_ENV)
+        .is_ok_and(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+}
+
+fn allowed_rules() -> Vec<String> {
+    std::env::var(ALLOW_SECRETS_ENV)
+        .map(|v| {
+            v.split(',')
+                .map(|rule| rule.trim().to_string())
+                .filter(|rule| !rule.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Fails with an `AssertionError` when any of `texts` matches a credential rule
+/// that has not been allowed. A no-op unless the scan is enabled.
+pub(crate) fn guard_text<'a>(
+    snapshot_name: &str,
+    texts: impl IntoIterator<Item = &'a str>,
+) -> PyResult<()> {
+    if !scan_enabled() {
+        return Ok(());
+    }
+    let allowed = allowed_rules();
+    let mut found: Vec<&'static str> = Vec::new();
+    for text in texts {
+        for rule in scan_for_secrets(text) {
+            if !found.contains(&rule) && !allowed.iter().any(|a| a == rule) {
+                found.push(rule);
+            }
+        }
+    }
+    if found.is_empty() {
+        return Ok(());
+    }
+    Err(PyAssertionError::new_err(format!(
+        "snapshot '{snapshot_name}' was not written because it appears to contain a secret \
+         ({}). Redact the value, or list the rule in {ALLOW_SECRETS_ENV} if it is harmless.",
+        found.join(", ")
+    )))
+}
+
+/// Scans a serializable value as insta would write it: the value is redacted
+/// with `redactions` first, then rendered as JSON text and checked, so rules
+/// that span a key and its value match too.
+pub(crate) fn guard_json<T: Serialize>(
+    snapshot_name: &str,
+    value: &T,
+    redactions: Option<&HashMap<String, RedactionType>>,
+) -> PyResult<()> {
+    if !scan_enabled() {
+        return Ok(());
+    }
+    let unserializable = |e: serde_json::Error| {
+        PyAssertionError::new_err(format!(
+            "snapshot '{snapshot_name}' could not be serialized for the secret scan: {e}"
+        ))
+    };
+    let json = serde_json::to_value(value).map_err(unserializable)?;
+    let mut content = json_to_content(&json);
+    if let Some(redactions) = redactions {
+        content = apply_redactions(content, redactions)?;
+    }
+    let rendered = serde_json::to_string_pretty(&content).map_err(unserializable)?;
+    guard_text(snapshot_name, [rendered.as_str()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_rule_compiles() {
+        let names: Vec<&str> = RULES.iter().map(|(name, _)| *name).collect();
+        assert_eq!(
+            names,
+            [
+                "aws_access_key_id",
+                "aws_secret_access_key",
+                "bearer_token",
+                "private_key",
+                "github_token",
+                "slack_token",
+            ]
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! Converting Python values to JSON on the Rust side.
+//!
+//! `pythonize::depythonize` follows references blindly, so a
+//! self-referential structure (an ORM object's parent holding its children,
+//! say) recurses until the stack overflows and takes the interpreter down.
+//! [`depythonize`] first walks the dicts, lists and tuples of the value and
+//! raises `SerializationError` naming the path of the first reference back
+//! to one of its ancestors. Shared, non-circular references pass. Sets are
+//! not walked: their items are hashable, so no cycle runs through them.
+//!
+//! The same walk enforces the process's [`SerializationLimits`]: a value
+//! nested deeper than `max_depth` containers is refused at the path where it
+//! goes too deep, and one whose compact JSON exceeds `max_size` bytes is
+//! refused with its size and the path of the part making up most of it, so
+//! an accidental snapshot of a huge object graph fails fast in CI.
+
+use std::io;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyTuple};
+use serde_json::Value;
+
+pyo3::create_exception!(
+    _pysnaptest,
+    SerializationError,
+    PyValueError,
+    "A value cannot be serialized to JSON, e.g. as it refers to itself."
+);
+
+/// How deeply nested a value may be unless `set_serialization_limits` says
+/// otherwise.
+pub(crate) const DEFAULT_MAX_DEPTH: usize = 256;
+
+/// How large (100 MiB) a value's compact JSON may be unless
+/// `set_serialization_limits` says otherwise.
+pub(crate) const DEFAULT_MAX_SIZE: usize = 100 * 1024 * 1024;
+
+/// The limits values are converted within; `None` lifts a limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SerializationLimits {
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) max_size: Option<usize>,
+}
+
+impl Default for SerializationLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: Some(DEFAULT_MAX_DEPTH),
+            max_size: Some(DEFAULT_MAX_SIZE),
+        }
+    }
+}
+
+/// What the walk of a value found wrong with it.
+enum Problem {
+    Cycle,
+    TooDeep,
+}
+
+/// Converts `value` to JSON within the process's limits, raising
+/// `SerializationError` on a circular reference or a value too deep or too
+/// large instead of overflowing the stack or the snapshot.
+pub(crate) fn depythonize(value: &Bound<'_, PyAny>) -> PyResult<Value> {
+    let limits = crate::settings::serialization_limits();
+    let max_depth = limits.max_depth.unwrap_or(usize::MAX);
+    if let Some((problem, mut path)) = find_problem(value, &mut Vec::new(), max_depth) {
+        path.reverse();
+        let path = path.concat();
+        return Err(SerializationError::new_err(match problem {
+            Problem::Cycle => format!("circular reference at path {path}"),
+            Problem::TooDeep => {
+                format!("value nests deeper than {max_depth} levels at path {path}")
+            }
+        }));
+    }
+    let json: Value = pythonize::depythonize(value)?;
+    if let Some(max_size) = limits.max_size {
+        check_size(&json, max_size)?;
+    }
+    Ok(json)
+}
+
+/// The problem, and its path segments innermost first, of the first
+/// reference within `value` to itself or one of its `ancestors` (by
+/// address), or of its first container nested deeper than `max_depth`.
+fn find_problem(
+    value: &Bound<'_, PyAny>,
+    ancestors: &mut Vec<usize>,
+    max_depth: usize,
+) -> Option<(Problem, Vec<String>)> {
+    let is_container = value.is_instance_of::<PyDict>()
+        || value.is_instance_of::<PyList>()
+        || value.is_instance_of::<PyTuple>();
+    if !is_container {
+        return None;
+    }
+    let id = value.as_ptr() as usize;
+    if ancestors.contains(&id) {
+        return Some((Problem::Cycle, Vec::new()));
+    }
+    if ancestors.len() >= max_depth {
+        return Some((Problem::TooDeep, Vec::new()));
+    }
+
+    ancestors.push(id);
+    let mut within = |item: &Bound<'_, PyAny>, segment: &dyn Fn() -> String| {
+        let (problem, mut path) = find_problem(item, ancestors, max_depth)?;
+        path.push(segment());
+        Some((problem, path))
+    };
+    let found = if let Ok(dict) = value.downcast::<PyDict>() {
+        dict.iter()
+            .find_map(|(key, item)| within(&item, &|| format!(".{key}")))
+    } else if let Ok(list) = value.downcast::<PyList>() {
+        list.iter()
+            .enumerate()
+            .find_map(|(index, item)| within(&item, &|| format!("[{index}]")))
+    } else {
+        value.downcast::<PyTuple>().ok().and_then(|tuple| {
+            tuple
+                .iter()
+                .enumerate()
+                .find_map(|(index, item)| within(&item, &|| format!("[{index}]")))
+        })
+    };
+    ancestors.pop();
+    found
+}
+
+/// Counts the bytes written to it, failing once there are more than
+/// `limit`.
+struct ByteCounter {
+    written: usize,
+    limit: usize,
+}
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.written += buf.len();
+        if self.written > self.limit {
+            return Err(io::Error::other("over the limit"));
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The size of `value`'s compact JSON, counting no further than just past
+/// `limit`.
+fn json_size(value: &Value, limit: usize) -> usize {
+    let mut counter = ByteCounter { written: 0, limit };
+    let _ = serde_json::to_writer(&mut counter, value);
+    counter.written
+}
+
+/// Raises `SerializationError` when `value`'s compact JSON is larger than
+/// `max_size` bytes, with its size and the path of its largest part.
+fn check_size(value: &Value, max_size: usize) -> PyResult<()> {
+    if json_size(value, max_size) <= max_size {
+        return Ok(());
+    }
+    let size = json_size(value, usize::MAX);
+    let (path, part) = largest_part(value, size);
+    let largest = match path.is_empty() {
+        true => String::new(),
+        false => format!("; most of it is {path} ({part} bytes)"),
+    };
+    Err(SerializationError::new_err(format!(
+        "value serializes to {size} bytes of JSON, over the limit of {max_size} bytes{largest}"
+    )))
+}
+
+/// The path and size of the part of `value` (of `size` bytes) making up
+/// most of it: the largest child for as long as it is over half its parent.
+fn largest_part(value: &Value, size: usize) -> (String, usize) {
+    let (mut path, mut current, mut size) = (String::new(), value, size);
+    loop {
+        let children: Vec<(String, &Value)> = match current {
+            Value::Object(map) => map
+                .iter()
+                .map(|(key, child)| (format!(".{key}"), child))
+                .collect(),
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(index, child)| (format!("[{index}]"), child))
+                .collect(),
+            _ => Vec::new(),
+        };
+        let largest = children
+            .into_iter()
+            .map(|(segment, child)| (json_size(child, usize::MAX), segment, child))
+            .max_by_key(|(child_size, _, _)| *child_size);
+        match largest {
+            Some((child_size, segment, child)) if child_size * 2 > size => {
+                path.push_str(&segment);
+                current = child;
+                size = child_size;
+            }
+            _ => return (path, size),
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! Process-wide settings applied to every assertion.
+//!
+//! Settings are written from `conftest.py` or the pytest plugin and read by
+//! assertions that may run on any Python thread (with the GIL released), so
+//! they live behind a `RwLock`: assertions take a copy under a read lock.
+//!
+//! `round_floats` rounds every float of the JSON snapshots to a number of
+//! decimal places unless an assertion asks for its own.
+//!
+//! `json_format` and `max_line_width` lay out JSON snapshots unless an
+//! assertion or its `SnapshotSettings` ask for their own; see
+//! [`crate::json_format`].
+//!
+//! `serialization_limits` bound how deep and large a value converted to
+//! JSON may be; see [`crate::serialization`].
+//!
+//! With `structural_json`, JSON assertions compare against committed
+//! snapshots irrespective of key order; see [`crate::structural`].
+//!
+//! `diff` lays out the diff a failing text assertion raises; see
+//! [`crate::diff`]. With `soft_assertions`, a mismatch is collected instead
+//! of raised; see [`crate::panic`].
+//!
+//! With `read_only`, an assertion or mocked call that would create or
+//! rewrite a snapshot file raises `SnapshotReadOnlyError` instead, and
+//! writes nothing; see [`crate::panic`].
+//!
+//! Default redactions come in two layers: those of the project's
+//! `[tool.pysnaptest.redactions]` table, installed by the pytest plugin, and
+//! those set with `set_default_redactions`, which win for the same selector.
+
+use std::collections::HashMap;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use once_cell::sync::Lazy;
+use pyo3::prelude::*;
+
+use crate::diff::DiffOptions;
+use crate::json_format::{self, JsonFormat, JsonStyle};
+use crate::redactions::extract_redactions;
+use crate::serialization::{SerializationLimits, DEFAULT_MAX_DEPTH, DEFAULT_MAX_SIZE};
+use crate::RedactionType;
+
+#[derive(Debug, Default)]
+struct Settings {
+    config_redactions: HashMap<String, RedactionType>,
+    default_redactions: HashMap<String, RedactionType>,
+    round_floats: Option<u32>,
+    json_format: Option<JsonFormat>,
+    max_line_width: Option<usize>,
+    serialization_limits: SerializationLimits,
+    structural_json: bool,
+    diff: DiffOptions,
+    soft_assertions: bool,
+    read_only: bool,
+}
+
+static SETTINGS: Lazy<RwLock<Settings>> = Lazy::new(|| RwLock::new(Settings::default()));
+
+fn read<'a>() -> RwLockReadGuard<'a, Settings> {
+    SETTINGS.read().unwrap_or_else(|x| x.into_inner())
+}
+
+fn write<'a>() -> RwLockWriteGuard<'a, Settings> {
+    SETTINGS.write().unwrap_or_else(|x| x.into_inner())
+}
+
+/// The redactions every assertion applies under its own: the configured
+/// ones, overridden per selector by those set with `set_default_redactions`.
+pub(crate) fn default_redactions() -> HashMap<String, RedactionType> {
+    let settings = read();
+    let mut redactions = settings.config_redactions.clone();
+    redactions.extend(settings.default_redactions.clone());
+    redactions
+}
+
+/// Sets the redactions applied by every JSON and CSV assertion and mocked
+/// call in the process, replacing those set before; `None` clears them.
+///
+/// They accept the same flat or nested mappings as an assertion's
+/// `redactions`, which are merged over them and win for the same selector.
+#[pyfunction]
+#[pyo3(signature = (redactions))]
+pub fn set_default_redactions(
+    #[pyo3(from_py_with = extract_redactions)] redactions: Option<HashMap<String, RedactionType>>,
+) {
+    write().default_redactions = redactions.unwrap_or_default();
+}
+
+/// Installs the redactions of the project's `[tool.pysnaptest.redactions]`
+/// table; called by the pytest plugin when a session starts.
+#[pyfunction]
+#[pyo3(signature = (redactions))]
+pub fn set_config_redactions(
+    #[pyo3(from_py_with = extract_redactions)] redactions: Option<HashMap<String, RedactionType>>,
+) {
+    write().config_redactions = redactions.unwrap_or_default();
+}
+
+/// The decimal places floats are rounded to: `decimals` when an assertion
+/// asks for them, or else those set with `set_round_floats`.
+pub(crate) fn round_floats(decimals: Option<u32>) -> Option<u32> {
+    decimals.or(read().round_floats)
+}
+
+/// Rounds every float of the JSON snapshots asserted in the process to
+/// `decimals` places, as a rounded redaction on each of them would; `None`
+/// stops rounding. An assertion's own `round_floats` wins.
+#[pyfunction]
+#[pyo3(signature = (decimals))]
+pub fn set_round_floats(decimals: Option<u32>) {
+    write().round_floats = decimals;
+}
+
+/// The layout of JSON snapshots: `format` and `max_line_width` when an
+/// assertion or its settings set them, or else those set with
+/// `set_json_format`.
+pub(crate) fn json_style(format: Option<JsonFormat>, max_line_width: Option<usize>) -> JsonStyle {
+    let settings = read();
+    JsonStyle {
+        format: format
+            .or(settings.json_format)
+            .unwrap_or(JsonFormat::Pretty),
+        max_line_width: max_line_width.or(settings.max_line_width),
+    }
+}
+
+/// Lays out the JSON snapshots asserted in the process as `format`:
+/// `"pretty"` (insta's 2-space JSON, the default), `"compact"` (one line
+/// while it fits in `max_line_width`, 120 by default) or `"yaml"` (insta's
+/// YAML serialization). With `max_line_width`, pretty snapshots keep the
+/// arrays and objects that fit within it on one line. `None` restores the
+/// default; an assertion's own or its `SnapshotSettings`' win.
+#[pyfunction]
+#[pyo3(signature = (format=None, max_line_width=None))]
+pub fn set_json_format(format: Option<&str>, max_line_width: Option<usize>) -> PyResult<()> {
+    let format = json_format::parse_format(format)?;
+    let mut settings = write();
+    settings.json_format = format;
+    settings.max_line_width = max_line_width;
+    Ok(())
+}
+
+/// The limits values are converted to JSON within.
+pub(crate) fn serialization_limits() -> SerializationLimits {
+    read().serialization_limits
+}
+
+/// Sets how deeply nested (`max_depth` dicts, lists and tuples, 256 by
+/// default) and how large (`max_size` bytes of compact JSON, 100 MiB by
+/// default) a value snapshotted or recorded as JSON may be; `None` lifts a
+/// limit. A value beyond them raises `SerializationError`.
+#[pyfunction]
+#[pyo3(signature = (max_depth=Some(DEFAULT_MAX_DEPTH), max_size=Some(DEFAULT_MAX_SIZE)))]
+pub fn set_serialization_limits(max_depth: Option<usize>, max_size: Option<usize>) {
+    write().serialization_limits = SerializationLimits {
+        max_depth,
+        max_size,
+    };
+}
+
+/// Returns the `max_depth` and `max_size` set with
+/// `set_serialization_limits`.
+#[pyfunction]
+pub fn serialization_limits_info() -> HashMap<&'static str, Option<usize>> {
+    let limits = serialization_limits();
+    HashMap::from([
+        ("max_depth", limits.max_depth),
+        ("max_size", limits.max_size),
+    ])
+}
+
+/// Whether JSON assertions compare structurally; see [`crate::structural`].
+pub(crate) fn structural_json() -> bool {
+    read().structural_json
+}
+
+/// Turns on (or off) structural comparison of JSON snapshots: a committed
+/// snapshot that differs from the new value only in key order or layout,
+/// e.g. one written by another serializer, passes and is left as it is.
+#[pyfunction]
+#[pyo3(signature = (enabled=true))]
+pub fn set_structural_json_comparison(enabled: bool) {
+    write().structural_json = enabled;
+}
+
+/// How the diff of a failing text assertion is rendered.
+pub(crate) fn diff_options() -> DiffOptions {
+    read().diff.clone()
+}
+
+/// Sets how the diff carried by a failing text assertion's `AssertionError`
+/// is rendered, replacing the options set before: `context_lines` unchanged
+/// lines around each change, changed words highlighted within changed lines
+/// with `word_diff`, at most `max_lines` lines (`None` for all), and colors
+/// forced on or off with `color` (`None` detects them from `NO_COLOR`,
+/// `FORCE_COLOR` and whether stdout is a terminal). With `html`, a
+/// side-by-side HTML diff is also written next to the `.snap.new` and its
+/// path added to the error.
+#[pyfunction]
+#[pyo3(signature = (context_lines=3, word_diff=false, max_lines=None, color=None, html=false))]
+pub fn set_diff_options(
+    context_lines: usize,
+    word_diff: bool,
+    max_lines: Option<usize>,
+    color: Option<bool>,
+    html: bool,
+) {
+    write().diff = DiffOptions {
+        context_lines,
+        word_diff,
+        max_lines,
+        color,
+        html,
+    };
+}
+
+/// Whether snapshot mismatches are collected instead of raised.
+pub(crate) fn soft_assertions() -> bool {
+    read().soft_assertions
+}
+
+/// Turns failing fast on snapshot mismatches back on (the default) or off.
+/// Off, a mismatching assertion does not raise: its error is collected and
+/// the test goes on, so every assertion of the test reports, and the pytest
+/// plugin raises one error combining them when the test ends (elsewhere,
+/// call `pysnaptest.raise_soft_failures`).
+#[pyfunction]
+#[pyo3(signature = (enabled=true))]
+pub fn set_fail_fast(enabled: bool) {
+    write().soft_assertions = !enabled;
+}
+
+/// Whether creating or rewriting snapshot files is refused.
+pub(crate) fn read_only() -> bool {
+    read().read_only
+}
+
+/// Turns read-only snapshots on or off (the default). On, as on CI, every
+/// assertion that would create a snapshot or rewrite a committed one, and
+/// every mocked call that would record, raises `SnapshotReadOnlyError`
+/// naming the test, whatever `INSTA_UPDATE` says, and leaves the files as
+/// they were. Matching snapshots pass as usual.
+#[pyfunction]
+#[pyo3(signature = (enabled=true))]
+pub fn set_read_only(enabled: bool) {
+    write().read_only = enabled;
+}
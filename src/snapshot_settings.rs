@@ -0,0 +1,312 @@
+//! `SnapshotSettings`: a reusable bundle of assertion settings.
+//!
+//! The settings an assertion takes besides its value (where snapshots go,
+//! what their header says, how insta serializes, lays out and filters them)
+//! can be built once as a `SnapshotSettings` and either passed to assertions
+//! as their `settings` or entered as a context manager, like insta's
+//! `Settings::bind`. Entered settings stack per thread: nested ones win for
+//! the fields they set, add their filters and redactions to the outer ones'
+//! and turn `sort_maps` on. Those an assertion is given win over the entered
+//! ones the same way, and the assertion's own arguments over both.
+//!
+//! A fixture binds settings for the assertions of a test by yielding from
+//! within the block; `bind` runs a single call with them entered, like
+//! insta's `Settings::bind_to_scope` and `Settings::bind`.
+//!
+//! [`SnapshotSettings::resolve`] is read when a `SnapshotInfo` is built, by
+//! any of its constructors; the info then carries the settings into the
+//! insta settings it binds (see [`SnapshotSettings::apply`]) and into its
+//! redactions.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+use regex::Regex;
+
+use crate::common::parse_namespace;
+use crate::json_format::{self, JsonFormat};
+use crate::redactions::extract_redactions;
+use crate::scratch_storage::ScratchStorage;
+use crate::RedactionType;
+
+thread_local! {
+    /// The settings entered on this thread, innermost last.
+    static ENTERED: RefCell<Vec<SnapshotSettings>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Extracts `filters`, given as a mapping or a sequence of
+/// `(regex, replacement)` pairs, checking each regex compiles.
+fn extract_filters(filters: &Bound<'_, PyAny>) -> PyResult<Option<Vec<(String, String)>>> {
+    if filters.is_none() {
+        return Ok(None);
+    }
+    let pairs: Vec<(String, String)> = match filters.downcast::<PyDict>() {
+        Ok(mapping) => mapping
+            .iter()
+            .map(|(regex, replacement)| Ok((regex.extract()?, replacement.extract()?)))
+            .collect::<PyResult<_>>()?,
+        Err(_) => filters.extract()?,
+    };
+    for (regex, _) in &pairs {
+        Regex::new(regex)
+            .map_err(|e| PyValueError::new_err(format!("Invalid filter regex {regex:?}: {e}")))?;
+    }
+    Ok(Some(pairs))
+}
+
+/// Settings applied to the snapshots of the assertions given them, or of
+/// every assertion while entered as a context manager.
+///
+/// `snapshot_path` is the folder snapshots are stored in; `snapshot_suffix`
+/// is appended to snapshot names (`<name>_<suffix>`); `description` is
+/// recorded in the snapshot header; `sort_maps` sorts the keys of
+/// serialized maps; `filters` maps regexes to replacements applied to
+/// snapshot contents; `redactions` are merged under an assertion's own;
+/// `json_format` and `max_line_width` lay out JSON snapshots (see
+/// `set_json_format`); `namespace` stores snapshots in that subdirectory of
+/// their folder; `storage`, a `ScratchStorage`, keeps them out of it instead.
+#[pyclass(frozen, module = "pysnaptest._pysnaptest")]
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotSettings {
+    snapshot_path: Option<PathBuf>,
+    snapshot_suffix: Option<String>,
+    description: Option<String>,
+    sort_maps: bool,
+    filters: Vec<(String, String)>,
+    redactions: HashMap<String, RedactionType>,
+    json_format: Option<JsonFormat>,
+    max_line_width: Option<usize>,
+    namespace: Option<String>,
+    storage: Option<ScratchStorage>,
+}
+
+impl SnapshotSettings {
+    /// `self` with `inner` applied over it; see the module documentation.
+    fn merged(mut self, inner: &SnapshotSettings) -> Self {
+        self.snapshot_path = inner.snapshot_path.clone().or(self.snapshot_path);
+        self.snapshot_suffix = inner.snapshot_suffix.clone().or(self.snapshot_suffix);
+        self.description = inner.description.clone().or(self.description);
+        self.sort_maps |= inner.sort_maps;
+        self.filters.extend(inner.filters.iter().cloned());
+        self.redactions.extend(inner.redactions.clone());
+        self.namespace = inner.namespace.clone().or(self.namespace);
+        self.storage = inner.storage.clone().or(self.storage);
+        self.with_json_style(inner.json_format, inner.max_line_width)
+    }
+
+    /// `self` with the JSON layout an assertion asks for applied over it.
+    pub(crate) fn with_json_style(
+        mut self,
+        json_format: Option<JsonFormat>,
+        max_line_width: Option<usize>,
+    ) -> Self {
+        self.json_format = json_format.or(self.json_format);
+        self.max_line_width = max_line_width.or(self.max_line_width);
+        self
+    }
+
+    /// The settings entered on this thread with `given` applied over them,
+    /// or `None` when there are neither.
+    pub(crate) fn resolve(given: Option<&SnapshotSettings>) -> Option<Self> {
+        let entered = ENTERED.with(|entered| entered.borrow().clone());
+        if entered.is_empty() && given.is_none() {
+            return None;
+        }
+        Some(
+            entered
+                .iter()
+                .chain(given)
+                .fold(Self::default(), Self::merged),
+        )
+    }
+
+    pub(crate) fn snapshot_path(&self) -> Option<&PathBuf> {
+        self.snapshot_path.as_ref()
+    }
+
+    pub(crate) fn snapshot_suffix(&self) -> Option<&str> {
+        self.snapshot_suffix.as_deref()
+    }
+
+    pub(crate) fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub(crate) fn redactions(&self) -> &HashMap<String, RedactionType> {
+        &self.redactions
+    }
+
+    pub(crate) fn json_format(&self) -> Option<JsonFormat> {
+        self.json_format
+    }
+
+    pub(crate) fn max_line_width(&self) -> Option<usize> {
+        self.max_line_width
+    }
+
+    pub(crate) fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    pub(crate) fn storage(&self) -> Option<&ScratchStorage> {
+        self.storage.as_ref()
+    }
+
+    /// Applies the settings insta handles itself to `settings`.
+    pub(crate) fn apply(&self, settings: &mut insta::Settings) {
+        if self.sort_maps {
+            settings.set_sort_maps(true);
+        }
+        for (regex, replacement) in &self.filters {
+            settings.add_filter(regex, replacement.as_str());
+        }
+    }
+}
+
+#[pymethods]
+impl SnapshotSettings {
+    #[new]
+    #[pyo3(signature = (
+        *,
+        snapshot_path=None,
+        snapshot_suffix=None,
+        description=None,
+        sort_maps=false,
+        filters=None,
+        redactions=None,
+        json_format=None,
+        max_line_width=None,
+        namespace=None,
+        storage=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        snapshot_path: Option<PathBuf>,
+        snapshot_suffix: Option<String>,
+        description: Option<String>,
+        sort_maps: bool,
+        #[pyo3(from_py_with = extract_filters)] filters: Option<Vec<(String, String)>>,
+        #[pyo3(from_py_with = extract_redactions)] redactions: Option<
+            HashMap<String, RedactionType>,
+        >,
+        json_format: Option<&str>,
+        max_line_width: Option<usize>,
+        namespace: Option<&str>,
+        storage: Option<ScratchStorage>,
+    ) -> PyResult<Self> {
+        if snapshot_suffix.as_deref() == Some("") {
+            return Err(PyValueError::new_err(
+                "snapshot_suffix must not be empty; pass None for no suffix",
+            ));
+        }
+        Ok(Self {
+            snapshot_path,
+            snapshot_suffix,
+            description,
+            sort_maps,
+            filters: filters.unwrap_or_default(),
+            redactions: redactions.unwrap_or_default(),
+            json_format: json_format::parse_format(json_format)?,
+            max_line_width,
+            namespace: namespace.map(parse_namespace).transpose()?,
+            storage,
+        })
+    }
+
+    /// The folder snapshots are stored in, if set.
+    #[getter(snapshot_path)]
+    fn py_snapshot_path(&self) -> Option<PathBuf> {
+        self.snapshot_path.clone()
+    }
+
+    /// Appended to snapshot names, if set.
+    #[getter(snapshot_suffix)]
+    fn py_snapshot_suffix(&self) -> Option<String> {
+        self.snapshot_suffix.clone()
+    }
+
+    /// Recorded in the snapshot header, if set.
+    #[getter(description)]
+    fn py_description(&self) -> Option<String> {
+        self.description.clone()
+    }
+
+    /// Whether the keys of serialized maps are sorted.
+    #[getter]
+    fn sort_maps(&self) -> bool {
+        self.sort_maps
+    }
+
+    /// The `(regex, replacement)` filters, in the order they apply.
+    #[getter]
+    fn filters(&self) -> Vec<(String, String)> {
+        self.filters.clone()
+    }
+
+    /// How JSON snapshots are laid out (`"pretty"`, `"compact"` or
+    /// `"yaml"`), if set.
+    #[getter(json_format)]
+    fn py_json_format(&self) -> Option<&'static str> {
+        self.json_format.map(JsonFormat::as_str)
+    }
+
+    /// The line width JSON snapshots are laid out within, if set.
+    #[getter(max_line_width)]
+    fn py_max_line_width(&self) -> Option<usize> {
+        self.max_line_width
+    }
+
+    /// The subdirectory of their folder snapshots are stored in, if set.
+    #[getter(namespace)]
+    fn py_namespace(&self) -> Option<String> {
+        self.namespace.clone()
+    }
+
+    /// The `ScratchStorage` snapshots are kept in, if set.
+    #[getter(storage)]
+    fn py_storage(&self) -> Option<ScratchStorage> {
+        self.storage.clone()
+    }
+
+    /// The settings entered on this thread (with `settings` applied over
+    /// them, if given) as a single `SnapshotSettings`.
+    #[staticmethod]
+    #[pyo3(signature = (settings=None))]
+    fn current(settings: Option<SnapshotSettings>) -> Self {
+        Self::resolve(settings.as_ref()).unwrap_or_default()
+    }
+
+    /// Calls `func(*args, **kwargs)` with these settings entered and returns
+    /// its result.
+    #[pyo3(signature = (func, *args, **kwargs))]
+    fn bind(
+        &self,
+        func: &Bound<'_, PyAny>,
+        args: &Bound<'_, PyTuple>,
+        kwargs: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<PyObject> {
+        ENTERED.with(|entered| entered.borrow_mut().push(self.clone()));
+        let result = func.call(args, kwargs);
+        ENTERED.with(|entered| entered.borrow_mut().pop());
+        result.map(Bound::unbind)
+    }
+
+    /// Applies these settings to every assertion on this thread until the
+    /// block exits.
+    fn __enter__(slf: Bound<'_, Self>) -> Bound<'_, Self> {
+        ENTERED.with(|entered| entered.borrow_mut().push(slf.get().clone()));
+        slf
+    }
+
+    #[pyo3(signature = (*_exc_info))]
+    fn __exit__(&self, _exc_info: &Bound<'_, PyAny>) -> PyResult<bool> {
+        ENTERED
+            .with(|entered| entered.borrow_mut().pop())
+            .map(|_| false)
+            .ok_or_else(|| PyRuntimeError::new_err("SnapshotSettings exited without being entered"))
+    }
+}
@@ -0,0 +1,161 @@
+//! Deterministic SQL formatting for `assert_sql_snapshot`.
+//!
+//! ORMs render the same query with different whitespace, keyword case and
+//! bind-parameter style depending on the driver, so a snapshot of the raw
+//! string churns for no real reason. Every placeholder style
+//! (`%s`, `%(name)s`, `?`, `?1`, `$1`, `:name`, `:1`, and `@name` for SQL
+//! Server) is rewritten to `?` outside of string literals and quoted
+//! identifiers; the query is then laid out by `sqlformat` with upper-case
+//! keywords.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::PyResult;
+use sqlformat::{FormatOptions, Indent, QueryParams};
+
+/// The SQL dialects `assert_sql_snapshot` knows the quoting and placeholder
+/// rules of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Dialect {
+    Generic,
+    Postgresql,
+    Mysql,
+    Sqlite,
+    Mssql,
+    Oracle,
+}
+
+impl Dialect {
+    pub(crate) fn parse(dialect: Option<&str>) -> PyResult<Self> {
+        let Some(dialect) = dialect else {
+            return Ok(Dialect::Generic);
+        };
+        match dialect.to_ascii_lowercase().as_str() {
+            "postgresql" | "postgres" => Ok(Dialect::Postgresql),
+            "mysql" | "mariadb" => Ok(Dialect::Mysql),
+            "sqlite" => Ok(Dialect::Sqlite),
+            "mssql" | "sqlserver" => Ok(Dialect::Mssql),
+            "oracle" => Ok(Dialect::Oracle),
+            other => Err(PyValueError::new_err(format!(
+                "Unsupported SQL dialect {other:?}; expected \"postgresql\", \"mysql\", \
+                 \"sqlite\", \"mssql\" or \"oracle\""
+            ))),
+        }
+    }
+
+    /// Whether a backslash escapes the next character inside a literal.
+    fn backslash_escapes(self) -> bool {
+        self == Dialect::Mysql
+    }
+
+    /// The character closing a quoted identifier or literal opened by `open`.
+    fn closing_quote(self, open: char) -> Option<char> {
+        match open {
+            '\'' | '"' => Some(open),
+            '`' if matches!(self, Dialect::Generic | Dialect::Mysql | Dialect::Sqlite) => Some('`'),
+            '[' if matches!(self, Dialect::Generic | Dialect::Mssql | Dialect::Sqlite) => Some(']'),
+            _ => None,
+        }
+    }
+}
+
+/// Formats `sql` with its bind-parameter placeholders normalized to `?`.
+pub(crate) fn format(sql: &str, dialect: Dialect) -> String {
+    let options = FormatOptions {
+        indent: Indent::Spaces(2),
+        uppercase: true,
+        lines_between_queries: 1,
+    };
+    sqlformat::format(
+        &normalize_placeholders(sql, dialect),
+        &QueryParams::None,
+        options,
+    )
+}
+
+fn is_identifier(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn normalize_placeholders(sql: &str, dialect: Dialect) -> String {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut normalized = String::with_capacity(sql.len());
+    let mut i = 0;
+    // Skips the identifier characters from `from` on.
+    let identifier_end = |from: usize| {
+        (from..chars.len())
+            .find(|&j| !is_identifier(chars[j]))
+            .unwrap_or(chars.len())
+    };
+    while i < chars.len() {
+        let c = chars[i];
+        let previous = i.checked_sub(1).map(|p| chars[p]);
+        let next = chars.get(i + 1).copied();
+        if let Some(close) = dialect.closing_quote(c) {
+            // Copy the literal or quoted identifier through to its end; a
+            // doubled closing quote is an escaped one.
+            let mut end = i + 1;
+            while end < chars.len() {
+                if dialect.backslash_escapes() && chars[end] == '\\' && close != ']' {
+                    end += 2;
+                    continue;
+                }
+                if chars[end] == close {
+                    if chars.get(end + 1) == Some(&close) && close != ']' {
+                        end += 2;
+                        continue;
+                    }
+                    break;
+                }
+                end += 1;
+            }
+            let end = (end + 1).min(chars.len());
+            normalized.extend(&chars[i..end]);
+            i = end;
+            continue;
+        }
+        if c == '-' && next == Some('-') {
+            let end = (i..chars.len())
+                .find(|&j| chars[j] == '\n')
+                .unwrap_or(chars.len());
+            normalized.extend(&chars[i..end]);
+            i = end;
+            continue;
+        }
+        let placeholder_end = match (c, next) {
+            // pyformat: `%s` and `%(name)s`.
+            ('%', Some('s')) => Some(i + 2),
+            ('%', Some('(')) => (i + 2..chars.len())
+                .find(|&j| chars[j] == ')')
+                .filter(|&j| chars.get(j + 1) == Some(&'s'))
+                .map(|j| j + 2),
+            // qmark and numbered: `?`, `?1` and `$1`.
+            ('?', _) => Some(identifier_end(i + 1)),
+            ('$', Some(d)) if d.is_ascii_digit() && !previous.is_some_and(is_identifier) => {
+                Some(identifier_end(i + 1))
+            }
+            // named and numeric: `:name` and `:1`, but not a `::` cast.
+            (':', Some(n))
+                if is_identifier(n)
+                    && previous != Some(':')
+                    && !previous.is_some_and(is_identifier) =>
+            {
+                Some(identifier_end(i + 1))
+            }
+            ('@', Some(n)) if dialect == Dialect::Mssql && is_identifier(n) => {
+                Some(identifier_end(i + 1))
+            }
+            _ => None,
+        };
+        match placeholder_end {
+            Some(end) => {
+                normalized.push('?');
+                i = end;
+            }
+            None => {
+                normalized.push(c);
+                i += 1;
+            }
+        }
+    }
+    normalized
+}
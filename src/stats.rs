@@ -0,0 +1,169 @@
+//! Aggregate statistics for `assert_stats_snapshot`.
+//!
+//! Feature pipelines produce values that shift in the last few bits between
+//! library versions or BLAS builds, so pinning them raw makes every snapshot
+//! brittle. A stats snapshot pins a handful of aggregates instead, each rounded
+//! to a fixed number of decimals. Sums use compensated (Neumaier) summation and
+//! the variance is computed in two passes, so the figures do not drift with
+//! how the input happens to be chunked.
+//!
+//! Missing values (`None`, `NaN`) are left out of every statistic and counted
+//! by `nulls`.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+/// Statistics computed when the caller does not choose.
+pub(crate) const DEFAULT_STATS: [&str; 5] = ["count", "mean", "std", "min", "max"];
+
+const SUPPORTED_STATS: [&str; 9] = [
+    "count", "nulls", "sum", "mean", "std", "var", "min", "max", "median",
+];
+
+/// What `assert_stats_snapshot` accepts: one sequence of numbers, or a
+/// mapping of column name to numbers (a DataFrame, after the Python wrapper
+/// has picked out its numeric columns).
+#[derive(FromPyObject)]
+pub enum StatsInput<'py> {
+    Values(Vec<Option<f64>>),
+    Columns(Bound<'py, PyDict>),
+}
+
+/// Named values serialized as a map in insertion order.
+pub(crate) struct Ordered<T>(Vec<(String, T)>);
+
+impl<T: Serialize> Serialize for Ordered<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (key, value) in &self.0 {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+/// The snapshotted record: statistics of the values, or per column.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub(crate) enum StatsRecord {
+    Values(Ordered<Value>),
+    Columns(Ordered<Ordered<Value>>),
+}
+
+/// Compensated sum, so the result does not depend on accumulated rounding.
+fn neumaier_sum(values: &[f64]) -> f64 {
+    let mut sum = 0.0;
+    let mut compensation = 0.0;
+    for &value in values {
+        let total = sum + value;
+        compensation += if sum.abs() >= value.abs() {
+            (sum - total) + value
+        } else {
+            (value - total) + sum
+        };
+        sum = total;
+    }
+    sum + compensation
+}
+
+fn round(value: f64, decimals: u32) -> Value {
+    let scale = 10f64.powi(decimals as i32);
+    let rounded = (value * scale).round() / scale;
+    // `-0.0` and `0.0` must snapshot identically.
+    serde_json::Number::from_f64(rounded + 0.0)
+        .map(Value::Number)
+        .unwrap_or(Value::Null)
+}
+
+fn compute(values: &[Option<f64>], stats: &[String], decimals: u32) -> Ordered<Value> {
+    let mut present: Vec<f64> = values
+        .iter()
+        .flatten()
+        .copied()
+        .filter(|v| !v.is_nan())
+        .collect();
+    let n = present.len();
+    let sum = neumaier_sum(&present);
+    let mean = (n > 0).then(|| sum / n as f64);
+    // Sample variance (ddof=1), matching pandas and polars.
+    let var = mean.filter(|_| n > 1).map(|mean| {
+        let squares: Vec<f64> = present.iter().map(|v| (v - mean).powi(2)).collect();
+        neumaier_sum(&squares) / (n - 1) as f64
+    });
+    present.sort_by(f64::total_cmp);
+    let median = (n > 0).then(|| {
+        if n % 2 == 1 {
+            present[n / 2]
+        } else {
+            (present[n / 2 - 1] + present[n / 2]) / 2.0
+        }
+    });
+    let float = |value: Option<f64>| value.map_or(Value::Null, |v| round(v, decimals));
+    Ordered(
+        stats
+            .iter()
+            .map(|stat| {
+                let value = match stat.as_str() {
+                    "count" => n.into(),
+                    "nulls" => (values.len() - n).into(),
+                    "sum" => round(sum, decimals),
+                    "mean" => float(mean),
+                    "std" => float(var.map(f64::sqrt)),
+                    "var" => float(var),
+                    "min" => float(present.first().copied()),
+                    "max" => float(present.last().copied()),
+                    "median" => float(median),
+                    _ => unreachable!("stats are validated before computing"),
+                };
+                (stat.clone(), value)
+            })
+            .collect(),
+    )
+}
+
+/// Computes the requested `stats` of `input`, rounded to `decimals`.
+pub(crate) fn stats_record(
+    py: Python<'_>,
+    input: &StatsInput<'_>,
+    stats: &[String],
+    decimals: u32,
+) -> PyResult<StatsRecord> {
+    if let Some(unknown) = stats
+        .iter()
+        .find(|stat| !SUPPORTED_STATS.contains(&stat.as_str()))
+    {
+        return Err(PyValueError::new_err(format!(
+            "Unsupported statistic {unknown:?}; expected one of: {}",
+            SUPPORTED_STATS.join(", ")
+        )));
+    }
+    match input {
+        StatsInput::Values(values) => Ok(StatsRecord::Values(
+            py.allow_threads(|| compute(values, stats, decimals)),
+        )),
+        StatsInput::Columns(columns) => {
+            let columns = columns
+                .iter()
+                .map(|(name, values)| {
+                    let name: String = name.str()?.extract()?;
+                    let values: Vec<Option<f64>> = values.extract().map_err(|_| {
+                        PyValueError::new_err(format!("Column {name:?} is not numeric"))
+                    })?;
+                    Ok((name, values))
+                })
+                .collect::<PyResult<Vec<_>>>()?;
+            Ok(StatsRecord::Columns(py.allow_threads(|| {
+                Ordered(
+                    columns
+                        .iter()
+                        .map(|(name, values)| (name.clone(), compute(values, stats, decimals)))
+                        .collect(),
+                )
+            })))
+        }
+    }
+}
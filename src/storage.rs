@@ -0,0 +1,246 @@
+//! Pluggable external storage for large binary snapshots.
+//!
+//! Recorded fixtures for data pipelines (parquet files, model outputs) quickly
+//! outgrow a git repository. When a storage backend is configured, a binary
+//! snapshot whose stored bytes exceed the size threshold is written to the
+//! backend under its SHA-256 digest, and the committed sidecar is replaced by
+//! a one-line pointer (extension gains a `.ptr` suffix, e.g.
+//! `@pysnap.snap.parquet.ptr`).
+//!
+//! The pointer is content-addressed, so insta's byte comparison still detects
+//! any change, and `PySnapshot.contents()` resolves it through the backend
+//! transparently. Two backends are provided:
+//!
+//! * a content-addressed directory (`configure_storage(directory=...)`, or the
+//!   `PYSNAPTEST_STORAGE_DIR` environment variable), which can live on a
+//!   shared volume or be synced to a bucket out of band;
+//! * Python callbacks (`configure_storage(put=..., get=...)`) for S3 or any
+//!   other object store the caller already has a client for.
+//!
+//! Objects are never deleted by pysnaptest; removing a snapshot only removes
+//! its pointer.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use once_cell::sync::Lazy;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use sha2::{Digest, Sha256};
+
+use crate::atomic;
+
+/// Environment variable naming a content-addressed directory backend, used
+/// when `configure_storage` has not been called.
+pub const STORAGE_DIR_ENV: &str = "PYSNAPTEST_STORAGE_DIR";
+
+/// Environment variable overriding the size threshold (in bytes) for the
+/// `PYSNAPTEST_STORAGE_DIR` backend.
+pub const STORAGE_THRESHOLD_ENV: &str = "PYSNAPTEST_STORAGE_THRESHOLD";
+
+/// Extension suffix marking a binary sidecar that holds a pointer.
+pub(crate) const POINTER_EXTENSION: &str = "ptr";
+
+/// Marks a pointer body; the rest is `<sha256 hex>:<size>`.
+const POINTER_PREFIX: &str = "pysnaptest-external:v1:sha256:";
+
+/// Snapshots larger than this (1 MiB) go to the backend by default.
+const DEFAULT_THRESHOLD: u64 = 1024 * 1024;
+
+/// Where externally stored snapshot contents live, keyed by SHA-256 digest.
+pub(crate) trait StorageBackend: Send + Sync {
+    fn put(&self, py: Python<'_>, key: &str, data: &[u8]) -> PyResult<()>;
+    fn get(&self, py: Python<'_>, key: &str) -> PyResult<Vec<u8>>;
+}
+
+/// Stores each object at `<root>/<first two hex digits>/<digest>`.
+struct DirectoryBackend {
+    root: PathBuf,
+}
+
+impl DirectoryBackend {
+    fn object_path(&self, key: &str) -> PathBuf {
+        self.root.join(&key[..2]).join(key)
+    }
+}
+
+impl StorageBackend for DirectoryBackend {
+    fn put(&self, _py: Python<'_>, key: &str, data: &[u8]) -> PyResult<()> {
+        let path = self.object_path(key);
+        if path.exists() {
+            return Ok(());
+        }
+        let write = || -> std::io::Result<()> {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            // Written atomically so a concurrent reader never sees a torn object.
+            atomic::write(&path, data)
+        };
+        write().map_err(|e| {
+            PyValueError::new_err(format!("Unable to store snapshot object {path:?}: {e}"))
+        })
+    }
+
+    fn get(&self, _py: Python<'_>, key: &str) -> PyResult<Vec<u8>> {
+        let path = self.object_path(key);
+        fs::read(&path).map_err(|e| {
+            PyValueError::new_err(format!("Unable to read snapshot object {path:?}: {e}"))
+        })
+    }
+}
+
+/// Delegates to user-supplied `put(key, data)` and `get(key) -> bytes`.
+struct CallbackBackend {
+    put: PyObject,
+    get: PyObject,
+}
+
+impl StorageBackend for CallbackBackend {
+    fn put(&self, py: Python<'_>, key: &str, data: &[u8]) -> PyResult<()> {
+        self.put.call1(py, (key, PyBytes::new(py, data)))?;
+        Ok(())
+    }
+
+    fn get(&self, py: Python<'_>, key: &str) -> PyResult<Vec<u8>> {
+        self.get.call1(py, (key,))?.extract(py)
+    }
+}
+
+#[derive(Clone)]
+struct Storage {
+    backend: Arc<dyn StorageBackend>,
+    threshold: u64,
+}
+
+static CONFIGURED: Lazy<Mutex<Option<Storage>>> = Lazy::new(|| Mutex::new(None));
+
+/// Configures the external storage backend for large binary snapshots.
+///
+/// Pass either `directory` (a content-addressed directory) or both `put` and
+/// `get` callbacks. Calling it with neither resets to the environment default
+/// (`PYSNAPTEST_STORAGE_DIR`, or no external storage).
+#[pyfunction]
+#[pyo3(signature = (directory=None, put=None, get=None, threshold=DEFAULT_THRESHOLD))]
+pub fn configure_storage(
+    directory: Option<PathBuf>,
+    put: Option<PyObject>,
+    get: Option<PyObject>,
+    threshold: u64,
+) -> PyResult<()> {
+    let backend: Option<Arc<dyn StorageBackend>> = match (directory, put, get) {
+        (None, None, None) => None,
+        (Some(root), None, None) => Some(Arc::new(DirectoryBackend { root })),
+        (None, Some(put), Some(get)) => Some(Arc::new(CallbackBackend { put, get })),
+        (Some(_), _, _) => {
+            return Err(PyValueError::new_err(
+                "Pass either directory or put/get callbacks, not both",
+            ))
+        }
+        _ => {
+            return Err(PyValueError::new_err(
+                "Callback storage needs both a put and a get callback",
+            ))
+        }
+    };
+    *CONFIGURED.lock().unwrap_or_else(|x| x.into_inner()) =
+        backend.map(|backend| Storage { backend, threshold });
+    Ok(())
+}
+
+fn from_env() -> PyResult<Option<Storage>> {
+    let Some(root) = std::env::var_os(STORAGE_DIR_ENV).filter(|v| !v.is_empty()) else {
+        return Ok(None);
+    };
+    let threshold = match std::env::var(STORAGE_THRESHOLD_ENV) {
+        Ok(value) => value.trim().parse().map_err(|e| {
+            PyValueError::new_err(format!(
+                "{STORAGE_THRESHOLD_ENV} must be a size in bytes, got {value:?}: {e}"
+            ))
+        })?,
+        Err(_) => DEFAULT_THRESHOLD,
+    };
+    Ok(Some(Storage {
+        backend: Arc::new(DirectoryBackend { root: root.into() }),
+        threshold,
+    }))
+}
+
+fn active() -> PyResult<Option<Storage>> {
+    let configured = CONFIGURED.lock().unwrap_or_else(|x| x.into_inner()).clone();
+    match configured {
+        Some(storage) => Ok(Some(storage)),
+        None => from_env(),
+    }
+}
+
+/// Reports whether any backend is configured, so callers can keep their fast
+/// path when external storage is off.
+pub(crate) fn is_configured() -> PyResult<bool> {
+    Ok(active()?.is_some())
+}
+
+/// Uploads `data` and returns the pointer to commit in its place when a
+/// backend is configured and `data` exceeds its threshold; `None` otherwise.
+pub(crate) fn externalize(py: Python<'_>, data: &[u8]) -> PyResult<Option<Vec<u8>>> {
+    let Some(storage) = active()? else {
+        return Ok(None);
+    };
+    if (data.len() as u64) <= storage.threshold {
+        return Ok(None);
+    }
+    let key = hex_digest(data);
+    storage.backend.put(py, &key, data)?;
+    Ok(Some(
+        format!("{POINTER_PREFIX}{key}:{}\n", data.len()).into_bytes(),
+    ))
+}
+
+/// Fetches the object a pointer refers to and checks it against the digest.
+pub(crate) fn resolve_pointer(py: Python<'_>, pointer: &[u8]) -> PyResult<Vec<u8>> {
+    let corrupt = || PyValueError::new_err("Corrupt external snapshot pointer");
+    let body = std::str::from_utf8(pointer).map_err(|_| corrupt())?;
+    let (key, _size) = body
+        .trim()
+        .strip_prefix(POINTER_PREFIX)
+        .and_then(|rest| rest.split_once(':'))
+        .filter(|(key, _)| key.len() == 64 && key.bytes().all(|b| b.is_ascii_hexdigit()))
+        .ok_or_else(corrupt)?;
+    let storage = active()?.ok_or_else(|| {
+        PyValueError::new_err(format!(
+            "Snapshot is stored externally ({key}) but no storage backend is configured; \
+             set {STORAGE_DIR_ENV} or call configure_storage"
+        ))
+    })?;
+    let data = storage.backend.get(py, key)?;
+    if hex_digest(&data) != key {
+        return Err(PyValueError::new_err(format!(
+            "External snapshot object {key} does not match its digest"
+        )));
+    }
+    Ok(data)
+}
+
+/// Reads the committed bytes for `sidecar`, following a pointer sidecar
+/// (`<sidecar>.ptr`) through the backend when the plain file is absent.
+/// Anything unreadable yields `None`, as for a first run.
+pub(crate) fn read_committed(py: Python<'_>, sidecar: &Path) -> Option<Vec<u8>> {
+    if let Ok(bytes) = fs::read(sidecar) {
+        return Some(bytes);
+    }
+    let mut pointer_path = sidecar.as_os_str().to_owned();
+    pointer_path.push(format!(".{POINTER_EXTENSION}"));
+    let pointer = fs::read(PathBuf::from(pointer_path)).ok()?;
+    resolve_pointer(py, &pointer).ok()
+}
+
+/// Reports whether a binary sidecar path holds a pointer.
+pub(crate) fn is_pointer_sidecar(sidecar: &Path) -> bool {
+    sidecar.extension().and_then(|e| e.to_str()) == Some(POINTER_EXTENSION)
+}
+
+fn hex_digest(data: &[u8]) -> String {
+    crate::hashing::hex(&Sha256::digest(data))
+}
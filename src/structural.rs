@@ -0,0 +1,54 @@
+//! Key-order-insensitive comparison of JSON snapshots.
+//!
+//! Snapshot suites recorded by another serializer often order object keys
+//! (or lay out JSON) differently than pysnaptest does, so every one of them
+//! would fail on the first run after migrating. With structural comparison
+//! turned on (`set_structural_json_comparison`), a JSON assertion first
+//! re-parses the committed snapshot and compares it with the new value as
+//! data: objects irrespective of key order, arrays in order. A structural
+//! match passes and leaves the committed file as it is; anything else goes
+//! through insta's textual assertion as usual. While insta rewrites
+//! snapshots (`INSTA_UPDATE=always` or `force`, see [`crate::insta_env`])
+//! every assertion does, so updating re-records legacy snapshots in
+//! pysnaptest's own layout.
+
+use std::path::Path;
+
+use insta::internals::SnapshotContents;
+use insta::Snapshot;
+use serde_json::Value;
+
+use crate::insta_env::{self, Behavior};
+use crate::mocks::memoize_snapshot_reference;
+
+/// Reads the committed snapshot at `path` as JSON; `None` when there is
+/// none, or when it is not JSON.
+fn read_committed(path: &Path) -> Option<Value> {
+    let snapshot = Snapshot::from_file(path).ok()?;
+    match snapshot.contents() {
+        SnapshotContents::Text(text) => serde_json::from_str(&text.to_string()).ok(),
+        SnapshotContents::Binary(_) => None,
+    }
+}
+
+/// Whether insta rewrites a stored snapshot that does not match.
+fn updating() -> bool {
+    insta_env::in_effect().is_ok_and(|config| config.behavior(true) == Behavior::InPlace)
+}
+
+/// Whether `rendered`, the JSON about to be asserted, is structurally equal
+/// to the committed snapshot at `path`. A match is recorded as a reference
+/// to the snapshot, as insta records the snapshots it asserts.
+pub(crate) fn matches_committed(path: &Path, rendered: &str) -> bool {
+    if updating() {
+        return false;
+    }
+    let Some(committed) = read_committed(path) else {
+        return false;
+    };
+    let matched = serde_json::from_str::<Value>(rendered).is_ok_and(|new| new == committed);
+    if matched {
+        memoize_snapshot_reference(path);
+    }
+    matched
+}
@@ -0,0 +1,288 @@
+//! Streaming CSV input for `assert_csv_snapshot`.
+//!
+//! Large exports used to be parsed into a `Vec<Vec<serde_json::Value>>` before
+//! insta serialized them. [`CsvRows`] instead implements `Serialize` by parsing
+//! the CSV text afresh each time it is serialized (once for the secret scan,
+//! once by insta), handing each record to the serializer as it is read, so no
+//! parsed copy of the whole export is kept alongside insta's own.
+//!
+//! For very large exports only a representative slice needs to be pinned:
+//! `head`/`tail` (`max_rows` is an alias of `head`) keep the ends of the table,
+//! `sample` keeps an evenly spaced, deterministic subset, and `columns` keeps
+//! (and orders) a subset of columns; the header row is always kept. A
+//! [`TableSummary`] of the *whole* table (row count and per-column min/max)
+//! can be snapshotted alongside, so changes in the omitted rows still show.
+
+use csv::{ReaderBuilder, StringRecord};
+use pyo3::exceptions::PyValueError;
+use pyo3::PyResult;
+use serde::ser::{Error, SerializeSeq};
+use serde::{Serialize, Serializer};
+
+/// Which data rows (after the header) end up in the snapshot.
+#[derive(Debug, Clone, Copy)]
+enum RowSelection {
+    All,
+    /// The first `head` and last `tail` of `total` rows.
+    Ends {
+        head: usize,
+        tail: usize,
+        total: usize,
+    },
+    /// `count` rows spread evenly over `total`.
+    Sample {
+        count: usize,
+        total: usize,
+    },
+}
+
+impl RowSelection {
+    fn keeps(self, idx: usize) -> bool {
+        match self {
+            RowSelection::All => true,
+            RowSelection::Ends { head, tail, total } => idx < head || idx + tail >= total,
+            // Keeps the first row of each of `count` equal buckets: row
+            // `idx` is kept when `idx * count / total` (rounded up) steps up.
+            // Deterministic, exactly `count` rows, always including row 0.
+            RowSelection::Sample { count, total } => {
+                (idx * count).div_ceil(total) < ((idx + 1) * count).div_ceil(total)
+            }
+        }
+    }
+
+    fn is_done(self, idx: usize) -> bool {
+        matches!(self, RowSelection::Ends { head, tail: 0, .. } if idx >= head)
+    }
+}
+
+/// Row and column selection requested by the caller.
+#[derive(Debug, Default)]
+pub(crate) struct TableOptions {
+    pub(crate) max_rows: Option<usize>,
+    pub(crate) sample: Option<usize>,
+    pub(crate) head: Option<usize>,
+    pub(crate) tail: Option<usize>,
+    pub(crate) columns: Option<Vec<String>>,
+    pub(crate) summary: bool,
+}
+
+/// Row count and per-column min/max over every row of the table.
+#[derive(Debug, Serialize)]
+pub(crate) struct TableSummary {
+    rows: usize,
+    columns: Vec<ColumnSummary>,
+}
+
+/// Numbers compare numerically; a column with any non-numeric value compares
+/// as text. Empty cells are ignored.
+#[derive(Debug, Serialize)]
+struct ColumnSummary {
+    name: String,
+    min: Option<serde_json::Value>,
+    max: Option<serde_json::Value>,
+}
+
+/// Running min/max of one column, tracked both numerically and as text until
+/// the end of the table decides which applies.
+struct ColumnRange {
+    numeric: Option<(f64, String, f64, String)>,
+    text: Option<(String, String)>,
+    all_numeric: bool,
+}
+
+impl ColumnRange {
+    fn new() -> Self {
+        Self {
+            numeric: None,
+            text: None,
+            all_numeric: true,
+        }
+    }
+
+    fn observe(&mut self, field: &str) {
+        if field.is_empty() {
+            return;
+        }
+        match self.text.as_mut() {
+            Some((min, max)) => {
+                if field < min.as_str() {
+                    *min = field.to_string();
+                }
+                if field > max.as_str() {
+                    *max = field.to_string();
+                }
+            }
+            None => self.text = Some((field.to_string(), field.to_string())),
+        }
+        let Some(number) = field.parse::<f64>().ok().filter(|n| n.is_finite()) else {
+            self.all_numeric = false;
+            return;
+        };
+        match self.numeric.as_mut() {
+            Some((min, min_field, max, max_field)) => {
+                if number < *min {
+                    (*min, *min_field) = (number, field.to_string());
+                }
+                if number > *max {
+                    (*max, *max_field) = (number, field.to_string());
+                }
+            }
+            None => self.numeric = Some((number, field.to_string(), number, field.to_string())),
+        }
+    }
+
+    fn finish(self, name: String) -> ColumnSummary {
+        // Numbers keep their original spelling (`1` stays an integer).
+        let number = |field: String| {
+            serde_json::from_str::<serde_json::Number>(&field)
+                .map(serde_json::Value::Number)
+                .unwrap_or(serde_json::Value::String(field))
+        };
+        let (min, max) = match (self.all_numeric, self.numeric, self.text) {
+            (true, Some((_, min, _, max)), _) => (Some(number(min)), Some(number(max))),
+            (_, _, Some((min, max))) => (Some(min.into()), Some(max.into())),
+            _ => (None, None),
+        };
+        ColumnSummary { name, min, max }
+    }
+}
+
+/// CSV text that serializes as a sequence of rows: the header, then each
+/// selected record with numbers and booleans inferred as before.
+pub(crate) struct CsvRows<'a> {
+    data: &'a str,
+    selection: RowSelection,
+    /// Indices of the kept columns, in output order; `None` keeps all.
+    columns: Option<Vec<usize>>,
+    summary: Option<TableSummary>,
+}
+
+impl<'a> CsvRows<'a> {
+    /// Validates `data` in one streaming pass (so malformed CSV surfaces as a
+    /// `ValueError`, not a failure inside insta), resolves the selection and,
+    /// if requested, computes the summary.
+    pub(crate) fn new(data: &'a str, options: TableOptions) -> PyResult<Self> {
+        let mut rdr = ReaderBuilder::new().from_reader(data.as_bytes());
+        let headers = rdr
+            .headers()
+            .map_err(|e| PyValueError::new_err(format!("Failed to read CSV headers: {e}")))?
+            .clone();
+        let columns = options
+            .columns
+            .map(|names| {
+                names
+                    .iter()
+                    .map(|name| {
+                        headers.iter().position(|h| h == name).ok_or_else(|| {
+                            PyValueError::new_err(format!(
+                                "Unknown CSV column {name:?}; available columns: {}",
+                                headers.iter().collect::<Vec<_>>().join(", ")
+                            ))
+                        })
+                    })
+                    .collect::<PyResult<Vec<usize>>>()
+            })
+            .transpose()?;
+        let summarized: Vec<usize> = columns
+            .clone()
+            .unwrap_or_else(|| (0..headers.len()).collect());
+        let mut ranges: Vec<ColumnRange> = if options.summary {
+            summarized.iter().map(|_| ColumnRange::new()).collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut record = StringRecord::new();
+        let mut total = 0;
+        while rdr
+            .read_record(&mut record)
+            .map_err(|e| PyValueError::new_err(format!("Failed to parse CSV records: {e}")))?
+        {
+            for (range, &column) in ranges.iter_mut().zip(&summarized) {
+                range.observe(record.get(column).unwrap_or_default());
+            }
+            total += 1;
+        }
+
+        let head = match (options.head, options.max_rows) {
+            (Some(_), Some(_)) => {
+                return Err(PyValueError::new_err(
+                    "max_rows is an alias of head; pass only one",
+                ))
+            }
+            (head, max_rows) => head.or(max_rows),
+        };
+        let selection = match (head, options.tail, options.sample) {
+            (Some(_), _, Some(_)) if options.max_rows.is_some() => {
+                return Err(PyValueError::new_err(
+                    "Pass either max_rows or sample, not both",
+                ))
+            }
+            (Some(_), _, Some(_)) | (_, Some(_), Some(_)) => {
+                return Err(PyValueError::new_err(
+                    "Pass either head/tail or sample, not both",
+                ))
+            }
+            (None, None, Some(count)) if count < total => RowSelection::Sample { count, total },
+            (None, None, _) => RowSelection::All,
+            (head, tail, _) => RowSelection::Ends {
+                head: head.unwrap_or(0),
+                tail: tail.unwrap_or(0),
+                total,
+            },
+        };
+        let summary = options.summary.then(|| TableSummary {
+            rows: total,
+            columns: ranges
+                .into_iter()
+                .zip(&summarized)
+                .map(|(range, &column)| range.finish(headers[column].to_string()))
+                .collect(),
+        });
+        Ok(Self {
+            data,
+            selection,
+            columns,
+            summary,
+        })
+    }
+
+    /// The whole-table summary, when `summary` was requested.
+    pub(crate) fn summary(&self) -> Option<&TableSummary> {
+        self.summary.as_ref()
+    }
+
+    fn project<T: Clone>(&self, row: Vec<T>) -> Vec<T> {
+        match &self.columns {
+            Some(columns) => columns.iter().map(|&c| row[c].clone()).collect(),
+            None => row,
+        }
+    }
+}
+
+impl Serialize for CsvRows<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut rdr = ReaderBuilder::new().from_reader(self.data.as_bytes());
+        let mut seq = serializer.serialize_seq(None)?;
+        let headers: Vec<serde_json::Value> = rdr
+            .headers()
+            .map_err(S::Error::custom)?
+            .iter()
+            .map(Into::into)
+            .collect();
+        seq.serialize_element(&self.project(headers))?;
+        for (idx, record) in rdr.records().enumerate() {
+            if self.selection.is_done(idx) {
+                break;
+            }
+            if !self.selection.keeps(idx) {
+                continue;
+            }
+            let row: Vec<serde_json::Value> = record
+                .and_then(|record| record.deserialize(None))
+                .map_err(S::Error::custom)?;
+            seq.serialize_element(&self.project(row))?;
+        }
+        seq.end()
+    }
+}
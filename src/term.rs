@@ -0,0 +1,189 @@
+//! Normalization of terminal output for `assert_term_snapshot`.
+//!
+//! Colored CLI output is full of escape sequences that make a snapshot
+//! unreadable and its diff useless. The output is normalized the way a
+//! terminal would show it:
+//!
+//! * Escape sequences are removed, or with `strip_ansi` off, colors and text
+//!   styles (SGR sequences) become readable markers such as `[bold red]` and
+//!   `[/]`. Cursor movement, hyperlinks and other sequences are always
+//!   dropped, keeping the text they surround.
+//! * `\r\n` becomes `\n`, and text overwritten through a bare `\r` (progress
+//!   bars, spinners) is replaced by what was written last.
+//! * With a `width`, lines are hard-wrapped at that many characters, like a
+//!   terminal of that width. Markers take up no columns.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::PyResult;
+
+const ESC: char = '\u{1b}';
+const BEL: char = '\u{7}';
+
+const COLORS: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// A piece of a line: text, or a zero-width marker.
+enum Cell {
+    Char(char),
+    Marker(String),
+}
+
+/// Returns `text` with its escape sequences stripped or turned into
+/// markers, and re-wrapped to `width` columns.
+pub(crate) fn normalize(text: &str, strip_ansi: bool, width: Option<usize>) -> PyResult<String> {
+    if width == Some(0) {
+        return Err(PyValueError::new_err("width must be at least 1"));
+    }
+    let mut lines = vec![Vec::new()];
+    // Column a bare `\r` moved the cursor back to on the current line.
+    let mut column = 0;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let line = lines.last_mut().expect("at least one line");
+        match c {
+            ESC => {
+                if let Some(marker) = escape_sequence(&mut chars) {
+                    if !strip_ansi {
+                        line.push(Cell::Marker(marker));
+                    }
+                }
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\r' => column = 0,
+            '\n' => {
+                lines.push(Vec::new());
+                column = 0;
+            }
+            c => {
+                overwrite(line, column, c);
+                column += 1;
+            }
+        }
+    }
+    let mut rendered = String::with_capacity(text.len());
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            rendered.push('\n');
+        }
+        let mut columns = 0;
+        for cell in line {
+            match cell {
+                Cell::Marker(marker) => rendered.push_str(marker),
+                Cell::Char(c) => {
+                    if width == Some(columns) {
+                        rendered.push('\n');
+                        columns = 0;
+                    }
+                    rendered.push(*c);
+                    columns += 1;
+                }
+            }
+        }
+    }
+    Ok(rendered)
+}
+
+/// Writes `c` at `column` of `line`, replacing the character already there.
+fn overwrite(line: &mut Vec<Cell>, column: usize, c: char) {
+    let mut seen = 0;
+    for cell in line.iter_mut() {
+        if let Cell::Char(existing) = cell {
+            if seen == column {
+                *existing = c;
+                return;
+            }
+            seen += 1;
+        }
+    }
+    line.push(Cell::Char(c));
+}
+
+/// Consumes the escape sequence after an `ESC`, returning the marker of an
+/// SGR sequence.
+fn escape_sequence(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> Option<String> {
+    match chars.next()? {
+        // CSI: parameters and intermediates up to a final byte in `@`..=`~`.
+        '[' => {
+            let mut parameters = String::new();
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    return (c == 'm').then(|| sgr_marker(&parameters));
+                }
+                parameters.push(c);
+            }
+            None
+        }
+        // OSC (titles, hyperlinks), DCS and friends: up to BEL or `ESC \`.
+        ']' | 'P' | '^' | '_' | 'X' => {
+            while let Some(c) = chars.next() {
+                if c == BEL || (c == ESC && chars.next_if_eq(&'\\').is_some()) {
+                    break;
+                }
+            }
+            None
+        }
+        // Character set and other two- or three-byte sequences.
+        c if (' '..='/').contains(&c) => {
+            chars.next();
+            None
+        }
+        _ => None,
+    }
+}
+
+/// A readable marker for the SGR parameters `parameters`, e.g. `1;31` as
+/// `[bold red]`.
+fn sgr_marker(parameters: &str) -> String {
+    let codes: Vec<&str> = parameters.split([';', ':']).collect();
+    let mut names = Vec::new();
+    let mut i = 0;
+    while i < codes.len() {
+        let code = codes[i].parse::<u16>().unwrap_or(0);
+        i += 1;
+        let name = match code {
+            0 => "/".to_string(),
+            1 => "bold".to_string(),
+            2 => "dim".to_string(),
+            3 => "italic".to_string(),
+            4 => "underline".to_string(),
+            5 | 6 => "blink".to_string(),
+            7 => "reverse".to_string(),
+            8 => "hidden".to_string(),
+            9 => "strike".to_string(),
+            21 | 22 => "/bold".to_string(),
+            23 => "/italic".to_string(),
+            24 => "/underline".to_string(),
+            25 => "/blink".to_string(),
+            27 => "/reverse".to_string(),
+            28 => "/hidden".to_string(),
+            29 => "/strike".to_string(),
+            30..=37 => COLORS[usize::from(code - 30)].to_string(),
+            39 => "/color".to_string(),
+            40..=47 => format!("on_{}", COLORS[usize::from(code - 40)]),
+            49 => "/on".to_string(),
+            90..=97 => format!("bright_{}", COLORS[usize::from(code - 90)]),
+            100..=107 => format!("on_bright_{}", COLORS[usize::from(code - 100)]),
+            38 | 48 => {
+                let on = if code == 48 { "on_" } else { "" };
+                let argument = |n: usize| codes.get(i + n).and_then(|c| c.parse::<u8>().ok());
+                match codes.get(i).copied() {
+                    Some("5") => {
+                        let index = argument(1).unwrap_or(0);
+                        i += 2;
+                        format!("{on}color({index})")
+                    }
+                    Some("2") => {
+                        let [r, g, b] = [1, 2, 3].map(|n| argument(n).unwrap_or(0));
+                        i += 4;
+                        format!("{on}#{r:02x}{g:02x}{b:02x}")
+                    }
+                    _ => code.to_string(),
+                }
+            }
+            other => other.to_string(),
+        };
+        names.push(name);
+    }
+    format!("[{}]", names.join(" "))
+}
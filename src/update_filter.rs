@@ -0,0 +1,171 @@
+//! Restricting snapshot updates to part of a suite.
+//!
+//! `PYSNAPTEST_UPDATE_FILTER` holds comma-separated globs, e.g.
+//! `tests/api/*,*users*`. While it is set, only the assertions whose running
+//! test (its node id, or the test file alone) or snapshot (its name, or the
+//! stem of its file) matches one of them write anything; the others compare
+//! as usual and fail on a mismatch, leaving their files as they were, as
+//! under `INSTA_UPDATE=no`. A mock's `record=True` re-records only within the
+//! filter too, so `pytest --snapshot-update` refreshes one subsystem in a
+//! single run without touching the rest.
+//!
+//! In a glob, `*` matches within a path segment, `**` across segments and
+//! `?` one character; the whole string must match.
+//!
+//! insta reads its update mode once per process, so an assertion outside the
+//! filter cannot be run under another. It runs as insta runs it instead, and
+//! whatever it wrote (the snapshot, its pending `.snap.new`, or their binary
+//! files) is put back as it was afterwards.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+use crate::atomic;
+use crate::provenance::current_test_node_id;
+
+/// Environment variable holding the globs updates are restricted to.
+pub const UPDATE_FILTER_ENV: &str = "PYSNAPTEST_UPDATE_FILTER";
+
+/// A regex matching the same strings as `glob`.
+fn glob_regex(glob: &str) -> Regex {
+    let mut pattern = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                pattern.push_str(".*");
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern).unwrap_or_else(|_| unreachable!("every glob character is escaped"))
+}
+
+/// The globs of `PYSNAPTEST_UPDATE_FILTER`; `None` when it is unset or
+/// holds none.
+fn filter() -> Option<Vec<Regex>> {
+    let value = std::env::var(UPDATE_FILTER_ENV).ok()?;
+    let globs: Vec<Regex> = value
+        .split(',')
+        .map(str::trim)
+        .filter(|glob| !glob.is_empty())
+        .map(glob_regex)
+        .collect();
+    (!globs.is_empty()).then_some(globs)
+}
+
+/// Whether the snapshot `name`, stored at `snapshot`, may be written by the
+/// running test: always without a filter.
+pub(crate) fn allows(name: &str, snapshot: &Path) -> bool {
+    let Some(globs) = filter() else {
+        return true;
+    };
+    let node_id = current_test_node_id();
+    let test_file = node_id
+        .as_deref()
+        .map(|node_id| node_id.split_once("::").map_or(node_id, |(file, _)| file));
+    let stem = snapshot
+        .file_name()
+        .and_then(|file| file.to_str())
+        .map(|file| file.split_once('@').map_or(file, |(stem, _)| stem));
+    let candidates: Vec<&str> = [node_id.as_deref(), test_file, Some(name), stem]
+        .into_iter()
+        .flatten()
+        .collect();
+    globs
+        .iter()
+        .any(|glob| candidates.iter().any(|candidate| glob.is_match(candidate)))
+}
+
+/// The files of a snapshot as they were before an assertion outside the
+/// filter (or under `set_read_only`) ran: the snapshot's, and those named
+/// after it (its `.snap.new` and binary files), with their contents.
+pub(crate) struct Preserved {
+    snapshot: PathBuf,
+    folder_existed: bool,
+    files: Vec<(PathBuf, Vec<u8>)>,
+}
+
+impl Preserved {
+    /// The files of `snapshot` as they are now.
+    pub(crate) fn capture(snapshot: &Path) -> io::Result<Self> {
+        let folder_existed = snapshot.parent().is_some_and(Path::is_dir);
+        let files = snapshot_files(snapshot)?
+            .into_iter()
+            .map(|path| fs::read(&path).map(|contents| (path, contents)))
+            .collect::<io::Result<_>>()?;
+        Ok(Self {
+            snapshot: snapshot.to_path_buf(),
+            folder_existed,
+            files,
+        })
+    }
+
+    /// Whether the snapshot was stored before the assertion.
+    pub(crate) fn was_stored(&self) -> bool {
+        self.files.iter().any(|(path, _)| *path == self.snapshot)
+    }
+
+    /// Puts the files back as they were, returning those that had been
+    /// written since: each with `"created"` or `"modified"`.
+    pub(crate) fn restore(self) -> io::Result<Vec<(PathBuf, &'static str)>> {
+        let mut written = Vec::new();
+        for path in snapshot_files(&self.snapshot)? {
+            if !self.files.iter().any(|(kept, _)| *kept == path) {
+                fs::remove_file(&path)?;
+                written.push((path, "created"));
+            }
+        }
+        for (path, contents) in &self.files {
+            match fs::read(path) {
+                Ok(current) if current == *contents => continue,
+                Ok(_) => written.push((path.clone(), "modified")),
+                // Removed, as insta removes the `.snap.new` of a match.
+                Err(_) => {}
+            }
+            // Replaced by rename, so an interrupt never truncates a committed
+            // snapshot and a mapped reader keeps the old file.
+            atomic::write(path, contents)?;
+        }
+        if !self.folder_existed {
+            if let Some(folder) = self.snapshot.parent() {
+                // Only succeeds when insta left nothing else in it.
+                let _ = fs::remove_dir(folder);
+            }
+        }
+        Ok(written)
+    }
+}
+
+/// The files named after `snapshot` (itself included) in its folder.
+fn snapshot_files(snapshot: &Path) -> io::Result<Vec<PathBuf>> {
+    let (Some(folder), Some(name)) = (snapshot.parent(), snapshot.file_name()) else {
+        return Ok(Vec::new());
+    };
+    let entries = match fs::read_dir(folder) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let name = name.to_string_lossy();
+    let mut files = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if entry
+            .file_name()
+            .to_string_lossy()
+            .starts_with(name.as_ref())
+            && entry.file_type()?.is_file()
+        {
+            files.push(entry.path());
+        }
+    }
+    Ok(files)
+}
@@ -0,0 +1,145 @@
+//! Upgrading snapshots written by older pysnaptest versions.
+//!
+//! Every snapshot header records the [`SNAPSHOT_FORMAT_VERSION`] it was
+//! written in; snapshots from before it was recorded have none (version 0).
+//! Their file names may predate name sanitization, keeping the `::` of test
+//! classes, whitespace runs and overlong test ids in them, so the current
+//! version asserts against another file and silently records a new one.
+//! [`upgrade_snapshot`] renames such a snapshot (and its binary sidecar) to
+//! the name it would get today and brings its header to the current layout:
+//! the `expression` insta recorded is dropped and the format version set.
+//! Snapshots already in the current format keep theirs, which assertions now
+//! only record when given one.
+
+use std::path::{Path, PathBuf};
+
+use insta::Snapshot;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::atomic;
+use crate::common::{file_stem_of, indexed_name};
+use crate::mocks::{read_info_field, write_info_field};
+use crate::provenance::SNAPSHOT_FORMAT_VERSION;
+use crate::{sanitize_snapshot_name, SNAPSHOT_FILE_SUFFIX};
+
+/// Separates the prefix, the namespace and the name in a snapshot filename.
+const SEPARATOR: &str = "__";
+
+/// The namespace of the mock layer's snapshots.
+const MOCK_NAMESPACE: &str = "mocks";
+
+/// The file name `file_name` takes when its snapshot name is sanitized, or
+/// `None` when it is not a snapshot file.
+fn upgraded_file_name(file_name: &str) -> Option<String> {
+    let stem = file_name.strip_suffix(SNAPSHOT_FILE_SUFFIX)?;
+    let (stem, index) = stem
+        .rsplit_once('-')
+        .and_then(|(base, index)| Some((base, index.parse().ok().filter(|&i: &usize| i > 1)?)))
+        .unwrap_or((stem, 1));
+    let (prefix, rest) = match stem.split_once(SEPARATOR) {
+        Some((prefix, rest)) => (Some(prefix), rest),
+        None => (None, stem),
+    };
+    let (namespace, name) = match rest.split_once(SEPARATOR) {
+        Some((MOCK_NAMESPACE, name)) => (Some(MOCK_NAMESPACE), name),
+        _ => (None, rest),
+    };
+    let name = indexed_name(&sanitize_snapshot_name(name), index);
+    Some(format!(
+        "{}{SNAPSHOT_FILE_SUFFIX}",
+        file_stem_of(prefix, namespace, &name)
+    ))
+}
+
+/// The header of `text` without a top-level `expression` field, or `None`
+/// when it has none.
+fn without_expression(text: &str) -> Option<String> {
+    let end = text.strip_prefix("---\n")?.find("\n---\n")? + "---\n".len();
+    let mut lines = text[..end].lines().peekable();
+    let mut kept = Vec::new();
+    let mut dropped = false;
+    while let Some(line) = lines.next() {
+        if line.starts_with("expression:") {
+            dropped = true;
+            // A multi-line expression continues on indented lines.
+            while lines.next_if(|line| line.starts_with(' ')).is_some() {}
+        } else {
+            kept.push(line);
+        }
+    }
+    dropped.then(|| format!("{}\n{}", kept.join("\n"), &text[end + 1..]))
+}
+
+fn rename(from: &Path, to: &Path) -> PyResult<()> {
+    std::fs::rename(from, to)
+        .map_err(|e| PyValueError::new_err(format!("Unable to rename {from:?} to {to:?}: {e}")))
+}
+
+/// Brings the snapshot at `path` to the current layout: renames it (and its
+/// binary sidecar) to its sanitized name and records the current format
+/// version in its header, dropping the `expression` field older versions
+/// recorded.
+///
+/// Returns the snapshot's path once upgraded, or `None` when it already was.
+/// Fails for a snapshot written in a newer format than this version's, and
+/// when another snapshot already has the upgraded name.
+#[pyfunction]
+pub fn upgrade_snapshot(path: PathBuf) -> PyResult<Option<PathBuf>> {
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(upgraded_file_name)
+        .ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "Not a snapshot file (expected a trailing '{SNAPSHOT_FILE_SUFFIX}'): {path:?}"
+            ))
+        })?;
+    let target = path.with_file_name(file_name);
+    let version = read_info_field(&path, "format_version").map_or(Ok(0), |version| {
+        version.parse::<u32>().map_err(|_| {
+            PyValueError::new_err(format!(
+                "Invalid format_version {version:?} in snapshot {path:?}"
+            ))
+        })
+    })?;
+    if version > SNAPSHOT_FORMAT_VERSION {
+        return Err(PyValueError::new_err(format!(
+            "Snapshot {path:?} was written in format {version}, newer than the \
+             format {SNAPSHOT_FORMAT_VERSION} of this pysnaptest; upgrade pysnaptest"
+        )));
+    }
+    let text = std::fs::read_to_string(&path)?;
+    let stripped = (version < SNAPSHOT_FORMAT_VERSION)
+        .then(|| without_expression(&text))
+        .flatten();
+    if target == path && version == SNAPSHOT_FORMAT_VERSION && stripped.is_none() {
+        return Ok(None);
+    }
+    if target != path && target.exists() {
+        return Err(PyValueError::new_err(format!(
+            "Cannot upgrade snapshot {path:?}: {target:?} already exists"
+        )));
+    }
+
+    if let Some(stripped) = stripped {
+        atomic::write(&path, stripped)?;
+    }
+    write_info_field(
+        &path,
+        "format_version",
+        Some(&SNAPSHOT_FORMAT_VERSION.to_string()),
+    )?;
+    if target != path {
+        if let Ok(snapshot) = Snapshot::from_file(&path) {
+            let sidecars = snapshot
+                .build_binary_path(&path)
+                .zip(snapshot.build_binary_path(&target));
+            if let Some((sidecar, new_sidecar)) = sidecars.filter(|(sidecar, _)| sidecar.exists()) {
+                rename(&sidecar, &new_sidecar)?;
+            }
+        }
+        rename(&path, &target)?;
+    }
+    Ok(Some(target))
+}
@@ -0,0 +1,150 @@
+//! Tolerance-based comparison for `assert_vector_snapshot`.
+//!
+//! Embeddings change in their last bits whenever a model runtime, BLAS build
+//! or GPU changes, so an exact snapshot of one fails for no real reason. A
+//! vector snapshot is stored as a plain JSON array, but before asserting, the
+//! new vector is compared with the committed one under a [`Metric`]: when it
+//! is within tolerance, the *committed* values are asserted instead, so the
+//! snapshot neither fails nor churns. Outside the tolerance the new vector is
+//! asserted and insta reports the usual diff.
+
+use std::path::Path;
+
+use insta::internals::SnapshotContents;
+use insta::Snapshot;
+use pyo3::exceptions::PyValueError;
+use pyo3::PyResult;
+
+/// How a new vector is compared with the committed one.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Metric {
+    /// Cosine distance (`1 - cosine similarity`) at most `atol`.
+    Cosine,
+    /// Euclidean distance at most `atol + rtol * |committed|`.
+    Euclidean,
+    /// Every element within `atol + rtol * |committed element|`, like
+    /// `numpy.allclose`.
+    Allclose,
+}
+
+impl Metric {
+    pub(crate) fn parse(metric: &str, rtol: f64) -> PyResult<Self> {
+        let metric = match metric.to_ascii_lowercase().as_str() {
+            "cosine" => Metric::Cosine,
+            "euclidean" => Metric::Euclidean,
+            "allclose" => Metric::Allclose,
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "Unsupported vector metric {other:?}; expected \"cosine\", \"euclidean\" \
+                     or \"allclose\""
+                )))
+            }
+        };
+        if matches!(metric, Metric::Cosine) && rtol != 0.0 {
+            return Err(PyValueError::new_err(
+                "rtol does not apply to the cosine metric; use atol",
+            ));
+        }
+        Ok(metric)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Metric::Cosine => "cosine distance",
+            Metric::Euclidean => "euclidean distance",
+            Metric::Allclose => "largest element difference",
+        }
+    }
+}
+
+fn norm(vector: &[f64]) -> f64 {
+    vector.iter().map(|v| v * v).sum::<f64>().sqrt()
+}
+
+/// Outcome of comparing a new vector with the committed one.
+pub(crate) struct Comparison {
+    pub(crate) within: bool,
+    /// A one-line account of the distance and the allowed tolerance.
+    pub(crate) detail: String,
+}
+
+pub(crate) fn compare(
+    metric: Metric,
+    committed: &[f64],
+    new: &[f64],
+    atol: f64,
+    rtol: f64,
+) -> Comparison {
+    if committed.len() != new.len() {
+        return Comparison {
+            within: false,
+            detail: format!(
+                "dimensions changed from {} to {}",
+                committed.len(),
+                new.len()
+            ),
+        };
+    }
+    let allowed = atol + rtol * norm(committed);
+    let (distance, within) = match metric {
+        Metric::Cosine => {
+            let (a, b) = (norm(committed), norm(new));
+            let distance = match (a == 0.0, b == 0.0) {
+                (true, true) => 0.0,
+                (true, false) | (false, true) => 1.0,
+                _ => {
+                    let dot: f64 = committed.iter().zip(new).map(|(x, y)| x * y).sum();
+                    1.0 - dot / (a * b)
+                }
+            };
+            (distance, distance <= atol)
+        }
+        Metric::Euclidean => {
+            let diff: Vec<f64> = committed.iter().zip(new).map(|(x, y)| x - y).collect();
+            let distance = norm(&diff);
+            (distance, distance <= allowed)
+        }
+        Metric::Allclose => {
+            committed
+                .iter()
+                .zip(new)
+                .fold((0f64, true), |(largest, within), (x, y)| {
+                    let difference = (x - y).abs();
+                    (
+                        largest.max(difference),
+                        within && difference <= atol + rtol * x.abs(),
+                    )
+                })
+        }
+    };
+    let detail = match metric {
+        Metric::Cosine => format!("{} is {distance:e}, allowed {atol:e}", metric.name()),
+        Metric::Euclidean => format!("{} is {distance:e}, allowed {allowed:e}", metric.name()),
+        Metric::Allclose => format!(
+            "{} is {distance:e} (atol={atol:e}, rtol={rtol:e})",
+            metric.name()
+        ),
+    };
+    Comparison { within, detail }
+}
+
+/// Rejects values JSON cannot store.
+pub(crate) fn check_finite(vector: &[f64]) -> PyResult<()> {
+    match vector.iter().position(|v| !v.is_finite()) {
+        Some(idx) => Err(PyValueError::new_err(format!(
+            "Vector element {idx} is {}; only finite values can be snapshotted",
+            vector[idx]
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Reads the committed vector at `path`; `None` on a first run or when the
+/// snapshot does not hold a JSON array of numbers.
+pub(crate) fn read_committed(path: &Path) -> Option<Vec<f64>> {
+    let snapshot = Snapshot::from_file(path).ok()?;
+    match snapshot.contents() {
+        SnapshotContents::Text(text) => serde_json::from_str(&text.to_string()).ok(),
+        SnapshotContents::Binary(_) => None,
+    }
+}
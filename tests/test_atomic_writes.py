@@ -0,0 +1,65 @@
+"""Tests for replacing snapshot files atomically."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import (
+    assert_binary_snapshot,
+    assert_snapshot,
+    export_snapshots,
+    import_exported_snapshots,
+)
+from pysnaptest.review import accept_pending_snapshot
+
+
+def _leftovers(folder: Path) -> list[Path]:
+    return sorted(path for path in folder.rglob(".*") if path.name.endswith(".tmp"))
+
+
+def test_accept_replaces_snapshot_and_sidecar(tmp_path: Path):
+    path = str(tmp_path)
+    with pytest.raises(AssertionError):
+        assert_snapshot("new", snapshot_path=path, snapshot_name="text")
+    with pytest.raises(AssertionError):
+        assert_binary_snapshot(b"\x01\x02", snapshot_path=path, snapshot_name="bytes")
+
+    text = accept_pending_snapshot(next(tmp_path.glob("*text@pysnap.snap.new")))
+    binary = accept_pending_snapshot(next(tmp_path.glob("*bytes@pysnap.snap.new")))
+
+    assert text.read_text().endswith("---\nnew\n")
+    assert Path(f"{binary}.bin").read_bytes() == b"\x01\x02"
+    assert not list(tmp_path.glob("*.new*"))
+    assert _leftovers(tmp_path) == []
+
+
+def test_failed_accept_leaves_no_temporary_files(tmp_path: Path):
+    with pytest.raises(AssertionError):
+        assert_snapshot("new", snapshot_path=str(tmp_path), snapshot_name="text")
+    pending = next(tmp_path.glob("*.snap.new"))
+    # A directory in the way makes the final rename fail.
+    blocker = pending.with_suffix("")
+    blocker.mkdir()
+    (blocker / "keep").write_text("")
+
+    with pytest.raises(ValueError, match="Unable to save snapshot"):
+        accept_pending_snapshot(pending)
+
+    assert pending.is_file()
+    assert _leftovers(tmp_path) == []
+
+
+def test_import_exported_leaves_no_temporary_files(tmp_path: Path):
+    folder = tmp_path / "snapshots"
+    folder.mkdir()
+    snapshot = folder / "test_api__test_api_greeting@pysnap.snap"
+    snapshot.write_text("---\nsource: src/lib.rs\n---\nHello, Ada\n")
+    destination = tmp_path / "golden"
+    export_snapshots(str(tmp_path), destination=str(destination))
+
+    import_exported_snapshots(str(destination), str(tmp_path))
+
+    assert snapshot.read_text().endswith("Hello, Ada\n")
+    assert _leftovers(tmp_path) == []
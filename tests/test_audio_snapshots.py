@@ -0,0 +1,120 @@
+"""Tests for tolerance-based snapshots of encoded audio."""
+
+from __future__ import annotations
+
+import io
+import math
+import shutil
+import struct
+import wave
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import assert_audio_snapshot
+
+SNAPSHOT = (
+    Path(__file__).parent
+    / "snapshots"
+    / "test_audio_snapshots__test_audio_snapshots_test_audio_snapshot@pysnap.snap"
+)
+
+
+def _wav(
+    frequency: float = 440.0,
+    seconds: float = 0.5,
+    rate: int = 8000,
+    channels: int = 1,
+    offset: int = 0,
+    noise: int = 0,
+) -> bytes:
+    """A 16-bit sine wave, optionally shifted by ``offset`` samples and with
+    deterministic +/- ``noise`` LSB dither, as an encoder might produce."""
+
+    frames = []
+    for i in range(int(seconds * rate)):
+        sample = round(12000 * math.sin(2 * math.pi * frequency * (i + offset) / rate))
+        sample += noise if i % 2 else -noise
+        frames.append(struct.pack("<h", sample) * channels)
+    out = io.BytesIO()
+    with wave.open(out, "wb") as writer:
+        writer.setnchannels(channels)
+        writer.setsampwidth(2)
+        writer.setframerate(rate)
+        writer.writeframes(b"".join(frames))
+    return out.getvalue()
+
+
+def _committed(tmp_path: Path, name: str) -> str:
+    stem = f"test_audio_snapshots__{name}@pysnap.snap"
+    shutil.copy(SNAPSHOT, tmp_path / stem)
+    shutil.copy(f"{SNAPSHOT}.wav", tmp_path / f"{stem}.wav")
+    return str(tmp_path)
+
+
+def test_audio_snapshot():
+    assert_audio_snapshot(_wav())
+
+
+def test_audio_snapshot_within_pcm_tolerance(tmp_path: Path):
+    path = _committed(tmp_path, "dithered")
+    assert_audio_snapshot(_wav(noise=3), snapshot_path=path, snapshot_name="dithered")
+    # The committed recording is kept, not rewritten with the new bytes.
+    assert (tmp_path / "test_audio_snapshots__dithered@pysnap.snap.wav").read_bytes() == (
+        _wav()
+    )
+
+
+def test_audio_snapshot_outside_pcm_tolerance(tmp_path: Path):
+    path = _committed(tmp_path, "retuned")
+    with pytest.raises(AssertionError, match="RMS sample difference"):
+        assert_audio_snapshot(
+            _wav(frequency=660.0), snapshot_path=path, snapshot_name="retuned"
+        )
+
+
+def test_audio_fingerprint_ignores_sample_shifts(tmp_path: Path):
+    path = _committed(tmp_path, "shifted")
+    with pytest.raises(AssertionError, match="RMS sample difference"):
+        assert_audio_snapshot(
+            _wav(offset=5), snapshot_path=path, snapshot_name="shifted"
+        )
+    assert_audio_snapshot(
+        _wav(offset=5),
+        snapshot_path=path,
+        snapshot_name="shifted",
+        mode="fingerprint",
+        allow_duplicates=True,
+    )
+    with pytest.raises(AssertionError, match="mean spectral difference"):
+        assert_audio_snapshot(
+            _wav(frequency=660.0),
+            snapshot_path=path,
+            snapshot_name="shifted",
+            mode="fingerprint",
+            allow_duplicates=True,
+        )
+
+
+def test_audio_snapshot_format_change(tmp_path: Path):
+    path = _committed(tmp_path, "resampled")
+    with pytest.raises(AssertionError, match="sample rate changed from 8000 Hz to 16000 Hz"):
+        assert_audio_snapshot(
+            _wav(rate=16000), snapshot_path=path, snapshot_name="resampled"
+        )
+    with pytest.raises(AssertionError, match="channel count changed from 1 to 2"):
+        assert_audio_snapshot(
+            _wav(channels=2),
+            snapshot_path=path,
+            snapshot_name="resampled",
+            allow_duplicates=True,
+        )
+
+
+def test_audio_snapshot_rejects_bad_input():
+    with pytest.raises(ValueError, match="Unsupported audio snapshot mode"):
+        assert_audio_snapshot(_wav(), mode="waveform")
+    with pytest.raises(ValueError, match="tolerance must be a non-negative number"):
+        assert_audio_snapshot(_wav(), tolerance=-1.0)
+    with pytest.raises(ValueError, match="Unable to decode audio"):
+        assert_audio_snapshot(b"not audio")
@@ -0,0 +1,87 @@
+"""Tests for the checksum of snapshot bodies, verified on load."""
+
+from __future__ import annotations
+
+import os
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import (
+    PySnapshot,
+    SnapshotCorruptedError,
+    assert_binary_snapshot,
+    assert_snapshot,
+    mock_json_snapshot,
+)
+from pysnaptest._pysnaptest import observe_test_stage
+
+
+@pytest.fixture
+def updating(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> Path:
+    """``INSTA_UPDATE=always`` under a workspace root insta has not read a
+    config for."""
+
+    monkeypatch.setenv("INSTA_WORKSPACE_ROOT", str(tmp_path))
+    monkeypatch.setenv("INSTA_UPDATE", "always")
+    monkeypatch.delenv("INSTA_FORCE_PASS", raising=False)
+    monkeypatch.delenv("PYSNAPTEST_UPDATE_FILTER", raising=False)
+    return tmp_path
+
+
+def test_written_snapshots_carry_a_checksum(updating: Path):
+    assert_snapshot("body", snapshot_path=str(updating), snapshot_name="summed")
+    (snapshot,) = updating.glob("*@pysnap.snap")
+
+    assert PySnapshot.from_file(snapshot).metadata()["checksum"].startswith("sha256:")
+    assert PySnapshot.from_file(snapshot).contents() == b"body"
+
+
+@pytest.mark.parametrize("damage", ["edit", "truncate"])
+def test_damaged_body_is_refused(updating: Path, damage: str):
+    assert_snapshot("line one\nline two", snapshot_path=str(updating), snapshot_name="damaged")
+    (snapshot,) = updating.glob("*@pysnap.snap")
+    text = snapshot.read_text()
+    if damage == "edit":
+        snapshot.write_text(text.replace("line two", "line 2"))
+    else:
+        snapshot.write_text(text[: text.index("line two")])
+
+    with pytest.raises(SnapshotCorruptedError, match="does not match the checksum"):
+        PySnapshot.from_file(snapshot)
+
+
+def test_binary_data_file_is_checked(updating: Path):
+    assert_binary_snapshot(b"\x00\x01", extension="bin", snapshot_path=str(updating))
+    (data,) = updating.glob("*.snap.bin")
+    data.write_bytes(b"\x00")
+
+    with pytest.raises(SnapshotCorruptedError):
+        PySnapshot.from_file(data.with_suffix(""))
+
+
+def test_snapshots_without_a_checksum_load(tmp_path: Path):
+    snapshot = tmp_path / "test_checksum__legacy@pysnap.snap"
+    snapshot.write_text("---\nsource: src/lib.rs\n---\nlegacy\n")
+
+    assert PySnapshot.from_file(snapshot).contents() == b"legacy"
+
+
+def test_mock_replay_refuses_an_edited_recording(updating: Path):
+    calls = []
+
+    def rate(currency: str) -> dict:
+        calls.append(currency)
+        return {"currency": currency, "rate": 1.1}
+
+    mock_json_snapshot(func=rate, snapshot_path=str(updating))("EUR")
+    (response,) = (
+        path for path in updating.glob("*@pysnap.snap") if "-request@" not in path.name
+    )
+    response.write_text(response.read_text().replace("1.1", "1.2"))
+    # Run the test again, so the call replays its recording.
+    observe_test_stage(os.environ["PYTEST_CURRENT_TEST"].rsplit(" (", 1)[0], "setup")
+
+    with pytest.raises(SnapshotCorruptedError):
+        mock_json_snapshot(func=rate, snapshot_path=str(updating))("EUR")
+    assert calls == ["EUR"]
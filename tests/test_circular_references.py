@@ -0,0 +1,52 @@
+"""Tests for reporting self-referential values instead of crashing on them."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import SerializationError, assert_json_snapshot, mock_json_snapshot
+from pysnaptest._pysnaptest import validate_redactions
+
+
+def _order() -> dict:
+    order: dict = {"id": 1, "lines": [{"sku": "A"}]}
+    order["lines"][0]["order"] = order
+    return order
+
+
+def test_assert_json_snapshot_names_the_circular_path(tmp_path: Path):
+    with pytest.raises(SerializationError) as error:
+        assert_json_snapshot({"order": _order()}, snapshot_path=str(tmp_path))
+
+    assert str(error.value) == "circular reference at path .order.lines[0].order"
+    assert isinstance(error.value, ValueError)
+    assert not list(tmp_path.iterdir())
+
+
+def test_mocked_call_with_a_self_referential_argument(tmp_path: Path):
+    def total(order: dict) -> int:
+        return len(order["lines"])
+
+    mocked = mock_json_snapshot(total, record=True, snapshot_path=str(tmp_path))
+
+    with pytest.raises(SerializationError) as error:
+        mocked(_order())
+
+    assert str(error.value).endswith("at path .args[0].lines[0].order")
+
+
+def test_rust_conversion_detects_cycles_through_tuples():
+    inner: list = []
+    value = {"pair": (1, inner)}
+    inner.append(value)
+
+    with pytest.raises(SerializationError, match=r"at path \.pair\[1\]\[0\]$"):
+        validate_redactions(value, {".pair": "[pair]"})
+
+
+def test_rust_conversion_allows_shared_references():
+    shared = {"id": 1}
+
+    assert validate_redactions({"a": shared, "b": (shared,)}, {".a.id": 1}) == []
@@ -0,0 +1,49 @@
+"""Tests for default redactions carried by a test's SnapshotInfo."""
+
+from __future__ import annotations
+
+import pytest
+
+from pysnaptest import extract_from_pytest_env
+from pysnaptest._pysnaptest import (
+    assert_json_snapshot,
+    assert_json_snapshot_named,
+    prepare_mock_call,
+)
+
+PAYLOAD = {"id": 8172, "user": {"token": "f1e2d3", "name": "Ada"}}
+
+
+@pytest.fixture
+def scrubbed():
+    return extract_from_pytest_env().with_redactions(
+        {"id": "[id]", "user": {"token": "[token]"}}
+    )
+
+
+def test_default_redactions_apply_to_assertions(scrubbed):
+    assert_json_snapshot(scrubbed, PAYLOAD)
+    assert_json_snapshot(scrubbed, {"user": {"token": "a9b8c7"}})
+
+
+def test_call_redactions_override_defaults(scrubbed):
+    assert_json_snapshot(
+        scrubbed, PAYLOAD, {".id": "[user-id]", "user": {"name": "[name]"}}
+    )
+
+
+def test_default_redactions_apply_to_mocks(scrubbed):
+    def fetch_user(user_id: int) -> dict:
+        return {"id": user_id, "user": {"token": "secret", "name": "Ada"}}
+
+    request = {"args": [], "kwargs": {"user_id": 8172}}
+    name, _, _ = prepare_mock_call(scrubbed, "fetch_user", request, False)
+    assert_json_snapshot_named(scrubbed, fetch_user(8172), name)
+
+
+def test_with_redactions_returns_a_copy():
+    info = extract_from_pytest_env()
+    redacted = info.with_redactions({".x": "[x]"})
+
+    assert redacted is not info
+    assert redacted.with_redactions({".y": "[y]"}).name == info.name
@@ -0,0 +1,90 @@
+"""Tests for the description and expression recorded in snapshot headers."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import (
+    SnapshotInfo,
+    assert_binary_snapshot,
+    assert_json_snapshot,
+    assert_snapshot,
+    upgrade_snapshots,
+)
+from pysnaptest._pysnaptest import (
+    SNAPSHOT_FORMAT_VERSION,
+    assert_snapshot as _assert_snapshot,
+)
+from pysnaptest.orphans import recorded_test_file
+
+
+SNAPSHOTS = Path(__file__).parent / "snapshots"
+
+
+def _snapshot(name: str) -> Path:
+    return SNAPSHOTS / f"test_descriptions__{name}@pysnap.snap"
+
+
+def _header(path: Path) -> list:
+    return path.read_text().split("\n---\n", 1)[0].splitlines()
+
+
+def test_header_records_description_and_expression():
+    assert_json_snapshot(
+        {"id": 7},
+        snapshot_name="described_json",
+        description="The user\n  returned by the API",
+        expression="client.get('/users/7').json()",
+    )
+
+    path = _snapshot("described_json")
+    header = _header(path)
+    assert (
+        'description: "The user returned by the API; Test File Path: '
+        'tests/test_descriptions.py"'
+    ) in header
+    assert "expression: \"client.get('/users/7').json()\"" in header
+    assert recorded_test_file(path) == "tests/test_descriptions.py"
+
+
+def test_expression_is_omitted_by_default():
+    assert_snapshot("plain", snapshot_name="undescribed_text")
+
+    header = _header(_snapshot("undescribed_text"))
+    assert not any(line.startswith("expression:") for line in header)
+    assert 'description: "Test File Path: tests/test_descriptions.py"' in header
+
+
+def test_binary_snapshot_records_the_expression():
+    assert_binary_snapshot(
+        b"\x00\x01", snapshot_name="described_binary", expression="payload"
+    )
+
+    header = _header(_snapshot("described_binary"))
+    assert "expression: payload" in header
+
+
+def test_manual_snapshot_description(tmp_path: Path):
+    info = SnapshotInfo.manual(tmp_path, "manual_described").with_header(
+        description="Rendered greeting"
+    )
+
+    with pytest.raises(AssertionError):
+        _assert_snapshot(info, "hello")
+
+    header = _header(tmp_path / "manual_described@pysnap.snap.new")
+    assert "description: Rendered greeting" in header
+
+
+def test_upgrade_keeps_recorded_expressions(tmp_path: Path):
+    path = tmp_path / "test_api__test_api_test_get@pysnap.snap"
+    text = (
+        "---\nsource: src/lib.rs\nexpression: client.get()\n"
+        f"info:\n  format_version: {SNAPSHOT_FORMAT_VERSION}\n---\nkept\n"
+    )
+    path.write_text(text)
+
+    assert upgrade_snapshots(str(tmp_path)) == []
+    assert path.read_text() == text
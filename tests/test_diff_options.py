@@ -0,0 +1,90 @@
+"""Tests for the diff carried by a failing text assertion."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import assert_snapshot, set_diff_options
+from pysnaptest.review import reject_pending_snapshot
+
+COMMITTED = "\n".join(f"line {i}" for i in range(1, 11))
+
+
+@pytest.fixture(autouse=True)
+def plain_diffs():
+    set_diff_options(color=False)
+    yield
+    set_diff_options()
+
+
+def _mismatch(tmp_path: Path, name: str, result: str) -> str:
+    snapshot = tmp_path / f"test_diff_options__{name}@pysnap.snap"
+    snapshot.write_text(f"---\nsource: src/lib.rs\n---\n{COMMITTED}\n")
+    with pytest.raises(AssertionError) as excinfo:
+        assert_snapshot(result, snapshot_path=str(tmp_path), snapshot_name=name)
+    return str(excinfo.value)
+
+
+def test_failure_carries_a_line_diff(tmp_path: Path):
+    message = _mismatch(tmp_path, "diff_lines", COMMITTED.replace("line 5", "line five"))
+
+    assert "snapshot 'diff_lines' did not match the stored value" in message
+    assert message.endswith(
+        "--- committed\n+++ new\n@@ -2,7 +2,7 @@\n line 2\n line 3\n line 4\n"
+        "-line 5\n+line five\n line 6\n line 7\n line 8"
+    )
+
+
+def test_context_lines(tmp_path: Path):
+    set_diff_options(context_lines=0, color=False)
+
+    message = _mismatch(tmp_path, "diff_context", COMMITTED.replace("line 5", "line five"))
+
+    assert message.endswith("@@ -5,1 +5,1 @@\n-line 5\n+line five")
+
+
+def test_word_diff(tmp_path: Path):
+    set_diff_options(context_lines=0, word_diff=True, color=False)
+
+    message = _mismatch(tmp_path, "diff_words", COMMITTED.replace("line 5", "line five"))
+
+    assert message.endswith("-line [-5-]\n+line {+five+}")
+
+
+def test_max_lines_truncates_with_a_notice(tmp_path: Path):
+    set_diff_options(max_lines=4, color=False)
+
+    message = _mismatch(tmp_path, "diff_truncated", COMMITTED.upper())
+
+    assert message.endswith(
+        "--- committed\n+++ new\n@@ -1,10 +1,10 @@\n-line 1\n"
+        "... 19 more diff line(s) not shown; raise max_lines with "
+        "set_diff_options to see them"
+    )
+
+
+def test_color(tmp_path: Path):
+    set_diff_options(color=True)
+
+    message = _mismatch(tmp_path, "diff_color", COMMITTED.replace("line 5", "line five"))
+
+    assert "\x1b[31m-line 5\x1b[0m" in message
+    assert "\x1b[32m+line five\x1b[0m" in message
+
+
+def test_html_diff_next_to_the_pending_snapshot(tmp_path: Path):
+    set_diff_options(color=False, html=True)
+
+    message = _mismatch(tmp_path, "diff_html", COMMITTED.replace("line 5", "line <5>"))
+
+    html = tmp_path / "test_diff_options__diff_html@pysnap.snap.new.html"
+    assert message.endswith(f"\n\nSide-by-side diff: {html}")
+    page = html.read_text()
+    assert '<td class="n">5</td><td class="delete">line <mark>5</mark></td>' in page
+    assert '<td class="insert">line <mark>&lt;5&gt;</mark></td>' in page
+
+    pending = tmp_path / "test_diff_options__diff_html@pysnap.snap.new"
+    reject_pending_snapshot(pending)
+    assert not html.exists()
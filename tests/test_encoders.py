@@ -12,6 +12,7 @@ from uuid import UUID
 import pytest
 
 from pysnaptest import (
+    SerializationError,
     assert_json_snapshot,
     is_jsonable_object,
     snapshot,
@@ -83,6 +84,21 @@ def test_to_jsonable_set_is_sorted_when_orderable():
     assert to_jsonable({3, 1, 2}) == [1, 2, 3]
 
 
+def test_to_jsonable_orders_mixed_sets_by_type_then_value():
+    mixed = {"b", 2, None, True, 0.5, (2, 1), frozenset({"z"}), Color.RED, "a"}
+
+    assert to_jsonable(mixed) == [None, True, 0.5, 2, "a", "b", "red", ["z"], [2, 1]]
+    assert to_jsonable(frozenset(reversed(list(mixed)))) == to_jsonable(mixed)
+
+
+def test_to_jsonable_sorts_sets_by_encoded_elements():
+    tags = {Color.RED, Color.GREEN}
+    days = frozenset({date(2024, 5, 1), date(2023, 12, 31)})
+
+    assert to_jsonable(tags) == ["green", "red"]
+    assert to_jsonable(days) == ["2023-12-31", "2024-05-01"]
+
+
 def test_to_jsonable_tuple_becomes_list():
     assert to_jsonable((1, 2, 3)) == [1, 2, 3]
 
@@ -107,10 +123,50 @@ def test_to_jsonable_custom_encoder_takes_priority():
     ]
 
 
-def test_to_jsonable_handles_reference_cycles():
-    a: dict = {}
-    a["self"] = a
-    assert to_jsonable(a) == {"self": "<circular reference>"}
+def test_to_jsonable_raises_on_reference_cycles():
+    a: dict = {"b": {}}
+    a["b"]["a"] = a
+    with pytest.raises(SerializationError, match=r"circular reference at path \.b\.a$"):
+        to_jsonable(a)
+
+
+def test_to_jsonable_allows_shared_references():
+    shared = {"id": 1}
+    assert to_jsonable({"a": shared, "b": [shared, shared]}) == {
+        "a": {"id": 1},
+        "b": [{"id": 1}, {"id": 1}],
+    }
+
+
+def test_to_jsonable_raises_on_cycles_through_dataclasses():
+    @dataclasses.dataclass
+    class Node:
+        name: str
+        children: list = dataclasses.field(default_factory=list)
+        parent: object = None
+
+    root = Node("root")
+    root.children.append(Node("leaf", parent=root))
+    with pytest.raises(SerializationError) as error:
+        to_jsonable({"tree": root})
+    assert str(error.value) == "circular reference at path .tree.children[0].parent"
+
+
+def test_to_jsonable_custom_encoder_applies_to_native_types():
+    assert to_jsonable({"totals": [1.4, 2.6]}, custom_encoder={float: round}) == {
+        "totals": [1, 3]
+    }
+
+
+def test_to_jsonable_handles_list_cycles_and_subclasses():
+    class Tags(list):
+        pass
+
+    items: list = [1]
+    items.append(items)
+    with pytest.raises(SerializationError, match=r"circular reference at path \[1\]$"):
+        to_jsonable(items)
+    assert to_jsonable({"tags": Tags([Color.RED])}) == {"tags": ["red"]}
 
 
 def test_to_jsonable_unknown_object_falls_back_to_str():
@@ -0,0 +1,100 @@
+"""Tests for transparent snapshot encryption at rest."""
+
+from __future__ import annotations
+
+import json
+import shutil
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import (
+    PySnapshot,
+    assert_binary_snapshot,
+    assert_csv_snapshot,
+    assert_json_snapshot,
+)
+
+KEY = "not-a-real-key"
+PAYLOAD = {"email": "ada@example.com", "id": 1}
+SNAPSHOT = (
+    Path(__file__).parent
+    / "snapshots"
+    / "test_encryption__test_encryption_test_encrypted_json_roundtrip@pysnap.snap"
+)
+
+
+def test_encrypted_json_roundtrip():
+    assert_json_snapshot(PAYLOAD, encryption_key=KEY)
+
+    assert "ada@example.com" not in SNAPSHOT.read_text(encoding="utf-8")
+    contents = PySnapshot.from_file(SNAPSHOT).contents(encryption_key=KEY)
+    assert json.loads(contents) == PAYLOAD
+
+
+def test_encrypted_snapshot_uses_env_key(monkeypatch):
+    monkeypatch.setenv("PYSNAPTEST_KEY", KEY)
+    assert json.loads(PySnapshot.from_file(SNAPSHOT).contents()) == PAYLOAD
+
+
+def test_encrypted_snapshot_wrong_key():
+    with pytest.raises(ValueError, match="Unable to decrypt"):
+        PySnapshot.from_file(SNAPSHOT).contents(encryption_key="wrong")
+
+
+def test_encrypted_snapshot_mismatch_shows_decrypted_diff(tmp_path: Path):
+    shutil.copy(SNAPSHOT, tmp_path / "test_encryption__encrypted@pysnap.snap")
+    with pytest.raises(AssertionError) as excinfo:
+        assert_json_snapshot(
+            {**PAYLOAD, "id": 2},
+            snapshot_path=str(tmp_path),
+            snapshot_name="encrypted",
+            encryption_key=KEY,
+        )
+    message = str(excinfo.value)
+    assert '-  "id": 1' in message
+    assert '+  "id": 2' in message
+
+
+def test_unencrypted_snapshot_asks_to_be_encrypted(tmp_path: Path, monkeypatch):
+    monkeypatch.setenv("INSTA_UPDATE", "no")
+    snapshot = tmp_path / "test_encryption__plaintext@pysnap.snap"
+    body = json.dumps(PAYLOAD, indent=2)
+    snapshot.write_text(f"---\nsource: src/lib.rs\n---\n{body}\n")
+
+    with pytest.raises(AssertionError, match="Update the snapshot to encrypt it"):
+        assert_json_snapshot(
+            PAYLOAD,
+            snapshot_path=str(tmp_path),
+            snapshot_name="plaintext",
+            encryption_key=KEY,
+        )
+
+
+def test_snapshots_are_encrypted_with_v2_keys():
+    body = SNAPSHOT.read_text(encoding="utf-8").rsplit("\n---\n", 1)[1].strip()
+
+    assert body.startswith("pysnaptest-encrypted:v2:")
+
+
+def test_csv_snapshot_is_encrypted_with_env_key(tmp_path: Path, monkeypatch):
+    monkeypatch.setenv("INSTA_WORKSPACE_ROOT", str(tmp_path))
+    monkeypatch.setenv("INSTA_UPDATE", "always")
+    monkeypatch.setenv("PYSNAPTEST_KEY", KEY)
+
+    assert_csv_snapshot(
+        "email,id\nada@example.com,1\n", snapshot_path=str(tmp_path), snapshot_name="table"
+    )
+
+    (snapshot,) = tmp_path.glob("*table@pysnap.snap")
+    assert "ada@example.com" not in snapshot.read_text(encoding="utf-8")
+    assert b"ada@example.com" in bytes(PySnapshot.from_file(snapshot).contents())
+
+
+def test_binary_snapshot_refuses_env_key(tmp_path: Path, monkeypatch):
+    monkeypatch.setenv("PYSNAPTEST_KEY", KEY)
+
+    with pytest.raises(ValueError, match="cannot be encrypted"):
+        assert_binary_snapshot(b"secret", snapshot_path=str(tmp_path), extension="bin")
+
+    assert not list(tmp_path.iterdir())
@@ -0,0 +1,83 @@
+"""Tests for explaining how snapshot file names are built."""
+
+from __future__ import annotations
+
+import json
+import os
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import assert_json_snapshot, explain_snapshot_name
+from pysnaptest.__main__ import main
+
+
+@pytest.fixture
+def project(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> Path:
+    (tmp_path / "tests").mkdir()
+    (tmp_path / "tests" / "test_api.py").write_text("def test_get():\n    pass\n")
+    monkeypatch.chdir(tmp_path)
+    monkeypatch.delenv("PYSNAPTEST_PREFIX", raising=False)
+    return tmp_path
+
+
+def test_explains_every_component(project: Path):
+    parts = explain_snapshot_name("tests/test_api.py::test_get")
+
+    assert parts["test_file"] == "tests/test_api.py"
+    assert Path(parts["folder"]) == (project / "tests" / "snapshots").resolve()
+    assert parts["prefix"] == "test_api"
+    assert parts["test_name"] == "test_api_test_get"
+    assert parts["counter"] == 1
+    assert parts["suffix"] == "@pysnap.snap"
+    assert parts["file_name"] == "test_api__test_api_test_get@pysnap.snap"
+    assert Path(parts["path"]) == Path(parts["folder"]) / parts["file_name"]
+    assert parts["exists"] is False
+
+    (project / "tests" / "snapshots").mkdir()
+    Path(parts["path"]).write_text("")
+    assert explain_snapshot_name("tests/test_api.py::test_get")["exists"] is True
+
+
+def test_explains_sanitized_parametrized_names(project: Path):
+    parts = explain_snapshot_name("tests/test_api.py::test_get[in/put:1]")
+
+    assert parts["test_name"] == "test_api_test_get[input1]"
+
+
+def test_explains_overrides(project: Path):
+    parts = explain_snapshot_name(
+        "tests/test_api.py::test_get",
+        {"snapshot_name": "custom", "prefix": "none", "allow_duplicates": True},
+    )
+
+    assert parts["prefix"] is None
+    assert parts["test_name"] == "custom"
+    assert parts["file_name"] == "custom@pysnap.snap"
+
+
+def test_rejects_unknown_overrides(project: Path):
+    with pytest.raises(ValueError, match='Unknown override "snapshot_folder"'):
+        explain_snapshot_name("tests/test_api.py::test_get", {"snapshot_folder": "x"})
+
+
+def test_rejects_missing_test_files(project: Path):
+    with pytest.raises(ValueError, match="tests/test_gone.py::test_get not found"):
+        explain_snapshot_name("tests/test_gone.py::test_get")
+
+
+def test_explains_the_file_the_assertion_writes():
+    node_id = os.environ["PYTEST_CURRENT_TEST"].split(" ")[0]
+    parts = explain_snapshot_name(node_id)
+
+    assert_json_snapshot({"explained": True})
+
+    assert parts["counter"] == 1
+    assert Path(parts["path"]).is_file()
+
+
+def test_cli_explain(project: Path, capsys: pytest.CaptureFixture[str]):
+    assert main(["explain", "tests/test_api.py::test_get", "--prefix", "none"]) == 0
+
+    parts = json.loads(capsys.readouterr().out)
+    assert parts["file_name"] == "test_api_test_get@pysnap.snap"
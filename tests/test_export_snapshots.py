@@ -0,0 +1,113 @@
+"""Tests for exporting snapshots to plain golden files and back."""
+
+from __future__ import annotations
+
+import json
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import export_snapshots, import_exported_snapshots
+from pysnaptest.__main__ import main
+
+TEXT = """---
+source: src/lib.rs
+description: "Test File Path: tests/test_api.py"
+---
+Hello, Ada
+"""
+
+JSON = """---
+source: src/lib.rs
+---
+{
+  "id": 7
+}
+"""
+
+BINARY = """---
+source: src/lib.rs
+snapshot_kind: binary
+---
+"""
+
+
+def _snapshots(tmp_path: Path) -> dict[str, Path]:
+    folder = tmp_path / "tests" / "snapshots"
+    folder.mkdir(parents=True)
+    paths = {}
+    for name, text in (("greeting", TEXT), ("user", JSON), ("logo", BINARY)):
+        path = folder / f"test_api__test_api_{name}@pysnap.snap"
+        path.write_text(text)
+        paths[name] = path
+    return paths
+
+
+def test_export_writes_bodies_and_manifest(tmp_path: Path):
+    _snapshots(tmp_path)
+    destination = tmp_path / "golden"
+
+    exported = export_snapshots(str(tmp_path), destination=str(destination))
+
+    folder = destination / "tests" / "snapshots"
+    assert exported == [
+        folder / "test_api__test_api_greeting.txt",
+        folder / "test_api__test_api_user.txt",
+    ]
+    assert exported[0].read_text() == "Hello, Ada\n"
+    assert exported[1].read_text() == '{\n  "id": 7\n}\n'
+    manifest = json.loads((destination / "manifest.json").read_text())
+    assert manifest["format"] == "raw"
+    assert manifest["snapshots"]["tests/snapshots/test_api__test_api_greeting.txt"] == {
+        "header": TEXT[: -len("Hello, Ada\n")],
+        "snapshot": "tests/snapshots/test_api__test_api_greeting@pysnap.snap",
+    }
+
+
+def test_export_round_trip_is_lossless(tmp_path: Path):
+    paths = _snapshots(tmp_path)
+    destination = tmp_path / "golden"
+    export_snapshots(str(tmp_path), destination=str(destination))
+    for path in paths.values():
+        path.unlink()
+
+    written = import_exported_snapshots(str(destination), str(tmp_path))
+
+    assert written == [paths["greeting"], paths["user"]]
+    assert paths["greeting"].read_text() == TEXT
+    assert paths["user"].read_text() == JSON
+
+
+def test_import_exported_takes_edited_bodies(tmp_path: Path):
+    paths = _snapshots(tmp_path)
+    destination = tmp_path / "golden"
+    exported = export_snapshots(str(tmp_path), destination=str(destination))
+    exported[0].write_text("Hello, Grace\n")
+
+    import_exported_snapshots(str(destination), str(tmp_path))
+
+    assert paths["greeting"].read_text() == TEXT.replace("Ada", "Grace")
+
+
+def test_export_rejects_unknown_format(tmp_path: Path):
+    with pytest.raises(ValueError, match="Unsupported export format"):
+        export_snapshots(str(tmp_path), format="yaml")  # type: ignore[arg-type]
+
+
+def test_import_exported_requires_manifest(tmp_path: Path):
+    with pytest.raises(ValueError, match="No manifest.json"):
+        import_exported_snapshots(str(tmp_path), str(tmp_path))
+
+
+def test_cli_export_and_import(tmp_path: Path, capsys):
+    paths = _snapshots(tmp_path)
+
+    assert main(["--root", str(tmp_path), "export"]) == 0
+    paths["user"].unlink()
+    destination = str(tmp_path / "exported_snapshots")
+    assert main(["--root", str(tmp_path), "import-exported", destination]) == 0
+
+    out = capsys.readouterr().out
+    assert "Exported 2 snapshot(s)." in out
+    assert "Wrote 2 snapshot(s) back." in out
+    assert paths["user"].read_text() == JSON
@@ -0,0 +1,65 @@
+"""Tests for passing mismatching snapshots and reporting their drift."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import assert_snapshot, pending_summary
+from pysnaptest.pytest_plugin import pytest_terminal_summary
+
+
+@pytest.fixture
+def force_pass(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> Path:
+    """Force-pass under a workspace root insta has not read a config for."""
+
+    monkeypatch.setenv("INSTA_WORKSPACE_ROOT", str(tmp_path))
+    monkeypatch.setenv("INSTA_FORCE_PASS", "1")
+    monkeypatch.setenv("INSTA_UPDATE", "new")
+    return tmp_path
+
+
+class _Reporter:
+    def __init__(self):
+        self.lines = []
+
+    def write_sep(self, sep, title):
+        self.lines.append(f"{sep * 2} {title} {sep * 2}")
+
+    def write_line(self, line, **markup):
+        self.lines.append(line)
+
+
+def test_mismatch_passes_with_its_diff_recorded(force_pass: Path):
+    snapshot = force_pass / "test_force_pass__drifted@pysnap.snap"
+    snapshot.write_text("---\nsource: src/lib.rs\n---\nold\n")
+
+    assert_snapshot("new", snapshot_path=str(force_pass), snapshot_name="drifted")
+
+    pending = Path(f"{snapshot}.new")
+    assert pending.is_file()
+    diff = pending_summary()["force_passed"][pending]
+    assert "-old" in diff and "+new" in diff
+    assert "\x1b[" not in diff
+
+
+def test_new_snapshot_is_recorded_without_diff(force_pass: Path):
+    assert_snapshot("new", snapshot_path=str(force_pass), snapshot_name="fresh")
+
+    (pending,) = force_pass.glob("*fresh@pysnap.snap.new")
+    assert pending_summary()["force_passed"][pending] is None
+
+
+def test_terminal_summary_reports_the_drift(force_pass: Path):
+    snapshot = force_pass / "test_force_pass__reported@pysnap.snap"
+    snapshot.write_text("---\nsource: src/lib.rs\n---\nold\n")
+    assert_snapshot("new", snapshot_path=str(force_pass), snapshot_name="reported")
+    config = type("Config", (), {"rootpath": force_pass})()
+    reporter = _Reporter()
+
+    pytest_terminal_summary(reporter, config)
+
+    assert "-- snapshot drift --" in reporter.lines
+    index = reporter.lines.index(f"  {snapshot.name}.new")
+    assert "-old" in "\n".join(reporter.lines[index + 1 :])
@@ -0,0 +1,50 @@
+"""Tests for process-wide default redactions."""
+
+from __future__ import annotations
+
+import pytest
+
+from pysnaptest import assert_json_snapshot, set_default_redactions
+from pysnaptest._pysnaptest import set_config_redactions
+from pysnaptest.pytest_plugin import load_config_redactions
+
+PAYLOAD = {"id": 8172, "created_at": "2026-10-16T09:30:00Z", "user": {"id": 3}}
+
+
+@pytest.fixture
+def defaults():
+    yield
+    set_default_redactions(None)
+    set_config_redactions(None)
+
+
+def test_default_redactions_apply_to_every_assertion(defaults):
+    set_config_redactions({".created_at": "[config]", "user": {"id": "[config]"}})
+    set_default_redactions({".created_at": "[timestamp]"})
+    assert_json_snapshot(PAYLOAD, redactions={".id": "[id]"})
+
+
+def test_call_redactions_override_defaults(defaults):
+    set_default_redactions({"id": "[default]", "created_at": "[timestamp]"})
+    assert_json_snapshot(PAYLOAD, redactions={".id": "[id]"})
+
+
+def test_set_default_redactions_replaces_and_clears(defaults):
+    set_default_redactions({".id": "[id]"})
+    set_default_redactions({".created_at": "[timestamp]"})
+    assert_json_snapshot(PAYLOAD)
+    set_default_redactions(None)
+    assert_json_snapshot(PAYLOAD)
+
+
+def test_load_config_redactions(tmp_path):
+    assert load_config_redactions(tmp_path) is None
+    (tmp_path / "pyproject.toml").write_text(
+        "[tool.pysnaptest.redactions]\n"
+        '".created_at" = "[timestamp]"\n'
+        'user = { id = "[id]" }\n'
+    )
+    assert load_config_redactions(tmp_path) == {
+        ".created_at": "[timestamp]",
+        "user": {"id": "[id]"},
+    }
@@ -0,0 +1,86 @@
+"""Tests for golden functions, which assert every call instead of replaying."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import characterize, extract_from_pytest_env, golden
+
+SNAPSHOTS = Path(__file__).parent / "snapshots"
+
+
+@golden
+def slugify(title: str, *, separator: str = "-") -> str:
+    return separator.join(title.lower().split())
+
+
+def test_golden_asserts_every_call():
+    assert slugify("Hello World") == "hello-world"
+    assert slugify("Snapshot  Testing", separator="_") == "snapshot_testing"
+
+
+def test_golden_wraps_the_function():
+    assert slugify.__wrapped__("A B") == "a-b"
+
+
+def test_golden_never_replays(tmp_path: Path):
+    committed = (
+        SNAPSHOTS / "test_golden__test_golden_test_golden_asserts_every_call_slugify@pysnap.snap"
+    )
+    (tmp_path / "test_golden__changed_slugify@pysnap.snap").write_text(
+        committed.read_text().replace('"hello-world"', '"hello_world"')
+    )
+    changed = golden(
+        slugify.__wrapped__,
+        extract_from_pytest_env(snapshot_path=str(tmp_path), snapshot_name="changed"),
+    )
+    with pytest.raises(AssertionError):
+        changed("Hello World")
+
+
+def test_golden_rejects_async_functions():
+    async def fetch():
+        return 1
+
+    with pytest.raises(TypeError, match="cannot wrap async function"):
+        golden(fetch)
+
+
+def legacy_price(quantity: int, member: bool = False) -> float:
+    price = quantity * 4.5
+    if quantity >= 10:
+        price *= 0.9
+    return round(price * (0.95 if member else 1.0), 2)
+
+
+def test_characterize():
+    results = characterize(legacy_price, [1, 10, (10, True)])
+
+    assert [result.input for result in results] == [1, 10, (10, True)]
+    assert {result.status for result in results} == {"unchanged"}
+    assert all(result.snapshot.exists() for result in results)
+    assert all(
+        result.snapshot.name.startswith(
+            "test_golden__test_golden_test_characterize_legacy_price-"
+        )
+        for result in results
+    )
+
+
+def test_characterize_reports_every_failure(tmp_path: Path):
+    for source in SNAPSHOTS.glob("test_golden__test_golden_test_characterize_legacy_*"):
+        target = tmp_path / source.name.replace("test_golden_test_characterize", "batch")
+        target.write_text(source.read_text())
+
+    def legacy_price(quantity: int, member: bool = False) -> float:
+        return 0.0
+
+    with pytest.raises(AssertionError, match="3 of 3 characterization snapshot"):
+        characterize(
+            legacy_price,
+            [1, 10, (10, True)],
+            snapshot_path=str(tmp_path),
+            snapshot_name="batch",
+        )
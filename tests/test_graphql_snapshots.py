@@ -0,0 +1,80 @@
+"""Tests for the GraphQL response snapshot preset."""
+
+from __future__ import annotations
+
+import json
+
+import pytest
+
+from pysnaptest import assert_graphql_snapshot
+
+QUERY = """
+query Viewer($first: Int = 2) {
+  viewer {
+    name
+    handle: login
+    ...Repositories
+  }
+}
+
+fragment Repositories on User {
+  repositories(first: $first, orderBy: {field: NAME, direction: ASC}) {
+    totalCount
+    nodes {
+      ... on Repository { name stargazerCount }
+    }
+  }
+}
+"""
+
+RESPONSE = {
+    "extensions": {
+        "tracing": {"startTime": "2026-10-16T09:30:00Z", "duration": 1200345},
+        "requestId": "b5f7e0c2",
+    },
+    "data": {
+        "viewer": {
+            "repositories": {
+                "totalCount": 2,
+                "nodes": [
+                    {"stargazerCount": 10, "name": "zeta"},
+                    {"stargazerCount": 3, "name": "alpha"},
+                ],
+            },
+            "name": "Octo Cat",
+            "handle": "octocat",
+        }
+    },
+}
+
+ERRORS = [
+    {
+        "path": ["viewer", "email"],
+        "extensions": {"code": "FORBIDDEN", "x-request-id": "3f9a"},
+        "message": "Not allowed",
+        "locations": [{"line": 4, "column": 5}],
+    }
+]
+
+
+def test_graphql_snapshot_in_selection_order():
+    assert_graphql_snapshot(RESPONSE, QUERY)
+
+
+def test_graphql_snapshot_of_json_text():
+    assert_graphql_snapshot(json.dumps({**RESPONSE, "errors": ERRORS}), QUERY)
+
+
+def test_graphql_snapshot_separate_errors():
+    assert_graphql_snapshot(
+        {**RESPONSE, "errors": ERRORS}, QUERY, separate_errors=True
+    )
+
+
+def test_graphql_snapshot_rejects_bad_input():
+    with pytest.raises(ValueError, match="Unable to parse GraphQL query"):
+        assert_graphql_snapshot(RESPONSE, "query { viewer { name }")
+    with pytest.raises(ValueError, match='no operation "Other"'):
+        assert_graphql_snapshot(RESPONSE, QUERY, operation_name="Other")
+    with pytest.raises(ValueError, match="must be an object"):
+        assert_graphql_snapshot([RESPONSE], QUERY)
@@ -0,0 +1,60 @@
+"""Tests for hash-only snapshots of large artifacts."""
+
+from __future__ import annotations
+
+import shutil
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import assert_hash_snapshot
+
+ARTIFACT = b"pretend this is a multi-gigabyte model checkpoint\n" * 64
+
+
+def test_hash_snapshot_bytes():
+    assert_hash_snapshot(ARTIFACT)
+
+
+def test_hash_snapshot_path_matches_bytes(tmp_path: Path):
+    artifact = tmp_path / "model.ckpt"
+    artifact.write_bytes(ARTIFACT)
+    assert_hash_snapshot(artifact)
+    snapshots = Path(__file__).parent / "snapshots"
+    from_path = snapshots / (
+        "test_hash_snapshots__test_hash_snapshots_test_hash_snapshot_path_matches_bytes"
+        "@pysnap.snap"
+    )
+    from_bytes = snapshots / (
+        "test_hash_snapshots__test_hash_snapshots_test_hash_snapshot_bytes@pysnap.snap"
+    )
+    assert from_path.read_text().split("---")[-1] == (
+        from_bytes.read_text().split("---")[-1]
+    )
+
+
+def test_hash_snapshot_sha512():
+    assert_hash_snapshot(ARTIFACT, algorithm="sha512")
+
+
+def test_hash_snapshot_detects_change(tmp_path: Path):
+    shutil.copy(
+        Path(__file__).parent
+        / "snapshots"
+        / "test_hash_snapshots__test_hash_snapshots_test_hash_snapshot_bytes@pysnap.snap",
+        tmp_path / "test_hash_snapshots__art@pysnap.snap",
+    )
+    with pytest.raises(AssertionError):
+        assert_hash_snapshot(
+            ARTIFACT + b"!", snapshot_path=str(tmp_path), snapshot_name="art"
+        )
+
+
+def test_hash_snapshot_rejects_unknown_algorithm():
+    with pytest.raises(ValueError, match="Unsupported hash algorithm"):
+        assert_hash_snapshot(ARTIFACT, algorithm="md5")
+
+
+def test_hash_snapshot_missing_file(tmp_path: Path):
+    with pytest.raises(ValueError, match="Unable to hash"):
+        assert_hash_snapshot(tmp_path / "missing.bin")
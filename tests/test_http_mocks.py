@@ -0,0 +1,144 @@
+"""Tests for recording and replaying HTTP calls through real client libraries.
+
+A stub transport stands in for the network, so recording works offline and the
+tests can tell whether a call was replayed (the stub is never reached).
+"""
+
+from __future__ import annotations
+
+import shutil
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import mock_http_json_snapshot
+
+requests = pytest.importorskip("requests")
+
+BASE_URL = "https://api.example.com"
+SNAPSHOTS = Path(__file__).parent / "snapshots"
+DECORATOR_TEST = "test_http_mocks_test_requests_decorator"
+
+
+class StubAdapter(requests.adapters.BaseAdapter):
+    def __init__(self):
+        super().__init__()
+        self.calls = 0
+
+    def send(self, request, **kwargs):
+        self.calls += 1
+        response = requests.Response()
+        response.status_code = 201 if request.method == "POST" else 200
+        response.headers["Content-Type"] = "application/json"
+        response._content = (
+            request.body.encode() if isinstance(request.body, str) else request.body
+        ) or b'{"id": 7, "name": "ada"}'
+        response.url = request.url
+        response.request = request
+        return response
+
+    def close(self):
+        pass
+
+
+@pytest.fixture
+def session():
+    session = requests.Session()
+    session.mount(BASE_URL, StubAdapter())
+    return session
+
+
+def test_requests_replay(session):
+    with mock_http_json_snapshot("requests.Session.request"):
+        user = session.get(f"{BASE_URL}/users/7", params={"expand": "team"})
+        created = session.post(f"{BASE_URL}/users", json={"name": "grace"})
+
+    assert session.get_adapter(BASE_URL).calls == 0
+    assert user.status_code == 200
+    assert user.json() == {"id": 7, "name": "ada"}
+    assert user.url == f"{BASE_URL}/users/7?expand=team"
+    assert created.status_code == 201
+    assert created.reason == "Created"
+    assert created.json() == {"name": "grace"}
+
+
+def test_requests_changed_request_fails(session, tmp_path):
+    _copy_recording(tmp_path, DECORATOR_TEST, "changed")
+
+    with pytest.raises(AssertionError, match="changed_http-[0-9a-f]{8}-request"):
+        with mock_http_json_snapshot(
+            "requests.Session.request",
+            snapshot_path=str(tmp_path),
+            snapshot_name="changed",
+        ):
+            session.delete(f"{BASE_URL}/users/8")
+
+
+@mock_http_json_snapshot("requests.Session.request")
+def test_requests_decorator(session):
+    response = session.delete(f"{BASE_URL}/users/7")
+    assert response.ok
+
+
+def test_unsupported_client():
+    with pytest.raises(ValueError, match="Unsupported HTTP client entry point"):
+        mock_http_json_snapshot("urllib.request.urlopen")
+
+
+def _copy_recording(tmp_path, test, name):
+    """Copy every call ``test`` recorded, renamed as the recordings of ``name``."""
+
+    for source in SNAPSHOTS.glob(f"test_http_mocks__mocks__{test}_http-*@pysnap.snap"):
+        shutil.copy(source, tmp_path / source.name.replace(test, name, 1))
+    return sorted(tmp_path.glob(f"test_http_mocks__mocks__{name}_http-*-request@pysnap.snap"))
+
+
+def test_requests_credentials_not_recorded(session):
+    with mock_http_json_snapshot("requests.Session.request"):
+        session.get(
+            f"{BASE_URL}/me",
+            headers={"Authorization": "Bearer s3cret", "X-Request-Id": "abc-123"},
+        )
+
+    (request_snapshot,) = SNAPSHOTS.glob(
+        "test_http_mocks__mocks__test_http_mocks_test_requests_"
+        "credentials_not_recorded_http-*-request@pysnap.snap"
+    )
+    recorded = request_snapshot.read_text()
+    assert "s3cret" not in recorded
+    assert "abc-123" not in recorded
+
+
+def test_requests_match_on_subset(session, tmp_path):
+    requests_recorded = _copy_recording(
+        tmp_path, "test_http_mocks_test_requests_replay", "loose"
+    )
+    before = [path.read_text() for path in requests_recorded]
+
+    with mock_http_json_snapshot(
+        "requests.Session.request",
+        snapshot_path=str(tmp_path),
+        snapshot_name="loose",
+        match_on=["method", "path"],
+    ):
+        response = session.get(f"{BASE_URL}/users/7", params={"expand": "all"})
+
+    assert session.get_adapter(BASE_URL).calls == 0
+    assert response.json() == {"id": 7, "name": "ada"}
+    assert [path.read_text() for path in requests_recorded] == before
+
+
+def test_requests_default_match_is_strict(session, tmp_path):
+    _copy_recording(tmp_path, "test_http_mocks_test_requests_replay", "strict")
+    with pytest.raises(AssertionError):
+        with mock_http_json_snapshot(
+            "requests.Session.request",
+            snapshot_path=str(tmp_path),
+            snapshot_name="strict",
+        ):
+            session.get(f"{BASE_URL}/users/7", params={"expand": "all"})
+
+
+def test_unknown_match_on_part():
+    with pytest.raises(ValueError, match="Unknown match_on part"):
+        mock_http_json_snapshot("requests.Session.request", match_on=["cookies"])
@@ -0,0 +1,77 @@
+"""Tests for scrubbing volatile image metadata before binary snapshots."""
+
+from __future__ import annotations
+
+import struct
+import zlib
+
+import pytest
+
+from pysnaptest import (
+    PySnapshot,
+    assert_binary_snapshot,
+    extract_from_pytest_env,
+    strip_image_metadata,
+)
+
+PNG_SIGNATURE = b"\x89PNG\r\n\x1a\n"
+
+
+def _chunk(kind: bytes, data: bytes) -> bytes:
+    crc = zlib.crc32(kind + data)
+    return struct.pack(">I", len(data)) + kind + data + struct.pack(">I", crc)
+
+
+def _png(*metadata: bytes) -> bytes:
+    """A 1x1 white RGB PNG with the given chunks before its pixel data."""
+
+    header = _chunk(b"IHDR", struct.pack(">IIBBBBB", 1, 1, 8, 2, 0, 0, 0))
+    pixels = _chunk(b"IDAT", zlib.compress(b"\x00\xff\xff\xff"))
+    return PNG_SIGNATURE + header + b"".join(metadata) + pixels + _chunk(b"IEND", b"")
+
+
+def _created(year: int) -> bytes:
+    return _chunk(b"tIME", struct.pack(">HBBBBB", year, 1, 2, 3, 4, 5))
+
+
+def _segment(marker: int, payload: bytes) -> bytes:
+    return bytes([0xFF, marker]) + struct.pack(">H", len(payload) + 2) + payload
+
+
+def _jpeg(*metadata: bytes) -> bytes:
+    """The marker structure of a JPEG, with the given segments after JFIF."""
+
+    jfif = _segment(0xE0, b"JFIF\x00\x01\x01\x00\x00\x01\x00\x01\x00\x00")
+    icc = _segment(0xE2, b"ICC_PROFILE\x00\x01\x01profile")
+    scan = _segment(0xDA, b"\x01\x01\x00\x00\x3f\x00") + b"\x12\x34\xff\x00\x56"
+    return b"\xff\xd8" + jfif + b"".join(metadata) + icc + scan + b"\xff\xd9"
+
+
+def test_png_drops_timestamps_and_text():
+    text = _chunk(b"tEXt", b"Software\x00renderer 1.2")
+    exif = _chunk(b"eXIf", b"MM\x00*\x00\x00\x00\x08")
+
+    assert strip_image_metadata(_png(_created(2025), text, exif)) == _png()
+    assert strip_image_metadata(_png()) == _png()
+
+
+def test_jpeg_drops_exif_xmp_and_comments():
+    exif = _segment(0xE1, b"Exif\x00\x00MM\x00*2025:01:02 03:04:05")
+    xmp = _segment(0xE1, b"http://ns.adobe.com/xap/1.0/\x00<x:xmpmeta/>")
+    comment = _segment(0xFE, b"rendered at 03:04")
+
+    assert strip_image_metadata(_jpeg(exif, xmp, comment)) == _jpeg()
+
+
+def test_rejects_other_data():
+    with pytest.raises(ValueError, match="PNG and JPEG images only"):
+        strip_image_metadata(b"GIF89a")
+    with pytest.raises(ValueError, match="truncated"):
+        strip_image_metadata(_png()[:-3])
+
+
+def test_assert_binary_snapshot_scrub_metadata():
+    assert_binary_snapshot(_png(_created(2026)), extension="png", scrub_metadata=True)
+
+    snapshot_path = extract_from_pytest_env().last_snapshot_path(None)
+    assert PySnapshot.from_file(snapshot_path).contents() == _png()
@@ -0,0 +1,177 @@
+"""Tests for importing syrupy and snapshottest snapshots."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import assert_json_snapshot, assert_snapshot, import_snapshots
+from pysnaptest.__main__ import main
+
+TESTS = """
+def test_get(): ...
+def test_render(): ...
+class TestOrders:
+    def test_list(self): ...
+"""
+
+AMBR = """# serializer version: 1
+# name: TestOrders.test_list[eu-1.5]
+  list([
+    dict({
+      'id': 1,
+      'total': 9.5,
+    }),
+  ])
+# ---
+# name: test_get
+  dict({
+    'name': 'Ada',
+    'roles': set({
+      'dev',
+      'admin',
+    }),
+  })
+# ---
+# name: test_get.1
+  'done'
+# ---
+# name: test_render
+  '''
+  <h1>Hi</h1>
+    <p>Ada</p>
+  '''
+# ---
+"""
+
+SNAPSHOTTEST = """# -*- coding: utf-8 -*-
+# snapshottest: v1 - https://goo.gl/zC4yUc
+from __future__ import unicode_literals
+
+from snapshottest import GenericRepr, Snapshot
+
+
+snapshots = Snapshot()
+
+snapshots['test_get 1'] = {
+    'name': 'Ada',
+    'roles': ('admin', 'dev')
+}
+
+snapshots['test_get 2'] = 'done'
+
+snapshots['TestOrders.test_list 1'] = GenericRepr('<Order 1>')
+"""
+
+
+def _project(tmp_path: Path) -> Path:
+    tests = tmp_path / "tests"
+    tests.mkdir()
+    (tests / "test_shop.py").write_text(TESTS)
+    return tests
+
+
+def _body(path: Path) -> str:
+    return path.read_text().split("\n---\n", 1)[1]
+
+
+def test_import_syrupy_snapshots(tmp_path: Path):
+    tests = _project(tmp_path)
+    (tests / "__snapshots__").mkdir()
+    (tests / "__snapshots__" / "test_shop.ambr").write_text(AMBR)
+
+    written = import_snapshots("syrupy", str(tmp_path))
+
+    folder = tests / "snapshots"
+    assert [path.name for path in written] == [
+        "test_shop__test_shop_TestOrders.test_list[eu-1.5]@pysnap.snap",
+        "test_shop__test_shop_test_get@pysnap.snap",
+        "test_shop__test_shop_test_get-2@pysnap.snap",
+        "test_shop__test_shop_test_render@pysnap.snap",
+    ]
+    assert all(path.parent == folder for path in written)
+    header = written[1].read_text().split("\n---\n", 1)[0]
+    assert 'description: "Test File Path: tests/test_shop.py"' in header
+    assert 'test: "tests/test_shop.py::test_get"' in header
+    assert "imported_from: syrupy" in header
+    assert _body(written[2]) == "done\n"
+    assert _body(written[3]) == "<h1>Hi</h1>\n  <p>Ada</p>\n"
+
+    # The imported snapshots are the ones the tests' assertions read.
+    snapshot_path = str(folder)
+    assert_json_snapshot(
+        {"name": "Ada", "roles": ["admin", "dev"]},
+        snapshot_path=snapshot_path,
+        snapshot_name="test_shop_test_get",
+        prefix="test_shop",
+    )
+    assert_json_snapshot(
+        [{"id": 1, "total": 9.5}],
+        snapshot_path=snapshot_path,
+        snapshot_name="test_shop_TestOrders.test_list[eu-1.5]",
+        prefix="test_shop",
+    )
+    assert_snapshot(
+        "<h1>Hi</h1>\n  <p>Ada</p>",
+        snapshot_path=snapshot_path,
+        snapshot_name="test_shop_test_render",
+        prefix="test_shop",
+    )
+
+
+def test_import_snapshottest_snapshots(tmp_path: Path):
+    tests = _project(tmp_path)
+    (tests / "snapshots").mkdir()
+    (tests / "snapshots" / "snap_test_shop.py").write_text(SNAPSHOTTEST)
+
+    written = import_snapshots("snapshottest", str(tmp_path))
+
+    assert [path.name for path in written] == [
+        "test_shop__test_shop_test_get@pysnap.snap",
+        "test_shop__test_shop_test_get-2@pysnap.snap",
+        "test_shop__test_shop_TestOrders.test_list@pysnap.snap",
+    ]
+    assert "imported_from: snapshottest" in written[0].read_text()
+    assert _body(written[0]) == (
+        '{\n  "name": "Ada",\n  "roles": [\n    "admin",\n    "dev"\n  ]\n}\n'
+    )
+    assert _body(written[2]) == "<Order 1>\n"
+
+
+def test_import_keeps_existing_snapshots(tmp_path: Path):
+    tests = _project(tmp_path)
+    (tests / "__snapshots__").mkdir()
+    (tests / "__snapshots__" / "test_shop.ambr").write_text(AMBR)
+    existing = tests / "snapshots" / "test_shop__test_shop_test_get@pysnap.snap"
+    existing.parent.mkdir()
+    existing.write_text("---\nsource: src/lib.rs\n---\nmine\n")
+
+    written = import_snapshots("syrupy", str(tmp_path))
+
+    assert existing not in written
+    assert len(written) == 3
+    assert existing.read_text() == "---\nsource: src/lib.rs\n---\nmine\n"
+
+
+def test_import_skips_snapshots_of_deleted_test_files(tmp_path: Path):
+    snapshots = tmp_path / "tests" / "__snapshots__"
+    snapshots.mkdir(parents=True)
+    (snapshots / "test_gone.ambr").write_text(AMBR)
+
+    assert import_snapshots("syrupy", str(tmp_path)) == []
+
+
+def test_import_rejects_unknown_library(tmp_path: Path):
+    with pytest.raises(ValueError, match="Unsupported snapshot library"):
+        import_snapshots("jest", str(tmp_path))  # type: ignore[arg-type]
+
+
+def test_cli_import(tmp_path: Path, capsys):
+    tests = _project(tmp_path)
+    (tests / "snapshots").mkdir()
+    (tests / "snapshots" / "snap_test_shop.py").write_text(SNAPSHOTTEST)
+
+    assert main(["--root", str(tmp_path), "import", "snapshottest"]) == 0
+
+    assert "Imported 3 snapshot(s) from snapshottest." in capsys.readouterr().out
@@ -0,0 +1,106 @@
+"""Tests for reading insta's environment variables the way insta does."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import assert_json_snapshot, get_effective_config, pending_summary
+
+
+@pytest.fixture
+def workspace(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> Path:
+    """A workspace root of its own, which insta has not read a config for."""
+
+    monkeypatch.setenv("INSTA_WORKSPACE_ROOT", str(tmp_path))
+    for name in (
+        "INSTA_UPDATE",
+        "INSTA_FORCE_UPDATE",
+        "INSTA_FORCE_PASS",
+        "INSTA_OUTPUT",
+        "CI",
+        "TF_BUILD",
+    ):
+        monkeypatch.delenv(name, raising=False)
+    return tmp_path
+
+
+def test_defaults(workspace: Path):
+    assert get_effective_config() == {
+        "update": "auto",
+        "behavior": "new_file",
+        "force_pass": False,
+        "output": "diff",
+        "workspace_root": workspace,
+        "ci": False,
+        "ignored": [],
+    }
+
+
+def test_values_from_the_environment(workspace: Path, monkeypatch):
+    monkeypatch.setenv("INSTA_UPDATE", "no")
+    monkeypatch.setenv("INSTA_FORCE_PASS", "1")
+    monkeypatch.setenv("INSTA_OUTPUT", "summary")
+    monkeypatch.setenv("CI", "true")
+
+    config = get_effective_config()
+
+    assert (config["update"], config["behavior"]) == ("no", "no_update")
+    assert (config["force_pass"], config["output"], config["ci"]) == (
+        True,
+        "summary",
+        True,
+    )
+
+
+@pytest.mark.parametrize(
+    ("env", "update", "behavior"),
+    [
+        ({"CI": "1"}, "auto", "no_update"),
+        ({"CI": "false"}, "auto", "new_file"),
+        ({"INSTA_UPDATE": "1"}, "always", "in_place"),
+        ({"INSTA_UPDATE": "unseen"}, "unseen", "new_file"),
+        ({"INSTA_FORCE_UPDATE": "1", "INSTA_UPDATE": "new"}, "force", "in_place"),
+    ],
+)
+def test_update_modes(workspace: Path, monkeypatch, env, update, behavior):
+    for name, value in env.items():
+        monkeypatch.setenv(name, value)
+
+    config = get_effective_config()
+
+    assert (config["update"], config["behavior"]) == (update, behavior)
+
+
+def test_invalid_value_raises_before_asserting(workspace: Path, monkeypatch):
+    monkeypatch.setenv("INSTA_OUTPUT", "verbose")
+
+    with pytest.raises(ValueError, match=r'Invalid INSTA_OUTPUT="verbose"'):
+        get_effective_config()
+    with pytest.raises(ValueError, match="expected diff, summary, minimal or none"):
+        assert_json_snapshot({"a": 1}, snapshot_path=str(workspace))
+    assert not list(workspace.iterdir())
+
+
+def test_values_are_kept_once_read(workspace: Path, monkeypatch):
+    monkeypatch.setenv("INSTA_UPDATE", "new")
+    with pytest.raises(AssertionError):
+        assert_json_snapshot({"a": 1}, snapshot_path=str(workspace))
+
+    monkeypatch.setenv("INSTA_UPDATE", "always")
+    config = get_effective_config()
+
+    assert config["update"] == "new"
+    assert config["ignored"] == ["INSTA_UPDATE"]
+
+
+def test_force_pass_leaves_the_snapshot_pending(workspace: Path, monkeypatch):
+    monkeypatch.setenv("INSTA_FORCE_PASS", "1")
+
+    assert_json_snapshot({"a": 1}, snapshot_path=str(workspace))
+
+    (pending,) = workspace.glob("*.snap.new")
+    assert str(pending) in {
+        str(path) for test in pending_summary()["tests"].values() for path in test["paths"]
+    }
@@ -0,0 +1,91 @@
+"""Tests for snapshots taken in interactive sessions."""
+
+from __future__ import annotations
+
+import os
+import subprocess
+import sys
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import SnapshotInfo
+from pysnaptest.interactive import end_session, start_session
+
+SESSION_SCRIPT = """
+import sys
+from pysnaptest import assert_json_snapshot
+from pysnaptest.interactive import (
+    accept_session, pending_session_snapshots, reject_session, start_session,
+)
+
+start_session("exploration", snapshot_dir=sys.argv[1])
+for rows in (3, 3):
+    try:
+        assert_json_snapshot({"rows": rows}, snapshot_name="summary")
+    except AssertionError:
+        print("pending", [p.name for p in pending_session_snapshots()])
+print("accepted", [p.name for p in accept_session()])
+
+start_session()
+assert_json_snapshot({"rows": 3}, snapshot_name="summary")
+try:
+    assert_json_snapshot({"rows": 4}, snapshot_name="summary")
+except AssertionError:
+    print("rejected", reject_session())
+"""
+
+
+@pytest.fixture
+def no_session(monkeypatch):
+    for name in ("PYTEST_CURRENT_TEST", "JPY_SESSION_NAME", "PYSNAPTEST_SESSION"):
+        monkeypatch.delenv(name, raising=False)
+    monkeypatch.delenv("PYSNAPTEST_SESSION_DIR", raising=False)
+    yield
+    end_session()
+
+
+def test_session_accept_workflow(tmp_path: Path):
+    env = {key: value for key, value in os.environ.items() if key != "PYTEST_CURRENT_TEST"}
+    env.pop("INSTA_UPDATE", None)
+    env.pop("CI", None)
+    result = subprocess.run(
+        [sys.executable, "-c", SESSION_SCRIPT, str(tmp_path)],
+        env=env,
+        capture_output=True,
+        text=True,
+    )
+    assert result.returncode == 0, result.stderr
+    # insta prints its own diff summaries to stdout too.
+    report = [
+        line
+        for line in result.stdout.splitlines()
+        if line.startswith(("pending", "accepted", "rejected"))
+    ]
+    assert report == [
+        "pending ['summary@pysnap.snap.new']",
+        "pending ['summary-2@pysnap.snap.new', 'summary@pysnap.snap.new']",
+        "accepted ['summary-2@pysnap.snap', 'summary@pysnap.snap']",
+        "rejected 1",
+    ]
+    assert sorted(p.name for p in tmp_path.iterdir()) == [
+        ".pysnaptest-manifest.jsonl",
+        "summary-2@pysnap.snap",
+        "summary@pysnap.snap",
+    ]
+
+
+def test_session_named_after_notebook(no_session, monkeypatch, tmp_path: Path):
+    notebook = tmp_path / "analysis" / "Pricing Study.ipynb"
+    monkeypatch.setenv("JPY_SESSION_NAME", str(notebook))
+    info = start_session()
+    assert Path(info.snapshot_folder()) == tmp_path / "analysis" / "snapshots" / "Pricing Study"
+    assert info.next_snapshot_name() == "Pricing Study"
+    assert SnapshotInfo.from_pytest().next_snapshot_name() == "Pricing Study"
+
+
+def test_without_session(no_session):
+    with pytest.raises(ValueError, match="start an interactive session"):
+        SnapshotInfo.from_pytest()
+    with pytest.raises(ValueError, match="No interactive session"):
+        start_session()
@@ -0,0 +1,87 @@
+"""Tests for choosing how JSON snapshots are laid out."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import SnapshotSettings, assert_json_snapshot, set_json_format
+
+RESULT = {
+    "user": {"id": 7, "name": "Ada", "token": "s3cr3t"},
+    "scores": [1.5, 2.0, 3.25],
+    "tags": [],
+}
+
+
+@pytest.fixture
+def formatting():
+    yield
+    set_json_format(None)
+
+
+def _body(folder: Path) -> str:
+    (pending,) = folder.glob("*.snap.new")
+    return pending.read_text().split("---\n", 2)[2]
+
+
+def _assert_new(folder: Path, result=RESULT, **kwargs) -> str:
+    with pytest.raises(AssertionError):
+        assert_json_snapshot(
+            result, snapshot_path=str(folder), snapshot_name="result", **kwargs
+        )
+    return _body(folder)
+
+
+def test_compact_json():
+    assert_json_snapshot(
+        RESULT, json_format="compact", redactions={".user.token": "[token]"}
+    )
+
+
+def test_yaml():
+    assert_json_snapshot(RESULT, json_format="yaml")
+
+
+def test_pretty_json_within_a_line_width():
+    assert_json_snapshot(RESULT, max_line_width=40)
+
+
+def test_compact_json_wider_than_the_line_width_is_pretty(tmp_path: Path):
+    body = _assert_new(tmp_path, json_format="compact", max_line_width=20)
+
+    assert body == _assert_new(tmp_path / "pretty")
+
+
+def test_layout_keeps_scalars_as_insta_writes_them(tmp_path: Path):
+    result = {"b": 'a, "quoted" [string]', "a": [1e-9, 10.0, None, True]}
+
+    body = _assert_new(tmp_path, result, json_format="compact")
+
+    assert body == (
+        '{"a": [0.000000001, 10.0, null, true], "b": "a, \\"quoted\\" [string]"}\n'
+    )
+
+
+def test_process_settings_and_assertion_formats(tmp_path: Path, formatting):
+    set_json_format("compact")
+    assert _assert_new(tmp_path / "process").count("\n") == 1
+
+    settings = SnapshotSettings(json_format="yaml")
+    assert settings.json_format == "yaml"
+    assert _assert_new(tmp_path / "settings", settings=settings).startswith("scores:")
+
+    with settings:
+        body = _assert_new(tmp_path / "assertion", json_format="pretty")
+    assert body == _assert_new(tmp_path / "default", json_format="pretty")
+    assert body.startswith("{\n")
+
+
+def test_invalid_json_format(tmp_path: Path, formatting):
+    with pytest.raises(ValueError, match='Invalid JSON format "tabs"'):
+        assert_json_snapshot(RESULT, snapshot_path=str(tmp_path), json_format="tabs")
+    with pytest.raises(ValueError, match="Invalid JSON format"):
+        set_json_format("tabs")
+    with pytest.raises(ValueError, match="Invalid JSON format"):
+        SnapshotSettings(json_format="tabs")
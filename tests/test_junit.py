@@ -0,0 +1,91 @@
+"""Tests for the machine-readable payload of snapshot failures."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import (
+    SnapshotMismatchError,
+    assert_binary_snapshot,
+    assert_snapshot,
+    format_junit_system_out,
+    set_diff_options,
+)
+
+
+@pytest.fixture(autouse=True)
+def colored_diffs():
+    # The payload's diff is uncolored and whole whatever the message's is.
+    set_diff_options(color=True, max_lines=1)
+    yield
+    set_diff_options()
+
+
+def _mismatch(tmp_path: Path, name: str) -> SnapshotMismatchError:
+    snapshot = tmp_path / f"test_junit__{name}@pysnap.snap"
+    snapshot.write_text("---\nsource: src/lib.rs\n---\nHello, Ada\n")
+    with pytest.raises(SnapshotMismatchError) as excinfo:
+        assert_snapshot("Hello, Grace", snapshot_path=str(tmp_path), snapshot_name=name)
+    return excinfo.value
+
+
+def test_mismatch_carries_a_snapshot_failure(tmp_path: Path):
+    error = _mismatch(tmp_path, "junit_payload")
+
+    assert isinstance(error, AssertionError)
+    expected = tmp_path / "test_junit__junit_payload@pysnap.snap"
+    assert error.snapshot_failure == {
+        "snapshot": "junit_payload",
+        "expected_path": str(expected),
+        "actual_path": f"{expected}.new",
+        "diff": "--- committed\n+++ new\n@@ -1,1 +1,1 @@\n-Hello, Ada\n+Hello, Grace",
+        "html_path": None,
+    }
+
+
+def test_format_junit_system_out(tmp_path: Path):
+    error = _mismatch(tmp_path, "junit_system_out")
+    expected = tmp_path / "test_junit__junit_system_out@pysnap.snap"
+
+    assert format_junit_system_out(error) == (
+        f"[[ATTACHMENT|{expected}.new]]\n"
+        "Snapshot 'junit_system_out' did not match.\n"
+        f"Expected: {expected}\n"
+        f"Actual: {expected}.new\n"
+        "\n"
+        "--- committed\n+++ new\n@@ -1,1 +1,1 @@\n-Hello, Ada\n+Hello, Grace\n"
+    )
+    assert format_junit_system_out(error.snapshot_failure) == format_junit_system_out(
+        error
+    )
+
+
+def test_format_junit_system_out_attaches_the_html_diff(tmp_path: Path):
+    set_diff_options(html=True)
+
+    error = _mismatch(tmp_path, "junit_html")
+
+    html = f"{tmp_path / 'test_junit__junit_html@pysnap.snap'}.new.html"
+    assert error.snapshot_failure["html_path"] == html
+    assert format_junit_system_out(error).splitlines()[1] == f"[[ATTACHMENT|{html}]]"
+
+
+def test_binary_mismatch_has_no_snapshot_failure(tmp_path: Path):
+    (tmp_path / "test_junit__junit_binary@pysnap.snap").write_text(
+        "---\nsource: src/lib.rs\nsnapshot_kind: binary\nextension: bin\n---\n"
+    )
+    (tmp_path / "test_junit__junit_binary@pysnap.snap.bin").write_bytes(b"\x00")
+
+    with pytest.raises(SnapshotMismatchError) as excinfo:
+        assert_binary_snapshot(
+            b"\x01", extension="bin", snapshot_path=str(tmp_path), snapshot_name="junit_binary"
+        )
+
+    assert excinfo.value.snapshot_failure is None
+    assert format_junit_system_out(excinfo.value) == ""
+
+
+def test_format_junit_system_out_ignores_other_errors():
+    assert format_junit_system_out(AssertionError("boom")) == ""
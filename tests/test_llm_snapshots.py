@@ -0,0 +1,111 @@
+"""Tests for LLM response snapshots and the redaction presets they use."""
+
+from __future__ import annotations
+
+import pytest
+
+from pysnaptest import (
+    assert_llm_snapshot,
+    mock_llm_snapshot,
+    register_preset,
+)
+from pysnaptest.presets import get_preset, resolve_redactions
+
+OPENAI_RESPONSE = {
+    "id": "chatcmpl-9zX1",
+    "object": "chat.completion",
+    "created": 1718000000,
+    "model": "gpt-4o-mini",
+    "system_fingerprint": "fp_44709d6fcb",
+    "choices": [
+        {
+            "index": 0,
+            "finish_reason": "tool_calls",
+            "message": {
+                "role": "assistant",
+                "content": None,
+                "tool_calls": [
+                    {
+                        "id": "call_abc123",
+                        "type": "function",
+                        "function": {
+                            "name": "get_weather",
+                            "arguments": '{"city": "Paris"}',
+                        },
+                    }
+                ],
+            },
+        }
+    ],
+    "usage": {"prompt_tokens": 82, "completion_tokens": 17, "total_tokens": 99},
+}
+
+ANTHROPIC_RESPONSE = {
+    "id": "msg_01XFDUDYJgAACzvnptvVoYEL",
+    "type": "message",
+    "role": "assistant",
+    "model": "claude-sonnet-4-5",
+    "content": [
+        {"type": "text", "text": "Let me check the weather."},
+        {
+            "type": "tool_use",
+            "id": "toolu_01A09q90qw90lq917835lq9",
+            "name": "get_weather",
+            "input": {"city": "Paris"},
+        },
+    ],
+    "stop_reason": "tool_use",
+    "usage": {"input_tokens": 310, "output_tokens": 54},
+}
+
+
+def test_llm_snapshot_openai():
+    assert_llm_snapshot(OPENAI_RESPONSE, preset="openai")
+
+
+def test_llm_snapshot_anthropic():
+    assert_llm_snapshot(ANTHROPIC_RESPONSE)
+
+
+def test_llm_snapshot_ignores_volatile_fields():
+    rerun = {
+        **ANTHROPIC_RESPONSE,
+        "id": "msg_other",
+        "usage": {"input_tokens": 1, "output_tokens": 2},
+    }
+    assert_llm_snapshot(
+        rerun,
+        snapshot_name="test_llm_snapshots_test_llm_snapshot_anthropic",
+        allow_duplicates=True,
+    )
+
+
+def test_mock_llm_snapshot_replays_scrubbed_response():
+    def create(**kwargs):
+        return OPENAI_RESPONSE
+
+    mocked = mock_llm_snapshot(create)
+    result = mocked(model="gpt-4o-mini", messages=[{"role": "user", "content": "hi"}])
+
+    message = result["choices"][0]["message"]
+    assert message["tool_calls"][0]["id"] == "[tool_call_id]"
+    assert message["tool_calls"][0]["function"]["name"] == "get_weather"
+    assert result["usage"]["total_tokens"] == "[tokens]"
+
+
+def test_redactions_override_preset():
+    merged = resolve_redactions("openai", {".model": "[model]", ".id": "[rid]"})
+    assert merged[".model"] == "[model]"
+    assert merged[".id"] == "[rid]"
+    assert merged[".created"] == "[created]"
+
+
+def test_register_preset():
+    register_preset("trace", {".trace_id": "[trace]"})
+    assert get_preset("trace") == {".trace_id": "[trace]"}
+    assert resolve_redactions(["anthropic", "trace"])[".trace_id"] == "[trace]"
+
+
+def test_unknown_preset():
+    with pytest.raises(ValueError, match="Unknown redaction preset 'gemini'"):
+        assert_llm_snapshot(OPENAI_RESPONSE, preset="gemini")
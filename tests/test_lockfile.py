@@ -0,0 +1,29 @@
+"""Tests for serialized writes to one snapshot."""
+
+from __future__ import annotations
+
+from concurrent.futures import ThreadPoolExecutor
+from pathlib import Path
+
+from pysnaptest import SnapshotInfo
+from pysnaptest._pysnaptest import assert_snapshot as _assert_snapshot
+
+
+def test_concurrent_pending_writes_do_not_interleave(tmp_path: Path):
+    info = SnapshotInfo.manual(tmp_path, "shared", allow_duplicates=True)
+    values = [f"{worker}\n" * 200 for worker in "abcdefgh"]
+
+    def assert_value(value: str) -> bool:
+        try:
+            _assert_snapshot(info, value)
+        except AssertionError:
+            return False
+        return True
+
+    with ThreadPoolExecutor(max_workers=len(values)) as pool:
+        outcomes = list(pool.map(assert_value, values))
+
+    assert not any(outcomes)
+    (pending,) = tmp_path.glob("*.snap.new")
+    body = pending.read_text().split("\n---\n", 1)[1]
+    assert body in values
@@ -0,0 +1,81 @@
+"""Tests for snapshots of log records."""
+
+from __future__ import annotations
+
+import logging
+import sys
+
+import pytest
+
+from pysnaptest import assert_logs_snapshot
+
+
+def _record(
+    level: int, name: str, msg: str, *args, exc_info=None
+) -> logging.LogRecord:
+    return logging.LogRecord(name, level, __file__, 1, msg, args, exc_info)
+
+
+def test_logs_snapshot_from_records():
+    try:
+        raise ConnectionError("connection reset")
+    except ConnectionError:
+        exc_info = sys.exc_info()
+    assert_logs_snapshot(
+        [
+            _record(
+                logging.INFO,
+                "app.db",
+                "connected to %s in %dms",
+                "/var/lib/app/data.db",
+                12,
+            ),
+            _record(
+                logging.WARNING,
+                "app.worker",
+                "retrying job 0b0c8f2e-2b7a-4c1e-9f3e-5d1a2b3c4d5e on Thread-7 (pid=48213)",
+            ),
+            _record(
+                logging.ERROR,
+                "app.api",
+                "request failed\nat 2026-10-16T09:30:00.123Z",
+                exc_info=exc_info,
+            ),
+        ]
+    )
+
+
+def test_logs_snapshot_from_dicts():
+    assert_logs_snapshot(
+        [
+            {
+                "levelname": "DEBUG",
+                "name": "cache",
+                "message": "hit <Entry at 0x7f3a9c2b1e50>",
+            },
+            {"level": 30, "logger": "cache", "msg": "evicted at 12:04:55.120"},
+            {
+                "levelname": "INFO",
+                "message": "config loaded from C:\\Users\\ci\\app.toml",
+            },
+        ]
+    )
+
+
+def test_logs_snapshot_without_normalizing():
+    assert_logs_snapshot(
+        [{"levelname": "INFO", "name": "clock", "message": "tick at 12:00:00"}],
+        normalize=False,
+    )
+
+
+def test_logs_snapshot_rejects_incomplete_records():
+    with pytest.raises(ValueError, match="Log record 1 has no message"):
+        assert_logs_snapshot(
+            [
+                {"levelname": "INFO", "message": "ok"},
+                {"levelname": "INFO"},
+            ]
+        )
+    with pytest.raises(ValueError, match="Log record 0 has no level"):
+        assert_logs_snapshot([{"message": "no level"}])
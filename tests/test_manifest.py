@@ -0,0 +1,115 @@
+"""Tests for the manifest recording the snapshots pysnaptest writes."""
+
+from __future__ import annotations
+
+import os
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import (
+    ManifestIssue,
+    assert_snapshot,
+    read_manifest,
+    verify_manifest,
+)
+from pysnaptest.__main__ import main
+from pysnaptest._pysnaptest import (
+    MANIFEST_FILE,
+    accept_pending_snapshot,
+    delete_snapshot,
+    observe_test_stage,
+    snapshot_content_hash,
+)
+
+
+@pytest.fixture
+def updating(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> Path:
+    """``INSTA_UPDATE=always`` under a workspace root insta has not read a
+    config for."""
+
+    monkeypatch.setenv("INSTA_WORKSPACE_ROOT", str(tmp_path))
+    monkeypatch.setenv("INSTA_UPDATE", "always")
+    monkeypatch.delenv("INSTA_FORCE_PASS", raising=False)
+    monkeypatch.delenv("PYSNAPTEST_UPDATE_FILTER", raising=False)
+    return tmp_path
+
+
+def _rerun() -> None:
+    """Start the running test again, so its snapshots are numbered afresh."""
+
+    observe_test_stage(os.environ["PYTEST_CURRENT_TEST"].rsplit(" (", 1)[0], "setup")
+
+
+def test_created_and_updated_snapshots_are_recorded(updating: Path):
+    for value in ("one", "one", "two"):
+        _rerun()
+        assert_snapshot(value, snapshot_path=str(updating), snapshot_name="tracked")
+
+    created, updated = read_manifest(updating)
+    (snapshot,) = updating.glob("*@pysnap.snap")
+    assert created.path == updated.path == snapshot
+    assert (created.change, updated.change) == ("created", "updated")
+    assert created.test == (
+        "tests/test_manifest.py::test_created_and_updated_snapshots_are_recorded"
+    )
+    assert created.sha256 != updated.sha256 == snapshot_content_hash(snapshot)
+    assert verify_manifest(str(updating)) == []
+
+
+def test_verify_reports_edited_unrecorded_and_missing(updating: Path):
+    for name in ("edited", "deleted"):
+        assert_snapshot("value", snapshot_path=str(updating), snapshot_name=name)
+    edited = next(updating.glob("*edited@pysnap.snap"))
+    edited.write_text(edited.read_text().replace("value", "tampered"))
+    deleted = next(updating.glob("*deleted@pysnap.snap"))
+    deleted.unlink()
+    unrecorded = updating / "test_manifest__by_hand@pysnap.snap"
+    unrecorded.write_text("---\nsource: src/lib.rs\n---\nhand made\n")
+
+    assert verify_manifest(str(updating)) == [
+        ManifestIssue(unrecorded, "unrecorded"),
+        ManifestIssue(deleted, "missing"),
+        ManifestIssue(edited, "edited"),
+    ]
+    assert main(["--root", str(updating), "verify-manifest"]) == 1
+
+
+def test_header_rewrites_do_not_count_as_edits(updating: Path):
+    assert_snapshot("value", snapshot_path=str(updating), snapshot_name="header")
+    (snapshot,) = updating.glob("*@pysnap.snap")
+    snapshot.write_text(snapshot.read_text().replace("---\n", "---\ninfo: 1\n", 1))
+
+    assert verify_manifest(str(updating)) == []
+
+
+def test_deleting_drops_the_entries(updating: Path):
+    for name in ("kept", "dropped"):
+        assert_snapshot("value", snapshot_path=str(updating), snapshot_name=name)
+
+    delete_snapshot(next(updating.glob("*dropped@pysnap.snap")))
+
+    (entry,) = read_manifest(updating)
+    assert entry.path.name.endswith("kept@pysnap.snap")
+    assert main(["--root", str(updating), "verify-manifest"]) == 0
+
+
+def test_accepting_records_the_snapshot(updating: Path, monkeypatch):
+    monkeypatch.setenv("INSTA_UPDATE", "new")
+
+    with pytest.raises(AssertionError):
+        assert_snapshot("value", snapshot_path=str(updating), snapshot_name="pending")
+    assert not (updating / MANIFEST_FILE).exists()
+    (pending,) = updating.glob("*.snap.new")
+
+    target = accept_pending_snapshot(pending)
+
+    (entry,) = read_manifest(updating)
+    assert (entry.path, entry.change) == (target, "created")
+    assert entry.test == "tests/test_manifest.py::test_accepting_records_the_snapshot"
+
+
+def test_folders_without_a_manifest_are_not_checked(tmp_path: Path):
+    (tmp_path / "test_manifest__old@pysnap.snap").write_text("---\n---\nold\n")
+
+    assert verify_manifest(str(tmp_path)) == []
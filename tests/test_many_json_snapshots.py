@@ -0,0 +1,77 @@
+"""Tests for asserting many labeled JSON snapshots in one call."""
+
+from __future__ import annotations
+
+import shutil
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import assert_json_snapshot, assert_many_json_snapshots
+from pysnaptest.review import accept_pending_snapshot
+
+VALUES = {
+    "user": {"id": 7, "name": "Ada", "token": "s3cr3t"},
+    "totals": [1.5, 2.25],
+    "empty": {},
+}
+TEST = "test_many_json_snapshots_test_each_value_is_stored_like_a_separate_assertion"
+
+
+def _body(path: Path) -> str:
+    return path.read_text().split("---\n", 2)[2]
+
+
+def test_assert_many_json_snapshots():
+    assert_many_json_snapshots(
+        VALUES, redactions={".token": "[token]"}, description="Batch of values"
+    )
+
+
+def test_each_value_is_stored_like_a_separate_assertion(tmp_path: Path):
+    batch, single = tmp_path / "batch", tmp_path / "single"
+
+    with pytest.raises(AssertionError) as error:
+        assert_many_json_snapshots(
+            VALUES, snapshot_path=str(batch), redactions={".token": "[token]"}
+        )
+    for label, value in VALUES.items():
+        with pytest.raises(AssertionError):
+            assert_json_snapshot(
+                value,
+                snapshot_path=str(single),
+                snapshot_name=f"{TEST}_{label}",
+                redactions={".token": "[token]"},
+            )
+
+    assert str(error.value).startswith("3 of 3 JSON snapshot(s) did not match:")
+    assert "  empty (" in str(error.value)
+    assert "new, pending review" in str(error.value)
+    pending = sorted(batch.glob("*.snap.new"))
+    assert [path.name for path in pending] == [
+        path.name for path in sorted(single.glob("*.snap.new"))
+    ]
+    for path in pending:
+        assert _body(path) == _body(single / path.name)
+    assert "[token]" in _body(batch / pending[-1].name)
+
+
+def test_only_changed_values_are_reported(tmp_path: Path):
+    recorded = tmp_path / "recorded"
+    with pytest.raises(AssertionError):
+        assert_many_json_snapshots(VALUES, snapshot_path=str(recorded))
+    for pending in recorded.glob("*.snap.new"):
+        accept_pending_snapshot(pending)
+    # A fresh folder numbers its snapshots from the start again.
+    replayed = tmp_path / "replayed"
+    shutil.copytree(recorded, replayed)
+
+    with pytest.raises(AssertionError) as error:
+        assert_many_json_snapshots(
+            {**VALUES, "totals": [1.5, 3.0]}, snapshot_path=str(replayed)
+        )
+
+    assert str(error.value).splitlines() == [
+        "1 of 3 JSON snapshot(s) did not match:",
+        f"  totals ({next(replayed.glob('*_totals@pysnap.snap'))}): changed",
+    ]
@@ -0,0 +1,61 @@
+"""Tests for memory-mapped access to large snapshots."""
+
+from __future__ import annotations
+
+import json
+from pathlib import Path
+
+from pysnaptest import PySnapshot
+from pysnaptest._pysnaptest import SnapshotBuffer, read_json_snapshot
+
+LARGE = bytes(range(256)) * 8192  # 2 MiB, above the 1 MiB mapping threshold
+HEADER = "---\nsource: src/lib.rs\nextension: bin\nsnapshot_kind: binary\n---\n"
+
+
+def write_binary_snapshot(tmp_path: Path, data: bytes) -> Path:
+    snapshot = tmp_path / "pysnaptest__large@pysnap.snap"
+    snapshot.write_text(HEADER)
+    Path(f"{snapshot}.bin").write_bytes(data)
+    return snapshot
+
+
+def test_large_binary_snapshot_is_memory_mapped(tmp_path: Path):
+    contents = PySnapshot.from_file(write_binary_snapshot(tmp_path, LARGE)).contents()
+
+    assert isinstance(contents, SnapshotBuffer)
+    assert len(contents) == len(LARGE)
+    assert contents == LARGE
+    assert bytes(contents) == LARGE
+    view = memoryview(contents)
+    assert view.readonly
+    assert view[:4].tobytes() == LARGE[:4]
+
+
+def test_small_binary_snapshot_returns_bytes(tmp_path: Path):
+    contents = PySnapshot.from_file(write_binary_snapshot(tmp_path, b"abc")).contents()
+    assert contents == b"abc"
+    assert isinstance(contents, bytes)
+
+
+def test_large_mock_recording_replays_from_mapping(tmp_path: Path):
+    response = {"rows": [{"id": i, "name": f"row-{i}"} for i in range(60_000)]}
+    snapshot = tmp_path / "pysnaptest__mocks__large-response@pysnap.snap"
+    snapshot.write_text(
+        "---\nsource: src/mocks.rs\nexpression: res\n---\n"
+        + json.dumps(response, indent=2)
+    )
+    assert snapshot.stat().st_size > 1024 * 1024
+    assert read_json_snapshot(snapshot) == response
+
+
+def test_snapshots_insta_rewrites_in_place_are_copied(tmp_path: Path, monkeypatch):
+    monkeypatch.setenv("INSTA_WORKSPACE_ROOT", str(tmp_path))
+    monkeypatch.setenv("INSTA_UPDATE", "always")
+    snapshot = write_binary_snapshot(tmp_path, LARGE)
+    contents = PySnapshot.from_file(snapshot).contents()
+
+    # As insta's in-place rewrite does: truncate, then write.
+    Path(f"{snapshot}.bin").write_bytes(b"short")
+
+    assert isinstance(contents, SnapshotBuffer)
+    assert contents == LARGE
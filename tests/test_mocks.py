@@ -8,9 +8,43 @@ values are normalized through `to_jsonable` before crossing into Rust) and
 
 from __future__ import annotations
 
+import json
+import os
+import shutil
+import time
+from datetime import datetime, timezone
+from pathlib import Path
+from typing import Callable, NamedTuple
+
 import pytest
 
-from pysnaptest import mock_json_snapshot
+from pysnaptest import (
+    RECORDED,
+    find_expired_recordings,
+    mock_json_snapshot,
+    register_replay_decoder,
+    register_result_factory,
+)
+from pysnaptest._pysnaptest import (
+    SnapshotInfo,
+    observe_test_stage,
+    prepare_mock_call,
+    read_json_snapshot,
+    recorded_at,
+    recorded_by,
+    recorded_duration_ms,
+    recorded_type,
+)
+
+SNAPSHOTS = Path(__file__).parent / "snapshots"
+
+
+def _recording(stem: str) -> Path:
+    """The response snapshot recorded for ``stem``'s (single) call."""
+
+    (path,) = SNAPSHOTS.glob(f"test_mocks__mocks__{stem}-????????@pysnap.snap")
+    return path
+
 
 pydantic = pytest.importorskip("pydantic")
 
@@ -39,3 +73,438 @@ async def test_mock_json_snapshot_async():
     result = await mocked(7)
 
     assert result == {"id": 7, "name": "grace"}
+
+
+def test_mock_json_snapshot_simulated_latency():
+    def slow_lookup(user_id: int) -> dict:
+        time.sleep(0.2)
+        return {"id": user_id}
+
+    mocked = mock_json_snapshot(func=slow_lookup, simulate_latency=0.5)
+    start = time.perf_counter()
+    assert mocked(3) == {"id": 3}
+    elapsed = time.perf_counter() - start
+
+    recorded = recorded_duration_ms(
+        _recording("test_mocks_test_mock_json_snapshot_simulated_latency_slow_lookup")
+    )
+    assert recorded >= 200
+    assert elapsed >= recorded / 1000 * 0.5
+
+
+def test_recorded_duration_missing_for_old_recordings(tmp_path: Path):
+    path = tmp_path / "test_mocks__mocks__old_fetch_user@pysnap.snap"
+    path.write_text('---\nsource: src/mocks.rs\nexpression: res\n---\n{"id": 7}\n')
+    assert recorded_duration_ms(path) is None
+
+
+def test_mock_json_snapshot_side_effects():
+    calls = []
+
+    def charge(amount: int) -> dict:
+        calls.append(amount)
+        return {"charged": amount}
+
+    mocked = mock_json_snapshot(
+        func=charge,
+        side_effects=[ConnectionError("card network down"), {"charged": 0}, RECORDED],
+    )
+
+    with pytest.raises(ConnectionError, match="card network down"):
+        mocked(5)
+    assert mocked(5) == {"charged": 0}
+    assert mocked(5) == {"charged": 5}
+    # Past the end of the list, calls record or replay as usual.
+    assert mocked(7) == {"charged": 7}
+    assert calls == []  # both replayed from the committed recordings
+
+
+def exchange_rate(currency: str) -> dict:
+    return {"currency": currency, "rate": 1.25}
+
+
+def test_mock_json_snapshot_spy():
+    calls = []
+
+    def tracked(currency: str) -> dict:
+        calls.append(currency)
+        return exchange_rate(currency)
+
+    tracked.__name__ = "exchange_rate"
+    mocked = mock_json_snapshot(func=tracked, mode="spy")
+
+    assert mocked("GBP") == {"currency": "GBP", "rate": 1.25}
+    assert calls == ["GBP"]  # the real function runs despite the recording
+
+
+def test_mock_json_snapshot_spy_flags_drift(tmp_path: Path):
+    recorded = _recording("test_mocks_test_mock_json_snapshot_spy_exchange_rate")
+    for source in recorded.parent.glob(recorded.name.replace("@", "*@")):
+        shutil.copy(
+            source,
+            tmp_path / source.name.replace(
+                "test_mocks_test_mock_json_snapshot_spy", "drift"
+            ),
+        )
+
+    def exchange_rate(currency: str) -> dict:
+        return {"currency": currency, "rate": 1.3}
+
+    mocked = mock_json_snapshot(
+        func=exchange_rate,
+        mode="spy",
+        snapshot_path=str(tmp_path),
+        snapshot_name="drift",
+    )
+    with pytest.raises(AssertionError, match="drifted from its recording"):
+        mocked("GBP")
+
+
+def test_mock_json_snapshot_dry_run_reports_changes(tmp_path: Path):
+    recorded = _recording("test_mocks_test_mock_json_snapshot_spy_exchange_rate")
+    for source in recorded.parent.glob(recorded.name.replace("@", "*@")):
+        shutil.copy(
+            source,
+            tmp_path / source.name.replace(
+                "test_mocks_test_mock_json_snapshot_spy", "drift"
+            ),
+        )
+    before = {path: path.read_text() for path in tmp_path.iterdir()}
+
+    def exchange_rate(currency: str) -> dict:
+        return {"currency": currency, "rate": 1.3}
+
+    mocked = mock_json_snapshot(
+        func=exchange_rate,
+        mode="dry_run",
+        snapshot_path=str(tmp_path),
+        snapshot_name="drift",
+    )
+
+    assert mocked("GBP") == {"currency": "GBP", "rate": 1.3}
+    assert mocked("EUR") == {"currency": "EUR", "rate": 1.3}
+    response, eur_request, eur_response = mocked.dry_run_report
+    assert response.change == "changed"
+    assert response.path == tmp_path / recorded.name.replace(
+        "test_mocks_test_mock_json_snapshot_spy", "drift"
+    )
+    assert eur_request.change == eur_response.change == "new"
+    assert eur_request.path.name.endswith("-request@pysnap.snap")
+    assert {path: path.read_text() for path in tmp_path.iterdir()} == before
+
+
+def test_mock_json_snapshot_rejects_unknown_mode():
+    with pytest.raises(ValueError, match="Unknown mock mode 'live'"):
+        mock_json_snapshot(func=exchange_rate, mode="live")
+
+
+def test_mock_json_snapshot_names_ignore_call_order(tmp_path: Path):
+    recorded = "test_snapshots_test_mock_or_json_snapshot_diff_args"
+    for source in SNAPSHOTS.glob(f"test_snapshots__mocks__{recorded}_add-*"):
+        target = source.name.replace(
+            f"test_snapshots__mocks__{recorded}", "test_mocks__mocks__reordered"
+        )
+        shutil.copy(source, tmp_path / target)
+    calls = []
+
+    def add(x, y):
+        calls.append((x, y))
+        return {"sum": x + y, "x": x, "y": y}
+
+    mocked = mock_json_snapshot(
+        func=add, snapshot_path=str(tmp_path), snapshot_name="reordered"
+    )
+    # Recorded in the opposite order; each call still finds its own recording.
+    assert mocked(1, 3) == {"sum": 4, "x": 1, "y": 3}
+    assert mocked(1, y=2) == {"sum": 3, "x": 1, "y": 2}
+    assert calls == []
+
+
+def shipping_quote(weight_kg: float) -> dict:
+    return {"weight_kg": weight_kg, "price": 9.5}
+
+
+def test_mock_json_snapshot_recorded_at():
+    mocked = mock_json_snapshot(func=shipping_quote)
+    assert mocked(2.0) == {"weight_kg": 2.0, "price": 9.5}
+
+    recording = _recording("test_mocks_test_mock_json_snapshot_recorded_at_shipping_quote")
+    assert datetime.strptime(recorded_at(recording), "%Y-%m-%dT%H:%M:%SZ")
+    assert recorded_by(recording) == (
+        "tests/test_mocks.py::test_mock_json_snapshot_recorded_at"
+    )
+
+
+def _aged_recording(tmp_path: Path, name: str, stamp: str) -> Path:
+    """Copy the recorded shipping quote as ``name``, stamped ``stamp``."""
+
+    recorded = _recording("test_mocks_test_mock_json_snapshot_recorded_at_shipping_quote")
+    committed = recorded_at(recorded)
+    snapshots = tmp_path / "snapshots"
+    snapshots.mkdir(exist_ok=True)
+    for source in recorded.parent.glob(recorded.name.replace("@", "*@")):
+        target = snapshots / source.name.replace(
+            "test_mocks_test_mock_json_snapshot_recorded_at", name
+        )
+        target.write_text(source.read_text().replace(committed, stamp))
+    return snapshots / recorded.name.replace(
+        "test_mocks_test_mock_json_snapshot_recorded_at", name
+    )
+
+
+def test_mock_json_snapshot_max_age_days(tmp_path: Path):
+    _aged_recording(tmp_path, "stale", "2020-01-01T00:00:00Z")
+    mocked = mock_json_snapshot(
+        func=shipping_quote,
+        snapshot_path=str(tmp_path / "snapshots"),
+        snapshot_name="stale",
+        max_age_days=30,
+    )
+    with pytest.raises(AssertionError, match="days old \\(max_age_days=30\\)"):
+        mocked(2.0)
+
+    lenient = mock_json_snapshot(
+        func=shipping_quote,
+        snapshot_path=str(tmp_path / "snapshots"),
+        snapshot_name="stale",
+        allow_duplicates=True,
+        max_age_days=365 * 100,
+    )
+    assert lenient(2.0) == {"weight_kg": 2.0, "price": 9.5}
+
+
+def test_rerecording_refreshes_recorded_at(tmp_path: Path):
+    path = _aged_recording(tmp_path, "renewed", "2020-01-01T00:00:00Z")
+    mocked = mock_json_snapshot(
+        func=shipping_quote,
+        record=True,
+        snapshot_path=str(tmp_path / "snapshots"),
+        snapshot_name="renewed",
+    )
+    assert mocked(2.0) == {"weight_kg": 2.0, "price": 9.5}
+    assert recorded_at(path) > "2020-01-01T00:00:00Z"
+
+
+def test_find_expired_recordings(tmp_path: Path):
+    stale = _aged_recording(tmp_path, "stale", "2020-01-01T00:00:00Z")
+    _aged_recording(tmp_path, "fresh", "2020-03-01T00:00:00Z")
+    now = datetime(2020, 3, 15, tzinfo=timezone.utc)
+
+    assert find_expired_recordings(str(tmp_path), max_age_days=30, now=now) == [
+        stale.resolve()
+    ]
+    assert find_expired_recordings(str(tmp_path), max_age_days=90, now=now) == []
+
+
+def open_session(user: str, now: float) -> dict:
+    return {"user": user, "session_id": f"{now:.6f}", "ttl": 3600}
+
+
+def test_mock_json_snapshot_ignore():
+    mocked = mock_json_snapshot(
+        func=open_session, ignore=[".kwargs.now", ".session_id"]
+    )
+    assert mocked("ada", now=time.time())["ttl"] == 3600
+
+    recording = _recording("test_mocks_test_mock_json_snapshot_ignore_open_session")
+    assert read_json_snapshot(recording) == {"user": "ada", "ttl": 3600}
+    request = recording.with_name(recording.name.replace("@", "-request@"))
+    assert read_json_snapshot(request) == {"args": ["ada"], "kwargs": {}}
+
+
+def _binary_recording(tmp_path: Path, name: str, data: bytes) -> Path:
+    """The spy test's ``exchange_rate`` recording, with its response swapped
+    for a binary ``.png`` snapshot holding ``data``."""
+
+    recorded = _recording("test_mocks_test_mock_json_snapshot_spy_exchange_rate")
+    for source in recorded.parent.glob(recorded.name.replace("@", "*@")):
+        shutil.copy(
+            source,
+            tmp_path
+            / source.name.replace("test_mocks_test_mock_json_snapshot_spy", name),
+        )
+    response = tmp_path / recorded.name.replace(
+        "test_mocks_test_mock_json_snapshot_spy", name
+    )
+    response.write_text(
+        "---\nsource: src/lib.rs\nextension: png\nsnapshot_kind: binary\n---\n"
+    )
+    response.with_name(f"{response.name}.png").write_bytes(data)
+    return response
+
+
+def test_binary_recording_replays_as_bytes(tmp_path: Path):
+    response = _binary_recording(tmp_path, "binary", b"\x89PNG\r\n")
+    mocked = mock_json_snapshot(
+        func=exchange_rate, snapshot_path=str(tmp_path), snapshot_name="binary"
+    )
+
+    assert mocked("GBP") == b"\x89PNG\r\n"
+    assert read_json_snapshot(response) == b"\x89PNG\r\n"
+
+
+def test_binary_recording_replays_through_its_decoder(tmp_path: Path):
+    response = _binary_recording(tmp_path, "decoded", b"\x89PNG\r\n")
+    mocked = mock_json_snapshot(
+        func=exchange_rate, snapshot_path=str(tmp_path), snapshot_name="decoded"
+    )
+    register_replay_decoder(".png", lambda data: {"png": len(data)})
+    try:
+        assert mocked("GBP") == {"png": 6}
+    finally:
+        register_replay_decoder("png", None)
+
+    assert read_json_snapshot(response) == b"\x89PNG\r\n"
+    with pytest.raises(ValueError, match="Invalid extension"):
+        register_replay_decoder("png.zst", None)
+
+
+def test_mock_json_snapshot_decode(tmp_path: Path):
+    recorded = "test_mocks_test_mock_json_snapshot_pydantic_args_and_result"
+    for source in SNAPSHOTS.glob(f"test_mocks__mocks__{recorded}_fetch_user-*"):
+        shutil.copy(source, tmp_path / source.name.replace(recorded, "decoded"))
+
+    def fetch_user(user: User) -> User:
+        raise AssertionError("replayed calls do not reach the real function")
+
+    mocked = mock_json_snapshot(
+        func=fetch_user,
+        snapshot_path=str(tmp_path),
+        snapshot_name="decoded",
+        decode=User.model_validate_json,
+    )
+
+    assert mocked(User(id=1, name="ada")) == User(id=1, name="ADA")
+
+
+def test_mock_json_snapshot_decode_binary_recording(tmp_path: Path):
+    _binary_recording(tmp_path, "decoded_binary", b"\x89PNG\r\n")
+    mocked = mock_json_snapshot(
+        func=exchange_rate,
+        snapshot_path=str(tmp_path),
+        snapshot_name="decoded_binary",
+        decode=len,
+    )
+
+    assert mocked("GBP") == 6
+
+
+class Cursor:
+    """A result with no generic serialization."""
+
+    def __init__(self, rows: list) -> None:
+        self.rows = rows
+
+    def fetchall(self) -> list:
+        return self.rows
+
+
+def test_mock_json_snapshot_encode(tmp_path: Path, monkeypatch):
+    monkeypatch.setenv("INSTA_WORKSPACE_ROOT", str(tmp_path))
+    monkeypatch.setenv("INSTA_UPDATE", "always")
+    monkeypatch.delenv("INSTA_FORCE_PASS", raising=False)
+    cursor = Cursor([[1, "ada"], [2, "grace"]])
+
+    def query(sql: str) -> Cursor:
+        return cursor
+
+    mocked = mock_json_snapshot(
+        func=query,
+        snapshot_path=str(tmp_path),
+        snapshot_name="encoded",
+        encode=Cursor.fetchall,
+        side_effects=[RECORDED, Cursor([])],
+    )
+
+    assert mocked("SELECT id, name FROM users") is cursor
+    assert mocked("SELECT 1").rows == []
+    responses = sorted(
+        path for path in tmp_path.glob("*@pysnap.snap") if "-request" not in path.name
+    )
+    assert sorted(read_json_snapshot(path) for path in responses) == [
+        [],
+        [[1, "ada"], [2, "grace"]],
+    ]
+
+
+def test_long_mock_names_are_shortened_and_replayed(tmp_path: Path, monkeypatch):
+    monkeypatch.setenv("INSTA_WORKSPACE_ROOT", str(tmp_path))
+    monkeypatch.setenv("INSTA_UPDATE", "always")
+    monkeypatch.delenv("INSTA_FORCE_PASS", raising=False)
+    info = SnapshotInfo.manual(tmp_path, "test_case_" + "x" * 200)
+
+    first = prepare_mock_call(info, "fetch", {"id": 1}, False)
+    assert all(len(path.name) <= 200 for path in tmp_path.iterdir())
+    (request,) = tmp_path.glob("*-request@pysnap.snap")
+    assert request.name.endswith("_fetch-" + first[0][-8:] + "-request@pysnap.snap")
+
+    matched = prepare_mock_call(
+        info, "fetch", {"id": 2}, False, matches=lambda recorded, new: True
+    )
+    assert matched[0] == f"{first[0]}-2"
+
+
+class Quote(NamedTuple):
+    currency: str
+    rate: float
+
+
+def test_mock_json_snapshot_rebuilds_the_recorded_type(tmp_path: Path, monkeypatch):
+    monkeypatch.setenv("INSTA_WORKSPACE_ROOT", str(tmp_path))
+    monkeypatch.setenv("INSTA_UPDATE", "always")
+    monkeypatch.delenv("INSTA_FORCE_PASS", raising=False)
+
+    def quote(currency: str) -> Quote:
+        return Quote(currency, 1.25)
+
+    def mocked() -> Callable:
+        return mock_json_snapshot(
+            func=quote, snapshot_path=str(tmp_path), snapshot_name="typed"
+        )
+
+    assert mocked()("GBP") == Quote("GBP", 1.25)
+    (response,) = (
+        path for path in tmp_path.glob("*@pysnap.snap") if "-request" not in path.name
+    )
+    assert recorded_type(response) == f"{Quote.__module__}:Quote"
+
+    observe_test_stage(os.environ["PYTEST_CURRENT_TEST"].rsplit(" (", 1)[0], "setup")
+    register_result_factory(Quote, lambda fields: Quote(*fields))
+    try:
+        replayed = mocked()("GBP")
+    finally:
+        register_result_factory(f"{Quote.__module__}:Quote", None)
+
+    assert isinstance(replayed, Quote)
+    assert replayed == Quote("GBP", 1.25)
+    assert read_json_snapshot(response) == ["GBP", 1.25]
+    with pytest.raises(ValueError, match="builtin type"):
+        register_result_factory(dict, dict)
+
+
+def test_mock_json_snapshot_rebuilds_the_decoded_result(tmp_path: Path, monkeypatch):
+    monkeypatch.setenv("INSTA_WORKSPACE_ROOT", str(tmp_path))
+    monkeypatch.setenv("INSTA_UPDATE", "always")
+    monkeypatch.delenv("INSTA_FORCE_PASS", raising=False)
+
+    def quote(currency: str) -> Quote:
+        return Quote(currency, 1.25)
+
+    def mocked() -> Callable:
+        return mock_json_snapshot(
+            func=quote,
+            snapshot_path=str(tmp_path),
+            snapshot_name="decoded",
+            decode=lambda text: [field.lower() for field in json.loads(text)[:1]] + [2.5],
+        )
+
+    assert mocked()("GBP") == Quote("GBP", 1.25)
+
+    observe_test_stage(os.environ["PYTEST_CURRENT_TEST"].rsplit(" (", 1)[0], "setup")
+    register_result_factory(Quote, lambda fields: Quote(*fields))
+    try:
+        replayed = mocked()("GBP")
+    finally:
+        register_result_factory(Quote, None)
+
+    assert replayed == Quote("gbp", 2.5)
@@ -0,0 +1,60 @@
+"""Tests for storing snapshots in namespace subdirectories of their folder."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import (
+    SnapshotInfo,
+    SnapshotSettings,
+    assert_json_snapshot,
+    mock_json_snapshot,
+)
+
+
+def exchange_rate(currency: str) -> dict:
+    return {"currency": currency, "rate": 1.1}
+
+
+def test_with_namespace(tmp_path: Path):
+    info = SnapshotInfo.manual(tmp_path, "report")
+    namespaced = info.with_namespace("http/v2")
+
+    assert namespaced.namespace == "http/v2"
+    assert namespaced.folder == tmp_path / "http" / "v2"
+    assert namespaced.snapshot_folder() == tmp_path / "http" / "v2"
+    assert namespaced != info
+    assert namespaced.with_namespace(None) == info
+    assert info.namespace is None
+
+
+def test_settings_namespace_applies_to_assertions(tmp_path: Path):
+    with SnapshotSettings(snapshot_path=tmp_path, namespace="db"):
+        with pytest.raises(AssertionError):
+            assert_json_snapshot({"rows": 3}, snapshot_name="query")
+
+    (pending,) = tmp_path.rglob("*.new")
+    assert pending.parent == tmp_path / "db"
+    assert pending.name.endswith("query@pysnap.snap.new")
+
+
+def test_mock_recordings_in_a_namespace(tmp_path: Path):
+    mocked = mock_json_snapshot(
+        func=exchange_rate, snapshot_path=str(tmp_path), namespace="http"
+    )
+
+    with pytest.raises(AssertionError):
+        mocked("EUR")
+
+    assert [p.parent for p in tmp_path.rglob("*.new")] == [tmp_path / "http"]
+
+
+@pytest.mark.parametrize("namespace", ["", "http/", "../http", "a\\b", "db:1"])
+def test_invalid_namespace(tmp_path: Path, namespace: str):
+    with pytest.raises(ValueError, match="Invalid snapshot namespace"):
+        SnapshotInfo.manual(tmp_path, "report").with_namespace(namespace)
+
+    with pytest.raises(ValueError, match="Invalid snapshot namespace"):
+        SnapshotSettings(namespace=namespace)
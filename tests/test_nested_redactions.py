@@ -0,0 +1,38 @@
+"""Tests for redactions given as nested dicts mirroring the payload."""
+
+from __future__ import annotations
+
+from pysnaptest import assert_json_snapshot
+
+PAYLOAD = {
+    "user": {
+        "id": 8172,
+        "name": "Ada",
+        "sessions": {
+            "web": {"token": "f1e2d3", "ip": "10.0.0.1"},
+            "cli": {"token": "a9b8c7", "ip": "10.0.0.2"},
+        },
+    },
+    "events": [{"at": "2026-10-16T09:30:00Z", "kind": "login"}],
+}
+
+
+def test_nested_redactions():
+    assert_json_snapshot(
+        PAYLOAD,
+        redactions={
+            "user": {"id": "[id]", "sessions": {"*": {"token": "[token]"}}},
+            "events": {"[]": {"at": "[timestamp]"}},
+        },
+    )
+
+
+def test_nested_redactions_mix_with_selectors():
+    assert_json_snapshot(
+        PAYLOAD,
+        redactions={
+            ".user.id": "[id]",
+            ".user.sessions": {"*": {"token": "[token]", "ip": "[ip]"}},
+            "events": {0: {"at": "[timestamp]"}},
+        },
+    )
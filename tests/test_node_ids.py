@@ -0,0 +1,89 @@
+"""Tests for snapshot infos bound to an explicit pytest node id."""
+
+from __future__ import annotations
+
+import os
+import warnings
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import (
+    SnapshotInfo,
+    SnapshotTeardownWarning,
+    mock_json_snapshot,
+    patch_json_snapshot,
+)
+
+SNAPSHOTS = Path(__file__).parent / "snapshots"
+SHARED = "tests/test_node_ids.py::shared_rates"
+
+
+def exchange_rate(currency: str) -> dict:
+    return {"currency": currency, "rate": 1.1}
+
+
+def _recorded_test(pending: Path) -> str:
+    header = pending.read_text().split("\n---\n", 1)[0]
+    (line,) = [line for line in header.splitlines() if line.strip().startswith("test:")]
+    return line.split(":", 1)[1].strip().strip('"')
+
+
+def test_info_for_another_test():
+    info = SnapshotInfo.from_node_id("tests/test_node_ids.py::test_requesting (setup)")
+
+    assert info.snapshot_folder() == SNAPSHOTS.resolve()
+    assert info.next_snapshot_name() == "test_node_ids_test_requesting"
+
+
+def test_overrides_apply(tmp_path: Path):
+    info = SnapshotInfo.from_node_id(
+        SHARED, snapshot_path_override=tmp_path, prefix="none"
+    )
+
+    assert info.snapshot_folder() == tmp_path
+    assert info.next_snapshot_name() == "test_node_ids_shared_rates"
+
+
+def test_mock_records_under_the_given_node(tmp_path: Path):
+    mocked = mock_json_snapshot(
+        func=exchange_rate, snapshot_path=str(tmp_path), node_id=SHARED
+    )
+
+    with pytest.raises(AssertionError):
+        mocked("EUR")
+
+    (pending,) = tmp_path.glob("*-request@pysnap.snap.new")
+    assert pending.name.startswith("test_node_ids__mocks__test_node_ids_shared_rates")
+    assert _recorded_test(pending) == SHARED
+
+
+def test_bound_info_does_not_warn_in_teardown(tmp_path: Path, monkeypatch):
+    running = os.environ["PYTEST_CURRENT_TEST"].rsplit(" (", 1)[0]
+    monkeypatch.setenv("PYTEST_CURRENT_TEST", f"{running} (teardown)")
+
+    with warnings.catch_warnings():
+        warnings.simplefilter("error", SnapshotTeardownWarning)
+        with patch_json_snapshot(
+            f"{__name__}.exchange_rate", snapshot_path=str(tmp_path), node_id=SHARED
+        ) as mocked:
+            with pytest.raises(AssertionError):
+                mocked("USD")
+
+
+def test_from_pytest_prefers_the_given_node_id(monkeypatch):
+    monkeypatch.setenv("PYTEST_CURRENT_TEST", "tests/test_other.py::test_env (call)")
+
+    info = SnapshotInfo.from_pytest(node_id=SHARED)
+
+    assert info.node_id == SHARED
+    assert info.next_snapshot_name() == "test_node_ids_shared_rates"
+
+
+def test_from_pytest_needs_no_environment(monkeypatch):
+    monkeypatch.delenv("PYTEST_CURRENT_TEST")
+
+    info = SnapshotInfo.from_pytest(node_id="tests/test_node_ids.py::test_plain")
+
+    assert info.snapshot_folder() == SNAPSHOTS.resolve()
+    assert info.next_snapshot_name() == "test_node_ids_test_plain"
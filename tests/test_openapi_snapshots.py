@@ -0,0 +1,123 @@
+"""Tests for OpenAPI contract snapshots."""
+
+from __future__ import annotations
+
+import copy
+import json
+import shutil
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import assert_openapi_snapshot
+
+DOCUMENT = {
+    "openapi": "3.1.0",
+    "info": {"title": "Pets", "version": "1.0.0", "x-logo": {"url": "logo.png"}},
+    "paths": {
+        "/pets": {
+            "get": {
+                "operationId": "listPets",
+                "x-internal-owner": "team-pets",
+                "parameters": [
+                    {"in": "query", "name": "limit", "schema": {"type": "integer"}},
+                    {"in": "header", "name": "x-tenant", "schema": {"type": "string"}},
+                ],
+                "responses": {
+                    "200": {
+                        "description": "A list of pets",
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "array",
+                                    "items": {"$ref": "#/components/schemas/Pet"},
+                                }
+                            }
+                        },
+                    }
+                },
+            }
+        },
+        "/pets/{petId}": {
+            "delete": {
+                "operationId": "deletePet",
+                "responses": {"204": {"description": "Deleted"}},
+            }
+        },
+    },
+    "components": {
+        "schemas": {
+            "Pet": {
+                "type": "object",
+                "required": ["name", "id"],
+                "properties": {
+                    "id": {"type": "integer"},
+                    "name": {"type": "string"},
+                    "x-legacy-code": {"type": "string"},
+                    "owner": {
+                        "oneOf": [
+                            {"$ref": "#/components/schemas/Person"},
+                            {"$ref": "#/components/schemas/Company"},
+                        ]
+                    },
+                },
+            },
+            "Person": {"type": "object"},
+            "Company": {"type": "object"},
+        }
+    },
+}
+
+SNAPSHOT = (
+    Path(__file__).parent
+    / "snapshots"
+    / "test_openapi_snapshots__test_openapi_snapshots_test_openapi_snapshot@pysnap.snap"
+)
+
+
+def test_openapi_snapshot():
+    assert_openapi_snapshot(DOCUMENT)
+
+
+def test_openapi_snapshot_accepts_json_and_reordered_lists(tmp_path: Path):
+    shutil.copy(SNAPSHOT, tmp_path / "test_openapi_snapshots__reordered@pysnap.snap")
+    reordered = copy.deepcopy(DOCUMENT)
+    pet = reordered["components"]["schemas"]["Pet"]
+    pet["required"].reverse()
+    pet["properties"]["owner"]["oneOf"].reverse()
+    reordered["paths"]["/pets"]["get"]["parameters"].reverse()
+    reordered["info"]["x-audience"] = "public"
+    assert_openapi_snapshot(
+        json.dumps(reordered), snapshot_path=str(tmp_path), snapshot_name="reordered"
+    )
+
+
+def test_openapi_snapshot_reports_contract_changes(tmp_path: Path):
+    shutil.copy(SNAPSHOT, tmp_path / "test_openapi_snapshots__changed@pysnap.snap")
+    changed = copy.deepcopy(DOCUMENT)
+    changed["info"]["version"] = "1.1.0"
+    del changed["paths"]["/pets/{petId}"]
+    changed["paths"]["/pets"]["post"] = {"responses": {"201": {"description": "Created"}}}
+    changed["paths"]["/pets"]["get"]["responses"]["400"] = {"description": "Bad"}
+    changed["components"]["schemas"]["Pet"]["properties"]["tag"] = {"type": "string"}
+    changed["components"]["schemas"]["Error"] = {"type": "object"}
+
+    with pytest.raises(AssertionError) as excinfo:
+        assert_openapi_snapshot(
+            changed, snapshot_path=str(tmp_path), snapshot_name="changed"
+        )
+    message = str(excinfo.value)
+    for change in (
+        "- DELETE /pets/{petId}",
+        "~ GET /pets: responses.400 added",
+        "+ POST /pets",
+        "+ schema Error",
+        "~ schema Pet: property tag added",
+        '~ info.version: "1.0.0" -> "1.1.0"',
+    ):
+        assert f"\n  {change}\n" in message
+
+
+def test_openapi_snapshot_rejects_other_documents():
+    with pytest.raises(ValueError, match="Not an OpenAPI document"):
+        assert_openapi_snapshot({"title": "not a spec"})
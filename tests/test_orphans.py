@@ -0,0 +1,92 @@
+"""Tests for finding snapshots whose owning test no longer exists."""
+
+from __future__ import annotations
+
+import shutil
+import subprocess
+from pathlib import Path
+
+import pytest
+
+from pysnaptest.__main__ import main
+from pysnaptest.orphans import find_orphaned_snapshots
+
+
+def _snapshot(snapshots: Path, name: str, test_file: str = "tests/test_api.py") -> Path:
+    path = snapshots / f"{Path(test_file).stem}__{name}@pysnap.snap"
+    path.write_text(
+        f'---\nsource: src/lib.rs\ndescription: "Test File Path: {test_file}"\n'
+        "expression: res\n---\n{}\n"
+    )
+    return path
+
+
+@pytest.fixture
+def project(tmp_path: Path) -> Path:
+    tests = tmp_path / "tests"
+    (tests / "snapshots").mkdir(parents=True)
+    (tests / "test_api.py").write_text(
+        "def test_checkout():\n    pass\n\n\n"
+        "class TestRefunds:\n    def test_partial(self):\n        pass\n"
+    )
+    return tmp_path
+
+
+def test_live_tests_keep_their_snapshots(project: Path):
+    snapshots = project / "tests" / "snapshots"
+    _snapshot(snapshots, "test_api_test_checkout")
+    _snapshot(snapshots, "test_api_test_checkout-2")
+    _snapshot(snapshots, "test_api_TestRefunds::test_partial")
+    _snapshot(snapshots, "test_api_TestRefunds.test_partial-2")
+    _snapshot(snapshots, "custom_name")
+
+    assert find_orphaned_snapshots(str(project)) == []
+
+
+def test_removed_test_is_orphaned(project: Path):
+    snapshots = project / "tests" / "snapshots"
+    removed = _snapshot(snapshots, "test_api_test_cancel")
+
+    (orphan,) = find_orphaned_snapshots(str(project))
+    assert orphan.path == removed.resolve()
+    assert orphan.reason == "no test in tests/test_api.py owns this snapshot"
+
+
+def test_deleted_test_file_reports_commit(project: Path):
+    if shutil.which("git") is None:
+        pytest.skip("git is not installed")
+    snapshots = project / "tests" / "snapshots"
+    legacy = project / "tests" / "test_legacy.py"
+    legacy.write_text("def test_old():\n    pass\n")
+    orphaned = _snapshot(snapshots, "test_legacy_test_old", "tests/test_legacy.py")
+
+    def git(*args: str) -> None:
+        subprocess.run(
+            ["git", "-c", "user.name=t", "-c", "user.email=t@example.com", *args],
+            cwd=project,
+            check=True,
+            capture_output=True,
+        )
+
+    git("init", "-q")
+    git("add", "-A")
+    git("commit", "-qm", "Add legacy tests")
+    legacy.unlink()
+    git("commit", "-qam", "Drop legacy tests")
+
+    (orphan,) = find_orphaned_snapshots(str(project))
+    assert orphan.path == orphaned.resolve()
+    assert orphan.reason.startswith("test file tests/test_legacy.py no longer exists")
+    assert orphan.reason.endswith("Drop legacy tests)")
+
+
+def test_gc_cli_deletes(project: Path):
+    snapshots = project / "tests" / "snapshots"
+    kept = _snapshot(snapshots, "test_api_test_checkout")
+    removed = _snapshot(snapshots, "test_api_test_cancel")
+
+    assert main(["--root", str(project), "gc"]) == 1
+    assert removed.exists()
+    assert main(["--root", str(project), "gc", "--delete"]) == 0
+    assert not removed.exists()
+    assert kept.exists()
@@ -0,0 +1,78 @@
+"""Tests for `patch_json_snapshot`'s attribute patching."""
+
+from __future__ import annotations
+
+import sys
+import types
+
+import pytest
+
+from pysnaptest import patch_json_snapshot
+
+
+def lookup_price(sku: str) -> dict:
+    return {"sku": sku, "price": 12}
+
+
+class Catalog:
+    @staticmethod
+    def describe(sku: str) -> dict:
+        return {"sku": sku}
+
+
+class Shop(Catalog):
+    pass
+
+
+@pytest.fixture
+def shop_module(monkeypatch: pytest.MonkeyPatch):
+    module = types.ModuleType("shop_under_test")
+    module.lookup_price = lookup_price
+    module.Shop = Shop
+    monkeypatch.setitem(sys.modules, "shop_under_test", module)
+    return module
+
+
+def test_patch_json_snapshot_context_manager(shop_module):
+    with patch_json_snapshot("shop_under_test.lookup_price") as mocked:
+        assert shop_module.lookup_price is mocked
+        assert shop_module.lookup_price("A1") == {"sku": "A1", "price": 12}
+    assert shop_module.lookup_price is lookup_price
+
+
+def test_patch_json_snapshot_restores_after_errors(shop_module):
+    with pytest.raises(KeyError):
+        with patch_json_snapshot("shop_under_test.lookup_price"):
+            raise KeyError("boom")
+    assert shop_module.lookup_price is lookup_price
+
+
+def test_patch_json_snapshot_inherited_attribute(shop_module):
+    with patch_json_snapshot("shop_under_test.Shop.describe"):
+        assert "describe" in vars(Shop)
+        assert Shop().describe("B2") == {"sku": "B2"}
+    # Inherited from Catalog, so it is removed again rather than copied onto Shop.
+    assert "describe" not in vars(Shop)
+    assert Shop.describe is Catalog.describe
+
+
+def test_patch_json_snapshot_decorator(shop_module):
+    @patch_json_snapshot("shop_under_test.lookup_price")
+    def checkout(sku: str) -> int:
+        return shop_module.lookup_price(sku)["price"]
+
+    assert checkout.__name__ == "checkout"
+    assert checkout("C3") == 12
+    assert shop_module.lookup_price is lookup_price
+
+
+def test_patch_json_snapshot_rejects_bare_names():
+    with pytest.raises(ValueError, match="expected a dotted path"):
+        with patch_json_snapshot("lookup_price"):
+            pass
+
+
+def test_patch_json_snapshot_missing_attribute(shop_module):
+    with pytest.raises(AttributeError):
+        with patch_json_snapshot("shop_under_test.missing"):
+            pass
@@ -0,0 +1,79 @@
+"""Tests for PDF snapshots."""
+
+from __future__ import annotations
+
+from typing import Dict, List
+
+import pytest
+
+from pysnaptest import PySnapshot, assert_pdf_snapshot, extract_from_pytest_env
+
+
+def _pdf(pages: List[str], created: str = "20260101000000Z", reverse: bool = False) -> bytes:
+    """A PDF with one Helvetica text line per page, written the way renderers
+    vary between runs: a creation date and, with ``reverse``, objects in
+    reverse order."""
+
+    count = len(pages)
+    page_ids = [4 + 2 * i for i in range(count)]
+    objects: Dict[int, bytes] = {
+        1: b"<< /Type /Catalog /Pages 2 0 R >>",
+        2: (
+            f"<< /Type /Pages /Kids [{' '.join(f'{i} 0 R' for i in page_ids)}] "
+            f"/Count {count} /MediaBox [0 0 612 792] >>"
+        ).encode(),
+        3: b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>",
+        4 + 2 * count: f"<< /Producer (renderer) /CreationDate (D:{created}) >>".encode(),
+    }
+    for page_id, text in zip(page_ids, pages):
+        content = f"BT /F1 12 Tf 72 720 Td ({text}) Tj ET".encode()
+        objects[page_id] = (
+            f"<< /Type /Page /Parent 2 0 R /Contents {page_id + 1} 0 R "
+            "/Resources << /Font << /F1 3 0 R >> >> >>"
+        ).encode()
+        objects[page_id + 1] = (
+            f"<< /Length {len(content)} >>\nstream\n".encode() + content + b"\nendstream"
+        )
+
+    out = bytearray(b"%PDF-1.4\n")
+    offsets = {}
+    for object_id in sorted(objects, reverse=reverse):
+        offsets[object_id] = len(out)
+        out += f"{object_id} 0 obj\n".encode() + objects[object_id] + b"\nendobj\n"
+    xref = len(out)
+    out += f"xref\n0 {len(objects) + 1}\n0000000000 65535 f \n".encode()
+    for object_id in range(1, len(objects) + 1):
+        out += f"{offsets[object_id]:010d} 00000 n \n".encode()
+    out += (
+        f"trailer\n<< /Size {len(objects) + 1} /Root 1 0 R /Info {4 + 2 * count} 0 R >>\n"
+        f"startxref\n{xref}\n%%EOF\n"
+    ).encode()
+    return bytes(out)
+
+
+def test_assert_pdf_snapshot_text():
+    assert_pdf_snapshot(_pdf(["Invoice 42", "Total: 10.00 EUR"]))
+
+
+def test_pdf_text_snapshot_ignores_dates_and_object_order():
+    original = _pdf(["Invoice 42"])
+    rerendered = _pdf(["Invoice 42"], created="20261016093000Z", reverse=True)
+    assert original != rerendered
+
+    for pdf in (original, rerendered):
+        assert_pdf_snapshot(pdf, allow_duplicates=True)
+
+
+def test_assert_pdf_snapshot_binary():
+    pdf = _pdf(["Invoice 42"])
+    assert_pdf_snapshot(pdf, mode="binary")
+
+    snapshot_path = extract_from_pytest_env().last_snapshot_path(None)
+    assert PySnapshot.from_file(snapshot_path).contents() == pdf
+
+
+def test_assert_pdf_snapshot_rejects_bad_input():
+    with pytest.raises(ValueError, match="Unsupported PDF snapshot mode"):
+        assert_pdf_snapshot(_pdf(["x"]), mode="pixels")
+    with pytest.raises(ValueError, match="Unable to parse PDF"):
+        assert_pdf_snapshot(b"not a pdf")
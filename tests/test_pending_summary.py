@@ -0,0 +1,77 @@
+"""Tests for the summary of the snapshots a run leaves pending review."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import assert_binary_snapshot, assert_snapshot, pending_summary
+from pysnaptest.pytest_plugin import pytest_terminal_summary
+from pysnaptest.review import accept_pending_snapshot
+
+TEST = "tests/test_pending_summary.py::test_pending_snapshots_are_summarized_by_test"
+
+
+def _committed(tmp_path: Path, name: str, body: str = "old\n") -> Path:
+    snapshot = tmp_path / f"test_pending_summary__{name}@pysnap.snap"
+    snapshot.write_text(f"---\nsource: src/lib.rs\n---\n{body}")
+    return snapshot
+
+
+def test_pending_snapshots_are_summarized_by_test(tmp_path: Path):
+    text = _committed(tmp_path, "pending_text")
+    binary = _committed(tmp_path, "pending_binary", "")
+    binary.write_text(
+        "---\nsource: src/lib.rs\nsnapshot_kind: binary\nextension: bin\n---\n"
+    )
+    (tmp_path / f"{binary.name}.bin").write_bytes(b"\x00")
+    _committed(tmp_path, "pending_matching", "same\n")
+    path = str(tmp_path)
+
+    with pytest.raises(AssertionError):
+        assert_snapshot("new", snapshot_path=path, snapshot_name="pending_text")
+    with pytest.raises(AssertionError):
+        assert_binary_snapshot(b"\x01", snapshot_path=path, snapshot_name="pending_binary")
+    assert_snapshot("same", snapshot_path=path, snapshot_name="pending_matching")
+
+    summary = pending_summary()
+    assert summary["tests"][TEST] == {
+        "count": 2,
+        "paths": [Path(f"{binary}.new"), Path(f"{text}.new")],
+    }
+    assert summary["total"] >= 2
+
+    # Reviewed snapshots are no longer pending.
+    accept_pending_snapshot(f"{text}.new")
+    assert pending_summary()["tests"][TEST]["paths"] == [Path(f"{binary}.new")]
+
+
+class _Reporter:
+    def __init__(self):
+        self.lines = []
+
+    def write_sep(self, sep, title):
+        self.lines.append(f"== {title} ==")
+
+    def write_line(self, line, **markup):
+        self.lines.append(line)
+
+
+def test_terminal_summary_banner(tmp_path: Path):
+    snapshot = _committed(tmp_path, "pending_banner")
+    with pytest.raises(AssertionError):
+        assert_snapshot("new", snapshot_path=str(tmp_path), snapshot_name="pending_banner")
+    config = type("Config", (), {"rootpath": tmp_path})()
+    reporter = _Reporter()
+
+    pytest_terminal_summary(reporter, config)
+
+    total = pending_summary()["total"]
+    assert reporter.lines[:2] == [
+        "== pysnaptest ==",
+        f"{total} snapshot(s) pending review, run `pysnaptest review`:",
+    ]
+    test = "tests/test_pending_summary.py::test_terminal_summary_banner"
+    index = reporter.lines.index(f"  {test} (1)")
+    assert reporter.lines[index + 1] == f"    {snapshot.name}.new"
@@ -0,0 +1,50 @@
+"""Tests for the provenance recorded in snapshot headers."""
+
+from __future__ import annotations
+
+import re
+from pathlib import Path
+
+from pysnaptest import PySnapshot, SnapshotInfo, assert_json_snapshot
+
+
+def test_snapshot_header_records_provenance():
+    assert_json_snapshot({"recorded": True})
+
+    path = SnapshotInfo.from_pytest().last_snapshot_path()
+    metadata = PySnapshot.from_file(path).metadata()
+    assert metadata["test"] == (
+        "tests/test_provenance.py::test_snapshot_header_records_provenance"
+    )
+    assert re.fullmatch(r"\d+\.\d+\.\d+", metadata["pysnaptest_version"])
+    assert re.fullmatch(r"\d+\.\d+\.\d+", metadata["python_version"])
+    assert re.fullmatch(r"\d{4}-\d\d-\d\dT\d\d:\d\d:\d\dZ", metadata["created_at"])
+
+
+def test_metadata_keeps_header_order_and_types(tmp_path: Path):
+    path = tmp_path / "test_api__mocks__test_api_test_get_fetch-1a2b3c4d@pysnap.snap"
+    path.write_text(
+        "---\nsource: src/mocks.rs\n"
+        'description: "Test File Path: tests/test_api.py"\n'
+        "info:\n  pysnaptest_version: 0.6.0\n"
+        '  test: "tests/test_api.py::test_get[a \\"b\\"]"\n'
+        '  created_at: "2026-01-02T03:04:05Z"\n'
+        "  git_commit: 0123abc\n  duration_ms: 12.5\n"
+        "---\n{}\n"
+    )
+
+    metadata = PySnapshot.from_file(path).metadata()
+    assert list(metadata.items()) == [
+        ("pysnaptest_version", "0.6.0"),
+        ("test", 'tests/test_api.py::test_get[a "b"]'),
+        ("created_at", "2026-01-02T03:04:05Z"),
+        ("git_commit", "0123abc"),
+        ("duration_ms", 12.5),
+    ]
+
+
+def test_metadata_of_snapshots_without_provenance(tmp_path: Path):
+    path = tmp_path / "test_api__test_api_test_get@pysnap.snap"
+    path.write_text("---\nsource: src/lib.rs\nexpression: res\n---\n{}\n")
+
+    assert PySnapshot.from_file(path).metadata() == {}
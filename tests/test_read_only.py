@@ -0,0 +1,105 @@
+"""Tests for read-only snapshots, which refuse to write any snapshot file."""
+
+from __future__ import annotations
+
+from pathlib import Path
+from typing import Iterator
+
+import pytest
+
+from pysnaptest import (
+    SnapshotReadOnlyError,
+    assert_json_snapshot,
+    assert_snapshot,
+    mock_json_snapshot,
+    set_read_only,
+)
+
+
+@pytest.fixture
+def read_only(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> Iterator[Path]:
+    """Read-only snapshots, under ``INSTA_UPDATE=always`` in a workspace root
+    insta has not read a config for."""
+
+    monkeypatch.setenv("INSTA_WORKSPACE_ROOT", str(tmp_path))
+    monkeypatch.setenv("INSTA_UPDATE", "always")
+    monkeypatch.delenv("INSTA_FORCE_PASS", raising=False)
+    monkeypatch.delenv("PYSNAPTEST_UPDATE_FILTER", raising=False)
+    set_read_only()
+    yield tmp_path
+    set_read_only(False)
+
+
+def _committed(folder: Path, name: str) -> Path:
+    snapshot = folder / f"test_read_only__{name}@pysnap.snap"
+    snapshot.write_text("---\nsource: src/lib.rs\n---\nold\n")
+    return snapshot
+
+
+def test_new_snapshot_is_refused(read_only: Path):
+    folder = read_only / "snapshots"
+
+    with pytest.raises(SnapshotReadOnlyError, match="would create snapshot") as raised:
+        assert_snapshot("new", snapshot_path=str(folder), snapshot_name="fresh")
+
+    assert "tests/test_read_only.py::test_new_snapshot_is_refused" in str(raised.value)
+    assert isinstance(raised.value, AssertionError)
+    assert not folder.exists()
+
+
+def test_changed_snapshot_is_refused(read_only: Path):
+    snapshot = _committed(read_only, "changed")
+    before = snapshot.read_text()
+
+    with pytest.raises(SnapshotReadOnlyError, match="would rewrite snapshot") as raised:
+        assert_snapshot("new", snapshot_path=str(read_only), snapshot_name="changed")
+
+    assert f"modified: {snapshot}" in str(raised.value)
+    assert snapshot.read_text() == before
+    assert [p.name for p in read_only.iterdir()] == [snapshot.name]
+
+
+def test_changed_snapshot_is_never_written(read_only: Path):
+    snapshot = _committed(read_only, "untouched")
+    before = snapshot.stat()
+
+    with pytest.raises(SnapshotReadOnlyError):
+        assert_snapshot("new", snapshot_path=str(read_only), snapshot_name="untouched")
+
+    after = snapshot.stat()
+    assert (after.st_ino, after.st_mtime_ns) == (before.st_ino, before.st_mtime_ns)
+
+
+def test_mismatch_leaves_no_pending_file(read_only: Path, monkeypatch):
+    monkeypatch.setenv("INSTA_UPDATE", "new")
+    snapshot = _committed(read_only, "pending")
+
+    with pytest.raises(AssertionError, match="did not match") as raised:
+        assert_snapshot("new", snapshot_path=str(read_only), snapshot_name="pending")
+
+    assert not isinstance(raised.value, SnapshotReadOnlyError)
+    assert [p.name for p in read_only.iterdir()] == [snapshot.name]
+
+
+def test_matching_snapshot_passes(read_only: Path):
+    _committed(read_only, "same")
+
+    assert_snapshot("old", snapshot_path=str(read_only), snapshot_name="same")
+
+
+def test_mock_cannot_record(read_only: Path):
+    def rate(currency: str) -> dict:
+        return {"currency": currency, "rate": 1.1}
+
+    with pytest.raises(SnapshotReadOnlyError):
+        mock_json_snapshot(func=rate, snapshot_path=str(read_only))("EUR")
+
+    assert not list(read_only.iterdir())
+
+
+def test_turned_off_writes_again(read_only: Path):
+    set_read_only(False)
+
+    assert_json_snapshot({"a": 1}, snapshot_path=str(read_only), snapshot_name="written")
+
+    assert len(list(read_only.glob("*@pysnap.snap"))) == 1
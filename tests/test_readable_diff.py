@@ -47,12 +47,12 @@ def _commit_snapshot(df, snap_dir: Path, name: str, fmt: str) -> None:
     """
 
     snap_dir.mkdir(parents=True, exist_ok=True)
-    metadata = snap_dir / f"pysnaptest__{name}@pysnap.snap"
+    metadata = snap_dir / f"test_readable_diff__{name}@pysnap.snap"
     metadata.write_text(
         f"---\nsource: src/lib.rs\nextension: {fmt}\nsnapshot_kind: binary\n---\n",
         encoding="utf-8",
     )
-    sidecar = snap_dir / f"pysnaptest__{name}@pysnap.snap.{fmt}"
+    sidecar = snap_dir / f"test_readable_diff__{name}@pysnap.snap.{fmt}"
     sidecar.write_bytes(_serialize(df, fmt))
 
 
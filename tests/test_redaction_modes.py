@@ -0,0 +1,49 @@
+"""Tests for redaction modes that keep a hint of the redacted value."""
+
+from __future__ import annotations
+
+import pytest
+
+from pysnaptest import (
+    assert_json_snapshot,
+    hash_redaction,
+    truncate_redaction,
+    type_redaction,
+)
+
+PAYLOAD = {
+    "token": "f1e2d3c4b5a6",
+    "session": "a9b8c7d6e5f4",
+    "user": {"id": 81723, "email": "ada@example.com", "roles": ["admin", "dev"]},
+    "note": "a very long free-text note that changes on every run",
+    "score": 0.75,
+    "verified": True,
+    "deleted_at": None,
+}
+
+
+def test_redaction_modes():
+    assert_json_snapshot(
+        PAYLOAD,
+        redactions={
+            ".token": hash_redaction(),
+            ".session": "hash",
+            "user": {"id": "type", "email": type_redaction(), "roles": "type"},
+            ".score": "type",
+            ".verified": "type",
+            ".deleted_at": "type",
+            ".note": truncate_redaction(11),
+        },
+    )
+
+
+def test_hash_redaction_is_stable():
+    for token in ("f1e2d3c4b5a6", "f1e2d3c4b5a6"):
+        assert_json_snapshot(
+            {"token": token}, redactions={".token": "hash"}, allow_duplicates=True
+        )
+
+
+def test_truncate_redaction_rejects_bad_length():
+    with pytest.raises(ValueError, match='expected "truncate:N"'):
+        assert_json_snapshot(PAYLOAD, redactions={".note": "truncate:ten"})
@@ -0,0 +1,53 @@
+"""Tests for redaction helpers that run insta's selector engine directly."""
+
+from __future__ import annotations
+
+import pytest
+
+from pysnaptest import validate_redactions
+
+
+PAYLOAD = {
+    "data": {
+        "a": {"id": 1, "name": "x"},
+        "b": {"id": 2, "name": "y"},
+    },
+    "items": [{"id": 3}, {"id": 4}],
+}
+
+
+def test_validate_redactions_all_match():
+    redactions = {".data.*.id": "[id]", ".items[].id": "[id]", ".**.name": None}
+    assert validate_redactions(PAYLOAD, redactions) == []
+
+
+def test_validate_redactions_reports_typos():
+    redactions = {
+        ".data[].id": "[id]",  # maps are not sequences
+        ".items.*.id": "[id]",
+        ".missing": "[gone]",
+    }
+    assert validate_redactions(PAYLOAD, redactions) == [".data[].id", ".missing"]
+
+
+def test_validate_redactions_invalid_selector():
+    with pytest.raises(ValueError, match="Invalid redaction selector"):
+        validate_redactions(PAYLOAD, {".data[": "[x]"})
+
+
+def test_validate_redactions_compiles_nested_maps():
+    redactions = {
+        "data": {"*": {"id": "[id]", "missing": "[gone]"}},
+        "items": {"[]": {"id": "[id]"}, 0: {"id": None}},
+        "headers": {"content-type": "[type]"},
+        ".**.name": None,
+    }
+    assert validate_redactions(PAYLOAD, redactions) == [
+        ".data.*.missing",
+        '.headers["content-type"]',
+    ]
+
+
+def test_validate_redactions_rejects_empty_nested_map():
+    with pytest.raises(ValueError, match='Nested redactions under ".data" are empty'):
+        validate_redactions(PAYLOAD, {"data": {}})
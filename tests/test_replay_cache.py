@@ -0,0 +1,85 @@
+"""Tests for caching the recordings parsed during mock replay."""
+
+from __future__ import annotations
+
+import os
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import clear_replay_cache, replay_cache_info, set_replay_cache_size
+from pysnaptest._pysnaptest import read_json_snapshot
+
+
+@pytest.fixture(autouse=True)
+def _fresh_cache():
+    clear_replay_cache()
+    yield
+    set_replay_cache_size(128)
+    clear_replay_cache()
+
+
+def _recording(folder: Path, name: str, body: str) -> Path:
+    path = folder / f"test_replay_cache__mocks__{name}@pysnap.snap"
+    path.write_text(f"---\nsource: src/mocks.rs\n---\n{body}\n")
+    return path
+
+
+def _counts() -> tuple:
+    info = replay_cache_info()
+    return info["hits"], info["misses"], info["entries"]
+
+
+def test_recording_is_parsed_once(tmp_path: Path):
+    path = _recording(tmp_path, "rate", '{"rate": 1.1}')
+
+    first = read_json_snapshot(path)
+    first["rate"] = 0
+    assert read_json_snapshot(path) == {"rate": 1.1}
+
+    assert _counts() == (1, 1, 1)
+
+
+def test_rewritten_recording_is_parsed_again(tmp_path: Path):
+    path = _recording(tmp_path, "rate", '{"rate": 1.1}')
+    read_json_snapshot(path)
+
+    _recording(tmp_path, "rate", '{"rate": 1.25}')
+    stat = path.stat()
+    os.utime(path, ns=(stat.st_atime_ns, stat.st_mtime_ns + 1_000_000_000))
+
+    assert read_json_snapshot(path) == {"rate": 1.25}
+    assert _counts() == (0, 2, 1)
+
+
+def test_least_recently_used_recording_is_evicted(tmp_path: Path):
+    set_replay_cache_size(2)
+    a, b, c = (_recording(tmp_path, name, "[]") for name in "abc")
+    read_json_snapshot(a)
+    read_json_snapshot(b)
+    read_json_snapshot(a)
+    read_json_snapshot(c)
+
+    read_json_snapshot(a)
+    read_json_snapshot(b)
+
+    assert _counts() == (2, 4, 2)
+
+
+def test_size_zero_turns_caching_off(tmp_path: Path):
+    path = _recording(tmp_path, "rate", "[]")
+    read_json_snapshot(path)
+
+    set_replay_cache_size(0)
+    read_json_snapshot(path)
+
+    assert _counts() == (0, 2, 0)
+    assert replay_cache_info()["capacity"] == 0
+
+
+def test_clear_replay_cache(tmp_path: Path):
+    read_json_snapshot(_recording(tmp_path, "rate", "[]"))
+
+    clear_replay_cache()
+
+    assert _counts() == (0, 0, 0)
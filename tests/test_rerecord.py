@@ -0,0 +1,58 @@
+"""Tests for deleting one test's mock recordings."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+from pysnaptest import rerecord
+
+HEADER = '---\nsource: src/mocks.rs\ndescription: "Test File Path: tests/test_api.py"\n'
+
+
+def _recording(snapshots: Path, name: str, test: str | None = None) -> Path:
+    path = snapshots / f"test_api__mocks__{name}@pysnap.snap"
+    info = f'info:\n  test: "{test}"\n' if test else ""
+    path.write_text(f"{HEADER}{info}---\n{{}}\n")
+    return path
+
+
+def _project(tmp_path: Path) -> Path:
+    tests = tmp_path / "tests"
+    (tests / "snapshots").mkdir(parents=True)
+    (tests / "test_api.py").write_text(
+        "def test_checkout():\n    pass\n\n\ndef test_checkout_refund():\n    pass\n"
+    )
+    return tests / "snapshots"
+
+
+def test_rerecord_uses_recorded_test(tmp_path: Path):
+    snapshots = _project(tmp_path)
+    node = "tests/test_api.py::test_checkout"
+    request = _recording(snapshots, "test_api_test_checkout_charge-0a1b2c3d-request", node)
+    response = _recording(snapshots, "test_api_test_checkout_charge-0a1b2c3d", node)
+    # Recorded under a custom snapshot_name, still found through its metadata.
+    renamed = _recording(snapshots, "custom_charge-0a1b2c3d", node)
+    other = _recording(
+        snapshots,
+        "test_api_test_checkout_refund_charge-0a1b2c3d",
+        "tests/test_api.py::test_checkout_refund",
+    )
+    plain = snapshots / "test_api__test_api_test_checkout@pysnap.snap"
+    plain.write_text(f"{HEADER}---\n{{}}\n")
+
+    deleted = rerecord(node, tmp_path)
+
+    assert deleted == sorted(p.resolve() for p in (request, response, renamed))
+    assert other.exists()
+    assert plain.exists()
+
+
+def test_rerecord_matches_older_recordings_by_name(tmp_path: Path):
+    snapshots = _project(tmp_path)
+    own = _recording(snapshots, "test_api_test_checkout_charge-0a1b2c3d")
+    longer = _recording(snapshots, "test_api_test_checkout_refund_charge-0a1b2c3d")
+
+    deleted = rerecord("tests/test_api.py::test_checkout", tmp_path)
+
+    assert deleted == [own.resolve()]
+    assert longer.exists()
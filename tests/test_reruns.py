@@ -0,0 +1,58 @@
+"""Tests for restarting snapshot numbering when a test is run again."""
+
+from __future__ import annotations
+
+import os
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import SnapshotInfo, assert_snapshot
+from pysnaptest._pysnaptest import observe_test_stage
+
+
+def _assert_twice(folder: Path) -> None:
+    for value in ("first", "second"):
+        with pytest.raises(AssertionError):
+            assert_snapshot(value, snapshot_path=folder, snapshot_name="flaky")
+
+
+def _node_id() -> str:
+    return os.environ["PYTEST_CURRENT_TEST"].rsplit(" (", 1)[0]
+
+
+def test_setup_of_a_rerun_restarts_numbering(tmp_path: Path):
+    _assert_twice(tmp_path)
+    info = SnapshotInfo.from_pytest(tmp_path, "flaky")
+    assert info.next_snapshot_name() == "flaky-3"
+
+    assert observe_test_stage(_node_id(), "setup")
+
+    assert info.next_snapshot_name() == "flaky"
+
+
+def test_stage_read_from_the_environment(tmp_path: Path, monkeypatch):
+    node_id = _node_id()
+    _assert_twice(tmp_path)
+
+    monkeypatch.setenv("PYTEST_CURRENT_TEST", f"{node_id} (teardown)")
+    assert SnapshotInfo.from_pytest(tmp_path, "flaky").next_snapshot_name() == (
+        "flaky-3"
+    )
+    monkeypatch.setenv("PYTEST_CURRENT_TEST", f"{node_id} (setup)")
+
+    assert SnapshotInfo.from_pytest(tmp_path, "flaky").next_snapshot_name() == (
+        "flaky"
+    )
+
+
+def test_later_stages_keep_numbering(tmp_path: Path):
+    _assert_twice(tmp_path)
+
+    assert not observe_test_stage(_node_id(), "call")
+    assert not observe_test_stage(_node_id(), "unknown")
+
+    info = SnapshotInfo.from_pytest(tmp_path, "flaky")
+    assert info.next_snapshot_name() == "flaky-3"
+    assert not observe_test_stage(_node_id(), "teardown")
+    assert info.next_snapshot_name() == "flaky-3"
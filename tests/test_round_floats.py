@@ -0,0 +1,29 @@
+"""Tests for rounding every float of a JSON snapshot."""
+
+from __future__ import annotations
+
+import pytest
+
+from pysnaptest import assert_json_snapshot, set_round_floats
+
+RESULT = {
+    "loss": 0.30000000000000004,
+    "epochs": 12,
+    "metrics": [{"auc": 0.91234567, "f1": 0.8765}, {"auc": 1e-9, "f1": -2.675}],
+}
+
+
+@pytest.fixture
+def rounding():
+    yield
+    set_round_floats(None)
+
+
+def test_round_floats_per_call():
+    assert_json_snapshot(RESULT, round_floats=3)
+
+
+def test_round_floats_globally(rounding):
+    set_round_floats(2)
+    assert_json_snapshot(RESULT)
+    assert_json_snapshot(RESULT, round_floats=4)
@@ -0,0 +1,122 @@
+"""Tests for schema snapshots, which pin a payload's shape instead of its data."""
+
+from __future__ import annotations
+
+import random
+import shutil
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import (
+    SchemaViolationError,
+    assert_schema_snapshot,
+    validate_against_schema_snapshot,
+)
+
+
+def order(rng: random.Random) -> dict:
+    return {
+        "id": rng.randint(1, 10_000),
+        "total": round(rng.uniform(1, 100), 2),
+        "coupon": None,
+        "lines": [
+            {"sku": "A-1", "quantity": rng.randint(1, 5), "note": "gift"},
+            {"sku": "B-2", "quantity": rng.randint(1, 5), "note": None},
+            {"sku": "C-3", "quantity": rng.randint(1, 5)},
+        ],
+        "tags": [],
+        "paid": rng.random() < 0.5,
+    }
+
+
+def test_schema_snapshot():
+    assert_schema_snapshot(order(random.Random(1)))
+
+
+def test_schema_snapshot_ignores_values(tmp_path: Path):
+    shutil.copy(
+        Path(__file__).parent
+        / "snapshots"
+        / "test_schema_snapshots__test_schema_snapshots_test_schema_snapshot@pysnap.snap",
+        tmp_path / "test_schema_snapshots__orders@pysnap.snap",
+    )
+    assert_schema_snapshot(
+        order(random.Random(2)), snapshot_path=str(tmp_path), snapshot_name="orders"
+    )
+
+
+def test_schema_snapshot_fails_on_shape_change(tmp_path: Path):
+    shutil.copy(
+        Path(__file__).parent
+        / "snapshots"
+        / "test_schema_snapshots__test_schema_snapshots_test_schema_snapshot@pysnap.snap",
+        tmp_path / "test_schema_snapshots__changed_orders@pysnap.snap",
+    )
+    changed = order(random.Random(3))
+    changed["total"] = str(changed["total"])
+    with pytest.raises(AssertionError):
+        assert_schema_snapshot(
+            changed, snapshot_path=str(tmp_path), snapshot_name="changed_orders"
+        )
+
+
+def test_validate_against_schema_snapshot(tmp_path: Path):
+    shutil.copy(
+        Path(__file__).parent
+        / "snapshots"
+        / "test_schema_snapshots__test_schema_snapshots_test_schema_snapshot@pysnap.snap",
+        tmp_path / "test_schema_snapshots__valid_orders@pysnap.snap",
+    )
+    payload = order(random.Random(4))
+    payload["tags"] = ["rush"]
+    payload["currency"] = "EUR"
+    payload["lines"].append({"sku": "D-4", "quantity": 1})
+    validate_against_schema_snapshot(
+        payload, snapshot_path=str(tmp_path), snapshot_name="valid_orders"
+    )
+
+
+def test_validate_against_schema_snapshot_reports_violations(tmp_path: Path):
+    shutil.copy(
+        Path(__file__).parent
+        / "snapshots"
+        / "test_schema_snapshots__test_schema_snapshots_test_schema_snapshot@pysnap.snap",
+        tmp_path / "test_schema_snapshots__broken_orders@pysnap.snap",
+    )
+    payload = order(random.Random(5))
+    payload["total"] = "12.50"
+    del payload["paid"]
+    del payload["lines"][2]["sku"]
+    with pytest.raises(SchemaViolationError, match="3 violation") as exc_info:
+        validate_against_schema_snapshot(
+            payload, snapshot_path=str(tmp_path), snapshot_name="broken_orders"
+        )
+    assert isinstance(exc_info.value, AssertionError)
+    assert exc_info.value.violations == [
+        {
+            "path": ".paid",
+            "kind": "missing_key",
+            "expected": "boolean",
+            "actual": None,
+        },
+        {
+            "path": ".lines[2].sku",
+            "kind": "missing_key",
+            "expected": "string",
+            "actual": None,
+        },
+        {
+            "path": ".total",
+            "kind": "type_mismatch",
+            "expected": "number",
+            "actual": "string",
+        },
+    ]
+
+
+def test_validate_against_schema_snapshot_requires_a_schema(tmp_path: Path):
+    with pytest.raises(ValueError, match="record one with assert_schema_snapshot"):
+        validate_against_schema_snapshot(
+            {}, snapshot_path=str(tmp_path), snapshot_name="missing"
+        )
@@ -0,0 +1,76 @@
+"""Tests for snapshots shared by the tests of a module or of the session."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import SnapshotInfo, mock_json_snapshot
+from pysnaptest._pysnaptest import assert_snapshot as _assert_snapshot
+
+SNAPSHOTS = Path(__file__).parent / "snapshots"
+
+
+def exchange_rate(currency: str) -> dict:
+    return {"currency": currency, "rate": 1.1}
+
+
+def test_module_scope_names_snapshots_after_the_module():
+    info = SnapshotInfo.from_pytest(scope="module")
+
+    assert info.scope() == "module"
+    assert info.snapshot_folder() == SNAPSHOTS.resolve()
+    assert info.next_snapshot_name() == "test_scopes"
+    assert SnapshotInfo.from_pytest().scope() is None
+
+
+def test_session_scope_is_stored_at_the_workspace_root(tmp_path: Path, monkeypatch):
+    monkeypatch.setenv("INSTA_WORKSPACE_ROOT", str(tmp_path))
+
+    info = SnapshotInfo.from_pytest(scope="session")
+
+    assert info.snapshot_folder() == tmp_path / "snapshots"
+    assert info.next_snapshot_name() == "session"
+    assert info.last_snapshot_path().name == "session@pysnap.snap"
+
+
+def test_overrides_win_over_the_scope(tmp_path: Path):
+    info = SnapshotInfo.from_pytest(tmp_path, "rates", scope="session")
+
+    assert info.snapshot_folder() == tmp_path
+    assert info.next_snapshot_name() == "rates"
+
+
+def test_each_dependent_test_replays_from_the_base_name(tmp_path: Path, monkeypatch):
+    names = {}
+    for test in ("test_one", "test_two"):
+        monkeypatch.setenv("PYTEST_CURRENT_TEST", f"tests/test_scopes.py::{test} (call)")
+        info = SnapshotInfo.from_pytest(tmp_path, scope="module")
+        for value in ("first", "second")[: len(names) + 1]:
+            with pytest.raises(AssertionError):
+                _assert_snapshot(info, value)
+        names[test] = info.last_snapshot_name()
+
+    assert names == {"test_one": "test_scopes", "test_two": "test_scopes-2"}
+    assert info.references() == [
+        "tests/test_scopes.py::test_one",
+        "tests/test_scopes.py::test_two",
+    ]
+
+
+def test_scoped_mocks_are_shared(tmp_path: Path):
+    mocked = mock_json_snapshot(
+        func=exchange_rate, snapshot_path=str(tmp_path), scope="module"
+    )
+
+    with pytest.raises(AssertionError):
+        mocked("EUR")
+
+    (pending,) = tmp_path.glob("*-request@pysnap.snap.new")
+    assert pending.name.startswith("test_scopes__mocks__test_scopes_exchange_rate")
+
+
+def test_invalid_scope():
+    with pytest.raises(ValueError, match='Invalid snapshot scope "class"'):
+        SnapshotInfo.from_pytest(scope="class")
@@ -0,0 +1,106 @@
+"""Tests for keeping snapshots in a scratch storage instead of their folder."""
+
+from __future__ import annotations
+
+import os
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import (
+    ScratchStorage,
+    SnapshotInfo,
+    SnapshotSettings,
+    assert_json_snapshot,
+    mock_json_snapshot,
+)
+from pysnaptest._pysnaptest import observe_test_stage
+
+
+@pytest.fixture
+def updating(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> Path:
+    """``INSTA_UPDATE=always`` under a workspace root insta has not read a
+    config for."""
+
+    monkeypatch.setenv("INSTA_WORKSPACE_ROOT", str(tmp_path))
+    monkeypatch.setenv("INSTA_UPDATE", "always")
+    monkeypatch.delenv("INSTA_FORCE_PASS", raising=False)
+    monkeypatch.delenv("PYSNAPTEST_UPDATE_FILTER", raising=False)
+    return tmp_path
+
+
+def _rerun() -> None:
+    """Start the running test again, so its snapshots are numbered afresh."""
+
+    observe_test_stage(os.environ["PYTEST_CURRENT_TEST"].rsplit(" (", 1)[0], "setup")
+
+
+def test_assertions_keep_snapshots_in_storage(updating: Path):
+    storage = ScratchStorage()
+    folder = updating / "snapshots"
+
+    with SnapshotSettings(storage=storage):
+        for value in (1, 1, 2):
+            _rerun()
+            assert_json_snapshot({"a": value}, snapshot_path=str(folder), snapshot_name="kept")
+
+    assert not list(updating.iterdir())
+    ((path, contents),) = storage.files().items()
+    assert path.name.endswith("kept@pysnap.snap")
+    assert contents.endswith(b'{\n  "a": 2\n}\n')
+    assert storage.read(path) == contents
+    assert not path.exists()
+
+    storage.clear()
+    assert storage.read(path) is None
+
+
+def test_mismatch_against_a_snapshot_in_storage(updating: Path, monkeypatch):
+    storage = ScratchStorage()
+    settings = SnapshotSettings(storage=storage)
+    assert_json_snapshot(
+        {"a": 1}, snapshot_path=str(updating), snapshot_name="kept", settings=settings
+    )
+    (stored,) = storage.files().values()
+    monkeypatch.setenv("PYSNAPTEST_UPDATE_FILTER", "nothing")
+    _rerun()
+
+    with pytest.raises(AssertionError, match="did not match"):
+        assert_json_snapshot(
+            {"a": 2}, snapshot_path=str(updating), snapshot_name="kept", settings=settings
+        )
+
+    assert list(storage.files().values()) == [stored]
+    assert not list(updating.iterdir())
+
+
+def test_each_info_chooses_its_storage(tmp_path: Path):
+    info = SnapshotInfo.manual(tmp_path, "helper")
+    first, second = ScratchStorage(), ScratchStorage()
+
+    in_first = info.with_storage(first)
+
+    assert in_first.snapshot_folder() != info.snapshot_folder()
+    assert in_first.snapshot_folder() != info.with_storage(second).snapshot_folder()
+    assert in_first.with_storage(None).snapshot_folder() == info.snapshot_folder()
+    assert in_first != info
+    assert len(first) == len(second) == 0
+
+
+def test_mock_replays_from_storage(updating: Path):
+    storage = ScratchStorage()
+    calls = []
+
+    def rate(currency: str) -> dict:
+        calls.append(currency)
+        return {"currency": currency, "rate": 1.1}
+
+    with SnapshotSettings(storage=storage):
+        mock_json_snapshot(func=rate, snapshot_path=str(updating))("EUR")
+        _rerun()
+        result = mock_json_snapshot(func=rate, snapshot_path=str(updating))("EUR")
+
+    assert result == {"currency": "EUR", "rate": 1.1}
+    assert calls == ["EUR"]
+    assert len(storage) == 2
+    assert not list(updating.iterdir())
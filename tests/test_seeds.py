@@ -0,0 +1,79 @@
+"""Tests for seed-scoped snapshots of property-based-testing examples."""
+
+from __future__ import annotations
+
+import shutil
+from pathlib import Path
+
+from pysnaptest import (
+    assert_snapshot_for_seed,
+    list_seed_snapshots,
+    prune_seed_snapshots,
+)
+from pysnaptest.seeds import seed_label
+
+SNAPSHOTS = Path(__file__).parent / "snapshots"
+
+
+def parse_duration(text: str) -> dict:
+    value, unit = text[:-1], text[-1]
+    return {"value": int(value), "unit": {"s": "seconds", "m": "minutes"}[unit]}
+
+
+def test_assert_snapshot_for_seed():
+    # Counterexamples found by a property test, pinned for good.
+    assert_snapshot_for_seed(
+        302934307671667531413257853548643485645, parse_duration("0s")
+    )
+    assert_snapshot_for_seed("ex:17", parse_duration("90m"))
+
+
+def test_seed_label():
+    assert seed_label(42) == "42"
+    assert seed_label("ex:17/a b") == "ex_17_a_b"
+
+
+def _project(tmp_path: Path) -> Path:
+    snapshots = tmp_path / "tests" / "snapshots"
+    snapshots.mkdir(parents=True)
+    (tmp_path / "tests" / "test_durations.py").write_text(
+        "def test_parse():\n    pass\n\ndef test_parse_more():\n    pass\n"
+    )
+    for source in SNAPSHOTS.glob(
+        "test_seeds__test_seeds_test_assert_snapshot_for_seed_seed-*"
+    ):
+        for test in ("test_parse", "test_parse_more"):
+            target = source.name.replace(
+                "test_seeds__test_seeds_test_assert_snapshot_for_seed",
+                f"test_durations__test_durations_{test}",
+            )
+            shutil.copy(source, snapshots / target)
+    return tmp_path
+
+
+def test_list_seed_snapshots(tmp_path: Path):
+    root = _project(tmp_path)
+
+    listed = list_seed_snapshots("tests/test_durations.py::test_parse", root=root)
+    assert [(snapshot.name, snapshot.seed) for snapshot in listed] == [
+        ("test_durations_test_parse", "302934307671667531413257853548643485645"),
+        ("test_durations_test_parse", "ex_17"),
+    ]
+    assert len(list_seed_snapshots(root=root)) == 4
+
+
+def test_prune_seed_snapshots(tmp_path: Path):
+    root = _project(tmp_path)
+
+    deleted = prune_seed_snapshots(
+        ["ex:17"], "tests/test_durations.py::test_parse", root=root
+    )
+    assert [path.name for path in deleted] == [
+        "test_durations__test_durations_test_parse_seed-"
+        "302934307671667531413257853548643485645@pysnap.snap"
+    ]
+    assert [snapshot.seed for snapshot in list_seed_snapshots(root=root)] == [
+        "302934307671667531413257853548643485645",
+        "ex_17",
+        "ex_17",
+    ]
@@ -0,0 +1,82 @@
+"""Tests for the depth and size limits of values converted to JSON."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import (
+    SerializationError,
+    assert_json_snapshot,
+    serialization_limits_info,
+    set_serialization_limits,
+    to_jsonable,
+)
+from pysnaptest._pysnaptest import validate_redactions
+
+
+@pytest.fixture(autouse=True)
+def _default_limits():
+    yield
+    set_serialization_limits()
+
+
+def _nested(depth: int) -> dict:
+    value: dict = {}
+    for _ in range(depth - 1):
+        value = {"child": value}
+    return value
+
+
+def test_default_limits():
+    assert serialization_limits_info() == {
+        "max_depth": 256,
+        "max_size": 100 * 1024 * 1024,
+    }
+
+
+def test_value_nested_too_deep(tmp_path: Path):
+    set_serialization_limits(max_depth=3)
+    assert to_jsonable(_nested(3)) == {"child": {"child": {}}}
+
+    with pytest.raises(SerializationError) as error:
+        assert_json_snapshot({"tree": [_nested(2)]}, snapshot_path=str(tmp_path))
+
+    assert str(error.value) == "value nests deeper than 3 levels at path .tree[0].child"
+    assert not list(tmp_path.iterdir())
+
+
+def test_rust_conversion_checks_depth():
+    set_serialization_limits(max_depth=2)
+
+    with pytest.raises(SerializationError, match=r"than 2 levels at path \.a\[0\]$"):
+        validate_redactions({"a": [[1]]}, {".a": "[a]"})
+
+
+def test_default_depth_allows_deep_values(tmp_path: Path):
+    with pytest.raises(AssertionError) as error:
+        assert_json_snapshot(_nested(200), snapshot_path=str(tmp_path))
+
+    assert not isinstance(error.value, SerializationError)
+
+
+def test_value_too_large(tmp_path: Path):
+    set_serialization_limits(max_size=1000)
+    payload = {"id": 1, "rows": [{"blob": "x" * 2000}, {"blob": "y"}]}
+
+    with pytest.raises(SerializationError) as error:
+        assert_json_snapshot(payload, snapshot_path=str(tmp_path))
+
+    assert str(error.value) == (
+        "value serializes to 2042 bytes of JSON, over the limit of 1000 bytes; "
+        "most of it is .rows[0].blob (2002 bytes)"
+    )
+    assert not list(tmp_path.iterdir())
+
+
+def test_limits_can_be_lifted():
+    set_serialization_limits(max_depth=None, max_size=None)
+
+    assert serialization_limits_info() == {"max_depth": None, "max_size": None}
+    assert validate_redactions(_nested(300), {".child": "[c]"}) == []
@@ -0,0 +1,60 @@
+"""Tests for deriving SnapshotInfo copies with another name, folder or
+duplicate policy."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import SnapshotInfo
+from pysnaptest._pysnaptest import assert_json_snapshot
+
+
+def test_one_info_per_pipeline_stage():
+    info = SnapshotInfo.from_pytest()
+
+    for stage, rows in [("extract", [1, 2, 3]), ("load", [3])]:
+        stage_info = info.with_name(stage)
+        assert_json_snapshot(stage_info, {"rows": rows})
+        assert Path(stage_info.last_snapshot_path()).name == (
+            "test_snapshot_info_builders__"
+            f"test_snapshot_info_builders_test_one_info_per_pipeline_stage_{stage}"
+            "@pysnap.snap"
+        )
+
+
+def test_builders_return_new_infos(tmp_path: Path):
+    info = SnapshotInfo.manual(tmp_path, "report")
+
+    renamed = info.with_name("v2/final")
+    moved = info.with_folder(tmp_path / "other")
+    shared = info.with_allow_duplicates(True)
+
+    assert (info.name, info.folder, info.allow_duplicates) == (
+        "report",
+        tmp_path,
+        False,
+    )
+    assert renamed.name == "report_v2final"
+    assert moved.folder == tmp_path / "other"
+    assert moved.name == "report"
+    assert shared.allow_duplicates is True
+    assert shared.with_allow_duplicates(False) == info
+
+
+def test_with_name_needs_a_suffix(tmp_path: Path):
+    with pytest.raises(ValueError, match="non-empty suffix"):
+        SnapshotInfo.manual(tmp_path, "report").with_name("")
+
+
+def test_with_folder_writes_there(tmp_path: Path, monkeypatch: pytest.MonkeyPatch):
+    monkeypatch.chdir(tmp_path)
+    info = SnapshotInfo.manual(tmp_path / "unused", "report").with_folder("relative")
+
+    assert info.folder == tmp_path / "relative"
+    with pytest.raises(AssertionError):
+        assert_json_snapshot(info, {"ok": True})
+    assert [p.name for p in (tmp_path / "relative").iterdir()] == [
+        "report@pysnap.snap.new"
+    ]
@@ -0,0 +1,138 @@
+"""Tests for SnapshotSettings passed to assertions or entered as a context."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import (
+    SnapshotInfo,
+    SnapshotSettings,
+    assert_json_snapshot,
+    assert_snapshot,
+)
+from pysnaptest.assertion import insta_snapshot
+
+SNAPSHOTS = Path(__file__).parent / "snapshots"
+
+
+def test_settings_passed_to_an_assertion():
+    settings = SnapshotSettings(
+        snapshot_suffix="linux",
+        description="Filtered and redacted",
+        filters={r"\d{4}-\d\d-\d\d": "[date]"},
+        redactions={".token": "[token]"},
+    )
+
+    assert_json_snapshot(
+        {"token": "abc", "created": "2026-10-16"},
+        snapshot_name="settings_given",
+        settings=settings,
+    )
+
+    path = SNAPSHOTS / "test_snapshot_settings__settings_given_linux@pysnap.snap"
+    header, body = path.read_text().split("\n---\n", 1)
+    assert (
+        'description: "Filtered and redacted; Test File Path: '
+        'tests/test_snapshot_settings.py"'
+    ) in header.splitlines()
+    assert body == '{\n  "created": "[date]",\n  "token": "[token]"\n}\n'
+
+
+def test_entered_settings_apply_to_every_assertion(tmp_path: Path):
+    with pytest.raises(AssertionError):
+        with SnapshotSettings(snapshot_path=tmp_path, filters=[("secret", "[x]")]):
+            assert_snapshot("a secret", snapshot_name="entered")
+
+    pending = tmp_path / "test_snapshot_settings__entered@pysnap.snap.new"
+    assert pending.read_text().endswith("---\na [x]\n")
+    assert SnapshotSettings.current().snapshot_path is None
+
+
+def test_insta_snapshot_passes_settings_on(tmp_path: Path):
+    settings = SnapshotSettings(snapshot_path=tmp_path, sort_maps=True)
+
+    with pytest.raises(AssertionError):
+        insta_snapshot({"b": 1, "a": 2}, snapshot_name="dispatched", settings=settings)
+
+    pending = tmp_path / "test_snapshot_settings__dispatched@pysnap.snap.new"
+    assert pending.read_text().endswith('---\n{\n  "a": 2,\n  "b": 1\n}\n')
+
+
+def test_nested_and_given_settings_merge(tmp_path: Path):
+    outer = SnapshotSettings(
+        snapshot_path=tmp_path, description="outer", filters={"a": "b"}
+    )
+    inner = SnapshotSettings(description="inner", sort_maps=True)
+    given = SnapshotSettings(snapshot_suffix="given", filters={"c": "d"})
+
+    with outer, inner:
+        merged = SnapshotSettings.current(given)
+        info = SnapshotInfo.from_pytest(snapshot_name_override="merged", settings=given)
+
+    assert merged.snapshot_path == tmp_path
+    assert merged.description == "inner"
+    assert merged.snapshot_suffix == "given"
+    assert merged.sort_maps
+    assert merged.filters == [("a", "b"), ("c", "d")]
+    assert info.snapshot_folder() == tmp_path
+    assert info.next_snapshot_name() == "merged_given"
+
+
+def test_assertion_arguments_win(tmp_path: Path):
+    settings = SnapshotSettings(snapshot_path=tmp_path / "settings")
+
+    info = SnapshotInfo.from_pytest(tmp_path / "argument", settings=settings)
+
+    assert info.snapshot_folder() == tmp_path / "argument"
+
+
+def test_invalid_settings():
+    with pytest.raises(ValueError, match="Invalid filter regex"):
+        SnapshotSettings(filters={"(": ""})
+    with pytest.raises(ValueError, match="snapshot_suffix must not be empty"):
+        SnapshotSettings(snapshot_suffix="")
+
+
+@pytest.fixture
+def api_settings(tmp_path: Path):
+    with SnapshotSettings(snapshot_path=tmp_path, description="From the fixture"):
+        yield tmp_path
+
+
+def test_fixture_binds_settings_for_the_test(api_settings: Path):
+    assert SnapshotInfo.from_pytest().snapshot_folder() == api_settings
+
+    with SnapshotSettings(description="Inner block", snapshot_suffix="inner"):
+        with pytest.raises(AssertionError):
+            assert_snapshot("inner", snapshot_name="scoped")
+    with pytest.raises(AssertionError):
+        assert_snapshot("outer", snapshot_name="scoped")
+
+    inner = api_settings / "test_snapshot_settings__scoped_inner@pysnap.snap.new"
+    outer = api_settings / "test_snapshot_settings__scoped@pysnap.snap.new"
+    assert "description: Inner block" in inner.read_text().splitlines()
+    assert "description: From the fixture" in outer.read_text().splitlines()
+
+
+def test_bind_enters_settings_for_one_call(tmp_path: Path):
+    settings = SnapshotSettings(snapshot_path=tmp_path)
+
+    folder = settings.bind(lambda: SnapshotInfo.from_pytest().snapshot_folder())
+
+    assert folder == tmp_path
+    assert SnapshotSettings.current().snapshot_path is None
+
+
+def test_every_constructor_uses_entered_settings(tmp_path: Path):
+    with SnapshotSettings(snapshot_path=tmp_path, snapshot_suffix="s"):
+        unittest_info = SnapshotInfo.from_unittest(__file__, "TestApi", "test_get")
+        manual_info = SnapshotInfo.manual(tmp_path / "manual", "named")
+
+    assert unittest_info.snapshot_folder() == tmp_path
+    assert unittest_info.next_snapshot_name() == (
+        "test_snapshot_settings_TestApi.test_get_s"
+    )
+    assert manual_info.snapshot_folder() == tmp_path / "manual"
+    assert manual_info.next_snapshot_name() == "named_s"
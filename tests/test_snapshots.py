@@ -1,12 +1,16 @@
 from __future__ import annotations
+from concurrent.futures import ThreadPoolExecutor
 from pathlib import Path
 import sys
 import platform
+import inspect
 import json
+import os
 
 from pysnaptest import (
     snapshot,
     assert_json_snapshot,
+    assert_call_snapshot,
     assert_csv_snapshot,
     assert_dataframe_snapshot,
     assert_binary_snapshot,
@@ -15,8 +19,11 @@ from pysnaptest import (
     assert_snapshot,
     extract_from_pytest_env,
     PySnapshot,
+    SnapshotInfo,
     mock_json_snapshot,
 )
+from pysnaptest._pysnaptest import assert_json_snapshot as assert_json_snapshot_for
+from pysnaptest._pysnaptest import observe_test_stage, set_read_only, settle_repetitions
 import pytest
 
 try:
@@ -61,6 +68,24 @@ def test_next_snapshot_allow_duplicates():
     )
 
 
+@pytest.mark.parametrize("source", ["data/in:put.json"])
+def test_parametrized_id_is_sanitized(source):
+    assert_snapshot(source)
+    assert Path(extract_from_pytest_env().last_snapshot_path(None)).exists()
+
+
+def test_long_test_id_is_shortened(monkeypatch):
+    test_id = f"test_long[{'x' * 300}]"
+    monkeypatch.setenv("PYTEST_CURRENT_TEST", f"tests/test_snapshots.py::{test_id} (call)")
+    info = extract_from_pytest_env()
+    name = info.last_snapshot_name()
+    assert len(name) <= 180
+    assert name.startswith("test_snapshots_test_long[xxx")
+    stem = Path(info.next_snapshot_path(None)).name.split("@", 1)[0]
+    assert len(stem) <= 180
+    assert stem.startswith("test_snapshots__test_snapshots_test_long[xxx")
+
+
 def test_snapshot_folder():
     snapshot_info = extract_from_pytest_env()
     folder = Path(snapshot_info.snapshot_folder())
@@ -68,6 +93,90 @@ def test_snapshot_folder():
     assert folder == Path(__file__).parent / "snapshots"
 
 
+TESTS_DIR = Path(__file__).resolve().parent
+
+
+def test_test_file_found_from_a_subdirectory(monkeypatch):
+    monkeypatch.delenv("INSTA_WORKSPACE_ROOT", raising=False)
+    monkeypatch.delenv("PYTEST_ROOTDIR", raising=False)
+    monkeypatch.chdir(TESTS_DIR / "snapshots")
+    folder = extract_from_pytest_env().snapshot_folder()
+    assert Path(folder).resolve() == TESTS_DIR / "snapshots"
+
+
+@pytest.mark.parametrize("root_env", ["INSTA_WORKSPACE_ROOT", "PYTEST_ROOTDIR"])
+def test_test_file_found_under_workspace_root(root_env, monkeypatch, tmp_path):
+    monkeypatch.delenv("INSTA_WORKSPACE_ROOT", raising=False)
+    monkeypatch.delenv("PYTEST_ROOTDIR", raising=False)
+    monkeypatch.setenv(root_env, str(TESTS_DIR.parent))
+    monkeypatch.chdir(tmp_path)
+    folder = extract_from_pytest_env().snapshot_folder()
+    assert Path(folder).resolve() == TESTS_DIR / "snapshots"
+
+
+def test_manual_snapshot_info(monkeypatch):
+    monkeypatch.delenv("PYTEST_CURRENT_TEST")
+    info = SnapshotInfo.manual(TESTS_DIR / "snapshots", "manual_snapshot_info")
+    assert_json_snapshot_for(info, {"source": "script"})
+
+
+def test_snapshot_file_named_after_test_module():
+    assert_json_snapshot({"prefix": "module"})
+    path = Path(extract_from_pytest_env().last_snapshot_path())
+    assert path.name == (
+        "test_snapshots__test_snapshots_test_snapshot_file_named_after_test_module"
+        "@pysnap.snap"
+    )
+    assert path.exists()
+
+
+def test_snapshot_file_without_prefix():
+    assert_json_snapshot({"prefix": "none"}, prefix="none")
+    info = extract_from_pytest_env()
+    assert Path(info.last_snapshot_path(prefix="none")).exists()
+    assert not Path(info.last_snapshot_path()).exists()
+
+
+def test_snapshot_path_rejects_a_positional_module_path():
+    info = extract_from_pytest_env()
+    assert info.last_snapshot_path(None) == info.last_snapshot_path()
+    with pytest.raises(TypeError, match="no longer takes a module path"):
+        info.last_snapshot_path("pysnaptest::common")
+    with pytest.raises(TypeError, match="no longer takes a module path"):
+        info.next_snapshot_path("none")
+
+
+def test_snapshot_file_with_custom_prefix(monkeypatch):
+    monkeypatch.setenv("PYSNAPTEST_PREFIX", "api.v2")
+    assert_snapshot("custom prefix")
+    path = Path(extract_from_pytest_env().last_snapshot_path())
+    assert path.name.startswith("api__v2__test_snapshots_")
+    assert path.exists()
+    with pytest.raises(ValueError, match="Invalid snapshot prefix"):
+        extract_from_pytest_env(prefix="")
+
+
+def test_snapshot_info_from_frame():
+    for frame in (inspect.currentframe(), inspect.stack()[0]):
+        info = SnapshotInfo.from_frame(frame)
+        assert Path(info.snapshot_folder()) == TESTS_DIR / "snapshots"
+        assert info.next_snapshot_name() == "test_snapshots_test_snapshot_info_from_frame"
+
+
+def test_snapshot_info_from_frame_without_file():
+    namespace = {}
+    exec(compile("import inspect\nframe = inspect.currentframe()", "<cell>", "exec"), namespace)
+    with pytest.raises(ValueError, match="SnapshotInfo.manual"):
+        SnapshotInfo.from_frame(namespace["frame"])
+
+
+@pytest.mark.skipif(platform.system() != "Windows", reason="Windows paths only")
+def test_snapshot_folder_is_not_verbatim_on_windows():
+    folder = extract_from_pytest_env().snapshot_folder()
+    assert not str(folder).startswith("\\\\?\\")
+    assert Path(folder).is_dir()
+
+
 @snapshot
 def test_snapshot_dict_result() -> dict[str, str]:
     return {"test": 2}
@@ -88,6 +197,52 @@ def test_snapshot_rounded_redactions() -> list[str]:
     return {"test": 1.236789}
 
 
+@snapshot(sort_arrays_by={".users": "id", ".users[].roles": "name"})
+def test_snapshot_sort_arrays_by() -> dict:
+    return {
+        "users": [
+            {"id": 3, "roles": [{"name": "viewer"}, {"name": "admin"}]},
+            {"id": 1, "roles": []},
+            {"id": 2, "roles": [{"name": "editor"}]},
+        ]
+    }
+
+
+def test_assert_json_snapshot_sort_arrays_by_nested_field():
+    assert_json_snapshot(
+        [
+            {"user": {"name": "zoe"}, "total": 3},
+            {"user": {"name": "adam"}, "total": 7},
+            {"total": 0},
+        ],
+        sort_arrays_by={".": "user.name"},
+    )
+
+
+def test_assert_json_snapshot_ignore():
+    assert_json_snapshot(
+        {
+            "order": {"id": 7, "trace_id": "a1b2c3"},
+            "lines": [{"sku": "A", "debug": {"cache": "hit"}}, {"sku": "B"}],
+            "events": ["created", "noise", "paid"],
+        },
+        ignore=[".order.trace_id", ".lines[].debug", ".events[1]"],
+    )
+
+
+def apply_discount(cart: dict, percent: int, *, round_to: int = 2) -> dict:
+    total = sum(cart["prices"]) * (100 - percent) / 100
+    cart["prices"].clear()
+    return {"total": round(total, round_to)}
+
+
+def test_assert_call_snapshot():
+    result = assert_call_snapshot(
+        apply_discount, {"prices": [10.0, 5.5]}, 10, round_to=1
+    )
+    assert result == {"total": 13.9}
+
+
 def test_assert_json_snapshot():
     assert_json_snapshot({"assert_json_snapshot": "expected_result"})
 
@@ -104,12 +259,53 @@ def test_assert_csv_snapshot_simple():
     assert_csv_snapshot("a,b\n1,2")
 
 
+LARGE_CSV = "id,value\n" + "".join(f"{i},{i * i}\n" for i in range(10_000))
+
+
+def test_assert_csv_snapshot_max_rows():
+    assert_csv_snapshot(LARGE_CSV, max_rows=3)
+
+
+def test_assert_csv_snapshot_sample():
+    assert_csv_snapshot(LARGE_CSV, sample=4)
+
+
+def test_assert_csv_snapshot_rejects_max_rows_and_sample():
+    with pytest.raises(ValueError, match="either max_rows or sample"):
+        assert_csv_snapshot(LARGE_CSV, max_rows=3, sample=4)
+
+
+def test_assert_csv_snapshot_head_tail_columns():
+    assert_csv_snapshot(LARGE_CSV, head=2, tail=2, columns=["value", "id"])
+
+
+def test_assert_csv_snapshot_summary():
+    csv = "id,name,score\n1,ada,9.5\n2,bob,\n10,cy,-1\n"
+    assert_csv_snapshot(csv, head=1, summary=True)
+
+
+def test_assert_csv_snapshot_unknown_column():
+    with pytest.raises(ValueError, match="Unknown CSV column"):
+        assert_csv_snapshot(LARGE_CSV, columns=["missing"])
+
+
+def test_assert_csv_snapshot_malformed():
+    with pytest.raises(ValueError, match="Failed to parse CSV records"):
+        assert_csv_snapshot("a,b\n1,2,3")
+
+
 @pytest.mark.skipif(PANDAS_UNAVAILABLE, reason="Pandas is an optional dependency")
 def test_assert_pandas_dataframe_snapshot():
     df = pd.DataFrame({"name": ["foo", "bar"], "id": [1, 2]})
     assert_dataframe_snapshot(df, index=False)
 
 
+@pytest.mark.skipif(PANDAS_UNAVAILABLE, reason="Pandas is an optional dependency")
+def test_assert_pandas_dataframe_snapshot_slice():
+    df = pd.DataFrame({"name": ["foo", "bar", "baz"], "id": [1, 2, 3]})
+    assert_dataframe_snapshot(df, index=False, head=1, columns=["id"], summary=True)
+
+
 @pytest.mark.skipif(
     PANDAS_UNAVAILABLE or platform.system() != "Darwin",
     reason="Pandas is an optional dependency",
@@ -225,7 +421,7 @@ def test_snapshot_contents_json():
     snapshot_name = "test_snapshot_contents_json"
     assert_json_snapshot({"test": "content"}, snapshot_name=snapshot_name)
     snapshot = PySnapshot.from_file(
-        r"tests/snapshots/pysnaptest__test_snapshot_contents_json@pysnap.snap"
+        r"tests/snapshots/test_snapshots__test_snapshot_contents_json@pysnap.snap"
     )
     result = json.loads(snapshot.contents())
     assert_json_snapshot(result, snapshot_name=snapshot_name, allow_duplicates=True)
@@ -246,6 +442,114 @@ def test_snapshot_then_load():
     assert snapshot.contents().decode() == expected
 
 
+def test_snapshot_contents_text():
+    expected = "expected_result_1"
+    assert_snapshot(expected)
+    snapshot = PySnapshot.from_file(extract_from_pytest_env().last_snapshot_path(None))
+    assert snapshot.contents_text() == expected
+
+
+def test_snapshot_contents_text_rejects_binary():
+    assert_binary_snapshot(b"\x00\x01", extension="bin")
+    snapshot = PySnapshot.from_file(extract_from_pytest_env().last_snapshot_path(None))
+    assert snapshot.contents() == b"\x00\x01"
+    with pytest.raises(ValueError, match="binary"):
+        snapshot.contents_text()
+
+
+def test_snapshot_usable_from_other_threads():
+    snapshot = PySnapshot.from_file(
+        r"tests/snapshots/test_snapshots__test_snapshot_contents_json@pysnap.snap"
+    )
+    with ThreadPoolExecutor(max_workers=4) as pool:
+        results = list(pool.map(lambda _: snapshot.contents(), range(8)))
+    assert all(json.loads(result) == {"test": "content"} for result in results)
+
+def test_snapshot_assertions_from_threads():
+    def check(_):
+        assert_json_snapshot(
+            {"test": "content"},
+            snapshot_name="test_snapshot_contents_json",
+            allow_duplicates=True,
+        )
+
+    with ThreadPoolExecutor(max_workers=4) as pool:
+        list(pool.map(check, range(8)))
+
+
+def _current_test() -> str:
+    return os.environ["PYTEST_CURRENT_TEST"].rsplit(" (", 1)[0]
+
+
+def test_allow_duplicates_collapses_repeats():
+    for _ in range(3):
+        assert_json_snapshot({"status": "ok"}, allow_duplicates=True)
+    settle_repetitions(_current_test())
+
+    snapshot_info = extract_from_pytest_env()
+    header = Path(snapshot_info.last_snapshot_path(None)).read_text().split("\n---\n")[0]
+    assert header.endswith("info:\n  repetitions: 3")
+
+
+def test_allow_duplicates_unchanged_repeats_pass_read_only(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+):
+    monkeypatch.setenv("INSTA_WORKSPACE_ROOT", str(tmp_path))
+    monkeypatch.setenv("INSTA_UPDATE", "always")
+    for _ in range(3):
+        assert_json_snapshot({"status": "ok"}, str(tmp_path), allow_duplicates=True)
+    settle_repetitions(_current_test())
+    (snapshot,) = tmp_path.glob("*.snap")
+    recorded = snapshot.read_text()
+    assert "repetitions: 3" in recorded
+
+    observe_test_stage(_current_test(), "setup")
+    set_read_only()
+    try:
+        for _ in range(3):
+            assert_json_snapshot({"status": "ok"}, str(tmp_path), allow_duplicates=True)
+        settle_repetitions(_current_test())
+    finally:
+        set_read_only(False)
+    assert snapshot.read_text() == recorded
+
+
+def test_allow_duplicates_collapses_text_repeats():
+    for _ in range(2):
+        assert_snapshot("ready", allow_duplicates=True)
+    settle_repetitions(_current_test())
+
+    snapshot_info = extract_from_pytest_env()
+    assert "repetitions: 2" in Path(snapshot_info.last_snapshot_path(None)).read_text()
+
+
+def test_allow_duplicates_rejects_divergent_occurrences():
+    assert_json_snapshot({"status": "ok"}, allow_duplicates=True)
+    with pytest.raises(AssertionError, match="different contents earlier"):
+        assert_json_snapshot({"status": "failed"}, allow_duplicates=True)
+
+    snapshot_info = extract_from_pytest_env()
+    stored = Path(snapshot_info.last_snapshot_path(None)).read_text()
+    assert '"status": "ok"' in stored
+
+
+def test_allow_duplicates_claims_the_name():
+    assert_snapshot("shared", allow_duplicates=True)
+    assert_snapshot("unique")
+
+    snapshot_info = extract_from_pytest_env()
+    assert snapshot_info.last_snapshot_name().endswith(
+        "test_allow_duplicates_claims_the_name-2"
+    )
+
+
+def test_allow_duplicates_single_assertion_has_no_repetitions():
+    assert_json_snapshot({"status": "ok"}, allow_duplicates=True)
+    settle_repetitions(_current_test())
+
+    snapshot_info = extract_from_pytest_env()
+    assert "repetitions" not in Path(snapshot_info.last_snapshot_path(None)).read_text()
+
 def test_mock_or_json_snapshot():
     def add(x, y):
         return {"sum": x + y, "x": x, "y": y}
@@ -286,3 +590,12 @@ def test_mock_or_json_snapshot_redactions():
     assert result["sum"] == "[redacted]"
     assert result["x"] == 1
     assert result["y"] == 2
+
+
+def test_assert_binary_snapshot_compressed():
+    payload = b"highly repetitive payload " * 1000
+    assert_binary_snapshot(payload, extension="txt", compress=True)
+    snapshot_path = extract_from_pytest_env().last_snapshot_path(None)
+    sidecar = Path(f"{snapshot_path}.txt.zst")
+    assert sidecar.stat().st_size < len(payload)
+    assert PySnapshot.from_file(snapshot_path).contents() == payload
@@ -0,0 +1,121 @@
+"""Tests for collecting every snapshot mismatch of a test."""
+
+from __future__ import annotations
+
+import os
+from pathlib import Path
+from types import SimpleNamespace
+
+import pytest
+
+from pysnaptest import (
+    SnapshotMismatchError,
+    SoftSnapshotError,
+    assert_json_snapshot,
+    assert_snapshot,
+    assert_vector_snapshot,
+    format_junit_system_out,
+    raise_soft_failures,
+    set_fail_fast,
+)
+from pysnaptest.pytest_plugin import pytest_runtest_call
+
+
+@pytest.fixture
+def soft():
+    set_fail_fast(False)
+    yield
+    set_fail_fast()
+    raise_soft_failures()
+
+
+def _item() -> SimpleNamespace:
+    """The pytest item of the running test, as the plugin's hooks see it."""
+
+    return SimpleNamespace(nodeid=os.environ["PYTEST_CURRENT_TEST"].rsplit(" (", 1)[0])
+
+
+def _committed(tmp_path: Path, name: str, body: str) -> str:
+    snapshot = tmp_path / f"test_soft_assertions__{name}@pysnap.snap"
+    snapshot.write_text(f"---\nsource: src/lib.rs\n---\n{body}\n")
+    return str(tmp_path)
+
+
+def test_mismatches_are_collected_and_raised_together(tmp_path: Path, soft):
+    path = _committed(tmp_path, "soft_first", "one")
+    _committed(tmp_path, "soft_matching", "same")
+    _committed(tmp_path, "soft_second", '{\n  "n": 2\n}')
+
+    assert_snapshot("uno", snapshot_path=path, snapshot_name="soft_first")
+    assert_snapshot("same", snapshot_path=path, snapshot_name="soft_matching")
+    assert_json_snapshot({"n": 3}, snapshot_path=path, snapshot_name="soft_second")
+
+    with pytest.raises(SoftSnapshotError) as excinfo:
+        raise_soft_failures()
+    error = excinfo.value
+    assert isinstance(error, AssertionError)
+    assert [type(failure) for failure in error.failures] == [SnapshotMismatchError] * 2
+    assert [f.snapshot_failure["snapshot"] for f in error.failures] == [
+        "soft_first",
+        "soft_second",
+    ]
+    message = str(error)
+    assert message.startswith("2 snapshot(s) did not match:\n\n1. snapshot 'soft_first'")
+    assert "\n\n2. snapshot 'soft_second' did not match" in message
+    assert "-one\n+uno" in message
+    assert format_junit_system_out(error).count("[[ATTACHMENT|") == 2
+
+    # Raising takes the failures.
+    raise_soft_failures()
+
+
+def test_encrypted_and_vector_mismatches_are_collected(
+    tmp_path: Path, monkeypatch: pytest.MonkeyPatch
+):
+    path = str(tmp_path)
+    monkeypatch.setenv("INSTA_WORKSPACE_ROOT", path)
+    monkeypatch.setenv("INSTA_UPDATE", "no")
+
+    set_fail_fast(False)
+    try:
+        key = "soft-assertions-key"
+        assert_json_snapshot({"n": 2}, path, "soft_secret", encryption_key=key)
+        assert_vector_snapshot([0.0, 1.0], path, "soft_vector")
+    finally:
+        set_fail_fast()
+
+    with pytest.raises(SoftSnapshotError) as excinfo:
+        raise_soft_failures()
+    failures = excinfo.value.failures
+    assert [type(failure) for failure in failures] == [SnapshotMismatchError] * 2
+    assert "encrypted snapshot 'soft_secret' did not match" in str(failures[0])
+    assert "'soft_vector' is outside the vector tolerance" in str(failures[1])
+
+
+def test_fail_fast_by_default(tmp_path: Path):
+    path = _committed(tmp_path, "soft_off", "one")
+
+    with pytest.raises(SnapshotMismatchError):
+        assert_snapshot("uno", snapshot_path=path, snapshot_name="soft_off")
+
+
+def test_plugin_fails_the_test_at_its_end(tmp_path: Path, soft):
+    path = _committed(tmp_path, "soft_plugin", "one")
+
+    hook = pytest_runtest_call(_item())
+    next(hook)
+    assert_snapshot("uno", snapshot_path=path, snapshot_name="soft_plugin")
+    with pytest.raises(SoftSnapshotError, match="1 snapshot\\(s\\) did not match"):
+        hook.send(None)
+
+
+def test_plugin_chains_an_error_ending_the_test(tmp_path: Path, soft):
+    path = _committed(tmp_path, "soft_crash", "one")
+
+    hook = pytest_runtest_call(_item())
+    next(hook)
+    assert_snapshot("uno", snapshot_path=path, snapshot_name="soft_crash")
+    crash = KeyError("boom")
+    with pytest.raises(SoftSnapshotError) as excinfo:
+        hook.throw(crash)
+    assert excinfo.value.__cause__ is crash
@@ -0,0 +1,47 @@
+"""Tests for snapshots of SQL queries."""
+
+from __future__ import annotations
+
+import pytest
+
+from pysnaptest import assert_sql_snapshot
+
+QUERY = (
+    "select u.id, u.name from users u join orders o on o.user_id = u.id "
+    "where u.email = %(email)s and o.total > %s and o.note <> '%s' "
+    "and o.created_at::date = current_date order by u.id limit 10"
+)
+
+
+def test_sql_snapshot():
+    assert_sql_snapshot(QUERY)
+
+
+def test_sql_snapshot_ignores_placeholder_style_and_layout():
+    for query in (
+        QUERY,
+        QUERY.replace("%(email)s", ":email_1").replace("%s and", "$2 and"),
+        QUERY.replace("%(email)s", "?")
+        .replace("%s and", "?2 and")
+        .replace("select", "SELECT")
+        .replace(" where ", "\n    WHERE "),
+    ):
+        assert_sql_snapshot(query, allow_duplicates=True)
+
+
+def test_sql_snapshot_of_captured_queries():
+    assert_sql_snapshot(
+        [
+            {
+                "sql": 'SELECT "app_user"."id" FROM "app_user" WHERE "id" = %s',
+                "time": "0.001",
+            },
+            "UPDATE [users] SET [name] = @name WHERE [id] = @p1",
+        ],
+        dialect="mssql",
+    )
+
+
+def test_sql_snapshot_rejects_unknown_dialect():
+    with pytest.raises(ValueError, match="Unsupported SQL dialect"):
+        assert_sql_snapshot("select 1", dialect="cobol")
@@ -0,0 +1,43 @@
+"""Tests for the pytest stage snapshot infos record."""
+
+from __future__ import annotations
+
+import os
+import warnings
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import SnapshotInfo, SnapshotTeardownWarning, assert_snapshot
+
+
+def _node_id() -> str:
+    return os.environ["PYTEST_CURRENT_TEST"].rsplit(" (", 1)[0]
+
+
+def test_stage_of_the_running_test():
+    assert SnapshotInfo.from_pytest().stage() == "call"
+
+
+def test_stage_outside_pytest(tmp_path: Path):
+    assert SnapshotInfo.manual(tmp_path, "manual").stage() is None
+
+
+def test_assertion_in_teardown_warns(tmp_path: Path, monkeypatch):
+    monkeypatch.setenv("PYTEST_CURRENT_TEST", f"{_node_id()} (teardown)")
+    assert SnapshotInfo.from_pytest(tmp_path, "torn_down").stage() == "teardown"
+
+    with pytest.warns(SnapshotTeardownWarning, match="during the teardown of"):
+        with pytest.raises(AssertionError):
+            assert_snapshot("late", snapshot_path=tmp_path, snapshot_name="torn_down")
+
+
+def test_teardown_warning_as_error_refuses_the_snapshot(tmp_path: Path, monkeypatch):
+    monkeypatch.setenv("PYTEST_CURRENT_TEST", f"{_node_id()} (teardown)")
+
+    with warnings.catch_warnings():
+        warnings.simplefilter("error", SnapshotTeardownWarning)
+        with pytest.raises(SnapshotTeardownWarning):
+            assert_snapshot("late", snapshot_path=tmp_path, snapshot_name="refused")
+
+    assert not list(tmp_path.iterdir())
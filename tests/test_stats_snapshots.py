@@ -0,0 +1,64 @@
+"""Tests for summary-statistics snapshots of numeric data."""
+
+from __future__ import annotations
+
+import math
+import shutil
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import assert_stats_snapshot
+
+try:
+    import pandas as pd
+
+    PANDAS_UNAVAILABLE = False
+except ImportError:
+    PANDAS_UNAVAILABLE = True
+
+FEATURES = [0.1 * i for i in range(1, 101)]
+
+
+def test_stats_snapshot_values():
+    assert_stats_snapshot(FEATURES)
+
+
+def test_stats_snapshot_columns_with_missing_values():
+    assert_stats_snapshot(
+        {"age": [31, None, 45, 27], "score": [0.5, math.nan, 0.25, 1.0]},
+        stats=["count", "nulls", "sum", "median"],
+        decimals=2,
+    )
+
+
+def test_stats_snapshot_tolerates_float_noise(tmp_path: Path):
+    shutil.copy(
+        Path(__file__).parent
+        / "snapshots"
+        / "test_stats_snapshots__test_stats_snapshots_test_stats_snapshot_values@pysnap.snap",
+        tmp_path / "test_stats_snapshots__features@pysnap.snap",
+    )
+    noisy = [value + 1e-12 for value in reversed(FEATURES)]
+    assert_stats_snapshot(noisy, snapshot_path=str(tmp_path), snapshot_name="features")
+
+    with pytest.raises(AssertionError):
+        assert_stats_snapshot(
+            FEATURES + [100.0], snapshot_path=str(tmp_path), snapshot_name="features"
+        )
+
+
+def test_stats_snapshot_rejects_unknown_statistic():
+    with pytest.raises(ValueError, match="Unsupported statistic"):
+        assert_stats_snapshot(FEATURES, stats=["mode"])
+
+
+def test_stats_snapshot_rejects_non_numeric_column():
+    with pytest.raises(ValueError, match='Column "name" is not numeric'):
+        assert_stats_snapshot({"name": ["ada", "bob"]})
+
+
+@pytest.mark.skipif(PANDAS_UNAVAILABLE, reason="Pandas is an optional dependency")
+def test_stats_snapshot_pandas_dataframe():
+    df = pd.DataFrame({"name": ["a", "b", "c"], "x": [1.0, 2.0, 4.0], "n": [1, 2, 3]})
+    assert_stats_snapshot(df)
@@ -0,0 +1,66 @@
+"""Tests for external storage of large binary snapshots."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import (
+    PySnapshot,
+    assert_binary_snapshot,
+    configure_storage,
+    extract_from_pytest_env,
+)
+
+PAYLOAD = bytes(range(256)) * 4
+
+
+@pytest.fixture
+def store(tmp_path: Path):
+    configure_storage(directory=tmp_path, threshold=16)
+    yield tmp_path
+    configure_storage()
+
+
+def test_large_binary_snapshot_is_stored_externally(store: Path):
+    assert_binary_snapshot(PAYLOAD)
+    snapshot_path = extract_from_pytest_env().last_snapshot_path(None)
+
+    pointer = Path(f"{snapshot_path}.bin.ptr")
+    assert pointer.read_text().startswith("pysnaptest-external:v1:sha256:")
+    assert [p.stat().st_size for p in store.rglob("*") if p.is_file()] == [1024]
+    assert PySnapshot.from_file(snapshot_path).contents() == PAYLOAD
+
+
+def test_external_storage_with_compression(store: Path):
+    assert_binary_snapshot(PAYLOAD, compress=True)
+    snapshot_path = extract_from_pytest_env().last_snapshot_path(None)
+
+    assert Path(f"{snapshot_path}.bin.zst.ptr").exists()
+    assert PySnapshot.from_file(snapshot_path).contents() == PAYLOAD
+
+
+def test_external_storage_callbacks():
+    objects: dict[str, bytes] = {}
+    configure_storage(put=objects.__setitem__, get=objects.__getitem__, threshold=16)
+    try:
+        assert_binary_snapshot(PAYLOAD)
+        snapshot_path = extract_from_pytest_env().last_snapshot_path(None)
+        assert list(objects.values()) == [PAYLOAD]
+        assert PySnapshot.from_file(snapshot_path).contents() == PAYLOAD
+    finally:
+        configure_storage()
+
+
+def test_pointer_without_backend_fails(store: Path):
+    assert_binary_snapshot(PAYLOAD)
+    snapshot_path = extract_from_pytest_env().last_snapshot_path(None)
+    configure_storage()
+    with pytest.raises(ValueError, match="no storage backend is configured"):
+        PySnapshot.from_file(snapshot_path).contents()
+
+
+def test_configure_storage_rejects_partial_callbacks():
+    with pytest.raises(ValueError, match="both a put and a get"):
+        configure_storage(put=print)
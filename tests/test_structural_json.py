@@ -0,0 +1,51 @@
+"""Tests for key-order-insensitive comparison of legacy JSON snapshots."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import assert_json_snapshot, set_structural_json_comparison
+
+LEGACY = """---
+source: legacy serializer
+---
+{"user": {"name": "Ada", "id": 7}, "roles": ["admin", "dev"], "active": true}
+"""
+
+RESULT = {"active": True, "roles": ["admin", "dev"], "user": {"id": 7, "name": "Ada"}}
+
+
+@pytest.fixture
+def structural():
+    set_structural_json_comparison()
+    yield
+    set_structural_json_comparison(False)
+
+
+def _legacy(tmp_path: Path, name: str) -> str:
+    snapshot = tmp_path / f"test_structural_json__{name}@pysnap.snap"
+    snapshot.write_text(LEGACY)
+    return str(tmp_path)
+
+
+def test_structural_comparison_accepts_reordered_keys(tmp_path: Path, structural):
+    path = _legacy(tmp_path, "legacy_reordered")
+    assert_json_snapshot(RESULT, snapshot_path=path, snapshot_name="legacy_reordered")
+    # The legacy file is left as it is.
+    snapshot = tmp_path / "test_structural_json__legacy_reordered@pysnap.snap"
+    assert snapshot.read_text() == LEGACY
+
+
+def test_structural_comparison_still_fails_on_changes(tmp_path: Path, structural):
+    path = _legacy(tmp_path, "legacy_changed")
+    changed = {**RESULT, "roles": ["dev", "admin"]}
+    with pytest.raises(AssertionError):
+        assert_json_snapshot(changed, snapshot_path=path, snapshot_name="legacy_changed")
+
+
+def test_textual_comparison_by_default(tmp_path: Path):
+    path = _legacy(tmp_path, "legacy_textual")
+    with pytest.raises(AssertionError):
+        assert_json_snapshot(RESULT, snapshot_path=path, snapshot_name="legacy_textual")
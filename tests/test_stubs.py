@@ -0,0 +1,72 @@
+"""Tests for the type stub generated from the compiled extension module."""
+
+from __future__ import annotations
+
+import ast
+import inspect
+from pathlib import Path
+
+from pysnaptest import _pysnaptest
+from pysnaptest.stubgen import STUB_PATH, main, render_stub
+
+
+def _exports() -> dict:
+    return {
+        name: getattr(_pysnaptest, name)
+        for name in dir(_pysnaptest)
+        if not name.startswith("__")
+    }
+
+
+def test_committed_stub_is_current():
+    assert main(["--check"]) == 0, "run python -m pysnaptest.stubgen"
+
+
+def test_every_function_has_a_text_signature():
+    functions = [obj for obj in _exports().values() if inspect.isbuiltin(obj)]
+
+    assert functions
+    assert all(obj.__text_signature__ for obj in functions)
+
+
+def test_stub_declares_every_export():
+    tree = ast.parse(STUB_PATH.read_text())
+    declared = {
+        node.name if not isinstance(node, ast.AnnAssign) else node.target.id
+        for node in tree.body
+        if isinstance(node, (ast.FunctionDef, ast.ClassDef, ast.AnnAssign))
+    }
+
+    assert set(_exports()) <= declared
+
+
+def test_new_parameters_and_exports_come_from_the_runtime():
+    stub = render_stub(stub="")
+
+    assert "SNAPSHOT_SUFFIX: str\n" in stub
+    assert "class SerializationError(ValueError):" in stub
+    assert "def set_json_format(format: Any = ..., max_line_width: Any = ...)" in stub
+
+
+def test_annotations_of_the_stub_are_kept():
+    stub = (
+        "from typing import Any\n\n"
+        "def seed_label(seed: int, stale: str) -> str:\n"
+        '    """Hand-written."""\n'
+        "    ...\n\n"
+        "def removed() -> None: ...\n"
+    )
+
+    rendered = render_stub(stub=stub)
+
+    assert 'def seed_label(seed: int) -> str:\n    """Hand-written."""' in rendered
+    assert "def removed" not in rendered
+
+
+def test_check_reports_a_stale_stub(tmp_path: Path):
+    stub = tmp_path / "_pysnaptest.pyi"
+    stub.write_text("from typing import Any\n")
+
+    assert main(["--check", "--stub", str(stub)]) == 1
+    assert main(["--stub", str(stub)]) == 0
+    assert main(["--check", "--stub", str(stub)]) == 0
@@ -0,0 +1,35 @@
+"""Tests for snapshots of terminal output."""
+
+from __future__ import annotations
+
+import pytest
+
+from pysnaptest import assert_term_snapshot
+
+COLORED = (
+    "\x1b[1;32m✔\x1b[0m 3 passed\r\n"
+    "\x1b[31;1mFAILED\x1b[22m tests/test_api.py\x1b[39m\r\n"
+    "\x1b[38;5;244mtook 0.2s\x1b[0m \x1b[48;2;255;128;0mwarn\x1b[49m\n"
+    "\x1b]8;;https://example.com\x07docs\x1b]8;;\x07 \x1b[?25ldone\x1b[?25h"
+)
+
+
+def test_term_snapshot_strips_ansi():
+    assert_term_snapshot(COLORED)
+
+
+def test_term_snapshot_markers():
+    assert_term_snapshot(COLORED, strip_ansi=False)
+
+
+def test_term_snapshot_progress_and_width():
+    output = (
+        b"downloading  10%\rdownloading  55%\rdownloading 100%\n"
+        b"\x1b[33mwarning:\x1b[0m a deliberately long line that wraps\n"
+    )
+    assert_term_snapshot(output, strip_ansi=False, width=20)
+
+
+def test_term_snapshot_rejects_zero_width():
+    with pytest.raises(ValueError, match="width must be at least 1"):
+        assert_term_snapshot("x", width=0)
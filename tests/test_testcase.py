@@ -0,0 +1,30 @@
+"""Tests for snapshot assertions in unittest test cases."""
+
+from __future__ import annotations
+
+import io
+import unittest
+from pathlib import Path
+
+from pysnaptest import SnapshotTestCaseMixin
+
+
+class TestOrders(SnapshotTestCaseMixin, unittest.TestCase):
+    def test_totals(self):
+        self.assertJsonSnapshot({"order": 7, "total": 12.5})
+        self.assertSnapshot("paid")
+
+
+def test_test_case_snapshots_without_pytest(monkeypatch):
+    monkeypatch.delenv("PYTEST_CURRENT_TEST")
+    for _ in range(2):
+        suite = unittest.defaultTestLoader.loadTestsFromTestCase(TestOrders)
+        result = unittest.TextTestRunner(stream=io.StringIO()).run(suite)
+        assert result.wasSuccessful(), result.failures + result.errors
+
+
+def test_test_case_snapshot_info():
+    info = TestOrders("test_totals").snapshot_info()
+    info.reset_counter()
+    assert Path(info.snapshot_folder()) == Path(__file__).resolve().parent / "snapshots"
+    assert info.next_snapshot_name() == "test_testcase_TestOrders.test_totals"
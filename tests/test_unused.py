@@ -52,6 +52,17 @@ def test_owning_stem_no_known_stem():
     assert owning_stem(snap, {"test_other"}) is None
 
 
+def test_owning_stem_with_any_prefix():
+    for name in (
+        "test_snapshots__test_snapshots_test_thing",
+        "test_snapshots__mocks__test_snapshots_test_thing_fetch",
+        "test_snapshots_test_thing",
+        "api__v2__test_snapshots_test_thing",
+    ):
+        snap = Path(f"{name}@pysnap.snap")
+        assert owning_stem(snap, {"test", "test_snapshots"}) == "test_snapshots"
+
+
 def test_owning_stem_ignores_non_snapshot_name():
     assert owning_stem(Path("not-a-snapshot.txt"), {"test_main"}) is None
 
@@ -181,11 +192,11 @@ def test_cli_unused_reports_and_deletes(tmp_path: Path):
         text=True,
     )
     snaps = tmp_path / "snapshots"
-    kept = snaps / "pysnaptest__test_thing_test_thing@pysnap.snap"
+    kept = snaps / "test_thing__test_thing_test_thing@pysnap.snap"
     assert kept.exists()
 
     # Add an orphan snapshot owned by the same (existing) test file.
-    orphan = _write_snapshot(snaps / "pysnaptest__test_thing_test_gone@pysnap.snap")
+    orphan = _write_snapshot(snaps / "test_thing__test_thing_test_gone@pysnap.snap")
 
     report = _run_cli(tmp_path)
     assert report.returncode == 1
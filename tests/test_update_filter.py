@@ -0,0 +1,89 @@
+"""Tests for restricting snapshot updates to the tests and names of a filter."""
+
+from __future__ import annotations
+
+import os
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import assert_snapshot, mock_json_snapshot
+from pysnaptest._pysnaptest import observe_test_stage
+
+
+@pytest.fixture
+def updating(tmp_path: Path, monkeypatch: pytest.MonkeyPatch) -> Path:
+    """``INSTA_UPDATE=always`` under a workspace root insta has not read a
+    config for."""
+
+    monkeypatch.setenv("INSTA_WORKSPACE_ROOT", str(tmp_path))
+    monkeypatch.setenv("INSTA_UPDATE", "always")
+    monkeypatch.delenv("INSTA_FORCE_PASS", raising=False)
+    return tmp_path
+
+
+def _committed(folder: Path, name: str) -> Path:
+    snapshot = folder / f"test_update_filter__{name}@pysnap.snap"
+    snapshot.write_text("---\nsource: src/lib.rs\n---\nold\n")
+    return snapshot
+
+
+def test_snapshot_outside_the_filter_is_left_alone(updating: Path, monkeypatch):
+    monkeypatch.setenv("PYSNAPTEST_UPDATE_FILTER", "tests/api/*")
+    snapshot = _committed(updating, "outside")
+    before = snapshot.read_text()
+
+    with pytest.raises(AssertionError, match="outside PYSNAPTEST_UPDATE_FILTER"):
+        assert_snapshot("new", snapshot_path=str(updating), snapshot_name="outside")
+
+    assert snapshot.read_text() == before
+    assert sorted(p.name for p in updating.iterdir()) == [snapshot.name]
+
+
+@pytest.mark.parametrize(
+    "update_filter",
+    ["*inside*", "tests/api/*, tests/test_update_filter.py", "**/test_update_*"],
+)
+def test_snapshot_within_the_filter_is_updated(updating: Path, monkeypatch, update_filter):
+    monkeypatch.setenv("PYSNAPTEST_UPDATE_FILTER", update_filter)
+    snapshot = _committed(updating, "inside")
+
+    assert_snapshot("new", snapshot_path=str(updating), snapshot_name="inside")
+
+    assert snapshot.read_text().endswith("\nnew\n")
+
+
+def test_new_snapshot_outside_the_filter_is_not_written(updating: Path, monkeypatch):
+    monkeypatch.setenv("PYSNAPTEST_UPDATE_FILTER", "tests/api/*")
+    folder = updating / "snapshots"
+
+    with pytest.raises(AssertionError):
+        assert_snapshot("new", snapshot_path=str(folder), snapshot_name="fresh")
+
+    assert not folder.exists()
+
+
+def test_matching_snapshot_outside_the_filter_passes(updating: Path, monkeypatch):
+    monkeypatch.setenv("PYSNAPTEST_UPDATE_FILTER", "tests/api/*")
+    _committed(updating, "same")
+
+    assert_snapshot("old", snapshot_path=str(updating), snapshot_name="same")
+
+
+def test_mock_outside_the_filter_replays_instead_of_recording(updating: Path, monkeypatch):
+    calls = []
+
+    def rate(currency: str) -> dict:
+        calls.append(currency)
+        return {"currency": currency, "rate": 1.1}
+
+    mock_json_snapshot(func=rate, snapshot_path=str(updating))("EUR")
+    monkeypatch.setenv("PYSNAPTEST_UPDATE_FILTER", "tests/api/*")
+
+    # Run the test again, as `pytest --snapshot-update` would.
+    observe_test_stage(os.environ["PYTEST_CURRENT_TEST"].rsplit(" (", 1)[0], "setup")
+
+    result = mock_json_snapshot(func=rate, record=True, snapshot_path=str(updating))("EUR")
+
+    assert result == {"currency": "EUR", "rate": 1.1}
+    assert calls == ["EUR"]
@@ -0,0 +1,113 @@
+"""Tests for upgrading snapshots written by older pysnaptest versions."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import (
+    PySnapshot,
+    SnapshotInfo,
+    assert_json_snapshot,
+    upgrade_snapshots,
+)
+from pysnaptest._pysnaptest import SNAPSHOT_FORMAT_VERSION
+from pysnaptest.__main__ import main
+
+LEGACY = (
+    "---\nsource: src/lib.rs\n"
+    'description: "Test File Path: tests/test_api.py"\n'
+    "expression: res\n---\n"
+    '{\n  "id": 7\n}\n'
+)
+
+
+def _legacy(folder: Path, name: str, text: str = LEGACY) -> Path:
+    folder.mkdir(parents=True, exist_ok=True)
+    path = folder / f"{name}@pysnap.snap"
+    path.write_text(text)
+    return path
+
+
+def test_new_snapshots_record_the_format_version():
+    assert_json_snapshot({"versioned": True})
+
+    path = SnapshotInfo.from_pytest().last_snapshot_path()
+    assert PySnapshot.from_file(path).metadata()["format_version"] == (
+        SNAPSHOT_FORMAT_VERSION
+    )
+
+
+def test_upgrade_renames_and_rewrites_legacy_snapshots(tmp_path: Path):
+    folder = tmp_path / "tests" / "snapshots"
+    _legacy(folder, "test_api__test_api_TestApi::test_get")
+    _legacy(folder, "test_api__test_api_TestApi::test_get-2")
+    _legacy(folder, "test_api__test_api_test_load[a  b]")
+
+    upgraded = upgrade_snapshots(str(tmp_path))
+
+    assert [path.name for path in upgraded] == [
+        "test_api__test_api_TestApi.test_get-2@pysnap.snap",
+        "test_api__test_api_TestApi.test_get@pysnap.snap",
+        "test_api__test_api_test_load[a b]@pysnap.snap",
+    ]
+    assert sorted(path.name for path in folder.iterdir()) == sorted(
+        path.name for path in upgraded
+    )
+    assert upgraded[1].read_text() == (
+        "---\nsource: src/lib.rs\n"
+        'description: "Test File Path: tests/test_api.py"\n'
+        f"info:\n  format_version: {SNAPSHOT_FORMAT_VERSION}\n---\n"
+        '{\n  "id": 7\n}\n'
+    )
+    # Upgrading again changes nothing.
+    assert upgrade_snapshots(str(tmp_path)) == []
+
+
+def test_upgrade_moves_binary_sidecars(tmp_path: Path):
+    folder = tmp_path / "snapshots"
+    _legacy(
+        folder,
+        "test_img__test_img_TestImg::test_logo",
+        "---\nsource: src/lib.rs\nsnapshot_kind: binary\nextension: png\n---\n",
+    )
+    sidecar = folder / "test_img__test_img_TestImg::test_logo@pysnap.snap.png"
+    sidecar.write_bytes(b"\x89PNG")
+
+    [upgraded] = upgrade_snapshots(str(tmp_path))
+
+    assert upgraded.name == "test_img__test_img_TestImg.test_logo@pysnap.snap"
+    sidecar = folder / "test_img__test_img_TestImg.test_logo@pysnap.snap.png"
+    assert sidecar.read_bytes() == b"\x89PNG"
+    assert sorted(path.name for path in folder.iterdir()) == [
+        upgraded.name,
+        sidecar.name,
+    ]
+
+
+def test_upgrade_refuses_snapshots_of_newer_versions(tmp_path: Path):
+    _legacy(
+        tmp_path,
+        "test_api__test_api_test_get",
+        f"---\ninfo:\n  format_version: {SNAPSHOT_FORMAT_VERSION + 1}\n---\n{{}}\n",
+    )
+
+    with pytest.raises(ValueError, match="newer than the format"):
+        upgrade_snapshots(str(tmp_path))
+
+
+def test_upgrade_refuses_to_overwrite_snapshots(tmp_path: Path):
+    _legacy(tmp_path, "test_api__test_api_TestApi::test_get")
+    _legacy(tmp_path, "test_api__test_api_TestApi.test_get")
+
+    with pytest.raises(ValueError, match="already exists"):
+        upgrade_snapshots(str(tmp_path))
+
+
+def test_cli_upgrade(tmp_path: Path, capsys):
+    _legacy(tmp_path, "test_api__test_api_TestApi::test_get")
+
+    assert main(["--root", str(tmp_path), "upgrade"]) == 0
+
+    assert "Upgraded 1 snapshot(s)." in capsys.readouterr().out
@@ -0,0 +1,93 @@
+"""Tests for the fields, equality and reprs of SnapshotInfo and PySnapshot."""
+
+from __future__ import annotations
+
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import PySnapshot, SnapshotInfo, SnapshotSettings
+
+HEADER = "---\nsource: src/lib.rs\nexpression: result\n---\n"
+
+
+def test_snapshot_info_fields(tmp_path: Path):
+    info = SnapshotInfo.manual(tmp_path, "report", allow_duplicates=True)
+
+    assert info.name == "report"
+    assert info.folder == tmp_path
+    assert info.allow_duplicates is True
+    assert info.relative_test_file_path is None
+    assert info.description is None
+
+
+def test_snapshot_info_fields_under_pytest():
+    info = SnapshotInfo.from_pytest()
+
+    assert info.name == "test_value_objects_test_snapshot_info_fields_under_pytest"
+    assert info.folder == Path(__file__).parent / "snapshots"
+    assert info.relative_test_file_path == "tests/test_value_objects.py"
+    assert info.module == "test_value_objects"
+    assert info.allow_duplicates is False
+
+
+def test_snapshot_infos_compare_by_value(tmp_path: Path):
+    info = SnapshotInfo.manual(tmp_path, "report")
+    settings = SnapshotSettings(sort_maps=True)
+
+    with settings:
+        same = SnapshotInfo.manual(tmp_path, "report")
+
+    assert info == same
+    assert hash(info) == hash(same)
+    assert len({info, same}) == 1
+    assert info != SnapshotInfo.manual(tmp_path, "other")
+    assert info != SnapshotInfo.manual(tmp_path, "report", allow_duplicates=True)
+    assert info != info.with_header(description="A report")
+    assert info != "report"
+
+
+def test_snapshot_info_repr(tmp_path: Path):
+    info = SnapshotInfo.manual(tmp_path, "it's").with_header(description="A report")
+
+    assert repr(info) == (
+        f"SnapshotInfo(name=\"it's\", folder={str(tmp_path)!r}, "
+        "relative_test_file_path=None, allow_duplicates=False, "
+        "description='A report')"
+    )
+
+
+@pytest.mark.parametrize(
+    "info",
+    [SnapshotInfo.manual("snapshots", "a"), SnapshotInfo.manual("snapshots", "b")],
+    ids=repr,
+)
+def test_snapshot_info_as_parameter(info: SnapshotInfo):
+    assert info.name in {"a", "b"}
+
+
+def test_py_snapshot_fields_and_equality(tmp_path: Path):
+    first = tmp_path / "first.snap"
+    first.write_text(HEADER + "hello\n")
+    second = tmp_path / "second.snap"
+    second.write_text(HEADER + "hello\n")
+
+    snapshot = PySnapshot.from_file(first)
+
+    assert snapshot.path == first
+    assert snapshot.kind == "text"
+    assert snapshot.compressed is False
+    assert snapshot.external is False
+    assert snapshot == PySnapshot.from_file(first)
+    assert hash(snapshot) == hash(PySnapshot.from_file(first))
+    assert snapshot != PySnapshot.from_file(second)
+    assert repr(snapshot) == f"PySnapshot(path={str(first)!r}, kind='text')"
+
+
+def test_py_snapshot_differs_once_rewritten(tmp_path: Path):
+    path = tmp_path / "value.snap"
+    path.write_text(HEADER + "one\n")
+    before = PySnapshot.from_file(path)
+    path.write_text(HEADER + "two\n")
+
+    assert before != PySnapshot.from_file(path)
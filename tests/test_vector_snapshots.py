@@ -0,0 +1,82 @@
+"""Tests for tolerance-based snapshots of embeddings and other vectors."""
+
+from __future__ import annotations
+
+import shutil
+from pathlib import Path
+
+import pytest
+
+from pysnaptest import assert_vector_snapshot
+
+EMBEDDING = [0.12, -0.5, 0.33, 0.9, -0.07, 0.41]
+SNAPSHOT = (
+    Path(__file__).parent
+    / "snapshots"
+    / "test_vector_snapshots__test_vector_snapshots_test_vector_snapshot@pysnap.snap"
+)
+
+
+def _committed(tmp_path: Path, name: str) -> str:
+    shutil.copy(SNAPSHOT, tmp_path / f"test_vector_snapshots__{name}@pysnap.snap")
+    return str(tmp_path)
+
+
+def test_vector_snapshot():
+    assert_vector_snapshot(EMBEDDING)
+
+
+def test_vector_snapshot_within_cosine_tolerance(tmp_path: Path):
+    path = _committed(tmp_path, "noisy")
+    noisy = [value * 1.001 + 1e-9 for value in EMBEDDING]
+    assert_vector_snapshot(noisy, snapshot_path=path, snapshot_name="noisy")
+    # The committed values are kept, not rewritten with the noisy ones.
+    assert (tmp_path / "test_vector_snapshots__noisy@pysnap.snap").read_text() == (
+        SNAPSHOT.read_text()
+    )
+
+
+def test_vector_snapshot_outside_tolerance(tmp_path: Path):
+    path = _committed(tmp_path, "drifted")
+    drifted = list(reversed(EMBEDDING))
+    with pytest.raises(AssertionError, match="cosine distance is"):
+        assert_vector_snapshot(drifted, snapshot_path=path, snapshot_name="drifted")
+
+
+def test_vector_snapshot_allclose(tmp_path: Path):
+    path = _committed(tmp_path, "nudged")
+    nudged = [value + 1e-4 for value in EMBEDDING]
+    assert_vector_snapshot(
+        nudged,
+        snapshot_path=path,
+        snapshot_name="nudged",
+        metric="allclose",
+        atol=1e-3,
+    )
+    with pytest.raises(AssertionError, match="largest element difference"):
+        assert_vector_snapshot(
+            nudged,
+            snapshot_path=path,
+            snapshot_name="nudged",
+            metric="allclose",
+            atol=1e-5,
+            allow_duplicates=True,
+        )
+
+
+def test_vector_snapshot_dimension_change(tmp_path: Path):
+    path = _committed(tmp_path, "grown")
+    with pytest.raises(AssertionError, match="dimensions changed from 6 to 7"):
+        assert_vector_snapshot(
+            EMBEDDING + [0.0], snapshot_path=path, snapshot_name="grown"
+        )
+
+
+def test_vector_snapshot_rejects_rtol_for_cosine():
+    with pytest.raises(ValueError, match="rtol does not apply"):
+        assert_vector_snapshot(EMBEDDING, rtol=1e-3)
+
+
+def test_vector_snapshot_rejects_non_finite():
+    with pytest.raises(ValueError, match="Vector element 1 is NaN"):
+        assert_vector_snapshot([0.0, float("nan")])